@@ -14,6 +14,7 @@
 
 use std::any::Any;
 use std::rc::Rc;
+use std::sync::Arc;
 
 use itertools::Itertools;
 use jj_cli::cli_util::CliRunner;
@@ -195,7 +196,7 @@ fn even_digits(
 ) -> Result<Rc<UserRevsetExpression>, RevsetParseError> {
     function.expect_no_arguments()?;
     Ok(RevsetExpression::filter(RevsetFilterPredicate::Extension(
-        Rc::new(EvenDigitsFilter),
+        Arc::new(EvenDigitsFilter),
     )))
 }
 
@@ -23,6 +23,7 @@ use std::fmt;
 use std::fmt::Debug;
 use std::fs;
 use std::io;
+use std::io::Read as _;
 use std::io::Write as _;
 use std::iter;
 use std::mem;
@@ -33,6 +34,7 @@ use std::rc::Rc;
 use std::str;
 use std::str::FromStr;
 use std::sync::Arc;
+use std::time::Instant;
 use std::time::SystemTime;
 
 use bstr::ByteVec as _;
@@ -64,7 +66,14 @@ use jj_lib::config::ConfigLayer;
 use jj_lib::config::ConfigNamePathBuf;
 use jj_lib::config::ConfigSource;
 use jj_lib::config::StackedConfig;
+use jj_lib::conflict_cache::MaterializedConflictCache;
+use jj_lib::conflicts;
 use jj_lib::conflicts::ConflictMarkerStyle;
+use jj_lib::conflicts::ConflictMarkerStyleMaterializer;
+use jj_lib::conflicts::ConflictMaterializer;
+use jj_lib::conflicts::ConflictMaterializers;
+use jj_lib::default_index::AsCompositeIndex as _;
+use jj_lib::default_index::DefaultReadonlyIndex;
 use jj_lib::file_util;
 use jj_lib::fileset;
 use jj_lib::fileset::FilesetDiagnostics;
@@ -75,6 +84,7 @@ use jj_lib::gitignore::GitIgnoreError;
 use jj_lib::gitignore::GitIgnoreFile;
 use jj_lib::id_prefix::IdPrefixContext;
 use jj_lib::matchers::Matcher;
+use jj_lib::matchers::NothingMatcher;
 use jj_lib::merge::MergedTreeValue;
 use jj_lib::merged_tree::MergedTree;
 use jj_lib::object_id::ObjectId;
@@ -99,6 +109,7 @@ use jj_lib::repo_path::RepoPath;
 use jj_lib::repo_path::RepoPathBuf;
 use jj_lib::repo_path::RepoPathUiConverter;
 use jj_lib::repo_path::UiPathParseError;
+use jj_lib::resolution_cache::ResolutionCache;
 use jj_lib::revset;
 use jj_lib::revset::ResolvedRevsetExpression;
 use jj_lib::revset::RevsetAliasesMap;
@@ -122,9 +133,10 @@ use jj_lib::view::View;
 use jj_lib::working_copy;
 use jj_lib::working_copy::CheckoutOptions;
 use jj_lib::working_copy::CheckoutStats;
+use jj_lib::working_copy::EolConversionMode;
 use jj_lib::working_copy::SnapshotOptions;
 use jj_lib::working_copy::SnapshotStats;
-use jj_lib::working_copy::UntrackedReason;
+use jj_lib::working_copy::SymlinkMode;
 use jj_lib::working_copy::WorkingCopy;
 use jj_lib::working_copy::WorkingCopyFactory;
 use jj_lib::working_copy::WorkingCopyFreshness;
@@ -142,6 +154,7 @@ use tracing_chrome::ChromeLayerBuilder;
 use tracing_subscriber::prelude::*;
 
 use crate::command_error::cli_error;
+use crate::command_error::config_error;
 use crate::command_error::config_error_with_message;
 use crate::command_error::handle_command_result;
 use crate::command_error::internal_error;
@@ -151,6 +164,9 @@ use crate::command_error::user_error;
 use crate::command_error::user_error_with_hint;
 use crate::command_error::user_error_with_message;
 use crate::command_error::CommandError;
+use crate::commit_classes::CommitClass;
+use crate::commit_classes::CommitClassConfig;
+use crate::commit_classes::CommitClassifier;
 use crate::commit_templater::CommitTemplateLanguage;
 use crate::commit_templater::CommitTemplateLanguageExtension;
 use crate::complete;
@@ -170,9 +186,13 @@ use crate::formatter::PlainTextFormatter;
 use crate::git_util::is_colocated_git_workspace;
 use crate::git_util::print_failed_git_export;
 use crate::git_util::print_git_import_stats;
+use crate::merge_tools::parse_hunk_indices;
 use crate::merge_tools::DiffEditor;
+use crate::merge_tools::DiffSelection;
 use crate::merge_tools::MergeEditor;
 use crate::merge_tools::MergeToolConfigError;
+use crate::metrics;
+use crate::metrics::MetricsLayer;
 use crate::operation_templater::OperationTemplateLanguage;
 use crate::operation_templater::OperationTemplateLanguageExtension;
 use crate::revset_util;
@@ -210,6 +230,7 @@ pub struct TracingSubscription {
         tracing_subscriber::Registry,
     >,
     _chrome_tracing_flush_guard: ChromeTracingFlushGuard,
+    metrics_layer: MetricsLayer,
 }
 
 impl TracingSubscription {
@@ -252,6 +273,7 @@ impl TracingSubscription {
             Err(_) => (None, ChromeTracingFlushGuard { _inner: None }),
         };
 
+        let metrics_layer = MetricsLayer::default();
         tracing_subscriber::registry()
             .with(
                 tracing_subscriber::fmt::Layer::default()
@@ -259,10 +281,12 @@ impl TracingSubscription {
                     .with_filter(filter),
             )
             .with(chrome_tracing_layer)
+            .with(metrics_layer.clone().with_filter(metrics_layer.filter()))
             .init();
         TracingSubscription {
             reload_log_filter,
             _chrome_tracing_flush_guard: chrome_tracing_flush_guard,
+            metrics_layer,
         }
     }
 
@@ -278,6 +302,11 @@ impl TracingSubscription {
         tracing::info!("debug logging enabled");
         Ok(())
     }
+
+    /// Layer recording metrics for `telemetry.metrics-file`.
+    pub fn metrics_layer(&self) -> &MetricsLayer {
+        &self.metrics_layer
+    }
 }
 
 #[derive(Clone)]
@@ -298,8 +327,10 @@ struct CommandHelperData {
     commit_template_extensions: Vec<Arc<dyn CommitTemplateLanguageExtension>>,
     operation_template_extensions: Vec<Arc<dyn OperationTemplateLanguageExtension>>,
     maybe_workspace_loader: Result<Box<dyn WorkspaceLoader>, CommandError>,
+    workspace_loader_factory: Box<dyn WorkspaceLoaderFactory>,
     store_factories: StoreFactories,
     working_copy_factories: WorkingCopyFactories,
+    conflict_materializers: ConflictMaterializers,
 }
 
 impl CommandHelper {
@@ -412,6 +443,30 @@ impl CommandHelper {
         Ok(workspace_command)
     }
 
+    /// Loads workspace and repo for a command which doesn't modify the repo.
+    ///
+    /// If `snapshot.auto` is disabled, the working copy isn't snapshotted, and
+    /// a notice is printed instead since the working-copy commit may be out of
+    /// date.
+    #[instrument(skip(self, ui))]
+    pub fn workspace_helper_read_only(
+        &self,
+        ui: &Ui,
+    ) -> Result<WorkspaceCommandHelper, CommandError> {
+        if self.settings().get_bool("snapshot.auto")? {
+            return self.workspace_helper(ui);
+        }
+        let workspace_command = self.workspace_helper_no_snapshot(ui)?;
+        if workspace_command.may_update_working_copy {
+            writeln!(
+                ui.status(),
+                "Working copy not snapshotted (snapshot.auto = false); run `jj snapshot` to \
+                 record recent changes"
+            )?;
+        }
+        Ok(workspace_command)
+    }
+
     /// Loads workspace and repo, but never snapshots the working copy. Most
     /// commands should use `workspace_helper()` instead.
     #[instrument(skip(self, ui))]
@@ -454,6 +509,38 @@ impl CommandHelper {
             })
     }
 
+    /// Loads the workspace at `path`, which may belong to another repo than
+    /// the current one.
+    pub fn load_workspace_at(&self, path: &str) -> Result<Workspace, CommandError> {
+        let abs_path = self.cwd().join(path);
+        let abs_path = abs_path.canonicalize().unwrap_or(abs_path);
+        self.data
+            .workspace_loader_factory
+            .create(&abs_path)
+            .and_then(|loader| {
+                loader.load(
+                    &self.data.settings,
+                    &self.data.store_factories,
+                    &self.data.working_copy_factories,
+                )
+            })
+            .map_err(|err| map_workspace_load_error(err, Some(path)))
+    }
+
+    /// Loads the workspace at `path` and its repo at the head operation,
+    /// without touching the working copy. Useful for reading commits from
+    /// another repo than the current one.
+    pub fn workspace_helper_at(
+        &self,
+        ui: &Ui,
+        path: &str,
+    ) -> Result<WorkspaceCommandHelper, CommandError> {
+        let workspace = self.load_workspace_at(path)?;
+        let repo = workspace.repo_loader().load_at_head(self.settings())?;
+        let env = self.workspace_environment(ui, &workspace)?;
+        WorkspaceCommandHelper::new(ui, workspace, repo, env, false)
+    }
+
     pub fn recover_stale_working_copy(
         &self,
         ui: &Ui,
@@ -729,7 +816,19 @@ pub struct WorkspaceCommandEnvironment {
     workspace_id: WorkspaceId,
     immutable_heads_expression: Rc<UserRevsetExpression>,
     short_prefixes_expression: Option<Rc<UserRevsetExpression>>,
+    commit_classes: Vec<CommitClass>,
     conflict_marker_style: ConflictMarkerStyle,
+    conflict_materializers: ConflictMaterializers,
+    symlink_mode: SymlinkMode,
+    eol_conversion: EolConversionMode,
+}
+
+/// Entry of `ui.conflict-marker-style-overrides`.
+#[derive(Clone, Debug, serde::Deserialize)]
+#[serde(rename_all = "kebab-case")]
+struct ConflictMarkerStyleOverride {
+    files: String,
+    style: ConflictMarkerStyle,
 }
 
 impl WorkspaceCommandEnvironment {
@@ -749,10 +848,24 @@ impl WorkspaceCommandEnvironment {
             workspace_id: workspace.workspace_id().to_owned(),
             immutable_heads_expression: RevsetExpression::root(),
             short_prefixes_expression: None,
+            commit_classes: vec![],
             conflict_marker_style: command.settings().get("ui.conflict-marker-style")?,
+            conflict_materializers: ConflictMaterializers::new(),
+            symlink_mode: command
+                .settings()
+                .get("core.symlinks")
+                .optional()?
+                .unwrap_or_default(),
+            eol_conversion: command
+                .settings()
+                .get("working-copy.eol-conversion")
+                .optional()?
+                .unwrap_or_default(),
         };
         env.immutable_heads_expression = env.load_immutable_heads_expression(ui)?;
         env.short_prefixes_expression = env.load_short_prefixes_expression(ui)?;
+        env.commit_classes = env.load_commit_classes(ui)?;
+        env.conflict_materializers = env.load_conflict_materializers(ui)?;
         Ok(env)
     }
 
@@ -811,11 +924,54 @@ impl WorkspaceCommandEnvironment {
         &self.immutable_heads_expression
     }
 
+    /// User-configured commit classes, ordered by name.
+    pub fn commit_classes(&self) -> &[CommitClass] {
+        &self.commit_classes
+    }
+
     /// User-configured conflict marker style for materializing conflicts
     pub fn conflict_marker_style(&self) -> ConflictMarkerStyle {
         self.conflict_marker_style
     }
 
+    /// User-configured representation of symlinks in the working copy
+    pub fn symlink_mode(&self) -> SymlinkMode {
+        self.symlink_mode
+    }
+
+    /// User-configured line ending conversion of text files in the working
+    /// copy
+    pub fn eol_conversion(&self) -> EolConversionMode {
+        self.eol_conversion
+    }
+
+    fn load_conflict_materializers(&self, ui: &Ui) -> Result<ConflictMaterializers, CommandError> {
+        let mut materializers = self.command.data.conflict_materializers.clone();
+        let overrides: Vec<ConflictMarkerStyleOverride> = self
+            .settings()
+            .get("ui.conflict-marker-style-overrides")
+            .optional()?
+            .unwrap_or_default();
+        for style_override in overrides {
+            let mut diagnostics = FilesetDiagnostics::new();
+            // Patterns are relative to the workspace root regardless of cwd.
+            let expression = fileset::parse(
+                &mut diagnostics,
+                &style_override.files,
+                &RepoPathUiConverter::Fs {
+                    cwd: "".into(),
+                    base: "".into(),
+                },
+            )?;
+            print_parse_diagnostics(ui, "In `ui.conflict-marker-style-overrides`", &diagnostics)?;
+            materializers.add(Arc::new(ConflictMarkerStyleMaterializer::new(
+                expression.to_matcher(),
+                style_override.style,
+            )));
+        }
+        Ok(materializers)
+    }
+
     fn load_immutable_heads_expression(
         &self,
         ui: &Ui,
@@ -855,6 +1011,34 @@ impl WorkspaceCommandEnvironment {
         }
     }
 
+    fn load_commit_classes(&self, ui: &Ui) -> Result<Vec<CommitClass>, CommandError> {
+        let settings = self.settings();
+        settings
+            .table_keys("classes")
+            // Sort keys early so errors are deterministic.
+            .sorted()
+            .map(|name| -> Result<CommitClass, CommandError> {
+                let config: CommitClassConfig = settings.get(["classes", name])?;
+                let mut diagnostics = RevsetDiagnostics::new();
+                let (expression, modifier) = revset::parse_with_modifier(
+                    &mut diagnostics,
+                    config.revset(),
+                    &self.revset_parse_context(),
+                )
+                .map_err(|err| {
+                    config_error_with_message(format!("Invalid `classes.{name}`"), err)
+                })?;
+                print_parse_diagnostics(ui, &format!("In `classes.{name}`"), &diagnostics)?;
+                let (None | Some(RevsetModifier::All)) = modifier;
+                Ok(CommitClass {
+                    name: name.to_owned(),
+                    expression,
+                    warn_on_push: config.warn_on_push(),
+                })
+            })
+            .try_collect()
+    }
+
     fn find_immutable_commit<'a>(
         &self,
         repo: &dyn Repo,
@@ -917,11 +1101,13 @@ impl WorkspaceCommandEnvironment {
     ) -> CommitTemplateLanguage<'a> {
         CommitTemplateLanguage::new(
             repo,
+            self.settings(),
             &self.path_converter,
             &self.workspace_id,
             self.revset_parse_context(),
             id_prefix_context,
             self.immutable_expression(),
+            &self.commit_classes,
             self.conflict_marker_style,
             &self.command.data.commit_template_extensions,
         )
@@ -930,6 +1116,12 @@ impl WorkspaceCommandEnvironment {
     pub fn operation_template_extensions(&self) -> &[Arc<dyn OperationTemplateLanguageExtension>] {
         &self.command.data.operation_template_extensions
     }
+
+    /// Materializers registered by extensions, followed by the per-file
+    /// conflict marker styles configured by the user.
+    pub fn conflict_materializers(&self) -> &ConflictMaterializers {
+        &self.conflict_materializers
+    }
 }
 
 /// Provides utilities for writing a command that works on a [`Workspace`]
@@ -966,6 +1158,9 @@ where
     SnapshotWorkingCopyError::Command(err.into())
 }
 
+/// Size limits of new files matching the matchers, in order of precedence.
+type MaxNewFileSizeOverrides = Vec<(Arc<dyn Matcher>, u64)>;
+
 impl WorkspaceCommandHelper {
     #[instrument(skip_all)]
     fn new(
@@ -981,6 +1176,7 @@ impl WorkspaceCommandHelper {
         let may_update_working_copy =
             loaded_at_head && !env.command.global_args().ignore_working_copy;
         let working_copy_shared_with_git = is_colocated_git_workspace(&workspace, &repo);
+        record_repo_metrics(&repo);
         let helper = Self {
             workspace,
             user_repo: ReadonlyUserRepo::new(repo),
@@ -1165,9 +1361,13 @@ impl WorkspaceCommandHelper {
         &self.env
     }
 
-    pub fn checkout_options(&self) -> CheckoutOptions {
+    pub fn checkout_options(&self) -> CheckoutOptions<'static> {
         CheckoutOptions {
             conflict_marker_style: self.env.conflict_marker_style(),
+            conflict_materializers: self.env.conflict_materializers().clone(),
+            progress: None,
+            symlinks: self.env.symlink_mode(),
+            eol_conversion: self.env.eol_conversion(),
         }
     }
 
@@ -1306,8 +1506,51 @@ to the current parents may contain changes from multiple commits.
         Ok(expression.to_matcher())
     }
 
+    /// Parses the `snapshot.max-new-file-size-overrides` table, which maps
+    /// filesets to the size limits of new files matching them. Overrides from
+    /// config layers with higher precedence come first, and the overrides of
+    /// a layer are in the order they're listed in.
+    fn max_new_file_size_overrides(
+        &self,
+        ui: &Ui,
+    ) -> Result<MaxNewFileSizeOverrides, CommandError> {
+        const TABLE_NAME: &str = "snapshot.max-new-file-size-overrides";
+        let path_converter = RepoPathUiConverter::Fs {
+            cwd: "".into(),
+            base: "".into(),
+        };
+        // Unlike table_keys(), list the patterns of the layers with higher
+        // precedence first. A non-table value shadows the lower layers.
+        let patterns = self
+            .settings()
+            .config()
+            .layers()
+            .iter()
+            .rev()
+            .map_while(|layer| layer.look_up_table(TABLE_NAME).ok())
+            .flatten()
+            .flat_map(|table| table.iter().map(|(pattern, _)| pattern))
+            .unique();
+        let mut overrides = vec![];
+        for pattern in patterns {
+            let mut diagnostics = FilesetDiagnostics::new();
+            let expression = fileset::parse(&mut diagnostics, pattern, &path_converter)?;
+            print_parse_diagnostics(ui, &format!("In `{TABLE_NAME}`"), &diagnostics)?;
+            let HumanByteSize(mut size) = self.settings().get_value_with(
+                ["snapshot", "max-new-file-size-overrides", pattern],
+                TryInto::try_into,
+            )?;
+            if size == 0 {
+                size = u64::MAX;
+            }
+            overrides.push((Arc::from(expression.to_matcher()), size));
+        }
+        Ok(overrides)
+    }
+
     pub fn snapshot_options_with_start_tracking_matcher<'a>(
         &self,
+        ui: &Ui,
         start_tracking_matcher: &'a dyn Matcher,
     ) -> Result<SnapshotOptions<'a>, CommandError> {
         let base_ignores = self.base_ignores()?;
@@ -1318,14 +1561,22 @@ to the current parents may contain changes from multiple commits.
         if max_new_file_size == 0 {
             max_new_file_size = u64::MAX;
         }
+        let max_new_file_size_overrides = self.max_new_file_size_overrides(ui)?;
         let conflict_marker_style = self.env.conflict_marker_style();
+        let untracked_cache = self.settings().get_bool("snapshot.untracked-cache")?;
         Ok(SnapshotOptions {
             base_ignores,
             fsmonitor_settings,
             progress: None,
             start_tracking_matcher,
             max_new_file_size,
+            max_new_file_size_overrides,
             conflict_marker_style,
+            conflict_materializers: self.env.conflict_materializers().clone(),
+            symlinks: self.env.symlink_mode(),
+            eol_conversion: self.env.eol_conversion(),
+            untracked_cache,
+            refresh_matcher: &NothingMatcher,
         })
     }
 
@@ -1333,6 +1584,14 @@ to the current parents may contain changes from multiple commits.
         self.env.path_converter()
     }
 
+    /// Formats paths relative to the workspace root regardless of cwd.
+    pub(crate) fn use_workspace_relative_paths(&mut self) {
+        self.env.path_converter = RepoPathUiConverter::Fs {
+            cwd: "".into(),
+            base: "".into(),
+        };
+    }
+
     #[instrument(skip_all)]
     pub fn base_ignores(&self) -> Result<Arc<GitIgnoreFile>, GitIgnoreError> {
         let get_excludes_file_path = |config: &gix::config::File| -> Option<PathBuf> {
@@ -1449,6 +1708,35 @@ to the current parents may contain changes from multiple commits.
         }
     }
 
+    /// Parses `--select` specs into a diff selector. A patch is read from
+    /// stdin if `-` is specified.
+    pub fn diff_selector_from_specs(&self, specs: &[String]) -> Result<DiffSelector, CommandError> {
+        let mut selection = DiffSelection::default();
+        for spec in specs {
+            if spec == "-" {
+                let mut patch = vec![];
+                io::stdin().read_to_end(&mut patch).map_err(|err| {
+                    user_error_with_message("Failed to read patch from stdin", err)
+                })?;
+                selection.add_patch(&patch)?;
+            } else if let Some(path) = spec.strip_prefix("file:") {
+                selection.add_file(self.parse_file_path(path)?)?;
+            } else if let Some((path, indices)) = spec
+                .strip_prefix("hunks:")
+                .and_then(|rest| rest.rsplit_once(':'))
+            {
+                let indices = parse_hunk_indices(indices)?;
+                selection.add_hunks(self.parse_file_path(path)?, indices)?;
+            } else {
+                return Err(user_error_with_hint(
+                    format!("Invalid selection `{spec}`"),
+                    "Use `file:PATH`, `hunks:PATH:1,2-3`, or `-` to read a patch from stdin.",
+                ));
+            }
+        }
+        Ok(DiffSelector::Selection(selection))
+    }
+
     /// Loads 3-way merge editor from the settings.
     ///
     /// If the `tool_name` isn't specified, the default editor will be returned.
@@ -1458,11 +1746,13 @@ to the current parents may contain changes from multiple commits.
         tool_name: Option<&str>,
     ) -> Result<MergeEditor, MergeToolConfigError> {
         let conflict_marker_style = self.env.conflict_marker_style();
-        if let Some(name) = tool_name {
-            MergeEditor::with_name(name, self.settings(), conflict_marker_style)
+        let editor = if let Some(name) = tool_name {
+            MergeEditor::with_name(name, self.settings(), conflict_marker_style)?
         } else {
-            MergeEditor::from_settings(ui, self.settings(), conflict_marker_style)
-        }
+            MergeEditor::from_settings(ui, self.settings(), conflict_marker_style)?
+        };
+        let conflict_cache = MaterializedConflictCache::load(self.repo_path());
+        Ok(editor.with_conflict_cache(Arc::new(conflict_cache)))
     }
 
     pub fn resolve_single_op(&self, op_str: &str) -> Result<Operation, OpsetEvaluationError> {
@@ -1585,6 +1875,17 @@ to the current parents may contain changes from multiple commits.
         )
     }
 
+    /// Evaluates the user-configured commit classes against the current repo.
+    pub fn commit_classifier(&self) -> Result<CommitClassifier<'_>, CommandError> {
+        CommitClassifier::new(self.env.commit_classes(), |class| {
+            self.attach_revset_evaluator(class.expression.clone())
+                .evaluate()
+                .map_err(|err| {
+                    config_error_with_message(format!("Invalid `classes.{}`", class.name), err)
+                })
+        })
+    }
+
     pub(crate) fn revset_parse_context(&self) -> RevsetParseContext {
         self.env.revset_parse_context()
     }
@@ -1670,6 +1971,7 @@ to the current parents may contain changes from multiple commits.
     /// Creates operation template language environment for this workspace.
     pub fn operation_template_language(&self) -> OperationTemplateLanguage {
         OperationTemplateLanguage::new(
+            self.settings(),
             self.repo().op_store().root_operation_id(),
             Some(self.repo().op_id()),
             self.env.operation_template_extensions(),
@@ -1779,7 +2081,7 @@ to the current parents may contain changes from multiple commits.
             .auto_tracking_matcher(ui)
             .map_err(snapshot_command_error)?;
         let options = self
-            .snapshot_options_with_start_tracking_matcher(&auto_tracking_matcher)
+            .snapshot_options_with_start_tracking_matcher(ui, &auto_tracking_matcher)
             .map_err(snapshot_command_error)?;
 
         // Compare working-copy tree and operation with repo's, and reload as needed.
@@ -1880,7 +2182,12 @@ See https://jj-vcs.github.io/jj/latest/working-copy/#stale-working-copy \
             }
 
             if self.working_copy_shared_with_git {
-                let refs = git::export_refs(mut_repo).map_err(snapshot_command_error)?;
+                let git_settings = command
+                    .settings()
+                    .git_settings()
+                    .map_err(snapshot_command_error)?;
+                let refs =
+                    git::export_refs(mut_repo, &git_settings).map_err(snapshot_command_error)?;
                 print_failed_git_export(ui, &refs).map_err(snapshot_command_error)?;
             }
 
@@ -1904,7 +2211,9 @@ See https://jj-vcs.github.io/jj/latest/working-copy/#stale-working-copy \
         new_commit: &Commit,
     ) -> Result<(), CommandError> {
         assert!(self.may_update_working_copy);
-        let checkout_options = self.checkout_options();
+        let mut checkout_options = self.checkout_options();
+        let progress = crate::progress::checkout_progress(ui);
+        checkout_options.progress = progress.as_ref().map(|x| x as _);
         let stats = update_working_copy(
             &self.user_repo.repo,
             &mut self.workspace,
@@ -1935,7 +2244,7 @@ See https://jj-vcs.github.io/jj/latest/working-copy/#stale-working-copy \
                 let conflicts = new_commit.tree()?.conflicts().collect_vec();
                 if !conflicts.is_empty() {
                     writeln!(formatter, "There are unresolved conflicts at these paths:")?;
-                    print_conflicted_paths(conflicts, formatter.as_mut(), self)?;
+                    print_conflicted_paths(conflicts, formatter.as_mut(), self, Some(new_commit))?;
                 }
             }
         }
@@ -2007,7 +2316,8 @@ See https://jj-vcs.github.io/jj/latest/working-copy/#stale-working-copy \
             if let Some(wc_commit) = &maybe_new_wc_commit {
                 git::reset_head(tx.repo_mut(), &git_repo, wc_commit)?;
             }
-            let refs = git::export_refs(tx.repo_mut())?;
+            let git_settings = self.settings().git_settings()?;
+            let refs = git::export_refs(tx.repo_mut(), &git_settings)?;
             print_failed_git_export(ui, &refs)?;
         }
 
@@ -2365,6 +2675,29 @@ impl WorkspaceCommandTransaction<'_> {
         self.helper.finish_transaction(ui, self.tx, description)
     }
 
+    /// Rewrites conflicted commits created in this transaction to use
+    /// resolutions recorded by `jj resolve --remember`.
+    pub fn reapply_recorded_resolutions(&mut self, ui: &Ui) -> Result<(), CommandError> {
+        let resolutions = ResolutionCache::load(self.helper.repo_path()).read_all()?;
+        if resolutions.is_empty() {
+            return Ok(());
+        }
+        let settings = self.helper.settings();
+        self.id_prefix_context.take(); // invalidate
+        let num_rebased = self.tx.repo_mut().rebase_descendants(settings)?;
+        if num_rebased > 0 {
+            writeln!(ui.status(), "Rebased {num_rebased} descendant commits")?;
+        }
+        let num_resolved = resolutions.reapply_to_new_commits(self.tx.repo_mut(), settings)?;
+        if num_resolved > 0 {
+            writeln!(
+                ui.status(),
+                "Reused recorded conflict resolutions in {num_resolved} commits"
+            )?;
+        }
+        Ok(())
+    }
+
     /// Returns the wrapped [`Transaction`] for circumstances where
     /// finer-grained control is needed. The caller becomes responsible for
     /// finishing the `Transaction`, including rebasing descendants and updating
@@ -2429,6 +2762,7 @@ jj git init --colocate",
             err @ (StoreLoadError::ReadError { .. } | StoreLoadError::Backend(_)),
         ) => internal_error_with_message("The repository appears broken or inaccessible", err),
         WorkspaceLoadError::StoreLoadError(StoreLoadError::Signing(err)) => user_error(err),
+        WorkspaceLoadError::StoreLoadError(StoreLoadError::Config(err)) => err.into(),
         WorkspaceLoadError::WorkingCopyState(err) => internal_error(err),
         WorkspaceLoadError::NonUnicodePath | WorkspaceLoadError::Path(_) => user_error(err),
     }
@@ -2494,11 +2828,16 @@ fn update_stale_working_copy(
     Ok(stats)
 }
 
+/// Prints the conflicted paths and the types of their conflicts.
+///
+/// If `origins_commit` is given, the commits the sides of each conflict in it
+/// originated from are printed too.
 #[instrument(skip_all)]
 pub fn print_conflicted_paths(
     conflicts: Vec<(RepoPathBuf, BackendResult<MergedTreeValue>)>,
     formatter: &mut dyn Formatter,
     workspace_command: &WorkspaceCommandHelper,
+    origins_commit: Option<&Commit>,
 ) -> Result<(), CommandError> {
     let formatted_paths = conflicts
         .iter()
@@ -2509,7 +2848,7 @@ pub fn print_conflicted_paths(
         .into_iter()
         .map(|p| format!("{:width$}", p, width = max_path_len.min(32) + 3));
 
-    for ((_, conflict), formatted_path) in std::iter::zip(conflicts, formatted_paths) {
+    for ((path, conflict), formatted_path) in std::iter::zip(conflicts, formatted_paths) {
         // TODO: Display the error for the path instead of failing the whole command if
         // `conflict` is an error?
         let conflict = conflict?.simplify();
@@ -2584,6 +2923,18 @@ pub fn print_conflicted_paths(
             io::Result::Ok(())
         })?;
         writeln!(formatter)?;
+
+        if let Some(commit) = origins_commit {
+            let origins = conflicts::find_conflict_side_origins(commit, &path, &conflict)?;
+            for (i, origin) in origins.iter().enumerate() {
+                write!(formatter, "  side #{}: ", i + 1)?;
+                match origin {
+                    Some(origin) => workspace_command.write_commit_summary(formatter, origin)?,
+                    None => write!(formatter, "(unknown)")?,
+                }
+                writeln!(formatter)?;
+            }
+        }
     }
     Ok(())
 }
@@ -2599,32 +2950,25 @@ pub fn print_snapshot_stats(
     if !stats.untracked_paths.is_empty() {
         writeln!(ui.warning_default(), "Refused to snapshot some files:")?;
         let mut formatter = ui.stderr_formatter();
-        for (path, reason) in &stats.untracked_paths {
+        for (path, size, max_size) in stats.files_too_large() {
             let ui_path = path_converter.format_file_path(path);
-            let message = match reason {
-                UntrackedReason::FileTooLarge { size, max_size } => {
-                    // Show both exact and human bytes sizes to avoid something
-                    // like '1.0MiB, maximum size allowed is ~1.0MiB'
-                    let size_approx = HumanByteSize(*size);
-                    let max_size_approx = HumanByteSize(*max_size);
-                    format!(
-                        "{size_approx} ({size} bytes); the maximum size allowed is \
-                         {max_size_approx} ({max_size} bytes)",
-                    )
-                }
-            };
-            writeln!(formatter, "  {ui_path}: {message}")?;
+            // Show both exact and human bytes sizes to avoid something
+            // like '1.0MiB, maximum size allowed is ~1.0MiB'
+            let size_approx = HumanByteSize(size);
+            let max_size_approx = HumanByteSize(max_size);
+            writeln!(
+                formatter,
+                "  {ui_path}: {size_approx} ({size} bytes); the maximum size allowed is \
+                 {max_size_approx} ({max_size} bytes)",
+            )?;
         }
     }
 
-    if let Some(size) = stats
-        .untracked_paths
-        .values()
-        .map(|reason| match reason {
-            UntrackedReason::FileTooLarge { size, .. } => *size,
-        })
-        .max()
-    {
+    if let Some(size) = stats.files_too_large().map(|(_, size, _)| size).max() {
+        let ui_paths = stats
+            .files_too_large()
+            .map(|(path, _, _)| file_track_arg(&path_converter.format_file_path(path)))
+            .join(" ");
         writedoc!(
             ui.hint_default(),
             r"
@@ -2634,12 +2978,31 @@ pub fn print_snapshot_stats(
                 This will increase the maximum file size allowed for new files, in this repository only.
               - Run `jj --config snapshot.max-new-file-size={size} st`
                 This will increase the maximum file size allowed for new files, for this command only.
+              - Run `jj file track --force {ui_paths}`
+                This will track the files regardless of their size.
             "
         )?;
     }
     Ok(())
 }
 
+/// Formats the path so it can be passed to `jj file track` in a shell. Paths
+/// with special characters are quoted as fileset strings.
+fn file_track_arg(ui_path: &str) -> String {
+    if ui_path
+        .bytes()
+        .all(|b| b.is_ascii_alphanumeric() || b"-./_".contains(&b))
+    {
+        ui_path.to_owned()
+    } else {
+        let fileset = format!(
+            r#"file:"{}""#,
+            ui_path.replace('\\', r"\\").replace('"', r#"\""#)
+        );
+        format!("'{}'", fileset.replace('\'', r"'\''"))
+    }
+}
+
 pub fn print_checkout_stats(
     ui: &Ui,
     stats: CheckoutStats,
@@ -2938,6 +3301,8 @@ pub fn short_operation_hash(operation_id: &OperationId) -> String {
 pub enum DiffSelector {
     NonInteractive,
     Interactive(DiffEditor),
+    /// Changes selected by `--select` specs.
+    Selection(DiffSelection),
 }
 
 impl DiffSelector {
@@ -2946,7 +3311,8 @@ impl DiffSelector {
     }
 
     /// Restores diffs from the `right_tree` to the `left_tree` by using an
-    /// interactive editor if enabled.
+    /// interactive editor if enabled, or by applying the selection if
+    /// specified.
     pub fn select(
         &self,
         left_tree: &MergedTree,
@@ -2959,6 +3325,9 @@ impl DiffSelector {
             DiffSelector::Interactive(editor) => {
                 Ok(editor.edit(left_tree, right_tree, matcher, format_instructions)?)
             }
+            DiffSelector::Selection(selection) => {
+                Ok(selection.apply(left_tree, right_tree, matcher)?)
+            }
         }
     }
 }
@@ -3125,6 +3494,16 @@ pub struct EarlyArgs {
     // Parsing with ignore_errors will crash if this is bool, so use
     // Option<bool>.
     pub no_pager: Option<bool>,
+    /// Style of conflict markers to materialize in the working copy
+    ///
+    /// This overrides the `ui.conflict-marker-style` setting.
+    #[arg(
+        long,
+        value_name = "STYLE",
+        global = true,
+        value_parser = ["diff", "snapshot", "git", "zdiff3"],
+    )]
+    pub conflict_marker_style: Option<String>,
     /// Additional configuration options (can be repeated)
     ///
     /// The name should be specified as TOML dotted keys. The value should be
@@ -3266,7 +3645,13 @@ fn resolve_default_command(
                     "Run `jj config set --user ui.default-command log` to disable this message."
                 )?;
             }
-            let default_command = args.unwrap_or_else(|| vec!["log".to_string()]);
+            let default_command = match args {
+                Some(args) => {
+                    validate_default_command(config, app, &args)?;
+                    args
+                }
+                None => vec!["log".to_string()],
+            };
 
             // Insert the default command directly after the path to the binary.
             string_args.splice(1..1, default_command);
@@ -3275,6 +3660,34 @@ fn resolve_default_command(
     Ok(string_args)
 }
 
+/// Checks that `ui.default-command` names a known command or alias, so a typo
+/// is reported as a config error rather than a confusing parse error.
+fn validate_default_command(
+    config: &StackedConfig,
+    app: &Command,
+    args: &[String],
+) -> Result<(), CommandError> {
+    let Some(name) = args.first() else {
+        return Err(config_error(
+            "Invalid `ui.default-command`: the command must not be empty",
+        ));
+    };
+    // Leading global options can't be validated without parsing the rest.
+    if name.starts_with('-') {
+        return Ok(());
+    }
+    let is_known = app.find_subcommand(name).is_some()
+        || config
+            .table_keys("aliases")
+            .any(|alias| alias == name.as_str());
+    if !is_known {
+        return Err(config_error(format!(
+            "Invalid `ui.default-command`: unrecognized command '{name}'"
+        )));
+    }
+    Ok(())
+}
+
 fn resolve_aliases(
     ui: &Ui,
     config: &StackedConfig,
@@ -3368,6 +3781,11 @@ fn parse_early_args(
     if args.no_pager.unwrap_or_default() {
         layer.set_value("ui.paginate", "never").unwrap();
     }
+    if let Some(style) = &args.conflict_marker_style {
+        layer
+            .set_value("ui.conflict-marker-style", style.as_str())
+            .unwrap();
+    }
     if !layer.is_empty() {
         config_layers.push(layer);
     }
@@ -3477,6 +3895,7 @@ pub struct CliRunner {
     config_layers: Vec<ConfigLayer>,
     store_factories: StoreFactories,
     working_copy_factories: WorkingCopyFactories,
+    conflict_materializers: ConflictMaterializers,
     workspace_loader_factory: Box<dyn WorkspaceLoaderFactory>,
     revset_extensions: RevsetExtensions,
     commit_template_extensions: Vec<Arc<dyn CommitTemplateLanguageExtension>>,
@@ -3502,6 +3921,7 @@ impl CliRunner {
             config_layers: crate::config::default_config_layers(),
             store_factories: StoreFactories::default(),
             working_copy_factories: default_working_copy_factories(),
+            conflict_materializers: ConflictMaterializers::new(),
             workspace_loader_factory: Box::new(DefaultWorkspaceLoaderFactory),
             revset_extensions: Default::default(),
             commit_template_extensions: vec![],
@@ -3598,6 +4018,16 @@ impl CliRunner {
         self
     }
 
+    /// Materializes conflicts at the paths matched by `materializer` in its
+    /// custom format instead of with conflict markers.
+    pub fn add_conflict_materializer(
+        mut self,
+        materializer: Box<dyn ConflictMaterializer>,
+    ) -> Self {
+        self.conflict_materializers.add(materializer.into());
+        self
+    }
+
     pub fn add_start_hook(mut self, start_hook_fn: CliDispatchFn) -> Self {
         self.start_hook_fns.push(start_hook_fn);
         self
@@ -3640,6 +4070,7 @@ impl CliRunner {
 
     #[instrument(skip_all)]
     fn run_internal(self, ui: &mut Ui, mut raw_config: RawConfig) -> Result<(), CommandError> {
+        let start_time = Instant::now();
         // `cwd` is canonicalized for consistency with `Workspace::workspace_root()` and
         // to easily compute relative paths between them.
         let cwd = env::current_dir()
@@ -3735,16 +4166,41 @@ impl CliRunner {
             commit_template_extensions: self.commit_template_extensions,
             operation_template_extensions: self.operation_template_extensions,
             maybe_workspace_loader,
+            workspace_loader_factory: self.workspace_loader_factory,
             store_factories: self.store_factories,
             working_copy_factories: self.working_copy_factories,
+            conflict_materializers: self.conflict_materializers,
         };
         let command_helper = CommandHelper {
             data: Rc::new(command_helper_data),
         };
+        let metrics_file = command_helper
+            .settings()
+            .get_string("telemetry.metrics-file")
+            .optional()?
+            .map(|path| file_util::expand_home_path(&path));
+        if metrics_file.is_some() {
+            self.tracing_subscription.metrics_layer().enable();
+        }
         for start_hook_fn in self.start_hook_fns {
             start_hook_fn(ui, &command_helper)?;
         }
-        (self.dispatch_fn)(ui, &command_helper)
+        let result = (self.dispatch_fn)(ui, &command_helper);
+        if let Some(path) = &metrics_file {
+            let metrics = self.tracing_subscription.metrics_layer().finish(
+                subcommand_names(command_helper.matches()),
+                result.is_ok(),
+                start_time.elapsed(),
+            );
+            if let Err(err) = metrics::append_metrics(path, &metrics) {
+                writeln!(
+                    ui.warning_default(),
+                    "Failed to write metrics to {}: {err}",
+                    path.display()
+                )?;
+            }
+        }
+        result
     }
 
     #[must_use]
@@ -3764,6 +4220,28 @@ impl CliRunner {
     }
 }
 
+/// Emits repo size stats to be recorded if `telemetry.metrics-file` is set.
+fn record_repo_metrics(repo: &ReadonlyRepo) {
+    let num_commits = repo
+        .readonly_index()
+        .as_any()
+        .downcast_ref::<DefaultReadonlyIndex>()
+        .map(|index| index.as_composite().num_commits());
+    tracing::info!(
+        target: metrics::METRICS_TARGET,
+        num_commits,
+        num_heads = repo.view().heads().len(),
+        num_bookmarks = repo.view().local_bookmarks().count(),
+    );
+}
+
+/// Returns the space-separated names of the invoked (sub)commands.
+fn subcommand_names(matches: &ArgMatches) -> String {
+    iter::successors(matches.subcommand(), |(_, matches)| matches.subcommand())
+        .map(|(name, _)| name)
+        .join(" ")
+}
+
 fn map_clap_cli_error(mut cmd_err: CommandError, ui: &Ui, config: &StackedConfig) -> CommandError {
     let Some(err) = cmd_err.error.downcast_ref::<clap::Error>() else {
         return cmd_err;
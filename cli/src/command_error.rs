@@ -30,11 +30,13 @@ use jj_lib::dsl_util::Diagnostics;
 use jj_lib::fileset::FilePatternParseError;
 use jj_lib::fileset::FilesetParseError;
 use jj_lib::fileset::FilesetParseErrorKind;
+use jj_lib::git::GitBundleError;
 use jj_lib::git::GitConfigParseError;
 use jj_lib::git::GitExportError;
 use jj_lib::git::GitImportError;
 use jj_lib::git::GitRemoteManagementError;
 use jj_lib::gitignore::GitIgnoreError;
+use jj_lib::native_sync::NativeSyncError;
 use jj_lib::op_heads_store::OpHeadResolutionError;
 use jj_lib::op_heads_store::OpHeadsStoreError;
 use jj_lib::op_store::OpStoreError;
@@ -46,6 +48,7 @@ use jj_lib::repo::RepoLoaderError;
 use jj_lib::repo::RewriteRootCommit;
 use jj_lib::repo_path::RepoPathBuf;
 use jj_lib::repo_path::UiPathParseError;
+use jj_lib::resolution_cache::ResolutionCacheError;
 use jj_lib::revset::RevsetEvaluationError;
 use jj_lib::revset::RevsetParseError;
 use jj_lib::revset::RevsetParseErrorKind;
@@ -67,6 +70,7 @@ use crate::formatter::FormatRecorder;
 use crate::formatter::Formatter;
 use crate::merge_tools::ConflictResolveError;
 use crate::merge_tools::DiffEditError;
+use crate::merge_tools::DiffSelectionError;
 use crate::merge_tools::MergeToolConfigError;
 use crate::revset_util::UserRevsetEvaluationError;
 use crate::template_parser::TemplateParseError;
@@ -346,6 +350,7 @@ impl From<WorkspaceInitError> for CommandError {
                 internal_error_with_message("Failed to access the repository", err)
             }
             WorkspaceInitError::SignInit(err) => user_error(err),
+            WorkspaceInitError::Config(err) => err.into(),
         }
     }
 }
@@ -406,6 +411,15 @@ impl From<DiffEditError> for CommandError {
     }
 }
 
+impl From<DiffSelectionError> for CommandError {
+    fn from(err: DiffSelectionError) -> Self {
+        match err {
+            DiffSelectionError::Backend(err) => err.into(),
+            _ => user_error_with_message("Failed to select changes", err),
+        }
+    }
+}
+
 impl From<DiffRenderError> for CommandError {
     fn from(err: DiffRenderError) -> Self {
         match err {
@@ -438,6 +452,7 @@ impl From<MergeToolConfigError> for CommandError {
                     ),
                 )
             }
+            MergeToolConfigError::NotADiffEditor { .. } => user_error(err),
             _ => user_error_with_message("Failed to load tool configuration", err),
         }
     }
@@ -480,12 +495,53 @@ impl From<GitExportError> for CommandError {
     }
 }
 
+impl From<GitBundleError> for CommandError {
+    fn from(err: GitBundleError) -> Self {
+        match err {
+            GitBundleError::BundleCommand(_) | GitBundleError::UnexpectedBackend => user_error(err),
+            GitBundleError::Backend(err) => err.into(),
+            _ => internal_error_with_message("Failed to access Git bundle", err),
+        }
+    }
+}
+
 impl From<GitRemoteManagementError> for CommandError {
     fn from(err: GitRemoteManagementError) -> Self {
         user_error(err)
     }
 }
 
+impl From<NativeSyncError> for CommandError {
+    fn from(err: NativeSyncError) -> Self {
+        match err {
+            NativeSyncError::Backend(err) => err.into(),
+            NativeSyncError::Path(_) | NativeSyncError::BackendLoad(_) => {
+                internal_error_with_message("Failed to copy objects between repos", err)
+            }
+            NativeSyncError::ConflictedBookmark(_) => user_error_with_hint(
+                err,
+                "Use `jj sync --no-push` to pull only, then resolve the conflict.",
+            ),
+            NativeSyncError::RemoteBookmarkChanged(_) => user_error_with_hint(
+                err,
+                "Run `jj sync` again to pull the changes before pushing.",
+            ),
+        }
+    }
+}
+
+impl From<ResolutionCacheError> for CommandError {
+    fn from(err: ResolutionCacheError) -> Self {
+        match err {
+            ResolutionCacheError::Backend(err) => err.into(),
+            ResolutionCacheError::RevsetEvaluation(err) => err.into(),
+            ResolutionCacheError::Path(_) | ResolutionCacheError::InvalidRecord { .. } => {
+                internal_error_with_message("Failed to access the resolution cache", err)
+            }
+        }
+    }
+}
+
 impl From<RevsetEvaluationError> for CommandError {
     fn from(err: RevsetEvaluationError) -> Self {
         user_error(err)
@@ -17,11 +17,14 @@ use std::io::Write;
 use clap_complete::ArgValueCandidates;
 use itertools::Itertools as _;
 use jj_lib::commit::CommitIteratorExt;
+use jj_lib::config::ConfigGetResultExt as _;
 use jj_lib::object_id::ObjectId;
+use jj_lib::repo::AbandonedBookmarks;
 use tracing::instrument;
 
 use crate::cli_util::CommandHelper;
 use crate::cli_util::RevisionArg;
+use crate::command_error::user_error_with_hint;
 use crate::command_error::CommandError;
 use crate::complete;
 use crate::ui::Ui;
@@ -34,6 +37,11 @@ use crate::ui::Ui;
 ///
 /// If a working-copy commit gets abandoned, it will be given a new, empty
 /// commit. This is true in general; it is not specific to this command.
+///
+/// Local bookmarks pointing to the abandoned commits are moved to the parent(s)
+/// by default. The `abandon.bookmarks` setting can be set to `"delete"` to
+/// delete them instead, or to `"error"` to refuse to abandon commits with
+/// bookmarks.
 #[derive(clap::Args, Clone, Debug)]
 pub(crate) struct AbandonArgs {
     /// The revision(s) to abandon
@@ -52,6 +60,25 @@ pub(crate) struct AbandonArgs {
     /// Do not modify the content of the children of the abandoned commits
     #[arg(long)]
     restore_descendants: bool,
+    /// Move bookmarks of the abandoned commits to the parent(s)
+    ///
+    /// This overrides the `abandon.bookmarks` setting.
+    #[arg(long)]
+    retain_bookmarks: bool,
+}
+
+/// What to do with bookmarks pointing to abandoned commits. Configured by the
+/// `abandon.bookmarks` setting.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, serde::Deserialize)]
+#[serde(rename_all = "kebab-case")]
+enum AbandonBookmarksPolicy {
+    /// Move the bookmarks to the parent(s) of the abandoned commits.
+    #[default]
+    Retain,
+    /// Delete the bookmarks.
+    Delete,
+    /// Refuse to abandon commits with bookmarks.
+    Error,
 }
 
 #[instrument(skip_all)]
@@ -71,9 +98,43 @@ pub(crate) fn cmd_abandon(
     }
     workspace_command.check_rewritable(to_abandon.iter().ids())?;
 
+    let policy = if args.retain_bookmarks {
+        AbandonBookmarksPolicy::Retain
+    } else {
+        command
+            .settings()
+            .get("abandon.bookmarks")
+            .optional()?
+            .unwrap_or_default()
+    };
+    let view = workspace_command.repo().view();
+    let bookmark_names = to_abandon
+        .iter()
+        .flat_map(|commit| view.local_bookmarks_for_commit(commit.id()))
+        .map(|(name, _)| name.to_owned())
+        .sorted()
+        .dedup()
+        .collect_vec();
+    let bookmarks = match policy {
+        AbandonBookmarksPolicy::Retain => AbandonedBookmarks::Move,
+        AbandonBookmarksPolicy::Delete => AbandonedBookmarks::Delete,
+        AbandonBookmarksPolicy::Error if bookmark_names.is_empty() => AbandonedBookmarks::Move,
+        AbandonBookmarksPolicy::Error => {
+            return Err(user_error_with_hint(
+                format!(
+                    "Refusing to abandon commits with bookmarks: {}",
+                    bookmark_names.join(", ")
+                ),
+                "Use --retain-bookmarks to move the bookmarks to the parent commits, or delete \
+                 them first with `jj bookmark delete`.",
+            ));
+        }
+    };
+
     let mut tx = workspace_command.start_transaction();
     for commit in &to_abandon {
-        tx.repo_mut().record_abandoned_commit(commit.id().clone());
+        tx.repo_mut()
+            .record_abandoned_commit_with_bookmarks(commit.id().clone(), bookmarks);
     }
     let (num_rebased, extra_msg) = if args.restore_descendants {
         (
@@ -108,6 +169,13 @@ pub(crate) fn cmd_abandon(
                  commits",
             )?;
         }
+        if bookmarks == AbandonedBookmarks::Delete && !bookmark_names.is_empty() {
+            writeln!(
+                formatter,
+                "Deleted bookmarks: {}",
+                bookmark_names.join(", ")
+            )?;
+        }
     }
     let transaction_description = if to_abandon.len() == 1 {
         format!("abandon commit {}", to_abandon[0].id().hex())
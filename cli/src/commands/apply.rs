@@ -0,0 +1,106 @@
+// Copyright 2024 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::io::Write;
+
+use clap_complete::ArgValueCandidates;
+use itertools::Itertools;
+use jj_lib::commit::CommitIteratorExt;
+use jj_lib::object_id::ObjectId;
+use jj_lib::rewrite::apply_tree_diff_across_stores;
+use jj_lib::rewrite::merge_commit_trees;
+use tracing::instrument;
+
+use crate::cli_util::CommandHelper;
+use crate::cli_util::RevisionArg;
+use crate::command_error::CommandError;
+use crate::complete;
+use crate::ui::Ui;
+
+/// Apply the changes of a revision from another repo
+///
+/// The changes the revision makes compared to its parents are copied from the
+/// other repo and applied on top of the destination in a new commit. The new
+/// commit keeps the description and author of the original revision. If the
+/// changes don't apply cleanly, the new commit will have conflicts.
+///
+/// This is useful for porting patches between forks that don't share a
+/// remote.
+#[derive(clap::Args, Clone, Debug)]
+pub(crate) struct ApplyArgs {
+    /// Path to a workspace of the repo to apply the revision from
+    #[arg(long, value_name = "PATH", value_hint = clap::ValueHint::DirPath)]
+    from_repo: String,
+    /// The revision to apply, resolved in the other repo
+    #[arg(long, short, value_name = "REVSET")]
+    revision: RevisionArg,
+    /// The revision(s) to apply the changes onto (can be repeated to create a
+    /// merge commit)
+    #[arg(
+        long,
+        short,
+        default_value = "@",
+        value_name = "REVSETS",
+        add = ArgValueCandidates::new(complete::all_revisions)
+    )]
+    destination: Vec<RevisionArg>,
+}
+
+#[instrument(skip_all)]
+pub(crate) fn cmd_apply(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    args: &ApplyArgs,
+) -> Result<(), CommandError> {
+    let mut workspace_command = command.workspace_helper(ui)?;
+    let source_workspace_command = command.workspace_helper_at(ui, &args.from_repo)?;
+    let source_commit = source_workspace_command.resolve_single_rev(ui, &args.revision)?;
+    let source_base_tree = source_commit.parent_tree(source_workspace_command.repo().as_ref())?;
+    let source_tree = source_commit.tree()?;
+    let parent_commits = workspace_command
+        .resolve_some_revsets_default_single(ui, &args.destination)?
+        .into_iter()
+        .collect_vec();
+
+    let mut tx = workspace_command.start_transaction();
+    let destination_tree = merge_commit_trees(tx.repo(), &parent_commits)?;
+    let new_tree =
+        apply_tree_diff_across_stores(&source_base_tree, &source_tree, &destination_tree)?;
+    let new_commit = tx
+        .repo_mut()
+        .new_commit(
+            command.settings(),
+            parent_commits.iter().ids().cloned().collect(),
+            new_tree.id(),
+        )
+        .set_description(source_commit.description())
+        .set_author(source_commit.author().clone())
+        .write()?;
+    if let Some(mut formatter) = ui.status_formatter() {
+        write!(formatter, "Applied ")?;
+        source_workspace_command.write_commit_summary(formatter.as_mut(), &source_commit)?;
+        write!(formatter, " as ")?;
+        tx.write_commit_summary(formatter.as_mut(), &new_commit)?;
+        writeln!(formatter)?;
+    }
+    tx.finish(
+        ui,
+        format!(
+            "apply commit {} from {}",
+            source_commit.id().hex(),
+            args.from_repo
+        ),
+    )?;
+    Ok(())
+}
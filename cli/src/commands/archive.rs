@@ -0,0 +1,379 @@
+// Copyright 2024 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fs::File;
+use std::io;
+use std::io::BufWriter;
+use std::io::Cursor;
+use std::io::Read as _;
+use std::io::Seek;
+use std::io::Write;
+use std::path::Path;
+use std::path::PathBuf;
+
+use chrono::Datelike as _;
+use chrono::Timelike as _;
+use clap_complete::ArgValueCandidates;
+use clap_complete::ArgValueCompleter;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use jj_lib::conflicts::materialize_merge_result;
+use jj_lib::conflicts::materialize_tree_value;
+use jj_lib::conflicts::MaterializedTreeValue;
+use jj_lib::matchers::IntersectionMatcher;
+use jj_lib::matchers::Matcher;
+use jj_lib::merged_tree::MergedTree;
+use jj_lib::repo::Repo;
+use jj_lib::sparse::sparse_matcher;
+use pollster::FutureExt;
+use tracing::instrument;
+
+use crate::cli_util::CommandHelper;
+use crate::cli_util::RevisionArg;
+use crate::cli_util::WorkspaceCommandHelper;
+use crate::command_error::user_error_with_message;
+use crate::command_error::CommandError;
+use crate::complete;
+use crate::ui::Ui;
+
+/// Write the contents of a revision to a tar or zip archive
+///
+/// The archive is built from the revision's tree in the repo, so the working
+/// copy is neither read nor updated. Conflicted files are written with
+/// conflict markers. Git submodules and other conflicts are skipped.
+#[derive(clap::Args, Clone, Debug)]
+pub(crate) struct ArchiveArgs {
+    /// The revision to archive
+    #[arg(
+        long, short,
+        default_value = "@",
+        value_name = "REVSET",
+        add = ArgValueCandidates::new(complete::all_revisions),
+    )]
+    revision: RevisionArg,
+    /// The archive format
+    ///
+    /// If not specified, the format is inferred from the extension of the
+    /// output file, falling back to `tar`.
+    #[arg(long, value_enum)]
+    format: Option<ArchiveFormat>,
+    /// Write the archive to this file instead of stdout
+    #[arg(long, short, value_hint = clap::ValueHint::FilePath)]
+    output: Option<PathBuf>,
+    /// Prepend this directory to every path in the archive
+    #[arg(long, value_name = "DIR")]
+    prefix: Option<String>,
+    /// Only include paths covered by the workspace's sparse patterns
+    #[arg(long)]
+    sparse: bool,
+    /// Only include files matching these filesets (instead of all files)
+    #[arg(
+        value_name = "FILESETS",
+        value_hint = clap::ValueHint::AnyPath,
+        add = ArgValueCompleter::new(complete::all_revision_files),
+    )]
+    paths: Vec<String>,
+}
+
+#[derive(clap::ValueEnum, Clone, Copy, Debug, Eq, PartialEq)]
+enum ArchiveFormat {
+    /// Uncompressed tar archive
+    Tar,
+    /// Gzip-compressed tar archive
+    #[value(name = "tar.gz", alias = "tgz")]
+    TarGz,
+    /// Zip archive with deflate compression
+    Zip,
+}
+
+impl ArchiveFormat {
+    fn from_path(path: &Path) -> Option<Self> {
+        let name = path.file_name()?.to_str()?;
+        if name.ends_with(".tar.gz") || name.ends_with(".tgz") {
+            Some(ArchiveFormat::TarGz)
+        } else if name.ends_with(".tar") {
+            Some(ArchiveFormat::Tar)
+        } else if name.ends_with(".zip") {
+            Some(ArchiveFormat::Zip)
+        } else {
+            None
+        }
+    }
+}
+
+#[instrument(skip_all)]
+pub(crate) fn cmd_archive(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    args: &ArchiveArgs,
+) -> Result<(), CommandError> {
+    let workspace_command = command.workspace_helper(ui)?;
+    let commit = workspace_command.resolve_single_rev(ui, &args.revision)?;
+    let tree = commit.tree()?;
+    let format = args
+        .format
+        .or_else(|| args.output.as_deref().and_then(ArchiveFormat::from_path))
+        .unwrap_or(ArchiveFormat::Tar);
+    let prefix = match args.prefix.as_deref() {
+        Some(prefix) if !prefix.is_empty() && !prefix.ends_with('/') => format!("{prefix}/"),
+        Some(prefix) => prefix.to_owned(),
+        None => String::new(),
+    };
+    let path_matcher = workspace_command
+        .parse_file_patterns(ui, &args.paths)?
+        .to_matcher();
+    let matcher: Box<dyn Matcher> = if args.sparse {
+        let sparse_matcher = sparse_matcher(workspace_command.working_copy().sparse_patterns()?);
+        Box::new(IntersectionMatcher::new(path_matcher, sparse_matcher))
+    } else {
+        path_matcher
+    };
+    let mtime = u64::try_from(commit.committer().timestamp.timestamp.0 / 1000).unwrap_or(0);
+
+    let entries = ArchiveEntries {
+        ui,
+        workspace_command: &workspace_command,
+        tree: &tree,
+        matcher: matcher.as_ref(),
+        prefix: &prefix,
+        mtime,
+    };
+
+    let num_entries = match (format, &args.output) {
+        (ArchiveFormat::Tar, _) => {
+            let mut archive = tar::Builder::new(create_output(ui, args.output.as_deref())?);
+            let num_entries = entries.write_to(&mut archive)?;
+            archive.into_inner()?.flush()?;
+            num_entries
+        }
+        (ArchiveFormat::TarGz, _) => {
+            let output = create_output(ui, args.output.as_deref())?;
+            let mut archive = tar::Builder::new(GzEncoder::new(output, Compression::default()));
+            let num_entries = entries.write_to(&mut archive)?;
+            archive.into_inner()?.finish()?.flush()?;
+            num_entries
+        }
+        // The zip writer needs to seek back to fill in the headers, so the
+        // archive is built in memory if it's written to stdout.
+        (ArchiveFormat::Zip, Some(path)) => {
+            let mut archive = zip::ZipWriter::new(BufWriter::new(create_file(path)?));
+            let num_entries = entries.write_to(&mut archive)?;
+            archive.finish().map_err(zip_error)?.flush()?;
+            num_entries
+        }
+        (ArchiveFormat::Zip, None) => {
+            let mut archive = zip::ZipWriter::new(Cursor::new(vec![]));
+            let num_entries = entries.write_to(&mut archive)?;
+            let data = archive.finish().map_err(zip_error)?.into_inner();
+            let mut stdout = ui.stdout();
+            stdout.write_all(&data)?;
+            stdout.flush()?;
+            num_entries
+        }
+    };
+
+    if let Some(path) = &args.output {
+        writeln!(
+            ui.status(),
+            "Wrote {num_entries} entries to {}",
+            path.display()
+        )?;
+    }
+    Ok(())
+}
+
+fn create_file(path: &Path) -> Result<File, CommandError> {
+    File::create(path)
+        .map_err(|err| user_error_with_message(format!("Failed to create {}", path.display()), err))
+}
+
+fn create_output<'a>(ui: &'a Ui, path: Option<&Path>) -> Result<Box<dyn Write + 'a>, CommandError> {
+    match path {
+        Some(path) => Ok(Box::new(BufWriter::new(create_file(path)?))),
+        None => Ok(Box::new(ui.stdout())),
+    }
+}
+
+fn zip_error(err: zip::result::ZipError) -> io::Error {
+    match err {
+        zip::result::ZipError::Io(err) => err,
+        err => io::Error::other(err),
+    }
+}
+
+/// The files of the tree to be written to an archive.
+struct ArchiveEntries<'a> {
+    ui: &'a Ui,
+    workspace_command: &'a WorkspaceCommandHelper,
+    tree: &'a MergedTree,
+    matcher: &'a dyn Matcher,
+    prefix: &'a str,
+    mtime: u64,
+}
+
+impl ArchiveEntries<'_> {
+    /// Adds the entries to the archive, and returns the number of entries
+    /// added.
+    fn write_to(&self, archive: &mut dyn ArchiveWriter) -> Result<usize, CommandError> {
+        let ui = self.ui;
+        let workspace_command = self.workspace_command;
+        let store = workspace_command.repo().store();
+        let mtime = self.mtime;
+        let mut num_entries = 0;
+        for (path, value) in self.tree.entries_matching(self.matcher) {
+            let value = value?;
+            let name = format!("{}{}", self.prefix, path.as_internal_file_string());
+            match materialize_tree_value(store, &path, value).block_on()? {
+                MaterializedTreeValue::Absent => panic!("absent values should be excluded"),
+                MaterializedTreeValue::AccessDenied(err) => {
+                    let ui_path = workspace_command.format_file_path(&path);
+                    return Err(user_error_with_message(
+                        format!("Failed to read {ui_path}"),
+                        err,
+                    ));
+                }
+                MaterializedTreeValue::File {
+                    mut reader,
+                    executable,
+                    ..
+                } => {
+                    let mut contents = vec![];
+                    reader.read_to_end(&mut contents)?;
+                    archive.add_file(&name, &contents, executable, mtime)?;
+                }
+                MaterializedTreeValue::FileConflict {
+                    contents,
+                    executable,
+                    ..
+                } => {
+                    let mut materialized = vec![];
+                    materialize_merge_result(
+                        &contents,
+                        workspace_command.env().conflict_marker_style(),
+                        &mut materialized,
+                    )?;
+                    archive.add_file(&name, &materialized, executable, mtime)?;
+                }
+                MaterializedTreeValue::Symlink { target, .. } => {
+                    archive.add_symlink(&name, &target, mtime)?;
+                }
+                MaterializedTreeValue::OtherConflict { .. } => {
+                    let ui_path = workspace_command.format_file_path(&path);
+                    writeln!(
+                        ui.warning_default(),
+                        "Skipping {ui_path} because it has an unresolvable conflict"
+                    )?;
+                    continue;
+                }
+                MaterializedTreeValue::GitSubmodule(_) => {
+                    let ui_path = workspace_command.format_file_path(&path);
+                    writeln!(ui.warning_default(), "Skipping Git submodule {ui_path}")?;
+                    continue;
+                }
+                MaterializedTreeValue::Tree(_) => panic!("entries should not contain trees"),
+            }
+            num_entries += 1;
+        }
+        Ok(num_entries)
+    }
+}
+
+trait ArchiveWriter {
+    fn add_file(
+        &mut self,
+        name: &str,
+        contents: &[u8],
+        executable: bool,
+        mtime: u64,
+    ) -> io::Result<()>;
+
+    fn add_symlink(&mut self, name: &str, target: &str, mtime: u64) -> io::Result<()>;
+}
+
+/// Entries are written with GNU headers, which can represent long names and
+/// sizes of 8GiB and more.
+impl<W: Write> ArchiveWriter for tar::Builder<W> {
+    fn add_file(
+        &mut self,
+        name: &str,
+        contents: &[u8],
+        executable: bool,
+        mtime: u64,
+    ) -> io::Result<()> {
+        let mut header = tar::Header::new_gnu();
+        header.set_entry_type(tar::EntryType::Regular);
+        header.set_mode(if executable { 0o755 } else { 0o644 });
+        header.set_size(contents.len() as u64);
+        header.set_mtime(mtime);
+        self.append_data(&mut header, name, contents)
+    }
+
+    fn add_symlink(&mut self, name: &str, target: &str, mtime: u64) -> io::Result<()> {
+        let mut header = tar::Header::new_gnu();
+        header.set_entry_type(tar::EntryType::Symlink);
+        header.set_mode(0o777);
+        header.set_size(0);
+        header.set_mtime(mtime);
+        self.append_link(&mut header, name, target)
+    }
+}
+
+impl<W: Write + Seek> ArchiveWriter for zip::ZipWriter<W> {
+    fn add_file(
+        &mut self,
+        name: &str,
+        contents: &[u8],
+        executable: bool,
+        mtime: u64,
+    ) -> io::Result<()> {
+        let options = zip_file_options(name, mtime)?
+            .compression_method(zip::CompressionMethod::Deflated)
+            .unix_permissions(if executable { 0o755 } else { 0o644 })
+            // Deflate can make incompressible data slightly larger, so switch
+            // to zip64 well before the sizes reach the 4GiB limit.
+            .large_file(contents.len() >= 0xf000_0000);
+        self.start_file(name, options).map_err(zip_error)?;
+        self.write_all(contents)
+    }
+
+    fn add_symlink(&mut self, name: &str, target: &str, mtime: u64) -> io::Result<()> {
+        let options = zip_file_options(name, mtime)?.unix_permissions(0o777);
+        self.add_symlink(name, target, options).map_err(zip_error)
+    }
+}
+
+fn zip_file_options(name: &str, mtime: u64) -> io::Result<zip::write::SimpleFileOptions> {
+    if name.len() > u16::MAX.into() {
+        return Err(io::Error::other(format!(
+            "{name} is too long for a zip archive"
+        )));
+    }
+    // Timestamps before 1980 can't be represented, so they are clamped.
+    let mtime = i64::try_from(mtime)
+        .ok()
+        .and_then(|secs| chrono::DateTime::from_timestamp(secs, 0))
+        .and_then(|time| {
+            zip::DateTime::from_date_and_time(
+                time.year().try_into().ok()?,
+                time.month() as u8,
+                time.day() as u8,
+                time.hour() as u8,
+                time.minute() as u8,
+                time.second() as u8,
+            )
+            .ok()
+        })
+        .unwrap_or_default();
+    Ok(zip::write::SimpleFileOptions::default().last_modified_time(mtime))
+}
@@ -22,9 +22,15 @@ use crate::cli_util::CommandHelper;
 use crate::cli_util::RevisionArg;
 use crate::command_error::CommandError;
 use crate::complete;
+use crate::description_util::backout_description;
+use crate::description_util::edit_description;
 use crate::ui::Ui;
 
 /// Apply the reverse of a revision on top of another revision
+///
+/// The descriptions of the new commits are rendered by the
+/// `templates.backout_description` template, and an editor is opened to edit
+/// each of them unless `--no-edit` is passed.
 #[derive(clap::Args, Clone, Debug)]
 pub(crate) struct BackoutArgs {
     /// The revision(s) to apply the reverse of
@@ -45,6 +51,12 @@ pub(crate) struct BackoutArgs {
         add = ArgValueCandidates::new(complete::all_revisions),
     )]
     destination: Vec<RevisionArg>,
+    /// Don't open an editor to edit the descriptions of the new commits
+    ///
+    /// The descriptions rendered by the `templates.backout_description`
+    /// template are used as is.
+    #[arg(long)]
+    no_edit: bool,
 }
 
 #[instrument(skip_all)]
@@ -79,16 +91,14 @@ pub(crate) fn cmd_backout(
     };
     let mut new_base_tree = merge_commit_trees(tx.repo(), &parents)?;
     for commit_to_back_out in to_back_out {
-        let commit_to_back_out_subject = commit_to_back_out
-            .description()
-            .lines()
-            .next()
-            .unwrap_or_default();
-        let new_commit_description = format!(
-            "Back out \"{}\"\n\nThis backs out commit {}.\n",
-            commit_to_back_out_subject,
-            &commit_to_back_out.id().hex()
-        );
+        let mut new_commit_description = backout_description(ui, &tx, &commit_to_back_out)?;
+        if !args.no_edit {
+            new_commit_description = edit_description(
+                tx.base_workspace_helper().repo_path(),
+                &new_commit_description,
+                command.settings(),
+            )?;
+        }
         let old_base_tree = commit_to_back_out.parent_tree(tx.repo())?;
         let old_tree = commit_to_back_out.tree()?;
         let new_tree = new_base_tree.merge(&old_tree, &old_base_tree)?;
@@ -69,7 +69,15 @@ pub struct BookmarkListArgs {
     tracked: bool,
 
     /// Show conflicted bookmarks only
-    #[arg(long, short, conflicts_with_all = ["all_remotes"])]
+    ///
+    /// Use `jj bookmark resolve` to pick one of the targets of a conflicted
+    /// bookmark.
+    #[arg(
+        long,
+        short,
+        alias = "conflicted-only",
+        conflicts_with_all = ["all_remotes"]
+    )]
     conflicted: bool,
 
     /// Show bookmarks whose local name matches
@@ -17,7 +17,9 @@ mod delete;
 mod forget;
 mod list;
 mod r#move;
+mod rebase_onto_remote;
 mod rename;
+mod resolve;
 mod set;
 mod track;
 mod untrack;
@@ -41,8 +43,12 @@ use self::list::cmd_bookmark_list;
 use self::list::BookmarkListArgs;
 use self::r#move::cmd_bookmark_move;
 use self::r#move::BookmarkMoveArgs;
+use self::rebase_onto_remote::cmd_bookmark_rebase_onto_remote;
+use self::rebase_onto_remote::BookmarkRebaseOntoRemoteArgs;
 use self::rename::cmd_bookmark_rename;
 use self::rename::BookmarkRenameArgs;
+use self::resolve::cmd_bookmark_resolve;
+use self::resolve::BookmarkResolveArgs;
 use self::set::cmd_bookmark_set;
 use self::set::BookmarkSetArgs;
 use self::track::cmd_bookmark_track;
@@ -75,8 +81,10 @@ pub enum BookmarkCommand {
     List(BookmarkListArgs),
     #[command(visible_alias("m"))]
     Move(BookmarkMoveArgs),
+    RebaseOntoRemote(BookmarkRebaseOntoRemoteArgs),
     #[command(visible_alias("r"))]
     Rename(BookmarkRenameArgs),
+    Resolve(BookmarkResolveArgs),
     #[command(visible_alias("s"))]
     Set(BookmarkSetArgs),
     #[command(visible_alias("t"))]
@@ -95,7 +103,11 @@ pub fn cmd_bookmark(
         BookmarkCommand::Forget(args) => cmd_bookmark_forget(ui, command, args),
         BookmarkCommand::List(args) => cmd_bookmark_list(ui, command, args),
         BookmarkCommand::Move(args) => cmd_bookmark_move(ui, command, args),
+        BookmarkCommand::RebaseOntoRemote(args) => {
+            cmd_bookmark_rebase_onto_remote(ui, command, args)
+        }
         BookmarkCommand::Rename(args) => cmd_bookmark_rename(ui, command, args),
+        BookmarkCommand::Resolve(args) => cmd_bookmark_resolve(ui, command, args),
         BookmarkCommand::Set(args) => cmd_bookmark_set(ui, command, args),
         BookmarkCommand::Track(args) => cmd_bookmark_track(ui, command, args),
         BookmarkCommand::Untrack(args) => cmd_bookmark_untrack(ui, command, args),
@@ -0,0 +1,219 @@
+// Copyright 2024 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use clap_complete::ArgValueCandidates;
+use itertools::Itertools as _;
+use jj_lib::git;
+use jj_lib::op_store::RefTarget;
+use jj_lib::repo::Repo as _;
+use jj_lib::revset::FailingSymbolResolver;
+use jj_lib::revset::RevsetExpression;
+use jj_lib::revset::RevsetIteratorExt as _;
+use jj_lib::rewrite::move_commits;
+use jj_lib::rewrite::MoveCommitsTarget;
+use jj_lib::rewrite::RebaseOptions;
+use jj_lib::str_util::StringPattern;
+
+use crate::cli_util::CommandHelper;
+use crate::command_error::internal_error;
+use crate::command_error::user_error;
+use crate::command_error::user_error_with_hint;
+use crate::command_error::CommandError;
+use crate::complete;
+use crate::ui::Ui;
+
+/// Rebase local commits onto a rewritten remote bookmark
+///
+/// If a tracked remote bookmark is rewritten (e.g. force-pushed) while there
+/// are local commits on top of it, fetching leaves the local bookmark
+/// conflicted between the local commits and the new remote target.
+///
+/// This command rebases the local commits, which were added on top of the old
+/// remote target, onto the new remote target and points the local bookmark to
+/// the rebased commits. Old remote commits that are no longer referenced are
+/// abandoned.
+#[derive(clap::Args, Clone, Debug)]
+pub struct BookmarkRebaseOntoRemoteArgs {
+    /// The conflicted local bookmark
+    #[arg(add = ArgValueCandidates::new(complete::local_bookmarks))]
+    name: String,
+
+    /// The remote whose bookmark was rewritten
+    ///
+    /// Defaults to the only remote the bookmark is tracking.
+    #[arg(long)]
+    remote: Option<String>,
+}
+
+pub fn cmd_bookmark_rebase_onto_remote(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    args: &BookmarkRebaseOntoRemoteArgs,
+) -> Result<(), CommandError> {
+    let mut workspace_command = command.workspace_helper(ui)?;
+    let repo = workspace_command.repo().clone();
+    let name = &args.name;
+    let local_target = repo.view().get_local_bookmark(name);
+    if local_target.is_absent() {
+        return Err(user_error(format!("No such bookmark: {name}")));
+    }
+
+    let remote = match &args.remote {
+        Some(remote) => remote.clone(),
+        None => {
+            let tracked_remotes = repo
+                .view()
+                .remote_bookmarks_matching(
+                    &StringPattern::exact(name),
+                    &StringPattern::everything(),
+                )
+                .filter(|((_, remote), remote_ref)| {
+                    *remote != git::REMOTE_NAME_FOR_LOCAL_GIT_REPO && remote_ref.is_tracking()
+                })
+                .map(|((_, remote), _)| remote)
+                .collect_vec();
+            match &tracked_remotes[..] {
+                [remote] => remote.to_string(),
+                [] => {
+                    return Err(user_error(format!(
+                        "Bookmark {name} doesn't track any remote bookmarks"
+                    )))
+                }
+                _ => {
+                    return Err(user_error_with_hint(
+                        format!("Bookmark {name} tracks multiple remote bookmarks"),
+                        "Specify the remote with --remote.",
+                    ))
+                }
+            }
+        }
+    };
+    let remote_ref = repo.view().get_remote_bookmark(name, &remote);
+    if !remote_ref.is_tracking() {
+        return Err(user_error(format!(
+            "Remote bookmark {name}@{remote} is not tracked"
+        )));
+    }
+    let Some(new_remote_id) = remote_ref.target.as_normal() else {
+        return Err(user_error(format!(
+            "Remote bookmark {name}@{remote} is absent or conflicted"
+        )));
+    };
+
+    // After a rewritten remote bookmark is merged into the local bookmark, the
+    // local target is "local_head + new_remote - old_remote".
+    let (old_remote_id, local_head_id) = {
+        let merge = local_target.as_merge();
+        let removes = merge.removes().flatten().collect_vec();
+        let local_heads = merge
+            .adds()
+            .flatten()
+            .filter(|id| *id != new_remote_id)
+            .collect_vec();
+        match (&removes[..], &local_heads[..]) {
+            ([old_remote_id], [local_head_id])
+                if merge.adds().flatten().contains(new_remote_id) =>
+            {
+                ((*old_remote_id).clone(), (*local_head_id).clone())
+            }
+            _ => {
+                return Err(user_error_with_hint(
+                    format!(
+                        "Bookmark {name} doesn't have local commits on top of a rewritten \
+                         {name}@{remote}"
+                    ),
+                    "Use `jj rebase` and `jj bookmark set` to update the bookmark manually.",
+                ))
+            }
+        }
+    };
+
+    let commits: Vec<_> = RevsetExpression::commits(vec![old_remote_id.clone()])
+        .range(&RevsetExpression::commits(vec![local_head_id.clone()]))
+        .evaluate(repo.as_ref())?
+        .iter()
+        .commits(repo.store())
+        .try_collect()?;
+    workspace_command.check_rewritable(commits.iter().map(|commit| commit.id()))?;
+
+    let mut tx = workspace_command.start_transaction();
+    let stats = move_commits(
+        command.settings(),
+        tx.repo_mut(),
+        std::slice::from_ref(new_remote_id),
+        &[],
+        &MoveCommitsTarget::Commits(commits),
+        &RebaseOptions::default(),
+    )?;
+    // References to the local commits have been updated to the rebased ones.
+    let new_local_head_id = tx
+        .repo()
+        .view()
+        .get_local_bookmark(name)
+        .added_ids()
+        .find(|id| *id != new_remote_id)
+        .ok_or_else(|| internal_error("Rebased local head not found"))?
+        .clone();
+    tx.repo_mut()
+        .set_local_bookmark_target(name, RefTarget::normal(new_local_head_id.clone()));
+
+    // Abandon the old remote commits unless something else still refers to
+    // them.
+    let pinned_expression = RevsetExpression::union_all(&[
+        RevsetExpression::bookmarks(StringPattern::everything()),
+        RevsetExpression::remote_bookmarks(
+            StringPattern::everything(),
+            StringPattern::everything(),
+            None,
+        ),
+        RevsetExpression::tags(StringPattern::everything()),
+        RevsetExpression::working_copies(),
+        RevsetExpression::visible_heads()
+            .minus(&RevsetExpression::commit(old_remote_id.clone()).ancestors()),
+        RevsetExpression::root(),
+    ]);
+    let abandoned_ids: Vec<_> = pinned_expression
+        .range(&RevsetExpression::commit(old_remote_id))
+        .resolve_user_expression(tx.repo(), &FailingSymbolResolver)?
+        .evaluate(tx.repo())?
+        .iter()
+        .try_collect()?;
+    for id in &abandoned_ids {
+        tx.repo_mut().record_abandoned_commit(id.clone());
+    }
+
+    if let Some(mut formatter) = ui.status_formatter() {
+        writeln!(
+            formatter,
+            "Rebased {} commits onto {name}@{remote}",
+            stats.num_rebased_targets
+        )?;
+        if !abandoned_ids.is_empty() {
+            writeln!(
+                formatter,
+                "Abandoned {} commits that are no longer reachable.",
+                abandoned_ids.len()
+            )?;
+        }
+        let new_local_head = tx.repo().store().get_commit(&new_local_head_id)?;
+        write!(formatter, "Moved bookmark {name} to ")?;
+        tx.write_commit_summary(formatter.as_mut(), &new_local_head)?;
+        writeln!(formatter)?;
+    }
+    tx.finish(
+        ui,
+        format!("rebase bookmark {name} onto rewritten remote {name}@{remote}"),
+    )?;
+    Ok(())
+}
@@ -0,0 +1,136 @@
+// Copyright 2024 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use clap_complete::ArgValueCandidates;
+use itertools::Itertools as _;
+use jj_lib::backend::CommitId;
+use jj_lib::object_id::ObjectId as _;
+use jj_lib::op_store::RefTarget;
+use jj_lib::repo::Repo as _;
+use jj_lib::str_util::StringPattern;
+use jj_lib::view::View;
+
+use crate::cli_util::CommandHelper;
+use crate::command_error::user_error;
+use crate::command_error::user_error_with_hint;
+use crate::command_error::CommandError;
+use crate::complete;
+use crate::ui::Ui;
+
+/// Resolve a conflicted bookmark by picking one of its targets
+///
+/// Without `--keep`, the targets ("sides") of the conflicted bookmark are
+/// listed along with the remotes whose bookmarks point to them.
+#[derive(clap::Args, Clone, Debug)]
+pub struct BookmarkResolveArgs {
+    /// The conflicted bookmark
+    #[arg(add = ArgValueCandidates::new(complete::local_bookmarks))]
+    name: String,
+
+    /// The side to point the bookmark to
+    ///
+    /// Either the number of the side as listed by this command, or the name of
+    /// a remote whose bookmark points to the side.
+    #[arg(long, value_name = "SIDE")]
+    keep: Option<String>,
+}
+
+pub fn cmd_bookmark_resolve(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    args: &BookmarkResolveArgs,
+) -> Result<(), CommandError> {
+    let mut workspace_command = command.workspace_helper(ui)?;
+    let repo = workspace_command.repo().clone();
+    let name = &args.name;
+    let local_target = repo.view().get_local_bookmark(name);
+    if local_target.is_absent() {
+        return Err(user_error(format!("No such bookmark: {name}")));
+    }
+    if !local_target.has_conflict() {
+        return Err(user_error(format!("Bookmark {name} is not conflicted")));
+    }
+    let sides = local_target.added_ids().unique().collect_vec();
+
+    let Some(side) = &args.keep else {
+        let mut formatter = ui.stdout_formatter();
+        for (i, &id) in sides.iter().enumerate() {
+            let commit = repo.store().get_commit(id)?;
+            write!(formatter, "{}: ", i + 1)?;
+            workspace_command.write_commit_summary(formatter.as_mut(), &commit)?;
+            let remotes = remotes_pointing_to(repo.view(), name, id);
+            if !remotes.is_empty() {
+                write!(formatter, " (from {})", remotes.join(", "))?;
+            }
+            writeln!(formatter)?;
+        }
+        drop(formatter);
+        writeln!(
+            ui.hint_default(),
+            "Use `jj bookmark resolve {name} --keep SIDE` to point the bookmark to one of these."
+        )?;
+        return Ok(());
+    };
+
+    let keep_id = if let Ok(number) = side.parse::<usize>() {
+        number
+            .checked_sub(1)
+            .and_then(|i| sides.get(i))
+            .copied()
+            .ok_or_else(|| {
+                user_error_with_hint(
+                    format!("Bookmark {name} has no side {number}"),
+                    format!("Run `jj bookmark resolve {name}` to list the sides."),
+                )
+            })?
+    } else {
+        repo.view()
+            .get_remote_bookmark(name, side)
+            .target
+            .as_normal()
+            .filter(|id| sides.contains(id))
+            .ok_or_else(|| {
+                user_error_with_hint(
+                    format!("Bookmark {name}@{side} doesn't point to a side of the conflict"),
+                    format!("Run `jj bookmark resolve {name}` to list the sides."),
+                )
+            })?
+    };
+    let keep_commit = repo.store().get_commit(keep_id)?;
+
+    let mut tx = workspace_command.start_transaction();
+    tx.repo_mut()
+        .set_local_bookmark_target(name, RefTarget::normal(keep_id.clone()));
+    if let Some(mut formatter) = ui.status_formatter() {
+        write!(formatter, "Moved bookmark {name} to ")?;
+        tx.write_commit_summary(formatter.as_mut(), &keep_commit)?;
+        writeln!(formatter)?;
+    }
+    tx.finish(
+        ui,
+        format!(
+            "resolve conflicted bookmark {name} to commit {}",
+            keep_id.hex()
+        ),
+    )?;
+    Ok(())
+}
+
+/// Returns the names of the remotes whose bookmark `name` points to `id`.
+fn remotes_pointing_to<'a>(view: &'a View, name: &str, id: &CommitId) -> Vec<&'a str> {
+    view.remote_bookmarks_matching(&StringPattern::exact(name), &StringPattern::everything())
+        .filter(|(_, remote_ref)| remote_ref.target.as_normal() == Some(id))
+        .map(|((_, remote), _)| remote)
+        .collect()
+}
@@ -22,6 +22,8 @@ use crate::cli_util::CommandHelper;
 use crate::command_error::user_error;
 use crate::command_error::CommandError;
 use crate::complete;
+use crate::description_util::check_description_placeholders;
+use crate::description_util::default_description;
 use crate::description_util::description_template;
 use crate::description_util::edit_description;
 use crate::description_util::join_message_paragraphs;
@@ -136,16 +138,24 @@ new working-copy commit.
         join_message_paragraphs(&args.message_paragraphs)
     } else {
         if commit_builder.description().is_empty() {
-            commit_builder
-                .set_description(command.settings().get_string("ui.default-description")?);
+            commit_builder.set_description(default_description(
+                tx.base_workspace_helper().workspace_root(),
+                command.settings(),
+            )?);
         }
         let temp_commit = commit_builder.write_hidden()?;
         let template = description_template(ui, &tx, "", &temp_commit)?;
-        edit_description(
+        let description = edit_description(
             tx.base_workspace_helper().repo_path(),
             &template,
             command.settings(),
-        )?
+        )?;
+        check_description_placeholders(
+            tx.base_workspace_helper().workspace_root(),
+            command.settings(),
+            &description,
+        )?;
+        description
     };
     commit_builder.set_description(description);
     let new_commit = commit_builder.write(tx.repo_mut())?;
@@ -0,0 +1,146 @@
+// Copyright 2024 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fmt::Debug;
+use std::fs::File;
+use std::io::BufReader;
+use std::io::BufWriter;
+use std::io::Write as _;
+use std::path::PathBuf;
+
+use clap::Subcommand;
+use jj_lib::default_index::read_commit_graph;
+use jj_lib::default_index::write_commit_graph;
+use jj_lib::default_index::AsCompositeIndex as _;
+use jj_lib::default_index::DefaultIndexStore;
+use jj_lib::default_index::DefaultReadonlyIndex;
+use jj_lib::file_util::IoResultExt as _;
+
+use crate::cli_util::CommandHelper;
+use crate::command_error::internal_error;
+use crate::command_error::user_error;
+use crate::command_error::user_error_with_message;
+use crate::command_error::CommandError;
+use crate::ui::Ui;
+
+/// Export or import the commit graph stored in the index
+///
+/// The exported graph can be imported in another clone of the same repo to
+/// seed its index, which avoids walking all commits in the backend.
+#[derive(Subcommand, Clone, Debug)]
+pub enum DebugCommitGraphCommand {
+    Export(DebugCommitGraphExportArgs),
+    Import(DebugCommitGraphImportArgs),
+}
+
+/// Write the commit ids, change ids, and parents of all indexed commits
+#[derive(clap::Args, Clone, Debug)]
+pub struct DebugCommitGraphExportArgs {
+    /// File to write to (default: standard output)
+    #[arg(value_hint = clap::ValueHint::FilePath)]
+    output: Option<PathBuf>,
+}
+
+/// Rebuild the index starting from an exported commit graph
+///
+/// The commits in the graph are trusted to exist in the repo. Commits that
+/// aren't in the graph are indexed as usual.
+#[derive(clap::Args, Clone, Debug)]
+pub struct DebugCommitGraphImportArgs {
+    /// File written by `jj debug commit-graph export`
+    #[arg(value_hint = clap::ValueHint::FilePath)]
+    input: PathBuf,
+}
+
+pub fn cmd_debug_commit_graph(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    subcommand: &DebugCommitGraphCommand,
+) -> Result<(), CommandError> {
+    match subcommand {
+        DebugCommitGraphCommand::Export(args) => cmd_debug_commit_graph_export(ui, command, args),
+        DebugCommitGraphCommand::Import(args) => cmd_debug_commit_graph_import(ui, command, args),
+    }
+}
+
+fn cmd_debug_commit_graph_export(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    args: &DebugCommitGraphExportArgs,
+) -> Result<(), CommandError> {
+    // Resolve the operation without loading the repo, so this command won't
+    // update the index.
+    let workspace = command.load_workspace()?;
+    let repo_loader = workspace.repo_loader();
+    let op = command.resolve_operation(ui, repo_loader)?;
+    let index_store = repo_loader.index_store();
+    let index = index_store
+        .get_index_at_op(&op, repo_loader.store())
+        .map_err(internal_error)?;
+    let Some(default_index) = index.as_any().downcast_ref::<DefaultReadonlyIndex>() else {
+        return Err(user_error(format!(
+            "Cannot export commit graph of indexes of type '{}'",
+            index_store.name()
+        )));
+    };
+    let num_commits = if let Some(path) = &args.output {
+        let file = File::create(path).context(path)?;
+        let mut output = BufWriter::new(file);
+        write_commit_graph(default_index.as_composite(), &mut output).context(path)?;
+        output.flush().context(path)?;
+        default_index.as_composite().num_commits()
+    } else {
+        write_commit_graph(default_index.as_composite(), &mut ui.stdout())?
+    };
+    writeln!(ui.status(), "Exported {num_commits} commits.")?;
+    Ok(())
+}
+
+fn cmd_debug_commit_graph_import(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    args: &DebugCommitGraphImportArgs,
+) -> Result<(), CommandError> {
+    // Resolve the operation without loading the repo. The index might have to
+    // be rebuilt while loading the repo.
+    let workspace = command.load_workspace()?;
+    let repo_loader = workspace.repo_loader();
+    let op = command.resolve_operation(ui, repo_loader)?;
+    let index_store = repo_loader.index_store();
+    let Some(default_index_store) = index_store.as_any().downcast_ref::<DefaultIndexStore>() else {
+        return Err(user_error(format!(
+            "Cannot import commit graph into indexes of type '{}'",
+            index_store.name()
+        )));
+    };
+    let store = repo_loader.store();
+    let file = File::open(&args.input).context(&args.input)?;
+    let entries = read_commit_graph(
+        &mut BufReader::new(file),
+        store.commit_id_length(),
+        store.change_id_length(),
+    )
+    .map_err(|err| user_error_with_message("Failed to import commit graph", err))?;
+    default_index_store.reinit().map_err(internal_error)?;
+    let default_index = default_index_store
+        .import_commit_graph(&entries, &op, store)
+        .map_err(internal_error)?;
+    writeln!(
+        ui.status(),
+        "Imported {} commits. Finished indexing {} commits.",
+        entries.len(),
+        default_index.as_composite().stats().num_commits
+    )?;
+    Ok(())
+}
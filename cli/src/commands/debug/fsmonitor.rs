@@ -0,0 +1,83 @@
+// Copyright 2024 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fmt::Debug;
+use std::io::Write as _;
+
+use clap::Subcommand;
+use jj_lib::fsmonitor::native;
+use jj_lib::fsmonitor::FsmonitorSettings;
+
+use super::check_local_disk_wc;
+use crate::cli_util::CommandHelper;
+use crate::command_error::user_error_with_message;
+use crate::command_error::CommandError;
+use crate::ui::Ui;
+
+#[derive(Subcommand, Clone, Debug)]
+pub enum DebugFsmonitorCommand {
+    /// Run the built-in filesystem monitor daemon in the foreground
+    ///
+    /// The daemon records the paths changed in the current workspace until it
+    /// is killed. Set `core.fsmonitor = "native"` to make snapshots use it.
+    Daemon,
+    /// Check whether the built-in filesystem monitor is enabled and running
+    Status,
+}
+
+pub fn cmd_debug_fsmonitor(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    subcommand: &DebugFsmonitorCommand,
+) -> Result<(), CommandError> {
+    let workspace_command = command.workspace_helper_no_snapshot(ui)?;
+    let wc = check_local_disk_wc(workspace_command.working_copy().as_any())?;
+    match subcommand {
+        DebugFsmonitorCommand::Daemon => {
+            writeln!(
+                ui.status(),
+                "Watching {} for changes",
+                workspace_command.workspace_root().display()
+            )?;
+            native::run_daemon(workspace_command.workspace_root(), wc.state_path()).map_err(
+                |err| user_error_with_message("The filesystem monitor daemon failed", err),
+            )?;
+        }
+        DebugFsmonitorCommand::Status => {
+            if command.settings().fsmonitor_settings()? == FsmonitorSettings::Native {
+                writeln!(
+                    ui.stdout(),
+                    "The built-in filesystem monitor is enabled via `core.fsmonitor`."
+                )?;
+            } else {
+                writeln!(
+                    ui.stdout(),
+                    r#"The built-in filesystem monitor is disabled. Set `core.fsmonitor="native"` to enable."#
+                )?;
+            }
+            let running = native::is_daemon_running(wc.state_path()).map_err(|err| {
+                user_error_with_message("Failed to check the filesystem monitor daemon", err)
+            })?;
+            if running {
+                writeln!(ui.stdout(), "The daemon is running.")?;
+            } else {
+                writeln!(
+                    ui.stdout(),
+                    "The daemon is not running. Start it with `jj debug fsmonitor daemon`."
+                )?;
+            }
+        }
+    }
+    Ok(())
+}
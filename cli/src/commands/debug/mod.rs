@@ -12,41 +12,63 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+pub mod commit_graph;
 pub mod copy_detection;
 pub mod fileset;
+pub mod fsmonitor;
 pub mod index;
 pub mod local_working_copy;
 pub mod operation;
+pub mod profile_revset;
+pub mod reachability;
 pub mod reindex;
+pub mod restore_backup;
 pub mod revset;
 pub mod snapshot;
+pub mod store;
 pub mod template;
 pub mod tree;
 pub mod watchman;
 pub mod working_copy;
 
 use std::any::Any;
+use std::error;
 use std::fmt::Debug;
+use std::io;
+use std::io::Write as _;
+use std::iter;
 
 use clap::Subcommand;
 use jj_lib::local_working_copy::LocalWorkingCopy;
 
+use self::commit_graph::cmd_debug_commit_graph;
+use self::commit_graph::DebugCommitGraphCommand;
 use self::copy_detection::cmd_debug_copy_detection;
 use self::copy_detection::CopyDetectionArgs;
 use self::fileset::cmd_debug_fileset;
 use self::fileset::DebugFilesetArgs;
+use self::fsmonitor::cmd_debug_fsmonitor;
+use self::fsmonitor::DebugFsmonitorCommand;
 use self::index::cmd_debug_index;
 use self::index::DebugIndexArgs;
 use self::local_working_copy::cmd_debug_local_working_copy;
 use self::local_working_copy::DebugLocalWorkingCopyArgs;
 use self::operation::cmd_debug_operation;
 use self::operation::DebugOperationArgs;
+use self::profile_revset::cmd_debug_profile_revset;
+use self::profile_revset::DebugProfileRevsetArgs;
+use self::reachability::cmd_debug_reachability;
+use self::reachability::DebugReachabilityArgs;
 use self::reindex::cmd_debug_reindex;
 use self::reindex::DebugReindexArgs;
+use self::restore_backup::cmd_debug_restore_backup;
+use self::restore_backup::DebugRestoreBackupArgs;
 use self::revset::cmd_debug_revset;
 use self::revset::DebugRevsetArgs;
 use self::snapshot::cmd_debug_snapshot;
 use self::snapshot::DebugSnapshotArgs;
+use self::store::cmd_debug_store;
+use self::store::DebugStoreCommand;
 use self::template::cmd_debug_template;
 use self::template::DebugTemplateArgs;
 use self::tree::cmd_debug_tree;
@@ -64,15 +86,24 @@ use crate::ui::Ui;
 #[derive(Subcommand, Clone, Debug)]
 #[command(hide = true)]
 pub enum DebugCommand {
+    #[command(subcommand)]
+    CommitGraph(DebugCommitGraphCommand),
     CopyDetection(CopyDetectionArgs),
     Fileset(DebugFilesetArgs),
+    #[command(subcommand)]
+    Fsmonitor(DebugFsmonitorCommand),
     Index(DebugIndexArgs),
     LocalWorkingCopy(DebugLocalWorkingCopyArgs),
     #[command(visible_alias = "view")]
     Operation(DebugOperationArgs),
+    ProfileRevset(DebugProfileRevsetArgs),
+    Reachability(DebugReachabilityArgs),
     Reindex(DebugReindexArgs),
+    RestoreBackup(DebugRestoreBackupArgs),
     Revset(DebugRevsetArgs),
     Snapshot(DebugSnapshotArgs),
+    #[command(subcommand)]
+    Store(DebugStoreCommand),
     Template(DebugTemplateArgs),
     Tree(DebugTreeArgs),
     #[command(subcommand)]
@@ -86,14 +117,20 @@ pub fn cmd_debug(
     subcommand: &DebugCommand,
 ) -> Result<(), CommandError> {
     match subcommand {
+        DebugCommand::CommitGraph(args) => cmd_debug_commit_graph(ui, command, args),
         DebugCommand::Fileset(args) => cmd_debug_fileset(ui, command, args),
+        DebugCommand::Fsmonitor(args) => cmd_debug_fsmonitor(ui, command, args),
         DebugCommand::Index(args) => cmd_debug_index(ui, command, args),
         DebugCommand::LocalWorkingCopy(args) => cmd_debug_local_working_copy(ui, command, args),
         DebugCommand::Operation(args) => cmd_debug_operation(ui, command, args),
+        DebugCommand::ProfileRevset(args) => cmd_debug_profile_revset(ui, command, args),
+        DebugCommand::Reachability(args) => cmd_debug_reachability(ui, command, args),
         DebugCommand::Reindex(args) => cmd_debug_reindex(ui, command, args),
+        DebugCommand::RestoreBackup(args) => cmd_debug_restore_backup(ui, command, args),
         DebugCommand::CopyDetection(args) => cmd_debug_copy_detection(ui, command, args),
         DebugCommand::Revset(args) => cmd_debug_revset(ui, command, args),
         DebugCommand::Snapshot(args) => cmd_debug_snapshot(ui, command, args),
+        DebugCommand::Store(args) => cmd_debug_store(ui, command, args),
         DebugCommand::Template(args) => cmd_debug_template(ui, command, args),
         DebugCommand::Tree(args) => cmd_debug_tree(ui, command, args),
         DebugCommand::Watchman(args) => cmd_debug_watchman(ui, command, args),
@@ -105,3 +142,28 @@ fn check_local_disk_wc(x: &dyn Any) -> Result<&LocalWorkingCopy, CommandError> {
     x.downcast_ref()
         .ok_or_else(|| user_error("This command requires a standard local-disk working copy"))
 }
+
+/// Prints an error found by `--check` in the config item `name`.
+fn print_config_check_error(ui: &Ui, name: &str, err: &dyn error::Error) -> io::Result<()> {
+    writeln!(ui.warning_default(), r#"Failed to parse "{name}""#)?;
+    for err in iter::successors(Some(err), |err| err.source()) {
+        writeln!(ui.stderr(), "{err}")?;
+    }
+    Ok(())
+}
+
+/// Reports the summary of `--check`, failing if any config item is invalid.
+fn finish_config_check(
+    ui: &Ui,
+    kind: &str,
+    num_checked: usize,
+    num_failed: usize,
+) -> Result<(), CommandError> {
+    if num_failed > 0 {
+        return Err(user_error(format!(
+            "{num_failed} of {num_checked} {kind} in config failed to parse"
+        )));
+    }
+    writeln!(ui.status(), "Checked {num_checked} {kind} in config")?;
+    Ok(())
+}
@@ -0,0 +1,152 @@
+// Copyright 2024 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fmt::Debug;
+use std::io;
+use std::io::Write as _;
+use std::time::Duration;
+use std::time::Instant;
+
+use jj_lib::default_index::AsCompositeIndex as _;
+use jj_lib::default_index::DefaultReadonlyIndex;
+use jj_lib::revset;
+use jj_lib::revset::RevsetDiagnostics;
+
+use crate::cli_util::CommandHelper;
+use crate::command_error::print_parse_diagnostics;
+use crate::command_error::CommandError;
+use crate::formatter::Formatter;
+use crate::revset_util;
+use crate::ui::Ui;
+
+/// Profile evaluation of a revset
+///
+/// Parses, resolves, optimizes, and evaluates the revset repeatedly, and
+/// prints the time spent in each phase along with the index stats. This can be
+/// used to report slow revsets without sharing the repo.
+#[derive(clap::Args, Clone, Debug)]
+pub struct DebugProfileRevsetArgs {
+    revision: String,
+    /// Number of times to evaluate the revset
+    #[arg(
+        long,
+        short = 'n',
+        default_value_t = 10,
+        value_parser = clap::value_parser!(u32).range(1..),
+    )]
+    iterations: u32,
+}
+
+const PHASE_NAMES: [&str; 5] = ["parse", "resolve", "optimize", "evaluate", "iterate"];
+
+pub fn cmd_debug_profile_revset(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    args: &DebugProfileRevsetArgs,
+) -> Result<(), CommandError> {
+    let workspace_command = command.workspace_helper(ui)?;
+    let workspace_ctx = workspace_command.revset_parse_context();
+    let repo = workspace_command.repo().as_ref();
+    let symbol_resolver = revset_util::default_symbol_resolver(
+        repo,
+        command.revset_extensions().symbol_resolvers(),
+        workspace_command.id_prefix_context(),
+    );
+
+    let mut timings: Vec<[Duration; PHASE_NAMES.len()]> = vec![];
+    let mut num_commits = 0;
+    for i in 0..args.iterations {
+        let mut diagnostics = RevsetDiagnostics::new();
+        let start = Instant::now();
+        let expression = revset::parse(&mut diagnostics, &args.revision, &workspace_ctx)?;
+        let parsed = Instant::now();
+        if i == 0 {
+            print_parse_diagnostics(ui, "In revset expression", &diagnostics)?;
+        }
+        let resolve_start = Instant::now();
+        let expression = expression.resolve_user_expression(repo, &symbol_resolver)?;
+        let resolved = Instant::now();
+        let expression = revset::optimize(expression);
+        let optimized = Instant::now();
+        let revset = expression.evaluate_unoptimized(repo)?;
+        let evaluated = Instant::now();
+        num_commits = 0;
+        for commit_id in revset.iter() {
+            commit_id?;
+            num_commits += 1;
+        }
+        let iterated = Instant::now();
+        timings.push([
+            parsed - start,
+            resolved - resolve_start,
+            optimized - resolved,
+            evaluated - optimized,
+            iterated - evaluated,
+        ]);
+    }
+
+    let mut formatter = ui.stdout_formatter();
+    writeln!(formatter, "Revset: {}", args.revision)?;
+    writeln!(formatter, "Number of commits: {num_commits}")?;
+    if let Some(default_index) = repo
+        .readonly_index()
+        .as_any()
+        .downcast_ref::<DefaultReadonlyIndex>()
+    {
+        let stats = default_index.as_composite().stats();
+        writeln!(formatter, "Index:")?;
+        writeln!(formatter, "  Number of commits: {}", stats.num_commits)?;
+        writeln!(formatter, "  Number of merges: {}", stats.num_merges)?;
+        writeln!(
+            formatter,
+            "  Max generation number: {}",
+            stats.max_generation_number
+        )?;
+        writeln!(formatter, "  Number of heads: {}", stats.num_heads)?;
+        writeln!(formatter, "  Number of levels: {}", stats.levels.len())?;
+    }
+    writeln!(formatter, "Timings over {} iterations:", args.iterations)?;
+    writeln!(
+        formatter,
+        "  {:<10} {:>12} {:>12} {:>12}",
+        "phase", "min", "mean", "max"
+    )?;
+    let mut totals = vec![Duration::ZERO; timings.len()];
+    for (phase, name) in PHASE_NAMES.iter().enumerate() {
+        let durations: Vec<_> = timings.iter().map(|timing| timing[phase]).collect();
+        for (total, duration) in totals.iter_mut().zip(&durations) {
+            *total += *duration;
+        }
+        write_phase_timing(formatter.as_mut(), name, &durations)?;
+    }
+    write_phase_timing(formatter.as_mut(), "total", &totals)?;
+    Ok(())
+}
+
+fn write_phase_timing(
+    formatter: &mut dyn Formatter,
+    name: &str,
+    durations: &[Duration],
+) -> io::Result<()> {
+    let min = durations.iter().min().copied().unwrap_or_default();
+    let max = durations.iter().max().copied().unwrap_or_default();
+    let mean = durations.iter().sum::<Duration>() / u32::try_from(durations.len()).unwrap();
+    writeln!(
+        formatter,
+        "  {name:<10} {:>12} {:>12} {:>12}",
+        format!("{min:?}"),
+        format!("{mean:?}"),
+        format!("{max:?}"),
+    )
+}
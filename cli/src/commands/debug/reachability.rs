@@ -0,0 +1,70 @@
+// Copyright 2024 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fmt::Debug;
+use std::io::Write as _;
+
+use itertools::Itertools as _;
+use jj_lib::backend::CommitId;
+use jj_lib::repo::Repo as _;
+
+use crate::cli_util::short_commit_hash;
+use crate::cli_util::CommandHelper;
+use crate::cli_util::RevisionArg;
+use crate::command_error::CommandError;
+use crate::ui::Ui;
+
+/// Check which commits are ancestors of which other commits
+///
+/// Every commit in `ANCESTORS` is checked against every commit in
+/// `DESCENDANTS` by a single batched index query.
+#[derive(clap::Args, Clone, Debug)]
+pub struct DebugReachabilityArgs {
+    /// The possible ancestors
+    ancestors: RevisionArg,
+    /// The possible descendants
+    descendants: RevisionArg,
+}
+
+pub fn cmd_debug_reachability(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    args: &DebugReachabilityArgs,
+) -> Result<(), CommandError> {
+    let workspace_command = command.workspace_helper(ui)?;
+    let ancestor_ids: Vec<CommitId> = workspace_command
+        .parse_revset(ui, &args.ancestors)?
+        .evaluate_to_commit_ids()?
+        .try_collect()?;
+    let descendant_ids: Vec<CommitId> = workspace_command
+        .parse_revset(ui, &args.descendants)?
+        .evaluate_to_commit_ids()?
+        .try_collect()?;
+    let pairs = descendant_ids
+        .iter()
+        .cartesian_product(&ancestor_ids)
+        .map(|(descendant_id, ancestor_id)| (ancestor_id.clone(), descendant_id.clone()))
+        .collect_vec();
+    let results = workspace_command.repo().index().is_ancestor_batch(&pairs);
+    for ((ancestor_id, descendant_id), is_ancestor) in pairs.iter().zip(results) {
+        writeln!(
+            ui.stdout(),
+            "{} {} {}",
+            short_commit_hash(ancestor_id),
+            if is_ancestor { "->" } else { "-/->" },
+            short_commit_hash(descendant_id),
+        )?;
+    }
+    Ok(())
+}
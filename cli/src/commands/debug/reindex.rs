@@ -17,6 +17,7 @@ use std::io::Write as _;
 
 use jj_lib::default_index::AsCompositeIndex as _;
 use jj_lib::default_index::DefaultIndexStore;
+use jj_lib::repo_backup::RepoBackups;
 
 use crate::cli_util::CommandHelper;
 use crate::command_error::internal_error;
@@ -25,13 +26,20 @@ use crate::command_error::CommandError;
 use crate::ui::Ui;
 
 /// Rebuild commit index
+///
+/// A backup of the index directory is saved first. Use `jj debug
+/// restore-backup` to restore it.
 #[derive(clap::Args, Clone, Debug)]
-pub struct DebugReindexArgs {}
+pub struct DebugReindexArgs {
+    /// Don't save a backup of the index before rebuilding it
+    #[arg(long)]
+    no_backup: bool,
+}
 
 pub fn cmd_debug_reindex(
     ui: &mut Ui,
     command: &CommandHelper,
-    _args: &DebugReindexArgs,
+    args: &DebugReindexArgs,
 ) -> Result<(), CommandError> {
     // Resolve the operation without loading the repo. The index might have to
     // be rebuilt while loading the repo.
@@ -40,6 +48,16 @@ pub fn cmd_debug_reindex(
     let op = command.resolve_operation(ui, repo_loader)?;
     let index_store = repo_loader.index_store();
     if let Some(default_index_store) = index_store.as_any().downcast_ref::<DefaultIndexStore>() {
+        if !args.no_backup {
+            let backup = RepoBackups::load(workspace.repo_path())
+                .create("reindex", &["index"])
+                .map_err(internal_error)?;
+            writeln!(
+                ui.status(),
+                "Saved a backup of the index as {}.",
+                backup.name
+            )?;
+        }
         default_index_store.reinit().map_err(internal_error)?;
         let default_index = default_index_store
             .build_index_at_operation(&op, repo_loader.store())
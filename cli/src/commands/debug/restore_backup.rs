@@ -0,0 +1,72 @@
+// Copyright 2024 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fmt::Debug;
+use std::io::Write as _;
+
+use jj_lib::repo_backup::RepoBackupError;
+use jj_lib::repo_backup::RepoBackups;
+
+use crate::cli_util::CommandHelper;
+use crate::command_error::internal_error;
+use crate::command_error::user_error;
+use crate::command_error::CommandError;
+use crate::ui::Ui;
+
+/// List or restore backups of repo directories
+///
+/// Commands that rewrite store files in place, such as `jj debug reindex`,
+/// first save a backup of the directories they touch. Without arguments, the
+/// available backups are listed.
+#[derive(clap::Args, Clone, Debug)]
+pub struct DebugRestoreBackupArgs {
+    /// Name of the backup to restore
+    backup: Option<String>,
+}
+
+pub fn cmd_debug_restore_backup(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    args: &DebugRestoreBackupArgs,
+) -> Result<(), CommandError> {
+    // Don't load the repo. The directories to be restored might be broken.
+    let workspace = command.load_workspace()?;
+    let backups = RepoBackups::load(workspace.repo_path());
+    let Some(name) = &args.backup else {
+        let mut formatter = ui.stdout_formatter();
+        for backup in backups.list().map_err(internal_error)? {
+            writeln!(
+                formatter,
+                "{}: {} ({})",
+                backup.name,
+                backup.metadata.reason,
+                backup.metadata.dirs.join(", ")
+            )?;
+        }
+        return Ok(());
+    };
+    let backup = backups.restore(name).map_err(|err| match err {
+        RepoBackupError::NotFound(_) | RepoBackupError::UnsupportedVersion { .. } => {
+            user_error(err)
+        }
+        _ => internal_error(err),
+    })?;
+    writeln!(
+        ui.status(),
+        "Restored {} from backup {}.",
+        backup.metadata.dirs.join(", "),
+        backup.name
+    )?;
+    Ok(())
+}
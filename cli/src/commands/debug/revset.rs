@@ -12,23 +12,45 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::error;
 use std::fmt::Debug;
+use std::io;
 use std::io::Write as _;
 
+use itertools::Itertools as _;
+use jj_lib::config::ConfigGetError;
 use jj_lib::object_id::ObjectId;
 use jj_lib::revset;
 use jj_lib::revset::RevsetDiagnostics;
 
+use super::finish_config_check;
+use super::print_config_check_error;
 use crate::cli_util::CommandHelper;
+use crate::cli_util::RevisionArg;
+use crate::cli_util::WorkspaceCommandHelper;
 use crate::command_error::print_parse_diagnostics;
 use crate::command_error::CommandError;
 use crate::revset_util;
 use crate::ui::Ui;
 
 /// Evaluate revset to full commit IDs
+///
+/// If commits are given with `--commit`, prints whether each of them is
+/// included in the revset instead. This can be used to test revset config
+/// snippets.
 #[derive(clap::Args, Clone, Debug)]
 pub struct DebugRevsetArgs {
-    revision: String,
+    #[arg(required_unless_present = "check")]
+    revision: Option<String>,
+    /// Test whether these commits are included in the revset
+    #[arg(long, short, value_name = "REVSETS")]
+    commit: Vec<RevisionArg>,
+    /// Check the revsets and revset aliases defined in the config
+    ///
+    /// Fails if any of them is invalid, which is useful for validating
+    /// `.jj/repo/config.toml` in CI.
+    #[arg(long, conflicts_with_all = ["revision", "commit"])]
+    check: bool,
 }
 
 pub fn cmd_debug_revset(
@@ -37,11 +59,31 @@ pub fn cmd_debug_revset(
     args: &DebugRevsetArgs,
 ) -> Result<(), CommandError> {
     let workspace_command = command.workspace_helper(ui)?;
+    let Some(revision) = &args.revision else {
+        return check_config_revsets(ui, command, &workspace_command);
+    };
+    if !args.commit.is_empty() {
+        let revset = workspace_command
+            .parse_revset(ui, &RevisionArg::from(revision.clone()))?
+            .evaluate()?;
+        let is_included = revset.containing_fn();
+        let commits: Vec<_> = workspace_command
+            .parse_union_revsets(ui, &args.commit)?
+            .evaluate_to_commits()?
+            .try_collect()?;
+        let mut formatter = ui.stdout_formatter();
+        for commit in &commits {
+            write!(formatter, "{} ", is_included(commit.id())?)?;
+            workspace_command.write_commit_summary(formatter.as_mut(), commit)?;
+            writeln!(formatter)?;
+        }
+        return Ok(());
+    }
     let workspace_ctx = workspace_command.revset_parse_context();
     let repo = workspace_command.repo().as_ref();
 
     let mut diagnostics = RevsetDiagnostics::new();
-    let expression = revset::parse(&mut diagnostics, &args.revision, &workspace_ctx)?;
+    let expression = revset::parse(&mut diagnostics, revision, &workspace_ctx)?;
     print_parse_diagnostics(ui, "In revset expression", &diagnostics)?;
     writeln!(ui.stdout(), "-- Parsed:")?;
     writeln!(ui.stdout(), "{expression:#?}")?;
@@ -78,3 +120,41 @@ pub fn cmd_debug_revset(
     }
     Ok(())
 }
+
+fn check_config_revsets(
+    ui: &Ui,
+    command: &CommandHelper,
+    workspace_command: &WorkspaceCommandHelper,
+) -> Result<(), CommandError> {
+    let settings = command.settings();
+    let workspace_ctx = workspace_command.revset_parse_context();
+    let mut num_checked = 0;
+    let mut num_failed = 0;
+    let mut check = |name: &str, revset_str: Result<String, ConfigGetError>| -> io::Result<()> {
+        num_checked += 1;
+        let result: Result<_, Box<dyn error::Error + Send + Sync>> =
+            revset_str.map_err(|err| err.into()).and_then(|text| {
+                let mut diagnostics = RevsetDiagnostics::new();
+                let result = revset::parse(&mut diagnostics, &text, &workspace_ctx);
+                print_parse_diagnostics(ui, &format!(r#"In revset "{name}""#), &diagnostics)?;
+                result.map_err(|err| err.into())
+            });
+        if let Err(err) = result {
+            print_config_check_error(ui, name, &*err)?;
+            num_failed += 1;
+        }
+        Ok(())
+    };
+    // Parsing the declaration expands the alias with its parameters as
+    // symbols, so the definition is parsed too.
+    for decl in settings.table_keys("revset-aliases") {
+        check(&format!("revset-aliases.{decl}"), Ok(decl.to_owned()))?;
+    }
+    for key in settings.table_keys("revsets") {
+        check(
+            &format!("revsets.{key}"),
+            settings.get_string(["revsets", key]),
+        )?;
+    }
+    finish_config_check(ui, "revsets", num_checked, num_failed)
+}
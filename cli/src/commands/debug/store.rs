@@ -0,0 +1,217 @@
+// Copyright 2026 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::BTreeMap;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use clap::Subcommand;
+use itertools::Itertools as _;
+use jj_lib::backend::BackendResult;
+use jj_lib::backend::FileId;
+use jj_lib::backend::ObjectKind;
+use jj_lib::backend::TreeId;
+use jj_lib::backend::TreeValue;
+use jj_lib::object_id::ObjectId as _;
+use jj_lib::repo::Repo as _;
+use jj_lib::repo_path::RepoPathBuf;
+use jj_lib::store::Store;
+
+use crate::cli_util::CommandHelper;
+use crate::cli_util::RevisionArg;
+use crate::command_error::user_error;
+use crate::command_error::CommandError;
+use crate::ui::Ui;
+
+/// Inspect the storage of commits, trees, and files
+#[derive(Subcommand, Clone, Debug)]
+pub enum DebugStoreCommand {
+    Stats(DebugStoreStatsArgs),
+}
+
+/// Show the number and size of stored objects by type
+///
+/// Objects which are no longer reachable but haven't been garbage-collected
+/// yet are included. Also lists the largest files, and how much the file
+/// contents of the given revisions are deduplicated in the store.
+#[derive(clap::Args, Clone, Debug)]
+pub struct DebugStoreStatsArgs {
+    /// Revisions to compute the deduplication ratio for
+    #[arg(long, short, default_value = "all()", value_name = "REVSETS")]
+    revisions: RevisionArg,
+    /// Number of largest files to list
+    #[arg(long, default_value_t = 10)]
+    limit: usize,
+}
+
+pub fn cmd_debug_store(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    subcommand: &DebugStoreCommand,
+) -> Result<(), CommandError> {
+    match subcommand {
+        DebugStoreCommand::Stats(args) => cmd_debug_store_stats(ui, command, args),
+    }
+}
+
+fn cmd_debug_store_stats(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    args: &DebugStoreStatsArgs,
+) -> Result<(), CommandError> {
+    let workspace_command = command.workspace_helper(ui)?;
+    let store = workspace_command.repo().store();
+    let Some(backend) = store.enumerable_backend() else {
+        return Err(user_error(format!(
+            "Cannot get stats for backends of type '{}'",
+            store.backend_name()
+        )));
+    };
+    let objects = backend.all_objects()?;
+
+    let mut totals: BTreeMap<ObjectKind, (usize, u64)> = BTreeMap::new();
+    for object in &objects {
+        let (count, size) = totals.entry(object.kind).or_default();
+        *count += 1;
+        *size += object.size;
+    }
+    let file_sizes: HashMap<&[u8], u64> = objects
+        .iter()
+        .filter(|object| object.kind == ObjectKind::File)
+        .map(|object| (object.id.as_slice(), object.size))
+        .collect();
+
+    let mut dedup = DedupStats::new(store, &file_sizes);
+    let mut num_revisions = 0;
+    for commit in workspace_command
+        .parse_revset(ui, &args.revisions)?
+        .evaluate_to_commits()?
+    {
+        for tree_id in commit?.tree_id().to_merge().iter() {
+            dedup.referenced_size += dedup.tree_size(RepoPathBuf::root(), tree_id)?;
+        }
+        num_revisions += 1;
+    }
+
+    let mut formatter = ui.stdout_formatter();
+    let formatter = formatter.as_mut();
+    writeln!(formatter, "Backend: {}", store.backend_name())?;
+    writeln!(formatter, "Objects:")?;
+    for kind in [
+        ObjectKind::Commit,
+        ObjectKind::Tree,
+        ObjectKind::File,
+        ObjectKind::Symlink,
+        ObjectKind::Conflict,
+    ] {
+        let (count, size) = totals.get(&kind).copied().unwrap_or_default();
+        writeln!(formatter, "  {}: {count} ({size} bytes)", kind_name(kind))?;
+    }
+    let total_size: u64 = objects.iter().map(|object| object.size).sum();
+    writeln!(formatter, "  Total: {} ({total_size} bytes)", objects.len())?;
+
+    writeln!(formatter, "Largest files:")?;
+    let largest = objects
+        .iter()
+        .filter(|object| object.kind == ObjectKind::File)
+        .sorted_by_key(|object| (std::cmp::Reverse(object.size), &object.id))
+        .take(args.limit);
+    for object in largest {
+        let id = FileId::new(object.id.clone()).hex();
+        write!(
+            formatter,
+            "  {} bytes {}",
+            object.size,
+            &id[..id.len().min(12)]
+        )?;
+        if let Some(path) = dedup.file_paths.get(object.id.as_slice()) {
+            write!(formatter, " {}", path.as_internal_file_string())?;
+        }
+        writeln!(formatter)?;
+    }
+
+    let unique_size: u64 = dedup
+        .file_paths
+        .keys()
+        .map(|id| file_sizes.get(id.as_slice()).copied().unwrap_or(0))
+        .sum();
+    writeln!(formatter, "Deduplication over {num_revisions} revisions:")?;
+    writeln!(
+        formatter,
+        "  Referenced file contents: {} bytes",
+        dedup.referenced_size
+    )?;
+    writeln!(formatter, "  Unique file contents: {unique_size} bytes")?;
+    if unique_size > 0 {
+        let ratio = dedup.referenced_size as f64 / unique_size as f64;
+        writeln!(formatter, "  Ratio: {ratio:.2}")?;
+    }
+    Ok(())
+}
+
+fn kind_name(kind: ObjectKind) -> &'static str {
+    match kind {
+        ObjectKind::Commit => "Commits",
+        ObjectKind::Tree => "Trees",
+        ObjectKind::File => "Files",
+        ObjectKind::Symlink => "Symlinks",
+        ObjectKind::Conflict => "Conflicts",
+    }
+}
+
+/// Sums up the sizes of the files in trees. Each tree is read only once, so
+/// the cost is proportional to the number of distinct trees.
+struct DedupStats<'a> {
+    store: &'a Arc<Store>,
+    file_sizes: &'a HashMap<&'a [u8], u64>,
+    tree_sizes: HashMap<TreeId, u64>,
+    /// First path at which each file was found.
+    file_paths: HashMap<Vec<u8>, RepoPathBuf>,
+    referenced_size: u64,
+}
+
+impl<'a> DedupStats<'a> {
+    fn new(store: &'a Arc<Store>, file_sizes: &'a HashMap<&'a [u8], u64>) -> Self {
+        DedupStats {
+            store,
+            file_sizes,
+            tree_sizes: HashMap::new(),
+            file_paths: HashMap::new(),
+            referenced_size: 0,
+        }
+    }
+
+    fn tree_size(&mut self, dir: RepoPathBuf, id: &TreeId) -> BackendResult<u64> {
+        if let Some(size) = self.tree_sizes.get(id) {
+            return Ok(*size);
+        }
+        let tree = self.store.get_tree(dir, id)?;
+        let mut total = 0;
+        for entry in tree.entries_non_recursive() {
+            let path = tree.dir().join(entry.name());
+            match entry.value() {
+                TreeValue::File { id, .. } => {
+                    total += self.file_sizes.get(id.as_bytes()).copied().unwrap_or(0);
+                    self.file_paths
+                        .entry(id.as_bytes().to_vec())
+                        .or_insert(path);
+                }
+                TreeValue::Tree(id) => total += self.tree_size(path, id)?,
+                TreeValue::Symlink(_) | TreeValue::Conflict(_) | TreeValue::GitSubmodule(_) => {}
+            }
+        }
+        self.tree_sizes.insert(id.clone(), total);
+        Ok(total)
+    }
+}
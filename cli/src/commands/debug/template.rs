@@ -15,23 +15,138 @@
 use std::fmt::Debug;
 use std::io::Write as _;
 
+use itertools::Itertools as _;
+
+use super::finish_config_check;
+use super::print_config_check_error;
 use crate::cli_util::CommandHelper;
+use crate::cli_util::RevisionArg;
+use crate::command_error::print_parse_diagnostics;
 use crate::command_error::CommandError;
+use crate::commit_templater::CommitTemplateLanguage;
+use crate::operation_templater::OperationTemplateLanguage;
+use crate::template_builder;
 use crate::template_parser;
+use crate::template_parser::TemplateDiagnostics;
 use crate::ui::Ui;
 
+/// Templates in the `templates` table which are evaluated against commits.
+const COMMIT_TEMPLATE_KEYS: &[&str] = &[
+    "annotate_commit_summary",
+    "backout_description",
+    "commit_summary",
+    "draft_commit_description",
+    "log",
+    "show",
+];
+
+/// Templates in the `templates` table which are evaluated against operations.
+const OPERATION_TEMPLATE_KEYS: &[&str] = &["op_log", "op_summary"];
+
 /// Parse a template
+///
+/// If revisions are given, the template is evaluated against each of them,
+/// like `jj log --no-graph -T`. This can be used to test template config
+/// snippets.
 #[derive(clap::Args, Clone, Debug)]
 pub struct DebugTemplateArgs {
-    template: String,
+    #[arg(required_unless_present = "check")]
+    template: Option<String>,
+    /// Evaluate the template against these commits
+    #[arg(long, short, value_name = "REVSETS")]
+    revisions: Vec<RevisionArg>,
+    /// Check the templates and template aliases defined in the config
+    ///
+    /// Commit and operation templates are type-checked. Other templates and
+    /// the template aliases are only parsed. Fails if any of them is invalid,
+    /// which is useful for validating `.jj/repo/config.toml` in CI.
+    #[arg(long, conflicts_with_all = ["template", "revisions"])]
+    check: bool,
 }
 
 pub fn cmd_debug_template(
     ui: &mut Ui,
-    _command: &CommandHelper,
+    command: &CommandHelper,
     args: &DebugTemplateArgs,
 ) -> Result<(), CommandError> {
-    let node = template_parser::parse_template(&args.template)?;
-    writeln!(ui.stdout(), "{node:#?}")?;
+    let Some(template_text) = &args.template else {
+        return check_config_templates(ui, command);
+    };
+    if args.revisions.is_empty() {
+        let node = template_parser::parse_template(template_text)?;
+        writeln!(ui.stdout(), "{node:#?}")?;
+        return Ok(());
+    }
+    let workspace_command = command.workspace_helper(ui)?;
+    let template = workspace_command.parse_commit_template(ui, template_text)?;
+    let commits: Vec<_> = workspace_command
+        .parse_union_revsets(ui, &args.revisions)?
+        .evaluate_to_commits()?
+        .try_collect()?;
+    let mut formatter = ui.stdout_formatter();
+    for commit in &commits {
+        template.format(commit, formatter.as_mut())?;
+    }
     Ok(())
 }
+
+fn check_config_templates(ui: &mut Ui, command: &CommandHelper) -> Result<(), CommandError> {
+    let workspace_command = command.workspace_helper(ui)?;
+    let settings = command.settings();
+    let aliases_map = workspace_command.template_aliases_map();
+    let mut num_checked = 0;
+    let mut num_failed = 0;
+
+    // Parsing the declaration expands the alias with its parameters as
+    // keywords, so the definition is parsed too.
+    for decl in settings.table_keys("template-aliases") {
+        num_checked += 1;
+        if let Err(err) = template_parser::parse(decl, aliases_map) {
+            print_config_check_error(ui, &format!("template-aliases.{decl}"), &err)?;
+            num_failed += 1;
+        }
+    }
+
+    let commit_language = workspace_command.commit_template_language();
+    let operation_language = workspace_command.operation_template_language();
+    for key in settings.table_keys("templates") {
+        num_checked += 1;
+        let name = format!("templates.{key}");
+        let template_text = match settings.get_string(["templates", key]) {
+            Ok(text) => text,
+            Err(err) => {
+                print_config_check_error(ui, &name, &err)?;
+                num_failed += 1;
+                continue;
+            }
+        };
+        let mut diagnostics = TemplateDiagnostics::new();
+        let result = if COMMIT_TEMPLATE_KEYS.contains(&key) {
+            template_builder::parse(
+                &commit_language,
+                &mut diagnostics,
+                &template_text,
+                aliases_map,
+                CommitTemplateLanguage::wrap_commit,
+            )
+            .map(|_| ())
+        } else if OPERATION_TEMPLATE_KEYS.contains(&key) {
+            template_builder::parse(
+                &operation_language,
+                &mut diagnostics,
+                &template_text,
+                aliases_map,
+                OperationTemplateLanguage::wrap_operation,
+            )
+            .map(|_| ())
+        } else {
+            template_parser::parse(&template_text, aliases_map).map(|_| ())
+        };
+        print_parse_diagnostics(ui, &format!(r#"In template "{name}""#), &diagnostics)?;
+        if let Err(err) = result {
+            print_config_check_error(ui, &name, &err)?;
+            num_failed += 1;
+        }
+    }
+    finish_config_check(ui, "templates", num_checked, num_failed)
+}
@@ -28,6 +28,8 @@ use crate::cli_util::RevisionArg;
 use crate::command_error::user_error;
 use crate::command_error::CommandError;
 use crate::complete;
+use crate::description_util::check_description_placeholders;
+use crate::description_util::default_description;
 use crate::description_util::description_template;
 use crate::description_util::edit_description;
 use crate::description_util::edit_multiple_descriptions;
@@ -151,8 +153,10 @@ pub(crate) fn cmd_describe(
                     .rewrite_commit(command.settings(), commit)
                     .detach();
                 if commit_builder.description().is_empty() {
-                    commit_builder
-                        .set_description(command.settings().get_string("ui.default-description")?);
+                    commit_builder.set_description(default_description(
+                        tx.base_workspace_helper().workspace_root(),
+                        command.settings(),
+                    )?);
                 }
                 if args.reset_author {
                     let new_author = commit_builder.committer().clone();
@@ -178,6 +182,11 @@ pub(crate) fn cmd_describe(
                 &template,
                 command.settings(),
             )?;
+            check_description_placeholders(
+                tx.base_workspace_helper().workspace_root(),
+                command.settings(),
+                &description,
+            )?;
 
             vec![(&commits[0], description)]
         } else {
@@ -208,13 +217,20 @@ pub(crate) fn cmd_describe(
                 )));
             }
 
-            let commit_descriptions = commits
+            let commit_descriptions: Vec<_> = commits
                 .iter()
                 .map(|commit| {
                     let description = descriptions.get(commit.id()).unwrap().to_owned();
                     (commit, description)
                 })
                 .collect();
+            for (_, description) in &commit_descriptions {
+                check_description_placeholders(
+                    tx.base_workspace_helper().workspace_root(),
+                    command.settings(),
+                    description,
+                )?;
+            }
 
             commit_descriptions
         }
@@ -0,0 +1,96 @@
+// Copyright 2026 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::io::Write as _;
+
+use itertools::Itertools as _;
+use jj_lib::local_working_copy::LocalWorkingCopy;
+use jj_lib::local_working_copy::LockedLocalWorkingCopy;
+use tracing::instrument;
+
+use crate::cli_util::CommandHelper;
+use crate::command_error::user_error;
+use crate::command_error::CommandError;
+use crate::ui::Ui;
+
+/// Skip checking paths for changes when snapshotting the working copy
+///
+/// Files under the paths stay tracked, but changes to them aren't snapshotted,
+/// deleted files aren't noticed, and new files aren't tracked. Use `jj file
+/// refresh` to snapshot the paths once, or `--remove` to check them again on
+/// every snapshot. This is useful for huge generated directories which must
+/// be tracked but are never edited by hand.
+///
+/// The paths are recorded in the working-copy state, so they only apply to
+/// the current workspace. Without arguments, the paths which are currently
+/// assumed to be unchanged are listed.
+#[derive(clap::Args, Clone, Debug)]
+pub(crate) struct FileAssumeUnchangedArgs {
+    /// Files or directories to assume unchanged
+    #[arg(value_name = "PATHS", value_hint = clap::ValueHint::AnyPath)]
+    paths: Vec<String>,
+    /// Check the paths for changes again
+    #[arg(long, requires = "paths")]
+    remove: bool,
+}
+
+#[instrument(skip_all)]
+pub(crate) fn cmd_file_assume_unchanged(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    args: &FileAssumeUnchangedArgs,
+) -> Result<(), CommandError> {
+    let mut workspace_command = command.workspace_helper(ui)?;
+    if args.paths.is_empty() {
+        let Some(wc): Option<&LocalWorkingCopy> =
+            workspace_command.working_copy().as_any().downcast_ref()
+        else {
+            return Err(user_error(
+                "This command requires a standard local-disk working copy",
+            ));
+        };
+        for path in wc.assume_unchanged_paths()? {
+            writeln!(ui.stdout(), "{}", workspace_command.format_file_path(path))?;
+        }
+        return Ok(());
+    }
+
+    let paths: Vec<_> = args
+        .paths
+        .iter()
+        .map(|path| workspace_command.parse_file_path(path))
+        .try_collect()?;
+    let (mut locked_ws, _wc_commit) = workspace_command.start_working_copy_mutation()?;
+    let Some(locked_local_wc): Option<&mut LockedLocalWorkingCopy> =
+        locked_ws.locked_wc().as_any_mut().downcast_mut()
+    else {
+        return Err(user_error(
+            "This command requires a standard local-disk working copy",
+        ));
+    };
+    let old_paths = locked_local_wc.assume_unchanged_paths()?;
+    let new_paths = if args.remove {
+        old_paths
+            .iter()
+            .filter(|path| !paths.contains(path))
+            .cloned()
+            .collect()
+    } else {
+        old_paths.iter().chain(&paths).cloned().collect()
+    };
+    locked_local_wc.set_assume_unchanged_paths(new_paths)?;
+    let operation_id = locked_ws.locked_wc().old_operation_id().clone();
+    locked_ws.finish(operation_id)?;
+    Ok(())
+}
@@ -13,8 +13,11 @@
 // limitations under the License.
 
 pub mod annotate;
+pub mod assume_unchanged;
 pub mod chmod;
 pub mod list;
+pub mod refresh;
+pub mod restore;
 pub mod show;
 pub mod track;
 pub mod untrack;
@@ -27,8 +30,11 @@ use crate::ui::Ui;
 #[derive(clap::Subcommand, Clone, Debug)]
 pub enum FileCommand {
     Annotate(annotate::FileAnnotateArgs),
+    AssumeUnchanged(assume_unchanged::FileAssumeUnchangedArgs),
     Chmod(chmod::FileChmodArgs),
     List(list::FileListArgs),
+    Refresh(refresh::FileRefreshArgs),
+    Restore(restore::FileRestoreArgs),
     Show(show::FileShowArgs),
     Track(track::FileTrackArgs),
     Untrack(untrack::FileUntrackArgs),
@@ -41,8 +47,13 @@ pub fn cmd_file(
 ) -> Result<(), CommandError> {
     match subcommand {
         FileCommand::Annotate(args) => annotate::cmd_file_annotate(ui, command, args),
+        FileCommand::AssumeUnchanged(args) => {
+            assume_unchanged::cmd_file_assume_unchanged(ui, command, args)
+        }
         FileCommand::Chmod(args) => chmod::cmd_file_chmod(ui, command, args),
         FileCommand::List(args) => list::cmd_file_list(ui, command, args),
+        FileCommand::Refresh(args) => refresh::cmd_file_refresh(ui, command, args),
+        FileCommand::Restore(args) => restore::cmd_file_restore(ui, command, args),
         FileCommand::Show(args) => show::cmd_file_show(ui, command, args),
         FileCommand::Track(args) => track::cmd_file_track(ui, command, args),
         FileCommand::Untrack(args) => untrack::cmd_file_untrack(ui, command, args),
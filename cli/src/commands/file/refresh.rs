@@ -0,0 +1,72 @@
+// Copyright 2026 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::io::Write as _;
+
+use tracing::instrument;
+
+use crate::cli_util::print_snapshot_stats;
+use crate::cli_util::CommandHelper;
+use crate::command_error::CommandError;
+use crate::ui::Ui;
+
+/// Snapshot paths even if they're assumed to be unchanged
+///
+/// Changes to files under paths marked with `jj file assume-unchanged` are
+/// snapshotted once. The paths are still assumed to be unchanged afterwards.
+#[derive(clap::Args, Clone, Debug)]
+pub(crate) struct FileRefreshArgs {
+    /// Paths to refresh
+    #[arg(required = true, value_name = "FILESETS", value_hint = clap::ValueHint::AnyPath)]
+    paths: Vec<String>,
+}
+
+#[instrument(skip_all)]
+pub(crate) fn cmd_file_refresh(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    args: &FileRefreshArgs,
+) -> Result<(), CommandError> {
+    let mut workspace_command = command.workspace_helper(ui)?;
+    let matcher = workspace_command
+        .parse_file_patterns(ui, &args.paths)?
+        .to_matcher();
+    let auto_tracking_matcher = workspace_command.auto_tracking_matcher(ui)?;
+    let mut options = workspace_command
+        .snapshot_options_with_start_tracking_matcher(ui, &auto_tracking_matcher)?;
+    options.refresh_matcher = matcher.as_ref();
+
+    let mut tx = workspace_command.start_transaction().into_inner();
+    let (mut locked_ws, wc_commit) = workspace_command.start_working_copy_mutation()?;
+    let (new_tree_id, stats) = locked_ws.locked_wc().snapshot(&options)?;
+    if new_tree_id == *wc_commit.tree_id() {
+        // The refreshed file states still need to be saved.
+        let operation_id = locked_ws.locked_wc().old_operation_id().clone();
+        locked_ws.finish(operation_id)?;
+        writeln!(ui.status(), "Nothing changed.")?;
+    } else {
+        tx.repo_mut()
+            .rewrite_commit(command.settings(), &wc_commit)
+            .set_tree_id(new_tree_id)
+            .write()?;
+        let num_rebased = tx.repo_mut().rebase_descendants(command.settings())?;
+        if num_rebased > 0 {
+            writeln!(ui.status(), "Rebased {num_rebased} descendant commits")?;
+        }
+        let repo = tx.commit("refresh paths")?;
+        locked_ws.finish(repo.op_id().clone())?;
+    }
+    print_snapshot_stats(ui, &stats, workspace_command.env().path_converter())?;
+    Ok(())
+}
@@ -0,0 +1,108 @@
+// Copyright 2026 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::io::Write as _;
+use std::path::PathBuf;
+
+use clap_complete::ArgValueCandidates;
+use clap_complete::ArgValueCompleter;
+use jj_lib::file_util;
+use jj_lib::repo::Repo as _;
+use jj_lib::tree_export::export_tree_value;
+use jj_lib::tree_export::ExportedValue;
+use pollster::FutureExt as _;
+use tracing::instrument;
+
+use crate::cli_util::print_unmatched_explicit_paths;
+use crate::cli_util::CommandHelper;
+use crate::cli_util::RevisionArg;
+use crate::command_error::user_error;
+use crate::command_error::user_error_with_message;
+use crate::command_error::CommandError;
+use crate::complete;
+use crate::ui::Ui;
+
+/// Write files from a revision to a directory outside the working copy
+///
+/// The files are written to the same relative paths under the destination
+/// directory, replacing existing files. The destination isn't tracked in any
+/// way, so this is useful for extracting old versions of files to compare them
+/// with other tools, or for getting files outside the sparse patterns. The
+/// working copy isn't updated.
+///
+/// Conflicted files are written with conflict markers. Git submodules and
+/// other conflicts are skipped.
+#[derive(clap::Args, Clone, Debug)]
+pub(crate) struct FileRestoreArgs {
+    /// The revision to get the files from
+    #[arg(
+        long,
+        default_value = "@",
+        value_name = "REVSET",
+        add = ArgValueCandidates::new(complete::all_revisions),
+    )]
+    from: RevisionArg,
+    /// The directory to write the files to
+    #[arg(long, required = true, value_name = "DIR", value_hint = clap::ValueHint::DirPath)]
+    to_disk: PathBuf,
+    /// Paths to write
+    #[arg(
+        required = true,
+        value_name = "FILESETS",
+        value_hint = clap::ValueHint::FilePath,
+        add = ArgValueCompleter::new(complete::all_revision_files),
+    )]
+    paths: Vec<String>,
+}
+
+#[instrument(skip_all)]
+pub(crate) fn cmd_file_restore(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    args: &FileRestoreArgs,
+) -> Result<(), CommandError> {
+    let workspace_command = command.workspace_helper(ui)?;
+    let commit = workspace_command.resolve_single_rev(ui, &args.from)?;
+    let tree = commit.tree()?;
+    let fileset_expression = workspace_command.parse_file_patterns(ui, &args.paths)?;
+    let matcher = fileset_expression.to_matcher();
+    let destination = command.cwd().join(&args.to_disk);
+    let store = workspace_command.repo().store();
+    let conflict_marker_style = workspace_command.env().conflict_marker_style();
+
+    let mut num_written = 0;
+    for (path, value) in tree.entries_matching(matcher.as_ref()) {
+        let value = value?;
+        let disk_path = path.to_fs_path(&destination).map_err(user_error)?;
+        let exported = export_tree_value(store, &path, value, &disk_path, conflict_marker_style)
+            .block_on()
+            .map_err(|err| {
+                let ui_path = workspace_command.format_file_path(&path);
+                user_error_with_message(format!("Failed to write {ui_path}"), err)
+            })?;
+        if exported == ExportedValue::Skipped {
+            let ui_path = workspace_command.format_file_path(&path);
+            writeln!(ui.warning_default(), "Skipping {ui_path}")?;
+        } else {
+            num_written += 1;
+        }
+    }
+    print_unmatched_explicit_paths(ui, &workspace_command, &fileset_expression, [&tree])?;
+    writeln!(
+        ui.status(),
+        "Wrote {num_written} files to {}",
+        file_util::relative_path(command.cwd(), &destination).display()
+    )?;
+    Ok(())
+}
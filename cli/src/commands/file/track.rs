@@ -30,11 +30,17 @@ use crate::ui::Ui;
 /// `snapshot.auto-track` (e.g. to `"none()"` or `"glob:**/*.rs"`). Files that
 /// don't match the pattern can be manually tracked using this command. The
 /// default pattern is `all()` and this command has no effect.
+///
+/// New files larger than `snapshot.max-new-file-size` are not tracked unless
+/// `--force` is given.
 #[derive(clap::Args, Clone, Debug)]
 pub(crate) struct FileTrackArgs {
     /// Paths to track
     #[arg(required = true, value_name = "FILESETS", value_hint = clap::ValueHint::AnyPath)]
     paths: Vec<String>,
+    /// Track the files regardless of their size
+    #[arg(long)]
+    force: bool,
 }
 
 #[instrument(skip_all)]
@@ -47,7 +53,12 @@ pub(crate) fn cmd_file_track(
     let matcher = workspace_command
         .parse_file_patterns(ui, &args.paths)?
         .to_matcher();
-    let options = workspace_command.snapshot_options_with_start_tracking_matcher(&matcher)?;
+    let mut options =
+        workspace_command.snapshot_options_with_start_tracking_matcher(ui, &matcher)?;
+    if args.force {
+        options.max_new_file_size = u64::MAX;
+        options.max_new_file_size_overrides.clear();
+    }
 
     let mut tx = workspace_command.start_transaction().into_inner();
     let (mut locked_ws, _wc_commit) = workspace_command.start_working_copy_mutation()?;
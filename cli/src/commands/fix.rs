@@ -105,7 +105,8 @@ use crate::ui::Ui;
 /// Execution order of tools that affect the same file is deterministic, but
 /// currently unspecified, and may change between releases. If two tools affect
 /// the same file, the second tool to run will receive its input from the
-/// output of the first tool.
+/// output of the first tool. If a tool fails on a file, a warning naming the
+/// tool and the file is printed, and the file content is passed on unchanged.
 ///
 /// There is also a deprecated configuration schema that defines a single
 /// command that will affect all changed files in the specified revisions. For
@@ -240,11 +241,26 @@ pub(crate) fn cmd_fix(
     }
 
     // Run the configured tool on all of the chosen inputs.
-    let fixed_file_ids = fix_file_ids(
+    let FixedFileIds {
+        fixed_file_ids,
+        failures,
+    } = fix_file_ids(
         tx.repo().store().as_ref(),
         &tools_config,
         &unique_tool_inputs,
     )?;
+    for (tool_name, repo_path) in failures
+        .iter()
+        .map(|(tool_name, tool_input)| (tool_name, &tool_input.repo_path))
+        .sorted()
+        .dedup()
+    {
+        writeln!(
+            ui.warning_default(),
+            "Fix tool `{tool_name}` failed on {}",
+            tx.base_workspace_helper().format_file_path(repo_path)
+        )?;
+    }
 
     // Substitute the fixed file IDs into all of the affected commits. Currently,
     // fixes cannot delete or rename files, change the executable bit, or modify
@@ -319,25 +335,31 @@ struct ToolInput {
     repo_path: RepoPathBuf,
 }
 
+/// Outcome of running the tools on a set of `ToolInput`s.
+struct FixedFileIds<'a> {
+    /// The subset of the inputs that resulted in changed file content.
+    fixed_file_ids: HashMap<&'a ToolInput, FileId>,
+    /// The inputs that a tool failed on, along with the name of the tool.
+    failures: Vec<(&'a str, &'a ToolInput)>,
+}
+
 /// Applies `run_tool()` to the inputs and stores the resulting file content.
 ///
-/// Returns a map describing the subset of `tool_inputs` that resulted in
-/// changed file content. Failures when handling an input will cause it to be
-/// omitted from the return value, which is indistinguishable from succeeding
-/// with no changes.
-/// TODO: Better error handling so we can tell the user what went wrong with
-/// each failed input.
+/// When a tool fails on an input, the failure is recorded and the input is
+/// passed on to the next matching tool unchanged. An input that every tool
+/// failed on is omitted from `fixed_file_ids`.
 fn fix_file_ids<'a>(
     store: &Store,
-    tools_config: &ToolsConfig,
+    tools_config: &'a ToolsConfig,
     tool_inputs: &'a HashSet<ToolInput>,
-) -> Result<HashMap<&'a ToolInput, FileId>, CommandError> {
+) -> Result<FixedFileIds<'a>, CommandError> {
     let (updates_tx, updates_rx) = channel();
+    let (failures_tx, failures_rx) = channel();
     // TODO: Switch to futures, or document the decision not to. We don't need
     // threads unless the threads will be doing more than waiting for pipes.
     tool_inputs.into_par_iter().try_for_each_init(
-        || updates_tx.clone(),
-        |updates_tx, tool_input| -> Result<(), CommandError> {
+        || (updates_tx.clone(), failures_tx.clone()),
+        |(updates_tx, failures_tx), tool_input| -> Result<(), CommandError> {
             let mut matching_tools = tools_config
                 .tools
                 .iter()
@@ -354,10 +376,14 @@ fn fix_file_ids<'a>(
                     matching_tools.fold(old_content.clone(), |prev_content, tool_config| {
                         match run_tool(&tool_config.command, tool_input, &prev_content) {
                             Ok(next_content) => next_content,
-                            // TODO: Because the stderr is passed through, this isn't always failing
-                            // silently, but it should do something better will the exit code, tool
-                            // name, etc.
-                            Err(_) => prev_content,
+                            // TODO: Report the exit code, and associate the passed-through
+                            // stderr with the tool.
+                            Err(()) => {
+                                failures_tx
+                                    .send((tool_config.name.as_str(), tool_input))
+                                    .unwrap();
+                                prev_content
+                            }
                         }
                     });
                 if new_content != old_content {
@@ -372,11 +398,16 @@ fn fix_file_ids<'a>(
         },
     )?;
     drop(updates_tx);
-    let mut result = HashMap::new();
+    drop(failures_tx);
+    let mut fixed_file_ids = HashMap::new();
     while let Ok((tool_input, new_file_id)) = updates_rx.recv() {
-        result.insert(tool_input, new_file_id);
+        fixed_file_ids.insert(tool_input, new_file_id);
     }
-    Ok(result)
+    let failures = failures_rx.iter().collect();
+    Ok(FixedFileIds {
+        fixed_file_ids,
+        failures,
+    })
 }
 
 /// Runs the `tool_command` to fix the given file content.
@@ -419,12 +450,12 @@ fn run_tool(
 
 /// Represents an entry in the `fix.tools` config table.
 struct ToolConfig {
+    /// The key of the entry, used to refer to the tool in messages.
+    name: String,
     /// The command that will be run to fix a matching file.
     command: CommandNameAndArgs,
     /// The matcher that determines if this tool matches a file.
     matcher: Box<dyn Matcher>,
-    // TODO: Store the `name` field here and print it with the command's stderr, to clearly
-    // associate any errors/warnings with the tool and its configuration entry.
 }
 
 /// Represents the `fix.tools` config table.
@@ -459,6 +490,7 @@ fn get_tools_config(ui: &mut Ui, settings: &UserSettings) -> Result<ToolsConfig,
         // doesn't have a `name` that could conflict with them. That would matter more
         // if we already had better error handling that made use of the `name`.
         tools_config.tools.push(ToolConfig {
+            name: "legacy-tool-command".to_owned(),
             command: tool_command,
             matcher: Box::new(EverythingMatcher),
         });
@@ -504,6 +536,7 @@ fn get_tools_config(ui: &mut Ui, settings: &UserSettings) -> Result<ToolsConfig,
             );
             print_parse_diagnostics(ui, &format!("In `fix.tools.{name}`"), &diagnostics)?;
             Ok(ToolConfig {
+                name: name.to_owned(),
                 command: tool.command,
                 matcher: expression.to_matcher(),
             })
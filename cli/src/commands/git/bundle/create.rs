@@ -0,0 +1,106 @@
+// Copyright 2024 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashSet;
+use std::io::Write as _;
+use std::path::PathBuf;
+
+use itertools::Itertools as _;
+use jj_lib::backend::CommitId;
+use jj_lib::git;
+use jj_lib::object_id::ObjectId as _;
+use jj_lib::revset::RevsetExpression;
+
+use crate::cli_util::CommandHelper;
+use crate::cli_util::RevisionArg;
+use crate::command_error::user_error;
+use crate::command_error::CommandError;
+use crate::ui::Ui;
+
+/// Write commits to a Git bundle file
+///
+/// The bundle contains the given revisions and their ancestors, along with the
+/// local bookmarks pointing to them. Use `jj git bundle unbundle` to read it
+/// into another repo.
+#[derive(clap::Args, Clone, Debug)]
+pub struct GitBundleCreateArgs {
+    /// Path of the bundle file to write
+    #[arg(value_hint = clap::ValueHint::FilePath)]
+    path: PathBuf,
+    /// Revisions to include in the bundle, along with their ancestors
+    #[arg(long, short, required = true, value_name = "REVSETS")]
+    revisions: Vec<RevisionArg>,
+    /// Revisions which the receiving repo already has
+    ///
+    /// These revisions and their ancestors are left out of the bundle. The
+    /// receiving repo must have them to read the bundle.
+    #[arg(long, value_name = "REVSETS")]
+    exclude: Vec<RevisionArg>,
+}
+
+pub fn cmd_git_bundle_create(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    args: &GitBundleCreateArgs,
+) -> Result<(), CommandError> {
+    let workspace_command = command.workspace_helper(ui)?;
+    let repo = workspace_command.repo().as_ref();
+    let included = workspace_command
+        .parse_union_revsets(ui, &args.revisions)?
+        .expression()
+        .ancestors();
+    let excluded = workspace_command
+        .parse_union_revsets(ui, &args.exclude)?
+        .expression()
+        .ancestors()
+        .minus(&RevsetExpression::root());
+    let bundled = included.minus(&excluded).minus(&RevsetExpression::root());
+    let bundled_ids: HashSet<CommitId> = workspace_command
+        .attach_revset_evaluator(bundled.clone())
+        .evaluate_to_commit_ids()?
+        .try_collect()?;
+    if bundled_ids.is_empty() {
+        return Err(user_error("No revisions to bundle"));
+    }
+    let head_ids: Vec<CommitId> = workspace_command
+        .attach_revset_evaluator(bundled.heads())
+        .evaluate_to_commit_ids()?
+        .try_collect()?;
+    let prerequisite_ids: Vec<CommitId> = workspace_command
+        .attach_revset_evaluator(excluded.heads())
+        .evaluate_to_commit_ids()?
+        .try_collect()?;
+
+    let mut refs = vec![];
+    for (name, target) in repo.view().local_bookmarks() {
+        if let Some(id) = target.as_normal().filter(|id| bundled_ids.contains(id)) {
+            refs.push((format!("refs/heads/{name}"), id.clone()));
+        }
+    }
+    let num_bookmarks = refs.len();
+    // Heads without bookmarks need refs to be included in the bundle.
+    for id in head_ids {
+        if !refs.iter().any(|(_, target)| *target == id) {
+            refs.push((format!("refs/jj/keep/{}", id.hex()), id));
+        }
+    }
+    git::create_bundle(repo, &args.path, &refs, &prerequisite_ids)?;
+    writeln!(
+        ui.status(),
+        "Bundled {} commits and {num_bookmarks} bookmarks to {}",
+        bundled_ids.len(),
+        args.path.display()
+    )?;
+    Ok(())
+}
@@ -0,0 +1,47 @@
+// Copyright 2024 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+pub mod create;
+pub mod unbundle;
+
+use clap::Subcommand;
+
+use self::create::cmd_git_bundle_create;
+use self::create::GitBundleCreateArgs;
+use self::unbundle::cmd_git_bundle_unbundle;
+use self::unbundle::GitBundleUnbundleArgs;
+use crate::cli_util::CommandHelper;
+use crate::command_error::CommandError;
+use crate::ui::Ui;
+
+/// Move commits between repos through Git bundle files
+///
+/// Bundles can be used to exchange commits without a remote, e.g. between
+/// machines which aren't connected to a network.
+#[derive(Subcommand, Clone, Debug)]
+pub enum GitBundleCommand {
+    Create(GitBundleCreateArgs),
+    Unbundle(GitBundleUnbundleArgs),
+}
+
+pub fn cmd_git_bundle(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    subcommand: &GitBundleCommand,
+) -> Result<(), CommandError> {
+    match subcommand {
+        GitBundleCommand::Create(args) => cmd_git_bundle_create(ui, command, args),
+        GitBundleCommand::Unbundle(args) => cmd_git_bundle_unbundle(ui, command, args),
+    }
+}
@@ -0,0 +1,72 @@
+// Copyright 2024 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::io::Write as _;
+use std::path::PathBuf;
+
+use jj_lib::git;
+use jj_lib::op_store::RefTarget;
+use jj_lib::repo::Repo as _;
+
+use crate::cli_util::CommandHelper;
+use crate::command_error::CommandError;
+use crate::ui::Ui;
+
+/// Read commits from a Git bundle file
+///
+/// The commits in the bundle become visible, and the local bookmarks recorded
+/// in the bundle are created. Existing bookmarks are moved only if they point
+/// to ancestors of the bundled commits.
+#[derive(clap::Args, Clone, Debug)]
+pub struct GitBundleUnbundleArgs {
+    /// Path of the bundle file to read
+    #[arg(value_hint = clap::ValueHint::FilePath)]
+    path: PathBuf,
+}
+
+pub fn cmd_git_bundle_unbundle(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    args: &GitBundleUnbundleArgs,
+) -> Result<(), CommandError> {
+    let mut workspace_command = command.workspace_helper(ui)?;
+    let mut tx = workspace_command.start_transaction();
+    let refs = git::unbundle(tx.repo_mut(), &args.path)?;
+    for (qualified_name, id) in &refs {
+        let Some(name) = qualified_name.strip_prefix("refs/heads/") else {
+            continue;
+        };
+        let old_target = tx.repo().view().get_local_bookmark(name);
+        if old_target.as_normal() == Some(id) {
+            continue;
+        } else if old_target.is_absent() {
+            writeln!(ui.status(), "Created bookmark {name}")?;
+        } else if old_target
+            .as_normal()
+            .is_some_and(|old_id| tx.repo().index().is_ancestor(old_id, id))
+        {
+            writeln!(ui.status(), "Moved bookmark {name}")?;
+        } else {
+            writeln!(
+                ui.warning_default(),
+                "Not moving bookmark {name} since the bundled commit isn't a descendant of it"
+            )?;
+            continue;
+        }
+        tx.repo_mut()
+            .set_local_bookmark_target(name, RefTarget::normal(id.clone()));
+    }
+    tx.finish(ui, format!("unbundle {}", args.path.display()))?;
+    Ok(())
+}
@@ -20,10 +20,10 @@ use std::path::Path;
 use std::path::PathBuf;
 
 use jj_lib::git;
+use jj_lib::git::BranchPatterns;
 use jj_lib::git::GitFetchError;
 use jj_lib::git::GitFetchStats;
 use jj_lib::repo::Repo;
-use jj_lib::str_util::StringPattern;
 use jj_lib::workspace::Workspace;
 
 use super::write_repository_level_trunk_alias;
@@ -63,6 +63,12 @@ pub struct GitCloneArgs {
     /// Create a shallow clone of the given depth
     #[arg(long)]
     depth: Option<NonZeroU32>,
+    /// Create a partial clone with the given object filter (e.g. `blob:none`)
+    ///
+    /// Objects omitted by the filter are fetched from the remote when they're
+    /// needed. This requires the `git` executable.
+    #[arg(long)]
+    filter: Option<String>,
 }
 
 fn absolute_git_source(cwd: &Path, source: &str) -> String {
@@ -136,6 +142,7 @@ pub fn cmd_git_clone(
         command,
         args.colocate,
         args.depth,
+        args.filter.as_deref(),
         remote_name,
         &source,
         &canonical_wc_path,
@@ -190,11 +197,13 @@ pub fn cmd_git_clone(
     Ok(())
 }
 
+#[allow(clippy::too_many_arguments)]
 fn do_git_clone(
     ui: &mut Ui,
     command: &CommandHelper,
     colocate: bool,
     depth: Option<NonZeroU32>,
+    filter: Option<&str>,
     remote_name: &str,
     source: &str,
     wc_path: &Path,
@@ -210,9 +219,18 @@ fn do_git_clone(
         r#"Fetching into new repo in "{}""#,
         wc_path.display()
     )?;
+    git_repo.remote(remote_name, source).unwrap();
+    let (workspace, repo) = if let Some(filter) = filter {
+        git::fetch_partial(&git_repo, remote_name, filter, depth).map_err(map_clone_fetch_error)?;
+        // Reload the repo so the Git backend picks up the promisor remote.
+        let workspace = command.load_workspace_at(&wc_path.to_string_lossy())?;
+        let repo = workspace.repo_loader().load_at_head(command.settings())?;
+        (workspace, repo)
+    } else {
+        (workspace, repo)
+    };
     let mut workspace_command = command.for_workable_repo(ui, workspace, repo)?;
     maybe_add_gitignore(&workspace_command)?;
-    git_repo.remote(remote_name, source).unwrap();
     let mut fetch_tx = workspace_command.start_transaction();
     let git_settings = command.settings().git_settings()?;
 
@@ -221,13 +239,20 @@ fn do_git_clone(
             fetch_tx.repo_mut(),
             &git_repo,
             remote_name,
-            &[StringPattern::everything()],
+            &BranchPatterns::everything(),
             cb,
             &git_settings,
             depth,
         )
     })
-    .map_err(|err| match err {
+    .map_err(map_clone_fetch_error)?;
+    print_git_import_stats(ui, fetch_tx.repo(), &stats.import_stats, true)?;
+    fetch_tx.finish(ui, "fetch from git remote into empty repo")?;
+    Ok((workspace_command, stats))
+}
+
+fn map_clone_fetch_error(err: GitFetchError) -> CommandError {
+    match err {
         GitFetchError::NoSuchRemote(_) => {
             panic!("shouldn't happen as we just created the git remote")
         }
@@ -236,8 +261,6 @@ fn do_git_clone(
         GitFetchError::InvalidBranchPattern => {
             unreachable!("we didn't provide any globs")
         }
-    })?;
-    print_git_import_stats(ui, fetch_tx.repo(), &stats.import_stats, true)?;
-    fetch_tx.finish(ui, "fetch from git remote into empty repo")?;
-    Ok((workspace_command, stats))
+        GitFetchError::FetchCommand(_) => user_error(err),
+    }
 }
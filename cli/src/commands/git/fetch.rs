@@ -12,12 +12,18 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::slice;
+
 use clap_complete::ArgValueCandidates;
+use itertools::Either;
 use itertools::Itertools;
 use jj_lib::config::ConfigGetResultExt as _;
+use jj_lib::git::BranchPatterns;
 use jj_lib::repo::Repo;
+use jj_lib::settings::FetchPruneMode;
 use jj_lib::settings::UserSettings;
 use jj_lib::str_util::StringPattern;
+use jj_lib::str_util::StringPatternParseError;
 
 use crate::cli_util::CommandHelper;
 use crate::command_error::CommandError;
@@ -38,14 +44,21 @@ pub struct GitFetchArgs {
     /// By default, the specified name matches exactly. Use `glob:` prefix to
     /// expand `*` as a glob, e.g. `--branch 'glob:push-*'`. Other wildcard
     /// characters such as `?` are *not* supported.
+    ///
+    /// Prefix the pattern with `!` to exclude matching branches, e.g.
+    /// `--branch 'glob:releases/*' --branch '!glob:releases/old-*'`. If only
+    /// excluded patterns are specified, all other branches are fetched.
+    ///
+    /// If not specified, the branches configured by
+    /// `remotes.<name>.fetch-bookmarks` are fetched, or all branches if that
+    /// isn't set.
     #[arg(
         long, short,
         alias = "bookmark",
-        default_value = "glob:*",
-        value_parser = StringPattern::parse,
+        value_parser = parse_branch_arg,
         add = ArgValueCandidates::new(complete::bookmarks),
     )]
-    branch: Vec<StringPattern>,
+    branch: Vec<BranchArg>,
     /// The remote to fetch from (only named remotes are supported, can be
     /// repeated)
     ///
@@ -60,6 +73,58 @@ pub struct GitFetchArgs {
     /// Fetch from all remotes
     #[arg(long, conflicts_with = "remotes")]
     all_remotes: bool,
+    /// What to do with tracking local bookmarks whose remote bookmarks were
+    /// deleted
+    ///
+    /// This defaults to the `git.fetch-prune` setting.
+    #[arg(long, value_name = "MODE")]
+    prune: Option<PruneArg>,
+}
+
+/// Branch pattern, which may be prefixed with `!` to exclude matches.
+#[derive(Clone, Debug)]
+enum BranchArg {
+    Include(StringPattern),
+    Exclude(StringPattern),
+}
+
+fn parse_branch_arg(src: &str) -> Result<BranchArg, StringPatternParseError> {
+    if let Some(pattern) = src.strip_prefix('!') {
+        Ok(BranchArg::Exclude(StringPattern::parse(pattern)?))
+    } else {
+        Ok(BranchArg::Include(StringPattern::parse(src)?))
+    }
+}
+
+fn branch_patterns(args: &[BranchArg]) -> BranchPatterns {
+    let (mut included, excluded): (Vec<_>, Vec<_>) = args.iter().partition_map(|arg| match arg {
+        BranchArg::Include(pattern) => Either::Left(pattern.clone()),
+        BranchArg::Exclude(pattern) => Either::Right(pattern.clone()),
+    });
+    if included.is_empty() {
+        included.push(StringPattern::everything());
+    }
+    BranchPatterns { included, excluded }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, clap::ValueEnum)]
+enum PruneArg {
+    /// Delete the local bookmark unless it was moved locally
+    Delete,
+    /// Keep the local bookmark
+    Keep,
+    /// Keep the local bookmark, but mark it as conflicted with the deletion
+    MarkDeleted,
+}
+
+impl From<PruneArg> for FetchPruneMode {
+    fn from(arg: PruneArg) -> Self {
+        match arg {
+            PruneArg::Delete => FetchPruneMode::Delete,
+            PruneArg::Keep => FetchPruneMode::Keep,
+            PruneArg::MarkDeleted => FetchPruneMode::MarkDeleted,
+        }
+    }
 }
 
 #[tracing::instrument(skip(ui, command))]
@@ -77,8 +142,33 @@ pub fn cmd_git_fetch(
     } else {
         args.remotes.clone()
     };
+    let mut git_settings = command.settings().git_settings()?;
+    if let Some(prune) = args.prune {
+        git_settings.fetch_prune = prune.into();
+    }
     let mut tx = workspace_command.start_transaction();
-    git_fetch(ui, &mut tx, &git_repo, &remotes, &args.branch)?;
+    if args.branch.is_empty() {
+        for remote in &remotes {
+            let remote_settings = command.settings().remote_settings(remote)?;
+            let branches = BranchPatterns {
+                included: remote_settings
+                    .fetch_bookmarks
+                    .unwrap_or_else(|| vec![StringPattern::everything()]),
+                excluded: vec![],
+            };
+            git_fetch(
+                ui,
+                &mut tx,
+                &git_repo,
+                slice::from_ref(remote),
+                &branches,
+                &git_settings,
+            )?;
+        }
+    } else {
+        let branches = branch_patterns(&args.branch);
+        git_fetch(ui, &mut tx, &git_repo, &remotes, &branches, &git_settings)?;
+    }
     tx.finish(
         ui,
         format!("fetch from git remote(s) {}", remotes.iter().join(",")),
@@ -220,7 +220,7 @@ fn init_git_refs(
     if colocated {
         // If git.auto-local-bookmark = true, local bookmarks could be created for
         // the imported remote branches.
-        let failed_refs = git::export_refs(tx.repo_mut())?;
+        let failed_refs = git::export_refs(tx.repo_mut(), &git_settings)?;
         print_failed_git_export(ui, &failed_refs)?;
     }
     let repo = tx.commit("import git refs")?;
@@ -12,6 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+pub mod bundle;
 pub mod clone;
 pub mod export;
 pub mod fetch;
@@ -27,6 +28,8 @@ use clap::Subcommand;
 use jj_lib::config::ConfigFile;
 use jj_lib::config::ConfigSource;
 
+use self::bundle::cmd_git_bundle;
+use self::bundle::GitBundleCommand;
 use self::clone::cmd_git_clone;
 use self::clone::GitCloneArgs;
 use self::export::cmd_git_export;
@@ -55,6 +58,8 @@ use crate::ui::Ui;
 /// https://jj-vcs.github.io/jj/latest/git-comparison/.
 #[derive(Subcommand, Clone, Debug)]
 pub enum GitCommand {
+    #[command(subcommand)]
+    Bundle(GitBundleCommand),
     Clone(GitCloneArgs),
     Export(GitExportArgs),
     Fetch(GitFetchArgs),
@@ -73,6 +78,7 @@ pub fn cmd_git(
     subcommand: &GitCommand,
 ) -> Result<(), CommandError> {
     match subcommand {
+        GitCommand::Bundle(args) => cmd_git_bundle(ui, command, args),
         GitCommand::Clone(args) => cmd_git_clone(ui, command, args),
         GitCommand::Export(args) => cmd_git_export(ui, command, args),
         GitCommand::Fetch(args) => cmd_git_fetch(ui, command, args),
@@ -12,19 +12,24 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::collections::HashMap;
 use std::collections::HashSet;
 use std::fmt;
 use std::io;
 use std::io::Write;
+use std::iter;
 
+use bstr::ByteVec as _;
 use clap::ArgGroup;
 use clap_complete::ArgValueCandidates;
 use itertools::Itertools;
 use jj_lib::backend::CommitId;
+use jj_lib::commit::Commit;
 use jj_lib::config::ConfigGetResultExt as _;
 use jj_lib::git;
 use jj_lib::git::GitBranchPushTargets;
 use jj_lib::git::GitPushError;
+use jj_lib::git::GitPushOptions;
 use jj_lib::object_id::ObjectId;
 use jj_lib::op_store::RefTarget;
 use jj_lib::refs::classify_bookmark_push_action;
@@ -36,6 +41,7 @@ use jj_lib::revset::RevsetExpression;
 use jj_lib::settings::UserSettings;
 use jj_lib::str_util::StringPattern;
 use jj_lib::view::View;
+use serde::Serialize;
 
 use crate::cli_util::short_change_hash;
 use crate::cli_util::short_commit_hash;
@@ -47,8 +53,10 @@ use crate::command_error::user_error;
 use crate::command_error::user_error_with_hint;
 use crate::command_error::CommandError;
 use crate::commands::git::get_single_remote;
+use crate::commands::tag::find_tags;
 use crate::complete;
 use crate::formatter::Formatter;
+use crate::formatter::PlainTextFormatter;
 use crate::git_util::get_git_repo;
 use crate::git_util::map_git_error;
 use crate::git_util::with_remote_git_callbacks;
@@ -60,7 +68,8 @@ use crate::ui::Ui;
 /// By default, pushes tracking bookmarks pointing to
 /// `remote_bookmarks(remote=<remote>)..@`. Use `--bookmark` to push specific
 /// bookmarks. Use `--all` to push all bookmarks. Use `--change` to generate
-/// bookmark names based on the change IDs of specific commits.
+/// bookmark names based on the change IDs of specific commits. Use `--tag` to
+/// push tags.
 ///
 /// Unlike in Git, the remote to push to is not derived from the tracked remote
 /// bookmarks. Use `--remote` to select the remote Git repository by name. There
@@ -78,7 +87,7 @@ use crate::ui::Ui;
 ///     https://jj-vcs.github.io/jj/latest/bookmarks/#conflicts
 
 #[derive(clap::Args, Clone, Debug)]
-#[command(group(ArgGroup::new("specific").args(&["bookmark", "change", "revisions"]).multiple(true)))]
+#[command(group(ArgGroup::new("specific").args(&["bookmark", "change", "revisions", "tag"]).multiple(true)))]
 #[command(group(ArgGroup::new("what").args(&["all", "deleted", "tracked"]).conflicts_with("specific")))]
 pub struct GitPushArgs {
     /// The remote to push to (only named remotes are supported)
@@ -128,6 +137,13 @@ pub struct GitPushArgs {
     /// Allow pushing commits that are private
     #[arg(long)]
     allow_private: bool,
+    /// Allow pushing commits with conflicts
+    ///
+    /// The sides of the conflicts are stored in the pushed Git commits, so
+    /// other jj clients fetching them get the same conflicts. Git itself sees
+    /// the sides as `.jjconflict-*` directories instead.
+    #[arg(long)]
+    allow_conflicts: bool,
     /// Push bookmarks pointing to these commits (can be repeated)
     #[arg(long, short, value_name = "REVSETS")]
     revisions: Vec<RevisionArg>,
@@ -136,12 +152,58 @@ pub struct GitPushArgs {
     ///
     /// The created bookmark will be tracked automatically. Use the
     /// `git.push-bookmark-prefix` setting to change the prefix for generated
-    /// names.
+    /// names, or the `templates.git_push_bookmark` setting to generate names
+    /// by a commit template.
     #[arg(long, short, value_name = "REVSETS")]
     change: Vec<RevisionArg>,
+    /// Push this tag, or tags matching a pattern (can be repeated)
+    ///
+    /// The tag must not exist on the remote yet. By default, the specified
+    /// name matches exactly. Use `glob:` prefix to select tags by wildcard
+    /// pattern. For details, see
+    /// https://jj-vcs.github.io/jj/latest/revsets#string-patterns.
+    #[arg(
+        long,
+        value_parser = StringPattern::parse,
+        add = ArgValueCandidates::new(complete::tags),
+    )]
+    tag: Vec<StringPattern>,
+    /// Update either all bookmarks on the remote or none of them
+    ///
+    /// If any bookmark can't be updated, none of them are. This requires the
+    /// remote to support atomic pushes. If it doesn't, a warning is printed and
+    /// the bookmarks are pushed non-atomically.
+    ///
+    /// The push is done by running the `git` executable, so credentials for
+    /// the remote are obtained from your Git configuration (e.g. credential
+    /// helpers and SSH agent) instead of by jj.
+    #[arg(long)]
+    atomic: bool,
+    /// Transmit the given option to the remote (can be repeated)
+    ///
+    /// The options are passed to the hooks on the remote, e.g. to create a
+    /// merge request on GitLab (`-o merge_request.create`) or to set the topic
+    /// of changes on Gerrit (`-o topic=...`).
+    #[arg(long = "push-option", short = 'o', value_name = "OPTION")]
+    push_options: Vec<String>,
+    /// Sign the push with the key configured in Git
+    ///
+    /// This is needed if the remote requires signed pushes. It uses the `git`
+    /// executable, so the signing key is configured by Git's `user.signingKey`
+    /// and `gpg.*` settings. Credentials for the remote are likewise obtained
+    /// from your Git configuration instead of by jj. Defaults to the
+    /// `git.sign-on-push` setting.
+    #[arg(long)]
+    signed: bool,
     /// Only display what will change on the remote
     #[arg(long)]
     dry_run: bool,
+    /// Print the result of the push as JSON
+    ///
+    /// The object lists the pushed bookmarks with their old and new commit
+    /// IDs and the URL for creating a pull request, and the pushed tags.
+    #[arg(long, conflicts_with = "dry_run")]
+    json: bool,
 }
 
 fn make_bookmark_term(bookmark_names: &[impl fmt::Display]) -> String {
@@ -151,6 +213,13 @@ fn make_bookmark_term(bookmark_names: &[impl fmt::Display]) -> String {
     }
 }
 
+fn make_tag_term(tag_names: &[impl fmt::Display]) -> String {
+    match tag_names {
+        [tag_name] => format!("tag {tag_name}"),
+        tag_names => format!("tags {}", tag_names.iter().join(", ")),
+    }
+}
+
 const DEFAULT_REMOTE: &str = "origin";
 
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
@@ -178,6 +247,7 @@ pub fn cmd_git_push(
     let view = tx.repo().view();
     let tx_description;
     let mut bookmark_updates = vec![];
+    let mut tag_updates = vec![];
     if args.all {
         for (bookmark_name, targets) in view.local_remote_bookmarks(&remote) {
             let allow_new = true; // implied by --all
@@ -218,9 +288,9 @@ pub fn cmd_git_push(
         let mut seen_bookmarks: HashSet<&str> = HashSet::new();
 
         // Process --change bookmarks first because matching bookmarks can be moved.
-        let bookmark_prefix = get_change_bookmark_prefix(ui, command.settings())?;
+        let bookmark_naming = get_change_bookmark_naming(ui, command.settings(), &remote)?;
         let change_bookmark_names =
-            update_change_bookmarks(ui, &mut tx, &args.change, &bookmark_prefix)?;
+            update_change_bookmarks(ui, &mut tx, &args.change, &remote, &bookmark_naming)?;
         let change_bookmarks = change_bookmark_names.iter().map(|bookmark_name| {
             let targets = LocalAndRemoteRef {
                 local_target: tx.repo().view().get_local_bookmark(bookmark_name),
@@ -259,8 +329,12 @@ pub fn cmd_git_push(
             }
         }
 
-        let use_default_revset =
-            args.bookmark.is_empty() && args.change.is_empty() && args.revisions.is_empty();
+        tag_updates = find_tags_to_push(view, &args.tag)?;
+
+        let use_default_revset = args.bookmark.is_empty()
+            && args.change.is_empty()
+            && args.revisions.is_empty()
+            && args.tag.is_empty();
         let bookmarks_targeted = find_bookmarks_targeted_by_revisions(
             ui,
             tx.base_workspace_helper(),
@@ -279,26 +353,44 @@ pub fn cmd_git_push(
             }
         }
 
-        tx_description = format!(
-            "push {} to git remote {}",
-            make_bookmark_term(
-                &bookmark_updates
-                    .iter()
-                    .map(|(bookmark, _)| bookmark.as_str())
-                    .collect_vec()
-            ),
-            &remote
+        let bookmark_term = make_bookmark_term(
+            &bookmark_updates
+                .iter()
+                .map(|(bookmark, _)| bookmark.as_str())
+                .collect_vec(),
         );
+        let tag_term = make_tag_term(&tag_updates.iter().map(|(tag, _)| tag).collect_vec());
+        let ref_term = if tag_updates.is_empty() {
+            bookmark_term
+        } else if bookmark_updates.is_empty() {
+            tag_term
+        } else {
+            format!("{bookmark_term} and {tag_term}")
+        };
+        tx_description = format!("push {ref_term} to git remote {remote}");
     }
-    if bookmark_updates.is_empty() {
+    if bookmark_updates.is_empty() && tag_updates.is_empty() {
         writeln!(ui.status(), "Nothing changed.")?;
         return Ok(());
     }
 
-    validate_commits_ready_to_push(ui, &bookmark_updates, &remote, &tx, command, args)?;
+    validate_commits_ready_to_push(
+        ui,
+        &bookmark_updates,
+        &tag_updates,
+        &remote,
+        &tx,
+        command,
+        args,
+    )?;
     if let Some(mut formatter) = ui.status_formatter() {
         writeln!(formatter, "Changes to push to {remote}:")?;
-        print_commits_ready_to_push(formatter.as_mut(), tx.repo(), &bookmark_updates)?;
+        print_commits_ready_to_push(
+            formatter.as_mut(),
+            tx.repo(),
+            &bookmark_updates,
+            &tag_updates,
+        )?;
     }
 
     if args.dry_run {
@@ -308,13 +400,38 @@ pub fn cmd_git_push(
 
     let targets = GitBranchPushTargets {
         branch_updates: bookmark_updates,
+        tag_updates,
+    };
+    let remote_settings = command.settings().remote_settings(&remote)?;
+    let remote_url = git_repo
+        .find_remote(&remote)
+        .ok()
+        .and_then(|git_remote| git_remote.url().map(str::to_owned));
+    let create_pr_url_template =
+        get_create_pr_url_template(remote_settings.create_pr_url, remote_url.as_deref());
+    let push_options = GitPushOptions {
+        atomic: args.atomic,
+        push_options: args.push_options.clone(),
+        signed: args.signed
+            || command
+                .settings()
+                .get_bool("git.sign-on-push")
+                .optional()?
+                .unwrap_or(false),
     };
     let mut writer = GitSidebandProgressMessageWriter::new(ui);
     let mut sideband_progress_callback = |progress_message: &[u8]| {
         _ = writer.write(ui, progress_message);
     };
-    with_remote_git_callbacks(ui, Some(&mut sideband_progress_callback), |cb| {
-        git::push_branches(tx.repo_mut(), &git_repo, &remote, &targets, cb)
+    let stats = with_remote_git_callbacks(ui, Some(&mut sideband_progress_callback), |cb| {
+        git::push_branches(
+            tx.repo_mut(),
+            &git_repo,
+            &remote,
+            &targets,
+            &push_options,
+            cb,
+        )
     })
     .map_err(|err| match err {
         GitPushError::InternalGitError(err) => map_git_error(err),
@@ -327,18 +444,173 @@ pub fn cmd_git_push(
             "Try fetching from the remote, then make the bookmark point to where you want it to \
              be, and push again.",
         ),
+        GitPushError::AtomicUpdateRejected(refs) => user_error(format!(
+            "Remote rejected the update of some bookmarks, so no bookmarks were updated. \
+             Rejected refs: {}",
+            refs.join(", ")
+        )),
         _ => user_error(err),
     })?;
     writer.flush(ui)?;
-    tx.finish(ui, tx_description)?;
+    if args.atomic && !stats.atomic {
+        writeln!(
+            ui.warning_default(),
+            "Remote {remote} doesn't support atomic pushes, so the bookmarks were pushed \
+             non-atomically."
+        )?;
+    }
+    let result = PushResult::new(&remote, &targets, create_pr_url_template.as_deref());
+    result.print_create_pr_urls(ui)?;
+    if args.json {
+        let mut stdout = ui.stdout();
+        serde_json::to_writer(&mut stdout, &result).map_err(io::Error::from)?;
+        writeln!(stdout)?;
+    }
+    // Pushing only tags doesn't change the view, so there's no operation to
+    // record.
+    if tx.repo().has_changes() {
+        tx.finish(ui, tx_description)?;
+    }
     Ok(())
 }
 
+/// Summary of a successful push, printed as JSON by `--json`.
+#[derive(Serialize)]
+struct PushResult {
+    remote: String,
+    bookmarks: Vec<PushedBookmark>,
+    tags: Vec<PushedTag>,
+}
+
+#[derive(Serialize)]
+struct PushedBookmark {
+    name: String,
+    old_commit_id: Option<String>,
+    new_commit_id: Option<String>,
+    /// URL for creating a pull request from the bookmark. Unset for deleted
+    /// bookmarks and for remotes without a known forge.
+    create_pr_url: Option<String>,
+}
+
+#[derive(Serialize)]
+struct PushedTag {
+    name: String,
+    commit_id: String,
+}
+
+impl PushResult {
+    fn new(
+        remote: &str,
+        targets: &GitBranchPushTargets,
+        create_pr_url_template: Option<&str>,
+    ) -> Self {
+        let bookmarks = targets
+            .branch_updates
+            .iter()
+            .map(|(name, update)| PushedBookmark {
+                name: name.clone(),
+                old_commit_id: update.old_target.as_ref().map(|id| id.hex()),
+                new_commit_id: update.new_target.as_ref().map(|id| id.hex()),
+                create_pr_url: create_pr_url_template
+                    .filter(|_| update.new_target.is_some())
+                    .map(|template| template.replace("{bookmark}", name)),
+            })
+            .collect();
+        let tags = targets
+            .tag_updates
+            .iter()
+            .map(|(name, id)| PushedTag {
+                name: name.clone(),
+                commit_id: id.hex(),
+            })
+            .collect();
+        PushResult {
+            remote: remote.to_owned(),
+            bookmarks,
+            tags,
+        }
+    }
+
+    /// Prints the URLs for creating pull requests from the bookmarks that
+    /// didn't exist on the remote before the push.
+    fn print_create_pr_urls(&self, ui: &Ui) -> io::Result<()> {
+        let Some(mut formatter) = ui.status_formatter() else {
+            return Ok(());
+        };
+        for bookmark in &self.bookmarks {
+            if bookmark.old_commit_id.is_some() {
+                continue;
+            }
+            if let Some(url) = &bookmark.create_pr_url {
+                write!(formatter, "Create a pull request for bookmark ")?;
+                write!(formatter.labeled("bookmark"), "{}", bookmark.name)?;
+                writeln!(formatter, ":")?;
+                writeln!(formatter, "  {url}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Returns the template of the URL for creating a pull request from a
+/// bookmark pushed to the remote.
+///
+/// The configured template takes precedence. Otherwise, a template is derived
+/// from the remote URL if it points to GitHub or GitLab.
+fn get_create_pr_url_template(
+    configured: Option<String>,
+    remote_url: Option<&str>,
+) -> Option<String> {
+    if let Some(template) = configured {
+        return (!template.is_empty()).then_some(template);
+    }
+    let (host, path) = parse_forge_url(remote_url?)?;
+    match host {
+        "github.com" => Some(format!("https://{host}/{path}/pull/new/{{bookmark}}")),
+        "gitlab.com" => Some(format!(
+            "https://{host}/{path}/-/merge_requests/new?merge_request[source_branch]={{bookmark}}"
+        )),
+        _ => None,
+    }
+}
+
+/// Splits a remote URL into the host name and the repository path without
+/// the `.git` suffix.
+///
+/// HTTP(S) and SSH URLs as well as the scp-like `user@host:path` syntax are
+/// supported.
+fn parse_forge_url(url: &str) -> Option<(&str, &str)> {
+    let (host, path) = if let Some((scheme, rest)) = url.split_once("://") {
+        if !matches!(scheme, "https" | "http" | "ssh" | "git+ssh") {
+            return None;
+        }
+        let (authority, path) = rest.split_once('/')?;
+        let host = authority
+            .rsplit_once('@')
+            .map_or(authority, |(_, host)| host);
+        let host = host.split_once(':').map_or(host, |(host, _port)| host);
+        (host, path)
+    } else {
+        let (user_host, path) = url.split_once(':')?;
+        if user_host.contains('/') {
+            return None;
+        }
+        let host = user_host
+            .rsplit_once('@')
+            .map_or(user_host, |(_, host)| host);
+        (host, path)
+    };
+    let path = path.trim_matches('/');
+    let path = path.strip_suffix(".git").unwrap_or(path);
+    (!host.is_empty() && !path.is_empty()).then_some((host, path))
+}
+
 /// Validates that the commits that will be pushed are ready (have authorship
 /// information, are not conflicted, etc.)
 fn validate_commits_ready_to_push(
     ui: &Ui,
     bookmark_updates: &[(String, BookmarkPushUpdate)],
+    tag_updates: &[(String, CommitId)],
     remote: &str,
     tx: &WorkspaceCommandTransaction,
     command: &CommandHelper,
@@ -350,6 +622,7 @@ fn validate_commits_ready_to_push(
     let new_heads = bookmark_updates
         .iter()
         .filter_map(|(_, update)| update.new_target.clone())
+        .chain(tag_updates.iter().map(|(_, id)| id.clone()))
         .collect_vec();
     let old_heads = repo
         .view()
@@ -370,6 +643,7 @@ fn validate_commits_ready_to_push(
     } else {
         Box::new(|_: &CommitId| Ok(false))
     };
+    let classifier = workspace_helper.commit_classifier()?;
 
     for commit in workspace_helper
         .attach_revset_evaluator(commits_to_push)
@@ -391,7 +665,7 @@ fn validate_commits_ready_to_push(
         {
             reasons.push("it has no author and/or committer set");
         }
-        if commit.has_conflict()? {
+        if !args.allow_conflicts && commit.has_conflict()? {
             reasons.push("it has conflicts");
         }
         let is_private = is_private(commit.id())?;
@@ -419,6 +693,20 @@ fn validate_commits_ready_to_push(
             }
             return Err(error);
         }
+        let warned_classes = classifier
+            .classes_of(commit.id())?
+            .into_iter()
+            .filter(|class| class.warn_on_push)
+            .map(|class| class.name.as_str())
+            .collect_vec();
+        if !warned_classes.is_empty() {
+            writeln!(
+                ui.warning_default(),
+                "Pushing commit in class {}: {}",
+                warned_classes.join(", "),
+                workspace_helper.format_commit_summary(&commit)
+            )?;
+        }
     }
     Ok(())
 }
@@ -427,17 +715,39 @@ fn print_commits_ready_to_push(
     formatter: &mut dyn Formatter,
     repo: &dyn Repo,
     bookmark_updates: &[(String, BookmarkPushUpdate)],
+    tag_updates: &[(String, CommitId)],
 ) -> io::Result<()> {
-    let to_direction = |old_target: &CommitId, new_target: &CommitId| {
-        assert_ne!(old_target, new_target);
-        if repo.index().is_ancestor(old_target, new_target) {
-            BookmarkMoveDirection::Forward
-        } else if repo.index().is_ancestor(new_target, old_target) {
-            BookmarkMoveDirection::Backward
-        } else {
-            BookmarkMoveDirection::Sideways
-        }
-    };
+    // Check reachability of all moved bookmarks in both directions at once.
+    let moved_bookmarks = bookmark_updates
+        .iter()
+        .filter_map(|(bookmark_name, update)| {
+            let old_target = update.old_target.as_ref()?;
+            let new_target = update.new_target.as_ref()?;
+            assert_ne!(old_target, new_target);
+            Some((bookmark_name.as_str(), old_target, new_target))
+        })
+        .collect_vec();
+    let reachability_pairs = moved_bookmarks
+        .iter()
+        .flat_map(|&(_, old_target, new_target)| {
+            [
+                (old_target.clone(), new_target.clone()),
+                (new_target.clone(), old_target.clone()),
+            ]
+        })
+        .collect_vec();
+    let reachability = repo.index().is_ancestor_batch(&reachability_pairs);
+    let directions: HashMap<&str, BookmarkMoveDirection> =
+        iter::zip(&moved_bookmarks, reachability.chunks_exact(2))
+            .map(|(&(bookmark_name, _, _), results)| {
+                let direction = match results {
+                    [true, _] => BookmarkMoveDirection::Forward,
+                    [_, true] => BookmarkMoveDirection::Backward,
+                    _ => BookmarkMoveDirection::Sideways,
+                };
+                (bookmark_name, direction)
+            })
+            .collect();
 
     for (bookmark_name, update) in bookmark_updates {
         match (&update.old_target, &update.new_target) {
@@ -450,7 +760,7 @@ fn print_commits_ready_to_push(
                 // among many was moved sideways (say). TODO: People on Discord
                 // suggest "Move bookmark ... forward by n commits",
                 // possibly "Move bookmark ... sideways (X forward, Y back)".
-                let msg = match to_direction(old_target, new_target) {
+                let msg = match directions[bookmark_name.as_str()] {
                     BookmarkMoveDirection::Forward => {
                         format!("Move forward bookmark {bookmark_name} from {old} to {new}")
                     }
@@ -482,6 +792,13 @@ fn print_commits_ready_to_push(
             }
         }
     }
+    for (tag_name, new_target) in tag_updates {
+        writeln!(
+            formatter,
+            "  Add tag {tag_name} to {}",
+            short_commit_hash(new_target)
+        )?;
+    }
     Ok(())
 }
 
@@ -506,7 +823,28 @@ fn get_default_push_remote(
     }
 }
 
-fn get_change_bookmark_prefix(ui: &Ui, settings: &UserSettings) -> Result<String, CommandError> {
+/// How to name the bookmarks created by `--change`.
+enum ChangeBookmarkNaming {
+    /// Prefix followed by the (possibly shortened) change ID.
+    Prefix(String),
+    /// Commit template text.
+    Template(String),
+}
+
+fn get_change_bookmark_naming(
+    ui: &Ui,
+    settings: &UserSettings,
+    remote: &str,
+) -> Result<ChangeBookmarkNaming, CommandError> {
+    if let Some(text) = settings
+        .get_string("templates.git_push_bookmark")
+        .optional()?
+    {
+        return Ok(ChangeBookmarkNaming::Template(text));
+    }
+    if let Some(prefix) = settings.remote_settings(remote)?.push_bookmark_prefix {
+        return Ok(ChangeBookmarkNaming::Prefix(prefix));
+    }
     // TODO: Drop support support for git.push-branch-prefix in 0.28.0+ and move
     // the default value to config/*.toml
     if let Some(prefix) = settings.get_string("git.push-branch-prefix").optional()? {
@@ -515,11 +853,11 @@ fn get_change_bookmark_prefix(ui: &Ui, settings: &UserSettings) -> Result<String
             "Config git.push-branch-prefix is deprecated. Please switch to \
              git.push-bookmark-prefix",
         )?;
-        Ok(prefix)
+        Ok(ChangeBookmarkNaming::Prefix(prefix))
     } else if let Some(prefix) = settings.get_string("git.push-bookmark-prefix").optional()? {
-        Ok(prefix)
+        Ok(ChangeBookmarkNaming::Prefix(prefix))
     } else {
-        Ok("push-".to_owned())
+        Ok(ChangeBookmarkNaming::Prefix("push-".to_owned()))
     }
 }
 
@@ -596,7 +934,8 @@ fn update_change_bookmarks(
     ui: &Ui,
     tx: &mut WorkspaceCommandTransaction,
     changes: &[RevisionArg],
-    bookmark_prefix: &str,
+    remote: &str,
+    bookmark_naming: &ChangeBookmarkNaming,
 ) -> Result<Vec<String>, CommandError> {
     if changes.is_empty() {
         // NOTE: we don't want resolve_some_revsets_default_single to fail if the
@@ -604,39 +943,111 @@ fn update_change_bookmarks(
         return Ok(vec![]);
     }
 
-    let mut bookmark_names = Vec::new();
     let workspace_command = tx.base_workspace_helper();
     let all_commits = workspace_command.resolve_some_revsets_default_single(ui, changes)?;
+    let bookmark_names = match bookmark_naming {
+        ChangeBookmarkNaming::Prefix(bookmark_prefix) => all_commits
+            .iter()
+            .map(|commit| {
+                let short_change_id = short_change_hash(commit.change_id());
+                let bookmark_name = format!("{bookmark_prefix}{}", commit.change_id().hex());
+                let view = tx.base_repo().view();
+                if view.get_local_bookmark(&bookmark_name).is_present() {
+                    return bookmark_name;
+                }
+                // A local bookmark with the full change ID doesn't exist already, so use the
+                // short ID if it's not ambiguous (which it shouldn't be most of the time).
+                if workspace_command
+                    .resolve_single_rev(ui, &RevisionArg::from(short_change_id.clone()))
+                    .is_ok()
+                {
+                    // Short change ID is not ambiguous, so update the bookmark name to use it.
+                    format!("{bookmark_prefix}{short_change_id}")
+                } else {
+                    bookmark_name
+                }
+            })
+            .collect_vec(),
+        ChangeBookmarkNaming::Template(text) => {
+            let template = workspace_command.parse_commit_template(ui, text)?;
+            all_commits
+                .iter()
+                .map(|commit| {
+                    let mut output = Vec::new();
+                    template.format(commit, &mut PlainTextFormatter::new(&mut output))?;
+                    Ok(output.into_string_lossy().trim().to_owned())
+                })
+                .collect::<io::Result<Vec<_>>>()?
+        }
+    };
 
-    for commit in all_commits {
-        let workspace_command = tx.base_workspace_helper();
+    for (i, (bookmark_name, commit)) in bookmark_names.iter().zip(&all_commits).enumerate() {
         let short_change_id = short_change_hash(commit.change_id());
-        let mut bookmark_name = format!("{bookmark_prefix}{}", commit.change_id().hex());
-        let view = tx.base_repo().view();
-        if view.get_local_bookmark(&bookmark_name).is_absent() {
-            // A local bookmark with the full change ID doesn't exist already, so use the
-            // short ID if it's not ambiguous (which it shouldn't be most of the time).
-            if workspace_command
-                .resolve_single_rev(ui, &RevisionArg::from(short_change_id.clone()))
-                .is_ok()
-            {
-                // Short change ID is not ambiguous, so update the bookmark name to use it.
-                bookmark_name = format!("{bookmark_prefix}{short_change_id}");
-            };
+        if bookmark_name.is_empty() {
+            return Err(user_error(format!(
+                "Empty bookmark name was generated for revision {short_change_id}"
+            )));
         }
-        if view.get_local_bookmark(&bookmark_name).is_absent() {
+        if !git2::Reference::is_valid_name(&format!("refs/heads/{bookmark_name}")) {
+            return Err(user_error(format!(
+                "Invalid bookmark name {bookmark_name:?} was generated for revision \
+                 {short_change_id}"
+            )));
+        }
+        if bookmark_names[..i].contains(bookmark_name) {
+            return Err(user_error(format!(
+                "Bookmark name {bookmark_name} was generated for multiple revisions"
+            )));
+        }
+        check_change_bookmark_collision(tx.base_repo().as_ref(), bookmark_name, remote, commit)?;
+    }
+
+    for (bookmark_name, commit) in bookmark_names.iter().zip(&all_commits) {
+        let short_change_id = short_change_hash(commit.change_id());
+        if tx
+            .base_repo()
+            .view()
+            .get_local_bookmark(bookmark_name)
+            .is_absent()
+        {
             writeln!(
                 ui.status(),
                 "Creating bookmark {bookmark_name} for revision {short_change_id}",
             )?;
         }
         tx.repo_mut()
-            .set_local_bookmark_target(&bookmark_name, RefTarget::normal(commit.id().clone()));
-        bookmark_names.push(bookmark_name);
+            .set_local_bookmark_target(bookmark_name, RefTarget::normal(commit.id().clone()));
     }
     Ok(bookmark_names)
 }
 
+/// Checks that the generated bookmark doesn't point to another change on the
+/// remote. The local bookmark may be moved freely.
+fn check_change_bookmark_collision(
+    repo: &dyn Repo,
+    bookmark_name: &str,
+    remote: &str,
+    commit: &Commit,
+) -> Result<(), CommandError> {
+    let remote_ref = repo.view().get_remote_bookmark(bookmark_name, remote);
+    for id in remote_ref.target.added_ids() {
+        let other_commit = repo.store().get_commit(id)?;
+        if other_commit.change_id() != commit.change_id() {
+            return Err(user_error_with_hint(
+                format!(
+                    "Bookmark name {bookmark_name} generated for revision {} is already used by \
+                     revision {}",
+                    short_change_hash(commit.change_id()),
+                    short_change_hash(other_commit.change_id()),
+                ),
+                "Configure `templates.git_push_bookmark` to generate unique names, or use \
+                 `--bookmark` to push an existing bookmark.",
+            ));
+        }
+    }
+    Ok(())
+}
+
 fn find_bookmarks_to_push<'a>(
     view: &'a View,
     bookmark_patterns: &[StringPattern],
@@ -668,6 +1079,24 @@ fn find_bookmarks_to_push<'a>(
     }
 }
 
+fn find_tags_to_push(
+    view: &View,
+    tag_patterns: &[StringPattern],
+) -> Result<Vec<(String, CommitId)>, CommandError> {
+    let mut tag_updates = vec![];
+    for (tag_name, target) in find_tags(view, tag_patterns)? {
+        let Some(id) = target.as_normal() else {
+            return Err(user_error_with_hint(
+                format!("Tag {tag_name} is conflicted"),
+                "Run `jj tag list` to inspect, and use `jj tag delete` and `jj tag create` to \
+                 resolve.",
+            ));
+        };
+        tag_updates.push((tag_name.to_owned(), id.clone()));
+    }
+    Ok(tag_updates)
+}
+
 fn find_bookmarks_targeted_by_revisions<'a>(
     ui: &Ui,
     workspace_command: &'a WorkspaceCommandHelper,
@@ -726,3 +1155,57 @@ fn find_bookmarks_targeted_by_revisions<'a>(
         .collect_vec();
     Ok(bookmarks_targeted)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_forge_url() {
+        assert_eq!(
+            parse_forge_url("https://github.com/owner/repo.git"),
+            Some(("github.com", "owner/repo"))
+        );
+        assert_eq!(
+            parse_forge_url("ssh://git@gitlab.com:22/group/sub/repo"),
+            Some(("gitlab.com", "group/sub/repo"))
+        );
+        assert_eq!(
+            parse_forge_url("git@github.com:owner/repo.git"),
+            Some(("github.com", "owner/repo"))
+        );
+        assert_eq!(parse_forge_url("/path/to/repo.git"), None);
+        assert_eq!(parse_forge_url("file:///path/to/repo.git"), None);
+    }
+
+    #[test]
+    fn test_get_create_pr_url_template() {
+        assert_eq!(
+            get_create_pr_url_template(None, Some("git@github.com:owner/repo.git")).as_deref(),
+            Some("https://github.com/owner/repo/pull/new/{bookmark}")
+        );
+        assert_eq!(
+            get_create_pr_url_template(None, Some("https://gitlab.com/group/repo.git")).as_deref(),
+            Some(
+                "https://gitlab.com/group/repo/-/merge_requests/new?merge_request[source_branch]=\
+                 {bookmark}"
+            )
+        );
+        assert_eq!(
+            get_create_pr_url_template(None, Some("https://example.com/owner/repo.git")),
+            None
+        );
+        assert_eq!(
+            get_create_pr_url_template(
+                Some("https://example.com/compare/{bookmark}".to_owned()),
+                Some("https://github.com/owner/repo.git")
+            )
+            .as_deref(),
+            Some("https://example.com/compare/{bookmark}")
+        );
+        assert_eq!(
+            get_create_pr_url_template(Some("".to_owned()), Some("https://github.com/o/r")),
+            None
+        );
+    }
+}
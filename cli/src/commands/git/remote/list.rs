@@ -23,24 +23,30 @@ use crate::ui::Ui;
 
 /// List Git remotes
 #[derive(clap::Args, Clone, Debug)]
-pub struct GitRemoteListArgs {}
+pub struct GitRemoteListArgs {
+    /// Show the fetch and push URLs of each remote separately
+    #[arg(long, short)]
+    verbose: bool,
+}
 
 pub fn cmd_git_remote_list(
     ui: &mut Ui,
     command: &CommandHelper,
-    _args: &GitRemoteListArgs,
+    args: &GitRemoteListArgs,
 ) -> Result<(), CommandError> {
     let workspace_command = command.workspace_helper(ui)?;
     let repo = workspace_command.repo();
     let git_repo = get_git_repo(repo.store())?;
     for remote_name in git_repo.remotes()?.iter().flatten() {
         let remote = git_repo.find_remote(remote_name)?;
-        writeln!(
-            ui.stdout(),
-            "{} {}",
-            remote_name,
-            remote.url().unwrap_or("<no URL>")
-        )?;
+        let fetch_url = remote.url().unwrap_or("<no URL>");
+        if args.verbose {
+            let push_url = remote.pushurl().unwrap_or(fetch_url);
+            writeln!(ui.stdout(), "{remote_name} {fetch_url} (fetch)")?;
+            writeln!(ui.stdout(), "{remote_name} {push_url} (push)")?;
+        } else {
+            writeln!(ui.stdout(), "{remote_name} {fetch_url}")?;
+        }
     }
     Ok(())
 }
@@ -30,6 +30,11 @@ pub struct GitRemoteSetUrlArgs {
     remote: String,
     /// The desired url for `remote`
     url: String,
+    /// Set the URL used for pushing instead of the one used for fetching
+    ///
+    /// If no push URL is set, the fetch URL is used for pushing too.
+    #[arg(long)]
+    push: bool,
 }
 
 pub fn cmd_git_remote_set_url(
@@ -40,6 +45,10 @@ pub fn cmd_git_remote_set_url(
     let workspace_command = command.workspace_helper(ui)?;
     let repo = workspace_command.repo();
     let git_repo = get_git_repo(repo.store())?;
-    git::set_remote_url(&git_repo, &args.remote, &args.url)?;
+    if args.push {
+        git::set_remote_push_url(&git_repo, &args.remote, Some(&args.url))?;
+    } else {
+        git::set_remote_url(&git_repo, &args.remote, &args.url)?;
+    }
     Ok(())
 }
@@ -32,7 +32,7 @@ use crate::ui::Ui;
 /// If the given directory does not exist, it will be created. If no directory
 /// is given, the current directory is used.
 #[derive(clap::Args, Clone, Debug)]
-#[command(group(ArgGroup::new("backend").args(&["git", "git_repo"])))]
+#[command(group(ArgGroup::new("backend").args(&["git", "git_repo", "shared_store"])))]
 pub(crate) struct InitArgs {
     /// The destination directory
     #[arg(default_value = ".", value_hint = clap::ValueHint::DirPath)]
@@ -45,6 +45,12 @@ pub(crate) struct InitArgs {
     /// Path to a git repo the jj repo will be backed by
     #[arg(long, hide = true, value_hint = clap::ValueHint::DirPath)]
     git_repo: Option<String>,
+    /// Write the objects to a shared object store directory
+    ///
+    /// The directory is created if it doesn't exist. Repos initialized with
+    /// the same shared store don't duplicate the objects they have in common.
+    #[arg(long, value_name = "DIR", value_hint = clap::ValueHint::DirPath)]
+    shared_store: Option<String>,
 }
 
 #[instrument(skip_all)]
@@ -83,7 +89,16 @@ Use `jj git init` instead"
 Set `ui.allow-init-native` to allow initializing a repo with the native backend.",
             ));
         }
-        Workspace::init_local(command.settings(), &wc_path)?;
+        if let Some(shared_store) = &args.shared_store {
+            let shared_store_path = cwd.join(shared_store);
+            Workspace::init_local_with_shared_store(
+                command.settings(),
+                &wc_path,
+                &shared_store_path,
+            )?;
+        } else {
+            Workspace::init_local(command.settings(), &wc_path)?;
+        }
     }
 
     let relative_wc_path = file_util::relative_path(cwd, &wc_path);
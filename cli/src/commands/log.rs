@@ -14,6 +14,7 @@
 
 use clap_complete::ArgValueCandidates;
 use clap_complete::ArgValueCompleter;
+use itertools::Itertools as _;
 use jj_lib::backend::CommitId;
 use jj_lib::config::ConfigGetError;
 use jj_lib::config::ConfigGetResultExt as _;
@@ -21,6 +22,7 @@ use jj_lib::graph::GraphEdgeType;
 use jj_lib::graph::ReverseGraphIterator;
 use jj_lib::graph::TopoGroupedGraphIterator;
 use jj_lib::repo::Repo;
+use jj_lib::revset::CommitPrefetchIterator;
 use jj_lib::revset::RevsetEvaluationError;
 use jj_lib::revset::RevsetExpression;
 use jj_lib::revset::RevsetFilterPredicate;
@@ -32,6 +34,7 @@ use crate::cli_util::format_template;
 use crate::cli_util::CommandHelper;
 use crate::cli_util::LogContentFormat;
 use crate::cli_util::RevisionArg;
+use crate::command_error::user_error_with_hint;
 use crate::command_error::CommandError;
 use crate::commit_templater::CommitTemplateLanguage;
 use crate::complete;
@@ -51,7 +54,9 @@ use crate::ui::Ui;
 /// syntax.
 ///
 /// Spans of revisions that are not included in the graph per `--revisions` are
-/// rendered as a synthetic node labeled "(elided revisions)".
+/// rendered as a synthetic node labeled "(elided revisions)". If
+/// `ui.log-omitted-hint` is enabled and revisions are elided or cut off by
+/// `--limit`, a hint noting how many were omitted is printed after the log.
 ///
 /// The working-copy commit is indicated by a `@` symbol in the graph. Immutable
 /// revisions (https://jj-vcs.github.io/jj/latest/config/#set-of-immutable-commits)
@@ -62,7 +67,8 @@ pub(crate) struct LogArgs {
     /// Which revisions to show
     ///
     /// If no paths nor revisions are specified, this defaults to the
-    /// `revsets.log` setting.
+    /// `revisions` of the selected `--preset`, or to the `revsets.log`
+    /// setting.
     #[arg(
         long,
         short,
@@ -103,31 +109,91 @@ pub(crate) struct LogArgs {
     /// You can also specify arbitrary template expressions. For the syntax,
     /// see https://jj-vcs.github.io/jj/latest/templates/.
     ///
-    /// If not specified, this defaults to the `templates.log` setting.
+    /// If not specified, this defaults to the `template` of the selected
+    /// `--preset`, or to the `templates.log` setting.
     #[arg(long, short = 'T')]
     template: Option<String>,
+    /// Use the default revisions and template of the named preset
+    ///
+    /// Presets are defined in the `log-presets.<name>` config tables. Explicit
+    /// `--revisions`, paths, or `--template` take precedence over the preset.
+    #[arg(long, value_name = "NAME")]
+    preset: Option<String>,
     /// Show patch
     #[arg(long, short = 'p')]
     patch: bool,
+    /// Flag revisions whose signatures are bad or can't be verified
+    ///
+    /// A line describing the problem is added below the revision. Unsigned
+    /// revisions aren't flagged.
+    #[arg(long)]
+    verify_sigs: bool,
     #[command(flatten)]
     diff_format: DiffFormatArgs,
 }
 
+/// Defaults for `jj log` defined in a `log-presets.<name>` config table.
+#[derive(Clone, Debug, Default, Eq, PartialEq, serde::Deserialize)]
+#[serde(rename_all = "kebab-case")]
+struct LogPreset {
+    revisions: Option<String>,
+    template: Option<String>,
+}
+
+fn load_log_preset(settings: &UserSettings, name: &str) -> Result<LogPreset, CommandError> {
+    if !settings.table_keys("log-presets").any(|key| key == name) {
+        let names = settings.table_keys("log-presets").sorted().join(", ");
+        let hint = if names.is_empty() {
+            "Define presets in the `log-presets` config table.".to_owned()
+        } else {
+            format!("Available presets: {names}")
+        };
+        return Err(user_error_with_hint(
+            format!("Unknown log preset: {name}"),
+            hint,
+        ));
+    }
+    Ok(settings.get(["log-presets", name])?)
+}
+
+/// Number of commits to read from the store ahead of rendering them.
+const COMMIT_PREFETCH_BATCH_SIZE: usize = 32;
+
+/// Maximum number of omitted revisions to count for `ui.log-omitted-hint`.
+const OMITTED_COUNT_LIMIT: usize = 100;
+
 #[instrument(skip_all)]
 pub(crate) fn cmd_log(
     ui: &mut Ui,
     command: &CommandHelper,
     args: &LogArgs,
 ) -> Result<(), CommandError> {
-    let workspace_command = command.workspace_helper(ui)?;
+    let workspace_command = command.workspace_helper_read_only(ui)?;
+    let preset = match &args.preset {
+        Some(name) => load_log_preset(command.settings(), name)?,
+        None => LogPreset::default(),
+    };
 
     let fileset_expression = workspace_command.parse_file_patterns(ui, &args.paths)?;
+    // Revset text to suggest in the footer, if the revset can be expressed as
+    // such (paths can't.)
+    let mut revset_text = None;
     let revset_expression = {
         // only use default revset if neither revset nor path are specified
         let mut expression = if args.revisions.is_empty() && args.paths.is_empty() {
-            let revset_string = command.settings().get_string("revsets.log")?;
+            let revset_string = match preset.revisions {
+                Some(revisions) => revisions,
+                None => command.settings().get_string("revsets.log")?,
+            };
+            revset_text = Some(revset_string.clone());
             workspace_command.parse_revset(ui, &RevisionArg::from(revset_string))?
         } else if !args.revisions.is_empty() {
+            if args.paths.is_empty() {
+                revset_text = Some(match args.revisions.as_slice() {
+                    [revision] => revision.to_string(),
+                    revisions => revisions.iter().map(|r| format!("({r})")).join(" | "),
+                });
+            }
             workspace_command.parse_union_revsets(ui, &args.revisions)?
         } else {
             // a path was specified so we use all() and add path filter later
@@ -153,15 +219,28 @@ pub(crate) fn cmd_log(
     let use_elided_nodes = command
         .settings()
         .get_bool("ui.log-synthetic-elided-nodes")?;
+    let show_omitted_hint = command.settings().get_bool("ui.log-omitted-hint")?;
     let with_content_format = LogContentFormat::new(ui, command.settings())?;
+    let limit = args.limit.or(args.deprecated_limit).unwrap_or(usize::MAX);
 
     let template;
     let node_template;
     {
         let language = workspace_command.commit_template_language();
-        let template_string = match &args.template {
-            Some(value) => value.to_string(),
-            None => command.settings().get_string("templates.log")?,
+        let template_string = match (&args.template, preset.template) {
+            (Some(value), _) => value.clone(),
+            (None, Some(value)) => value,
+            (None, None) => command.settings().get_string("templates.log")?,
+        };
+        let template_string = if args.verify_sigs {
+            signature_problem_template(&template_string)
+        } else {
+            template_string
+        };
+        let template_string = if workspace_command.env().commit_classes().is_empty() {
+            template_string
+        } else {
+            class_label_template(&template_string)
         };
         template = workspace_command
             .parse_template(
@@ -181,6 +260,8 @@ pub(crate) fn cmd_log(
             .labeled("node");
     }
 
+    let mut num_shown = 0;
+    let mut num_elided_spans = 0;
     {
         ui.request_pager();
         let mut formatter = ui.stdout_formatter();
@@ -192,7 +273,6 @@ pub(crate) fn cmd_log(
                 "The -l shorthand is deprecated, use -n instead."
             )?;
         }
-        let limit = args.limit.or(args.deprecated_limit).unwrap_or(usize::MAX);
 
         if !args.no_graph {
             let mut raw_output = formatter.raw()?;
@@ -213,8 +293,15 @@ pub(crate) fn cmd_log(
                     Box::new(forward_iter)
                 }
             };
-            for node in iter.take(limit) {
-                let (commit_id, edges) = node?;
+            let iter = CommitPrefetchIterator::new(
+                store,
+                iter.take(limit),
+                COMMIT_PREFETCH_BATCH_SIZE,
+                |(commit_id, _edges)| commit_id.clone(),
+            );
+            for node in iter {
+                let ((commit_id, edges), commit) = node?;
+                num_shown += 1;
 
                 // The graph is keyed by (CommitId, is_synthetic)
                 let mut graphlog_edges = vec![];
@@ -232,6 +319,7 @@ pub(crate) fn cmd_log(
                             graphlog_edges.push(Edge::Direct((edge.target, false)));
                         }
                         GraphEdgeType::Indirect => {
+                            num_elided_spans += 1;
                             if use_elided_nodes {
                                 elided_targets.push(edge.target.clone());
                                 graphlog_edges.push(Edge::Direct((edge.target, true)));
@@ -246,7 +334,6 @@ pub(crate) fn cmd_log(
                 }
                 let mut buffer = vec![];
                 let key = (commit_id, false);
-                let commit = store.get_commit(&key.0)?;
                 let within_graph =
                     with_content_format.sub_width(graph.width(&key, &graphlog_edges));
                 within_graph.write(ui.new_formatter(&mut buffer).as_mut(), |formatter| {
@@ -299,8 +386,15 @@ pub(crate) fn cmd_log(
                 } else {
                     Box::new(revset.iter())
                 };
-            for commit_or_error in iter.commits(store).take(limit) {
-                let commit = commit_or_error?;
+            let iter = CommitPrefetchIterator::new(
+                store,
+                iter.take(limit),
+                COMMIT_PREFETCH_BATCH_SIZE,
+                CommitId::clone,
+            );
+            for commit_or_error in iter {
+                let (_commit_id, commit) = commit_or_error?;
+                num_shown += 1;
                 with_content_format
                     .write(formatter, |formatter| template.format(&commit, formatter))?;
                 if let Some(renderer) = &diff_renderer {
@@ -311,6 +405,47 @@ pub(crate) fn cmd_log(
         }
     }
 
+    if show_omitted_hint && num_shown == limit {
+        // Count only a bounded number of the remaining revisions so that
+        // --limit still avoids walking the entire revset.
+        let num_omitted = revset
+            .iter()
+            .skip(limit)
+            .take(OMITTED_COUNT_LIMIT)
+            .process_results(|iter| iter.count())?;
+        if num_omitted > 0 {
+            writeln!(
+                ui.hint_default(),
+                "{} not shown because of --limit. Run without --limit to see them.",
+                format_omitted_count(num_omitted, "more revision")
+            )?;
+        }
+    }
+    if show_omitted_hint && num_elided_spans > 0 {
+        let connected = revset_expression.expression().connected();
+        let elided = connected.minus(revset_expression.expression());
+        let num_elided = workspace_command
+            .attach_revset_evaluator(elided)
+            .evaluate()?
+            .iter()
+            .take(OMITTED_COUNT_LIMIT)
+            .process_results(|iter| iter.count())?;
+        let mut writer = ui.hint_default();
+        write!(
+            writer,
+            "{} elided from the graph.",
+            format_omitted_count(num_elided, "revision")
+        )?;
+        if let Some(text) = &revset_text {
+            write!(
+                writer,
+                " Run `jj log -r {}` to see them.",
+                shell_quote(&format!("connected({text})"))
+            )?;
+        }
+        writeln!(writer)?;
+    }
+
     // Check to see if the user might have specified a path when they intended
     // to specify a revset.
     if let ([], [only_path]) = (args.revisions.as_slice(), args.paths.as_slice()) {
@@ -338,6 +473,32 @@ pub(crate) fn cmd_log(
     Ok(())
 }
 
+/// Formats the number of omitted revisions, which was counted up to
+/// `OMITTED_COUNT_LIMIT`.
+fn format_omitted_count(count: usize, noun: &str) -> String {
+    match count {
+        1 => format!("1 {noun} was"),
+        OMITTED_COUNT_LIMIT => format!("At least {count} {noun}s were"),
+        _ => format!("{count} {noun}s were"),
+    }
+}
+
+fn shell_quote(text: &str) -> String {
+    format!("'{}'", text.replace('\'', r"'\''"))
+}
+
+/// Appends a line about bad or unverifiable signatures to the output of the
+/// commit template.
+pub(crate) fn signature_problem_template(template_string: &str) -> String {
+    format!("concat(\n{template_string}\n,\nformat_signature_problem(self),\n)")
+}
+
+/// Wraps the given template so the output is labeled with the classes of the
+/// commit, e.g. `class wip`.
+fn class_label_template(template_string: &str) -> String {
+    format!("label(self.classes().map(|c| \"class \" ++ c).join(\" \"),\n{template_string}\n)")
+}
+
 pub fn get_node_template(
     style: GraphStyle,
     settings: &UserSettings,
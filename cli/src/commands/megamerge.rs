@@ -0,0 +1,200 @@
+// Copyright 2024 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::io::Write as _;
+
+use clap_complete::ArgValueCandidates;
+use itertools::Itertools as _;
+use jj_lib::backend::CommitId;
+use jj_lib::commit::Commit;
+use jj_lib::object_id::ObjectId as _;
+use jj_lib::repo::Repo as _;
+use jj_lib::revset::RevsetExpression;
+use jj_lib::rewrite::move_commits;
+use jj_lib::rewrite::MoveCommitsTarget;
+use jj_lib::rewrite::RebaseOptions;
+use tracing::instrument;
+
+use crate::cli_util::CommandHelper;
+use crate::cli_util::RevisionArg;
+use crate::cli_util::WorkspaceCommandHelper;
+use crate::command_error::user_error;
+use crate::command_error::CommandError;
+use crate::complete;
+use crate::ui::Ui;
+
+/// Manage merge commits of many branches
+///
+/// A megamerge is a merge commit of all the branches you're working on, which
+/// lets you work on top of all of them at once. Use `jj new --merge-all` to
+/// create one.
+#[derive(clap::Subcommand, Clone, Debug)]
+pub(crate) enum MegamergeCommand {
+    Update(MegamergeUpdateArgs),
+}
+
+/// Update the parents of a megamerge
+///
+/// By default, each mutable parent is replaced by the heads of its mutable
+/// descendants, so the megamerge includes commits added on top of the merged
+/// branches. Descendants of the megamerge itself are not considered.
+/// Immutable parents are kept as is.
+///
+/// With `--merge-all`, the parents are replaced by the heads of the given
+/// revset instead.
+///
+/// The megamerge and its descendants are rebased onto the new parents.
+#[derive(clap::Args, Clone, Debug)]
+pub(crate) struct MegamergeUpdateArgs {
+    /// The megamerge commit to update
+    #[arg(
+        long,
+        short,
+        default_value = "@",
+        value_name = "REVSET",
+        add = ArgValueCandidates::new(complete::mutable_revisions)
+    )]
+    revision: RevisionArg,
+    /// Merge the heads of these commits instead
+    #[arg(
+        long,
+        value_name = "REVSET",
+        add = ArgValueCandidates::new(complete::all_revisions)
+    )]
+    merge_all: Option<RevisionArg>,
+}
+
+pub(crate) fn cmd_megamerge(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    subcommand: &MegamergeCommand,
+) -> Result<(), CommandError> {
+    match subcommand {
+        MegamergeCommand::Update(args) => cmd_megamerge_update(ui, command, args),
+    }
+}
+
+/// Returns the heads of the commits in `revision` in topological order, oldest
+/// first.
+pub(crate) fn resolve_merge_all_heads(
+    ui: &Ui,
+    workspace_command: &WorkspaceCommandHelper,
+    revision: &RevisionArg,
+) -> Result<Vec<Commit>, CommandError> {
+    let expression = workspace_command
+        .parse_revset(ui, revision)?
+        .expression()
+        .heads();
+    let heads: Vec<Commit> = workspace_command
+        .attach_revset_evaluator(expression)
+        .evaluate_to_commits()?
+        .try_collect()?;
+    if heads.is_empty() {
+        return Err(user_error(format!(
+            "Revset `{revision}` didn't resolve to any revisions"
+        )));
+    }
+    Ok(heads.into_iter().rev().collect())
+}
+
+#[instrument(skip_all)]
+fn cmd_megamerge_update(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    args: &MegamergeUpdateArgs,
+) -> Result<(), CommandError> {
+    let mut workspace_command = command.workspace_helper(ui)?;
+    let megamerge = workspace_command.resolve_single_rev(ui, &args.revision)?;
+    workspace_command.check_rewritable([megamerge.id()])?;
+    let megamerge_descendants = RevsetExpression::commit(megamerge.id().clone()).descendants();
+
+    let new_parent_ids: Vec<CommitId> = if let Some(merge_all) = &args.merge_all {
+        let expression = workspace_command
+            .parse_revset(ui, merge_all)?
+            .expression()
+            .minus(&megamerge_descendants)
+            .heads();
+        let heads: Vec<CommitId> = workspace_command
+            .attach_revset_evaluator(expression)
+            .evaluate_to_commit_ids()?
+            .try_collect()?;
+        heads.into_iter().rev().collect()
+    } else {
+        let immutable = workspace_command.env().immutable_expression();
+        let mut new_parent_ids = vec![];
+        for parent_id in megamerge.parent_ids() {
+            let parent = RevsetExpression::commit(parent_id.clone());
+            let expression = parent.intersection(&immutable).union(
+                &parent
+                    .minus(&immutable)
+                    .descendants()
+                    .minus(&megamerge_descendants)
+                    .heads(),
+            );
+            let heads: Vec<CommitId> = workspace_command
+                .attach_revset_evaluator(expression)
+                .evaluate_to_commit_ids()?
+                .try_collect()?;
+            new_parent_ids.extend(heads.into_iter().rev());
+        }
+        new_parent_ids.into_iter().unique().collect()
+    };
+    if new_parent_ids.is_empty() {
+        return Err(user_error("The megamerge would have no parents"));
+    }
+    if new_parent_ids == megamerge.parent_ids() {
+        writeln!(ui.status(), "Nothing changed.")?;
+        return Ok(());
+    }
+
+    let mut tx = workspace_command.start_transaction();
+    let stats = move_commits(
+        command.settings(),
+        tx.repo_mut(),
+        &new_parent_ids,
+        &[],
+        &MoveCommitsTarget::Roots(vec![megamerge.clone()]),
+        &RebaseOptions::default(),
+    )?;
+    if let Some(mut formatter) = ui.status_formatter() {
+        let store = tx.repo().store().clone();
+        for id in new_parent_ids
+            .iter()
+            .filter(|id| !megamerge.parent_ids().contains(id))
+        {
+            write!(formatter, "Added parent: ")?;
+            tx.write_commit_summary(formatter.as_mut(), &store.get_commit(id)?)?;
+            writeln!(formatter)?;
+        }
+        for id in megamerge
+            .parent_ids()
+            .iter()
+            .filter(|id| !new_parent_ids.contains(id))
+        {
+            write!(formatter, "Removed parent: ")?;
+            tx.write_commit_summary(formatter.as_mut(), &store.get_commit(id)?)?;
+            writeln!(formatter)?;
+        }
+        // The descendants of the megamerge are moved along with it.
+        let num_rebased_descendants = stats.num_rebased_targets.saturating_sub(1);
+        if num_rebased_descendants > 0 {
+            writeln!(
+                formatter,
+                "Rebased {num_rebased_descendants} descendant commits"
+            )?;
+        }
+    }
+    tx.finish(ui, format!("update megamerge {}", megamerge.id().hex()))?;
+    Ok(())
+}
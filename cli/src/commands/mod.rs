@@ -14,6 +14,8 @@
 
 mod abandon;
 mod absorb;
+mod apply;
+mod archive;
 mod backout;
 #[cfg(feature = "bench")]
 mod bench;
@@ -34,23 +36,30 @@ mod help;
 mod init;
 mod interdiff;
 mod log;
+mod megamerge;
 mod new;
 mod next;
 mod operation;
 mod parallelize;
 mod prev;
+mod range_diff;
 mod rebase;
 mod resolve;
+mod resolve_divergence;
 mod restore;
 mod root;
 mod run;
 mod show;
+mod sign;
 mod simplify_parents;
+mod snapshot;
 mod sparse;
 mod split;
 mod squash;
 mod status;
+mod sync;
 mod tag;
+mod unsign;
 mod unsquash;
 mod util;
 mod version;
@@ -78,6 +87,8 @@ use crate::ui::Ui;
 enum Command {
     Abandon(abandon::AbandonArgs),
     Absorb(absorb::AbsorbArgs),
+    Apply(apply::ApplyArgs),
+    Archive(archive::ArchiveArgs),
     Backout(backout::BackoutArgs),
     #[cfg(feature = "bench")]
     #[command(subcommand)]
@@ -117,6 +128,8 @@ enum Command {
     Init(init::InitArgs),
     Interdiff(interdiff::InterdiffArgs),
     Log(log::LogArgs),
+    #[command(subcommand)]
+    Megamerge(megamerge::MegamergeCommand),
     New(new::NewArgs),
     Next(next::NextArgs),
     #[command(subcommand)]
@@ -124,8 +137,10 @@ enum Command {
     Operation(operation::OperationCommand),
     Parallelize(parallelize::ParallelizeArgs),
     Prev(prev::PrevArgs),
+    RangeDiff(range_diff::RangeDiffArgs),
     Rebase(rebase::RebaseArgs),
     Resolve(resolve::ResolveArgs),
+    ResolveDivergence(resolve_divergence::ResolveDivergenceArgs),
     Restore(restore::RestoreArgs),
     #[command(
         hide = true,
@@ -133,22 +148,24 @@ enum Command {
     )]
     Revert(DummyCommandArgs),
     Root(root::RootArgs),
-    #[command(hide = true)]
-    // TODO: Flesh out.
     Run(run::RunArgs),
     Show(show::ShowArgs),
+    Sign(sign::SignArgs),
     SimplifyParents(simplify_parents::SimplifyParentsArgs),
+    Snapshot(snapshot::SnapshotArgs),
     #[command(subcommand)]
     Sparse(sparse::SparseCommand),
     Split(split::SplitArgs),
     Squash(squash::SquashArgs),
     Status(status::StatusArgs),
+    Sync(sync::SyncArgs),
     #[command(subcommand)]
     Tag(tag::TagCommand),
     #[command(subcommand)]
     Util(util::UtilCommand),
     /// Undo an operation (shortcut for `jj op undo`)
     Undo(operation::undo::OperationUndoArgs),
+    Unsign(unsign::UnsignArgs),
     // TODO: Delete `unsquash` in jj 0.28+
     #[command(hide = true)]
     Unsquash(unsquash::UnsquashArgs),
@@ -177,6 +194,8 @@ pub fn run_command(ui: &mut Ui, command_helper: &CommandHelper) -> Result<(), Co
     match &subcommand {
         Command::Abandon(args) => abandon::cmd_abandon(ui, command_helper, args),
         Command::Absorb(args) => absorb::cmd_absorb(ui, command_helper, args),
+        Command::Apply(args) => apply::cmd_apply(ui, command_helper, args),
+        Command::Archive(args) => archive::cmd_archive(ui, command_helper, args),
         Command::Backout(args) => backout::cmd_backout(ui, command_helper, args),
         #[cfg(feature = "bench")]
         Command::Bench(args) => bench::cmd_bench(ui, command_helper, args),
@@ -212,14 +231,19 @@ pub fn run_command(ui: &mut Ui, command_helper: &CommandHelper) -> Result<(), Co
         Command::Init(args) => init::cmd_init(ui, command_helper, args),
         Command::Interdiff(args) => interdiff::cmd_interdiff(ui, command_helper, args),
         Command::Log(args) => log::cmd_log(ui, command_helper, args),
+        Command::Megamerge(args) => megamerge::cmd_megamerge(ui, command_helper, args),
         Command::New(args) => new::cmd_new(ui, command_helper, args),
         Command::Next(args) => next::cmd_next(ui, command_helper, args),
         Command::Evolog(args) => evolog::cmd_evolog(ui, command_helper, args),
         Command::Operation(args) => operation::cmd_operation(ui, command_helper, args),
         Command::Parallelize(args) => parallelize::cmd_parallelize(ui, command_helper, args),
         Command::Prev(args) => prev::cmd_prev(ui, command_helper, args),
+        Command::RangeDiff(args) => range_diff::cmd_range_diff(ui, command_helper, args),
         Command::Rebase(args) => rebase::cmd_rebase(ui, command_helper, args),
         Command::Resolve(args) => resolve::cmd_resolve(ui, command_helper, args),
+        Command::ResolveDivergence(args) => {
+            resolve_divergence::cmd_resolve_divergence(ui, command_helper, args)
+        }
         Command::Restore(args) => restore::cmd_restore(ui, command_helper, args),
         Command::Revert(_args) => revert(),
         Command::Root(args) => root::cmd_root(ui, command_helper, args),
@@ -227,13 +251,17 @@ pub fn run_command(ui: &mut Ui, command_helper: &CommandHelper) -> Result<(), Co
         Command::SimplifyParents(args) => {
             simplify_parents::cmd_simplify_parents(ui, command_helper, args)
         }
+        Command::Snapshot(args) => snapshot::cmd_snapshot(ui, command_helper, args),
         Command::Show(args) => show::cmd_show(ui, command_helper, args),
+        Command::Sign(args) => sign::cmd_sign(ui, command_helper, args),
         Command::Sparse(args) => sparse::cmd_sparse(ui, command_helper, args),
         Command::Split(args) => split::cmd_split(ui, command_helper, args),
         Command::Squash(args) => squash::cmd_squash(ui, command_helper, args),
         Command::Status(args) => status::cmd_status(ui, command_helper, args),
+        Command::Sync(args) => sync::cmd_sync(ui, command_helper, args),
         Command::Tag(args) => tag::cmd_tag(ui, command_helper, args),
         Command::Undo(args) => operation::undo::cmd_op_undo(ui, command_helper, args),
+        Command::Unsign(args) => unsign::cmd_unsign(ui, command_helper, args),
         Command::Unsquash(args) => unsquash::cmd_unsquash(ui, command_helper, args),
         Command::Untrack(args) => {
             let cmd = renamed_cmd("untrack", "file untrack", file::untrack::cmd_file_untrack);
@@ -15,10 +15,12 @@
 use std::collections::HashSet;
 use std::io::Write;
 use std::rc::Rc;
+use std::slice;
 
 use clap_complete::ArgValueCandidates;
 use itertools::Itertools;
 use jj_lib::backend::CommitId;
+use jj_lib::commit::Commit;
 use jj_lib::commit::CommitIteratorExt;
 use jj_lib::repo::ReadonlyRepo;
 use jj_lib::repo::Repo;
@@ -27,15 +29,21 @@ use jj_lib::revset::RevsetExpression;
 use jj_lib::revset::RevsetIteratorExt;
 use jj_lib::rewrite::merge_commit_trees;
 use jj_lib::rewrite::rebase_commit;
+use jj_lib::rewrite::restore_tree;
+use jj_lib::settings::UserSettings;
 use tracing::instrument;
 
 use crate::cli_util::short_commit_hash;
 use crate::cli_util::CommandHelper;
 use crate::cli_util::RevisionArg;
+use crate::cli_util::WorkspaceCommandTransaction;
 use crate::command_error::user_error;
 use crate::command_error::CommandError;
+use crate::commands::megamerge::resolve_merge_all_heads;
 use crate::complete;
 use crate::description_util::join_message_paragraphs;
+use crate::merge_tools::ConflictResolveError;
+use crate::merge_tools::MergeEditor;
 use crate::ui::Ui;
 
 /// Create a new, empty change and (by default) edit it in the working copy
@@ -45,7 +53,9 @@ use crate::ui::Ui;
 ///
 /// Note that you can create a merge commit by specifying multiple revisions as
 /// argument. For example, `jj new @ main` will create a new commit with the
-/// working copy and the `main` bookmark as parents.
+/// working copy and the `main` bookmark as parents. If the merge has
+/// conflicts, `--merge-tool` resolves them right away, and `--take-tree` takes
+/// the contents of some paths from one of the parents instead of merging them.
 ///
 /// For more information, see
 /// https://jj-vcs.github.io/jj/latest/working-copy/.
@@ -91,6 +101,44 @@ pub(crate) struct NewArgs {
         add = ArgValueCandidates::new(complete::mutable_revisions),
     )]
     insert_before: Vec<RevisionArg>,
+    /// Create a merge of the heads of the given revset
+    ///
+    /// This is useful for creating a "megamerge" of all the branches you're
+    /// working on. Use `jj megamerge update` to update its parents later.
+    #[arg(
+        long,
+        conflicts_with_all = ["revisions", "insert_after", "insert_before"],
+        value_name = "REVSET",
+        add = ArgValueCandidates::new(complete::all_revisions),
+    )]
+    merge_all: Option<RevisionArg>,
+    /// Resolve conflicts in the new change with a merge tool
+    ///
+    /// The merge tool is run for each conflicted file, as with `jj resolve`.
+    /// Conflicts that the merge tool can't resolve are left in place.
+    #[arg(long)]
+    merge_tool: bool,
+    /// Specify 3-way merge tool to be used with `--merge-tool`
+    #[arg(long, requires = "merge_tool", value_name = "NAME")]
+    tool: Option<String>,
+    /// Take the contents of the given parent instead of merging the parents
+    ///
+    /// Applies to the paths given by `--take-tree-path`, or to all paths by
+    /// default.
+    #[arg(
+        long,
+        value_name = "REVSET",
+        add = ArgValueCandidates::new(complete::all_revisions),
+    )]
+    take_tree: Option<RevisionArg>,
+    /// Restrict `--take-tree` to these paths
+    #[arg(
+        long,
+        requires = "take_tree",
+        value_name = "FILESETS",
+        value_hint = clap::ValueHint::AnyPath,
+    )]
+    take_tree_path: Vec<String>,
 }
 
 #[instrument(skip_all)]
@@ -172,6 +220,10 @@ pub(crate) fn cmd_new(
             .iter()
             .commits(workspace_command.repo().store())
             .try_collect()?;
+    } else if let Some(merge_all) = &args.merge_all {
+        parent_commits = resolve_merge_all_heads(ui, &workspace_command, merge_all)?;
+        parent_commit_ids = parent_commits.iter().ids().cloned().collect();
+        children_commits = vec![];
     } else {
         parent_commits = workspace_command
             .resolve_some_revsets_default_single(ui, &args.revisions)?
@@ -190,9 +242,33 @@ pub(crate) fn cmd_new(
     workspace_command.check_rewritable(children_commits.iter().ids())?;
 
     let parent_commit_ids_set: HashSet<CommitId> = parent_commit_ids.iter().cloned().collect();
+    let take_tree = if let Some(revision) = &args.take_tree {
+        let commit = workspace_command.resolve_single_rev(ui, revision)?;
+        if !parent_commit_ids_set.contains(commit.id()) {
+            return Err(user_error(format!(
+                "Commit {} passed to --take-tree is not a parent of the new commit",
+                short_commit_hash(commit.id()),
+            )));
+        }
+        let matcher = workspace_command
+            .parse_file_patterns(ui, &args.take_tree_path)?
+            .to_matcher();
+        Some((commit, matcher))
+    } else {
+        None
+    };
+    let merge_editor = if args.merge_tool {
+        Some(workspace_command.merge_editor(ui, args.tool.as_deref())?)
+    } else {
+        None
+    };
 
     let mut tx = workspace_command.start_transaction();
-    let merged_tree = merge_commit_trees(tx.repo(), &parent_commits)?;
+    let mut merged_tree = merge_commit_trees(tx.repo(), &parent_commits)?;
+    if let Some((commit, matcher)) = &take_tree {
+        let tree_id = restore_tree(&commit.tree()?, &merged_tree, matcher.as_ref())?;
+        merged_tree = tx.repo().store().get_root_tree(&tree_id)?;
+    }
     let new_commit = tx
         .repo_mut()
         .new_commit(command.settings(), parent_commit_ids, merged_tree.id())
@@ -217,6 +293,24 @@ pub(crate) fn cmd_new(
         num_rebased += 1;
     }
     num_rebased += tx.repo_mut().rebase_descendants(command.settings())?;
+    tx.reapply_recorded_resolutions(ui)?;
+    // The new commit may have been rewritten to use recorded resolutions.
+    let new_commit = match tx
+        .repo()
+        .new_parents(slice::from_ref(new_commit.id()))
+        .as_slice()
+    {
+        [new_commit_id] if new_commit_id != new_commit.id() => {
+            tx.repo().store().get_commit(new_commit_id)?
+        }
+        _ => new_commit,
+    };
+    let new_commit = match &merge_editor {
+        Some(merge_editor) => {
+            resolve_with_merge_tool(ui, command.settings(), &mut tx, merge_editor, new_commit)?
+        }
+        None => new_commit,
+    };
 
     if args.no_edit {
         if let Some(mut formatter) = ui.status_formatter() {
@@ -241,6 +335,45 @@ pub(crate) fn cmd_new(
     Ok(())
 }
 
+/// Runs the merge tool for each conflicted file in the `commit`, and rewrites
+/// the commit with the resolutions.
+fn resolve_with_merge_tool(
+    ui: &Ui,
+    settings: &UserSettings,
+    tx: &mut WorkspaceCommandTransaction,
+    merge_editor: &MergeEditor,
+    commit: Commit,
+) -> Result<Commit, CommandError> {
+    let mut tree = commit.tree()?;
+    let conflicted_paths = tree.conflicts().map(|(path, _)| path).collect_vec();
+    for path in &conflicted_paths {
+        let ui_path = tx.base_workspace_helper().format_file_path(path);
+        match merge_editor.edit_file(&tree, path) {
+            Ok(tree_id) => {
+                writeln!(ui.status(), "Resolved conflicts in: {ui_path}")?;
+                tree = tx.repo().store().get_root_tree(&tree_id)?;
+            }
+            Err(err @ ConflictResolveError::NotNormalFiles(..)) => {
+                writeln!(
+                    ui.warning_default(),
+                    "Skipped resolving conflicts in {ui_path}: {err}"
+                )?;
+            }
+            Err(err) => return Err(err.into()),
+        }
+    }
+    if tree.id() == *commit.tree_id() {
+        return Ok(commit);
+    }
+    let new_commit = tx
+        .repo_mut()
+        .rewrite_commit(settings, &commit)
+        .set_tree_id(tree.id())
+        .write()?;
+    tx.repo_mut().rebase_descendants(settings)?;
+    Ok(new_commit)
+}
+
 /// Ensure that there is no possible cycle between the potential children and
 /// parents of the new commit.
 fn ensure_no_commit_loop(
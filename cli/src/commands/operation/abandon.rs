@@ -19,6 +19,9 @@ use std::slice;
 use clap_complete::ArgValueCandidates;
 use itertools::Itertools as _;
 use jj_lib::op_walk;
+use jj_lib::op_walk::ReparentStats;
+use jj_lib::operation::Operation;
+use jj_lib::workspace::Workspace;
 
 use crate::cli_util::short_operation_hash;
 use crate::cli_util::CommandHelper;
@@ -37,13 +40,25 @@ use crate::ui::Ui;
 /// To discard recent operations, use `jj op restore <operation ID>` followed
 /// by `jj op abandon <operation ID>..@-`.
 ///
+/// To make the operation log more readable, use `jj op abandon
+/// --squash-snapshots`. Each run of consecutive working-copy snapshot
+/// operations will be squashed into the last operation of the run. Undoing the
+/// squashed operation restores the state before the first snapshot.
+///
 /// The abandoned operations, commits, and other unreachable objects can later
 /// be garbage collected by using `jj util gc` command.
 #[derive(clap::Args, Clone, Debug)]
 pub struct OperationAbandonArgs {
     /// The operation or operation range to abandon
-    #[arg(add = ArgValueCandidates::new(complete::operations))]
-    operation: String,
+    #[arg(
+        required_unless_present = "squash_snapshots",
+        add = ArgValueCandidates::new(complete::operations),
+    )]
+    operation: Option<String>,
+    /// Squash consecutive snapshot operations instead of abandoning an
+    /// operation range
+    #[arg(long, conflicts_with = "operation")]
+    squash_snapshots: bool,
 }
 
 pub fn cmd_op_abandon(
@@ -63,9 +78,24 @@ pub fn cmd_op_abandon(
         return Err(cli_error("--at-op is not respected"));
     }
     let current_head_ops = op_walk::get_current_head_ops(op_store, op_heads_store.as_ref())?;
+    if args.squash_snapshots {
+        let stats = op_walk::squash_snapshot_operations(op_store.as_ref(), &current_head_ops)?;
+        if stats.unreachable_count == 0 {
+            writeln!(ui.status(), "Nothing changed.")?;
+            return Ok(());
+        }
+        writeln!(
+            ui.status(),
+            "Squashed {} snapshot operations and reparented {} descendant operations.",
+            stats.unreachable_count,
+            stats.rewritten_count,
+        )?;
+        return update_reparented_heads(ui, command, &mut workspace, &current_head_ops, &stats);
+    }
+    let operation = args.operation.as_deref().unwrap();
     let resolve_op = |op_str| op_walk::resolve_op_at(op_store, &current_head_ops, op_str);
     let (abandon_root_op, abandon_head_ops) =
-        if let Some((root_op_str, head_op_str)) = args.operation.split_once("..") {
+        if let Some((root_op_str, head_op_str)) = operation.split_once("..") {
             let root_op = if root_op_str.is_empty() {
                 repo_loader.root_operation()
             } else {
@@ -78,7 +108,7 @@ pub fn cmd_op_abandon(
             };
             (root_op, head_ops)
         } else {
-            let op = resolve_op(&args.operation)?;
+            let op = resolve_op(operation)?;
             let parent_ops: Vec<_> = op.parents().try_collect()?;
             let parent_op = match parent_ops.len() {
                 0 => return Err(user_error("Cannot abandon the root operation")),
@@ -125,6 +155,20 @@ pub fn cmd_op_abandon(
         stats.unreachable_count,
         stats.rewritten_count,
     )?;
+    update_reparented_heads(ui, command, &mut workspace, &current_head_ops, &stats)
+}
+
+/// Updates the operation heads and the working-copy operation after the
+/// `current_head_ops` have been rewritten.
+fn update_reparented_heads(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    workspace: &mut Workspace,
+    current_head_ops: &[Operation],
+    stats: &ReparentStats,
+) -> Result<(), CommandError> {
+    let op_heads_store = workspace.repo_loader().op_heads_store().clone();
+    let reparented_head_ops = || iter::zip(current_head_ops, &stats.new_head_ids);
     for (old, new_id) in reparented_head_ops().filter(|&(old, new_id)| old.id() != new_id) {
         op_heads_store.update_op_heads(slice::from_ref(old.id()), new_id)?;
     }
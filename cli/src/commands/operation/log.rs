@@ -12,11 +12,14 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::collections::HashSet;
 use std::slice;
 
 use itertools::Itertools as _;
 use jj_lib::config::ConfigGetError;
 use jj_lib::config::ConfigGetResultExt as _;
+use jj_lib::op_store::OpStoreError;
+use jj_lib::op_store::OperationId;
 use jj_lib::op_walk;
 use jj_lib::operation::Operation;
 use jj_lib::repo::RepoLoader;
@@ -60,6 +63,12 @@ pub struct OperationLogArgs {
     /// Don't show the graph, show a flat list of operations
     #[arg(long)]
     no_graph: bool,
+    /// Don't show operations that only snapshot the working copy
+    ///
+    /// In the graph, an operation whose parents are hidden is connected to the
+    /// closest ancestors that are shown.
+    #[arg(long)]
+    no_snapshot_entries: bool,
     /// Render each operation using the given template
     ///
     /// For the syntax, see https://jj-vcs.github.io/jj/latest/templates/
@@ -116,6 +125,7 @@ fn do_op_log(
     let op_node_template;
     {
         let language = OperationTemplateLanguage::new(
+            settings,
             repo_loader.op_store().root_operation_id(),
             Some(current_op.id()),
             workspace_env.operation_template_extensions(),
@@ -204,16 +214,23 @@ fn do_op_log(
         )?;
     }
     let limit = args.limit.or(args.deprecated_limit).unwrap_or(usize::MAX);
-    let iter = op_walk::walk_ancestors(slice::from_ref(current_op)).take(limit);
+    let is_shown = |op: &Operation| !(args.no_snapshot_entries && op.metadata().is_snapshot);
+    let iter = op_walk::walk_ancestors(slice::from_ref(current_op))
+        .filter_ok(is_shown)
+        .take(limit);
     if !args.no_graph {
         let mut raw_output = formatter.raw()?;
         let mut graph = get_graphlog(graph_style, raw_output.as_mut());
         for op in iter {
             let op = op?;
-            let mut edges = vec![];
-            for id in op.parent_ids() {
-                edges.push(Edge::Direct(id.clone()));
-            }
+            let edges = if args.no_snapshot_entries {
+                get_shown_parent_edges(&op, is_shown)?
+            } else {
+                op.parent_ids()
+                    .iter()
+                    .map(|id| Edge::Direct(id.clone()))
+                    .collect()
+            };
             let mut buffer = vec![];
             let within_graph = with_content_format.sub_width(graph.width(op.id(), &edges));
             within_graph.write(ui.new_formatter(&mut buffer).as_mut(), |formatter| {
@@ -247,6 +264,38 @@ fn do_op_log(
     Ok(())
 }
 
+/// Returns edges from `op` to its closest ancestors that are shown, skipping
+/// over hidden operations.
+fn get_shown_parent_edges(
+    op: &Operation,
+    is_shown: impl Fn(&Operation) -> bool,
+) -> Result<Vec<Edge<OperationId>>, OpStoreError> {
+    let mut edges = vec![];
+    let mut visited = HashSet::new();
+    let mut to_visit = op
+        .parents()
+        .map_ok(|parent| (parent, true))
+        .collect::<Result<Vec<_>, _>>()?;
+    to_visit.reverse();
+    while let Some((parent, direct)) = to_visit.pop() {
+        if !visited.insert(parent.id().clone()) {
+            continue;
+        }
+        if is_shown(&parent) {
+            let id = parent.id().clone();
+            edges.push(if direct {
+                Edge::Direct(id)
+            } else {
+                Edge::Indirect(id)
+            });
+        } else {
+            let grand_parents: Vec<_> = parent.parents().try_collect()?;
+            to_visit.extend(grand_parents.into_iter().rev().map(|op| (op, false)));
+        }
+    }
+    Ok(edges)
+}
+
 fn get_node_template(style: GraphStyle, settings: &UserSettings) -> Result<String, ConfigGetError> {
     let symbol = settings.get_string("templates.op_log_node").optional()?;
     let default = if style.is_ascii() {
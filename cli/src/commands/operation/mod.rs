@@ -18,6 +18,7 @@ mod log;
 mod restore;
 mod show;
 pub mod undo;
+mod wait;
 
 use abandon::cmd_op_abandon;
 use abandon::OperationAbandonArgs;
@@ -32,6 +33,8 @@ use show::cmd_op_show;
 use show::OperationShowArgs;
 use undo::cmd_op_undo;
 use undo::OperationUndoArgs;
+use wait::cmd_op_wait;
+use wait::OperationWaitArgs;
 
 use crate::cli_util::CommandHelper;
 use crate::command_error::CommandError;
@@ -49,6 +52,7 @@ pub enum OperationCommand {
     Restore(OperationRestoreArgs),
     Show(OperationShowArgs),
     Undo(OperationUndoArgs),
+    Wait(OperationWaitArgs),
 }
 
 pub fn cmd_operation(
@@ -63,6 +67,7 @@ pub fn cmd_operation(
         OperationCommand::Restore(args) => cmd_op_restore(ui, command, args),
         OperationCommand::Show(args) => cmd_op_show(ui, command, args),
         OperationCommand::Undo(args) => cmd_op_undo(ui, command, args),
+        OperationCommand::Wait(args) => cmd_op_wait(ui, command, args),
     }
 }
 
@@ -0,0 +1,75 @@
+// Copyright 2024 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::io::Write as _;
+use std::time::Duration;
+
+use jj_lib::op_heads_store::wait_for_op_heads_change;
+
+use crate::cli_util::CommandHelper;
+use crate::command_error::cli_error;
+use crate::command_error::user_error;
+use crate::command_error::CommandError;
+use crate::ui::Ui;
+
+/// How often the operation heads are checked for changes.
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Wait until another process finishes an operation
+///
+/// Blocks until the operation log has a new head, then prints a summary of
+/// the current operation. This is useful for tools that want to refresh their
+/// view of the repository when another `jj` process modifies it.
+///
+/// The working copy is not snapshotted by this command.
+#[derive(clap::Args, Clone, Debug)]
+pub struct OperationWaitArgs {
+    /// Give up after this many seconds
+    ///
+    /// The command fails if no operation was finished by then.
+    #[arg(long, value_name = "SECONDS")]
+    timeout: Option<u64>,
+}
+
+pub fn cmd_op_wait(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    args: &OperationWaitArgs,
+) -> Result<(), CommandError> {
+    if command.global_args().at_operation.is_some() {
+        return Err(cli_error("--at-op is not respected"));
+    }
+    let workspace = command.load_workspace()?;
+    let op_heads_store = workspace.repo_loader().op_heads_store();
+    let old_op_heads = op_heads_store.get_op_heads()?;
+    let timeout = args.timeout.map(Duration::from_secs);
+    let new_op_heads = wait_for_op_heads_change(
+        op_heads_store.as_ref(),
+        &old_op_heads,
+        POLL_INTERVAL,
+        timeout,
+    )?;
+    if new_op_heads.is_none() {
+        return Err(user_error("Timed out waiting for a new operation"));
+    }
+
+    // Load the repo to resolve divergent operations, if any.
+    let workspace_command = command.workspace_helper_no_snapshot(ui)?;
+    let op = workspace_command.repo().operation();
+    let mut formatter = ui.stdout_formatter();
+    let template = workspace_command.operation_summary_template();
+    template.format(op, formatter.as_mut())?;
+    writeln!(formatter)?;
+    Ok(())
+}
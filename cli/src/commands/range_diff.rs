@@ -0,0 +1,217 @@
+// Copyright 2024 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::io;
+use std::slice;
+
+use clap_complete::ArgValueCandidates;
+use futures::StreamExt as _;
+use futures::TryStreamExt as _;
+use itertools::Itertools as _;
+use jj_lib::backend::BackendResult;
+use jj_lib::commit::Commit;
+use jj_lib::matchers::EverythingMatcher;
+use jj_lib::merged_tree::TreeDiffEntry;
+use jj_lib::repo::Repo;
+use jj_lib::repo_path::RepoPathBuf;
+use jj_lib::rewrite::rebase_to_dest_parent;
+use pollster::FutureExt as _;
+use tracing::instrument;
+
+use crate::cli_util::CommandHelper;
+use crate::cli_util::RevisionArg;
+use crate::command_error::CommandError;
+use crate::complete;
+use crate::diff_util::DiffFormatArgs;
+use crate::formatter::Formatter;
+use crate::ui::Ui;
+
+/// Compare two series of commits
+///
+/// Commits in the old and new series are paired up by change id. Commits whose
+/// change id only appears in one series are paired up by the similarity of the
+/// paths they touch. For each pair, the changes made by the new commit are
+/// compared to the changes made by the old commit, as `jj interdiff` does.
+/// Descriptions aren't compared.
+///
+/// This is useful for reviewing how a stack of commits evolved after it was
+/// rebased or edited, e.g. `jj range-diff 'trunk()..old' 'trunk()..new'`.
+#[derive(clap::Args, Clone, Debug)]
+#[command(mut_arg("ignore_all_space", |a| a.short('w')))]
+#[command(mut_arg("ignore_space_change", |a| a.short('b')))]
+pub(crate) struct RangeDiffArgs {
+    /// The old series of commits
+    #[arg(
+        value_name = "OLD_REVSET",
+        add = ArgValueCandidates::new(complete::all_revisions)
+    )]
+    old: RevisionArg,
+    /// The new series of commits
+    #[arg(
+        value_name = "NEW_REVSET",
+        add = ArgValueCandidates::new(complete::all_revisions)
+    )]
+    new: RevisionArg,
+    #[command(flatten)]
+    format: DiffFormatArgs,
+}
+
+/// Minimum ratio of shared paths for two commits with different change ids
+/// to be considered the same change.
+const MIN_PATH_SIMILARITY: f64 = 0.5;
+
+#[instrument(skip_all)]
+pub(crate) fn cmd_range_diff(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    args: &RangeDiffArgs,
+) -> Result<(), CommandError> {
+    let workspace_command = command.workspace_helper(ui)?;
+    let repo = workspace_command.repo().as_ref();
+    // Oldest commits first
+    let old_commits: Vec<Commit> = workspace_command
+        .parse_revset(ui, &args.old)?
+        .evaluate_to_commits()?
+        .try_collect()?;
+    let old_commits = old_commits.into_iter().rev().collect_vec();
+    let new_commits: Vec<Commit> = workspace_command
+        .parse_revset(ui, &args.new)?
+        .evaluate_to_commits()?
+        .try_collect()?;
+    let new_commits = new_commits.into_iter().rev().collect_vec();
+    let new_to_old = pair_commits(repo, &old_commits, &new_commits)?;
+
+    let diff_renderer = workspace_command.diff_renderer_for(&args.format)?;
+    ui.request_pager();
+    let mut formatter = ui.stdout_formatter();
+    let formatter = formatter.as_mut();
+    let matched_old: HashSet<usize> = new_to_old.iter().flatten().copied().collect();
+    let mut unmatched_old = (0..old_commits.len())
+        .filter(|i| !matched_old.contains(i))
+        .peekable();
+    for (new_commit, old_index) in new_commits.iter().zip(&new_to_old) {
+        let Some(old_index) = *old_index else {
+            write_heading(formatter, "added", "Added:")?;
+            workspace_command.write_commit_summary(formatter, new_commit)?;
+            writeln!(formatter)?;
+            continue;
+        };
+        // Show removed commits near where they used to be in the old series
+        while let Some(i) = unmatched_old.next_if(|&i| i < old_index) {
+            write_heading(formatter, "removed", "Removed:")?;
+            workspace_command.write_commit_summary(formatter, &old_commits[i])?;
+            writeln!(formatter)?;
+        }
+        let old_commit = &old_commits[old_index];
+        let old_tree = rebase_to_dest_parent(repo, slice::from_ref(old_commit), new_commit)?;
+        let new_tree = new_commit.tree()?;
+        let unchanged = old_tree.id() == new_tree.id();
+        if unchanged {
+            write_heading(formatter, "unchanged", "Unchanged:")?;
+        } else {
+            write_heading(formatter, "modified", "Changed:")?;
+        }
+        workspace_command.write_commit_summary(formatter, old_commit)?;
+        writeln!(formatter)?;
+        write!(formatter, "{:>10} ", "->")?;
+        workspace_command.write_commit_summary(formatter, new_commit)?;
+        writeln!(formatter)?;
+        if !unchanged {
+            diff_renderer.show_inter_diff(
+                ui,
+                formatter,
+                slice::from_ref(old_commit),
+                new_commit,
+                &EverythingMatcher,
+                ui.term_width(),
+            )?;
+        }
+    }
+    for i in unmatched_old {
+        write_heading(formatter, "removed", "Removed:")?;
+        workspace_command.write_commit_summary(formatter, &old_commits[i])?;
+        writeln!(formatter)?;
+    }
+    Ok(())
+}
+
+fn write_heading(formatter: &mut dyn Formatter, label: &str, heading: &str) -> io::Result<()> {
+    formatter.with_label("range_diff", |formatter| {
+        write!(formatter.labeled(label), "{heading:<11}")
+    })
+}
+
+/// Returns the index of the old commit paired with each new commit.
+fn pair_commits(
+    repo: &dyn Repo,
+    old_commits: &[Commit],
+    new_commits: &[Commit],
+) -> BackendResult<Vec<Option<usize>>> {
+    let mut old_by_change_id: HashMap<_, _> = HashMap::new();
+    for (i, commit) in old_commits.iter().enumerate() {
+        old_by_change_id.entry(commit.change_id()).or_insert(i);
+    }
+    let mut new_to_old = new_commits
+        .iter()
+        .map(|commit| old_by_change_id.remove(commit.change_id()))
+        .collect_vec();
+
+    // Fall back to comparing the changed paths of the remaining commits
+    let matched_old: HashSet<usize> = new_to_old.iter().flatten().copied().collect();
+    let mut unmatched_old: Vec<(usize, HashSet<RepoPathBuf>)> = (0..old_commits.len())
+        .filter(|i| !matched_old.contains(i))
+        .map(|i| changed_paths(repo, &old_commits[i]).map(|paths| (i, paths)))
+        .try_collect()?;
+    for (new_commit, old_index) in new_commits.iter().zip(&mut new_to_old) {
+        if old_index.is_some() || unmatched_old.is_empty() {
+            continue;
+        }
+        let new_paths = changed_paths(repo, new_commit)?;
+        let best = unmatched_old
+            .iter()
+            .enumerate()
+            .map(|(pos, (_, old_paths))| (pos, path_similarity(old_paths, &new_paths)))
+            .filter(|&(_, similarity)| similarity >= MIN_PATH_SIMILARITY)
+            // Prefer the earliest commit among equally similar ones
+            .rev()
+            .max_by(|(_, a), (_, b)| a.total_cmp(b));
+        if let Some((pos, _)) = best {
+            let (i, _) = unmatched_old.remove(pos);
+            *old_index = Some(i);
+        }
+    }
+    Ok(new_to_old)
+}
+
+fn changed_paths(repo: &dyn Repo, commit: &Commit) -> BackendResult<HashSet<RepoPathBuf>> {
+    let parent_tree = commit.parent_tree(repo)?;
+    let tree = commit.tree()?;
+    parent_tree
+        .diff_stream(&tree, &EverythingMatcher)
+        .map(|TreeDiffEntry { path, values }| values.map(|_| path))
+        .try_collect()
+        .block_on()
+}
+
+/// Jaccard index of the two sets of paths.
+fn path_similarity(a: &HashSet<RepoPathBuf>, b: &HashSet<RepoPathBuf>) -> f64 {
+    let union = a.union(b).count();
+    if union == 0 {
+        // Two empty commits
+        return 1.0;
+    }
+    a.intersection(b).count() as f64 / union as f64
+}
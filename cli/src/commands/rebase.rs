@@ -12,6 +12,8 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::collections::HashMap;
+use std::collections::HashSet;
 use std::io::Write;
 use std::rc::Rc;
 use std::sync::Arc;
@@ -23,12 +25,15 @@ use jj_lib::backend::CommitId;
 use jj_lib::commit::Commit;
 use jj_lib::commit::CommitIteratorExt;
 use jj_lib::object_id::ObjectId;
+use jj_lib::repo::MutableRepo;
 use jj_lib::repo::ReadonlyRepo;
 use jj_lib::repo::Repo;
 use jj_lib::revset::ResolvedRevsetExpression;
 use jj_lib::revset::RevsetExpression;
 use jj_lib::revset::RevsetIteratorExt;
+use jj_lib::rewrite::merge_commit_trees;
 use jj_lib::rewrite::move_commits;
+use jj_lib::rewrite::restore_tree;
 use jj_lib::rewrite::EmptyBehaviour;
 use jj_lib::rewrite::MoveCommitsStats;
 use jj_lib::rewrite::MoveCommitsTarget;
@@ -193,6 +198,47 @@ pub(crate) struct RebaseArgs {
     /// parents.
     #[arg(long)]
     skip_emptied: bool,
+
+    /// If an identical rewrite of a rebased revision already exists on the
+    /// destination, reuse it instead of creating a divergent revision
+    ///
+    /// The existing revision must have the same change ID, parents, contents,
+    /// description, and author. Such revisions are typically created by
+    /// concurrent operations.
+    #[arg(long)]
+    reuse_existing: bool,
+
+    /// With `-r`, copy immutable revisions onto the destination instead of
+    /// refusing to rewrite them
+    ///
+    /// The copies keep the change IDs of the original revisions, which are
+    /// left in place, so the changes become divergent. Mutable revisions are
+    /// rebased as usual, but not onto the copies.
+    #[arg(
+        long,
+        requires = "revisions",
+        conflicts_with_all = ["branch", "source", "insert_after", "insert_before"],
+    )]
+    keep_divergent: bool,
+
+    /// With `-r`, rebase only the changes to these paths
+    ///
+    /// The changes to the matching paths are moved into a new revision on top
+    /// of the destination. The original revision is left in place with the
+    /// remaining changes.
+    #[arg(
+        long,
+        value_name = "FILESETS",
+        requires = "revisions",
+        conflicts_with_all = [
+            "branch",
+            "source",
+            "insert_after",
+            "insert_before",
+            "keep_divergent",
+        ],
+    )]
+    paths: Vec<String>,
 }
 
 #[derive(clap::Args, Clone, Debug)]
@@ -249,9 +295,20 @@ pub(crate) fn cmd_rebase(
             false => EmptyBehaviour::Keep,
         },
         simplify_ancestor_merge: false,
+        reuse_existing_rewrites: args.reuse_existing,
     };
     let mut workspace_command = command.workspace_helper(ui)?;
-    if !args.revisions.is_empty() {
+    if !args.paths.is_empty() {
+        rebase_revision_paths(
+            ui,
+            command.settings(),
+            &mut workspace_command,
+            &args.revisions,
+            &args.paths,
+            &args.destination,
+            &rebase_options,
+        )?;
+    } else if !args.revisions.is_empty() {
         rebase_revisions(
             ui,
             command.settings(),
@@ -259,6 +316,7 @@ pub(crate) fn cmd_rebase(
             &args.revisions,
             &args.destination,
             &rebase_options,
+            args.keep_divergent && !command.global_args().ignore_immutable,
         )?;
     } else if !args.source.is_empty() {
         rebase_source(
@@ -289,17 +347,35 @@ fn rebase_revisions(
     revisions: &[RevisionArg],
     rebase_destination: &RebaseDestinationArgs,
     rebase_options: &RebaseOptions,
+    keep_divergent: bool,
 ) -> Result<(), CommandError> {
-    let target_commits: Vec<_> = workspace_command
+    // in reverse topological order
+    let mut target_commits: Vec<_> = workspace_command
         .parse_union_revsets(ui, revisions)?
         .evaluate_to_commits()?
-        .try_collect()?; // in reverse topological order
+        .try_collect()?;
+    // Immutable commits are copied instead of rewritten with --keep-divergent.
+    // The root commit can't be copied, so it's left to check_rewritable().
+    let mut commits_to_copy = vec![];
+    if keep_divergent {
+        let root_commit_id = workspace_command.repo().store().root_commit_id().clone();
+        let expression = RevsetExpression::commits(target_commits.iter().ids().cloned().collect())
+            .intersection(&workspace_command.env().immutable_expression())
+            .minus(&RevsetExpression::commit(root_commit_id));
+        let immutable_ids: HashSet<CommitId> = workspace_command
+            .attach_revset_evaluator(expression)
+            .evaluate_to_commit_ids()?
+            .try_collect()?;
+        (commits_to_copy, target_commits) = target_commits
+            .into_iter()
+            .partition(|commit| immutable_ids.contains(commit.id()));
+    }
     workspace_command.check_rewritable(target_commits.iter().ids())?;
 
     let (new_parents, new_children) =
         compute_rebase_destination(ui, workspace_command, rebase_destination)?;
     if rebase_destination.destination.is_some() && new_children.is_empty() {
-        for commit in &target_commits {
+        for commit in target_commits.iter().chain(&commits_to_copy) {
             if new_parents.contains(commit) {
                 return Err(user_error(format!(
                     "Cannot rebase {} onto itself",
@@ -315,10 +391,98 @@ fn rebase_revisions(
         &new_parents.iter().ids().cloned().collect_vec(),
         &new_children,
         target_commits,
+        commits_to_copy,
         rebase_options,
     )
 }
 
+/// Rebases the changes to `paths` in a single revision onto the destination,
+/// leaving the revision in place with the remaining changes.
+fn rebase_revision_paths(
+    ui: &mut Ui,
+    settings: &UserSettings,
+    workspace_command: &mut WorkspaceCommandHelper,
+    revisions: &[RevisionArg],
+    paths: &[String],
+    rebase_destination: &RebaseDestinationArgs,
+    rebase_options: &RebaseOptions,
+) -> Result<(), CommandError> {
+    let target_commits: Vec<_> = workspace_command
+        .parse_union_revsets(ui, revisions)?
+        .evaluate_to_commits()?
+        .try_collect()?;
+    let [commit] = &target_commits[..] else {
+        return Err(user_error(
+            "--paths can only be used with a single revision",
+        ));
+    };
+    workspace_command.check_rewritable([commit.id()])?;
+    let matcher = workspace_command
+        .parse_file_patterns(ui, paths)?
+        .to_matcher();
+
+    let (new_parents, _) = compute_rebase_destination(ui, workspace_command, rebase_destination)?;
+    if new_parents.contains(commit) {
+        return Err(user_error(format!(
+            "Cannot rebase {} onto itself",
+            short_commit_hash(commit.id()),
+        )));
+    }
+    let new_parent_ids = new_parents.iter().ids().cloned().collect_vec();
+
+    let parent_tree = commit.parent_tree(workspace_command.repo().as_ref())?;
+    let tree = commit.tree()?;
+    let selected_tree_id = restore_tree(&tree, &parent_tree, matcher.as_ref())?;
+    if selected_tree_id == parent_tree.id() {
+        return Err(user_error(format!(
+            "Revision {} has no changes to the given paths",
+            short_commit_hash(commit.id()),
+        )));
+    }
+    if selected_tree_id == *commit.tree_id() {
+        // All changes are selected, so the whole revision can be rebased.
+        return rebase_revisions_transaction(
+            ui,
+            settings,
+            workspace_command,
+            &new_parent_ids,
+            &[],
+            vec![commit.clone()],
+            vec![],
+            rebase_options,
+        );
+    }
+
+    let mut tx = workspace_command.start_transaction();
+    let selected_tree = tx.repo().store().get_root_tree(&selected_tree_id)?;
+    let new_base_tree = merge_commit_trees(tx.repo(), &new_parents)?;
+    let moved_tree = new_base_tree.merge(&parent_tree, &selected_tree)?;
+    let moved_commit = tx
+        .repo_mut()
+        .new_commit(settings, new_parent_ids, moved_tree.id())
+        .set_description(commit.description())
+        .set_author(commit.author().clone())
+        .write()?;
+    let remaining_tree_id = restore_tree(&parent_tree, &tree, matcher.as_ref())?;
+    tx.repo_mut()
+        .rewrite_commit(settings, commit)
+        .set_tree_id(remaining_tree_id)
+        .write()?;
+    let num_rebased = tx.repo_mut().rebase_descendants(settings)?;
+    if let Some(mut formatter) = ui.status_formatter() {
+        if num_rebased > 0 {
+            writeln!(formatter, "Rebased {num_rebased} descendant commits")?;
+        }
+        write!(formatter, "Moved changes onto destination as: ")?;
+        tx.write_commit_summary(formatter.as_mut(), &moved_commit)?;
+        writeln!(formatter)?;
+    }
+    tx.finish(
+        ui,
+        format!("rebase changes to paths in commit {}", commit.id().hex()),
+    )
+}
+
 fn rebase_source(
     ui: &mut Ui,
     settings: &UserSettings,
@@ -437,6 +601,7 @@ fn rebase_descendants_transaction(
         rebase_options,
     )?;
     print_move_commits_stats(ui, &stats)?;
+    tx.reapply_recorded_resolutions(ui)?;
     tx.finish(ui, tx_description)
 }
 
@@ -509,6 +674,7 @@ fn compute_rebase_destination(
 }
 
 /// Creates a transaction for rebasing revisions.
+#[allow(clippy::too_many_arguments)]
 fn rebase_revisions_transaction(
     ui: &mut Ui,
     settings: &UserSettings,
@@ -516,36 +682,102 @@ fn rebase_revisions_transaction(
     new_parent_ids: &[CommitId],
     new_children: &[Commit],
     target_commits: Vec<Commit>,
+    commits_to_copy: Vec<Commit>,
     rebase_options: &RebaseOptions,
 ) -> Result<(), CommandError> {
-    if target_commits.is_empty() {
-        writeln!(ui.status(), "Nothing changed.")?;
-        return Ok(());
-    }
+    let first_commit = match (target_commits.first(), commits_to_copy.first()) {
+        (Some(commit), _) | (None, Some(commit)) => commit,
+        (None, None) => {
+            writeln!(ui.status(), "Nothing changed.")?;
+            return Ok(());
+        }
+    };
 
     let mut tx = workspace_command.start_transaction();
-    let tx_description = if target_commits.len() == 1 {
-        format!("rebase commit {}", target_commits[0].id().hex())
+    let num_commits = target_commits.len() + commits_to_copy.len();
+    let tx_description = if num_commits == 1 {
+        format!("rebase commit {}", first_commit.id().hex())
     } else {
         format!(
             "rebase commit {} and {} more",
-            target_commits[0].id().hex(),
-            target_commits.len() - 1
+            first_commit.id().hex(),
+            num_commits - 1
         )
     };
 
-    let stats = move_commits(
-        settings,
-        tx.repo_mut(),
-        new_parent_ids,
-        new_children,
-        &MoveCommitsTarget::Commits(target_commits),
-        rebase_options,
-    )?;
-    print_move_commits_stats(ui, &stats)?;
+    if !commits_to_copy.is_empty() {
+        let num_copied = copy_commits_keeping_change_ids(
+            settings,
+            tx.repo_mut(),
+            new_parent_ids,
+            &commits_to_copy,
+        )?;
+        writeln!(
+            ui.status(),
+            "Copied {num_copied} immutable commit{} onto destination",
+            if num_copied == 1 { "" } else { "s" }
+        )?;
+    }
+    if !target_commits.is_empty() {
+        let stats = move_commits(
+            settings,
+            tx.repo_mut(),
+            new_parent_ids,
+            new_children,
+            &MoveCommitsTarget::Commits(target_commits),
+            rebase_options,
+        )?;
+        print_move_commits_stats(ui, &stats)?;
+    }
+    tx.reapply_recorded_resolutions(ui)?;
     tx.finish(ui, tx_description)
 }
 
+/// Copies `commits` (in reverse topological order) onto `new_parent_ids`
+/// without rewriting them. The copies keep the change IDs of the originals.
+///
+/// Commits whose parents are also being copied are put on top of the copies of
+/// those parents instead.
+fn copy_commits_keeping_change_ids(
+    settings: &UserSettings,
+    mut_repo: &mut MutableRepo,
+    new_parent_ids: &[CommitId],
+    commits: &[Commit],
+) -> Result<usize, CommandError> {
+    let mut copied_ids: HashMap<CommitId, CommitId> = HashMap::new();
+    for commit in commits.iter().rev() {
+        let copied_parent_ids = commit
+            .parent_ids()
+            .iter()
+            .filter_map(|id| copied_ids.get(id))
+            .cloned()
+            .collect_vec();
+        let parent_ids = if copied_parent_ids.is_empty() {
+            new_parent_ids.to_vec()
+        } else {
+            copied_parent_ids
+        };
+        let parents: Vec<_> = parent_ids
+            .iter()
+            .map(|id| mut_repo.store().get_commit(id))
+            .try_collect()?;
+        let new_base_tree = merge_commit_trees(mut_repo, &parents)?;
+        let old_base_tree = commit.parent_tree(mut_repo)?;
+        let new_tree = new_base_tree.merge(&old_base_tree, &commit.tree()?)?;
+        // Not using rewrite_commit() because the original commit must not be
+        // recorded as rewritten.
+        let new_commit = mut_repo
+            .new_commit(settings, parent_ids, new_tree.id())
+            .set_change_id(commit.change_id().clone())
+            .set_predecessors(vec![commit.id().clone()])
+            .set_author(commit.author().clone())
+            .set_description(commit.description())
+            .write()?;
+        copied_ids.insert(commit.id().clone(), new_commit.id().clone());
+    }
+    Ok(copied_ids.len())
+}
+
 /// Ensure that there is no possible cycle between the potential children and
 /// parents of rebased commits.
 fn ensure_no_commit_loop(
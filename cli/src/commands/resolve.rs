@@ -12,26 +12,51 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::collections::HashSet;
+use std::io;
 use std::io::Write;
 
 use clap_complete::ArgValueCandidates;
 use clap_complete::ArgValueCompleter;
 use itertools::Itertools;
+use jj_lib::backend::BackendResult;
+use jj_lib::backend::FileId;
+use jj_lib::backend::TreeValue;
+use jj_lib::commit::Commit;
+use jj_lib::conflicts;
+use jj_lib::matchers::Matcher;
+use jj_lib::merge::MergedTreeValue;
 use jj_lib::object_id::ObjectId;
+use jj_lib::repo::Repo as _;
+use jj_lib::repo_path::RepoPath;
+use jj_lib::repo_path::RepoPathBuf;
+use jj_lib::resolution_cache::ResolutionCache;
+use jj_lib::store::Store;
+use serde::Serialize;
 use tracing::instrument;
 
 use crate::cli_util::print_conflicted_paths;
 use crate::cli_util::CommandHelper;
 use crate::cli_util::RevisionArg;
+use crate::cli_util::WorkspaceCommandHelper;
 use crate::command_error::cli_error;
+use crate::command_error::user_error;
 use crate::command_error::CommandError;
 use crate::complete;
+use crate::diff_util;
 use crate::ui::Ui;
 
 /// Resolve a conflicted file with an external merge tool
 ///
-/// Only conflicts that can be resolved with a 3-way merge are supported. See
-/// docs for merge tool configuration instructions.
+/// See docs for merge tool configuration instructions. Conflicts with more than
+/// two sides are resolved one side at a time, by running the merge tool on the
+/// first two sides, then on the result and the next side, and so on.
+///
+/// The built-in tools `:ours`, `:theirs`, and `:union` resolve conflicts
+/// without user interaction by picking the first side, picking the last side,
+/// or keeping the lines of all sides of each conflicting hunk, respectively.
+/// Changes which don't conflict are kept from all sides. They resolve all
+/// conflicts matching the given paths instead of only the first one.
 ///
 /// Note that conflicts can also be resolved without using this command. You may
 /// edit the conflict markers in the conflicted file directly with a text
@@ -57,9 +82,36 @@ pub(crate) struct ResolveArgs {
     // `diff --summary`, but should be more verbose.
     #[arg(long, short)]
     list: bool,
+    /// Show which commits the sides of each conflict originated from
+    ///
+    /// The origin of a side is the nearest parent or predecessor (recursively)
+    /// where the file has the same contents as the side.
+    #[arg(long, requires = "list", conflicts_with = "json")]
+    origins: bool,
+    /// Print the list of conflicts as JSON, one object per line
+    ///
+    /// Each object describes the path, the number of sides, the terms of the
+    /// conflict, the commits the sides originated from, and whether any term
+    /// is binary, executable, or a symlink.
+    #[arg(long, requires = "list")]
+    json: bool,
     /// Specify 3-way merge tool to be used
+    ///
+    /// Besides the configured tools, `:builtin`, `:ours`, `:theirs`, and
+    /// `:union` are accepted.
     #[arg(long, conflicts_with = "list", value_name = "NAME")]
     tool: Option<String>,
+    /// Record how conflicts were resolved in the revision
+    ///
+    /// Conflicts in the revision's parents or in its earlier versions that
+    /// have since been resolved are recorded. When the same conflict appears
+    /// again in `jj rebase`, `jj new`, or `jj squash`, the recorded resolution
+    /// is applied automatically.
+    #[arg(long, conflicts_with_all = ["list", "tool"])]
+    remember: bool,
+    /// Forget recorded resolutions of the conflicts in the revision
+    #[arg(long, conflicts_with_all = ["list", "tool", "remember"])]
+    forget: bool,
     /// Restrict to these paths when searching for a conflict to resolve. We
     /// will attempt to resolve the first conflict we can find. You can use
     /// the `--list` argument to find paths to use here.
@@ -83,6 +135,12 @@ pub(crate) fn cmd_resolve(
         .parse_file_patterns(ui, &args.paths)?
         .to_matcher();
     let commit = workspace_command.resolve_single_rev(ui, &args.revision)?;
+    if args.remember {
+        return remember_resolutions(ui, &workspace_command, &commit, matcher.as_ref());
+    }
+    if args.forget {
+        return forget_resolutions(ui, &workspace_command, &commit, matcher.as_ref());
+    }
     let tree = commit.tree()?;
     let conflicts = tree
         .conflicts()
@@ -95,24 +153,55 @@ pub(crate) fn cmd_resolve(
             "No conflicts found at the given path(s)"
         }));
     }
+    if args.json {
+        return print_conflicts_json(ui, &workspace_command, &commit, conflicts);
+    }
     if args.list {
         return print_conflicted_paths(
             conflicts,
             ui.stdout_formatter().as_mut(),
             &workspace_command,
+            args.origins.then_some(&commit),
         );
     };
 
-    let (repo_path, _) = conflicts.first().unwrap();
     workspace_command.check_rewritable([commit.id()])?;
     let merge_editor = workspace_command.merge_editor(ui, args.tool.as_deref())?;
-    writeln!(
-        ui.status(),
-        "Resolving conflicts in: {}",
-        workspace_command.format_file_path(repo_path)
-    )?;
+    // Automatic tools resolve all matched conflicts, interactive tools only the
+    // first one.
+    let repo_paths = if merge_editor.is_automatic() {
+        conflicts
+            .iter()
+            .map(|(path, _)| path.as_ref())
+            .collect_vec()
+    } else {
+        vec![conflicts[0].0.as_ref()]
+    };
+    for repo_path in &repo_paths {
+        writeln!(
+            ui.status(),
+            "Resolving conflicts in: {}",
+            workspace_command.format_file_path(repo_path)
+        )?;
+    }
+    // While the user resolves the first conflict, get the next one ready for
+    // the next `jj resolve` invocation.
+    let prematerialize_thread = if merge_editor.is_automatic() {
+        None
+    } else {
+        let next_paths = conflicts.get(1).map(|(path, _)| path.clone());
+        merge_editor.prematerialize_in_background(&tree, next_paths.into_iter().collect())
+    };
     let mut tx = workspace_command.start_transaction();
-    let new_tree_id = merge_editor.edit_file(&tree, repo_path)?;
+    let result = merge_editor.edit_files(&tree, &repo_paths);
+    if let Some(thread) = prematerialize_thread {
+        // The cache is just an optimization, so the user's edits shouldn't be
+        // lost if preparing the next conflict failed.
+        if thread.join().is_err() {
+            tracing::warn!("pre-materialization thread panicked");
+        }
+    }
+    let new_tree_id = result?;
     let new_commit = tx
         .repo_mut()
         .rewrite_commit(command.settings(), &commit)
@@ -135,9 +224,202 @@ pub(crate) fn cmd_resolve(
                     formatter,
                     "After this operation, some files at this revision still have conflicts:"
                 )?;
-                print_conflicted_paths(new_conflicts, formatter.as_mut(), &workspace_command)?;
+                print_conflicted_paths(
+                    new_conflicts,
+                    formatter.as_mut(),
+                    &workspace_command,
+                    Some(&new_commit),
+                )?;
             }
         }
     }
     Ok(())
 }
+
+/// Collects the conflicts the `commit` was created from: conflicts in the
+/// merged parents and in the predecessors of `commit` which may have been
+/// resolved since.
+fn collect_past_conflicts(
+    workspace_command: &WorkspaceCommandHelper,
+    commit: &Commit,
+    matcher: &dyn Matcher,
+) -> Result<Vec<(RepoPathBuf, MergedTreeValue)>, CommandError> {
+    let mut conflicts = vec![];
+    let parent_tree = commit.parent_tree(workspace_command.repo().as_ref())?;
+    for (path, conflict) in parent_tree.conflicts() {
+        if matcher.matches(&path) {
+            conflicts.push((path, conflict?));
+        }
+    }
+    // Walk the evolution log back until the commit had no conflicts.
+    let mut visited = HashSet::new();
+    let mut to_visit = vec![commit.clone()];
+    while let Some(commit) = to_visit.pop() {
+        for predecessor in commit.predecessors() {
+            let predecessor = predecessor?;
+            if !visited.insert(predecessor.id().clone()) || !predecessor.has_conflict()? {
+                continue;
+            }
+            for (path, conflict) in predecessor.tree()?.conflicts() {
+                if matcher.matches(&path) {
+                    conflicts.push((path, conflict?));
+                }
+            }
+            to_visit.push(predecessor);
+        }
+    }
+    Ok(conflicts)
+}
+
+fn remember_resolutions(
+    ui: &mut Ui,
+    workspace_command: &WorkspaceCommandHelper,
+    commit: &Commit,
+    matcher: &dyn Matcher,
+) -> Result<(), CommandError> {
+    let cache = ResolutionCache::load(workspace_command.repo_path());
+    let tree = commit.tree()?;
+    let mut recorded_paths = vec![];
+    for (path, conflict) in collect_past_conflicts(workspace_command, commit, matcher)? {
+        let resolution = tree.path_value(&path)?;
+        if cache.record(&conflict, &resolution)? {
+            recorded_paths.push(path);
+        }
+    }
+    if recorded_paths.is_empty() {
+        return Err(user_error("No resolved conflicts found at this revision"));
+    }
+    if let Some(mut formatter) = ui.status_formatter() {
+        writeln!(formatter, "Recorded conflict resolutions in:")?;
+        for path in recorded_paths.iter().unique() {
+            writeln!(formatter, "  {}", workspace_command.format_file_path(path))?;
+        }
+    }
+    Ok(())
+}
+
+fn forget_resolutions(
+    ui: &mut Ui,
+    workspace_command: &WorkspaceCommandHelper,
+    commit: &Commit,
+    matcher: &dyn Matcher,
+) -> Result<(), CommandError> {
+    let cache = ResolutionCache::load(workspace_command.repo_path());
+    let mut conflicts = collect_past_conflicts(workspace_command, commit, matcher)?;
+    for (path, conflict) in commit.tree()?.conflicts() {
+        if matcher.matches(&path) {
+            conflicts.push((path, conflict?));
+        }
+    }
+    let mut num_forgotten = 0;
+    for (_path, conflict) in &conflicts {
+        if cache.forget(conflict)? {
+            num_forgotten += 1;
+        }
+    }
+    writeln!(
+        ui.status(),
+        "Forgot {num_forgotten} recorded conflict resolutions"
+    )?;
+    Ok(())
+}
+
+/// Machine-readable description of a conflicted path.
+#[derive(Serialize)]
+struct ConflictJson {
+    path: String,
+    sides: usize,
+    commit_id: String,
+    parent_commit_ids: Vec<String>,
+    removes: Vec<Option<ConflictTermJson>>,
+    adds: Vec<Option<ConflictTermJson>>,
+    side_commit_ids: Vec<Option<String>>,
+    binary: bool,
+    executable: bool,
+    symlink: bool,
+}
+
+#[derive(Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum ConflictTermJson {
+    File { id: String, executable: bool },
+    Symlink { id: String },
+    Tree { id: String },
+    GitSubmodule { id: String },
+    Conflict { id: String },
+}
+
+impl ConflictTermJson {
+    fn from_value(value: &TreeValue) -> Self {
+        match value {
+            TreeValue::File { id, executable } => ConflictTermJson::File {
+                id: id.hex(),
+                executable: *executable,
+            },
+            TreeValue::Symlink(id) => ConflictTermJson::Symlink { id: id.hex() },
+            TreeValue::Tree(id) => ConflictTermJson::Tree { id: id.hex() },
+            TreeValue::GitSubmodule(id) => ConflictTermJson::GitSubmodule { id: id.hex() },
+            TreeValue::Conflict(id) => ConflictTermJson::Conflict { id: id.hex() },
+        }
+    }
+}
+
+fn print_conflicts_json(
+    ui: &Ui,
+    workspace_command: &WorkspaceCommandHelper,
+    commit: &Commit,
+    conflicts: Vec<(RepoPathBuf, BackendResult<MergedTreeValue>)>,
+) -> Result<(), CommandError> {
+    let store = workspace_command.repo().store();
+    let mut stdout = ui.stdout();
+    for (path, conflict) in conflicts {
+        let conflict = conflict?.simplify();
+        let mut binary = false;
+        let mut executable = false;
+        let mut symlink = false;
+        for term in conflict.iter().flatten() {
+            match term {
+                TreeValue::File {
+                    id,
+                    executable: term_executable,
+                } => {
+                    binary |= is_binary_file(store, &path, id)?;
+                    executable |= *term_executable;
+                }
+                TreeValue::Symlink(_) => symlink = true,
+                _ => {}
+            }
+        }
+        let entry = ConflictJson {
+            path: path.as_internal_file_string().to_owned(),
+            sides: conflict.num_sides(),
+            commit_id: commit.id().hex(),
+            parent_commit_ids: commit.parent_ids().iter().map(|id| id.hex()).collect(),
+            removes: conflict
+                .removes()
+                .map(|term| term.as_ref().map(ConflictTermJson::from_value))
+                .collect(),
+            adds: conflict
+                .adds()
+                .map(|term| term.as_ref().map(ConflictTermJson::from_value))
+                .collect(),
+            side_commit_ids: conflicts::find_conflict_side_origins(commit, &path, &conflict)?
+                .iter()
+                .map(|origin| origin.as_ref().map(|commit| commit.id().hex()))
+                .collect(),
+            binary,
+            executable,
+            symlink,
+        };
+        serde_json::to_writer(&mut stdout, &entry).map_err(io::Error::from)?;
+        writeln!(stdout)?;
+    }
+    Ok(())
+}
+
+/// Returns true if the file content looks binary, using the same heuristic as
+/// the diff output.
+fn is_binary_file(store: &Store, path: &RepoPath, id: &FileId) -> Result<bool, CommandError> {
+    let mut reader = store.read_file(path, id)?;
+    Ok(diff_util::is_binary_file(&mut reader)?)
+}
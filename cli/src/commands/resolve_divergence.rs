@@ -0,0 +1,192 @@
+// Copyright 2024 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use clap_complete::ArgValueCandidates;
+use itertools::Itertools as _;
+use jj_lib::commit::Commit;
+use jj_lib::commit::CommitIteratorExt as _;
+use jj_lib::repo::Repo as _;
+use jj_lib::revset::RevsetExpression;
+use jj_lib::revset::RevsetIteratorExt as _;
+use tracing::instrument;
+
+use crate::cli_util::short_change_hash;
+use crate::cli_util::short_commit_hash;
+use crate::cli_util::CommandHelper;
+use crate::cli_util::RevisionArg;
+use crate::command_error::user_error;
+use crate::command_error::CommandError;
+use crate::complete;
+use crate::description_util::combine_messages;
+use crate::ui::Ui;
+
+/// Resolve a divergent change
+///
+/// A change is divergent when it has more than one visible commit. Without
+/// `--keep` or `--merge`, this lists the commits of the divergent change.
+///
+/// All the divergent commits are recorded as predecessors of the resulting
+/// commit, and their descendants are rebased onto it.
+#[derive(clap::Args, Clone, Debug)]
+pub(crate) struct ResolveDivergenceArgs {
+    /// The divergent change, or any of its commits
+    #[arg(
+        value_name = "CHANGE",
+        add = ArgValueCandidates::new(complete::mutable_revisions)
+    )]
+    change: RevisionArg,
+    /// Keep this commit and abandon the other commits of the change
+    #[arg(
+        long,
+        value_name = "REVSET",
+        conflicts_with = "merge",
+        add = ArgValueCandidates::new(complete::mutable_revisions)
+    )]
+    keep: Option<RevisionArg>,
+    /// Combine the commits of the change into one
+    ///
+    /// The changes made by the other commits are applied on top of the newest
+    /// commit. If the descriptions differ, an editor is opened to combine
+    /// them.
+    #[arg(long)]
+    merge: bool,
+}
+
+#[instrument(skip_all)]
+pub(crate) fn cmd_resolve_divergence(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    args: &ResolveDivergenceArgs,
+) -> Result<(), CommandError> {
+    let mut workspace_command = command.workspace_helper(ui)?;
+    let change_ids: Vec<_> = workspace_command
+        .parse_revset(ui, &args.change)?
+        .evaluate_to_commits()?
+        .map_ok(|commit| commit.change_id().clone())
+        .try_collect()?;
+    let change_id = match change_ids.iter().dedup().collect_vec()[..] {
+        [change_id] => change_id.clone(),
+        [] => return Err(user_error("Revset is empty")),
+        _ => return Err(user_error("Revset contains more than one change")),
+    };
+    let change_hash = short_change_hash(&change_id);
+    let repo = workspace_command.repo().clone();
+    let commit_ids = repo.resolve_change_id(&change_id).unwrap_or_default();
+    // Newest commit first
+    let commits: Vec<Commit> = RevsetExpression::commits(commit_ids)
+        .evaluate(repo.as_ref())?
+        .iter()
+        .commits(repo.store())
+        .try_collect()?;
+    if commits.len() < 2 {
+        return Err(user_error(format!("Change {change_hash} is not divergent")));
+    }
+
+    if args.keep.is_none() && !args.merge {
+        ui.request_pager();
+        let mut formatter = ui.stdout_formatter();
+        writeln!(
+            formatter,
+            "Change {change_hash} has {} divergent commits:",
+            commits.len()
+        )?;
+        for commit in &commits {
+            write!(formatter, "  ")?;
+            workspace_command.write_commit_summary(formatter.as_mut(), commit)?;
+            writeln!(formatter)?;
+        }
+        drop(formatter);
+        writeln!(
+            ui.hint_default(),
+            "Use --keep to keep one of the commits, or --merge to combine them."
+        )?;
+        return Ok(());
+    }
+
+    let kept_commit = if let Some(keep) = &args.keep {
+        let commit = workspace_command.resolve_single_rev(ui, keep)?;
+        if !commits.iter().any(|c| c.id() == commit.id()) {
+            return Err(user_error(format!(
+                "Commit {} is not a commit of change {change_hash}",
+                short_commit_hash(commit.id())
+            )));
+        }
+        commit
+    } else {
+        commits[0].clone()
+    };
+    let other_commits = commits
+        .iter()
+        .filter(|commit| commit.id() != kept_commit.id())
+        .collect_vec();
+    workspace_command.check_rewritable(commits.iter().ids())?;
+
+    let merged = if args.merge {
+        let mut tree = kept_commit.tree()?;
+        for commit in &other_commits {
+            tree = tree.merge(&commit.parent_tree(repo.as_ref())?, &commit.tree()?)?;
+        }
+        let description = if other_commits
+            .iter()
+            .all(|commit| commit.description() == kept_commit.description())
+        {
+            kept_commit.description().to_owned()
+        } else {
+            combine_messages(
+                workspace_command.repo_path(),
+                &other_commits,
+                &kept_commit,
+                command.settings(),
+            )?
+        };
+        Some((tree, description))
+    } else {
+        None
+    };
+
+    let mut tx = workspace_command.start_transaction();
+    let predecessors = [kept_commit.id().clone()]
+        .into_iter()
+        .chain(other_commits.iter().map(|commit| commit.id().clone()))
+        .collect_vec();
+    let mut commit_builder = tx
+        .repo_mut()
+        .rewrite_commit(command.settings(), &kept_commit)
+        .set_predecessors(predecessors);
+    if let Some((tree, description)) = merged {
+        commit_builder = commit_builder
+            .set_tree_id(tree.id())
+            .set_description(description);
+    }
+    let new_commit = commit_builder.write()?;
+    for commit in &other_commits {
+        tx.repo_mut()
+            .set_rewritten_commit(commit.id().clone(), new_commit.id().clone());
+    }
+    let num_rebased = tx.repo_mut().rebase_descendants(command.settings())?;
+
+    if let Some(mut formatter) = ui.status_formatter() {
+        write!(formatter, "Resolved divergent change {change_hash}: ")?;
+        tx.write_commit_summary(formatter.as_mut(), &new_commit)?;
+        writeln!(formatter)?;
+        if num_rebased > 0 {
+            writeln!(formatter, "Rebased {num_rebased} descendant commits")?;
+        }
+    }
+    tx.finish(
+        ui,
+        format!("resolve divergent change {}", change_id.reverse_hex()),
+    )?;
+    Ok(())
+}
@@ -14,19 +14,58 @@
 
 //! This file contains the internal implementation of `run`.
 
+use std::fs;
+use std::io::Write as _;
+use std::path::Path;
+use std::process::Command;
+use std::process::ExitStatus;
+use std::process::Output;
+use std::process::Stdio;
+use std::sync::mpsc::channel;
+use std::sync::mpsc::Sender;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::thread;
+
 use itertools::Itertools as _;
+use jj_lib::commit::Commit;
+use jj_lib::file_util::IoResultExt as _;
+use jj_lib::fsmonitor::FsmonitorSettings;
+use jj_lib::local_working_copy::LocalWorkingCopy;
+use jj_lib::matchers::EverythingMatcher;
+use jj_lib::object_id::ObjectId as _;
+use jj_lib::op_store::OperationId;
+use jj_lib::op_store::WorkspaceId;
+use jj_lib::repo::Repo as _;
+use jj_lib::store::Store;
+use jj_lib::working_copy::CheckoutOptions;
+use jj_lib::working_copy::SnapshotOptions;
+use jj_lib::working_copy::WorkingCopy;
+use tracing::instrument;
 
 use crate::cli_util::CommandHelper;
 use crate::cli_util::RevisionArg;
+use crate::cli_util::WorkspaceCommandHelper;
+use crate::command_error::internal_error_with_message;
 use crate::command_error::user_error;
+use crate::command_error::user_error_with_message;
 use crate::command_error::CommandError;
 use crate::ui::Ui;
 
-/// (**Stub**, does not work yet) Run a command across a set of revisions.
+/// Run a command across a set of revisions
+///
+/// Each revision is checked out into a scratch working copy under the `.jj`
+/// directory, where the command is run through the shell. Your own working
+/// copy is left untouched. The revisions are processed in topological order,
+/// spread over the given number of parallel jobs. Once all commands have
+/// finished, a summary of their exit statuses is printed.
 ///
+/// The scratch working copies are reused between invocations. Files ignored by
+/// `.gitignore` (e.g. build outputs) are kept around to allow incremental
+/// builds, so occasionally a `jj run --clean` is needed to clean up disk space.
 ///
-/// All recorded state will be persisted in the `.jj` directory, so occasionally
-/// a `jj run --clean` is needed to clean up disk space.
+/// The commit and change IDs of the revision are passed to the command in the
+/// `JJ_RUN_COMMIT_ID` and `JJ_RUN_CHANGE_ID` environment variables.
 ///
 /// # Example
 ///
@@ -39,7 +78,7 @@ use crate::ui::Ui;
 pub struct RunArgs {
     /// The command to run across all selected revisions.
     shell_command: String,
-    /// The revisions to change.
+    /// The revisions to run the command on.
     #[arg(long, short, default_value = "@", value_name = "REVSETS")]
     revisions: Vec<RevisionArg>,
     /// A no-op option to match the interface of `git rebase -x`.
@@ -48,23 +87,225 @@ pub struct RunArgs {
     /// How many processes should run in parallel, uses by default all cores.
     #[arg(long, short)]
     jobs: Option<usize>,
+    /// Remove the scratch working copies, including ignored files, before
+    /// running the command.
+    #[arg(long)]
+    clean: bool,
+}
+
+/// Outcome of running the command on a single revision.
+struct RunResult {
+    commit: Commit,
+    output: Output,
 }
 
+#[instrument(skip_all)]
 pub fn cmd_run(ui: &mut Ui, command: &CommandHelper, args: &RunArgs) -> Result<(), CommandError> {
     let workspace_command = command.workspace_helper(ui)?;
-    let _resolved_commits: Vec<_> = workspace_command
+    let mut resolved_commits: Vec<_> = workspace_command
         .parse_union_revsets(ui, &args.revisions)?
         .evaluate_to_commits()?
         .try_collect()?;
+    // Run on parents before their children.
+    resolved_commits.reverse();
     // Jobs are resolved in this order:
     // 1. Commandline argument iff > 0.
     // 2. the amount of cores available.
     // 3. a single job, if all of the above fails.
-    let _jobs = match args.jobs {
+    let jobs = match args.jobs {
         Some(0) | None => std::thread::available_parallelism().map(|t| t.into()).ok(),
         Some(jobs) => Some(jobs),
     }
     // Fallback to a single user-visible job.
-    .unwrap_or(1usize);
-    Err(user_error("This is a stub, do not use"))
+    .unwrap_or(1usize)
+    .min(resolved_commits.len());
+
+    let run_dir = workspace_command.workspace_root().join(".jj").join("run");
+    if args.clean && run_dir.exists() {
+        fs::remove_dir_all(&run_dir).context(&run_dir)?;
+    }
+
+    let mut snapshot_options =
+        workspace_command.snapshot_options_with_start_tracking_matcher(ui, &EverythingMatcher)?;
+    // The user's fsmonitor watches their own working copy, not ours. New files
+    // are only snapshotted so that the next checkout removes them.
+    snapshot_options.fsmonitor_settings = FsmonitorSettings::None;
+    snapshot_options.max_new_file_size = u64::MAX;
+    snapshot_options.max_new_file_size_overrides.clear();
+    let checkout_options = workspace_command.checkout_options();
+    let context = RunContext {
+        store: workspace_command.repo().store().clone(),
+        operation_id: workspace_command.repo().op_id().clone(),
+        shell_command: &args.shell_command,
+        snapshot_options: &snapshot_options,
+        checkout_options: &checkout_options,
+    };
+
+    let queue = Mutex::new(resolved_commits.into_iter());
+    let (results_tx, results_rx) = channel();
+    let mut results = vec![];
+    thread::scope(|s| -> Result<(), CommandError> {
+        let handles = (0..jobs)
+            .map(|slot| {
+                let slot_dir = run_dir.join(slot.to_string());
+                let (context, queue, results_tx) = (&context, &queue, results_tx.clone());
+                s.spawn(move || run_in_slot(context, &slot_dir, slot, queue, &results_tx))
+            })
+            .collect_vec();
+        drop(results_tx);
+        for result in results_rx {
+            report_output(ui, &workspace_command, &result)?;
+            results.push(result);
+        }
+        for handle in handles {
+            handle.join().unwrap()?;
+        }
+        Ok(())
+    })?;
+
+    let num_failed = results
+        .iter()
+        .filter(|result| !result.output.status.success())
+        .count();
+    if let Some(mut formatter) = ui.status_formatter() {
+        writeln!(
+            formatter,
+            "Ran `{}` on {} revisions:",
+            args.shell_command,
+            results.len()
+        )?;
+        for result in &results {
+            write!(
+                formatter,
+                "  {:<9}",
+                exit_status_label(result.output.status)
+            )?;
+            workspace_command.write_commit_summary(formatter.as_mut(), &result.commit)?;
+            writeln!(formatter)?;
+        }
+    }
+    if num_failed > 0 {
+        return Err(user_error(format!(
+            "The command failed on {num_failed} of {} revisions",
+            results.len()
+        )));
+    }
+    Ok(())
+}
+
+/// State shared by all jobs.
+struct RunContext<'a> {
+    store: Arc<Store>,
+    operation_id: OperationId,
+    shell_command: &'a str,
+    snapshot_options: &'a SnapshotOptions<'a>,
+    checkout_options: &'a CheckoutOptions<'a>,
+}
+
+/// Runs the command on revisions taken from the `queue` until it's empty,
+/// using the scratch working copy at `slot_dir`.
+fn run_in_slot(
+    context: &RunContext,
+    slot_dir: &Path,
+    slot: usize,
+    queue: &Mutex<impl Iterator<Item = Commit>>,
+    results_tx: &Sender<RunResult>,
+) -> Result<(), CommandError> {
+    let working_copy_path = slot_dir.join("working_copy");
+    let mut working_copy = load_scratch_working_copy(context, slot_dir, slot)?;
+    loop {
+        let Some(commit) = queue.lock().unwrap().next() else {
+            return Ok(());
+        };
+        let mut locked_wc = working_copy.start_mutation()?;
+        // Pick up changes left behind by the previous command, so they are
+        // reverted by the checkout. Ignored files are kept.
+        locked_wc.snapshot(context.snapshot_options)?;
+        locked_wc
+            .check_out(&commit, context.checkout_options)
+            .map_err(|err| {
+                internal_error_with_message("Failed to check out a scratch working copy", err)
+            })?;
+        working_copy = locked_wc.finish(context.operation_id.clone())?;
+        let output = run_shell_command(context.shell_command, &working_copy_path, &commit)?;
+        results_tx.send(RunResult { commit, output }).unwrap();
+    }
+}
+
+fn load_scratch_working_copy(
+    context: &RunContext,
+    slot_dir: &Path,
+    slot: usize,
+) -> Result<Box<dyn WorkingCopy>, CommandError> {
+    let working_copy_path = slot_dir.join("working_copy");
+    let state_path = slot_dir.join("state");
+    if state_path.join("checkout").exists() {
+        return Ok(Box::new(LocalWorkingCopy::load(
+            context.store.clone(),
+            working_copy_path,
+            state_path,
+        )));
+    }
+    for dir in [&working_copy_path, &state_path] {
+        fs::create_dir_all(dir).context(dir)?;
+    }
+    let working_copy = LocalWorkingCopy::init(
+        context.store.clone(),
+        working_copy_path,
+        state_path,
+        context.operation_id.clone(),
+        WorkspaceId::new(format!("run-{slot}")),
+    )?;
+    Ok(Box::new(working_copy))
+}
+
+fn run_shell_command(
+    shell_command: &str,
+    working_copy_path: &Path,
+    commit: &Commit,
+) -> Result<Output, CommandError> {
+    let mut cmd = if cfg!(windows) {
+        let mut cmd = Command::new("cmd");
+        cmd.arg("/C");
+        cmd
+    } else {
+        let mut cmd = Command::new("sh");
+        cmd.arg("-c");
+        cmd
+    };
+    cmd.arg(shell_command)
+        .current_dir(working_copy_path)
+        .env("JJ_RUN_COMMIT_ID", commit.id().hex())
+        .env("JJ_RUN_CHANGE_ID", commit.change_id().hex())
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+    tracing::info!(?cmd, "running command");
+    cmd.output().map_err(|err| {
+        user_error_with_message(format!("Failed to run command '{shell_command}'"), err)
+    })
+}
+
+/// Prints the output of the command as soon as it has finished.
+fn report_output(
+    ui: &Ui,
+    workspace_command: &WorkspaceCommandHelper,
+    result: &RunResult,
+) -> Result<(), CommandError> {
+    if let Some(mut formatter) = ui.status_formatter() {
+        write!(formatter, "Finished ")?;
+        workspace_command.write_commit_summary(formatter.as_mut(), &result.commit)?;
+        writeln!(formatter, " ({})", exit_status_label(result.output.status))?;
+    }
+    ui.stdout().write_all(&result.output.stdout)?;
+    ui.stderr().write_all(&result.output.stderr)?;
+    Ok(())
+}
+
+fn exit_status_label(status: ExitStatus) -> String {
+    match status.code() {
+        _ if status.success() => "ok".to_owned(),
+        Some(code) => format!("exit {code}"),
+        None => "killed".to_owned(),
+    }
 }
@@ -16,6 +16,7 @@ use clap_complete::ArgValueCandidates;
 use jj_lib::matchers::EverythingMatcher;
 use tracing::instrument;
 
+use super::log::signature_problem_template;
 use crate::cli_util::CommandHelper;
 use crate::cli_util::RevisionArg;
 use crate::command_error::CommandError;
@@ -43,6 +44,15 @@ pub(crate) struct ShowArgs {
     template: Option<String>,
     #[command(flatten)]
     format: DiffFormatArgs,
+    /// Do not show the patch
+    ///
+    /// This is useful with templates that render the changes themselves, such
+    /// as `builtin_log_json`.
+    #[arg(long, conflicts_with_all = ["short-format", "long-format"])]
+    no_patch: bool,
+    /// Flag the revision if its signature is bad or can't be verified
+    #[arg(long)]
+    verify_sigs: bool,
 }
 
 #[instrument(skip_all)]
@@ -51,18 +61,25 @@ pub(crate) fn cmd_show(
     command: &CommandHelper,
     args: &ShowArgs,
 ) -> Result<(), CommandError> {
-    let workspace_command = command.workspace_helper(ui)?;
+    let workspace_command = command.workspace_helper_read_only(ui)?;
     let commit = workspace_command.resolve_single_rev(ui, &args.revision)?;
     let template_string = match &args.template {
         Some(value) => value.to_string(),
         None => command.settings().get_string("templates.show")?,
     };
+    let template_string = if args.verify_sigs {
+        signature_problem_template(&template_string)
+    } else {
+        template_string
+    };
     let template = workspace_command.parse_commit_template(ui, &template_string)?;
     let diff_renderer = workspace_command.diff_renderer_for(&args.format)?;
     ui.request_pager();
     let mut formatter = ui.stdout_formatter();
     let formatter = formatter.as_mut();
     template.format(&commit, formatter)?;
-    diff_renderer.show_patch(ui, formatter, &commit, &EverythingMatcher, ui.term_width())?;
+    if !args.no_patch {
+        diff_renderer.show_patch(ui, formatter, &commit, &EverythingMatcher, ui.term_width())?;
+    }
     Ok(())
 }
@@ -0,0 +1,110 @@
+// Copyright 2024 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashSet;
+
+use clap_complete::ArgValueCandidates;
+use itertools::Itertools as _;
+use jj_lib::commit::Commit;
+use jj_lib::commit::CommitIteratorExt as _;
+use jj_lib::signing::SignBehavior;
+use tracing::instrument;
+
+use crate::cli_util::CommandHelper;
+use crate::cli_util::RevisionArg;
+use crate::command_error::user_error_with_hint;
+use crate::command_error::CommandError;
+use crate::complete;
+use crate::ui::Ui;
+
+/// Cryptographically sign revisions
+///
+/// The revisions are rewritten with a signature created by the configured
+/// `signing.backend`, even if they were authored by someone else or are already
+/// signed. Descendants are rebased onto the signed revisions.
+#[derive(clap::Args, Clone, Debug)]
+pub(crate) struct SignArgs {
+    /// The revision(s) to sign
+    #[arg(
+        long,
+        short,
+        default_value = "@",
+        value_name = "REVSETS",
+        add = ArgValueCandidates::new(complete::mutable_revisions)
+    )]
+    revisions: Vec<RevisionArg>,
+    /// The key to sign with, overriding `signing.key`
+    #[arg(long)]
+    key: Option<String>,
+}
+
+#[instrument(skip_all)]
+pub(crate) fn cmd_sign(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    args: &SignArgs,
+) -> Result<(), CommandError> {
+    if command.settings().signing_backend()?.is_none() {
+        return Err(user_error_with_hint(
+            "No signing backend is configured",
+            "Set `signing.backend` to sign commits. See \
+             https://jj-vcs.github.io/jj/latest/config/#commit-signing",
+        ));
+    }
+    let mut workspace_command = command.workspace_helper(ui)?;
+    let to_sign: Vec<Commit> = workspace_command
+        .parse_union_revsets(ui, &args.revisions)?
+        .evaluate_to_commits()?
+        .try_collect()?;
+    workspace_command.check_rewritable(to_sign.iter().ids())?;
+    let to_sign_ids: HashSet<_> = to_sign.iter().ids().cloned().collect();
+
+    let mut tx = workspace_command.start_transaction();
+    let mut signed_commits = vec![];
+    let mut num_reparented = 0;
+    tx.repo_mut().transform_descendants(
+        command.settings(),
+        to_sign.iter().ids().cloned().collect(),
+        |rewriter| {
+            let is_target = to_sign_ids.contains(rewriter.old_commit().id());
+            let mut commit_builder = rewriter.reparent(command.settings());
+            if is_target {
+                commit_builder = commit_builder.set_sign_behavior(SignBehavior::Force);
+                if let Some(key) = &args.key {
+                    commit_builder = commit_builder.set_sign_key(Some(key.clone()));
+                }
+                signed_commits.push(commit_builder.write()?);
+            } else {
+                commit_builder.write()?;
+                num_reparented += 1;
+            }
+            Ok(())
+        },
+    )?;
+
+    if let Some(mut formatter) = ui.status_formatter() {
+        let template = tx.commit_summary_template();
+        writeln!(formatter, "Signed {} commits:", signed_commits.len())?;
+        for commit in &signed_commits {
+            write!(formatter, "  ")?;
+            template.format(commit, formatter.as_mut())?;
+            writeln!(formatter)?;
+        }
+        if num_reparented > 0 {
+            writeln!(formatter, "Rebased {num_reparented} descendant commits")?;
+        }
+    }
+    tx.finish(ui, format!("sign {} commits", signed_commits.len()))?;
+    Ok(())
+}
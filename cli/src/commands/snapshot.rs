@@ -0,0 +1,38 @@
+// Copyright 2024 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use tracing::instrument;
+
+use crate::cli_util::CommandHelper;
+use crate::command_error::CommandError;
+use crate::ui::Ui;
+
+/// Record the current contents of the working copy
+///
+/// Most commands do this automatically. It's useful if `snapshot.auto` is
+/// disabled, in which case read-only commands such as `jj log`, `jj show`, and
+/// `jj diff` don't snapshot the working copy.
+#[derive(clap::Args, Clone, Debug)]
+pub(crate) struct SnapshotArgs {}
+
+#[instrument(skip_all)]
+pub(crate) fn cmd_snapshot(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    _args: &SnapshotArgs,
+) -> Result<(), CommandError> {
+    let workspace_command = command.workspace_helper(ui)?;
+    workspace_command.check_working_copy_writable()?;
+    Ok(())
+}
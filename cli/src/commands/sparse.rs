@@ -19,16 +19,18 @@ use std::path::Path;
 
 use clap::Subcommand;
 use itertools::Itertools;
-use jj_lib::repo_path::RepoPathBuf;
+use jj_lib::config::ConfigGetResultExt as _;
 use jj_lib::settings::UserSettings;
+use jj_lib::sparse::SparsePattern;
 use tracing::instrument;
 
 use crate::cli_util::edit_temp_file;
 use crate::cli_util::print_checkout_stats;
 use crate::cli_util::CommandHelper;
 use crate::cli_util::WorkspaceCommandHelper;
-use crate::command_error::internal_error;
+use crate::command_error::config_error_with_message;
 use crate::command_error::internal_error_with_message;
+use crate::command_error::user_error_with_hint;
 use crate::command_error::user_error_with_message;
 use crate::command_error::CommandError;
 use crate::ui::Ui;
@@ -37,6 +39,7 @@ use crate::ui::Ui;
 /// copy
 #[derive(Subcommand, Clone, Debug)]
 pub(crate) enum SparseCommand {
+    ApplyProfile(SparseApplyProfileArgs),
     Edit(SparseEditArgs),
     List(SparseListArgs),
     Reset(SparseResetArgs),
@@ -48,6 +51,12 @@ pub(crate) enum SparseCommand {
 /// By default, a newly cloned or initialized repo will have have a pattern
 /// matching all files from the repo root. That pattern is rendered as `.` (a
 /// single period).
+///
+/// Patterns are workspace-relative paths, which include the file or all files
+/// under the directory. A `glob:` prefix makes the pattern match files with a
+/// glob instead, e.g. `glob:docs/*.md`. A leading `!` excludes the matching
+/// files even if they are matched by another pattern, e.g.
+/// `!glob:lib/testdata/**`.
 #[derive(clap::Args, Clone, Debug)]
 pub(crate) struct SparseListArgs {}
 
@@ -56,28 +65,42 @@ pub(crate) struct SparseListArgs {}
 /// For example, if all you need is the `README.md` and the `lib/`
 /// directory, use `jj sparse set --clear --add README.md --add lib`.
 /// If you no longer need the `lib` directory, use `jj sparse set --remove lib`.
+/// To keep `lib/` but leave out its test data, use `jj sparse set --add
+/// '!glob:lib/testdata/**'`. See `jj sparse list --help` for the pattern
+/// syntax.
 #[derive(clap::Args, Clone, Debug)]
 pub(crate) struct SparseSetArgs {
     /// Patterns to add to the working copy
     #[arg(
         long,
         value_hint = clap::ValueHint::AnyPath,
-        value_parser = |s: &str| RepoPathBuf::from_relative_path(s),
+        value_parser = |s: &str| SparsePattern::parse(s),
     )]
-    add: Vec<RepoPathBuf>,
+    add: Vec<SparsePattern>,
     /// Patterns to remove from the working copy
     #[arg(
         long,
         conflicts_with = "clear",
         value_hint = clap::ValueHint::AnyPath,
-        value_parser = |s: &str| RepoPathBuf::from_relative_path(s),
+        value_parser = |s: &str| SparsePattern::parse(s),
     )]
-    remove: Vec<RepoPathBuf>,
+    remove: Vec<SparsePattern>,
     /// Include no files in the working copy (combine with --add)
     #[arg(long)]
     clear: bool,
 }
 
+/// Replace the patterns with the ones of a named sparse profile
+///
+/// Profiles are lists of patterns defined in the `sparse.profiles` config
+/// table, e.g. `sparse.profiles.backend = ["lib", "server"]`. Defining them in
+/// the repo config lets everyone working on the repo share the same profiles.
+#[derive(clap::Args, Clone, Debug)]
+pub(crate) struct SparseApplyProfileArgs {
+    /// Name of the profile
+    name: String,
+}
+
 /// Reset the patterns to include all files in the working copy
 #[derive(clap::Args, Clone, Debug)]
 pub(crate) struct SparseResetArgs {}
@@ -93,6 +116,7 @@ pub(crate) fn cmd_sparse(
     subcommand: &SparseCommand,
 ) -> Result<(), CommandError> {
     match subcommand {
+        SparseCommand::ApplyProfile(args) => cmd_sparse_apply_profile(ui, command, args),
         SparseCommand::Edit(args) => cmd_sparse_edit(ui, command, args),
         SparseCommand::List(args) => cmd_sparse_list(ui, command, args),
         SparseCommand::Reset(args) => cmd_sparse_reset(ui, command, args),
@@ -107,12 +131,8 @@ fn cmd_sparse_list(
     _args: &SparseListArgs,
 ) -> Result<(), CommandError> {
     let workspace_command = command.workspace_helper(ui)?;
-    for path in workspace_command.working_copy().sparse_patterns()? {
-        writeln!(
-            ui.stdout(),
-            "{}",
-            path.to_fs_path_unchecked(Path::new("")).display()
-        )?;
+    for pattern in workspace_command.working_copy().sparse_patterns()? {
+        writeln!(ui.stdout(), "{pattern}")?;
     }
     Ok(())
 }
@@ -128,12 +148,12 @@ fn cmd_sparse_set(
         let mut new_patterns = HashSet::new();
         if !args.clear {
             new_patterns.extend(old_patterns.iter().cloned());
-            for path in &args.remove {
-                new_patterns.remove(path);
+            for pattern in &args.remove {
+                new_patterns.remove(pattern);
             }
         }
-        for path in &args.add {
-            new_patterns.insert(path.to_owned());
+        for pattern in &args.add {
+            new_patterns.insert(pattern.clone());
         }
         Ok(new_patterns.into_iter().sorted_unstable().collect())
     })
@@ -147,7 +167,20 @@ fn cmd_sparse_reset(
 ) -> Result<(), CommandError> {
     let mut workspace_command = command.workspace_helper(ui)?;
     update_sparse_patterns_with(ui, &mut workspace_command, |_ui, _old_patterns| {
-        Ok(vec![RepoPathBuf::root()])
+        Ok(vec![SparsePattern::root()])
+    })
+}
+
+#[instrument(skip_all)]
+fn cmd_sparse_apply_profile(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    args: &SparseApplyProfileArgs,
+) -> Result<(), CommandError> {
+    let mut workspace_command = command.workspace_helper(ui)?;
+    let new_patterns = get_sparse_profile(command.settings(), &args.name)?;
+    update_sparse_patterns_with(ui, &mut workspace_command, |_ui, _old_patterns| {
+        Ok(new_patterns)
     })
 }
 
@@ -169,20 +202,12 @@ fn cmd_sparse_edit(
 
 fn edit_sparse(
     repo_path: &Path,
-    sparse: &[RepoPathBuf],
+    sparse: &[SparsePattern],
     settings: &UserSettings,
-) -> Result<Vec<RepoPathBuf>, CommandError> {
+) -> Result<Vec<SparsePattern>, CommandError> {
     let mut content = String::new();
-    for sparse_path in sparse {
-        // Invalid path shouldn't block editing. Edited paths will be validated.
-        let workspace_relative_sparse_path = sparse_path.to_fs_path_unchecked(Path::new(""));
-        let path_string = workspace_relative_sparse_path.to_str().ok_or_else(|| {
-            internal_error(format!(
-                "Stored sparse path is not valid utf-8: {}",
-                workspace_relative_sparse_path.display()
-            ))
-        })?;
-        writeln!(&mut content, "{path_string}").unwrap();
+    for pattern in sparse {
+        writeln!(&mut content, "{pattern}").unwrap();
     }
 
     let content = edit_temp_file(
@@ -199,17 +224,54 @@ fn edit_sparse(
         .map(|line| line.trim())
         .filter(|line| !line.is_empty())
         .map(|line| {
-            RepoPathBuf::from_relative_path(line).map_err(|err| {
+            SparsePattern::parse(line).map_err(|err| {
                 user_error_with_message(format!("Failed to parse sparse pattern: {line}"), err)
             })
         })
         .try_collect()
 }
 
+/// Looks up the patterns of the profile `name` in the `sparse.profiles` config
+/// table.
+pub(crate) fn get_sparse_profile(
+    settings: &UserSettings,
+    name: &str,
+) -> Result<Vec<SparsePattern>, CommandError> {
+    let Some(patterns) = settings
+        .get::<Vec<String>>(["sparse", "profiles", name])
+        .optional()?
+    else {
+        let names = settings.table_keys("sparse.profiles").sorted().join(", ");
+        let hint = if names.is_empty() {
+            "Profiles can be defined in the `sparse.profiles` config table".to_owned()
+        } else {
+            format!("Defined profiles: {names}")
+        };
+        return Err(user_error_with_hint(
+            format!("Sparse profile '{name}' is not defined"),
+            hint,
+        ));
+    };
+    let mut patterns: Vec<SparsePattern> = patterns
+        .iter()
+        .map(|pattern| {
+            SparsePattern::parse(pattern).map_err(|err| {
+                config_error_with_message(
+                    format!("Invalid pattern in sparse profile '{name}': {pattern}"),
+                    err,
+                )
+            })
+        })
+        .try_collect()?;
+    patterns.sort_unstable();
+    patterns.dedup();
+    Ok(patterns)
+}
+
 fn update_sparse_patterns_with(
     ui: &mut Ui,
     workspace_command: &mut WorkspaceCommandHelper,
-    f: impl FnOnce(&mut Ui, &[RepoPathBuf]) -> Result<Vec<RepoPathBuf>, CommandError>,
+    f: impl FnOnce(&mut Ui, &[SparsePattern]) -> Result<Vec<SparsePattern>, CommandError>,
 ) -> Result<(), CommandError> {
     let checkout_options = workspace_command.checkout_options();
     let (mut locked_ws, wc_commit) = workspace_command.start_working_copy_mutation()?;
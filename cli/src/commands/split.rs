@@ -11,19 +11,35 @@
 // WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
 // See the License for the specific language governing permissions and
 // limitations under the License.
+use std::collections::BTreeMap;
 use std::io::Write;
 
+use clap::ArgGroup;
 use clap_complete::ArgValueCandidates;
 use clap_complete::ArgValueCompleter;
+use futures::StreamExt as _;
+use futures::TryStreamExt as _;
+use itertools::Itertools as _;
+use jj_lib::backend::CommitId;
+use jj_lib::commit::Commit;
+use jj_lib::matchers::EverythingMatcher;
+use jj_lib::merged_tree::MergedTreeBuilder;
+use jj_lib::merged_tree::TreeDiffEntry;
 use jj_lib::object_id::ObjectId;
 use jj_lib::repo::Repo;
+use jj_lib::repo_path::RepoPathBuf;
+use jj_lib::repo_path::RepoPathComponentBuf;
+use pollster::FutureExt as _;
 use tracing::instrument;
 
 use crate::cli_util::CommandHelper;
 use crate::cli_util::RevisionArg;
+use crate::cli_util::WorkspaceCommandHelper;
 use crate::command_error::user_error_with_hint;
 use crate::command_error::CommandError;
 use crate::complete;
+use crate::description_util::check_description_placeholders;
+use crate::description_util::default_description;
 use crate::description_util::description_template;
 use crate::description_util::edit_description;
 use crate::ui::Ui;
@@ -43,9 +59,15 @@ use crate::ui::Ui;
 /// description, the second part will not get a description, and you will be
 /// asked for a description only for the first part.
 ///
+/// With `--auto`, the revision is split into several revisions without an
+/// editor, one per group of changed files. Use `--by-directory` to group files
+/// by their top-level directory, and `--max-files` to limit the number of files
+/// in each revision. The descriptions of the new revisions are numbered.
+///
 /// Splitting an empty commit is not supported because the same effect can be
 /// achieved with `jj new`.
 #[derive(clap::Args, Clone, Debug)]
+#[command(group(ArgGroup::new("auto_mode").args(&["max_files", "by_directory"]).multiple(true)))]
 pub(crate) struct SplitArgs {
     /// Interactively choose which parts to split
     ///
@@ -55,6 +77,40 @@ pub(crate) struct SplitArgs {
     /// Specify diff editor to be used (implies --interactive)
     #[arg(long, value_name = "NAME")]
     tool: Option<String>,
+    /// Choose which parts to put in the first commit without a diff editor
+    ///
+    /// `file:PATH` selects all changes to the file. `hunks:PATH:1,3-4` selects
+    /// changed lines of the file by their hunk numbers, as shown by `jj diff
+    /// --git --context=0`. `-` reads a Git-style diff from stdin and selects
+    /// the changes it describes. Can be repeated.
+    #[arg(
+        long,
+        value_name = "SPEC",
+        conflicts_with_all = ["interactive", "tool", "paths"]
+    )]
+    select: Vec<String>,
+    /// Split into several revisions by grouping changed files, without a diff
+    /// editor
+    ///
+    /// Requires `--by-directory` and/or `--max-files`.
+    #[arg(
+        long,
+        requires = "auto_mode",
+        conflicts_with_all = ["interactive", "tool", "select", "paths"]
+    )]
+    auto: bool,
+    /// Put the files in each top-level directory in a separate revision (with
+    /// --auto)
+    #[arg(long, requires = "auto")]
+    by_directory: bool,
+    /// Put at most this many files in each revision (with --auto)
+    #[arg(
+        long,
+        value_name = "N",
+        requires = "auto",
+        value_parser = clap::value_parser!(u32).range(1..),
+    )]
+    max_files: Option<u32>,
     /// The revision to split
     #[arg(
         long, short,
@@ -93,14 +149,21 @@ pub(crate) fn cmd_split(
     }
 
     workspace_command.check_rewritable([commit.id()])?;
+    if args.auto {
+        return split_automatically(ui, command, workspace_command, &commit, args);
+    }
     let matcher = workspace_command
         .parse_file_patterns(ui, &args.paths)?
         .to_matcher();
-    let diff_selector = workspace_command.diff_selector(
-        ui,
-        args.tool.as_deref(),
-        args.interactive || args.paths.is_empty(),
-    )?;
+    let diff_selector = if args.select.is_empty() {
+        workspace_command.diff_selector(
+            ui,
+            args.tool.as_deref(),
+            args.interactive || args.paths.is_empty(),
+        )?
+    } else {
+        workspace_command.diff_selector_from_specs(&args.select)?
+    };
     let mut tx = workspace_command.start_transaction();
     let end_tree = commit.tree()?;
     let base_tree = commit.parent_tree(tx.repo())?;
@@ -144,8 +207,10 @@ The remainder will be in the second commit.
             .detach();
         commit_builder.set_tree_id(selected_tree_id);
         if commit_builder.description().is_empty() {
-            commit_builder
-                .set_description(command.settings().get_string("ui.default-description")?);
+            commit_builder.set_description(default_description(
+                tx.base_workspace_helper().workspace_root(),
+                command.settings(),
+            )?);
         }
         let temp_commit = commit_builder.write_hidden()?;
         let template = description_template(
@@ -159,6 +224,11 @@ The remainder will be in the second commit.
             &template,
             command.settings(),
         )?;
+        check_description_placeholders(
+            tx.base_workspace_helper().workspace_root(),
+            command.settings(),
+            &description,
+        )?;
         commit_builder.set_description(description);
         commit_builder.write(tx.repo_mut())?
     };
@@ -201,11 +271,17 @@ The remainder will be in the second commit.
                 "Enter a description for the second commit.",
                 &temp_commit,
             )?;
-            edit_description(
+            let description = edit_description(
                 tx.base_workspace_helper().repo_path(),
                 &template,
                 command.settings(),
-            )?
+            )?;
+            check_description_placeholders(
+                tx.base_workspace_helper().workspace_root(),
+                command.settings(),
+                &description,
+            )?;
+            description
         };
         commit_builder.set_description(description);
         commit_builder.write(tx.repo_mut())?
@@ -248,3 +324,151 @@ The remainder will be in the second commit.
     tx.finish(ui, format!("split commit {}", commit.id().hex()))?;
     Ok(())
 }
+
+/// Splits the `commit` into a series of commits, one per group of changed
+/// files.
+fn split_automatically(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    mut workspace_command: WorkspaceCommandHelper,
+    commit: &Commit,
+    args: &SplitArgs,
+) -> Result<(), CommandError> {
+    let mut tx = workspace_command.start_transaction();
+    let base_tree = commit.parent_tree(tx.repo())?;
+    let end_tree = commit.tree()?;
+    let changes: Vec<_> = base_tree
+        .diff_stream(&end_tree, &EverythingMatcher)
+        .map(|TreeDiffEntry { path, values }| values.map(|(_before, after)| (path, after)))
+        .try_collect()
+        .block_on()?;
+    let groups = group_changed_paths(
+        changes,
+        args.by_directory,
+        args.max_files.map(|n| n as usize),
+    );
+    if groups.len() < 2 {
+        writeln!(
+            ui.warning_default(),
+            "All changes fit in a single part, so the commit was not split"
+        )?;
+        return Ok(());
+    }
+
+    let num_parts = groups.len();
+    let mut parts = Vec::with_capacity(num_parts);
+    let mut parent_ids = commit.parent_ids().to_vec();
+    let mut prev_tree_id = base_tree.id();
+    for (index, group) in groups.into_iter().enumerate() {
+        let mut tree_builder = MergedTreeBuilder::new(prev_tree_id.clone());
+        for (path, value) in group {
+            tree_builder.set_or_remove(path, value);
+        }
+        let tree_id = tree_builder.write_tree(tx.repo().store())?;
+        let mut commit_builder = tx
+            .repo_mut()
+            .rewrite_commit(command.settings(), commit)
+            .detach();
+        commit_builder
+            .set_parents(parent_ids.clone())
+            .set_tree_id(tree_id.clone())
+            .set_description(numbered_description(
+                commit.description(),
+                index + 1,
+                num_parts,
+            ));
+        if index > 0 {
+            // Only the first part keeps the change id so that the commit being
+            // split doesn't become divergent.
+            commit_builder.generate_new_change_id();
+        }
+        let part = commit_builder.write(tx.repo_mut())?;
+        if !args.parallel {
+            parent_ids = vec![part.id().clone()];
+            prev_tree_id = tree_id;
+        }
+        parts.push(part);
+    }
+
+    // Like the two-way split, the commit being split is rewritten to the last
+    // part, so @ and bookmarks move there.
+    let last_part = parts.last().unwrap();
+    tx.repo_mut()
+        .set_rewritten_commit(commit.id().clone(), last_part.id().clone());
+    let part_ids: Vec<CommitId> = parts.iter().map(|part| part.id().clone()).collect();
+    let mut num_rebased = 0;
+    tx.repo_mut().transform_descendants(
+        command.settings(),
+        vec![commit.id().clone()],
+        |mut rewriter| {
+            num_rebased += 1;
+            if args.parallel {
+                rewriter.replace_parent(last_part.id(), &part_ids);
+            }
+            rewriter.rebase(command.settings())?.write()?;
+            Ok(())
+        },
+    )?;
+
+    if let Some(mut formatter) = ui.status_formatter() {
+        if num_rebased > 0 {
+            writeln!(formatter, "Rebased {num_rebased} descendant commits")?;
+        }
+        writeln!(formatter, "Split into {num_parts} commits:")?;
+        for part in &parts {
+            write!(formatter, "  ")?;
+            tx.write_commit_summary(formatter.as_mut(), part)?;
+            writeln!(formatter)?;
+        }
+    }
+    tx.finish(
+        ui,
+        format!(
+            "split commit {} into {num_parts} commits",
+            commit.id().hex()
+        ),
+    )?;
+    Ok(())
+}
+
+/// Groups the changed paths by top-level directory and/or by count, keeping
+/// the paths in order. Files at the root directory form their own group.
+fn group_changed_paths<T>(
+    changes: Vec<(RepoPathBuf, T)>,
+    by_directory: bool,
+    max_files: Option<usize>,
+) -> Vec<Vec<(RepoPathBuf, T)>> {
+    let groups = if by_directory {
+        let mut dirs: BTreeMap<Option<RepoPathComponentBuf>, Vec<_>> = BTreeMap::new();
+        for (path, value) in changes {
+            let mut components = path.components();
+            let first = components.next();
+            let dir = first
+                .filter(|_| components.next().is_some())
+                .map(|name| name.to_owned());
+            dirs.entry(dir).or_default().push((path, value));
+        }
+        dirs.into_values().collect_vec()
+    } else {
+        vec![changes]
+    };
+    let Some(max_files) = max_files else {
+        return groups;
+    };
+    groups
+        .into_iter()
+        .flat_map(|group| {
+            let chunks = group.into_iter().chunks(max_files);
+            chunks.into_iter().map(Iterator::collect).collect_vec()
+        })
+        .collect()
+}
+
+/// Appends `(index/count)` to the subject line of the `description`.
+fn numbered_description(description: &str, index: usize, count: usize) -> String {
+    if description.is_empty() {
+        return String::new();
+    }
+    let (subject, rest) = description.split_once('\n').unwrap_or((description, ""));
+    format!("{subject} ({index}/{count})\n{rest}")
+}
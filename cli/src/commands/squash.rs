@@ -97,6 +97,18 @@ pub(crate) struct SquashArgs {
     /// Specify diff editor to be used (implies --interactive)
     #[arg(long, value_name = "NAME")]
     tool: Option<String>,
+    /// Choose which parts to squash without a diff editor
+    ///
+    /// `file:PATH` selects all changes to the file. `hunks:PATH:1,3-4` selects
+    /// changed lines of the file by their hunk numbers, as shown by `jj diff
+    /// --git --context=0`. `-` reads a Git-style diff from stdin and selects
+    /// the changes it describes. Can be repeated.
+    #[arg(
+        long,
+        value_name = "SPEC",
+        conflicts_with_all = ["interactive", "tool", "paths"]
+    )]
+    select: Vec<String>,
     /// Move only changes to these paths (instead of all paths)
     #[arg(
         conflicts_with_all = ["interactive", "tool"],
@@ -154,8 +166,11 @@ pub(crate) fn cmd_squash(
     let matcher = workspace_command
         .parse_file_patterns(ui, &args.paths)?
         .to_matcher();
-    let diff_selector =
-        workspace_command.diff_selector(ui, args.tool.as_deref(), args.interactive)?;
+    let diff_selector = if args.select.is_empty() {
+        workspace_command.diff_selector(ui, args.tool.as_deref(), args.interactive)?
+    } else {
+        workspace_command.diff_selector_from_specs(&args.select)?
+    };
     let mut tx = workspace_command.start_transaction();
     let tx_description = format!("squash commits into {}", destination.id().hex());
     move_diff(
@@ -171,6 +186,7 @@ pub(crate) fn cmd_squash(
         &args.paths,
         args.keep_emptied,
     )?;
+    tx.reapply_recorded_resolutions(ui)?;
     tx.finish(ui, tx_description)?;
     Ok(())
 }
@@ -268,7 +284,8 @@ from the source will be moved into the destination.
         },
     )? {
         rewrite::SquashResult::NoChanges => {
-            if diff_selector.is_interactive() {
+            if diff_selector.is_interactive() || matches!(diff_selector, DiffSelector::Selection(_))
+            {
                 return Err(user_error("No changes selected"));
             }
 
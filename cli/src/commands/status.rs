@@ -13,7 +13,9 @@
 // limitations under the License.
 
 use itertools::Itertools;
+use jj_lib::backend::BackendResult;
 use jj_lib::copies::CopyRecords;
+use jj_lib::merged_tree::MergedTree;
 use jj_lib::repo::Repo;
 use jj_lib::revset::RevsetExpression;
 use jj_lib::revset::RevsetFilterPredicate;
@@ -87,13 +89,33 @@ pub(crate) fn cmd_status(
 
         // TODO: Conflicts should also be filtered by the `matcher`. See the related
         // TODO on `MergedTree::conflicts()`.
-        let conflicts = wc_commit.tree()?.conflicts().collect_vec();
+        let conflicts = tree.conflicts().collect_vec();
         if !conflicts.is_empty() {
             writeln!(
                 formatter.labeled("conflict"),
                 "There are unresolved conflicts at these paths:"
             )?;
-            print_conflicted_paths(conflicts, formatter, &workspace_command)?;
+            print_conflicted_paths(conflicts, formatter, &workspace_command, Some(wc_commit))?;
+            let progress = ConflictProgress::compare(&parent_tree, &tree)?;
+            if progress.resolved > 0 || progress.edited > 0 {
+                write!(
+                    formatter.labeled("hint"),
+                    "{} of {} conflicts from the parent commit appear resolved",
+                    progress.resolved,
+                    progress.total
+                )?;
+                if progress.edited > 0 {
+                    write!(
+                        formatter.labeled("hint"),
+                        ", {} partially edited",
+                        progress.edited
+                    )?;
+                }
+                writeln!(
+                    formatter.labeled("hint"),
+                    ". Run `jj status` again after finishing."
+                )?;
+            }
         }
 
         let template = workspace_command.commit_summary_template();
@@ -188,3 +210,32 @@ pub(crate) fn cmd_status(
 
     Ok(())
 }
+
+/// How far the conflicts inherited from the parent commit have been resolved
+/// in the working copy.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+struct ConflictProgress {
+    /// Number of conflicted paths in the parent tree.
+    total: usize,
+    /// Paths that are no longer conflicted in the working copy.
+    resolved: usize,
+    /// Paths that are still conflicted, but whose conflict has been edited.
+    edited: usize,
+}
+
+impl ConflictProgress {
+    fn compare(parent_tree: &MergedTree, tree: &MergedTree) -> BackendResult<Self> {
+        let mut progress = ConflictProgress::default();
+        for (path, parent_value) in parent_tree.conflicts() {
+            let parent_value = parent_value?;
+            let value = tree.path_value(&path)?;
+            progress.total += 1;
+            if value.is_resolved() {
+                progress.resolved += 1;
+            } else if value != parent_value {
+                progress.edited += 1;
+            }
+        }
+        Ok(progress)
+    }
+}
@@ -0,0 +1,117 @@
+// Copyright 2024 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use jj_lib::native_sync;
+use tracing::instrument;
+
+use crate::cli_util::CommandHelper;
+use crate::command_error::user_error;
+use crate::command_error::user_error_with_hint;
+use crate::command_error::CommandError;
+use crate::ui::Ui;
+
+/// Exchange bookmarks and commits with another repo using the native backend
+///
+/// The local bookmarks of the other repo are pulled in as remote bookmarks
+/// (e.g. `main@origin`). Bookmarks tracking them are updated like with `jj git
+/// fetch`. Then the local bookmarks tracking the remote are pushed back to the
+/// other repo, unless `--no-push` is given. All commits and operations are
+/// copied to both repos, so the other repo's history can be inspected with
+/// `--at-operation`.
+///
+/// Both repos must use the native (non-Git) backend. The other repo is
+/// specified by the path to one of its workspaces; remote URLs aren't
+/// supported yet.
+#[derive(clap::Args, Clone, Debug)]
+pub struct SyncArgs {
+    /// Path to a workspace of the other repo
+    #[arg(value_hint = clap::ValueHint::DirPath)]
+    path: String,
+    /// Name to record the other repo's bookmarks under
+    #[arg(long, default_value = "origin")]
+    remote: String,
+    /// Push this bookmark even though it doesn't exist in the other repo yet
+    ///
+    /// The bookmark starts tracking the remote bookmark once pushed.
+    #[arg(long, short, value_name = "BOOKMARK")]
+    bookmark: Vec<String>,
+    /// Only pull from the other repo
+    #[arg(long, conflicts_with = "bookmark")]
+    no_push: bool,
+}
+
+#[instrument(skip_all)]
+pub fn cmd_sync(ui: &mut Ui, command: &CommandHelper, args: &SyncArgs) -> Result<(), CommandError> {
+    let mut workspace_command = command.workspace_helper(ui)?;
+    let not_native_error = || {
+        user_error_with_hint(
+            "Only repos using the native backend can be synced",
+            "Use `jj git fetch` and `jj git push` for Git-backed repos.",
+        )
+    };
+    if !native_sync::is_native_repo(workspace_command.repo()) {
+        return Err(not_native_error());
+    }
+    let local_repo_path = workspace_command.workspace().repo_path().to_owned();
+    let remote_workspace = command.load_workspace_at(&args.path)?;
+    let remote_repo_path = remote_workspace.repo_path().to_owned();
+    if remote_repo_path.canonicalize().ok() == local_repo_path.canonicalize().ok() {
+        return Err(user_error("Cannot sync a repo with itself"));
+    }
+    let remote_repo = remote_workspace
+        .repo_loader()
+        .load_at_head(command.settings())?;
+    if !native_sync::is_native_repo(&remote_repo) {
+        return Err(not_native_error());
+    }
+    for name in &args.bookmark {
+        if workspace_command
+            .repo()
+            .view()
+            .get_local_bookmark(name)
+            .is_absent()
+        {
+            return Err(user_error(format!("No such bookmark: {name}")));
+        }
+    }
+
+    native_sync::copy_objects(&remote_repo_path, &local_repo_path)?;
+    let mut tx = workspace_command.start_transaction();
+    let pulled = native_sync::pull_bookmarks(tx.repo_mut(), &remote_repo, &args.remote)?;
+    let mut pushed = vec![];
+    if !args.no_push {
+        native_sync::copy_objects(&local_repo_path, &remote_repo_path)?;
+        let mut remote_tx = remote_repo.start_transaction(command.settings());
+        pushed = native_sync::push_bookmarks(
+            tx.repo_mut(),
+            remote_tx.repo_mut(),
+            &args.remote,
+            &args.bookmark,
+        )?;
+        if !pushed.is_empty() {
+            let local_root = tx.base_workspace_helper().workspace_root().to_owned();
+            remote_tx.commit(format!("push from {}", local_root.display()))?;
+        }
+    }
+    if let Some(mut formatter) = ui.status_formatter() {
+        for name in &pulled {
+            writeln!(formatter, "Pulled bookmark {name}@{}", args.remote)?;
+        }
+        for name in &pushed {
+            writeln!(formatter, "Pushed bookmark {name} to {}", args.remote)?;
+        }
+    }
+    tx.finish(ui, format!("sync with remote {}", args.remote))?;
+    Ok(())
+}
@@ -0,0 +1,87 @@
+// Copyright 2024 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use clap::builder::NonEmptyStringValueParser;
+use clap_complete::ArgValueCandidates;
+use jj_lib::object_id::ObjectId as _;
+use jj_lib::op_store::RefTarget;
+
+use crate::cli_util::CommandHelper;
+use crate::cli_util::RevisionArg;
+use crate::command_error::user_error_with_hint;
+use crate::command_error::CommandError;
+use crate::complete;
+use crate::ui::Ui;
+
+/// Create a new tag
+///
+/// Unlike bookmarks, tags don't move when the commit they point to is
+/// rewritten. The tag is exported to Git as a lightweight tag along with
+/// bookmarks. Use `jj git push --tag` to push it to a remote.
+#[derive(clap::Args, Clone, Debug)]
+pub struct TagCreateArgs {
+    /// The tag's target revision
+    #[arg(
+        long, short,
+        value_name = "REVSET",
+        add = ArgValueCandidates::new(complete::all_revisions),
+    )]
+    revision: Option<RevisionArg>,
+
+    /// The tags to create
+    #[arg(required = true, value_parser = NonEmptyStringValueParser::new())]
+    names: Vec<String>,
+}
+
+pub fn cmd_tag_create(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    args: &TagCreateArgs,
+) -> Result<(), CommandError> {
+    let mut workspace_command = command.workspace_helper(ui)?;
+    let target_commit = workspace_command
+        .resolve_single_rev(ui, args.revision.as_ref().unwrap_or(&RevisionArg::AT))?;
+    let view = workspace_command.repo().view();
+    let tag_names = &args.names;
+    for name in tag_names {
+        if view.get_tag(name).is_present() {
+            return Err(user_error_with_hint(
+                format!("Tag already exists: {name}"),
+                "Use `jj tag delete` to delete it first.",
+            ));
+        }
+    }
+
+    let mut tx = workspace_command.start_transaction();
+    for tag_name in tag_names {
+        tx.repo_mut()
+            .set_tag_target(tag_name, RefTarget::normal(target_commit.id().clone()));
+    }
+
+    if let Some(mut formatter) = ui.status_formatter() {
+        write!(formatter, "Created {} tags pointing to ", tag_names.len())?;
+        tx.write_commit_summary(formatter.as_mut(), &target_commit)?;
+        writeln!(formatter)?;
+    }
+
+    tx.finish(
+        ui,
+        format!(
+            "create tag {names} pointing to commit {id}",
+            names = tag_names.join(", "),
+            id = target_commit.id().hex()
+        ),
+    )?;
+    Ok(())
+}
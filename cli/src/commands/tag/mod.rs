@@ -0,0 +1,85 @@
+// Copyright 2020-2024 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+mod create;
+mod delete;
+mod list;
+
+use itertools::Itertools as _;
+use jj_lib::op_store::RefTarget;
+use jj_lib::str_util::StringPattern;
+use jj_lib::view::View;
+
+use self::create::cmd_tag_create;
+use self::create::TagCreateArgs;
+use self::delete::cmd_tag_delete;
+use self::delete::TagDeleteArgs;
+use self::list::cmd_tag_list;
+use self::list::TagListArgs;
+use crate::cli_util::CommandHelper;
+use crate::command_error::user_error;
+use crate::command_error::CommandError;
+use crate::ui::Ui;
+
+/// Manage tags.
+#[derive(clap::Subcommand, Clone, Debug)]
+pub enum TagCommand {
+    #[command(visible_alias("c"))]
+    Create(TagCreateArgs),
+    #[command(visible_alias("d"))]
+    Delete(TagDeleteArgs),
+    #[command(visible_alias("l"))]
+    List(TagListArgs),
+}
+
+pub fn cmd_tag(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    subcommand: &TagCommand,
+) -> Result<(), CommandError> {
+    match subcommand {
+        TagCommand::Create(args) => cmd_tag_create(ui, command, args),
+        TagCommand::Delete(args) => cmd_tag_delete(ui, command, args),
+        TagCommand::List(args) => cmd_tag_list(ui, command, args),
+    }
+}
+
+/// Finds tags matching any of the `name_patterns`. Fails if a pattern doesn't
+/// match any tag.
+pub(crate) fn find_tags<'a>(
+    view: &'a View,
+    name_patterns: &[StringPattern],
+) -> Result<Vec<(&'a str, &'a RefTarget)>, CommandError> {
+    let mut matching_tags: Vec<(&'a str, &'a RefTarget)> = vec![];
+    let mut unmatched_patterns = vec![];
+    for pattern in name_patterns {
+        let mut matches = view.tags_matching(pattern).peekable();
+        if matches.peek().is_none() {
+            unmatched_patterns.push(pattern);
+        }
+        matching_tags.extend(matches);
+    }
+    match &unmatched_patterns[..] {
+        [] => {
+            matching_tags.sort_unstable_by_key(|(name, _)| *name);
+            matching_tags.dedup_by_key(|(name, _)| *name);
+            Ok(matching_tags)
+        }
+        [pattern] if pattern.is_exact() => Err(user_error(format!("No such tag: {pattern}"))),
+        patterns => Err(user_error(format!(
+            "No matching tags for patterns: {}",
+            patterns.iter().join(", ")
+        ))),
+    }
+}
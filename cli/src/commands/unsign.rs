@@ -0,0 +1,103 @@
+// Copyright 2024 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashSet;
+
+use clap_complete::ArgValueCandidates;
+use itertools::Itertools as _;
+use jj_lib::commit::Commit;
+use jj_lib::commit::CommitIteratorExt as _;
+use jj_lib::signing::SignBehavior;
+use tracing::instrument;
+
+use crate::cli_util::CommandHelper;
+use crate::cli_util::RevisionArg;
+use crate::command_error::CommandError;
+use crate::complete;
+use crate::ui::Ui;
+
+/// Drop the signatures of revisions
+///
+/// The signed revisions are rewritten without a signature. Descendants are
+/// rebased onto the unsigned revisions. Revisions that aren't signed are left
+/// alone.
+#[derive(clap::Args, Clone, Debug)]
+pub(crate) struct UnsignArgs {
+    /// The revision(s) to unsign
+    #[arg(
+        long,
+        short,
+        default_value = "@",
+        value_name = "REVSETS",
+        add = ArgValueCandidates::new(complete::mutable_revisions)
+    )]
+    revisions: Vec<RevisionArg>,
+}
+
+#[instrument(skip_all)]
+pub(crate) fn cmd_unsign(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    args: &UnsignArgs,
+) -> Result<(), CommandError> {
+    let mut workspace_command = command.workspace_helper(ui)?;
+    let to_unsign: Vec<Commit> = workspace_command
+        .parse_union_revsets(ui, &args.revisions)?
+        .evaluate_to_commits()?
+        .filter_ok(|commit| commit.is_signed())
+        .try_collect()?;
+    if to_unsign.is_empty() {
+        writeln!(ui.status(), "No signed revisions to unsign.")?;
+        return Ok(());
+    }
+    workspace_command.check_rewritable(to_unsign.iter().ids())?;
+    let to_unsign_ids: HashSet<_> = to_unsign.iter().ids().cloned().collect();
+
+    let mut tx = workspace_command.start_transaction();
+    let mut unsigned_commits = vec![];
+    let mut num_reparented = 0;
+    tx.repo_mut().transform_descendants(
+        command.settings(),
+        to_unsign.iter().ids().cloned().collect(),
+        |rewriter| {
+            let is_target = to_unsign_ids.contains(rewriter.old_commit().id());
+            let commit_builder = rewriter.reparent(command.settings());
+            if is_target {
+                let commit = commit_builder
+                    .set_sign_behavior(SignBehavior::Drop)
+                    .write()?;
+                unsigned_commits.push(commit);
+            } else {
+                commit_builder.write()?;
+                num_reparented += 1;
+            }
+            Ok(())
+        },
+    )?;
+
+    if let Some(mut formatter) = ui.status_formatter() {
+        let template = tx.commit_summary_template();
+        writeln!(formatter, "Unsigned {} commits:", unsigned_commits.len())?;
+        for commit in &unsigned_commits {
+            write!(formatter, "  ")?;
+            template.format(commit, formatter.as_mut())?;
+            writeln!(formatter)?;
+        }
+        if num_reparented > 0 {
+            writeln!(formatter, "Rebased {num_reparented} descendant commits")?;
+        }
+    }
+    tx.finish(ui, format!("unsign {} commits", unsigned_commits.len()))?;
+    Ok(())
+}
@@ -0,0 +1,364 @@
+// Copyright 2024 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fs;
+use std::io;
+use std::io::Write as _;
+use std::path::PathBuf;
+
+use bstr::ByteVec as _;
+use jj_lib::backend::ChangeId;
+use jj_lib::backend::MergedTreeId;
+use jj_lib::backend::Signature;
+use jj_lib::backend::TreeValue;
+use jj_lib::commit::Commit;
+use jj_lib::config::ConfigGetResultExt as _;
+use jj_lib::config::ConfigLayer;
+use jj_lib::config::ConfigSource;
+use jj_lib::merge::Merge;
+use jj_lib::op_store::RefTarget;
+use jj_lib::repo::Repo as _;
+use jj_lib::repo_path::RepoPath;
+use jj_lib::settings::UserSettings;
+use jj_lib::workspace::Workspace;
+use pollster::FutureExt as _;
+
+use crate::cli_util::CommandHelper;
+use crate::cli_util::WorkspaceCommandTransaction;
+use crate::command_error::user_error;
+use crate::command_error::user_error_with_hint;
+use crate::command_error::CommandError;
+use crate::formatter::PlainTextFormatter;
+use crate::template_parser;
+use crate::template_parser::ExpressionKind;
+use crate::template_parser::ExpressionNode;
+use crate::ui::Ui;
+
+/// Check the configured templates against golden files
+///
+/// The configured commit templates (such as `templates.log`) are rendered
+/// against synthetic commits covering edge cases such as empty descriptions,
+/// conflicts, divergent changes, and long names. The output of each template
+/// is compared with the golden file `<name>.txt` in the given directory, so
+/// template breakage can be detected after upgrading jj or editing the config.
+///
+/// The synthetic commits are created in a temporary repo, so the output
+/// doesn't depend on the current repo. Templates whose output depends on the
+/// current time (such as `timestamp.ago()`) are skipped.
+#[derive(clap::Args, Clone, Debug)]
+pub struct UtilCheckTemplatesArgs {
+    /// Directory containing the golden files
+    #[arg(value_hint = clap::ValueHint::DirPath)]
+    golden_dir: PathBuf,
+    /// Write the rendered output to the golden files instead of comparing
+    #[arg(long)]
+    update: bool,
+}
+
+/// Names of the `templates.<name>` entries which are rendered for commits.
+const COMMIT_TEMPLATE_NAMES: &[&str] = &[
+    "annotate_commit_summary",
+    "backout_description",
+    "commit_summary",
+    "draft_commit_description",
+    "log",
+    "show",
+];
+
+/// Timestamp methods whose output depends on the current time.
+const TIME_RELATIVE_METHODS: &[&str] = &["ago", "after", "before"];
+
+/// Config overrides to make the synthetic repo deterministic.
+const SYNTHETIC_REPO_CONFIG: &str = r#"
+user.name = "Test User"
+user.email = "test.user@example.com"
+debug.randomness-seed = 0
+debug.commit-timestamp = "2001-02-03T04:05:06+07:00"
+debug.operation-timestamp = "2001-02-03T04:05:06+07:00"
+"#;
+
+pub fn cmd_util_check_templates(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    args: &UtilCheckTemplatesArgs,
+) -> Result<(), CommandError> {
+    let mut config = command.settings().config().clone();
+    config.add_layer(ConfigLayer::parse(
+        ConfigSource::CommandArg,
+        SYNTHETIC_REPO_CONFIG,
+    )?);
+    let settings = UserSettings::from_config(config)?;
+    let temp_dir = tempfile::Builder::new()
+        .prefix("jj-check-templates-")
+        .tempdir()?;
+    let (workspace, repo) = Workspace::init_local(&settings, temp_dir.path())?;
+    let mut workspace_command = command.for_workable_repo(ui, workspace, repo)?;
+    workspace_command.use_workspace_relative_paths();
+    let mut tx = workspace_command.start_transaction();
+    let samples = create_sample_commits(&mut tx, &settings)?;
+
+    if args.update {
+        fs::create_dir_all(&args.golden_dir)?;
+    }
+    let mut num_changed = 0;
+    for name in COMMIT_TEMPLATE_NAMES {
+        let Some(text) = command
+            .settings()
+            .get_string(["templates", name])
+            .optional()?
+        else {
+            continue;
+        };
+        let template = tx
+            .parse_commit_template(ui, &text)
+            .map_err(|err| err.hinted(format!("Failed to parse `templates.{name}`")))?;
+        let aliases_map = tx.base_workspace_helper().template_aliases_map();
+        let node = template_parser::parse(&text, aliases_map)?;
+        if is_time_relative(&node) {
+            writeln!(
+                ui.warning_default(),
+                "Skipped `templates.{name}` because its output depends on the current time"
+            )?;
+            continue;
+        }
+        let mut sections = vec![];
+        for (label, commit) in &samples {
+            let mut output = vec![];
+            template.format(commit, &mut PlainTextFormatter::new(&mut output))?;
+            let mut output = output.into_string_lossy();
+            if !output.ends_with('\n') {
+                output.push('\n');
+            }
+            sections.push((*label, output));
+        }
+        let rendered: String = sections
+            .iter()
+            .map(|(label, output)| format!("=== {label} ===\n{output}"))
+            .collect();
+
+        let path = args.golden_dir.join(format!("{name}.txt"));
+        if args.update {
+            fs::write(&path, &rendered)?;
+            continue;
+        }
+        let golden = match fs::read_to_string(&path) {
+            Ok(golden) => golden,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => {
+                writeln!(
+                    ui.warning_default(),
+                    "No golden file for `templates.{name}`: {}",
+                    path.display()
+                )?;
+                num_changed += 1;
+                continue;
+            }
+            Err(err) => {
+                return Err(user_error(format!(
+                    "Failed to read golden file {}: {err}",
+                    path.display()
+                )));
+            }
+        };
+        if golden != rendered {
+            let golden_sections = split_sections(&golden);
+            let changed_labels = sections
+                .iter()
+                .filter(|(label, output)| !golden_sections.contains(&(label, output.as_str())))
+                .map(|(label, _)| *label);
+            writeln!(
+                ui.warning_default(),
+                "Output of `templates.{name}` changed: {}",
+                changed_labels.collect::<Vec<_>>().join(", ")
+            )?;
+            num_changed += 1;
+        }
+    }
+
+    if num_changed > 0 {
+        return Err(user_error_with_hint(
+            format!("{num_changed} templates don't match the golden files"),
+            format!(
+                "Run `jj util check-templates --update {}` to accept the new output.",
+                args.golden_dir.display()
+            ),
+        ));
+    }
+    if args.update {
+        writeln!(
+            ui.status(),
+            "Updated golden files in {}",
+            args.golden_dir.display()
+        )?;
+    } else {
+        writeln!(ui.status(), "All templates match the golden files")?;
+    }
+    Ok(())
+}
+
+/// Returns true if the template calls any of the `TIME_RELATIVE_METHODS`.
+fn is_time_relative(node: &ExpressionNode) -> bool {
+    match &node.kind {
+        ExpressionKind::Identifier(_)
+        | ExpressionKind::Boolean(_)
+        | ExpressionKind::Integer(_)
+        | ExpressionKind::String(_) => false,
+        ExpressionKind::Unary(_, arg) => is_time_relative(arg),
+        ExpressionKind::Binary(_, lhs, rhs) => is_time_relative(lhs) || is_time_relative(rhs),
+        ExpressionKind::Concat(nodes) => nodes.iter().any(is_time_relative),
+        ExpressionKind::FunctionCall(function) => function
+            .args
+            .iter()
+            .chain(function.keyword_args.iter().map(|arg| &arg.value))
+            .any(is_time_relative),
+        ExpressionKind::MethodCall(method) => {
+            TIME_RELATIVE_METHODS.contains(&method.function.name)
+                || is_time_relative(&method.object)
+                || method
+                    .function
+                    .args
+                    .iter()
+                    .chain(method.function.keyword_args.iter().map(|arg| &arg.value))
+                    .any(is_time_relative)
+        }
+        ExpressionKind::Lambda(lambda) => is_time_relative(&lambda.body),
+        ExpressionKind::AliasExpanded(_, node) => is_time_relative(node),
+    }
+}
+
+/// Splits golden file content into `(label, output)` pairs.
+fn split_sections(text: &str) -> Vec<(&str, &str)> {
+    let mut sections = vec![];
+    let mut rest = text;
+    while let Some(header) = rest.strip_prefix("=== ") {
+        let Some((label, body)) = header.split_once(" ===\n") else {
+            break;
+        };
+        let end = body.find("\n=== ").map_or(body.len(), |pos| pos + 1);
+        sections.push((label, &body[..end]));
+        rest = &body[end..];
+    }
+    sections
+}
+
+fn create_sample_commits(
+    tx: &mut WorkspaceCommandTransaction,
+    settings: &UserSettings,
+) -> Result<Vec<(&'static str, Commit)>, CommandError> {
+    let store = tx.repo().store().clone();
+    let root_commit = store.root_commit();
+    let write_tree = |content: &str| -> Result<_, CommandError> {
+        let path = RepoPath::from_internal_string("file");
+        let id = store.write_file(path, &mut content.as_bytes()).block_on()?;
+        let mut tree_builder = store.tree_builder(store.empty_tree_id().clone());
+        tree_builder.set(
+            path.to_owned(),
+            TreeValue::File {
+                id,
+                executable: false,
+            },
+        );
+        Ok(tree_builder.write_tree()?)
+    };
+    let mut change_ids = (1..).map(|i| ChangeId::new(vec![i; store.change_id_length()]));
+
+    let empty = tx
+        .repo_mut()
+        .new_commit(
+            settings,
+            vec![root_commit.id().clone()],
+            root_commit.tree_id().clone(),
+        )
+        .set_change_id(change_ids.next().unwrap())
+        .write()?;
+
+    let described = tx
+        .repo_mut()
+        .new_commit(
+            settings,
+            vec![root_commit.id().clone()],
+            MergedTreeId::resolved(write_tree("base\n")?),
+        )
+        .set_change_id(change_ids.next().unwrap())
+        .set_description("Add file\n\nThis is the body of the description.\n")
+        .write()?;
+    tx.repo_mut()
+        .set_local_bookmark_target("main", RefTarget::normal(described.id().clone()));
+
+    let long_signature = Signature {
+        name: "Someone With A Remarkably Long Name That Goes On And On".to_owned(),
+        email: "someone.with.a.remarkably.long.name@subdomain.example.com".to_owned(),
+        timestamp: described.author().timestamp,
+    };
+    let long_names = tx
+        .repo_mut()
+        .new_commit(
+            settings,
+            vec![described.id().clone()],
+            described.tree_id().clone(),
+        )
+        .set_change_id(change_ids.next().unwrap())
+        .set_author(long_signature.clone())
+        .set_committer(long_signature)
+        .set_description(format!(
+            "{}\n",
+            "A very long subject line ".repeat(8).trim_end()
+        ))
+        .write()?;
+    tx.repo_mut().set_local_bookmark_target(
+        "a-remarkably-long-bookmark-name/with/several/path/components",
+        RefTarget::normal(long_names.id().clone()),
+    );
+
+    let conflict_tree_id = MergedTreeId::Merge(Merge::from_vec(vec![
+        write_tree("side 1\n")?,
+        write_tree("base\n")?,
+        write_tree("side 2\n")?,
+    ]));
+    let conflict = tx
+        .repo_mut()
+        .new_commit(settings, vec![described.id().clone()], conflict_tree_id)
+        .set_change_id(change_ids.next().unwrap())
+        .set_description("Conflicting changes\n")
+        .write()?;
+
+    let divergent_change_id = change_ids.next().unwrap();
+    let divergent = tx
+        .repo_mut()
+        .new_commit(
+            settings,
+            vec![described.id().clone()],
+            described.tree_id().clone(),
+        )
+        .set_change_id(divergent_change_id.clone())
+        .set_description("Divergent change 1\n")
+        .write()?;
+    tx.repo_mut()
+        .new_commit(
+            settings,
+            vec![described.id().clone()],
+            described.tree_id().clone(),
+        )
+        .set_change_id(divergent_change_id)
+        .set_description("Divergent change 2\n")
+        .write()?;
+
+    Ok(vec![
+        ("root commit", root_commit),
+        ("empty description", empty),
+        ("description", described),
+        ("long names", long_names),
+        ("conflict", conflict),
+        ("divergent", divergent),
+    ])
+}
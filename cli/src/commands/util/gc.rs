@@ -16,6 +16,7 @@ use std::slice;
 use std::time::Duration;
 use std::time::SystemTime;
 
+use jj_lib::conflict_cache::MaterializedConflictCache;
 use jj_lib::repo::Repo as _;
 
 use crate::cli_util::CommandHelper;
@@ -65,5 +66,6 @@ pub fn cmd_util_gc(
     repo.op_store()
         .gc(slice::from_ref(repo.op_id()), keep_newer)?;
     repo.store().gc(repo.index(), keep_newer)?;
+    MaterializedConflictCache::load(workspace_command.repo_path()).gc(keep_newer)?;
     Ok(())
 }
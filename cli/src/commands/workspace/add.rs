@@ -29,6 +29,7 @@ use crate::cli_util::RevisionArg;
 use crate::command_error::internal_error_with_message;
 use crate::command_error::user_error;
 use crate::command_error::CommandError;
+use crate::commands::sparse::get_sparse_profile;
 use crate::ui::Ui;
 
 /// How to handle sparse patterns when creating a new workspace.
@@ -45,7 +46,9 @@ enum SparseInheritance {
 /// Add a workspace
 ///
 /// By default, the new workspace inherits the sparse patterns of the current
-/// workspace. You can override this with the `--sparse-patterns` option.
+/// workspace. You can override this with the `--sparse-patterns` option, or
+/// start from one of the profiles defined in the `sparse.profiles` config table
+/// with `--sparse-profile`.
 #[derive(clap::Args, Clone, Debug)]
 pub struct WorkspaceAddArgs {
     /// Where to create the new workspace
@@ -73,6 +76,10 @@ pub struct WorkspaceAddArgs {
     /// How to handle sparse patterns when creating a new workspace.
     #[arg(long, value_enum, default_value_t = SparseInheritance::Copy)]
     sparse_patterns: SparseInheritance,
+    /// Use the sparse patterns of the named profile (see `jj sparse
+    /// apply-profile`)
+    #[arg(long, value_name = "NAME", conflicts_with = "sparse_patterns")]
+    sparse_profile: Option<String>,
 }
 
 #[instrument(skip_all)]
@@ -82,6 +89,21 @@ pub fn cmd_workspace_add(
     args: &WorkspaceAddArgs,
 ) -> Result<(), CommandError> {
     let old_workspace_command = command.workspace_helper(ui)?;
+    // Look up the profile and the parent revisions before creating anything
+    let profile_patterns = args
+        .sparse_profile
+        .as_ref()
+        .map(|name| get_sparse_profile(command.settings(), name))
+        .transpose()?;
+    let specified_parents = if args.revision.is_empty() {
+        None
+    } else {
+        let parents = old_workspace_command
+            .resolve_some_revsets_default_single(ui, &args.revision)?
+            .into_iter()
+            .collect_vec();
+        Some(parents)
+    };
     let destination_path = command.cwd().join(&args.destination);
     if destination_path.exists() {
         return Err(user_error("Workspace already exists"));
@@ -134,6 +156,7 @@ pub fn cmd_workspace_add(
     let mut new_workspace_command = command.for_workable_repo(ui, new_workspace, repo)?;
 
     let sparsity = match args.sparse_patterns {
+        _ if profile_patterns.is_some() => profile_patterns,
         SparseInheritance::Full => None,
         SparseInheritance::Empty => Some(vec![]),
         SparseInheritance::Copy => {
@@ -160,27 +183,21 @@ pub fn cmd_workspace_add(
 
     // If no parent revisions are specified, create a working-copy commit based
     // on the parent of the current working-copy commit.
-    let parents = if args.revision.is_empty() {
-        // Check out parents of the current workspace's working-copy commit, or the
-        // root if there is no working-copy commit in the current workspace.
-        if let Some(old_wc_commit_id) = tx
-            .base_repo()
-            .view()
-            .get_wc_commit_id(old_workspace_command.workspace_id())
-        {
-            tx.repo()
-                .store()
-                .get_commit(old_wc_commit_id)?
-                .parents()
-                .try_collect()?
-        } else {
-            vec![tx.repo().store().root_commit()]
-        }
+    let parents = if let Some(parents) = specified_parents {
+        parents
+    } else if let Some(old_wc_commit_id) = tx
+        .base_repo()
+        .view()
+        .get_wc_commit_id(old_workspace_command.workspace_id())
+    {
+        tx.repo()
+            .store()
+            .get_commit(old_wc_commit_id)?
+            .parents()
+            .try_collect()?
     } else {
-        old_workspace_command
-            .resolve_some_revsets_default_single(ui, &args.revision)?
-            .into_iter()
-            .collect_vec()
+        // There's no working-copy commit in the current workspace
+        vec![tx.repo().store().root_commit()]
     };
 
     let tree = merge_commit_trees(tx.repo(), &parents)?;
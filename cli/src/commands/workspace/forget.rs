@@ -15,6 +15,7 @@
 use clap_complete::ArgValueCandidates;
 use itertools::Itertools;
 use jj_lib::op_store::WorkspaceId;
+use jj_lib::workspace_store::WorkspaceStore;
 use tracing::instrument;
 
 use crate::cli_util::CommandHelper;
@@ -78,5 +79,14 @@ pub fn cmd_workspace_forget(
     };
 
     tx.finish(ui, description)?;
+
+    // The records are only used for reporting, so failing to remove them
+    // shouldn't fail the command.
+    let store = WorkspaceStore::load(workspace_command.workspace().repo_path());
+    for ws in &wss {
+        if let Err(err) = store.remove(ws) {
+            tracing::warn!(?err, "Failed to remove workspace record");
+        }
+    }
     Ok(())
 }
@@ -13,32 +13,196 @@
 // limitations under the License.
 
 use itertools::Itertools;
+use jj_lib::commit::Commit;
+use jj_lib::object_id::ObjectId as _;
 use jj_lib::repo::Repo;
+use jj_lib::settings::UserSettings;
+use jj_lib::workspace_store::WorkspaceRecord;
+use jj_lib::workspace_store::WorkspaceStore;
 use tracing::instrument;
 
+use crate::cli_util::short_operation_hash;
 use crate::cli_util::CommandHelper;
 use crate::command_error::CommandError;
+use crate::generic_templater::GenericTemplateLanguage;
+use crate::template_builder::TemplateLanguage as _;
+use crate::templater::TemplatePropertyExt as _;
 use crate::ui::Ui;
 
 /// List workspaces
 #[derive(clap::Args, Clone, Debug)]
-pub struct WorkspaceListArgs {}
+pub struct WorkspaceListArgs {
+    /// Show the path, staleness, and sparse patterns of each workspace
+    ///
+    /// The details are recorded whenever a workspace's working copy is
+    /// updated, so they are unknown for workspaces that haven't been used
+    /// since they were created by an older version of jj.
+    #[arg(long, short, conflicts_with = "template")]
+    verbose: bool,
+    /// Render each workspace using the given template
+    ///
+    /// The following keywords are defined:
+    ///
+    /// * `name: String`: Workspace name.
+    /// * `commit_id: String`: Working-copy commit ID.
+    /// * `change_id: String`: Working-copy change ID.
+    /// * `description: String`: Description of the working-copy commit.
+    /// * `path: String`: Workspace root directory, or empty if unknown.
+    /// * `stale: Boolean`: True if the working copy is known to be stale.
+    /// * `last_operation: String`: Operation ID the working copy was last
+    ///   updated to, or empty if unknown.
+    /// * `sparse_patterns: List<String>`: Sparse patterns of the working copy.
+    ///
+    /// For the syntax, see https://jj-vcs.github.io/jj/latest/templates/
+    #[arg(long, short = 'T', verbatim_doc_comment)]
+    template: Option<String>,
+}
+
+#[derive(Clone, Debug)]
+struct WorkspaceEntry {
+    name: String,
+    commit: Commit,
+    record: Option<WorkspaceRecord>,
+}
+
+impl WorkspaceEntry {
+    fn is_stale(&self) -> Option<bool> {
+        let record = self.record.as_ref()?;
+        Some(record.is_stale(self.commit.tree_id()))
+    }
+}
 
 #[instrument(skip_all)]
 pub fn cmd_workspace_list(
     ui: &mut Ui,
     command: &CommandHelper,
-    _args: &WorkspaceListArgs,
+    args: &WorkspaceListArgs,
 ) -> Result<(), CommandError> {
     let workspace_command = command.workspace_helper(ui)?;
     let repo = workspace_command.repo();
+    let store = WorkspaceStore::load(workspace_command.workspace().repo_path());
+    let mut entries = vec![];
+    for (workspace_id, wc_commit_id) in repo.view().wc_commit_ids().iter().sorted() {
+        let record = store.get(workspace_id).unwrap_or_else(|err| {
+            tracing::warn!(?err, "Failed to read workspace record");
+            None
+        });
+        entries.push(WorkspaceEntry {
+            name: workspace_id.as_str().to_owned(),
+            commit: repo.store().get_commit(wc_commit_id)?,
+            record,
+        });
+    }
+
+    if let Some(text) = &args.template {
+        let language = workspace_template_language(command.settings());
+        let template = command
+            .parse_template(ui, &language, text, GenericTemplateLanguage::wrap_self)?
+            .labeled("workspace_list");
+        let mut formatter = ui.stdout_formatter();
+        for entry in &entries {
+            template.format(entry, formatter.as_mut())?;
+        }
+        return Ok(());
+    }
+
     let mut formatter = ui.stdout_formatter();
     let template = workspace_command.commit_summary_template();
-    for (workspace_id, wc_commit_id) in repo.view().wc_commit_ids().iter().sorted() {
-        write!(formatter, "{}: ", workspace_id.as_str())?;
-        let commit = repo.store().get_commit(wc_commit_id)?;
-        template.format(&commit, formatter.as_mut())?;
+    for entry in &entries {
+        write!(formatter, "{}: ", entry.name)?;
+        template.format(&entry.commit, formatter.as_mut())?;
         writeln!(formatter)?;
+        if !args.verbose {
+            continue;
+        }
+        let Some(record) = &entry.record else {
+            writeln!(formatter, "  Path: (unknown)")?;
+            writeln!(formatter, "  Status: unknown")?;
+            continue;
+        };
+        match &record.path {
+            Some(path) => writeln!(formatter, "  Path: {}", path.display())?,
+            None => writeln!(formatter, "  Path: (unknown)")?,
+        }
+        let status = if record.is_stale(entry.commit.tree_id()) {
+            "stale"
+        } else {
+            "up to date"
+        };
+        writeln!(formatter, "  Status: {status}")?;
+        writeln!(
+            formatter,
+            "  Last operation: {}",
+            short_operation_hash(&record.operation_id)
+        )?;
+        writeln!(formatter, "  Sparse patterns:")?;
+        for pattern in &record.sparse_patterns {
+            writeln!(formatter, "    {pattern}")?;
+        }
     }
     Ok(())
 }
+
+// WorkspaceEntry will be cloned internally in the templater. If the cloning
+// cost matters, wrap it with Rc.
+fn workspace_template_language(
+    settings: &UserSettings,
+) -> GenericTemplateLanguage<'static, WorkspaceEntry> {
+    type L = GenericTemplateLanguage<'static, WorkspaceEntry>;
+    let mut language = L::new(settings);
+    language.add_keyword("name", |self_property| {
+        let out_property = self_property.map(|entry| entry.name);
+        Ok(L::wrap_string(out_property))
+    });
+    language.add_keyword("commit_id", |self_property| {
+        let out_property = self_property.map(|entry| entry.commit.id().hex());
+        Ok(L::wrap_string(out_property))
+    });
+    language.add_keyword("change_id", |self_property| {
+        let out_property = self_property.map(|entry| entry.commit.change_id().reverse_hex());
+        Ok(L::wrap_string(out_property))
+    });
+    language.add_keyword("description", |self_property| {
+        let out_property = self_property.map(|entry| entry.commit.description().to_owned());
+        Ok(L::wrap_string(out_property))
+    });
+    language.add_keyword("path", |self_property| {
+        let out_property = self_property.map(|entry| {
+            entry
+                .record
+                .and_then(|record| record.path)
+                .map(|path| path.display().to_string())
+                .unwrap_or_default()
+        });
+        Ok(L::wrap_string(out_property))
+    });
+    language.add_keyword("stale", |self_property| {
+        let out_property = self_property.map(|entry| entry.is_stale().unwrap_or(false));
+        Ok(L::wrap_boolean(out_property))
+    });
+    language.add_keyword("last_operation", |self_property| {
+        let out_property = self_property.map(|entry| {
+            entry
+                .record
+                .map(|record| record.operation_id.hex())
+                .unwrap_or_default()
+        });
+        Ok(L::wrap_string(out_property))
+    });
+    language.add_keyword("sparse_patterns", |self_property| {
+        let out_property = self_property.map(|entry| {
+            entry
+                .record
+                .map(|record| {
+                    record
+                        .sparse_patterns
+                        .iter()
+                        .map(|pattern| pattern.to_string())
+                        .collect()
+                })
+                .unwrap_or_default()
+        });
+        Ok(L::wrap_string_list(out_property))
+    });
+    language
+}
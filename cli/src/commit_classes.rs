@@ -0,0 +1,98 @@
+// Copyright 2024 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Named commit classes defined by user revsets in the `classes` config table.
+
+use std::rc::Rc;
+
+use jj_lib::backend::CommitId;
+use jj_lib::revset::Revset;
+use jj_lib::revset::RevsetContainingFn;
+use jj_lib::revset::RevsetEvaluationError;
+use jj_lib::revset::UserRevsetExpression;
+
+/// Entry of the `classes` table.
+#[derive(Clone, Debug, serde::Deserialize)]
+#[serde(untagged)]
+pub(crate) enum CommitClassConfig {
+    Revset(String),
+    #[serde(rename_all = "kebab-case")]
+    Table {
+        revset: String,
+        #[serde(default)]
+        warn_on_push: bool,
+    },
+}
+
+impl CommitClassConfig {
+    pub fn revset(&self) -> &str {
+        match self {
+            CommitClassConfig::Revset(revset) => revset,
+            CommitClassConfig::Table { revset, .. } => revset,
+        }
+    }
+
+    pub fn warn_on_push(&self) -> bool {
+        match self {
+            CommitClassConfig::Revset(_) => false,
+            CommitClassConfig::Table { warn_on_push, .. } => *warn_on_push,
+        }
+    }
+}
+
+/// Named set of commits configured by user.
+#[derive(Clone, Debug)]
+pub struct CommitClass {
+    pub name: String,
+    pub expression: Rc<UserRevsetExpression>,
+    /// Whether `jj git push` should warn about commits in this class.
+    pub warn_on_push: bool,
+}
+
+/// Tests which classes commits belong to.
+///
+/// The class revsets are evaluated once, and the membership tests are shared
+/// by all commits.
+pub struct CommitClassifier<'a> {
+    classes: Vec<(&'a CommitClass, Box<RevsetContainingFn<'a>>)>,
+}
+
+impl<'a> CommitClassifier<'a> {
+    /// Evaluates the expressions of the given `classes` by using `evaluate`.
+    pub fn new<E>(
+        classes: &'a [CommitClass],
+        mut evaluate: impl FnMut(&CommitClass) -> Result<Box<dyn Revset + 'a>, E>,
+    ) -> Result<Self, E> {
+        let classes = classes
+            .iter()
+            .map(|class| Ok((class, evaluate(class)?.containing_fn())))
+            .collect::<Result<_, E>>()?;
+        Ok(CommitClassifier { classes })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.classes.is_empty()
+    }
+
+    /// Returns the classes the commit belongs to, ordered by name.
+    pub fn classes_of(&self, id: &CommitId) -> Result<Vec<&'a CommitClass>, RevsetEvaluationError> {
+        let mut classes = vec![];
+        for (class, contains) in &self.classes {
+            if contains(id)? {
+                classes.push(*class);
+            }
+        }
+        Ok(classes)
+    }
+}
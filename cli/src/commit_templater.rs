@@ -15,9 +15,11 @@
 use std::any::Any;
 use std::cmp::max;
 use std::cmp::Ordering;
+use std::collections::hash_map;
 use std::collections::HashMap;
 use std::io;
 use std::rc::Rc;
+use std::slice;
 
 use futures::stream::BoxStream;
 use itertools::Itertools as _;
@@ -37,21 +39,34 @@ use jj_lib::id_prefix::IdPrefixIndex;
 use jj_lib::matchers::Matcher;
 use jj_lib::merged_tree::MergedTree;
 use jj_lib::object_id::ObjectId as _;
+use jj_lib::op_store::OpStoreResult;
+use jj_lib::op_store::OperationId;
 use jj_lib::op_store::RefTarget;
 use jj_lib::op_store::RemoteRef;
 use jj_lib::op_store::WorkspaceId;
+use jj_lib::op_walk;
+use jj_lib::operation::Operation;
 use jj_lib::repo::Repo;
 use jj_lib::repo_path::RepoPathUiConverter;
 use jj_lib::revset;
+use jj_lib::revset::ResolvedRevsetExpression;
 use jj_lib::revset::Revset;
 use jj_lib::revset::RevsetContainingFn;
 use jj_lib::revset::RevsetDiagnostics;
 use jj_lib::revset::RevsetModifier;
 use jj_lib::revset::RevsetParseContext;
 use jj_lib::revset::UserRevsetExpression;
+use jj_lib::settings::UserSettings;
+use jj_lib::signing::SigStatus;
+use jj_lib::signing::SignError;
+use jj_lib::signing::SignResult;
+use jj_lib::signing::Verification;
 use jj_lib::store::Store;
+use jj_lib::view::View;
 use once_cell::unsync::OnceCell;
 
+use crate::commit_classes::CommitClass;
+use crate::commit_classes::CommitClassifier;
 use crate::diff_util;
 use crate::formatter::Formatter;
 use crate::revset_util;
@@ -87,6 +102,7 @@ pub trait CommitTemplateLanguageExtension {
 
 pub struct CommitTemplateLanguage<'repo> {
     repo: &'repo dyn Repo,
+    settings: UserSettings,
     path_converter: &'repo RepoPathUiConverter,
     workspace_id: WorkspaceId,
     // RevsetParseContext doesn't borrow a repo, but we'll need 'repo lifetime
@@ -97,6 +113,7 @@ pub struct CommitTemplateLanguage<'repo> {
     revset_parse_context: RevsetParseContext<'repo>,
     id_prefix_context: &'repo IdPrefixContext,
     immutable_expression: Rc<UserRevsetExpression>,
+    commit_classes: &'repo [CommitClass],
     conflict_marker_style: ConflictMarkerStyle,
     build_fn_table: CommitTemplateBuildFnTable<'repo>,
     keyword_cache: CommitKeywordCache<'repo>,
@@ -109,11 +126,13 @@ impl<'repo> CommitTemplateLanguage<'repo> {
     #[allow(clippy::too_many_arguments)]
     pub fn new(
         repo: &'repo dyn Repo,
+        settings: &UserSettings,
         path_converter: &'repo RepoPathUiConverter,
         workspace_id: &WorkspaceId,
         revset_parse_context: RevsetParseContext<'repo>,
         id_prefix_context: &'repo IdPrefixContext,
         immutable_expression: Rc<UserRevsetExpression>,
+        commit_classes: &'repo [CommitClass],
         conflict_marker_style: ConflictMarkerStyle,
         extensions: &[impl AsRef<dyn CommitTemplateLanguageExtension>],
     ) -> Self {
@@ -129,11 +148,13 @@ impl<'repo> CommitTemplateLanguage<'repo> {
 
         CommitTemplateLanguage {
             repo,
+            settings: settings.clone(),
             path_converter,
             workspace_id: workspace_id.clone(),
             revset_parse_context,
             id_prefix_context,
             immutable_expression,
+            commit_classes,
             conflict_marker_style,
             build_fn_table,
             keyword_cache: CommitKeywordCache::default(),
@@ -147,6 +168,10 @@ impl<'repo> TemplateLanguage<'repo> for CommitTemplateLanguage<'repo> {
 
     template_builder::impl_core_wrap_property_fns!('repo, CommitTemplatePropertyKind::Core);
 
+    fn settings(&self) -> &UserSettings {
+        &self.settings
+    }
+
     fn build_function(
         &self,
         diagnostics: &mut TemplateDiagnostics,
@@ -197,6 +222,7 @@ impl<'repo> TemplateLanguage<'repo> for CommitTemplateLanguage<'repo> {
                     property,
                     function,
                     Self::wrap_commit,
+                    Self::wrap_commit_list,
                 )
             }
             CommitTemplatePropertyKind::RefName(property) => {
@@ -226,6 +252,7 @@ impl<'repo> TemplateLanguage<'repo> for CommitTemplateLanguage<'repo> {
                     property,
                     function,
                     Self::wrap_ref_name,
+                    Self::wrap_ref_name_list,
                 )
             }
             CommitTemplatePropertyKind::CommitOrChangeId(property) => {
@@ -243,6 +270,19 @@ impl<'repo> TemplateLanguage<'repo> for CommitTemplateLanguage<'repo> {
                 let build = template_parser::lookup_method(type_name, table, function)?;
                 build(self, diagnostics, build_ctx, property, function)
             }
+            CommitTemplatePropertyKind::CryptographicSignatureOpt(property) => {
+                let type_name = "CryptographicSignature";
+                let table = &self.build_fn_table.cryptographic_signature_methods;
+                let build = template_parser::lookup_method(type_name, table, function)?;
+                let inner_property = property.try_unwrap(type_name);
+                build(
+                    self,
+                    diagnostics,
+                    build_ctx,
+                    Box::new(inner_property),
+                    function,
+                )
+            }
         }
     }
 }
@@ -319,6 +359,12 @@ impl<'repo> CommitTemplateLanguage<'repo> {
     ) -> CommitTemplatePropertyKind<'repo> {
         CommitTemplatePropertyKind::TreeDiff(Box::new(property))
     }
+
+    pub fn wrap_cryptographic_signature_opt(
+        property: impl TemplateProperty<Output = Option<CryptographicSignature>> + 'repo,
+    ) -> CommitTemplatePropertyKind<'repo> {
+        CommitTemplatePropertyKind::CryptographicSignatureOpt(Box::new(property))
+    }
 }
 
 pub enum CommitTemplatePropertyKind<'repo> {
@@ -332,6 +378,9 @@ pub enum CommitTemplatePropertyKind<'repo> {
     CommitOrChangeId(Box<dyn TemplateProperty<Output = CommitOrChangeId> + 'repo>),
     ShortestIdPrefix(Box<dyn TemplateProperty<Output = ShortestIdPrefix> + 'repo>),
     TreeDiff(Box<dyn TemplateProperty<Output = TreeDiff> + 'repo>),
+    CryptographicSignatureOpt(
+        Box<dyn TemplateProperty<Output = Option<CryptographicSignature>> + 'repo>,
+    ),
 }
 
 impl<'repo> IntoTemplateProperty<'repo> for CommitTemplatePropertyKind<'repo> {
@@ -347,6 +396,9 @@ impl<'repo> IntoTemplateProperty<'repo> for CommitTemplatePropertyKind<'repo> {
             CommitTemplatePropertyKind::CommitOrChangeId(_) => "CommitOrChangeId",
             CommitTemplatePropertyKind::ShortestIdPrefix(_) => "ShortestIdPrefix",
             CommitTemplatePropertyKind::TreeDiff(_) => "TreeDiff",
+            CommitTemplatePropertyKind::CryptographicSignatureOpt(_) => {
+                "Option<CryptographicSignature>"
+            }
         }
     }
 
@@ -372,6 +424,9 @@ impl<'repo> IntoTemplateProperty<'repo> for CommitTemplatePropertyKind<'repo> {
             // TODO: boolean cast could be implemented, but explicit
             // diff.empty() method might be better.
             CommitTemplatePropertyKind::TreeDiff(_) => None,
+            CommitTemplatePropertyKind::CryptographicSignatureOpt(property) => {
+                Some(Box::new(property.map(|opt| opt.is_some())))
+            }
         }
     }
 
@@ -408,6 +463,7 @@ impl<'repo> IntoTemplateProperty<'repo> for CommitTemplatePropertyKind<'repo> {
                 Some(property.into_template())
             }
             CommitTemplatePropertyKind::TreeDiff(_) => None,
+            CommitTemplatePropertyKind::CryptographicSignatureOpt(_) => None,
         }
     }
 
@@ -426,6 +482,7 @@ impl<'repo> IntoTemplateProperty<'repo> for CommitTemplatePropertyKind<'repo> {
             (CommitTemplatePropertyKind::CommitOrChangeId(_), _) => None,
             (CommitTemplatePropertyKind::ShortestIdPrefix(_), _) => None,
             (CommitTemplatePropertyKind::TreeDiff(_), _) => None,
+            (CommitTemplatePropertyKind::CryptographicSignatureOpt(_), _) => None,
         }
     }
 
@@ -447,6 +504,7 @@ impl<'repo> IntoTemplateProperty<'repo> for CommitTemplatePropertyKind<'repo> {
             (CommitTemplatePropertyKind::CommitOrChangeId(_), _) => None,
             (CommitTemplatePropertyKind::ShortestIdPrefix(_), _) => None,
             (CommitTemplatePropertyKind::TreeDiff(_), _) => None,
+            (CommitTemplatePropertyKind::CryptographicSignatureOpt(_), _) => None,
         }
     }
 }
@@ -463,6 +521,8 @@ pub struct CommitTemplateBuildFnTable<'repo> {
     pub commit_or_change_id_methods: CommitTemplateBuildMethodFnMap<'repo, CommitOrChangeId>,
     pub shortest_id_prefix_methods: CommitTemplateBuildMethodFnMap<'repo, ShortestIdPrefix>,
     pub tree_diff_methods: CommitTemplateBuildMethodFnMap<'repo, TreeDiff>,
+    pub cryptographic_signature_methods:
+        CommitTemplateBuildMethodFnMap<'repo, CryptographicSignature>,
 }
 
 impl<'repo> CommitTemplateBuildFnTable<'repo> {
@@ -475,6 +535,7 @@ impl<'repo> CommitTemplateBuildFnTable<'repo> {
             commit_or_change_id_methods: builtin_commit_or_change_id_methods(),
             shortest_id_prefix_methods: builtin_shortest_id_prefix_methods(),
             tree_diff_methods: builtin_tree_diff_methods(),
+            cryptographic_signature_methods: builtin_cryptographic_signature_methods(),
         }
     }
 
@@ -486,6 +547,7 @@ impl<'repo> CommitTemplateBuildFnTable<'repo> {
             commit_or_change_id_methods: HashMap::new(),
             shortest_id_prefix_methods: HashMap::new(),
             tree_diff_methods: HashMap::new(),
+            cryptographic_signature_methods: HashMap::new(),
         }
     }
 
@@ -497,6 +559,7 @@ impl<'repo> CommitTemplateBuildFnTable<'repo> {
             commit_or_change_id_methods,
             shortest_id_prefix_methods,
             tree_diff_methods,
+            cryptographic_signature_methods,
         } = extension;
 
         self.core.merge(core);
@@ -511,6 +574,10 @@ impl<'repo> CommitTemplateBuildFnTable<'repo> {
             shortest_id_prefix_methods,
         );
         merge_fn_map(&mut self.tree_diff_methods, tree_diff_methods);
+        merge_fn_map(
+            &mut self.cryptographic_signature_methods,
+            cryptographic_signature_methods,
+        );
     }
 }
 
@@ -520,7 +587,10 @@ pub struct CommitKeywordCache<'repo> {
     bookmarks_index: OnceCell<Rc<RefNamesIndex>>,
     tags_index: OnceCell<Rc<RefNamesIndex>>,
     git_refs_index: OnceCell<Rc<RefNamesIndex>>,
+    // Walking the operation log is expensive, so this is built on evaluation.
+    bookmark_moves_index: Rc<OnceCell<BookmarkMovesIndex>>,
     is_immutable_fn: OnceCell<Rc<RevsetContainingFn<'repo>>>,
+    commit_classifier: OnceCell<Rc<CommitClassifier<'repo>>>,
 }
 
 impl<'repo> CommitKeywordCache<'repo> {
@@ -539,6 +609,10 @@ impl<'repo> CommitKeywordCache<'repo> {
             .get_or_init(|| Rc::new(build_ref_names_index(repo.view().git_refs())))
     }
 
+    pub fn bookmark_moves_index(&self) -> &Rc<OnceCell<BookmarkMovesIndex>> {
+        &self.bookmark_moves_index
+    }
+
     pub fn is_immutable_fn(
         &self,
         language: &CommitTemplateLanguage<'repo>,
@@ -553,6 +627,19 @@ impl<'repo> CommitKeywordCache<'repo> {
             Ok(revset.containing_fn().into())
         })
     }
+
+    pub fn commit_classifier(
+        &self,
+        language: &CommitTemplateLanguage<'repo>,
+        span: pest::Span<'_>,
+    ) -> TemplateParseResult<&Rc<CommitClassifier<'repo>>> {
+        self.commit_classifier.get_or_try_init(|| {
+            let classifier = CommitClassifier::new(language.commit_classes, |class| {
+                evaluate_revset_expression(language, span, &class.expression)
+            })?;
+            Ok(classifier.into())
+        })
+    }
 }
 
 fn builtin_commit_methods<'repo>() -> CommitTemplateBuildMethodFnMap<'repo, Commit> {
@@ -612,6 +699,14 @@ fn builtin_commit_methods<'repo>() -> CommitTemplateBuildMethodFnMap<'repo, Comm
             Ok(L::wrap_signature(out_property))
         },
     );
+    map.insert(
+        "signature",
+        |_language, _diagnostics, _build_ctx, self_property, function| {
+            function.expect_no_arguments()?;
+            let out_property = self_property.map(CryptographicSignature::new);
+            Ok(L::wrap_cryptographic_signature_opt(out_property))
+        },
+    );
     map.insert(
         "mine",
         |language, _diagnostics, _build_ctx, self_property, function| {
@@ -792,6 +887,21 @@ fn builtin_commit_methods<'repo>() -> CommitTemplateBuildMethodFnMap<'repo, Comm
             Ok(L::wrap_boolean(out_property))
         },
     );
+    map.insert(
+        "classes",
+        |language, _diagnostics, _build_ctx, self_property, function| {
+            function.expect_no_arguments()?;
+            let classifier = language
+                .keyword_cache
+                .commit_classifier(language, function.name_span)?
+                .clone();
+            let out_property = self_property.and_then(move |commit| {
+                let classes = classifier.classes_of(commit.id())?;
+                Ok(classes.iter().map(|class| class.name.clone()).collect())
+            });
+            Ok(L::wrap_string_list(out_property))
+        },
+    );
     map.insert(
         "contained_in",
         |language, diagnostics, _build_ctx, self_property, function| {
@@ -806,6 +916,38 @@ fn builtin_commit_methods<'repo>() -> CommitTemplateBuildMethodFnMap<'repo, Comm
             Ok(L::wrap_boolean(out_property))
         },
     );
+    map.insert(
+        "merge_base",
+        |language, diagnostics, _build_ctx, self_property, function| {
+            let [revset_node] = function.expect_exact_arguments()?;
+
+            // The other side is evaluated once, not per commit.
+            let other_ids: Vec<CommitId> =
+                template_parser::expect_string_literal_with(revset_node, |revset, span| {
+                    let revset = evaluate_user_revset(language, diagnostics, span, revset)?;
+                    revset.iter().try_collect().map_err(|err| {
+                        TemplateParseError::expression("Failed to evaluate revset", span)
+                            .with_source(err)
+                    })
+                })?;
+            let other_ancestors = ResolvedRevsetExpression::commits(other_ids).ancestors();
+
+            let repo = language.repo;
+            let out_property = self_property.and_then(move |commit| {
+                let expression = ResolvedRevsetExpression::commit(commit.id().clone())
+                    .ancestors()
+                    .intersection(&other_ancestors)
+                    .heads();
+                let revset = expression.evaluate(repo)?;
+                // If there are multiple merge bases, pick the newest one.
+                let Some(id) = revset.iter().next().transpose()? else {
+                    return Ok(None);
+                };
+                Ok(Some(repo.store().get_commit(&id)?))
+            });
+            Ok(L::wrap_commit_opt(out_property))
+        },
+    );
     map.insert(
         "conflict",
         |_language, _diagnostics, _build_ctx, self_property, function| {
@@ -942,6 +1084,9 @@ pub struct RefName {
     /// Local ref is synchronized with all tracking remotes, or tracking remote
     /// ref is synchronized with the local.
     synced: bool,
+    /// Whether this is a local or remote bookmark (as opposed to a tag or Git
+    /// ref.)
+    is_bookmark: bool,
 }
 
 #[derive(Debug)]
@@ -974,12 +1119,21 @@ impl RefName {
             target,
             tracking_ref: None,
             synced,
+            is_bookmark: true,
         })
     }
 
-    /// Creates local ref representation which doesn't track any remote refs.
+    /// Creates tag or Git ref representation which doesn't track any remote
+    /// refs.
     pub fn local_only(name: impl Into<String>, target: RefTarget) -> Rc<Self> {
-        Self::local(name, target, [])
+        Rc::new(RefName {
+            name: name.into(),
+            remote: None,
+            target,
+            tracking_ref: None,
+            synced: true,
+            is_bookmark: false,
+        })
     }
 
     /// Creates remote ref representation which might be tracked by a local ref
@@ -1009,6 +1163,7 @@ impl RefName {
             target: remote_ref.target,
             tracking_ref,
             synced,
+            is_bookmark: true,
         })
     }
 
@@ -1024,6 +1179,7 @@ impl RefName {
             target,
             tracking_ref: None,
             synced: false, // has no local counterpart
+            is_bookmark: true,
         })
     }
 
@@ -1087,6 +1243,19 @@ impl RefName {
             })
             .copied()
     }
+
+    /// Operation which moved the bookmark to the current target.
+    fn last_moved_operation<'a>(
+        &self,
+        repo: &dyn Repo,
+        moves: &'a OnceCell<BookmarkMovesIndex>,
+    ) -> Result<&'a Operation, TemplatePropertyError> {
+        if !self.is_bookmark {
+            return Err(TemplatePropertyError("Not a bookmark".into()));
+        }
+        let index = moves.get_or_try_init(|| build_bookmark_moves_index(repo))?;
+        index.get(self)
+    }
 }
 
 // If wrapping with Rc<T> becomes common, add generic impl for Rc<T>.
@@ -1224,6 +1393,45 @@ fn builtin_ref_name_methods<'repo>() -> CommitTemplateBuildMethodFnMap<'repo, Rc
             Ok(L::wrap_size_hint(out_property))
         },
     );
+    map.insert(
+        "last_moved_op_id",
+        |language, _diagnostics, _build_ctx, self_property, function| {
+            function.expect_no_arguments()?;
+            let repo = language.repo;
+            let moves = language.keyword_cache.bookmark_moves_index().clone();
+            let out_property = self_property.and_then(move |ref_name| {
+                let op = ref_name.last_moved_operation(repo, &moves)?;
+                Ok(op.id().hex())
+            });
+            Ok(L::wrap_string(out_property))
+        },
+    );
+    map.insert(
+        "last_moved_op_description",
+        |language, _diagnostics, _build_ctx, self_property, function| {
+            function.expect_no_arguments()?;
+            let repo = language.repo;
+            let moves = language.keyword_cache.bookmark_moves_index().clone();
+            let out_property = self_property.and_then(move |ref_name| {
+                let op = ref_name.last_moved_operation(repo, &moves)?;
+                Ok(op.metadata().description.clone())
+            });
+            Ok(L::wrap_string(out_property))
+        },
+    );
+    map.insert(
+        "last_moved_time",
+        |language, _diagnostics, _build_ctx, self_property, function| {
+            function.expect_no_arguments()?;
+            let repo = language.repo;
+            let moves = language.keyword_cache.bookmark_moves_index().clone();
+            let out_property = self_property.and_then(move |ref_name| {
+                let op = ref_name.last_moved_operation(repo, &moves)?;
+                Ok(op.metadata().end_time)
+            });
+            Ok(L::wrap_timestamp(out_property))
+        },
+    );
     map
 }
 
@@ -1268,6 +1476,106 @@ fn build_bookmarks_index(repo: &dyn Repo) -> RefNamesIndex {
     index
 }
 
+/// `(name, remote)` of a bookmark. `remote` is `None` for local bookmarks.
+type BookmarkKey = (String, Option<String>);
+
+/// Maximum number of operations to walk to find the operations which moved
+/// the bookmarks.
+const BOOKMARK_MOVES_OP_LIMIT: usize = 1000;
+
+/// Operations which moved the bookmarks to their current targets.
+#[derive(Debug, Default)]
+pub struct BookmarkMovesIndex {
+    index: HashMap<BookmarkKey, (RefTarget, Operation)>,
+    /// Current targets of the bookmarks which weren't moved within the
+    /// `BOOKMARK_MOVES_OP_LIMIT` operations.
+    not_found: HashMap<BookmarkKey, RefTarget>,
+}
+
+impl BookmarkMovesIndex {
+    fn get(&self, ref_name: &RefName) -> Result<&Operation, TemplatePropertyError> {
+        let key = (ref_name.name.clone(), ref_name.remote.clone());
+        match (self.index.get(&key), self.not_found.get(&key)) {
+            (Some((target, op)), _) if *target == ref_name.target => Ok(op),
+            (_, Some(target)) if *target == ref_name.target => Err(TemplatePropertyError(
+                format!("Bookmark wasn't moved in the last {BOOKMARK_MOVES_OP_LIMIT} operations")
+                    .into(),
+            )),
+            // The bookmark might have been moved by the current transaction.
+            _ => Err(TemplatePropertyError(
+                "Bookmark was moved by an uncommitted operation".into(),
+            )),
+        }
+    }
+}
+
+fn bookmark_target<'a>(view: &'a View, (name, remote): &BookmarkKey) -> &'a RefTarget {
+    match remote {
+        Some(remote) => &view.get_remote_bookmark(name, remote).target,
+        None => view.get_local_bookmark(name),
+    }
+}
+
+/// Walks the operation log from the base operation of the `repo` to find the
+/// operations which moved the bookmarks to their current targets.
+///
+/// The walk stops after `BOOKMARK_MOVES_OP_LIMIT` operations. Each view is
+/// loaded once, and kept only until the operation is visited.
+fn build_bookmark_moves_index(repo: &dyn Repo) -> OpStoreResult<BookmarkMovesIndex> {
+    let base_repo = repo.base_repo();
+    let head_view = base_repo.view();
+    let mut pending: HashMap<BookmarkKey, RefTarget> = head_view
+        .local_bookmarks()
+        .map(|(name, target)| ((name.to_owned(), None), target.clone()))
+        .chain(
+            head_view
+                .all_remote_bookmarks()
+                .map(|((name, remote), remote_ref)| {
+                    let key = (name.to_owned(), Some(remote.to_owned()));
+                    (key, remote_ref.target.clone())
+                }),
+        )
+        .collect();
+    let mut index = HashMap::new();
+    // Views of the parents of the visited operations.
+    let mut views: HashMap<OperationId, View> = HashMap::new();
+    let ops = op_walk::walk_ancestors(slice::from_ref(base_repo.operation()));
+    for op in ops.take(BOOKMARK_MOVES_OP_LIMIT) {
+        if pending.is_empty() {
+            break;
+        }
+        let op = op?;
+        let view = match views.remove(op.id()) {
+            Some(view) => view,
+            None => op.view()?,
+        };
+        for parent in op.parents() {
+            let parent = parent?;
+            if let hash_map::Entry::Vacant(entry) = views.entry(parent.id().clone()) {
+                entry.insert(parent.view()?);
+            }
+        }
+        let parent_views = op.parent_ids().iter().map(|id| &views[id]).collect_vec();
+        // The operation moved the bookmark if it set the current target, and
+        // the target wasn't inherited from any of the parents. Concurrent
+        // operations which moved the bookmark elsewhere are skipped.
+        pending.retain(|key, target| {
+            let moved = bookmark_target(&view, key) == target
+                && parent_views
+                    .iter()
+                    .all(|parent_view| bookmark_target(parent_view, key) != target);
+            if moved {
+                index.insert(key.clone(), (target.clone(), op.clone()));
+            }
+            !moved
+        });
+    }
+    Ok(BookmarkMovesIndex {
+        index,
+        not_found: pending,
+    })
+}
+
 fn build_ref_names_index<'a>(
     ref_pairs: impl IntoIterator<Item = (&'a String, &'a RefTarget)>,
 ) -> RefNamesIndex {
@@ -1671,3 +1979,89 @@ fn builtin_tree_diff_methods<'repo>() -> CommitTemplateBuildMethodFnMap<'repo, T
     // TODO: add files() or map() to support custom summary-like formatting?
     map
 }
+
+/// Cryptographic signature of a commit. The signature is verified when one of
+/// the methods is called. Results are cached by the signer.
+#[derive(Debug)]
+pub struct CryptographicSignature {
+    commit: Commit,
+}
+
+impl CryptographicSignature {
+    fn new(commit: Commit) -> Option<Self> {
+        commit
+            .is_signed()
+            .then_some(CryptographicSignature { commit })
+    }
+
+    fn verify(&self) -> SignResult<Verification> {
+        self.commit
+            .verification()
+            .transpose()
+            .expect("must have signature")
+    }
+
+    fn status(&self) -> SignResult<String> {
+        let status = match self.verify() {
+            Ok(verification) => match verification.status {
+                SigStatus::Good => "good",
+                SigStatus::Unknown => "unknown",
+                SigStatus::Bad => "bad",
+            },
+            Err(SignError::InvalidSignatureFormat) => "invalid",
+            Err(err) => return Err(err),
+        };
+        Ok(status.to_owned())
+    }
+
+    /// Key id, or an empty string if the signature can't be verified.
+    fn key(&self) -> SignResult<String> {
+        match self.verify() {
+            Ok(verification) => Ok(verification.key.unwrap_or_default()),
+            Err(SignError::InvalidSignatureFormat) => Ok(String::new()),
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Signer identity, or an empty string if the signature can't be verified.
+    fn display(&self) -> SignResult<String> {
+        match self.verify() {
+            Ok(verification) => Ok(verification.display.unwrap_or_default()),
+            Err(SignError::InvalidSignatureFormat) => Ok(String::new()),
+            Err(err) => Err(err),
+        }
+    }
+}
+
+fn builtin_cryptographic_signature_methods<'repo>(
+) -> CommitTemplateBuildMethodFnMap<'repo, CryptographicSignature> {
+    type L<'repo> = CommitTemplateLanguage<'repo>;
+    // Not using maplit::hashmap!{} or custom declarative macro here because
+    // code completion inside macro is quite restricted.
+    let mut map = CommitTemplateBuildMethodFnMap::<CryptographicSignature>::new();
+    map.insert(
+        "status",
+        |_language, _diagnostics, _build_ctx, self_property, function| {
+            function.expect_no_arguments()?;
+            let out_property = self_property.and_then(|sig| Ok(sig.status()?));
+            Ok(L::wrap_string(out_property))
+        },
+    );
+    map.insert(
+        "key",
+        |_language, _diagnostics, _build_ctx, self_property, function| {
+            function.expect_no_arguments()?;
+            let out_property = self_property.and_then(|sig| Ok(sig.key()?));
+            Ok(L::wrap_string(out_property))
+        },
+    );
+    map.insert(
+        "display",
+        |_language, _diagnostics, _build_ctx, self_property, function| {
+            function.expect_no_arguments()?;
+            let out_property = self_property.and_then(|sig| Ok(sig.display()?));
+            Ok(L::wrap_string(out_property))
+        },
+    );
+    map
+}
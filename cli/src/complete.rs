@@ -76,6 +76,24 @@ pub fn local_bookmarks() -> Vec<CompletionCandidate> {
     })
 }
 
+pub fn tags() -> Vec<CompletionCandidate> {
+    with_jj(|jj, _| {
+        let output = jj
+            .build()
+            .arg("tag")
+            .arg("list")
+            .arg("--template")
+            .arg(r#"name ++ "\n""#)
+            .output()
+            .map_err(user_error)?;
+
+        Ok(String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .map(CompletionCandidate::new)
+            .collect())
+    })
+}
+
 pub fn tracked_bookmarks() -> Vec<CompletionCandidate> {
     with_jj(|jj, _| {
         let output = jj
@@ -221,9 +239,10 @@ fn revisions(revisions: Option<&str>) -> Vec<CompletionCandidate> {
         const LOCAL_BOOKMARK_MINE: usize = 0;
         const LOCAL_BOOKMARK: usize = 1;
         const TAG: usize = 2;
-        const CHANGE_ID: usize = 3;
-        const REMOTE_BOOKMARK_MINE: usize = 4;
-        const REMOTE_BOOKMARK: usize = 5;
+        const WORKING_COPY: usize = 3;
+        const CHANGE_ID: usize = 4;
+        const REMOTE_BOOKMARK_MINE: usize = 5;
+        const REMOTE_BOOKMARK: usize = 6;
 
         let mut candidates = Vec::new();
 
@@ -291,6 +310,27 @@ fn revisions(revisions: Option<&str>) -> Vec<CompletionCandidate> {
             }));
         }
 
+        // working copies
+
+        // Working-copy commits are mutable unless something went wrong, so
+        // they are suggested regardless of the revisions.
+        let output = jj
+            .build()
+            .arg("workspace")
+            .arg("list")
+            .arg("--template")
+            .arg(r#"name ++ " " ++ description.first_line() ++ "\n""#)
+            .output()
+            .map_err(user_error)?;
+        let stdout = String::from_utf8_lossy(&output.stdout);
+
+        candidates.extend(stdout.lines().map(|line| {
+            let (name, desc) = split_help_text(line);
+            CompletionCandidate::new(format!("{name}@"))
+                .help(desc)
+                .display_order(Some(WORKING_COPY))
+        }));
+
         // change IDs
 
         let revisions = revisions
@@ -1,5 +1,8 @@
 use std::collections::HashMap;
+use std::fs;
+use std::io;
 use std::io::Write as _;
+use std::iter;
 use std::path::Path;
 
 use bstr::ByteVec as _;
@@ -8,12 +11,15 @@ use indoc::indoc;
 use itertools::Itertools;
 use jj_lib::backend::CommitId;
 use jj_lib::commit::Commit;
+use jj_lib::config::ConfigGetResultExt as _;
 use jj_lib::settings::UserSettings;
 use thiserror::Error;
 
 use crate::cli_util::edit_temp_file;
 use crate::cli_util::short_commit_hash;
 use crate::cli_util::WorkspaceCommandTransaction;
+use crate::command_error::user_error_with_hint;
+use crate::command_error::user_error_with_message;
 use crate::command_error::CommandError;
 use crate::formatter::PlainTextFormatter;
 use crate::text_util;
@@ -224,6 +230,88 @@ pub fn join_message_paragraphs(paragraphs: &[String]) -> String {
         .join("\n")
 }
 
+/// Returns the description to start editing from when a commit has no
+/// description.
+///
+/// If `ui.default-description-file` is set and the file exists, its content
+/// is used. The path is relative to the workspace root. Otherwise,
+/// `ui.default-description` is used.
+pub fn default_description(
+    workspace_root: &Path,
+    settings: &UserSettings,
+) -> Result<String, CommandError> {
+    match read_default_description_file(workspace_root, settings)? {
+        Some(text) => Ok(text),
+        None => Ok(settings.get_string("ui.default-description")?),
+    }
+}
+
+/// Fails if the edited `description` still contains placeholders from the
+/// `ui.default-description-file` template.
+///
+/// Placeholders are written as `{{...}}` in the template file.
+pub fn check_description_placeholders(
+    workspace_root: &Path,
+    settings: &UserSettings,
+    description: &str,
+) -> Result<(), CommandError> {
+    let Some(template) = read_default_description_file(workspace_root, settings)? else {
+        return Ok(());
+    };
+    let unfilled = find_placeholders(&template)
+        .filter(|placeholder| description.contains(placeholder))
+        .unique()
+        .collect_vec();
+    if unfilled.is_empty() {
+        Ok(())
+    } else {
+        Err(user_error_with_hint(
+            format!(
+                "The description still contains placeholders from the description template: {}",
+                unfilled.join(", ")
+            ),
+            "Replace the placeholders with text, or remove them.",
+        ))
+    }
+}
+
+fn read_default_description_file(
+    workspace_root: &Path,
+    settings: &UserSettings,
+) -> Result<Option<String>, CommandError> {
+    let Some(path) = settings
+        .get_string("ui.default-description-file")
+        .optional()?
+    else {
+        return Ok(None);
+    };
+    let path = workspace_root.join(path);
+    match fs::read_to_string(&path) {
+        Ok(text) => Ok(Some(text)),
+        // The file may not exist in all revisions of the repo.
+        Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(None),
+        Err(err) => Err(user_error_with_message(
+            format!(
+                r#"Failed to read description template file "{}""#,
+                path.display()
+            ),
+            err,
+        )),
+    }
+}
+
+/// Finds `{{...}}` placeholders in the `text`.
+fn find_placeholders(text: &str) -> impl Iterator<Item = &str> {
+    let mut rest = text;
+    iter::from_fn(move || {
+        let start = rest.find("{{")?;
+        let end = start + rest[start..].find("}}")? + 2;
+        let placeholder = &rest[start..end];
+        rest = &rest[end..];
+        Some(placeholder)
+    })
+}
+
 /// Renders commit description template, which will be edited by user.
 pub fn description_template(
     ui: &Ui,
@@ -232,10 +320,8 @@ pub fn description_template(
     commit: &Commit,
 ) -> Result<String, CommandError> {
     // TODO: Should "ui.default-description" be deprecated?
-    // We might want default description templates per command instead. For
-    // example, "backout_description" template will be rendered against the
-    // commit to be backed out, and the generated description could be set
-    // without spawning editor.
+    // We might want default description templates per command instead, like
+    // "backout_description".
 
     // Named as "draft" because the output can contain "JJ:" comment lines.
     let template_key = "templates.draft_commit_description";
@@ -253,12 +339,29 @@ pub fn description_template(
     Ok(output.into_string_lossy())
 }
 
+/// Renders the description of the commit that backs out `commit`.
+pub fn backout_description(
+    ui: &Ui,
+    tx: &WorkspaceCommandTransaction,
+    commit: &Commit,
+) -> Result<String, CommandError> {
+    let template_text = tx.settings().get_string("templates.backout_description")?;
+    let template = tx.parse_commit_template(ui, &template_text)?;
+
+    let mut output = Vec::new();
+    template
+        .format(commit, &mut PlainTextFormatter::new(&mut output))
+        .expect("write() to vec backed formatter should never fail");
+    Ok(output.into_string_lossy())
+}
+
 #[cfg(test)]
 mod tests {
     use indexmap::indexmap;
     use indoc::indoc;
     use maplit::hashmap;
 
+    use super::find_placeholders;
     use super::parse_bulk_edit_message;
     use crate::description_util::ParseBulkEditMessageError;
 
@@ -412,4 +515,18 @@ mod tests {
         assert!(result.duplicates.is_empty());
         assert!(result.unexpected.is_empty());
     }
+
+    #[test]
+    fn test_find_placeholders() {
+        let placeholders = |text| find_placeholders(text).collect::<Vec<_>>();
+        assert_eq!(placeholders(""), Vec::<&str>::new());
+        assert_eq!(placeholders("no placeholders"), Vec::<&str>::new());
+        assert_eq!(
+            placeholders("{{summary}}\n\nTesting: {{how was it tested}}\n"),
+            vec!["{{summary}}", "{{how was it tested}}"]
+        );
+        assert_eq!(placeholders("{{a}}{{b}}"), vec!["{{a}}", "{{b}}"]);
+        // Unterminated placeholder
+        assert_eq!(placeholders("{{a}} {{b"), vec!["{{a}}"]);
+    }
 }
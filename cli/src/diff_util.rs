@@ -16,6 +16,7 @@ use std::borrow::Borrow;
 use std::cmp::max;
 use std::collections::HashSet;
 use std::io;
+use std::io::Read as _;
 use std::mem;
 use std::ops::Range;
 use std::path::Path;
@@ -858,20 +859,32 @@ impl FileContent {
     }
 }
 
+// Determine whether a file is binary by whether the first 8k bytes contain a
+// null character; this is the same heuristic used by git as of writing: https://github.com/git/git/blob/eea0e59ffbed6e33d171ace5be13cde9faa41639/xdiff-interface.c#L192-L198
+const BINARY_PEEK_SIZE: usize = 8000;
+
+fn is_binary_content(contents: &[u8]) -> bool {
+    contents[..BINARY_PEEK_SIZE.min(contents.len())].contains(&b'\0')
+}
+
+/// Returns true if the file looks binary. Only the start of the file is read.
+pub(crate) fn is_binary_file(reader: &mut dyn io::Read) -> io::Result<bool> {
+    let mut start = vec![];
+    reader
+        .take(BINARY_PEEK_SIZE as u64)
+        .read_to_end(&mut start)?;
+    Ok(is_binary_content(&start))
+}
+
 fn file_content_for_diff(reader: &mut dyn io::Read) -> io::Result<FileContent> {
     // If this is a binary file, don't show the full contents.
-    // Determine whether it's binary by whether the first 8k bytes contain a null
-    // character; this is the same heuristic used by git as of writing: https://github.com/git/git/blob/eea0e59ffbed6e33d171ace5be13cde9faa41639/xdiff-interface.c#L192-L198
-    const PEEK_SIZE: usize = 8000;
     // TODO: currently we look at the whole file, even though for binary files we
     // only need to know the file size. To change that we'd have to extend all
     // the data backends to support getting the length.
     let mut contents = vec![];
     reader.read_to_end(&mut contents)?;
-
-    let start = &contents[..PEEK_SIZE.min(contents.len())];
     Ok(FileContent {
-        is_binary: start.contains(&b'\0'),
+        is_binary: is_binary_content(&contents),
         contents,
     })
 }
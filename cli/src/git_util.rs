@@ -23,8 +23,10 @@ use std::path::PathBuf;
 use std::process::Stdio;
 use std::time::Instant;
 
+use itertools::chain;
 use itertools::Itertools;
 use jj_lib::git;
+use jj_lib::git::BranchPatterns;
 use jj_lib::git::FailedRefExport;
 use jj_lib::git::FailedRefExportReason;
 use jj_lib::git::GitFetchError;
@@ -35,6 +37,7 @@ use jj_lib::op_store::RefTarget;
 use jj_lib::op_store::RemoteRef;
 use jj_lib::repo::ReadonlyRepo;
 use jj_lib::repo::Repo;
+use jj_lib::settings::GitSettings;
 use jj_lib::store::Store;
 use jj_lib::str_util::StringPattern;
 use jj_lib::workspace::Workspace;
@@ -288,38 +291,53 @@ pub fn print_git_import_stats(
     stats: &GitImportStats,
     show_ref_stats: bool,
 ) -> Result<(), CommandError> {
-    let Some(mut formatter) = ui.status_formatter() else {
-        return Ok(());
-    };
-    if show_ref_stats {
-        let refs_stats = stats
-            .changed_remote_refs
-            .iter()
-            .map(|(ref_name, (remote_ref, ref_target))| {
-                RefStatus::new(ref_name, remote_ref, ref_target, repo)
-            })
-            .collect_vec();
+    if let Some(mut formatter) = ui.status_formatter() {
+        if show_ref_stats {
+            let refs_stats = stats
+                .changed_remote_refs
+                .iter()
+                .map(|(ref_name, (remote_ref, ref_target))| {
+                    RefStatus::new(ref_name, remote_ref, ref_target, repo)
+                })
+                .collect_vec();
 
-        let has_both_ref_kinds = refs_stats
-            .iter()
-            .any(|x| matches!(x.ref_kind, RefKind::Branch))
-            && refs_stats
+            let has_both_ref_kinds = refs_stats
                 .iter()
-                .any(|x| matches!(x.ref_kind, RefKind::Tag));
+                .any(|x| matches!(x.ref_kind, RefKind::Branch))
+                && refs_stats
+                    .iter()
+                    .any(|x| matches!(x.ref_kind, RefKind::Tag));
 
-        let max_width = refs_stats.iter().map(|x| x.ref_name.width()).max();
-        if let Some(max_width) = max_width {
-            for status in refs_stats {
-                status.output(max_width, has_both_ref_kinds, &mut *formatter)?;
+            let max_width = refs_stats.iter().map(|x| x.ref_name.width()).max();
+            if let Some(max_width) = max_width {
+                for status in refs_stats {
+                    status.output(max_width, has_both_ref_kinds, &mut *formatter)?;
+                }
             }
         }
+
+        if !stats.abandoned_commits.is_empty() {
+            writeln!(
+                formatter,
+                "Abandoned {} commits that are no longer reachable.",
+                stats.abandoned_commits.len()
+            )?;
+        }
     }
 
-    if !stats.abandoned_commits.is_empty() {
+    for (name, remote) in &stats.rewritten_remote_bookmarks {
+        if !repo.view().get_local_bookmark(name).has_conflict() {
+            continue;
+        }
         writeln!(
-            formatter,
-            "Abandoned {} commits that are no longer reachable.",
-            stats.abandoned_commits.len()
+            ui.warning_default(),
+            "Remote bookmark {name}@{remote} was rewritten, and local bookmark {name} now has \
+             conflicts."
+        )?;
+        writeln!(
+            ui.hint_default(),
+            "Use `jj bookmark rebase-onto-remote {name} --remote {remote}` to rebase the local \
+             commits onto the new remote target."
         )?;
     }
 
@@ -461,26 +479,24 @@ pub fn git_fetch(
     tx: &mut WorkspaceCommandTransaction,
     git_repo: &git2::Repository,
     remotes: &[String],
-    branch: &[StringPattern],
+    branches: &BranchPatterns,
+    git_settings: &GitSettings,
 ) -> Result<(), CommandError> {
-    let git_settings = tx.settings().git_settings()?;
-
     for remote in remotes {
         let stats = with_remote_git_callbacks(ui, None, |cb| {
             git::fetch(
                 tx.repo_mut(),
                 git_repo,
                 remote,
-                branch,
+                branches,
                 cb,
-                &git_settings,
+                git_settings,
                 None,
             )
         })
         .map_err(|err| match err {
             GitFetchError::InvalidBranchPattern => {
-                if branch
-                    .iter()
+                if chain(&branches.included, &branches.excluded)
                     .any(|pattern| pattern.as_exact().is_some_and(|s| s.contains('*')))
                 {
                     user_error_with_hint(
@@ -500,7 +516,7 @@ pub fn git_fetch(
     warn_if_branches_not_found(
         ui,
         tx,
-        branch,
+        &branches.included,
         &remotes.iter().map(StringPattern::exact).collect_vec(),
     )
 }
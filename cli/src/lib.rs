@@ -18,6 +18,7 @@ pub mod cleanup_guard;
 pub mod cli_util;
 pub mod command_error;
 pub mod commands;
+pub mod commit_classes;
 pub mod commit_templater;
 pub mod complete;
 pub mod config;
@@ -28,6 +29,7 @@ pub mod generic_templater;
 pub mod git_util;
 pub mod graphlog;
 pub mod merge_tools;
+pub mod metrics;
 pub mod movement_util;
 pub mod operation_templater;
 mod progress;
@@ -0,0 +1,124 @@
+// Copyright 2024 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use bstr::BString;
+use jj_lib::backend::MergedTreeId;
+use jj_lib::backend::TreeValue;
+use jj_lib::conflicts::extract_as_single_hunk;
+use jj_lib::files;
+use jj_lib::files::MergeResult;
+use jj_lib::merge::Merge;
+use jj_lib::merge::MergedTreeValue;
+use jj_lib::merged_tree::MergedTree;
+use jj_lib::merged_tree::MergedTreeBuilder;
+use jj_lib::repo_path::RepoPath;
+use pollster::FutureExt as _;
+
+use super::ConflictResolveError;
+
+/// Built-in strategy to resolve conflicts without user interaction.
+///
+/// Conflicts between regular files are resolved hunk by hunk, so changes which
+/// don't conflict are kept from all sides.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum AutoResolution {
+    /// Picks the first side of each conflicting hunk.
+    Ours,
+    /// Picks the last side of each conflicting hunk.
+    Theirs,
+    /// Keeps the lines of all sides of each conflicting hunk, in order.
+    Union,
+}
+
+/// Resolves the conflicts at `repo_paths` in `tree` using `resolution`.
+pub fn resolve_conflicts_auto(
+    tree: &MergedTree,
+    repo_paths: &[&RepoPath],
+    resolution: AutoResolution,
+) -> Result<MergedTreeId, ConflictResolveError> {
+    let mut tree_builder = MergedTreeBuilder::new(tree.id());
+    for &repo_path in repo_paths {
+        let value = resolve_file(tree, repo_path, resolution)?;
+        tree_builder.set_or_remove(repo_path.to_owned(), value);
+    }
+    Ok(tree_builder.write_tree(tree.store())?)
+}
+
+fn resolve_file(
+    tree: &MergedTree,
+    repo_path: &RepoPath,
+    resolution: AutoResolution,
+) -> Result<MergedTreeValue, ConflictResolveError> {
+    let conflict = match tree.path_value(repo_path)?.into_resolved() {
+        Err(conflict) => conflict.simplify(),
+        Ok(Some(_)) => return Err(ConflictResolveError::NotAConflict(repo_path.to_owned())),
+        Ok(None) => return Err(ConflictResolveError::PathNotFound(repo_path.to_owned())),
+    };
+    if let Some(value) = conflict.as_resolved() {
+        return Ok(Merge::resolved(value.clone()));
+    }
+    let Some(file_merge) = conflict.to_file_merge() else {
+        // Values other than regular files can't be merged hunk by hunk, so
+        // the whole value of one side is picked.
+        return match resolution {
+            AutoResolution::Ours => Ok(Merge::resolved(conflict.first().clone())),
+            AutoResolution::Theirs => Ok(Merge::resolved(conflict.adds().last().unwrap().clone())),
+            AutoResolution::Union => {
+                let summary = conflict.describe();
+                Err(ConflictResolveError::NotNormalFiles(
+                    repo_path.to_owned(),
+                    summary,
+                ))
+            }
+        };
+    };
+    let content = extract_as_single_hunk(&file_merge, tree.store(), repo_path).block_on()?;
+    let merged: BString = match files::merge(&content) {
+        MergeResult::Resolved(merged) => merged,
+        MergeResult::Conflict(hunks) => hunks
+            .iter()
+            .flat_map(|hunk| match hunk.as_resolved() {
+                Some(content) => vec![content],
+                None => pick_sides(hunk, resolution),
+            })
+            .map(|content| content.as_slice())
+            .collect::<Vec<_>>()
+            .concat()
+            .into(),
+    };
+    let executable = conflict
+        .to_executable_merge()
+        .and_then(|executable| match executable.resolve_trivial() {
+            Some(&executable) => Some(executable),
+            // The executable bit of the first picked side wins.
+            None => pick_sides(&executable, resolution)
+                .first()
+                .map(|&&executable| executable),
+        })
+        .unwrap_or(false);
+    let id = tree
+        .store()
+        .write_file(repo_path, &mut merged.as_slice())
+        .block_on()?;
+    Ok(Merge::normal(TreeValue::File { id, executable }))
+}
+
+/// Returns the sides of the conflicting `merge` to keep.
+fn pick_sides<T>(merge: &Merge<T>, resolution: AutoResolution) -> Vec<&T> {
+    match resolution {
+        AutoResolution::Ours => vec![merge.first()],
+        AutoResolution::Theirs => vec![merge.adds().last().unwrap()],
+        AutoResolution::Union => merge.adds().collect(),
+    }
+}
@@ -9,19 +9,23 @@ use std::sync::Arc;
 use futures::StreamExt;
 use jj_lib::backend::MergedTreeId;
 use jj_lib::conflicts::ConflictMarkerStyle;
+use jj_lib::conflicts::ConflictMaterializers;
 use jj_lib::fsmonitor::FsmonitorSettings;
 use jj_lib::gitignore::GitIgnoreFile;
 use jj_lib::local_working_copy::TreeState;
 use jj_lib::local_working_copy::TreeStateError;
 use jj_lib::matchers::EverythingMatcher;
 use jj_lib::matchers::Matcher;
+use jj_lib::matchers::NothingMatcher;
 use jj_lib::merged_tree::MergedTree;
 use jj_lib::merged_tree::TreeDiffEntry;
-use jj_lib::repo_path::RepoPathBuf;
+use jj_lib::sparse::SparsePattern;
 use jj_lib::store::Store;
 use jj_lib::working_copy::CheckoutError;
 use jj_lib::working_copy::CheckoutOptions;
+use jj_lib::working_copy::EolConversionMode;
 use jj_lib::working_copy::SnapshotOptions;
+use jj_lib::working_copy::SymlinkMode;
 use pollster::FutureExt;
 use tempfile::TempDir;
 use thiserror::Error;
@@ -85,7 +89,7 @@ fn check_out(
     wc_dir: PathBuf,
     state_dir: PathBuf,
     tree: &MergedTree,
-    sparse_patterns: Vec<RepoPathBuf>,
+    sparse_patterns: Vec<SparsePattern>,
     options: &CheckoutOptions,
 ) -> Result<TreeState, DiffCheckoutError> {
     std::fs::create_dir(&wc_dir).map_err(DiffCheckoutError::SetUpDir)?;
@@ -142,7 +146,7 @@ pub(crate) fn check_out_trees(
 ) -> Result<DiffWorkingCopies, DiffCheckoutError> {
     let changed_files: Vec<_> = left_tree
         .diff_stream(right_tree, matcher)
-        .map(|TreeDiffEntry { path, .. }| path)
+        .map(|TreeDiffEntry { path, .. }| SparsePattern::prefix(path))
         .collect()
         .block_on();
 
@@ -298,7 +302,13 @@ diff editing in mind and be a little inaccurate.
             progress: None,
             start_tracking_matcher: &EverythingMatcher,
             max_new_file_size: u64::MAX,
+            max_new_file_size_overrides: vec![],
             conflict_marker_style,
+            conflict_materializers: ConflictMaterializers::default(),
+            symlinks: SymlinkMode::default(),
+            eol_conversion: EolConversionMode::default(),
+            untracked_cache: false,
+            refresh_matcher: &NothingMatcher,
         })?;
         Ok(output_tree_state.current_tree_id().clone())
     }
@@ -11,10 +11,12 @@ use itertools::Itertools;
 use jj_lib::backend::FileId;
 use jj_lib::backend::MergedTreeId;
 use jj_lib::backend::TreeValue;
+use jj_lib::conflict_cache::MaterializedConflictCache;
 use jj_lib::conflicts;
 use jj_lib::conflicts::choose_materialized_conflict_marker_len;
 use jj_lib::conflicts::materialize_merge_result_to_bytes_with_marker_len;
 use jj_lib::conflicts::ConflictMarkerStyle;
+use jj_lib::conflicts::ConflictMaterializers;
 use jj_lib::conflicts::MIN_CONFLICT_MARKER_LEN;
 use jj_lib::gitignore::GitIgnoreFile;
 use jj_lib::matchers::Matcher;
@@ -24,6 +26,8 @@ use jj_lib::merged_tree::MergedTree;
 use jj_lib::merged_tree::MergedTreeBuilder;
 use jj_lib::repo_path::RepoPath;
 use jj_lib::working_copy::CheckoutOptions;
+use jj_lib::working_copy::EolConversionMode;
+use jj_lib::working_copy::SymlinkMode;
 use pollster::FutureExt;
 use thiserror::Error;
 
@@ -170,6 +174,7 @@ pub enum ExternalToolError {
     Io(#[source] std::io::Error),
 }
 
+#[allow(clippy::too_many_arguments)]
 pub fn run_mergetool_external(
     editor: &ExternalMergeTool,
     file_merge: Merge<Option<FileId>>,
@@ -178,6 +183,7 @@ pub fn run_mergetool_external(
     conflict: MergedTreeValue,
     tree: &MergedTree,
     default_conflict_marker_style: ConflictMarkerStyle,
+    conflict_cache: Option<&MaterializedConflictCache>,
 ) -> Result<MergedTreeId, ConflictResolveError> {
     let conflict_marker_style = editor
         .conflict_marker_style
@@ -192,14 +198,16 @@ pub fn run_mergetool_external(
     } else {
         MIN_CONFLICT_MARKER_LEN
     };
-    let initial_output_content = if editor.merge_tool_edits_conflict_markers {
+    let initial_output_content = if !editor.merge_tool_edits_conflict_markers {
+        BString::default()
+    } else if let Some(cache) = conflict_cache {
+        cache.materialize(&file_merge, &content, conflict_marker_style, None)
+    } else {
         materialize_merge_result_to_bytes_with_marker_len(
             &content,
             conflict_marker_style,
             conflict_marker_len,
         )
-    } else {
-        BString::default()
     };
     assert_eq!(content.num_sides(), 2);
     let files: HashMap<&str, &[u8]> = maplit::hashmap! {
@@ -320,6 +328,10 @@ pub fn edit_diff_external(
         .unwrap_or(default_conflict_marker_style);
     let options = CheckoutOptions {
         conflict_marker_style,
+        conflict_materializers: ConflictMaterializers::default(),
+        progress: None,
+        symlinks: SymlinkMode::default(),
+        eol_conversion: EolConversionMode::default(),
     };
 
     let got_output_field = find_all_variables(&editor.edit_args).contains(&"output");
@@ -368,6 +380,10 @@ pub fn generate_diff(
         .unwrap_or(default_conflict_marker_style);
     let options = CheckoutOptions {
         conflict_marker_style,
+        conflict_materializers: ConflictMaterializers::default(),
+        progress: None,
+        symlinks: SymlinkMode::default(),
+        eol_conversion: EolConversionMode::default(),
     };
     let store = left_tree.store();
     let diff_wc = check_out_trees(store, left_tree, right_tree, matcher, None, &options)?;
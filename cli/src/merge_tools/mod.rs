@@ -12,21 +12,31 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+mod auto_resolve;
 mod builtin;
 mod diff_working_copies;
 mod external;
+mod selection;
 
 use std::sync::Arc;
+use std::thread;
+use std::thread::JoinHandle;
 
+use itertools::Itertools as _;
+use jj_lib::backend::BackendResult;
 use jj_lib::backend::MergedTreeId;
 use jj_lib::config::ConfigGetError;
 use jj_lib::config::ConfigGetResultExt as _;
 use jj_lib::config::ConfigNamePathBuf;
+use jj_lib::conflict_cache::MaterializedConflictCache;
 use jj_lib::conflicts::extract_as_single_hunk;
 use jj_lib::conflicts::ConflictMarkerStyle;
 use jj_lib::gitignore::GitIgnoreFile;
 use jj_lib::matchers::Matcher;
+use jj_lib::merge::Merge;
+use jj_lib::merge::MergedTreeValue;
 use jj_lib::merged_tree::MergedTree;
+use jj_lib::merged_tree::MergedTreeBuilder;
 use jj_lib::repo_path::InvalidRepoPathError;
 use jj_lib::repo_path::RepoPath;
 use jj_lib::repo_path::RepoPathBuf;
@@ -35,6 +45,8 @@ use jj_lib::working_copy::SnapshotError;
 use pollster::FutureExt;
 use thiserror::Error;
 
+use self::auto_resolve::resolve_conflicts_auto;
+pub use self::auto_resolve::AutoResolution;
 use self::builtin::edit_diff_builtin;
 use self::builtin::edit_merge_builtin;
 use self::builtin::BuiltinToolError;
@@ -46,10 +58,16 @@ pub use self::external::invoke_external_diff;
 pub use self::external::DiffToolMode;
 pub use self::external::ExternalMergeTool;
 use self::external::ExternalToolError;
+pub use self::selection::parse_hunk_indices;
+pub use self::selection::DiffSelection;
+pub use self::selection::DiffSelectionError;
 use crate::config::CommandNameAndArgs;
 use crate::ui::Ui;
 
 const BUILTIN_EDITOR_NAME: &str = ":builtin";
+const OURS_TOOL_NAME: &str = ":ours";
+const THEIRS_TOOL_NAME: &str = ":theirs";
+const UNION_TOOL_NAME: &str = ":union";
 
 #[derive(Debug, Error)]
 pub enum DiffEditError {
@@ -90,8 +108,6 @@ pub enum ConflictResolveError {
          supported. Conflict summary for {0:?}:\n{1}"
     )]
     NotNormalFiles(RepoPathBuf, String),
-    #[error("The conflict at {path:?} has {sides} sides. At most 2 sides are supported.")]
-    ConflictTooComplicated { path: RepoPathBuf, sides: usize },
     #[error(
         "The output file is either unchanged or empty after the editor quit (run with --debug to \
          see the exact invocation)."
@@ -107,11 +123,15 @@ pub enum MergeToolConfigError {
     Config(#[from] ConfigGetError),
     #[error("The tool `{tool_name}` cannot be used as a merge tool with `jj resolve`")]
     MergeArgsNotConfigured { tool_name: String },
+    #[error("The tool `{tool_name}` cannot be used as a diff editor")]
+    NotADiffEditor { tool_name: String },
 }
 
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub enum MergeTool {
     Builtin,
+    /// Resolves conflicts without user interaction. Only usable for merges.
+    Auto(AutoResolution),
     // Boxed because ExternalMergeTool is big compared to the Builtin variant.
     External(Box<ExternalMergeTool>),
 }
@@ -150,10 +170,12 @@ fn get_tool_config(
     settings: &UserSettings,
     name: &str,
 ) -> Result<Option<MergeTool>, ConfigGetError> {
-    if name == BUILTIN_EDITOR_NAME {
-        Ok(Some(MergeTool::Builtin))
-    } else {
-        Ok(get_external_tool_config(settings, name)?.map(MergeTool::external))
+    match name {
+        BUILTIN_EDITOR_NAME => Ok(Some(MergeTool::Builtin)),
+        OURS_TOOL_NAME => Ok(Some(MergeTool::Auto(AutoResolution::Ours))),
+        THEIRS_TOOL_NAME => Ok(Some(MergeTool::Auto(AutoResolution::Theirs))),
+        UNION_TOOL_NAME => Ok(Some(MergeTool::Auto(AutoResolution::Union))),
+        _ => Ok(get_external_tool_config(settings, name)?.map(MergeTool::external)),
     }
 }
 
@@ -192,7 +214,7 @@ impl DiffEditor {
     ) -> Result<Self, MergeToolConfigError> {
         let tool = get_tool_config(settings, name)?
             .unwrap_or_else(|| MergeTool::external(ExternalMergeTool::with_program(name)));
-        Self::new_inner(tool, settings, base_ignores, conflict_marker_style)
+        Self::new_inner(name, tool, settings, base_ignores, conflict_marker_style)
     }
 
     /// Loads the default diff editor from the settings.
@@ -209,15 +231,21 @@ impl DiffEditor {
             None
         }
         .unwrap_or_else(|| MergeTool::external(ExternalMergeTool::with_edit_args(&args)));
-        Self::new_inner(tool, settings, base_ignores, conflict_marker_style)
+        Self::new_inner(&args, tool, settings, base_ignores, conflict_marker_style)
     }
 
     fn new_inner(
+        name: impl ToString,
         tool: MergeTool,
         settings: &UserSettings,
         base_ignores: Arc<GitIgnoreFile>,
         conflict_marker_style: ConflictMarkerStyle,
     ) -> Result<Self, MergeToolConfigError> {
+        if matches!(tool, MergeTool::Auto(_)) {
+            return Err(MergeToolConfigError::NotADiffEditor {
+                tool_name: name.to_string(),
+            });
+        }
         Ok(DiffEditor {
             tool,
             base_ignores,
@@ -241,6 +269,8 @@ impl DiffEditor {
                         .map_err(Box::new)?,
                 )
             }
+            // Rejected when the diff editor is created
+            MergeTool::Auto(_) => unreachable!(),
             MergeTool::External(editor) => {
                 let instructions = self.use_instructions.then(format_instructions);
                 edit_diff_external(
@@ -262,6 +292,7 @@ impl DiffEditor {
 pub struct MergeEditor {
     tool: MergeTool,
     conflict_marker_style: ConflictMarkerStyle,
+    conflict_cache: Option<Arc<MaterializedConflictCache>>,
 }
 
 impl MergeEditor {
@@ -306,15 +337,86 @@ impl MergeEditor {
         Ok(MergeEditor {
             tool,
             conflict_marker_style,
+            conflict_cache: None,
         })
     }
 
+    /// Makes the editor reuse materialized conflicts from the `cache`, and
+    /// store newly materialized ones in it.
+    pub fn with_conflict_cache(self, cache: Arc<MaterializedConflictCache>) -> Self {
+        MergeEditor {
+            conflict_cache: Some(cache),
+            ..self
+        }
+    }
+
+    /// Materializes the conflicts at the `repo_paths` into the conflict cache
+    /// on a background thread, so that launching the editor for them later is
+    /// faster. Returns `None` if there's nothing to do, e.g. because the tool
+    /// doesn't get materialized conflicts as input.
+    ///
+    /// The returned thread should be joined before the process exits.
+    pub fn prematerialize_in_background(
+        &self,
+        tree: &MergedTree,
+        repo_paths: Vec<RepoPathBuf>,
+    ) -> Option<JoinHandle<()>> {
+        let cache = self.conflict_cache.clone()?;
+        let MergeTool::External(editor) = &self.tool else {
+            return None;
+        };
+        if !editor.merge_tool_edits_conflict_markers || repo_paths.is_empty() {
+            return None;
+        }
+        let conflict_marker_style = editor
+            .conflict_marker_style
+            .unwrap_or(self.conflict_marker_style);
+        let tree = tree.clone();
+        let handle = thread::spawn(move || {
+            for repo_path in &repo_paths {
+                let result =
+                    prematerialize_conflict(&tree, repo_path, &cache, conflict_marker_style);
+                if let Err(err) = result {
+                    tracing::warn!(?err, ?repo_path, "failed to pre-materialize conflict");
+                }
+            }
+        });
+        Some(handle)
+    }
+
+    /// Returns true if the tool resolves conflicts without user interaction,
+    /// so all conflicts can be resolved at once.
+    pub fn is_automatic(&self) -> bool {
+        matches!(self.tool, MergeTool::Auto(_))
+    }
+
+    /// Resolves the conflicts at the specified files, starting a merge editor
+    /// for each file in turn unless the tool is automatic.
+    pub fn edit_files(
+        &self,
+        tree: &MergedTree,
+        repo_paths: &[&RepoPath],
+    ) -> Result<MergedTreeId, ConflictResolveError> {
+        if let MergeTool::Auto(resolution) = self.tool {
+            return resolve_conflicts_auto(tree, repo_paths, resolution);
+        }
+        let mut tree = tree.clone();
+        for &repo_path in repo_paths {
+            let tree_id = self.edit_file(&tree, repo_path)?;
+            tree = tree.store().get_root_tree(&tree_id)?;
+        }
+        Ok(tree.id())
+    }
+
     /// Starts a merge editor for the specified file.
     pub fn edit_file(
         &self,
         tree: &MergedTree,
         repo_path: &RepoPath,
     ) -> Result<MergedTreeId, ConflictResolveError> {
+        if let MergeTool::Auto(resolution) = self.tool {
+            return resolve_conflicts_auto(tree, &[repo_path], resolution);
+        }
         let conflict = match tree.path_value(repo_path)?.into_resolved() {
             Err(conflict) => conflict,
             Ok(Some(_)) => return Err(ConflictResolveError::NotAConflict(repo_path.to_owned())),
@@ -325,12 +427,9 @@ impl MergeEditor {
             ConflictResolveError::NotNormalFiles(repo_path.to_owned(), summary)
         })?;
         let simplified_file_merge = file_merge.clone().simplify();
-        // We only support conflicts with 2 sides (3-way conflicts)
+        // The tools only support conflicts with 2 sides (3-way conflicts)
         if simplified_file_merge.num_sides() > 2 {
-            return Err(ConflictResolveError::ConflictTooComplicated {
-                path: repo_path.to_owned(),
-                sides: simplified_file_merge.num_sides(),
-            });
+            return self.edit_file_pairwise(tree, repo_path, conflict.simplify());
         };
         let content =
             extract_as_single_hunk(&simplified_file_merge, tree.store(), repo_path).block_on()?;
@@ -340,6 +439,8 @@ impl MergeEditor {
                 let tree_id = edit_merge_builtin(tree, repo_path, content).map_err(Box::new)?;
                 Ok(tree_id)
             }
+            // Handled above
+            MergeTool::Auto(_) => unreachable!(),
             MergeTool::External(editor) => external::run_mergetool_external(
                 editor,
                 file_merge,
@@ -348,9 +449,83 @@ impl MergeEditor {
                 conflict,
                 tree,
                 self.conflict_marker_style,
+                self.conflict_cache.as_deref(),
             ),
         }
     }
+
+    /// Resolves a conflict with more than 2 sides one side at a time. The
+    /// first two sides are merged with their base first, then the result is
+    /// merged with the next side, and so on. If the tool leaves a step
+    /// unresolved, the remaining sides are kept in the conflict.
+    fn edit_file_pairwise(
+        &self,
+        tree: &MergedTree,
+        repo_path: &RepoPath,
+        conflict: MergedTreeValue,
+    ) -> Result<MergedTreeId, ConflictResolveError> {
+        let store = tree.store();
+        let mut value = conflict;
+        while !value.is_resolved() {
+            let mut terms = value.into_iter().collect_vec();
+            let rest = terms.split_off(3);
+            let mut pair = Merge::from_vec(terms).simplify();
+            if !pair.is_resolved() {
+                // Write the pair to an otherwise empty tree. Other paths in `tree` may
+                // have more sides, which would be merged into the file's conflict.
+                let empty_tree_id = MergedTreeId::resolved(store.empty_tree_id().clone());
+                let mut tree_builder = MergedTreeBuilder::new(empty_tree_id);
+                tree_builder.set_or_remove(repo_path.to_owned(), pair);
+                let pair_tree = store.get_root_tree(&tree_builder.write_tree(store)?)?;
+                let result_tree_id = self.edit_file(&pair_tree, repo_path)?;
+                pair = store
+                    .get_root_tree(&result_tree_id)?
+                    .path_value(repo_path)?;
+            }
+            let is_pair_resolved = pair.is_resolved();
+            value = Merge::from_vec(pair.into_iter().chain(rest).collect_vec()).simplify();
+            if !is_pair_resolved {
+                break;
+            }
+        }
+        let mut tree_builder = MergedTreeBuilder::new(tree.id());
+        tree_builder.set_or_remove(repo_path.to_owned(), value);
+        Ok(tree_builder.write_tree(store)?)
+    }
+}
+
+/// Materializes the conflict at `repo_path` the way
+/// [`MergeEditor::edit_file()`] passes it to an external tool, and stores it in
+/// the `cache`. Conflicts the tools can't handle are skipped.
+fn prematerialize_conflict(
+    tree: &MergedTree,
+    repo_path: &RepoPath,
+    cache: &MaterializedConflictCache,
+    conflict_marker_style: ConflictMarkerStyle,
+) -> BackendResult<()> {
+    let Err(conflict) = tree.path_value(repo_path)?.into_resolved() else {
+        return Ok(());
+    };
+    let Some(file_merge) = conflict.to_file_merge() else {
+        return Ok(());
+    };
+    let simplified_file_merge = file_merge.simplify();
+    if simplified_file_merge.num_sides() > 2
+        || cache
+            .get(&simplified_file_merge, conflict_marker_style, None)
+            .is_some()
+    {
+        return Ok(());
+    }
+    let content =
+        extract_as_single_hunk(&simplified_file_merge, tree.store(), repo_path).block_on()?;
+    cache.materialize(
+        &simplified_file_merge,
+        &content,
+        conflict_marker_style,
+        None,
+    );
+    Ok(())
 }
 
 #[cfg(test)]
@@ -384,6 +559,11 @@ mod tests {
         };
 
         insta::assert_debug_snapshot!(get(":builtin", "").unwrap(), @"Builtin");
+        insta::assert_debug_snapshot!(get(":theirs", "").unwrap_err(), @r#"
+        NotADiffEditor {
+            tool_name: ":theirs",
+        }
+        "#);
 
         // Just program name, edit_args are filled by default
         insta::assert_debug_snapshot!(get("my diff", "").unwrap(), @r###"
@@ -620,6 +800,16 @@ mod tests {
         };
 
         insta::assert_debug_snapshot!(get(":builtin", "").unwrap(), @"Builtin");
+        insta::assert_debug_snapshot!(get(":ours", "").unwrap(), @r"
+        Auto(
+            Ours,
+        )
+        ");
+        insta::assert_debug_snapshot!(get(":union", "").unwrap(), @r"
+        Auto(
+            Union,
+        )
+        ");
 
         // Just program name
         insta::assert_debug_snapshot!(get("my diff", "").unwrap_err(), @r###"
@@ -0,0 +1,556 @@
+// Copyright 2024 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Non-interactive selection of changes to files and hunks.
+
+use std::collections::HashSet;
+use std::io::Read as _;
+use std::str;
+
+use bstr::ByteSlice as _;
+use futures::StreamExt as _;
+use jj_lib::backend::BackendError;
+use jj_lib::backend::MergedTreeId;
+use jj_lib::backend::TreeValue;
+use jj_lib::diff::Diff;
+use jj_lib::diff::DiffHunkKind;
+use jj_lib::matchers::FilesMatcher;
+use jj_lib::matchers::Matcher;
+use jj_lib::merge::Merge;
+use jj_lib::merged_tree::MergedTree;
+use jj_lib::merged_tree::MergedTreeBuilder;
+use jj_lib::merged_tree::TreeDiffEntry;
+use jj_lib::repo_path::RepoPath;
+use jj_lib::repo_path::RepoPathBuf;
+use pollster::FutureExt as _;
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum DiffSelectionError {
+    #[error("Invalid hunk list `{0}`")]
+    InvalidHunkList(String),
+    #[error("Failed to parse patch at line {line}: {message}")]
+    InvalidPatch { line: usize, message: String },
+    #[error("Path {0:?} is selected more than once")]
+    DuplicatePath(RepoPathBuf),
+    #[error("No changes to select in {0:?}")]
+    NoChanges(RepoPathBuf),
+    #[error("Cannot select hunks in {0:?}, which is not a regular file on both sides")]
+    NotAFile(RepoPathBuf),
+    #[error("Hunk {index} doesn't exist in {path:?}, which has {num_hunks} hunks")]
+    HunkOutOfRange {
+        path: RepoPathBuf,
+        index: usize,
+        num_hunks: usize,
+    },
+    #[error("Patch doesn't apply to {path:?} at line {line}")]
+    PatchMismatch { path: RepoPathBuf, line: usize },
+    #[error("Failed to read file {0:?}")]
+    ReadFile(RepoPathBuf, #[source] std::io::Error),
+    #[error(transparent)]
+    Backend(#[from] BackendError),
+}
+
+/// Changes selected by paths, hunk indices, or patches.
+#[derive(Clone, Debug, Default)]
+pub struct DiffSelection {
+    entries: Vec<(RepoPathBuf, SelectionEntry)>,
+}
+
+#[derive(Clone, Debug)]
+enum SelectionEntry {
+    /// All changes to the file.
+    File,
+    /// Changed line ranges of the file, numbered from 1.
+    Hunks(Vec<usize>),
+    /// Patch to be applied to the left side.
+    Patch(FilePatch),
+}
+
+#[derive(Clone, Debug)]
+struct FilePatch {
+    deleted: bool,
+    executable: Option<bool>,
+    hunks: Vec<PatchHunk>,
+}
+
+#[derive(Clone, Debug)]
+struct PatchHunk {
+    /// Line number in the patch, for error reporting.
+    line: usize,
+    /// Index of the first line to be replaced, counted from 0.
+    left_start: usize,
+    /// Tuples of line prefix (`' '`, `'-'`, or `'+'`) and content.
+    lines: Vec<(u8, Vec<u8>)>,
+}
+
+impl DiffSelection {
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Selects all changes to the file at `path`.
+    pub fn add_file(&mut self, path: RepoPathBuf) -> Result<(), DiffSelectionError> {
+        self.add_entry(path, SelectionEntry::File)
+    }
+
+    /// Selects the changed line ranges of the file at `path` by their
+    /// 1-based `indices`.
+    pub fn add_hunks(
+        &mut self,
+        path: RepoPathBuf,
+        indices: Vec<usize>,
+    ) -> Result<(), DiffSelectionError> {
+        self.add_entry(path, SelectionEntry::Hunks(indices))
+    }
+
+    /// Selects the changes described by the given Git-style unified diff.
+    pub fn add_patch(&mut self, patch: &[u8]) -> Result<(), DiffSelectionError> {
+        for (path, file_patch) in parse_patch(patch)? {
+            self.add_entry(path, SelectionEntry::Patch(file_patch))?;
+        }
+        Ok(())
+    }
+
+    fn add_entry(
+        &mut self,
+        path: RepoPathBuf,
+        entry: SelectionEntry,
+    ) -> Result<(), DiffSelectionError> {
+        if self.entries.iter().any(|(p, _)| *p == path) {
+            return Err(DiffSelectionError::DuplicatePath(path));
+        }
+        self.entries.push((path, entry));
+        Ok(())
+    }
+
+    /// Applies the selected changes from `left_tree` to `right_tree` to the
+    /// `left_tree`. Paths not matched by the `matcher` are ignored.
+    pub fn apply(
+        &self,
+        left_tree: &MergedTree,
+        right_tree: &MergedTree,
+        matcher: &dyn Matcher,
+    ) -> Result<MergedTreeId, DiffSelectionError> {
+        let store = left_tree.store();
+        let mut tree_builder = MergedTreeBuilder::new(left_tree.id());
+        for (path, entry) in &self.entries {
+            if !matcher.matches(path) {
+                continue;
+            }
+            match entry {
+                SelectionEntry::File => {
+                    let file_matcher = FilesMatcher::new([path]);
+                    let entry = left_tree
+                        .diff_stream(right_tree, &file_matcher)
+                        .next()
+                        .block_on();
+                    let Some(TreeDiffEntry { path, values }) = entry else {
+                        return Err(DiffSelectionError::NoChanges(path.clone()));
+                    };
+                    let (_left_value, right_value) = values?;
+                    tree_builder.set_or_remove(path, right_value);
+                }
+                SelectionEntry::Hunks(indices) => {
+                    let left = read_file(left_tree, path)?;
+                    let right = read_file(right_tree, path)?;
+                    let value = if let Some(content) =
+                        select_hunks(path, &left.content, &right.content, indices)?
+                    {
+                        let executable = left.executable.or(right.executable).unwrap_or(false);
+                        let id = store.write_file(path, &mut content.as_slice()).block_on()?;
+                        Merge::normal(TreeValue::File { id, executable })
+                    } else {
+                        // All hunks are selected
+                        right_tree.path_value(path)?
+                    };
+                    tree_builder.set_or_remove(path.clone(), value);
+                }
+                SelectionEntry::Patch(patch) => {
+                    let left = read_file(left_tree, path)?;
+                    let value = if patch.deleted {
+                        Merge::absent()
+                    } else {
+                        let content = apply_patch(path, &left.content, &patch.hunks)?;
+                        let executable = patch.executable.or(left.executable).unwrap_or(false);
+                        let id = store.write_file(path, &mut content.as_slice()).block_on()?;
+                        Merge::normal(TreeValue::File { id, executable })
+                    };
+                    tree_builder.set_or_remove(path.clone(), value);
+                }
+            }
+        }
+        Ok(tree_builder.write_tree(store)?)
+    }
+}
+
+/// Parses comma-separated list of 1-based hunk indices or ranges such as
+/// `1,3-4`.
+pub fn parse_hunk_indices(text: &str) -> Result<Vec<usize>, DiffSelectionError> {
+    let invalid = || DiffSelectionError::InvalidHunkList(text.to_owned());
+    let parse_index = |s: &str| match s.parse() {
+        Ok(0) | Err(_) => Err(invalid()),
+        Ok(n) => Ok(n),
+    };
+    let mut indices = vec![];
+    for item in text.split(',') {
+        if let Some((start, end)) = item.split_once('-') {
+            let (start, end) = (parse_index(start)?, parse_index(end)?);
+            if start > end {
+                return Err(invalid());
+            }
+            indices.extend(start..=end);
+        } else {
+            indices.push(parse_index(item)?);
+        }
+    }
+    Ok(indices)
+}
+
+struct FileContent {
+    /// `None` if the file is absent.
+    executable: Option<bool>,
+    content: Vec<u8>,
+}
+
+fn read_file(tree: &MergedTree, path: &RepoPath) -> Result<FileContent, DiffSelectionError> {
+    let value = tree.path_value(path)?;
+    match value.into_resolved() {
+        Ok(None) => Ok(FileContent {
+            executable: None,
+            content: vec![],
+        }),
+        Ok(Some(TreeValue::File { id, executable })) => {
+            let mut content = vec![];
+            tree.store()
+                .read_file(path, &id)?
+                .read_to_end(&mut content)
+                .map_err(|err| DiffSelectionError::ReadFile(path.to_owned(), err))?;
+            Ok(FileContent {
+                executable: Some(executable),
+                content,
+            })
+        }
+        _ => Err(DiffSelectionError::NotAFile(path.to_owned())),
+    }
+}
+
+/// Returns the left content with the selected hunks replaced by the right
+/// content, or `None` if all hunks are selected.
+fn select_hunks(
+    path: &RepoPath,
+    left: &[u8],
+    right: &[u8],
+    indices: &[usize],
+) -> Result<Option<Vec<u8>>, DiffSelectionError> {
+    let diff = Diff::by_line([left, right]);
+    let num_hunks = diff
+        .hunks()
+        .filter(|hunk| hunk.kind == DiffHunkKind::Different)
+        .count();
+    if let Some(&index) = indices.iter().find(|&&index| index > num_hunks) {
+        return Err(DiffSelectionError::HunkOutOfRange {
+            path: path.to_owned(),
+            index,
+            num_hunks,
+        });
+    }
+    let selected: HashSet<usize> = indices.iter().copied().collect();
+    if selected.len() == num_hunks {
+        return Ok(None);
+    }
+    let mut content = vec![];
+    let mut index = 0;
+    for hunk in diff.hunks() {
+        match hunk.kind {
+            DiffHunkKind::Matching => content.extend_from_slice(hunk.contents[0]),
+            DiffHunkKind::Different => {
+                index += 1;
+                let side = if selected.contains(&index) { 1 } else { 0 };
+                content.extend_from_slice(hunk.contents[side]);
+            }
+        }
+    }
+    Ok(Some(content))
+}
+
+fn apply_patch(
+    path: &RepoPath,
+    left: &[u8],
+    hunks: &[PatchHunk],
+) -> Result<Vec<u8>, DiffSelectionError> {
+    let left_lines = left.split_inclusive(|b| *b == b'\n').collect::<Vec<_>>();
+    let mut content = vec![];
+    let mut pos = 0;
+    for hunk in hunks {
+        let mismatch = || DiffSelectionError::PatchMismatch {
+            path: path.to_owned(),
+            line: hunk.line,
+        };
+        if hunk.left_start < pos || hunk.left_start > left_lines.len() {
+            return Err(mismatch());
+        }
+        content.extend(left_lines[pos..hunk.left_start].concat());
+        pos = hunk.left_start;
+        for (kind, line) in &hunk.lines {
+            if *kind != b'+' {
+                if left_lines.get(pos) != Some(&line.as_slice()) {
+                    return Err(mismatch());
+                }
+                pos += 1;
+            }
+            if *kind != b'-' {
+                content.extend_from_slice(line);
+            }
+        }
+    }
+    content.extend(left_lines[pos..].concat());
+    Ok(content)
+}
+
+fn parse_patch(patch: &[u8]) -> Result<Vec<(RepoPathBuf, FilePatch)>, DiffSelectionError> {
+    let mut files: Vec<(RepoPathBuf, FilePatch)> = vec![];
+    let mut lines = patch.lines_with_terminator().enumerate().peekable();
+    while let Some((i, line)) = lines.next() {
+        let line_number = i + 1;
+        let invalid = |message: &str| DiffSelectionError::InvalidPatch {
+            line: line_number,
+            message: message.to_owned(),
+        };
+        let text = line.trim_end_with(|c| c == '\n' || c == '\r');
+        if let Some(paths) = text.strip_prefix(b"diff --git ") {
+            let path = parse_git_header_path(paths).ok_or_else(|| invalid("unsupported path"))?;
+            let file_patch = FilePatch {
+                deleted: false,
+                executable: None,
+                hunks: vec![],
+            };
+            files.push((path, file_patch));
+            continue;
+        }
+        let Some((_, file_patch)) = files.last_mut() else {
+            // Ignore leading lines such as commit description
+            continue;
+        };
+        if let Some(mode) = text
+            .strip_prefix(b"new file mode ")
+            .or_else(|| text.strip_prefix(b"new mode "))
+        {
+            file_patch.executable = Some(mode == b"100755");
+        } else if text.starts_with(b"deleted file mode ") || text == b"+++ /dev/null" {
+            file_patch.deleted = true;
+        } else if text.starts_with(b"rename ") || text.starts_with(b"copy ") {
+            return Err(invalid("renames and copies are not supported"));
+        } else if text.starts_with(b"Binary files ") || text.starts_with(b"GIT binary patch") {
+            return Err(invalid("binary patches are not supported"));
+        } else if let Some(header) = text.strip_prefix(b"@@ ") {
+            let (left_start, left_len, right_len) =
+                parse_hunk_header(header).ok_or_else(|| invalid("invalid hunk header"))?;
+            let mut hunk = PatchHunk {
+                line: line_number,
+                // An empty range refers to the line before the insertion point
+                left_start: if left_len == 0 {
+                    left_start
+                } else {
+                    left_start.saturating_sub(1)
+                },
+                lines: vec![],
+            };
+            let (mut left_remaining, mut right_remaining) = (left_len, right_len);
+            while left_remaining > 0 || right_remaining > 0 {
+                let Some((_, line)) = lines.next() else {
+                    return Err(invalid("hunk is truncated"));
+                };
+                let (kind, content) = match line.split_first() {
+                    Some((b' ', content)) => (b' ', content),
+                    Some((b'-', content)) => (b'-', content),
+                    Some((b'+', content)) => (b'+', content),
+                    // Some editors strip trailing whitespace from empty context
+                    // lines
+                    Some((b'\n', _)) => (b' ', line),
+                    _ => return Err(invalid("hunk is truncated")),
+                };
+                if kind != b'+' {
+                    left_remaining = left_remaining
+                        .checked_sub(1)
+                        .ok_or_else(|| invalid("hunk has too many lines"))?;
+                }
+                if kind != b'-' {
+                    right_remaining = right_remaining
+                        .checked_sub(1)
+                        .ok_or_else(|| invalid("hunk has too many lines"))?;
+                }
+                hunk.lines.push((kind, content.to_vec()));
+                if lines.next_if(|(_, line)| line.starts_with(b"\\")).is_some() {
+                    // "\ No newline at end of file"
+                    let (_, content) = hunk.lines.last_mut().unwrap();
+                    if content.ends_with(b"\n") {
+                        content.pop();
+                    }
+                }
+            }
+            file_patch.hunks.push(hunk);
+        }
+    }
+    Ok(files)
+}
+
+/// Extracts `path` from `a/<path> b/<path>`.
+fn parse_git_header_path(paths: &[u8]) -> Option<RepoPathBuf> {
+    let paths = str::from_utf8(paths).ok()?;
+    let len = paths.len().checked_sub(5)? / 2;
+    let left = paths.get(..len + 2)?.strip_prefix("a/")?;
+    let right = paths.get(len + 2..)?.strip_prefix(" b/")?;
+    if left != right {
+        return None;
+    }
+    RepoPathBuf::from_relative_path(left).ok()
+}
+
+/// Parses `-<start>[,<len>] +<start>[,<len>] @@`, and returns the left start,
+/// left length, and right length.
+fn parse_hunk_header(header: &[u8]) -> Option<(usize, usize, usize)> {
+    let header = str::from_utf8(header).ok()?;
+    let (ranges, _) = header.split_once(" @@")?;
+    let (left, right) = ranges.split_once(' ')?;
+    let parse_range = |range: &str| -> Option<(usize, usize)> {
+        match range.split_once(',') {
+            Some((start, len)) => Some((start.parse().ok()?, len.parse().ok()?)),
+            None => Some((range.parse().ok()?, 1)),
+        }
+    };
+    let (left_start, left_len) = parse_range(left.strip_prefix('-')?)?;
+    let (_, right_len) = parse_range(right.strip_prefix('+')?)?;
+    Some((left_start, left_len, right_len))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn select(left: &str, right: &str, indices: &[usize]) -> String {
+        let path = RepoPath::from_internal_string("file");
+        let content = select_hunks(path, left.as_bytes(), right.as_bytes(), indices)
+            .unwrap()
+            .unwrap_or_else(|| right.as_bytes().to_vec());
+        String::from_utf8(content).unwrap()
+    }
+
+    fn patch(left: &str, patch: &str) -> String {
+        let files = parse_patch(patch.as_bytes()).unwrap();
+        let [(path, file_patch)] = &files[..] else {
+            panic!("expected one file: {files:?}");
+        };
+        let content = apply_patch(path, left.as_bytes(), &file_patch.hunks).unwrap();
+        String::from_utf8(content).unwrap()
+    }
+
+    #[test]
+    fn test_parse_hunk_indices() {
+        assert_eq!(parse_hunk_indices("1").unwrap(), vec![1]);
+        assert_eq!(parse_hunk_indices("3,1-2").unwrap(), vec![3, 1, 2]);
+        assert!(parse_hunk_indices("0").is_err());
+        assert!(parse_hunk_indices("2-1").is_err());
+        assert!(parse_hunk_indices("1,").is_err());
+    }
+
+    #[test]
+    fn test_select_hunks() {
+        let left = "a\nb\nc\nd\n";
+        let right = "a\nB\nc\nD\ne\n";
+        assert_eq!(select(left, right, &[1]), "a\nB\nc\nd\n");
+        assert_eq!(select(left, right, &[2]), "a\nb\nc\nD\ne\n");
+        assert_eq!(select(left, right, &[1, 2]), right);
+        assert!(matches!(
+            select_hunks(
+                RepoPath::from_internal_string("file"),
+                left.as_bytes(),
+                right.as_bytes(),
+                &[3]
+            ),
+            Err(DiffSelectionError::HunkOutOfRange { num_hunks: 2, .. })
+        ));
+    }
+
+    #[test]
+    fn test_apply_patch() {
+        let left = "a\nb\nc\nd\n";
+        let text = indoc::indoc! {"
+            diff --git a/file b/file
+            index 0000000000..1111111111 100644
+            --- a/file
+            +++ b/file
+            @@ -1,3 +1,3 @@
+             a
+            -b
+            +B
+             c
+        "};
+        assert_eq!(patch(left, text), "a\nB\nc\nd\n");
+
+        // Insertion and missing newline at end of file
+        let text = indoc::indoc! {"
+            diff --git a/file b/file
+            --- a/file
+            +++ b/file
+            @@ -0,0 +1 @@
+            +new
+            @@ -4 +5 @@
+            -d
+            +D
+            \\ No newline at end of file
+        "};
+        assert_eq!(patch(left, text), "new\na\nb\nc\nD");
+
+        // Context doesn't match
+        let text = indoc::indoc! {"
+            diff --git a/file b/file
+            --- a/file
+            +++ b/file
+            @@ -2 +2 @@
+            -x
+            +B
+        "};
+        let files = parse_patch(text.as_bytes()).unwrap();
+        assert!(matches!(
+            apply_patch(&files[0].0, left.as_bytes(), &files[0].1.hunks),
+            Err(DiffSelectionError::PatchMismatch { line: 4, .. })
+        ));
+    }
+
+    #[test]
+    fn test_parse_patch_headers() {
+        let text = indoc::indoc! {"
+            diff --git a/dir/new b/dir/new
+            new file mode 100755
+            index 0000000000..1111111111
+            --- /dev/null
+            +++ b/dir/new
+            @@ -0,0 +1 @@
+            +new
+            diff --git a/old b/old
+            deleted file mode 100644
+        "};
+        let files = parse_patch(text.as_bytes()).unwrap();
+        assert_eq!(files.len(), 2);
+        assert_eq!(files[0].0.as_internal_file_string(), "dir/new");
+        assert_eq!(files[0].1.executable, Some(true));
+        assert!(!files[0].1.deleted);
+        assert_eq!(files[1].0.as_internal_file_string(), "old");
+        assert!(files[1].1.deleted);
+
+        let text = "diff --git a/x b/y\nrename from x\n";
+        assert!(parse_patch(text.as_bytes()).is_err());
+    }
+}
@@ -0,0 +1,226 @@
+// Copyright 2024 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Opt-in local metrics about command execution.
+//!
+//! If `telemetry.metrics-file` is set, one JSON object is appended to the file
+//! per command. Only span names, durations, and numeric repo stats are
+//! recorded. Command arguments, paths, and other span fields are not, so the
+//! file can be shared without leaking repository contents.
+
+use std::collections::BTreeMap;
+use std::fs::OpenOptions;
+use std::io;
+use std::io::Write as _;
+use std::path::Path;
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::time::Duration;
+use std::time::Instant;
+use std::time::SystemTime;
+
+use serde::Serialize;
+use tracing::field::Field;
+use tracing::field::Visit;
+use tracing::span;
+use tracing::subscriber::Interest;
+use tracing::Event;
+use tracing::Metadata;
+use tracing::Subscriber;
+use tracing_subscriber::filter::DynFilterFn;
+use tracing_subscriber::layer::Context;
+use tracing_subscriber::layer::Filter;
+use tracing_subscriber::registry::LookupSpan;
+use tracing_subscriber::Layer;
+
+/// Target of events carrying repo stats to be recorded.
+pub const METRICS_TARGET: &str = "jj_cli::metrics";
+
+/// Tracing layer that aggregates span durations while enabled.
+#[derive(Clone, Debug, Default)]
+pub struct MetricsLayer {
+    enabled: Arc<AtomicBool>,
+    state: Arc<Mutex<MetricsState>>,
+}
+
+#[derive(Debug, Default)]
+struct MetricsState {
+    spans: BTreeMap<&'static str, SpanStats>,
+    repo: BTreeMap<&'static str, u64>,
+}
+
+/// Aggregated timing of spans of the same name.
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct SpanStats {
+    pub count: u64,
+    pub total_ms: f64,
+}
+
+/// Metrics recorded for a single command.
+#[derive(Clone, Debug, Serialize)]
+pub struct CommandMetrics {
+    /// Seconds since the Unix epoch.
+    pub timestamp: u64,
+    /// Subcommand names, e.g. "git fetch". Arguments aren't included.
+    pub command: String,
+    pub success: bool,
+    pub duration_ms: f64,
+    pub spans: BTreeMap<&'static str, SpanStats>,
+    pub repo: BTreeMap<&'static str, u64>,
+}
+
+#[derive(Clone, Copy)]
+struct SpanStart(Instant);
+
+impl MetricsLayer {
+    /// Starts recording. Spans aren't recorded (or even created) until this is
+    /// called.
+    pub fn enable(&self) {
+        self.enabled.store(true, Ordering::Relaxed);
+    }
+
+    fn is_enabled(&self) -> bool {
+        self.enabled.load(Ordering::Relaxed)
+    }
+
+    /// Per-layer filter which enables spans and metrics events only while the
+    /// layer is enabled.
+    pub fn filter<S>(&self) -> impl Filter<S> {
+        let enabled = self.enabled.clone();
+        DynFilterFn::new(move |metadata: &Metadata<'_>, _| {
+            enabled.load(Ordering::Relaxed)
+                && (metadata.is_span() || metadata.target() == METRICS_TARGET)
+        })
+        .with_callsite_filter(|metadata| {
+            if metadata.is_span() || metadata.target() == METRICS_TARGET {
+                Interest::sometimes()
+            } else {
+                Interest::never()
+            }
+        })
+    }
+
+    /// Returns the metrics recorded so far for the command.
+    pub fn finish(&self, command: String, success: bool, duration: Duration) -> CommandMetrics {
+        let state = std::mem::take(&mut *self.state.lock().unwrap());
+        CommandMetrics {
+            timestamp: SystemTime::now()
+                .duration_since(SystemTime::UNIX_EPOCH)
+                .map_or(0, |d| d.as_secs()),
+            command,
+            success,
+            duration_ms: duration.as_secs_f64() * 1000.0,
+            spans: state.spans,
+            repo: state.repo,
+        }
+    }
+}
+
+impl<S> Layer<S> for MetricsLayer
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    fn on_new_span(&self, _attrs: &span::Attributes<'_>, id: &span::Id, ctx: Context<'_, S>) {
+        if let Some(span) = ctx.span(id) {
+            span.extensions_mut().insert(SpanStart(Instant::now()));
+        }
+    }
+
+    fn on_close(&self, id: span::Id, ctx: Context<'_, S>) {
+        if !self.is_enabled() {
+            return;
+        }
+        let Some(span) = ctx.span(&id) else {
+            return;
+        };
+        let Some(&SpanStart(start)) = span.extensions().get::<SpanStart>() else {
+            return;
+        };
+        let mut state = self.state.lock().unwrap();
+        let stats = state.spans.entry(span.metadata().name()).or_default();
+        stats.count += 1;
+        stats.total_ms += start.elapsed().as_secs_f64() * 1000.0;
+    }
+
+    fn on_event(&self, event: &Event<'_>, _ctx: Context<'_, S>) {
+        if event.metadata().target() != METRICS_TARGET {
+            return;
+        }
+        let mut state = self.state.lock().unwrap();
+        event.record(&mut RepoStatsVisitor(&mut state.repo));
+    }
+}
+
+/// Records unsigned integer fields only. Other fields may contain paths.
+struct RepoStatsVisitor<'a>(&'a mut BTreeMap<&'static str, u64>);
+
+impl Visit for RepoStatsVisitor<'_> {
+    fn record_u64(&mut self, field: &Field, value: u64) {
+        self.0.insert(field.name(), value);
+    }
+
+    fn record_i64(&mut self, field: &Field, value: i64) {
+        if let Ok(value) = u64::try_from(value) {
+            self.0.insert(field.name(), value);
+        }
+    }
+
+    fn record_debug(&mut self, _field: &Field, _value: &dyn std::fmt::Debug) {}
+}
+
+/// Appends the `metrics` to the file at `path` as a JSON line.
+pub fn append_metrics(path: &Path, metrics: &CommandMetrics) -> io::Result<()> {
+    let mut line = serde_json::to_vec(metrics)?;
+    line.push(b'\n');
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    file.write_all(&line)
+}
+
+#[cfg(test)]
+mod tests {
+    use tracing_subscriber::layer::SubscriberExt as _;
+
+    use super::*;
+
+    #[test]
+    fn test_metrics_layer() {
+        let layer = MetricsLayer::default();
+        let subscriber =
+            tracing_subscriber::registry().with(layer.clone().with_filter(layer.filter()));
+        tracing::subscriber::with_default(subscriber, || {
+            // Not recorded until enabled
+            tracing::info_span!("before").in_scope(|| {});
+            layer.enable();
+            tracing::info_span!("outer", path = "secret").in_scope(|| {
+                tracing::info_span!("inner").in_scope(|| {});
+                tracing::info_span!("inner").in_scope(|| {});
+            });
+            tracing::info!(target: METRICS_TARGET, num_commits = 3_u64, path = "secret");
+        });
+        let metrics = layer.finish("log".to_owned(), true, Duration::ZERO);
+        assert_eq!(
+            metrics
+                .spans
+                .iter()
+                .map(|(name, stats)| (*name, stats.count))
+                .collect::<Vec<_>>(),
+            [("inner", 2), ("outer", 1)]
+        );
+        assert_eq!(metrics.repo, BTreeMap::from([("num_commits", 3)]));
+        let json = serde_json::to_string(&metrics).unwrap();
+        assert!(!json.contains("secret"));
+    }
+}
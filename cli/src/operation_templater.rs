@@ -22,6 +22,7 @@ use jj_lib::extensions_map::ExtensionsMap;
 use jj_lib::object_id::ObjectId;
 use jj_lib::op_store::OperationId;
 use jj_lib::operation::Operation;
+use jj_lib::settings::UserSettings;
 
 use crate::template_builder;
 use crate::template_builder::merge_fn_map;
@@ -49,6 +50,7 @@ pub trait OperationTemplateLanguageExtension {
 }
 
 pub struct OperationTemplateLanguage {
+    settings: UserSettings,
     root_op_id: OperationId,
     current_op_id: Option<OperationId>,
     build_fn_table: OperationTemplateBuildFnTable,
@@ -59,6 +61,7 @@ impl OperationTemplateLanguage {
     /// Sets up environment where operation template will be transformed to
     /// evaluation tree.
     pub fn new(
+        settings: &UserSettings,
         root_op_id: &OperationId,
         current_op_id: Option<&OperationId>,
         extensions: &[impl AsRef<dyn OperationTemplateLanguageExtension>],
@@ -74,6 +77,7 @@ impl OperationTemplateLanguage {
         }
 
         OperationTemplateLanguage {
+            settings: settings.clone(),
             root_op_id: root_op_id.clone(),
             current_op_id: current_op_id.cloned(),
             build_fn_table,
@@ -87,6 +91,10 @@ impl TemplateLanguage<'static> for OperationTemplateLanguage {
 
     template_builder::impl_core_wrap_property_fns!('static, OperationTemplatePropertyKind::Core);
 
+    fn settings(&self) -> &UserSettings {
+        &self.settings
+    }
+
     fn build_function(
         &self,
         diagnostics: &mut TemplateDiagnostics,
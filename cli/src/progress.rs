@@ -164,6 +164,14 @@ impl RateEstimateState {
 }
 
 pub fn snapshot_progress(ui: &Ui) -> Option<impl Fn(&RepoPath) + '_> {
+    path_progress(ui, "Snapshotting")
+}
+
+pub fn checkout_progress(ui: &Ui) -> Option<impl Fn(&RepoPath) + '_> {
+    path_progress(ui, "Updating")
+}
+
+fn path_progress<'a>(ui: &'a Ui, verb: &'static str) -> Option<impl Fn(&RepoPath) + 'a> {
     struct State {
         guard: Option<OutputGuard>,
         output: ProgressOutput<std::io::Stderr>,
@@ -199,14 +207,14 @@ pub fn snapshot_progress(ui: &Ui) -> Option<impl Fn(&RepoPath) + '_> {
         }
 
         let line_width = state.output.term_width().map(usize::from).unwrap_or(80);
-        let max_path_width = line_width.saturating_sub(13); // Account for "Snapshotting "
+        let max_path_width = line_width.saturating_sub(verb.len() + 1); // Account for "{verb} "
         let fs_path = path.to_fs_path_unchecked(Path::new(""));
         let (display_path, _) =
             text_util::elide_start(fs_path.to_str().unwrap(), "...", max_path_width);
 
         _ = write!(
             state.output,
-            "\r{}Snapshotting {display_path}",
+            "\r{}{verb} {display_path}",
             Clear(ClearType::CurrentLine),
         );
         _ = state.output.flush();
@@ -15,11 +15,16 @@
 use std::cmp::Ordering;
 use std::collections::HashMap;
 use std::io;
+use std::iter;
 
 use itertools::Itertools as _;
 use jj_lib::backend::Signature;
 use jj_lib::backend::Timestamp;
+use jj_lib::config::ConfigGetResultExt as _;
+use jj_lib::config::ConfigNamePathBuf;
+use jj_lib::config::ConfigValue;
 use jj_lib::dsl_util::AliasExpandError as _;
+use jj_lib::settings::UserSettings;
 use jj_lib::time_util::DatePattern;
 
 use crate::formatter::FormatRecorder;
@@ -29,6 +34,7 @@ use crate::template_parser::BinaryOp;
 use crate::template_parser::ExpressionKind;
 use crate::template_parser::ExpressionNode;
 use crate::template_parser::FunctionCallNode;
+use crate::template_parser::LambdaNode;
 use crate::template_parser::TemplateAliasesMap;
 use crate::template_parser::TemplateDiagnostics;
 use crate::template_parser::TemplateParseError;
@@ -78,10 +84,16 @@ pub trait TemplateLanguage<'a> {
     fn wrap_timestamp_range(
         property: impl TemplateProperty<Output = TimestampRange> + 'a,
     ) -> Self::Property;
+    fn wrap_config_value_opt(
+        property: impl TemplateProperty<Output = Option<ConfigValue>> + 'a,
+    ) -> Self::Property;
 
     fn wrap_template(template: Box<dyn Template + 'a>) -> Self::Property;
     fn wrap_list_template(template: Box<dyn ListTemplate + 'a>) -> Self::Property;
 
+    /// Settings which `config()` function reads values from.
+    fn settings(&self) -> &UserSettings;
+
     /// Translates the given global `function` call to a property.
     ///
     /// This should be delegated to
@@ -123,6 +135,7 @@ macro_rules! impl_core_wrap_property_fns {
                 wrap_size_hint($crate::templater::SizeHint) => SizeHint,
                 wrap_timestamp(jj_lib::backend::Timestamp) => Timestamp,
                 wrap_timestamp_range($crate::templater::TimestampRange) => TimestampRange,
+                wrap_config_value_opt(Option<jj_lib::config::ConfigValue>) => ConfigValueOpt,
             }
         );
         fn wrap_template(
@@ -186,6 +199,7 @@ pub enum CoreTemplatePropertyKind<'a> {
     SizeHint(Box<dyn TemplateProperty<Output = SizeHint> + 'a>),
     Timestamp(Box<dyn TemplateProperty<Output = Timestamp> + 'a>),
     TimestampRange(Box<dyn TemplateProperty<Output = TimestampRange> + 'a>),
+    ConfigValueOpt(Box<dyn TemplateProperty<Output = Option<ConfigValue>> + 'a>),
 
     // Both TemplateProperty and Template can represent a value to be evaluated
     // dynamically, which suggests that `Box<dyn Template + 'a>` could be
@@ -214,6 +228,7 @@ impl<'a> IntoTemplateProperty<'a> for CoreTemplatePropertyKind<'a> {
             CoreTemplatePropertyKind::SizeHint(_) => "SizeHint",
             CoreTemplatePropertyKind::Timestamp(_) => "Timestamp",
             CoreTemplatePropertyKind::TimestampRange(_) => "TimestampRange",
+            CoreTemplatePropertyKind::ConfigValueOpt(_) => "Option<ConfigValue>",
             CoreTemplatePropertyKind::Template(_) => "Template",
             CoreTemplatePropertyKind::ListTemplate(_) => "ListTemplate",
         }
@@ -239,6 +254,9 @@ impl<'a> IntoTemplateProperty<'a> for CoreTemplatePropertyKind<'a> {
             CoreTemplatePropertyKind::SizeHint(_) => None,
             CoreTemplatePropertyKind::Timestamp(_) => None,
             CoreTemplatePropertyKind::TimestampRange(_) => None,
+            CoreTemplatePropertyKind::ConfigValueOpt(property) => {
+                Some(Box::new(property.map(|opt| opt.is_some())))
+            }
             // Template types could also be evaluated to boolean, but it's less likely
             // to apply label() or .map() and use the result as conditional. It's also
             // unclear whether ListTemplate should behave as a "list" or a "template".
@@ -279,6 +297,7 @@ impl<'a> IntoTemplateProperty<'a> for CoreTemplatePropertyKind<'a> {
             CoreTemplatePropertyKind::SizeHint(_) => None,
             CoreTemplatePropertyKind::Timestamp(property) => Some(property.into_template()),
             CoreTemplatePropertyKind::TimestampRange(property) => Some(property.into_template()),
+            CoreTemplatePropertyKind::ConfigValueOpt(property) => Some(property.into_template()),
             CoreTemplatePropertyKind::Template(template) => Some(template),
             CoreTemplatePropertyKind::ListTemplate(template) => Some(template.into_template()),
         }
@@ -314,6 +333,7 @@ impl<'a> IntoTemplateProperty<'a> for CoreTemplatePropertyKind<'a> {
             (CoreTemplatePropertyKind::SizeHint(_), _) => None,
             (CoreTemplatePropertyKind::Timestamp(_), _) => None,
             (CoreTemplatePropertyKind::TimestampRange(_), _) => None,
+            (CoreTemplatePropertyKind::ConfigValueOpt(_), _) => None,
             (CoreTemplatePropertyKind::Template(_), _) => None,
             (CoreTemplatePropertyKind::ListTemplate(_), _) => None,
         }
@@ -327,6 +347,9 @@ impl<'a> IntoTemplateProperty<'a> for CoreTemplatePropertyKind<'a> {
             (CoreTemplatePropertyKind::Integer(lhs), CoreTemplatePropertyKind::Integer(rhs)) => {
                 Some(Box::new((lhs, rhs).map(|(l, r)| l.cmp(&r))))
             }
+            (CoreTemplatePropertyKind::String(lhs), CoreTemplatePropertyKind::String(rhs)) => {
+                Some(Box::new((lhs, rhs).map(|(l, r)| l.cmp(&r))))
+            }
             (CoreTemplatePropertyKind::String(_), _) => None,
             (CoreTemplatePropertyKind::StringList(_), _) => None,
             (CoreTemplatePropertyKind::Boolean(_), _) => None,
@@ -337,6 +360,7 @@ impl<'a> IntoTemplateProperty<'a> for CoreTemplatePropertyKind<'a> {
             (CoreTemplatePropertyKind::SizeHint(_), _) => None,
             (CoreTemplatePropertyKind::Timestamp(_), _) => None,
             (CoreTemplatePropertyKind::TimestampRange(_), _) => None,
+            (CoreTemplatePropertyKind::ConfigValueOpt(_), _) => None,
             (CoreTemplatePropertyKind::Template(_), _) => None,
             (CoreTemplatePropertyKind::ListTemplate(_), _) => None,
         }
@@ -385,6 +409,7 @@ pub struct CoreTemplateBuildFnTable<'a, L: TemplateLanguage<'a> + ?Sized> {
     pub size_hint_methods: TemplateBuildMethodFnMap<'a, L, SizeHint>,
     pub timestamp_methods: TemplateBuildMethodFnMap<'a, L, Timestamp>,
     pub timestamp_range_methods: TemplateBuildMethodFnMap<'a, L, TimestampRange>,
+    pub config_value_methods: TemplateBuildMethodFnMap<'a, L, ConfigValue>,
 }
 
 pub fn merge_fn_map<'s, F>(base: &mut HashMap<&'s str, F>, extension: HashMap<&'s str, F>) {
@@ -408,6 +433,7 @@ impl<'a, L: TemplateLanguage<'a> + ?Sized> CoreTemplateBuildFnTable<'a, L> {
             size_hint_methods: builtin_size_hint_methods(),
             timestamp_methods: builtin_timestamp_methods(),
             timestamp_range_methods: builtin_timestamp_range_methods(),
+            config_value_methods: builtin_config_value_methods(),
         }
     }
 
@@ -422,6 +448,7 @@ impl<'a, L: TemplateLanguage<'a> + ?Sized> CoreTemplateBuildFnTable<'a, L> {
             size_hint_methods: HashMap::new(),
             timestamp_methods: HashMap::new(),
             timestamp_range_methods: HashMap::new(),
+            config_value_methods: HashMap::new(),
         }
     }
 
@@ -436,6 +463,7 @@ impl<'a, L: TemplateLanguage<'a> + ?Sized> CoreTemplateBuildFnTable<'a, L> {
             size_hint_methods,
             timestamp_methods,
             timestamp_range_methods,
+            config_value_methods,
         } = extension;
 
         merge_fn_map(&mut self.functions, functions);
@@ -447,6 +475,7 @@ impl<'a, L: TemplateLanguage<'a> + ?Sized> CoreTemplateBuildFnTable<'a, L> {
         merge_fn_map(&mut self.size_hint_methods, size_hint_methods);
         merge_fn_map(&mut self.timestamp_methods, timestamp_methods);
         merge_fn_map(&mut self.timestamp_range_methods, timestamp_range_methods);
+        merge_fn_map(&mut self.config_value_methods, config_value_methods);
     }
 
     /// Translates the function call node `function` by using this symbol table.
@@ -488,6 +517,7 @@ impl<'a, L: TemplateLanguage<'a> + ?Sized> CoreTemplateBuildFnTable<'a, L> {
                     property,
                     function,
                     L::wrap_string,
+                    L::wrap_string_list,
                 )
             }
             CoreTemplatePropertyKind::Boolean(property) => {
@@ -538,6 +568,19 @@ impl<'a, L: TemplateLanguage<'a> + ?Sized> CoreTemplateBuildFnTable<'a, L> {
                 let build = template_parser::lookup_method(type_name, table, function)?;
                 build(language, diagnostics, build_ctx, property, function)
             }
+            CoreTemplatePropertyKind::ConfigValueOpt(property) => {
+                let type_name = "ConfigValue";
+                let table = &self.config_value_methods;
+                let build = template_parser::lookup_method(type_name, table, function)?;
+                let inner_property = property.try_unwrap(type_name);
+                build(
+                    language,
+                    diagnostics,
+                    build_ctx,
+                    Box::new(inner_property),
+                    function,
+                )
+            }
             CoreTemplatePropertyKind::Template(_) => {
                 // TODO: migrate to table?
                 Err(TemplateParseError::no_such_method(type_name, function))
@@ -1122,6 +1165,74 @@ fn builtin_timestamp_range_methods<'a, L: TemplateLanguage<'a> + ?Sized>(
     map
 }
 
+fn builtin_config_value_methods<'a, L: TemplateLanguage<'a> + ?Sized>(
+) -> TemplateBuildMethodFnMap<'a, L, ConfigValue> {
+    // Not using maplit::hashmap!{} or custom declarative macro here because
+    // code completion inside macro is quite restricted.
+    let mut map = TemplateBuildMethodFnMap::<L, ConfigValue>::new();
+    map.insert(
+        "as_boolean",
+        |_language, _diagnostics, _build_ctx, self_property, function| {
+            function.expect_no_arguments()?;
+            let out_property = self_property.and_then(|value| {
+                value
+                    .as_bool()
+                    .ok_or_else(|| config_value_type_error(&value, "a boolean"))
+            });
+            Ok(L::wrap_boolean(out_property))
+        },
+    );
+    map.insert(
+        "as_integer",
+        |_language, _diagnostics, _build_ctx, self_property, function| {
+            function.expect_no_arguments()?;
+            let out_property = self_property.and_then(|value| {
+                value
+                    .as_integer()
+                    .ok_or_else(|| config_value_type_error(&value, "an integer"))
+            });
+            Ok(L::wrap_integer(out_property))
+        },
+    );
+    map.insert(
+        "as_string",
+        |_language, _diagnostics, _build_ctx, self_property, function| {
+            function.expect_no_arguments()?;
+            let out_property = self_property.and_then(|value| {
+                value
+                    .as_str()
+                    .map(|s| s.to_owned())
+                    .ok_or_else(|| config_value_type_error(&value, "a string"))
+            });
+            Ok(L::wrap_string(out_property))
+        },
+    );
+    map.insert(
+        "as_string_list",
+        |_language, _diagnostics, _build_ctx, self_property, function| {
+            function.expect_no_arguments()?;
+            let out_property = self_property.and_then(|value| {
+                value
+                    .as_array()
+                    .and_then(|array| {
+                        array
+                            .iter()
+                            .map(|item| item.as_str().map(|s| s.to_owned()))
+                            .collect()
+                    })
+                    .ok_or_else(|| config_value_type_error(&value, "an array of strings"))
+            });
+            Ok(L::wrap_string_list(out_property))
+        },
+    );
+    map
+}
+
+fn config_value_type_error(value: &ConfigValue, expected: &str) -> TemplatePropertyError {
+    let ty = value.type_name();
+    TemplatePropertyError(format!("Invalid type: {ty}, expected {expected}").into())
+}
+
 fn build_list_template_method<'a, L: TemplateLanguage<'a> + ?Sized>(
     language: &L,
     diagnostics: &mut TemplateDiagnostics,
@@ -1151,6 +1262,7 @@ pub fn build_formattable_list_method<'a, L, O>(
     // TODO: Generic L: WrapProperty<O> trait might be needed to support more
     // list operations such as first()/slice(). For .map(), a simple callback works.
     wrap_item: impl Fn(PropertyPlaceholder<O>) -> L::Property,
+    wrap_list: impl Fn(Box<dyn TemplateProperty<Output = Vec<O>> + 'a>) -> L::Property,
 ) -> TemplateParseResult<L::Property>
 where
     L: TemplateLanguage<'a> + ?Sized,
@@ -1180,6 +1292,24 @@ where
             function,
             wrap_item,
         )?,
+        "filter" => build_filter_operation(
+            language,
+            diagnostics,
+            build_ctx,
+            self_property,
+            function,
+            wrap_item,
+            wrap_list,
+        )?,
+        "sort_by" => build_sort_by_operation(
+            language,
+            diagnostics,
+            build_ctx,
+            self_property,
+            function,
+            wrap_item,
+            wrap_list,
+        )?,
         _ => return Err(TemplateParseError::no_such_method("List", function)),
     };
     Ok(property)
@@ -1192,6 +1322,7 @@ pub fn build_unformattable_list_method<'a, L, O>(
     self_property: impl TemplateProperty<Output = Vec<O>> + 'a,
     function: &FunctionCallNode,
     wrap_item: impl Fn(PropertyPlaceholder<O>) -> L::Property,
+    wrap_list: impl Fn(Box<dyn TemplateProperty<Output = Vec<O>> + 'a>) -> L::Property,
 ) -> TemplateParseResult<L::Property>
 where
     L: TemplateLanguage<'a> + ?Sized,
@@ -1212,6 +1343,24 @@ where
             function,
             wrap_item,
         )?,
+        "filter" => build_filter_operation(
+            language,
+            diagnostics,
+            build_ctx,
+            self_property,
+            function,
+            wrap_item,
+            wrap_list,
+        )?,
+        "sort_by" => build_sort_by_operation(
+            language,
+            diagnostics,
+            build_ctx,
+            self_property,
+            function,
+            wrap_item,
+            wrap_list,
+        )?,
         _ => return Err(TemplateParseError::no_such_method("List", function)),
     };
     Ok(property)
@@ -1241,20 +1390,9 @@ where
     let item_placeholder = PropertyPlaceholder::new();
     let item_template = template_parser::expect_lambda_with(lambda_node, |lambda, _span| {
         let item_fn = || wrap_item(item_placeholder.clone());
-        let mut local_variables = build_ctx.local_variables.clone();
-        if let [name] = lambda.params.as_slice() {
-            local_variables.insert(name, &item_fn);
-        } else {
-            return Err(TemplateParseError::expression(
-                "Expected 1 lambda parameters",
-                lambda.params_span,
-            ));
-        }
-        let inner_build_ctx = BuildContext {
-            local_variables,
-            self_variable: build_ctx.self_variable,
-        };
-        expect_template_expression(language, diagnostics, &inner_build_ctx, &lambda.body)
+        build_lambda_expression(build_ctx, lambda, &[&item_fn], |build_ctx, body| {
+            expect_template_expression(language, diagnostics, build_ctx, body)
+        })
     })?;
     let list_template = ListPropertyTemplate::new(
         self_property,
@@ -1266,6 +1404,141 @@ where
     Ok(L::wrap_list_template(Box::new(list_template)))
 }
 
+/// Builds expression that extracts iterable property and keeps the items for
+/// which the predicate evaluates to true.
+fn build_filter_operation<'a, L, O, P>(
+    language: &L,
+    diagnostics: &mut TemplateDiagnostics,
+    build_ctx: &BuildContext<L::Property>,
+    self_property: P,
+    function: &FunctionCallNode,
+    wrap_item: impl Fn(PropertyPlaceholder<O>) -> L::Property,
+    wrap_list: impl Fn(Box<dyn TemplateProperty<Output = Vec<O>> + 'a>) -> L::Property,
+) -> TemplateParseResult<L::Property>
+where
+    L: TemplateLanguage<'a> + ?Sized,
+    P: TemplateProperty + 'a,
+    P::Output: IntoIterator<Item = O>,
+    O: Clone + 'a,
+{
+    let [lambda_node] = function.expect_exact_arguments()?;
+    let item_placeholder = PropertyPlaceholder::new();
+    let item_predicate = template_parser::expect_lambda_with(lambda_node, |lambda, _span| {
+        let item_fn = || wrap_item(item_placeholder.clone());
+        build_lambda_expression(build_ctx, lambda, &[&item_fn], |build_ctx, body| {
+            expect_boolean_expression(language, diagnostics, build_ctx, body)
+        })
+    })?;
+    let out_property = self_property.and_then(move |items| {
+        items
+            .into_iter()
+            .filter_map(|item| {
+                let result = item_placeholder.with_value(item.clone(), || item_predicate.extract());
+                result.map(|matched| matched.then_some(item)).transpose()
+            })
+            .collect()
+    });
+    Ok(wrap_list(Box::new(out_property)))
+}
+
+/// Builds expression that extracts iterable property and sorts the items by
+/// the key the lambda evaluates to. The sort is stable.
+fn build_sort_by_operation<'a, L, O, P>(
+    language: &L,
+    diagnostics: &mut TemplateDiagnostics,
+    build_ctx: &BuildContext<L::Property>,
+    self_property: P,
+    function: &FunctionCallNode,
+    wrap_item: impl Fn(PropertyPlaceholder<O>) -> L::Property,
+    wrap_list: impl Fn(Box<dyn TemplateProperty<Output = Vec<O>> + 'a>) -> L::Property,
+) -> TemplateParseResult<L::Property>
+where
+    L: TemplateLanguage<'a> + ?Sized,
+    P: TemplateProperty + 'a,
+    P::Output: IntoIterator<Item = O>,
+    O: Clone + 'a,
+{
+    // The key expression is built for both sides of the comparison.
+    let [lambda_node] = function.expect_exact_arguments()?;
+    let lhs_placeholder = PropertyPlaceholder::new();
+    let rhs_placeholder = PropertyPlaceholder::new();
+    let item_cmp = template_parser::expect_lambda_with(lambda_node, |lambda, span| {
+        let lhs_fn = || wrap_item(lhs_placeholder.clone());
+        let rhs_fn = || wrap_item(rhs_placeholder.clone());
+        let lhs = build_lambda_expression(build_ctx, lambda, &[&lhs_fn], |build_ctx, body| {
+            build_expression(language, diagnostics, build_ctx, body)
+        })?;
+        // Don't report the same diagnostics twice.
+        let rhs = build_lambda_expression(build_ctx, lambda, &[&rhs_fn], |build_ctx, body| {
+            build_expression(language, &mut TemplateDiagnostics::new(), build_ctx, body)
+        })?;
+        let ty = lhs.type_name();
+        lhs.try_into_cmp(rhs).ok_or_else(|| {
+            let message = format!(r#"Cannot sort by expression of type "{ty}""#);
+            TemplateParseError::expression(message, span)
+        })
+    })?;
+    let out_property = self_property.and_then(move |items| {
+        let mut items = items.into_iter().collect_vec();
+        let mut err = None;
+        items.sort_by(|lhs, rhs| {
+            if err.is_some() {
+                return Ordering::Equal;
+            }
+            let result = lhs_placeholder.with_value(lhs.clone(), || {
+                rhs_placeholder.with_value(rhs.clone(), || item_cmp.extract())
+            });
+            result.unwrap_or_else(|e| {
+                err = Some(e);
+                Ordering::Equal
+            })
+        });
+        match err {
+            Some(err) => Err(err),
+            None => Ok(items),
+        }
+    });
+    Ok(wrap_list(Box::new(out_property)))
+}
+
+/// Builds lambda expression to be evaluated with the provided arguments.
+/// `arg_fns` is usually an array of wrapped [`PropertyPlaceholder`]s.
+fn build_lambda_expression<'i, P, T>(
+    build_ctx: &BuildContext<'i, P>,
+    lambda: &LambdaNode<'i>,
+    arg_fns: &[&'i dyn Fn() -> P],
+    build_body: impl FnOnce(&BuildContext<'i, P>, &ExpressionNode<'i>) -> TemplateParseResult<T>,
+) -> TemplateParseResult<T> {
+    if lambda.params.len() != arg_fns.len() {
+        return Err(TemplateParseError::expression(
+            format!("Expected {} lambda parameters", arg_fns.len()),
+            lambda.params_span,
+        ));
+    }
+    let mut local_variables = build_ctx.local_variables.clone();
+    local_variables.extend(iter::zip(
+        lambda.params.iter().copied(),
+        arg_fns.iter().copied(),
+    ));
+    let inner_build_ctx = BuildContext {
+        local_variables,
+        self_variable: build_ctx.self_variable,
+    };
+    build_body(&inner_build_ctx, &lambda.body)
+}
+
+/// Config tables which can be read by `config()` function.
+///
+/// Other tables may contain secrets (e.g. `signing.key` or credentials in
+/// command arguments), which shouldn't be leaked to template outputs.
+pub const TEMPLATE_READABLE_CONFIG_TABLES: &[&str] = &["template-vars", "ui", "user"];
+
+fn is_config_readable_from_templates(name: &ConfigNamePathBuf) -> bool {
+    name.components()
+        .next()
+        .is_some_and(|key| TEMPLATE_READABLE_CONFIG_TABLES.contains(&key.get()))
+}
+
 fn builtin_functions<'a, L: TemplateLanguage<'a> + ?Sized>() -> TemplateBuildFunctionFnMap<'a, L> {
     // Not using maplit::hashmap!{} or custom declarative macro here because
     // code completion inside macro is quite restricted.
@@ -1359,6 +1632,19 @@ fn builtin_functions<'a, L: TemplateLanguage<'a> + ?Sized>() -> TemplateBuildFun
             ))))
         },
     );
+    map.insert(
+        "escape_json",
+        |language, diagnostics, build_ctx, function| {
+            let [content_node] = function.expect_exact_arguments()?;
+            let content =
+                expect_template_expression(language, diagnostics, build_ctx, content_node)?;
+            let template = ReformatTemplate::new(content, |formatter, recorded| {
+                let text = String::from_utf8_lossy(recorded.data());
+                serde_json::to_writer(formatter.as_mut(), &text).map_err(io::Error::from)
+            });
+            Ok(L::wrap_template(Box::new(template)))
+        },
+    );
     map.insert("if", |language, diagnostics, build_ctx, function| {
         let ([condition_node, true_node], [false_node]) = function.expect_arguments()?;
         let condition =
@@ -1415,6 +1701,39 @@ fn builtin_functions<'a, L: TemplateLanguage<'a> + ?Sized>() -> TemplateBuildFun
         });
         Ok(L::wrap_template(Box::new(template)))
     });
+    map.insert("config", |language, _diagnostics, _build_ctx, function| {
+        // The name must be a literal so unreadable config names are reported
+        // early. This also means the value can be looked up at parse time.
+        let [name_node] = function.expect_exact_arguments()?;
+        let name: ConfigNamePathBuf =
+            template_parser::expect_string_literal_with(name_node, |name, span| {
+                let name: ConfigNamePathBuf = name.parse().map_err(|err| {
+                    TemplateParseError::expression("Failed to parse config name", span)
+                        .with_source(err)
+                })?;
+                if !is_config_readable_from_templates(&name) {
+                    let tables = TEMPLATE_READABLE_CONFIG_TABLES.join(", ");
+                    return Err(TemplateParseError::expression(
+                        format!(
+                            "Config `{name}` is not readable from templates (readable tables: \
+                             {tables})"
+                        ),
+                        span,
+                    ));
+                }
+                Ok(name)
+            })?;
+        let value = language
+            .settings()
+            .config()
+            .get_value(&name)
+            .optional()
+            .map_err(|err| {
+                TemplateParseError::expression("Failed to read config", name_node.span)
+                    .with_source(err)
+            })?;
+        Ok(L::wrap_config_value_opt(Literal(value)))
+    });
     map
 }
 
@@ -1721,6 +2040,9 @@ mod tests {
     use std::iter;
 
     use jj_lib::backend::MillisSinceEpoch;
+    use jj_lib::config::ConfigLayer;
+    use jj_lib::config::ConfigSource;
+    use jj_lib::config::StackedConfig;
 
     use super::*;
     use crate::formatter;
@@ -1739,8 +2061,12 @@ mod tests {
 
     impl TestTemplateEnv {
         fn new() -> Self {
+            Self::with_config(StackedConfig::empty())
+        }
+
+        fn with_config(config: StackedConfig) -> Self {
             TestTemplateEnv {
-                language: L::new(),
+                language: L::new(&UserSettings::from_config(config).unwrap()),
                 aliases_map: TemplateAliasesMap::new(),
                 color_rules: Vec::new(),
             }
@@ -2194,6 +2520,8 @@ mod tests {
         insta::assert_snapshot!(env.render_ok(r#"2 <= 1"#), @"false");
         insta::assert_snapshot!(env.render_ok(r#"0 < 1"#), @"true");
         insta::assert_snapshot!(env.render_ok(r#"1 < 1"#), @"false");
+        insta::assert_snapshot!(env.render_ok(r#"'a' < 'b'"#), @"true");
+        insta::assert_snapshot!(env.render_ok(r#"'b' <= 'a'"#), @"false");
     }
 
     #[test]
@@ -2340,6 +2668,48 @@ mod tests {
           |
           = Expected 1 lambda parameters
         "#);
+
+        // Filter items
+        insta::assert_snapshot!(
+            env.render_ok(r#""a\nbb\nc\nbd".lines().filter(|s| s.contains("b"))"#),
+            @"bb bd");
+        insta::assert_snapshot!(
+            env.render_ok(r#""a\nb".lines().filter(|s| false).len()"#),
+            @"0");
+        // Filtered list can be chained with other list methods
+        insta::assert_snapshot!(
+            env.render_ok(r#""a\nbb\nc".lines().filter(|s| !s.contains("b")).map(|s| s.upper()).join(",")"#),
+            @"A,C");
+        // Predicate must be boolean
+        insta::assert_snapshot!(env.parse_err(r#""a".lines().filter(|s| s.len())"#), @r#"
+         --> 1:24
+          |
+        1 | "a".lines().filter(|s| s.len())
+          |                        ^-----^
+          |
+          = Expected expression of type "Boolean", but actual type is "Integer"
+        "#);
+
+        // Sort items by key
+        insta::assert_snapshot!(
+            env.render_ok(r#""c\na\nb".lines().sort_by(|s| s)"#),
+            @"a b c");
+        insta::assert_snapshot!(
+            env.render_ok(r#""ccc\na\nbb".lines().sort_by(|s| -s.len()).join(",")"#),
+            @"ccc,bb,a");
+        // Sort is stable
+        insta::assert_snapshot!(
+            env.render_ok(r#""b2\na1\nb1\na2".lines().sort_by(|s| s.substr(0, 1))"#),
+            @"a1 a2 b2 b1");
+        // Key must be comparable
+        insta::assert_snapshot!(env.parse_err(r#""a".lines().sort_by(|s| s.lines())"#), @r#"
+         --> 1:21
+          |
+        1 | "a".lines().sort_by(|s| s.lines())
+          |                     ^-----------^
+          |
+          = Cannot sort by expression of type "List<String>"
+        "#);
     }
 
     #[test]
@@ -2839,6 +3209,23 @@ mod tests {
             @r#"]8;;http://example.com\Example]8;;\"#);
     }
 
+    #[test]
+    fn test_escape_json_function() {
+        let mut env = TestTemplateEnv::new();
+        env.add_color("error", crossterm::style::Color::DarkRed);
+
+        insta::assert_snapshot!(env.render_ok(r#"escape_json("")"#), @r#""""#);
+        insta::assert_snapshot!(
+            env.render_ok(r#"escape_json("a \"quoted\"\nline\t\\")"#),
+            @r#""a \"quoted\"\nline\t\\""#);
+        insta::assert_snapshot!(env.render_ok(r#"escape_json("\e")"#), @r#""\u001b""#);
+        insta::assert_snapshot!(env.render_ok(r#"escape_json(1 ++ true)"#), @r#""1true""#);
+        // Labels are stripped
+        insta::assert_snapshot!(
+            env.render_ok(r#"escape_json(label("error", "text"))"#),
+            @r#""text""#);
+    }
+
     #[test]
     fn test_coalesce_function() {
         let mut env = TestTemplateEnv::new();
@@ -2972,4 +3359,75 @@ mod tests {
             env.render_ok(r#"surround(lt, gt, if(empty_content, "not empty", ""))"#),
             @"");
     }
+
+    #[test]
+    fn test_config_function() {
+        let mut config = StackedConfig::empty();
+        config.add_layer(
+            ConfigLayer::parse(
+                ConfigSource::User,
+                indoc::indoc! {"
+                    template-vars.bool = true
+                    template-vars.int = 42
+                    template-vars.string = 'foo'
+                    template-vars.list = ['a', 'b']
+                    template-vars.table = { x = 1 }
+                    signing.key = 'secret'
+                "},
+            )
+            .unwrap(),
+        );
+        let env = TestTemplateEnv::with_config(config);
+
+        insta::assert_snapshot!(env.render_ok(r#"config("template-vars.bool")"#), @"true");
+        insta::assert_snapshot!(env.render_ok(r#"config("template-vars.int")"#), @"42");
+        insta::assert_snapshot!(env.render_ok(r#"config("template-vars.string")"#), @"'foo'");
+        insta::assert_snapshot!(env.render_ok(r#"config("template-vars.list")"#), @"['a', 'b']");
+        insta::assert_snapshot!(env.render_ok(r#"config("template-vars.table")"#), @"{ x = 1 }");
+        insta::assert_snapshot!(env.render_ok(r#"config("template-vars.unset")"#), @"");
+
+        insta::assert_snapshot!(
+            env.render_ok(r#"config("template-vars.bool").as_boolean()"#), @"true");
+        insta::assert_snapshot!(
+            env.render_ok(r#"config("template-vars.int").as_integer() > 40"#), @"true");
+        insta::assert_snapshot!(
+            env.render_ok(r#"config("template-vars.string").as_string()"#), @"foo");
+        insta::assert_snapshot!(
+            env.render_ok(r#"config("template-vars.list").as_string_list().join(",")"#), @"a,b");
+        insta::assert_snapshot!(
+            env.render_ok(r#"config("template-vars.int").as_string()"#), @"<Error: Invalid type: integer, expected a string>");
+        insta::assert_snapshot!(
+            env.render_ok(r#"config("template-vars.unset").as_string()"#), @"<Error: No ConfigValue available>");
+
+        // Unset value can be tested
+        insta::assert_snapshot!(
+            env.render_ok(r#"if(config("template-vars.bool"), "set", "unset")"#), @"set");
+        insta::assert_snapshot!(
+            env.render_ok(r#"if(config("template-vars.unset"), "set", "unset")"#), @"unset");
+
+        insta::assert_snapshot!(env.parse_err(r#"config("signing.key")"#), @r#"
+         --> 1:8
+          |
+        1 | config("signing.key")
+          |        ^-----------^
+          |
+          = Config `signing.key` is not readable from templates (readable tables: template-vars, ui, user)
+        "#);
+        insta::assert_snapshot!(env.parse_err(r#"config("template-vars.")"#), @r#"
+         --> 1:8
+          |
+        1 | config("template-vars.")
+          |        ^--------------^
+          |
+          = Failed to parse config name
+        "#);
+        insta::assert_snapshot!(env.parse_err(r#"config("template-vars" ++ ".int")"#), @r#"
+         --> 1:8
+          |
+        1 | config("template-vars" ++ ".int")
+          |        ^-----------------------^
+          |
+          = Expected string literal
+        "#);
+    }
 }
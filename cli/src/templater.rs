@@ -22,6 +22,7 @@ use std::rc::Rc;
 
 use jj_lib::backend::Signature;
 use jj_lib::backend::Timestamp;
+use jj_lib::config::ConfigValue;
 
 use crate::formatter::FormatRecorder;
 use crate::formatter::Formatter;
@@ -180,6 +181,14 @@ impl Template for i64 {
     }
 }
 
+impl Template for ConfigValue {
+    fn format(&self, formatter: &mut TemplateFormatter) -> io::Result<()> {
+        // Strip whitespace and comments around the value
+        let value = self.clone().decorated("", "");
+        write!(formatter, "{value}")
+    }
+}
+
 pub struct LabelTemplate<T, L> {
     content: T,
     labels: L,
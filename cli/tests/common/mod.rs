@@ -19,9 +19,14 @@ use std::path::PathBuf;
 
 use indoc::formatdoc;
 use itertools::Itertools as _;
+use jj_lib::commit::Commit;
+use jj_lib::config::ConfigLayer;
+use jj_lib::config::ConfigSource;
+use jj_lib::settings::UserSettings;
 use regex::Captures;
 use regex::Regex;
 use tempfile::TempDir;
+use testutils::scenario::Scenario;
 
 pub struct TestEnvironment {
     _temp_dir: TempDir,
@@ -103,11 +108,8 @@ impl TestEnvironment {
         cmd.env("JJ_OP_USERNAME", "test-username");
         cmd.env("JJ_TZ_OFFSET_MINS", "660");
 
-        let mut command_number = self.command_number.borrow_mut();
-        *command_number += 1;
+        let (command_number, timestamp) = self.next_command_number();
         cmd.env("JJ_RANDOMNESS_SEED", command_number.to_string());
-        let timestamp = chrono::DateTime::parse_from_rfc3339("2001-02-03T04:05:06+07:00").unwrap();
-        let timestamp = timestamp + chrono::Duration::try_seconds(*command_number).unwrap();
         cmd.env("JJ_TIMESTAMP", timestamp.to_rfc3339());
         cmd.env("JJ_OP_TIMESTAMP", timestamp.to_rfc3339());
 
@@ -147,6 +149,41 @@ impl TestEnvironment {
         cmd
     }
 
+    /// Increments the command number, and returns it along with the
+    /// timestamp of the command.
+    fn next_command_number(&self) -> (i64, chrono::DateTime<chrono::FixedOffset>) {
+        let mut command_number = self.command_number.borrow_mut();
+        *command_number += 1;
+        let timestamp = chrono::DateTime::parse_from_rfc3339("2001-02-03T04:05:06+07:00").unwrap();
+        let timestamp = timestamp + chrono::Duration::try_seconds(*command_number).unwrap();
+        (*command_number, timestamp)
+    }
+
+    /// Writes the commits and bookmarks of the `scenario` to the repo of the
+    /// workspace at `workspace_path` as if a `jj` command did it, so the IDs
+    /// in the output are stable.
+    pub fn write_scenario(
+        &self,
+        workspace_path: &Path,
+        scenario: &Scenario,
+    ) -> HashMap<String, Commit> {
+        let (command_number, timestamp) = self.next_command_number();
+        let mut config = testutils::base_user_config();
+        let mut layer = ConfigLayer::empty(ConfigSource::EnvOverrides);
+        layer
+            .set_value("debug.randomness-seed", command_number)
+            .unwrap();
+        layer
+            .set_value("debug.commit-timestamp", timestamp.to_rfc3339())
+            .unwrap();
+        layer
+            .set_value("debug.operation-timestamp", timestamp.to_rfc3339())
+            .unwrap();
+        config.add_layer(layer);
+        let settings = UserSettings::from_config(config).unwrap();
+        scenario.write_to_repo(&settings, &workspace_path.join(".jj").join("repo"))
+    }
+
     pub fn write_stdin(&self, cmd: &mut assert_cmd::Command, stdin: &str) {
         cmd.env("JJ_INTERACTIVE", "1");
         cmd.write_stdin(stdin);
@@ -13,6 +13,8 @@ mod test_absorb_command;
 mod test_acls;
 mod test_advance_bookmarks;
 mod test_alias;
+mod test_apply_command;
+mod test_archive_command;
 mod test_backout_command;
 mod test_bookmark_command;
 mod test_builtin_aliases;
@@ -30,11 +32,14 @@ mod test_duplicate_command;
 mod test_edit_command;
 mod test_evolog_command;
 mod test_file_annotate_command;
+mod test_file_assume_unchanged_command;
 mod test_file_chmod_command;
+mod test_file_restore_command;
 mod test_file_show_command;
 mod test_file_track_untrack_commands;
 mod test_fix_command;
 mod test_generate_md_cli_help;
+mod test_git_bundle;
 mod test_git_clone;
 mod test_git_colocated;
 mod test_git_fetch;
@@ -51,23 +56,29 @@ mod test_immutable_commits;
 mod test_init_command;
 mod test_interdiff_command;
 mod test_log_command;
+mod test_megamerge_command;
 mod test_new_command;
 mod test_next_prev_commands;
 mod test_operations;
 mod test_parallelize_command;
+mod test_range_diff_command;
 mod test_rebase_command;
 mod test_repo_change_report;
 mod test_resolve_command;
+mod test_resolve_divergence_command;
 mod test_restore_command;
 mod test_revset_output;
 mod test_root;
+mod test_run_command;
 mod test_shell_completion;
 mod test_show_command;
+mod test_sign_unsign_commands;
 mod test_simplify_parents_command;
 mod test_sparse_command;
 mod test_split_command;
 mod test_squash_command;
 mod test_status_command;
+mod test_sync_command;
 mod test_tag_command;
 mod test_templater;
 mod test_undo;
@@ -400,6 +400,73 @@ fn test_abandon_restore_descendants() {
     "#);
 }
 
+#[test]
+fn test_abandon_bookmarks_policy() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    create_commit(&test_env, &repo_path, "a", &[]);
+    create_commit(&test_env, &repo_path, "b", &["a"]);
+    test_env.jj_cmd_ok(&repo_path, &["bookmark", "create", "-r=b", "b2"]);
+    create_commit(&test_env, &repo_path, "c", &["b"]);
+
+    // Bookmarks can be deleted
+    let (stdout, stderr) = test_env.jj_cmd_ok(
+        &repo_path,
+        &["abandon", "b", "--config=abandon.bookmarks='delete'"],
+    );
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r"
+    Abandoned commit zsuskuln 1394f625 b b2 | b
+    Rebased 1 descendant commits onto parents of abandoned commits
+    Deleted bookmarks: b, b2
+    Working copy now at: yqosqzyt 91d30260 c | c
+    Parent commit      : rlvkpnrz 2443ea76 a | a
+    Added 0 files, modified 0 files, removed 1 files
+    ");
+    insta::assert_snapshot!(get_log_output(&test_env, &repo_path), @r"
+    @  [yqo] c
+    ○  [rlv] a
+    ◆  [zzz]
+    ");
+
+    // The operation can be blocked
+    test_env.jj_cmd_ok(&repo_path, &["undo"]);
+    test_env.add_config("abandon.bookmarks = 'error'");
+    let stderr = test_env.jj_cmd_failure(&repo_path, &["abandon", "a::b"]);
+    insta::assert_snapshot!(stderr, @r"
+    Error: Refusing to abandon commits with bookmarks: a, b, b2
+    Hint: Use --retain-bookmarks to move the bookmarks to the parent commits, or delete them first with `jj bookmark delete`.
+    ");
+
+    // Commits without bookmarks can still be abandoned
+    test_env.jj_cmd_ok(&repo_path, &["new", "c", "-m=d"]);
+    let (_stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["abandon"]);
+    insta::assert_snapshot!(stderr, @r"
+    Abandoned commit wqnwkozp f23eb391 (empty) d
+    Working copy now at: lylxulpl 12abcb3c (empty) (no description set)
+    Parent commit      : yqosqzyt 5a831ca6 c | c
+    ");
+
+    // --retain-bookmarks overrides the config
+    let (stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["abandon", "b", "--retain-bookmarks"]);
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r"
+    Abandoned commit zsuskuln 1394f625 b b2 | b
+    Rebased 2 descendant commits onto parents of abandoned commits
+    Working copy now at: lylxulpl d53a0c00 (empty) (no description set)
+    Parent commit      : yqosqzyt 9f79156e c | c
+    Added 0 files, modified 0 files, removed 1 files
+    ");
+    insta::assert_snapshot!(get_log_output(&test_env, &repo_path), @r"
+    @  [lyl]
+    ○  [yqo] c
+    ○  [rlv] a b b2
+    ◆  [zzz]
+    ");
+}
+
 fn get_log_output(test_env: &TestEnvironment, repo_path: &Path) -> String {
     test_env.jj_cmd_success(
         repo_path,
@@ -0,0 +1,146 @@
+// Copyright 2024 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::path::Path;
+
+use crate::common::TestEnvironment;
+
+fn create_commit(
+    test_env: &TestEnvironment,
+    repo_path: &Path,
+    message: &str,
+    files: &[(&str, &str)],
+) {
+    for (name, content) in files {
+        std::fs::write(repo_path.join(name), content).unwrap();
+    }
+    test_env.jj_cmd_ok(repo_path, &["commit", "-m", message]);
+}
+
+#[test]
+fn test_apply_from_other_repo() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "source"]);
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "target"]);
+    let source_path = test_env.env_root().join("source");
+    let target_path = test_env.env_root().join("target");
+
+    create_commit(&test_env, &source_path, "base", &[("file", "a\nb\nc\n")]);
+    create_commit(
+        &test_env,
+        &source_path,
+        "change",
+        &[("file", "a\nb\nC\n"), ("new", "new\n")],
+    );
+    create_commit(
+        &test_env,
+        &target_path,
+        "target base",
+        &[("file", "A\nb\nc\n")],
+    );
+
+    let (stdout, stderr) = test_env.jj_cmd_ok(
+        &target_path,
+        &[
+            "apply",
+            "--from-repo",
+            "../source",
+            "-r",
+            "description(change)",
+        ],
+    );
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @"Applied kkmpptxz 6f8687e1 change as royxmykx 099683ef change");
+    let stdout = test_env.jj_cmd_success(
+        &target_path,
+        &[
+            "log",
+            "-r",
+            "description(change)",
+            "-T",
+            "description",
+            "--git",
+        ],
+    );
+    insta::assert_snapshot!(stdout, @r"
+    ○  change
+    │  diff --git a/file b/file
+    ~  index 4f213f7002..24938456d4 100644
+       --- a/file
+       +++ b/file
+       @@ -1,3 +1,3 @@
+        A
+        b
+       -c
+       +C
+       diff --git a/new b/new
+       new file mode 100644
+       index 0000000000..3e757656cf
+       --- /dev/null
+       +++ b/new
+       @@ -0,0 +1,1 @@
+       +new
+    ");
+    let stdout = test_env.jj_cmd_success(
+        &target_path,
+        &["file", "show", "-r", "description(change)", "file"],
+    );
+    insta::assert_snapshot!(stdout, @r"
+    A
+    b
+    C
+    ");
+
+    // Changes that don't apply cleanly result in conflicts
+    let (_stdout, stderr) = test_env.jj_cmd_ok(
+        &target_path,
+        &[
+            "apply",
+            "--from-repo",
+            "../source",
+            "-r",
+            "description(change)",
+            "-d",
+            "root()",
+        ],
+    );
+    insta::assert_snapshot!(stderr, @r"
+    Applied kkmpptxz 6f8687e1 change as yostqsxw 75f266b3 (conflict) change
+    New conflicts appeared in these commits:
+      yostqsxw 75f266b3 (conflict) change
+    To resolve the conflicts, start by updating to it:
+      jj new yostqsxw
+    Then use `jj resolve`, or edit the conflict markers in the file directly.
+    Once the conflicts are resolved, you may want to inspect the result with `jj diff`.
+    Then run `jj squash` to move the resolution into the conflicted commit.
+    ");
+
+    // The revision is resolved in the other repo
+    let stderr = test_env.jj_cmd_failure(
+        &target_path,
+        &[
+            "apply",
+            "--from-repo",
+            "../source",
+            "-r",
+            "description('target base')",
+        ],
+    );
+    insta::assert_snapshot!(stderr, @r#"Error: Revset "description('target base')" didn't resolve to any revisions"#);
+    let stderr = test_env.jj_cmd_failure(
+        &target_path,
+        &["apply", "--from-repo", "../nonexistent", "-r", "@"],
+    );
+    insta::assert_snapshot!(stderr, @r#"Error: There is no jj repo in "../nonexistent""#);
+}
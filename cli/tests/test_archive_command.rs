@@ -0,0 +1,148 @@
+// Copyright 2024 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::io::Read as _;
+use std::path::Path;
+
+use crate::common::TestEnvironment;
+
+/// Lists the (name, size) of the entries in an uncompressed tar archive.
+fn list_tar_entries(path: &Path) -> Vec<(String, u64)> {
+    let mut archive = tar::Archive::new(std::fs::File::open(path).unwrap());
+    archive
+        .entries()
+        .unwrap()
+        .map(|entry| {
+            let entry = entry.unwrap();
+            let name = entry.path().unwrap().to_str().unwrap().to_owned();
+            (name, entry.size())
+        })
+        .collect()
+}
+
+#[test]
+fn test_archive_tar() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    std::fs::write(repo_path.join("file1"), "a\n").unwrap();
+    std::fs::create_dir(repo_path.join("dir")).unwrap();
+    std::fs::write(repo_path.join("dir").join("file2"), "bc\n").unwrap();
+    test_env.jj_cmd_ok(&repo_path, &["new"]);
+    std::fs::write(repo_path.join("file3"), "not committed\n").unwrap();
+
+    let archive_path = test_env.env_root().join("out.tar");
+    let (stdout, stderr) = test_env.jj_cmd_ok(
+        &repo_path,
+        &["archive", "-r@-", "-o", archive_path.to_str().unwrap()],
+    );
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r###"
+    Wrote 2 entries to $TEST_ENV/out.tar
+    "###);
+    insta::assert_debug_snapshot!(list_tar_entries(&archive_path), @r###"
+    [
+        (
+            "dir/file2",
+            3,
+        ),
+        (
+            "file1",
+            2,
+        ),
+    ]
+    "###);
+
+    // Filesets and --prefix
+    test_env.jj_cmd_ok(
+        &repo_path,
+        &[
+            "archive",
+            "-r@-",
+            "--prefix=project",
+            "-o",
+            archive_path.to_str().unwrap(),
+            "dir",
+        ],
+    );
+    insta::assert_debug_snapshot!(list_tar_entries(&archive_path), @r###"
+    [
+        (
+            "project/dir/file2",
+            3,
+        ),
+    ]
+    "###);
+
+    // Long paths are stored with the GNU long-name extension
+    let long_dir = "d".repeat(120);
+    std::fs::create_dir(repo_path.join(&long_dir)).unwrap();
+    std::fs::write(repo_path.join(&long_dir).join("file4"), "").unwrap();
+    test_env.jj_cmd_ok(
+        &repo_path,
+        &["archive", "-o", archive_path.to_str().unwrap(), &long_dir],
+    );
+    let entries = list_tar_entries(&archive_path);
+    assert_eq!(entries, vec![(format!("{long_dir}/file4"), 0)]);
+}
+
+#[test]
+fn test_archive_formats() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+    std::fs::write(repo_path.join("file"), "contents\n").unwrap();
+
+    // The format is inferred from the file extension
+    let archive_path = test_env.env_root().join("out.tar.gz");
+    test_env.jj_cmd_ok(
+        &repo_path,
+        &["archive", "-o", archive_path.to_str().unwrap()],
+    );
+    let data = std::fs::read(&archive_path).unwrap();
+    assert_eq!(data[..2], [0x1f, 0x8b]);
+
+    let archive_path = test_env.env_root().join("out.zip");
+    test_env.jj_cmd_ok(
+        &repo_path,
+        &["archive", "-o", archive_path.to_str().unwrap()],
+    );
+    let mut archive = zip::ZipArchive::new(std::fs::File::open(&archive_path).unwrap()).unwrap();
+    let mut file = archive.by_name("file").unwrap();
+    assert_eq!(file.unix_mode(), Some(0o100644));
+    let mut contents = String::new();
+    file.read_to_string(&mut contents).unwrap();
+    assert_eq!(contents, "contents\n");
+
+    // --format overrides the extension
+    let archive_path = test_env.env_root().join("out.zip");
+    test_env.jj_cmd_ok(
+        &repo_path,
+        &[
+            "archive",
+            "--format=tar",
+            "-o",
+            archive_path.to_str().unwrap(),
+        ],
+    );
+    insta::assert_debug_snapshot!(list_tar_entries(&archive_path), @r###"
+    [
+        (
+            "file",
+            9,
+        ),
+    ]
+    "###);
+}
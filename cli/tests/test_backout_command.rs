@@ -54,7 +54,7 @@ fn test_backout() {
     "###);
 
     // Backout the commit
-    let (stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["backout", "-r", "@"]);
+    let (stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["backout", "-r", "@", "--no-edit"]);
     insta::assert_snapshot!(stdout, @"");
     insta::assert_snapshot!(stderr, @"");
     insta::assert_snapshot!(get_log_output(&test_env, &repo_path), @r###"
@@ -71,7 +71,7 @@ fn test_backout() {
 
     // Backout the new backed-out commit
     test_env.jj_cmd_ok(&repo_path, &["edit", "@+"]);
-    let (stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["backout", "-r", "@"]);
+    let (stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["backout", "-r", "@", "--no-edit"]);
     insta::assert_snapshot!(stdout, @"");
     insta::assert_snapshot!(stderr, @"");
     insta::assert_snapshot!(get_log_output(&test_env, &repo_path), @r###"
@@ -119,8 +119,10 @@ fn test_backout_multiple() {
     "###);
 
     // Backout multiple commits
-    let (stdout, stderr) =
-        test_env.jj_cmd_ok(&repo_path, &["backout", "-r", "b", "-r", "c", "-r", "e"]);
+    let (stdout, stderr) = test_env.jj_cmd_ok(
+        &repo_path,
+        &["backout", "-r", "b", "-r", "c", "-r", "e", "--no-edit"],
+    );
     insta::assert_snapshot!(stdout, @"");
     insta::assert_snapshot!(stderr, @"");
     insta::assert_snapshot!(get_log_output(&test_env, &repo_path), @r###"
@@ -188,6 +190,74 @@ fn test_backout_multiple() {
     "#);
 }
 
+#[test]
+fn test_backout_description_template() {
+    let test_env = TestEnvironment::default();
+    test_env.add_config(
+        r#"
+        [templates]
+        backout_description = '''
+        "Revert " ++ change_id.short() ++ ": " ++ description.first_line() ++ "\n"
+        '''
+        "#,
+    );
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    create_commit(&test_env, &repo_path, "a", &[], &[("a", "a\n")]);
+    create_commit(&test_env, &repo_path, "b", &["a"], &[("a", "a\nb\n")]);
+
+    let (stdout, stderr) =
+        test_env.jj_cmd_ok(&repo_path, &["backout", "-r", "a", "-r", "b", "--no-edit"]);
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @"");
+    insta::assert_snapshot!(get_log_output(&test_env, &repo_path), @r"
+    ○  85d37943e733 Revert rlvkpnrzqnoo: a
+    ○  28d2d7ba87c9 Revert zsuskulnrvyr: b
+    @  46cc97af6802 b
+    ○  2443ea76b0b1 a
+    ◆  000000000000
+    ");
+}
+
+#[test]
+fn test_backout_edit() {
+    let mut test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+    let edit_script = test_env.set_up_fake_editor();
+
+    create_commit(&test_env, &repo_path, "a", &[], &[("a", "a\n")]);
+
+    std::fs::write(
+        &edit_script,
+        [
+            "dump editor0",
+            "write\nBack out a\n\nBecause it broke things.\n",
+        ]
+        .join("\0"),
+    )
+    .unwrap();
+    let (stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["backout", "-r", "a"]);
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @"");
+    insta::assert_snapshot!(
+        std::fs::read_to_string(test_env.env_root().join("editor0")).unwrap(), @r#"
+    Back out "a"
+
+    This backs out commit 2443ea76b0b1c531326908326aab7020abab8e6c.
+
+    JJ: Lines starting with "JJ:" (like this one) will be removed.
+    "#);
+    insta::assert_snapshot!(get_log_output(&test_env, &repo_path), @r"
+    ○  2d17e35d2d05 Back out a
+    │
+    │  Because it broke things.
+    @  2443ea76b0b1 a
+    ◆  000000000000
+    ");
+}
+
 fn get_log_output(test_env: &TestEnvironment, cwd: &Path) -> String {
     let template = r#"commit_id.short() ++ " " ++ description"#;
     test_env.jj_cmd_success(cwd, &["log", "-T", template])
@@ -1898,6 +1898,74 @@ fn test_bookmark_list_conflicted() {
     "###);
 }
 
+#[test]
+fn test_bookmark_resolve() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    test_env.jj_cmd_ok(&repo_path, &["new", "root()", "-m", "a"]);
+    test_env.jj_cmd_ok(&repo_path, &["new", "root()", "-m", "b"]);
+    test_env.jj_cmd_ok(
+        &repo_path,
+        &["bookmark", "create", "foo", "-r", "description(a)"],
+    );
+    test_env.jj_cmd_ok(&repo_path, &["git", "export"]);
+    test_env.jj_cmd_ok(
+        &repo_path,
+        &[
+            "bookmark",
+            "create",
+            "foo",
+            "-r",
+            "description(b)",
+            "--at-op=@--",
+        ],
+    );
+    test_env.jj_cmd_ok(&repo_path, &["status"]);
+
+    // List the sides
+    let (stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["bookmark", "resolve", "foo"]);
+    insta::assert_snapshot!(stdout, @r"
+    1: rlvkpnrz d8d5f980 foo?? foo@git | (empty) a (from git)
+    2: kkmpptxz 06a973bc foo?? | (empty) b
+    ");
+    insta::assert_snapshot!(stderr, @"Hint: Use `jj bookmark resolve foo --keep SIDE` to point the bookmark to one of these.");
+
+    // Bad sides
+    let stderr = test_env.jj_cmd_failure(&repo_path, &["bookmark", "resolve", "foo", "--keep=3"]);
+    insta::assert_snapshot!(stderr, @r"
+    Error: Bookmark foo has no side 3
+    Hint: Run `jj bookmark resolve foo` to list the sides.
+    ");
+    let stderr =
+        test_env.jj_cmd_failure(&repo_path, &["bookmark", "resolve", "foo", "--keep=origin"]);
+    insta::assert_snapshot!(stderr, @r"
+    Error: Bookmark foo@origin doesn't point to a side of the conflict
+    Hint: Run `jj bookmark resolve foo` to list the sides.
+    ");
+
+    // Keep a side by number
+    let (stdout, stderr) =
+        test_env.jj_cmd_ok(&repo_path, &["bookmark", "resolve", "foo", "--keep=2"]);
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @"Moved bookmark foo to kkmpptxz 06a973bc foo* | (empty) b");
+    insta::assert_snapshot!(get_bookmark_output(&test_env, &repo_path), @r"
+    foo: kkmpptxz 06a973bc (empty) b
+      @git (ahead by 1 commits, behind by 1 commits): rlvkpnrz d8d5f980 (empty) a
+    ");
+
+    // Keep a side by remote
+    test_env.jj_cmd_ok(&repo_path, &["undo"]);
+    let (_stdout, stderr) =
+        test_env.jj_cmd_ok(&repo_path, &["bookmark", "resolve", "foo", "--keep=git"]);
+    insta::assert_snapshot!(stderr, @"Moved bookmark foo to rlvkpnrz d8d5f980 foo | (empty) a");
+
+    // Not conflicted anymore
+    let stderr = test_env.jj_cmd_failure(&repo_path, &["bookmark", "resolve", "foo"]);
+    insta::assert_snapshot!(stderr, @"Error: Bookmark foo is not conflicted");
+}
+
 fn get_log_output(test_env: &TestEnvironment, cwd: &Path) -> String {
     let template = r#"bookmarks ++ " " ++ commit_id.short()"#;
     test_env.jj_cmd_success(cwd, &["log", "-T", template])
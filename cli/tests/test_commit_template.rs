@@ -337,6 +337,13 @@ fn test_log_builtin_templates() {
 
     [EOF]
     "#);
+
+    insta::assert_snapshot!(render(r#"builtin_log_json"#), @r#"
+    {"commit_id":"dc31539712c7294d1d712cec63cef4504b94ca74","change_id":"rlvkpnrzqnoowoytxnquwvuryrwnrmlp","parents":["230dd059e1b059aefc0da06a2e5a7dbf22362f22"],"description":"","author":{"name":"","email":"","timestamp":"2001-02-03T04:05:08+07:00"},"committer":{"name":"","email":"","timestamp":"2001-02-03T04:05:08+07:00"},"local_bookmarks":["my-bookmark"],"remote_bookmarks":[],"tags":[],"working_copies":"","current_working_copy":true,"immutable":false,"conflict":false,"empty":true,"divergent":false,"hidden":false,"diff_summary":""}
+    {"commit_id":"230dd059e1b059aefc0da06a2e5a7dbf22362f22","change_id":"qpvuntsmwlqtpsluzzsnyyzlmlwvmlnu","parents":["0000000000000000000000000000000000000000"],"description":"","author":{"name":"Test User","email":"test.user@example.com","timestamp":"2001-02-03T04:05:07+07:00"},"committer":{"name":"Test User","email":"test.user@example.com","timestamp":"2001-02-03T04:05:07+07:00"},"local_bookmarks":[],"remote_bookmarks":[],"tags":[],"working_copies":"","current_working_copy":false,"immutable":false,"conflict":false,"empty":true,"divergent":false,"hidden":false,"diff_summary":""}
+    {"commit_id":"0000000000000000000000000000000000000000","change_id":"zzzzzzzzzzzzzzzzzzzzzzzzzzzzzzzz","parents":[],"description":"","author":{"name":"","email":"","timestamp":"1970-01-01T00:00:00+00:00"},"committer":{"name":"","email":"","timestamp":"1970-01-01T00:00:00+00:00"},"local_bookmarks":[],"remote_bookmarks":[],"tags":[],"working_copies":"","current_working_copy":false,"immutable":true,"conflict":false,"empty":true,"divergent":false,"hidden":false,"diff_summary":""}
+    [EOF]
+    "#);
 }
 
 #[test]
@@ -468,6 +475,114 @@ fn test_log_builtin_templates_colored_debug() {
     "#);
 }
 
+#[test]
+fn test_log_signature() {
+    let test_env = TestEnvironment::default();
+    test_env.add_config(
+        r#"
+        [signing]
+        backend = "test"
+        key = "some-key"
+        "#,
+    );
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+    test_env.jj_cmd_ok(&repo_path, &["commit", "-m", "unsigned"]);
+    test_env.jj_cmd_ok(&repo_path, &["describe", "-m", "good"]);
+    test_env.jj_cmd_ok(&repo_path, &["sign"]);
+
+    // Add commits with tampered and unreadable signatures through Git
+    let git_repo = git2::Repository::open(repo_path.join(".jj/repo/store/git")).unwrap();
+    let good_commit_id =
+        test_env.jj_cmd_success(&repo_path, &["log", "--no-graph", "-r@", "-T", "commit_id"]);
+    let good_commit = git_repo
+        .find_commit(git2::Oid::from_str(&good_commit_id).unwrap())
+        .unwrap();
+    let signature =
+        git2::Signature::new("Someone", "someone@example.com", &git2::Time::new(0, 0)).unwrap();
+    let tree = good_commit.tree().unwrap();
+    for (message, sig) in [
+        ("bad", "--- JJ-TEST-SIGNATURE ---\nKEY: other-key\n0000"),
+        ("unknown", "garbage"),
+    ] {
+        let buf = git_repo
+            .commit_create_buffer(&signature, &signature, message, &tree, &[&good_commit])
+            .unwrap();
+        let oid = git_repo
+            .commit_signed(buf.as_str().unwrap(), sig, None)
+            .unwrap();
+        git_repo
+            .reference(&format!("refs/heads/{message}"), oid, false, "")
+            .unwrap();
+    }
+    test_env.jj_cmd_ok(&repo_path, &["git", "import"]);
+
+    let template = r#"
+    separate(" ",
+      description.first_line(),
+      if(signature,
+        separate(" ", signature.status(), signature.key(), signature.display()),
+        "unsigned",
+      ),
+    ) ++ "\n"
+    "#;
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &[
+            "log",
+            "--no-graph",
+            "-r",
+            "::(bad|unknown) ~ root()",
+            "-T",
+            template,
+        ],
+    );
+    insta::assert_snapshot!(stdout, @r"
+    bad bad other-key
+    unknown unknown
+    good good some-key
+    unsigned unsigned
+    ");
+
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &[
+            "log",
+            "-r",
+            "::(bad|unknown) ~ root()",
+            "-T",
+            "description",
+            "--verify-sigs",
+        ],
+    );
+    insta::assert_snapshot!(stdout, @r"
+    ○  bad
+    │  Signature: bad (key other-key)
+    │ ○  unknown
+    ├─╯  Signature: unknown
+    @  good
+    ○  unsigned
+    │
+    ~
+    ");
+
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &[
+            "show",
+            "bad",
+            "--no-patch",
+            "--verify-sigs",
+            "-T",
+            "description",
+        ],
+    );
+    insta::assert_snapshot!(stdout, @r"
+    bad
+    Signature: bad (key other-key)
+    ");
+}
+
 #[test]
 fn test_log_evolog_divergence() {
     let test_env = TestEnvironment::default();
@@ -648,6 +763,70 @@ fn test_log_bookmarks() {
     "###);
 }
 
+#[test]
+fn test_log_bookmarks_last_moved() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    test_env.jj_cmd_ok(&repo_path, &["bookmark", "create", "foo"]);
+    test_env.jj_cmd_ok(&repo_path, &["new", "-m=first"]);
+    test_env.jj_cmd_ok(&repo_path, &["bookmark", "create", "bar"]);
+    test_env.jj_cmd_ok(&repo_path, &["new", "-m=second"]);
+    // Rewriting the commit moves the bookmark
+    test_env.jj_cmd_ok(&repo_path, &["describe", "@-", "-m=first (edited)"]);
+    test_env.jj_cmd_ok(&repo_path, &["tag", "create", "-rfoo", "v1"]);
+
+    let template = r#"
+    separate(" ",
+      bookmarks.map(|ref| concat(
+        ref, "[", ref.last_moved_op_id().substr(0, 12), " ",
+        ref.last_moved_time().format("%H:%M:%S"), " ",
+        ref.last_moved_op_description(), "]",
+      )),
+      tags.map(|ref| concat(ref, "[", ref.last_moved_op_description(), "]")),
+    )
+    "#;
+    let output = test_env.jj_cmd_success(&repo_path, &["log", "-r::", "-T", template]);
+    insta::assert_snapshot!(output, @r"
+    @
+    ○  bar[75afb78c02fd 04:05:12 describe commit 8ae34eb0dccb6fdae9f0c94e4d7b0475c876f9ee]
+    ◆  foo[e36b992f8915 04:05:08 create bookmark foo pointing to commit 230dd059e1b059aefc0da06a2e5a7dbf22362f22] v1[<Error: Not a bookmark>]
+    ◆
+    ");
+    let output = test_env.jj_cmd_success(
+        &repo_path,
+        &[
+            "op",
+            "log",
+            "--no-graph",
+            "-T",
+            r#"id.short() ++ " " ++ description ++ "\n""#,
+        ],
+    );
+    insta::assert_snapshot!(output, @r"
+    7881129203a6 create tag v1 pointing to commit 230dd059e1b059aefc0da06a2e5a7dbf22362f22
+    75afb78c02fd describe commit 8ae34eb0dccb6fdae9f0c94e4d7b0475c876f9ee
+    d4c9e1faa129 new empty commit
+    7143cf5a259e create bookmark bar pointing to commit 8ae34eb0dccb6fdae9f0c94e4d7b0475c876f9ee
+    6a2d8661c582 new empty commit
+    e36b992f8915 create bookmark foo pointing to commit 230dd059e1b059aefc0da06a2e5a7dbf22362f22
+    eac759b9ab75 add workspace 'default'
+    000000000000
+    ");
+
+    // Operations are looked up from the loaded operation
+    let template = r#"name ++ ": " ++ last_moved_op_description ++ "\n""#;
+    let output = test_env.jj_cmd_success(
+        &repo_path,
+        &["bookmark", "list", "--at-op=@--", "-T", template],
+    );
+    insta::assert_snapshot!(output, @r"
+    bar: create bookmark bar pointing to commit 8ae34eb0dccb6fdae9f0c94e4d7b0475c876f9ee
+    foo: create bookmark foo pointing to commit 230dd059e1b059aefc0da06a2e5a7dbf22362f22
+    ");
+}
+
 #[test]
 fn test_log_git_head() {
     let test_env = TestEnvironment::default();
@@ -947,6 +1126,152 @@ fn test_log_contained_in() {
     "###);
 }
 
+#[test]
+fn test_log_merge_base() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+    test_env.jj_cmd_ok(&repo_path, &["new", "-mA", "root()"]);
+    test_env.jj_cmd_ok(&repo_path, &["new", "-mB"]);
+    test_env.jj_cmd_ok(&repo_path, &["bookmark", "create", "main"]);
+    test_env.jj_cmd_ok(&repo_path, &["new", "-mC"]);
+    test_env.jj_cmd_ok(&repo_path, &["new", "-mD", "root()"]);
+
+    let template = r#"
+    separate(" ",
+      description.first_line(),
+      bookmarks,
+      if(self.merge_base("main").root(),
+        "[root]",
+        "[" ++ self.merge_base("main").description().first_line() ++ "]"),
+      if(self.merge_base("none()"), "[unexpected]"),
+    ) ++ "\n"
+    "#;
+    let stdout = test_env.jj_cmd_success(&repo_path, &["log", "-r::", "-T", template]);
+    insta::assert_snapshot!(stdout, @r###"
+    @  D [root]
+    │ ○  C [B]
+    │ ○  B main [B]
+    │ ○  A [A]
+    ├─╯
+    ◆  [root]
+    "###);
+
+    // Commit-valued properties can be navigated further
+    let template = r#"
+    self.merge_base("main").parents().map(|c| c.description().first_line()) ++ "\n"
+    "#;
+    let stdout = test_env.jj_cmd_success(&repo_path, &["log", "-r::", "-T", template]);
+    insta::assert_snapshot!(stdout, @r###"
+    @
+    │ ○  A
+    │ ○  A
+    │ ○
+    ├─╯
+    ◆
+    "###);
+
+    let stderr = test_env.jj_cmd_failure(
+        &repo_path,
+        &["log", "-r::", "-T", r#"self.merge_base("maine").root()"#],
+    );
+    insta::assert_snapshot!(stderr, @r###"
+    Error: Failed to parse template: Failed to evaluate revset
+    Caused by:
+    1:  --> 1:17
+      |
+    1 | self.merge_base("maine").root()
+      |                 ^-----^
+      |
+      = Failed to evaluate revset
+    2: Revision "maine" doesn't exist
+    Hint: Did you mean "main"?
+    "###);
+}
+
+#[test]
+fn test_log_classes() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+    test_env.jj_cmd_ok(&repo_path, &["new", "-mA", "root()"]);
+    test_env.jj_cmd_ok(&repo_path, &["new", "-mwip: B"]);
+    test_env.jj_cmd_ok(&repo_path, &["new", "-mC"]);
+    test_env.jj_cmd_ok(&repo_path, &["new", "-mwip: D", "root()"]);
+
+    let template = r#"separate(" ", description.first_line(), classes) ++ "\n""#;
+
+    let stdout = test_env.jj_cmd_success(&repo_path, &["log", "-T", template]);
+    insta::assert_snapshot!(stdout, @r"
+    @  wip: D
+    │ ○  C
+    │ ○  wip: B
+    │ ○  A
+    ├─╯
+    ◆
+    ");
+
+    test_env.add_config(
+        r#"
+        [classes]
+        wip = 'description(glob:"wip*")'
+        tip = { revset = 'heads(all())', warn-on-push = true }
+        "#,
+    );
+    let stdout = test_env.jj_cmd_success(&repo_path, &["log", "-T", template]);
+    insta::assert_snapshot!(stdout, @r"
+    @  wip: D tip wip
+    │ ○  C tip
+    │ ○  wip: B wip
+    │ ○  A
+    ├─╯
+    ◆
+    ");
+
+    // Commits are labeled with their classes
+    test_env.add_config(r#"colors."class wip" = "red""#);
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &["log", "--color=always", "-T", "description.first_line()"],
+    );
+    insta::assert_snapshot!(stdout, @r"
+    [1m[38;5;2m@[0m  [38;5;1mwip: D[39m
+    │ ○  C
+    │ ○  [38;5;1mwip: B[39m
+    │ ○  A
+    ├─╯
+    [1m[38;5;14m◆[0m
+    ");
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &[
+            "log",
+            "--color=debug",
+            "-r@",
+            "-T",
+            "description.first_line()",
+        ],
+    );
+    insta::assert_snapshot!(stdout, @r"
+    [1m[38;5;2m<<node working_copy::@>>[0m  [38;5;1m<<log class tip class wip description first_line::wip: D>>[39m
+    │
+    ~
+    ");
+
+    test_env.add_config("classes.bad = 'unknown_fn()'");
+    let stderr = test_env.jj_cmd_failure(&repo_path, &["log", "-T", template]);
+    insta::assert_snapshot!(stderr, @r#"
+    Config error: Invalid `classes.bad`
+    Caused by:  --> 1:1
+      |
+    1 | unknown_fn()
+      | ^--------^
+      |
+      = Function "unknown_fn" doesn't exist
+    For help, see https://jj-vcs.github.io/jj/latest/config/.
+    "#);
+}
+
 #[test]
 fn test_short_prefix_in_transaction() {
     let test_env = TestEnvironment::default();
@@ -82,6 +82,7 @@ fn test_bookmark_names() {
     --color	When to colorize output (always, never, debug, auto)
     --quiet	Silence non-primary command output
     --no-pager	Disable the pager
+    --conflict-marker-style	Style of conflict markers to materialize in the working copy
     --config	Additional configuration options (can be repeated)
     --config-file	Additional configuration files (can be repeated)
     --help	Print help (see more with '--help')
@@ -400,6 +401,7 @@ fn test_revisions() {
     insta::assert_snapshot!(stdout, @r"
     immutable_bookmark	immutable
     mutable_bookmark	mutable
+    default@	working_copy
     k	working_copy
     y	mutable
     q	immutable
@@ -412,6 +414,7 @@ fn test_revisions() {
     let stdout = test_env.jj_cmd_success(&repo_path, &["--", "jj", "squash", "--into", ""]);
     insta::assert_snapshot!(stdout, @r"
     mutable_bookmark	mutable
+    default@	working_copy
     k	working_copy
     y	mutable
     zq	remote_commit
@@ -423,6 +426,7 @@ fn test_revisions() {
     insta::assert_snapshot!(stdout, @r"
     immutable_bookmark	immutable
     mutable_bookmark	mutable
+    default@	working_copy
     k	working_copy
     y	mutable
     q	immutable
@@ -521,15 +525,20 @@ fn test_config() {
 
     let stdout = test_env.jj_cmd_success(dir, &["--", "jj", "config", "get", "c"]);
     insta::assert_snapshot!(stdout, @r"
-    core.fsmonitor	Whether to use an external filesystem monitor, useful for large repos
+    core.fsmonitor	Whether to use a filesystem monitor, useful for large repos. The native monitor is only supported on Linux
+    core.symlinks	Whether to check out symlinks as symlinks if the platform allows it, or always as regular files containing the link target
+    core.verify-writes	Whether to read back every object written to the store and check that it matches what was written
     core.watchman.register_snapshot_trigger	Whether to use triggers to monitor for changes in the background.
     ");
 
     let stdout = test_env.jj_cmd_success(dir, &["--", "jj", "config", "list", "c"]);
     insta::assert_snapshot!(stdout, @r"
+    classes	Named classes of commits, which can be queried by templates and are labeled in `jj log`
     colors	Mapping from jj formatter labels to colors
     core
-    core.fsmonitor	Whether to use an external filesystem monitor, useful for large repos
+    core.fsmonitor	Whether to use a filesystem monitor, useful for large repos. The native monitor is only supported on Linux
+    core.symlinks	Whether to check out symlinks as symlinks if the platform allows it, or always as regular files containing the link target
+    core.verify-writes	Whether to read back every object written to the store and check that it matches what was written
     core.watchman
     core.watchman.register_snapshot_trigger	Whether to use triggers to monitor for changes in the background.
     ");
@@ -83,6 +83,152 @@ fn test_debug_revset() {
     });
 }
 
+#[test]
+fn test_debug_revset_commit() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let workspace_path = test_env.env_root().join("repo");
+    test_env.jj_cmd_ok(&workspace_path, &["describe", "-m", "first"]);
+    test_env.jj_cmd_ok(&workspace_path, &["new", "-m", "second"]);
+
+    let stdout = test_env.jj_cmd_success(
+        &workspace_path,
+        &["debug", "revset", "description(first)", "--commit", "::@"],
+    );
+    assert_snapshot!(stdout, @r"
+    false kkmpptxz 9ed53a4a (empty) second
+    true qpvuntsm fa15625b (empty) first
+    false zzzzzzzz 00000000 (empty) (no description set)
+    ");
+}
+
+#[test]
+fn test_debug_revset_check() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let workspace_path = test_env.env_root().join("repo");
+
+    let (stdout, stderr) = test_env.jj_cmd_ok(&workspace_path, &["debug", "revset", "--check"]);
+    assert_snapshot!(stdout, @"");
+    assert_snapshot!(stderr, @"Checked 8 revsets in config");
+
+    test_env.add_config(
+        r#"
+    revsets.fix = "bad(@)"
+    [revset-aliases]
+    'good(x)' = "x & mine()"
+    'broken(x)' = "x &"
+    "#,
+    );
+    let stderr = test_env.jj_cmd_failure(&workspace_path, &["debug", "revset", "--check"]);
+    assert_snapshot!(stderr, @r#"
+    Warning: Failed to parse "revset-aliases.broken(x)"
+     --> 1:1
+      |
+    1 | broken(x)
+      | ^-------^
+      |
+      = In alias "broken(x)"
+     --> 1:4
+      |
+    1 | x &
+      |    ^---
+      |
+      = expected `::`, `..`, `~`, or <primary>
+    Warning: Failed to parse "revsets.fix"
+     --> 1:1
+      |
+    1 | bad(@)
+      | ^-^
+      |
+      = Function "bad" doesn't exist
+    Error: 2 of 10 revsets in config failed to parse
+    "#);
+}
+
+#[test]
+fn test_debug_template() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let workspace_path = test_env.env_root().join("repo");
+    test_env.jj_cmd_ok(&workspace_path, &["describe", "-m", "first"]);
+    test_env.jj_cmd_ok(&workspace_path, &["new", "-m", "second"]);
+
+    let stdout = test_env.jj_cmd_success(
+        &workspace_path,
+        &[
+            "debug",
+            "template",
+            r#"description.first_line() ++ "\n""#,
+            "-r",
+            "::@",
+        ],
+    );
+    assert_snapshot!(stdout, @r"
+    second
+    first
+    ");
+
+    let stderr = test_env.jj_cmd_failure(
+        &workspace_path,
+        &["debug", "template", "description.bad()", "-r", "@"],
+    );
+    assert_snapshot!(stderr, @r#"
+    Error: Failed to parse template: Method "bad" doesn't exist for type "String"
+    Caused by:  --> 1:13
+      |
+    1 | description.bad()
+      |             ^-^
+      |
+      = Method "bad" doesn't exist for type "String"
+    "#);
+}
+
+#[test]
+fn test_debug_template_check() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let workspace_path = test_env.env_root().join("repo");
+
+    let (stdout, stderr) = test_env.jj_cmd_ok(&workspace_path, &["debug", "template", "--check"]);
+    assert_snapshot!(stdout, @"");
+    assert_snapshot!(stderr, @"Checked 49 templates in config");
+
+    test_env.add_config(
+        r#"
+    templates.log = "commit_id.bad()"
+    templates.op_log = "id.short()"
+    [template-aliases]
+    'good(x)' = "x ++ description"
+    'broken(x)' = "x ++"
+    "#,
+    );
+    let stderr = test_env.jj_cmd_failure(&workspace_path, &["debug", "template", "--check"]);
+    assert_snapshot!(stderr, @r#"
+    Warning: Failed to parse "template-aliases.broken(x)"
+     --> 1:1
+      |
+    1 | broken(x)
+      | ^-------^
+      |
+      = In alias "broken(x)"
+     --> 1:5
+      |
+    1 | x ++
+      |     ^---
+      |
+      = expected <expression>
+    Warning: Failed to parse "templates.log"
+     --> 1:11
+      |
+    1 | commit_id.bad()
+      |           ^-^
+      |
+      = Method "bad" doesn't exist for type "CommitOrChangeId"
+    Error: 2 of 51 templates in config failed to parse
+    "#);
+}
+
 #[test]
 fn test_debug_index() {
     let test_env = TestEnvironment::default();
@@ -103,6 +249,73 @@ fn test_debug_index() {
     );
 }
 
+#[test]
+fn test_debug_profile_revset() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let workspace_path = test_env.env_root().join("repo");
+    test_env.jj_cmd_ok(&workspace_path, &["new"]);
+    let stdout = test_env.jj_cmd_success(
+        &workspace_path,
+        &["debug", "profile-revset", "-n3", "::@ & ~root()"],
+    );
+    assert_snapshot!(filter_durations(&stdout), @r"
+    Revset: ::@ & ~root()
+    Number of commits: 2
+    Index:
+      Number of commits: 3
+      Number of merges: 0
+      Max generation number: 2
+      Number of heads: 1
+      Number of levels: 1
+    Timings over 3 iterations:
+      phase               min         mean          max
+      parse [time] [time] [time]
+      resolve [time] [time] [time]
+      optimize [time] [time] [time]
+      evaluate [time] [time] [time]
+      iterate [time] [time] [time]
+      total [time] [time] [time]
+    ");
+
+    let stderr =
+        test_env.jj_cmd_cli_error(&workspace_path, &["debug", "profile-revset", "-n0", "@"]);
+    assert_snapshot!(stderr, @r"
+    error: invalid value '0' for '--iterations <ITERATIONS>': 0 is not in 1..=4294967295
+
+    For more information, try '--help'.
+    ");
+    let stderr = test_env.jj_cmd_failure(&workspace_path, &["debug", "profile-revset", "foo"]);
+    assert_snapshot!(stderr, @r#"Error: Revision "foo" doesn't exist"#);
+}
+
+#[test]
+fn test_debug_reachability() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let workspace_path = test_env.env_root().join("repo");
+    test_env.jj_cmd_ok(&workspace_path, &["describe", "-m=a"]);
+    test_env.jj_cmd_ok(&workspace_path, &["new", "-m=b"]);
+    test_env.jj_cmd_ok(&workspace_path, &["new", "root()", "-m=c"]);
+    let stdout = test_env.jj_cmd_success(
+        &workspace_path,
+        &[
+            "debug",
+            "reachability",
+            "root() | description(a) | @",
+            "description(b) | @",
+        ],
+    );
+    assert_snapshot!(stdout, @r"
+    3b2085f07d42 -> 3b2085f07d42
+    d8d5f980a897 -/-> 3b2085f07d42
+    000000000000 -> 3b2085f07d42
+    3b2085f07d42 -/-> 017c7f689ed7
+    d8d5f980a897 -> 017c7f689ed7
+    000000000000 -> 017c7f689ed7
+    ");
+}
+
 #[test]
 fn test_debug_reindex() {
     let test_env = TestEnvironment::default();
@@ -128,9 +341,10 @@ fn test_debug_reindex() {
     );
     let (stdout, stderr) = test_env.jj_cmd_ok(&workspace_path, &["debug", "reindex"]);
     assert_snapshot!(stdout, @"");
-    insta::assert_snapshot!(stderr, @r###"
+    insta::assert_snapshot!(filter_backup_names(&stderr), @r"
+    Saved a backup of the index as [timestamp]-reindex.
     Finished indexing 4 commits.
-    "###);
+    ");
     let stdout = test_env.jj_cmd_success(&workspace_path, &["debug", "index"]);
     assert_snapshot!(filter_index_stats(&stdout), @r###"
     Number of commits: 4
@@ -146,6 +360,129 @@ fn test_debug_reindex() {
     );
 }
 
+#[test]
+fn test_debug_restore_backup() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let workspace_path = test_env.env_root().join("repo");
+    test_env.jj_cmd_ok(&workspace_path, &["new"]);
+    test_env.jj_cmd_ok(&workspace_path, &["new"]);
+    let stdout = test_env.jj_cmd_success(&workspace_path, &["debug", "restore-backup"]);
+    assert_snapshot!(stdout, @"");
+
+    let stdout = test_env.jj_cmd_success(&workspace_path, &["debug", "index"]);
+    let index_before = filter_index_stats(&stdout);
+    test_env.jj_cmd_ok(&workspace_path, &["debug", "reindex"]);
+    let stdout = test_env.jj_cmd_success(&workspace_path, &["debug", "index"]);
+    assert_ne!(filter_index_stats(&stdout), index_before);
+
+    // Reindexing again without a backup doesn't add one
+    test_env.jj_cmd_ok(&workspace_path, &["debug", "reindex", "--no-backup"]);
+    let stdout = test_env.jj_cmd_success(&workspace_path, &["debug", "restore-backup"]);
+    assert_snapshot!(filter_backup_names(&stdout), @"[timestamp]-reindex: reindex (index)");
+    let name = stdout.split(':').next().unwrap();
+
+    let (stdout, stderr) = test_env.jj_cmd_ok(&workspace_path, &["debug", "restore-backup", name]);
+    assert_snapshot!(stdout, @"");
+    assert_snapshot!(filter_backup_names(&stderr), @"Restored index from backup [timestamp]-reindex.");
+    let stdout = test_env.jj_cmd_success(&workspace_path, &["debug", "index"]);
+    assert_eq!(filter_index_stats(&stdout), index_before);
+
+    let stderr = test_env.jj_cmd_failure(&workspace_path, &["debug", "restore-backup", "bad"]);
+    assert_snapshot!(stderr, @"Error: No backup named bad");
+}
+
+#[test]
+fn test_debug_commit_graph() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let workspace_path = test_env.env_root().join("repo");
+    test_env.jj_cmd_ok(&workspace_path, &["new"]);
+    let (stdout, stderr) =
+        test_env.jj_cmd_ok(&workspace_path, &["debug", "commit-graph", "export"]);
+    assert_snapshot!(stdout, @r"
+    jj-commit-graph v1
+    0000000000000000000000000000000000000000 00000000000000000000000000000000
+    230dd059e1b059aefc0da06a2e5a7dbf22362f22 9a45c67d3e96a7e5007c110ede34dec5 0000000000000000000000000000000000000000
+    65b6b74e08973b88d38404430f119c8c79465250 8e4fac809cbb3b162c953458183c8dea 230dd059e1b059aefc0da06a2e5a7dbf22362f22
+    ");
+    assert_snapshot!(stderr, @"Exported 3 commits.");
+    let (_stdout, stderr) = test_env.jj_cmd_ok(
+        &workspace_path,
+        &["debug", "commit-graph", "export", "graph.txt"],
+    );
+    assert_snapshot!(stderr, @"Exported 3 commits.");
+
+    // Commits created after the export are indexed as usual
+    test_env.jj_cmd_ok(&workspace_path, &["new"]);
+    let (stdout, stderr) = test_env.jj_cmd_ok(
+        &workspace_path,
+        &["debug", "commit-graph", "import", "graph.txt"],
+    );
+    assert_snapshot!(stdout, @"");
+    assert_snapshot!(stderr, @"Imported 3 commits. Finished indexing 5 commits.");
+    let stdout = test_env.jj_cmd_success(&workspace_path, &["debug", "index"]);
+    assert_snapshot!(filter_index_stats(&stdout), @r"
+    Number of commits: 5
+    Number of merges: 0
+    Max generation number: 3
+    Number of heads: 2
+    Number of changes: 4
+    Stats per level:
+      Level 0:
+        Number of commits: 5
+        Name: [hash]
+    ");
+    let stdout = test_env.jj_cmd_success(&workspace_path, &["log", "-T", "commit_id.short()"]);
+    assert_snapshot!(stdout, @r"
+    @  f07de6aa055e
+    ○  34dcc9de4137
+    ○  230dd059e1b0
+    ◆  000000000000
+    ");
+
+    // Parents must be listed before their children
+    std::fs::write(
+        workspace_path.join("bad.txt"),
+        format!(
+            "jj-commit-graph v1\n{} {} {}\n",
+            "1".repeat(40),
+            "2".repeat(32),
+            "3".repeat(40)
+        ),
+    )
+    .unwrap();
+    let stderr = test_env.jj_cmd_failure(
+        &workspace_path,
+        &["debug", "commit-graph", "import", "bad.txt"],
+    );
+    assert_snapshot!(stderr, @r"
+    Error: Failed to import commit graph
+    Caused by: Invalid commit graph at line 2: parent 3333333333333333333333333333333333333333 is not listed before commit 1111111111111111111111111111111111111111
+    ");
+
+    // Commits must be listed only once
+    std::fs::write(
+        workspace_path.join("bad.txt"),
+        format!(
+            "jj-commit-graph v1\n{0} {1}\n{2} {3} {0}\n{2} {3} {0}\n",
+            "0".repeat(40),
+            "0".repeat(32),
+            "1".repeat(40),
+            "2".repeat(32),
+        ),
+    )
+    .unwrap();
+    let stderr = test_env.jj_cmd_failure(
+        &workspace_path,
+        &["debug", "commit-graph", "import", "bad.txt"],
+    );
+    assert_snapshot!(stderr, @r"
+    Error: Failed to import commit graph
+    Caused by: Invalid commit graph at line 4: commit 1111111111111111111111111111111111111111 is listed more than once
+    ");
+}
+
 #[test]
 fn test_debug_tree() {
     let test_env = TestEnvironment::default();
@@ -227,6 +564,42 @@ fn test_debug_tree() {
     );
 }
 
+#[test]
+fn test_debug_store_stats() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let workspace_path = test_env.env_root().join("repo");
+    std::fs::write(workspace_path.join("file1"), "content\n").unwrap();
+    std::fs::write(workspace_path.join("file2"), "content\n").unwrap();
+    test_env.jj_cmd_ok(&workspace_path, &["new"]);
+
+    // The number of commits and trees depends on the operations run so far, so
+    // only check the files.
+    let stdout = test_env.jj_cmd_success(&workspace_path, &["debug", "store", "stats"]);
+    let (objects, rest) = stdout.split_once("Largest files:\n").unwrap();
+    assert!(objects.starts_with("Backend: git\n"), "{objects}");
+    assert!(objects.contains("  Files: 1 (8 bytes)\n"), "{objects}");
+    assert_snapshot!(rest, @r"
+      8 bytes d95f3ad14dee file1
+    Deduplication over 3 revisions:
+      Referenced file contents: 32 bytes
+      Unique file contents: 8 bytes
+      Ratio: 4.00
+    ");
+
+    // The ratio is only computed for the given revisions
+    let stdout = test_env.jj_cmd_success(
+        &workspace_path,
+        &["debug", "store", "stats", "-r=root()", "--limit=0"],
+    );
+    let (_, rest) = stdout.split_once("Largest files:\n").unwrap();
+    assert_snapshot!(rest, @r"
+    Deduplication over 1 revisions:
+      Referenced file contents: 0 bytes
+      Unique file contents: 0 bytes
+    ");
+}
+
 #[test]
 fn test_debug_operation_id() {
     let test_env = TestEnvironment::default();
@@ -240,7 +613,17 @@ fn test_debug_operation_id() {
     );
 }
 
+fn filter_backup_names(text: &str) -> String {
+    let regex = Regex::new(r"[0-9]{8}T[0-9]{6}\.[0-9]{3}Z-").unwrap();
+    regex.replace_all(text, "[timestamp]-").to_string()
+}
+
 fn filter_index_stats(text: &str) -> String {
     let regex = Regex::new(r"    Name: [0-9a-z]+").unwrap();
     regex.replace_all(text, "    Name: [hash]").to_string()
 }
+
+fn filter_durations(text: &str) -> String {
+    let regex = Regex::new(r" +[0-9.]+(ns|µs|ms|s)\b").unwrap();
+    regex.replace_all(text, " [time]").to_string()
+}
@@ -491,6 +491,74 @@ fn test_describe_default_description() {
     "###);
 }
 
+#[test]
+fn test_describe_default_description_file() {
+    let mut test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    test_env.add_config(r#"ui.default-description = "\n\nTESTED=TODO""#);
+    test_env.add_config(r#"ui.default-description-file = "description-template""#);
+    let workspace_path = test_env.env_root().join("repo");
+    let edit_script = test_env.set_up_fake_editor();
+
+    // Falls back to ui.default-description if the file doesn't exist
+    std::fs::write(&edit_script, ["dump editor"].join("\0")).unwrap();
+    test_env.jj_cmd_ok(&workspace_path, &["describe"]);
+    insta::assert_snapshot!(
+        std::fs::read_to_string(test_env.env_root().join("editor")).unwrap(), @r#"
+    TESTED=TODO
+
+    JJ: Lines starting with "JJ:" (like this one) will be removed.
+    "#);
+
+    std::fs::write(
+        workspace_path.join("description-template"),
+        "{{summary}}\n\nTESTED={{how was this tested?}}\n",
+    )
+    .unwrap();
+    test_env.jj_cmd_ok(&workspace_path, &["new"]);
+    std::fs::write(&edit_script, ["dump editor"].join("\0")).unwrap();
+    let stderr = test_env.jj_cmd_failure(&workspace_path, &["describe"]);
+    insta::assert_snapshot!(stderr, @r"
+    Error: The description still contains placeholders from the description template: {{summary}}, {{how was this tested?}}
+    Hint: Replace the placeholders with text, or remove them.
+    ");
+    insta::assert_snapshot!(
+        std::fs::read_to_string(test_env.env_root().join("editor")).unwrap(), @r#"
+    {{summary}}
+
+    TESTED={{how was this tested?}}
+
+    JJ: Lines starting with "JJ:" (like this one) will be removed.
+    "#);
+
+    // Placeholders partially filled in
+    std::fs::write(
+        &edit_script,
+        "write\nAdd a feature\n\nTESTED={{how was this tested?}}\n",
+    )
+    .unwrap();
+    let stderr = test_env.jj_cmd_failure(&workspace_path, &["describe"]);
+    insta::assert_snapshot!(stderr, @r"
+    Error: The description still contains placeholders from the description template: {{how was this tested?}}
+    Hint: Replace the placeholders with text, or remove them.
+    ");
+
+    // All placeholders filled in
+    std::fs::write(&edit_script, "write\nAdd a feature\n\nTESTED=unit tests\n").unwrap();
+    let (_stdout, stderr) = test_env.jj_cmd_ok(&workspace_path, &["describe"]);
+    insta::assert_snapshot!(stderr, @r"
+    Working copy now at: kkmpptxz c8fd22d7 (empty) Add a feature
+    Parent commit      : qpvuntsm 4dc483f9 TESTED=TODO
+    ");
+
+    // Messages given on the command line aren't checked
+    let (_stdout, stderr) = test_env.jj_cmd_ok(&workspace_path, &["describe", "-m", "{{summary}}"]);
+    insta::assert_snapshot!(stderr, @r"
+    Working copy now at: kkmpptxz a04a7eb0 (empty) {{summary}}
+    Parent commit      : qpvuntsm 4dc483f9 TESTED=TODO
+    ");
+}
+
 #[test]
 fn test_describe_author() {
     let mut test_env = TestEnvironment::default();
@@ -326,7 +326,7 @@ fn test_evolog_with_no_template() {
     let repo_path = test_env.env_root().join("repo");
 
     let stderr = test_env.jj_cmd_cli_error(&repo_path, &["evolog", "-T"]);
-    insta::assert_snapshot!(stderr, @r#"
+    insta::assert_snapshot!(stderr, @r"
     error: a value is required for '--template <TEMPLATE>' but none was supplied
 
     For more information, try '--help'.
@@ -335,16 +335,18 @@ fn test_evolog_with_no_template() {
     - builtin_log_compact
     - builtin_log_compact_full_description
     - builtin_log_detailed
+    - builtin_log_json
     - builtin_log_node
     - builtin_log_node_ascii
     - builtin_log_oneline
     - builtin_op_log_comfortable
     - builtin_op_log_compact
+    - builtin_op_log_json
     - builtin_op_log_node
     - builtin_op_log_node_ascii
     - commit_summary_separator
     - description_placeholder
     - email_placeholder
     - name_placeholder
-    "#);
+    ");
 }
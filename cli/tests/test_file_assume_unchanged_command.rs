@@ -0,0 +1,90 @@
+// Copyright 2026 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::common::TestEnvironment;
+
+#[test]
+fn test_file_assume_unchanged() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    std::fs::create_dir(repo_path.join("gen")).unwrap();
+    std::fs::write(repo_path.join("gen").join("a"), "a\n").unwrap();
+    std::fs::write(repo_path.join("file"), "file\n").unwrap();
+    test_env.jj_cmd_ok(&repo_path, &["new"]);
+
+    let (stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["file", "assume-unchanged", "gen"]);
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @"");
+    let stdout = test_env.jj_cmd_success(&repo_path, &["file", "assume-unchanged"]);
+    insta::assert_snapshot!(stdout, @r###"
+    gen
+    "###);
+
+    // Changes under the path aren't snapshotted, but other changes are
+    std::fs::write(repo_path.join("gen").join("a"), "modified\n").unwrap();
+    std::fs::write(repo_path.join("gen").join("b"), "b\n").unwrap();
+    std::fs::write(repo_path.join("file"), "changed\n").unwrap();
+    let stdout = test_env.jj_cmd_success(&repo_path, &["diff", "--summary"]);
+    insta::assert_snapshot!(stdout, @r###"
+    M file
+    "###);
+
+    // Deleted files aren't noticed either
+    std::fs::remove_file(repo_path.join("gen").join("a")).unwrap();
+    let stdout = test_env.jj_cmd_success(&repo_path, &["diff", "--summary"]);
+    insta::assert_snapshot!(stdout, @r###"
+    M file
+    "###);
+
+    // Refreshing snapshots the changes once
+    let (stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["file", "refresh", "gen"]);
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @"");
+    let stdout = test_env.jj_cmd_success(&repo_path, &["diff", "--summary"]);
+    insta::assert_snapshot!(stdout, @r###"
+    M file
+    D gen/a
+    A gen/b
+    "###);
+    std::fs::write(repo_path.join("gen").join("b"), "modified\n").unwrap();
+    let stdout = test_env.jj_cmd_success(&repo_path, &["diff", "--summary"]);
+    insta::assert_snapshot!(stdout, @r###"
+    M file
+    D gen/a
+    A gen/b
+    "###);
+    let (_stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["file", "refresh", "file"]);
+    insta::assert_snapshot!(stderr, @r###"
+    Nothing changed.
+    "###);
+
+    // After removing the path, it's checked on every snapshot again
+    test_env.jj_cmd_ok(&repo_path, &["file", "assume-unchanged", "--remove", "gen"]);
+    let stdout = test_env.jj_cmd_success(&repo_path, &["file", "assume-unchanged"]);
+    insta::assert_snapshot!(stdout, @"");
+    std::fs::write(repo_path.join("gen").join("c"), "c\n").unwrap();
+    let stdout = test_env.jj_cmd_success(&repo_path, &["diff", "--summary"]);
+    insta::assert_snapshot!(stdout, @r###"
+    M file
+    D gen/a
+    A gen/b
+    A gen/c
+    "###);
+    let stdout = test_env.jj_cmd_success(&repo_path, &["file", "show", "gen/b"]);
+    insta::assert_snapshot!(stdout, @r###"
+    modified
+    "###);
+}
@@ -119,7 +119,7 @@ fn test_chmod_regular_conflict() {
     // Unmatched paths should generate warnings
     let (_stdout, stderr) =
         test_env.jj_cmd_ok(&repo_path, &["file", "chmod", "x", "nonexistent", "file"]);
-    insta::assert_snapshot!(stderr, @r###"
+    insta::assert_snapshot!(stderr, @r"
     Warning: No matching entries for paths: nonexistent
     Working copy now at: yostqsxw 2b11d002 conflict | (conflict) conflict
     Parent commit      : royxmykx 427fbd2f x | x
@@ -127,7 +127,9 @@ fn test_chmod_regular_conflict() {
     Added 0 files, modified 1 files, removed 0 files
     There are unresolved conflicts at these paths:
     file    2-sided conflict including an executable
-    "###);
+      side #1: royxmykx 427fbd2f x | x
+      side #2: (unknown)
+    ");
 }
 
 // TODO: Test demonstrating that conflicts whose *base* is not a file are
@@ -215,13 +217,15 @@ fn test_chmod_file_dir_deletion_conflicts() {
         &["file", "chmod", "x", "file", "-r=file_deletion"],
     );
     insta::assert_snapshot!(stdout, @"");
-    insta::assert_snapshot!(stderr, @r###"
+    insta::assert_snapshot!(stderr, @r"
     Working copy now at: kmkuslsw 139dee15 file_deletion | (conflict) file_deletion
     Parent commit      : zsuskuln c51c9c55 file | file
     Parent commit      : royxmykx 6b18b3c1 deletion | deletion
     Added 0 files, modified 1 files, removed 0 files
     There are unresolved conflicts at these paths:
     file    2-sided conflict including 1 deletion and an executable
+      side #1: (unknown)
+      side #2: royxmykx 6b18b3c1 deletion | deletion
     New conflicts appeared in these commits:
       kmkuslsw 139dee15 file_deletion | (conflict) file_deletion
     To resolve the conflicts, start by updating to it:
@@ -229,7 +233,7 @@ fn test_chmod_file_dir_deletion_conflicts() {
     Then use `jj resolve`, or edit the conflict markers in the file directly.
     Once the conflicts are resolved, you may want to inspect the result with `jj diff`.
     Then run `jj squash` to move the resolution into the conflicted commit.
-    "###);
+    ");
     let stdout = test_env.jj_cmd_success(&repo_path, &["debug", "tree", "-r=file_deletion"]);
     insta::assert_snapshot!(stdout,
     @r###"
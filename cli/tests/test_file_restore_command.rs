@@ -0,0 +1,111 @@
+// Copyright 2026 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::common::TestEnvironment;
+
+#[test]
+fn test_file_restore_to_disk() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+    let out_path = test_env.env_root().join("out");
+
+    std::fs::write(repo_path.join("file1"), "a\n").unwrap();
+    std::fs::create_dir(repo_path.join("dir")).unwrap();
+    std::fs::write(repo_path.join("dir").join("file2"), "b\n").unwrap();
+    test_env.jj_cmd_ok(&repo_path, &["new"]);
+    std::fs::write(repo_path.join("file1"), "c\n").unwrap();
+
+    // Writes the files of the revision to the same paths under the directory
+    let (stdout, stderr) = test_env.jj_cmd_ok(
+        &repo_path,
+        &[
+            "file",
+            "restore",
+            "--from=@-",
+            "--to-disk=../out",
+            "file1",
+            "dir",
+        ],
+    );
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr.replace('\\', "/"), @r###"
+    Wrote 2 files to ../out
+    "###);
+    assert_eq!(
+        std::fs::read_to_string(out_path.join("file1")).unwrap(),
+        "a\n"
+    );
+    assert_eq!(
+        std::fs::read_to_string(out_path.join("dir").join("file2")).unwrap(),
+        "b\n"
+    );
+    // The working copy is untouched
+    assert_eq!(
+        std::fs::read_to_string(repo_path.join("file1")).unwrap(),
+        "c\n"
+    );
+    let stdout = test_env.jj_cmd_success(&repo_path, &["diff", "--summary"]);
+    insta::assert_snapshot!(stdout, @r###"
+    M file1
+    "###);
+
+    // Existing files are replaced, and the working-copy revision is the default
+    let (_stdout, stderr) = test_env.jj_cmd_ok(
+        &repo_path,
+        &[
+            "file",
+            "restore",
+            "--to-disk=../out",
+            "file1",
+            "nonexistent",
+        ],
+    );
+    insta::assert_snapshot!(stderr, @r###"
+    Warning: No matching entries for paths: nonexistent
+    Wrote 1 files to ../out
+    "###);
+    assert_eq!(
+        std::fs::read_to_string(out_path.join("file1")).unwrap(),
+        "c\n"
+    );
+}
+
+#[test]
+fn test_file_restore_to_disk_outside_sparse_patterns() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    std::fs::write(repo_path.join("file1"), "a\n").unwrap();
+    std::fs::write(repo_path.join("file2"), "b\n").unwrap();
+    test_env.jj_cmd_ok(&repo_path, &["sparse", "set", "--clear", "--add=file1"]);
+    assert!(!repo_path.join("file2").exists());
+
+    // Files outside the sparse patterns can be written to another directory
+    test_env.jj_cmd_ok(
+        &repo_path,
+        &["file", "restore", "--to-disk=../out", "file2"],
+    );
+    let out_path = test_env.env_root().join("out");
+    assert_eq!(
+        std::fs::read_to_string(out_path.join("file2")).unwrap(),
+        "b\n"
+    );
+    assert!(!repo_path.join("file2").exists());
+    let stdout = test_env.jj_cmd_success(&repo_path, &["sparse", "list"]);
+    insta::assert_snapshot!(stdout, @r###"
+    file1
+    "###);
+}
@@ -220,3 +220,39 @@ fn test_track_ignored() {
     file1
     "###);
 }
+
+#[test]
+fn test_track_large_file_force() {
+    let test_env = TestEnvironment::default();
+    test_env.add_config(r#"snapshot.auto-track = 'none()'"#);
+    test_env.add_config(r#"snapshot.max-new-file-size = 10"#);
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    std::fs::write(repo_path.join("large"), "a lot of text").unwrap();
+
+    // The size limit applies to manually tracked paths too
+    let (stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["file", "track", "large"]);
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r"
+    Warning: Refused to snapshot some files:
+      large: 13.0B (13 bytes); the maximum size allowed is 10.0B (10 bytes)
+    Hint: This is to prevent large files from being added by accident. You can fix this by:
+      - Adding the file to `.gitignore`
+      - Run `jj config set --repo snapshot.max-new-file-size 13`
+        This will increase the maximum file size allowed for new files, in this repository only.
+      - Run `jj --config snapshot.max-new-file-size=13 st`
+        This will increase the maximum file size allowed for new files, for this command only.
+      - Run `jj file track --force large`
+        This will track the files regardless of their size.
+    ");
+    let stdout = test_env.jj_cmd_success(&repo_path, &["file", "list"]);
+    insta::assert_snapshot!(stdout, @"");
+
+    // --force tracks it regardless of the size
+    let (stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["file", "track", "--force", "large"]);
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @"");
+    let stdout = test_env.jj_cmd_success(&repo_path, &["file", "list"]);
+    insta::assert_snapshot!(stdout, @"large");
+}
@@ -828,6 +828,7 @@ fn test_failure() {
                 command = [<redacted formatter path>, "--fail"]
                 patterns = ["all()"]
                 
+    Warning: Fix tool `legacy-tool-command` failed on file
     Fixed 0 commits of 1 checked.
     Nothing changed.
     "#);
@@ -876,7 +877,8 @@ fn test_stderr_failure() {
                 command = [<redacted formatter path>, "--stderr", "error", "--stdout", "new content", "--fail"]
                 patterns = ["all()"]
                 
-    errorFixed 0 commits of 1 checked.
+    errorWarning: Fix tool `legacy-tool-command` failed on file
+    Fixed 0 commits of 1 checked.
     Nothing changed.
     "#);
     let content = test_env.jj_cmd_success(&repo_path, &["file", "show", "file", "-r", "@"]);
@@ -891,9 +893,8 @@ fn test_missing_command() {
     test_env.add_config(r#"fix.tool-command = ["this_executable_shouldnt_exist"]"#);
     let (stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["fix", "-s", "@"]);
     insta::assert_snapshot!(stdout, @"");
-    // TODO: We should display a warning about invalid tool configurations. When we
-    // support multiple tools, we should also keep going to see if any of the other
-    // executions succeed.
+    // TODO: We should display a warning about invalid tool configurations even
+    // if there are no files to fix.
     insta::assert_snapshot!(stderr, @r###"
     Warning: The `fix.tool-command` config option is deprecated and will be removed in a future version.
     Hint: Replace it with the following:
@@ -1066,6 +1067,8 @@ fn test_fix_both_sides_of_conflict() {
     Added 0 files, modified 1 files, removed 0 files
     There are unresolved conflicts at these paths:
     file    2-sided conflict
+      side #1: qpvuntsm 8e8aad69 a | (no description set)
+      side #2: kkmpptxz 91f9b284 b | (no description set)
     "#);
     let content = test_env.jj_cmd_success(&repo_path, &["file", "show", "file", "-r", "a"]);
     insta::assert_snapshot!(content, @r###"
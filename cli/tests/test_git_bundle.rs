@@ -0,0 +1,173 @@
+// Copyright 2024 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::path::Path;
+
+use crate::common::TestEnvironment;
+
+fn get_log_output(test_env: &TestEnvironment, repo_path: &Path) -> String {
+    let template = r#"separate(" ", commit_id.short(), bookmarks, description.first_line())"#;
+    test_env.jj_cmd_success(repo_path, &["log", "-r::", "-T", template])
+}
+
+#[test]
+fn test_git_bundle() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "source"]);
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "target"]);
+    let source_path = test_env.env_root().join("source");
+    let target_path = test_env.env_root().join("target");
+
+    std::fs::write(source_path.join("file"), "a\n").unwrap();
+    test_env.jj_cmd_ok(&source_path, &["commit", "-m=first"]);
+    std::fs::write(source_path.join("file"), "b\n").unwrap();
+    test_env.jj_cmd_ok(&source_path, &["commit", "-m=second"]);
+    test_env.jj_cmd_ok(&source_path, &["bookmark", "create", "-r@-", "main"]);
+    test_env.jj_cmd_ok(&source_path, &["new", "@--", "-m=side"]);
+
+    let (stdout, stderr) = test_env.jj_cmd_ok(
+        &source_path,
+        &["git", "bundle", "create", "../all.bundle", "-rmain", "-r@"],
+    );
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @"Bundled 3 commits and 1 bookmarks to ../all.bundle");
+
+    let (stdout, stderr) = test_env.jj_cmd_ok(
+        &target_path,
+        &["git", "bundle", "unbundle", "../all.bundle"],
+    );
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @"Created bookmark main");
+    insta::assert_snapshot!(get_log_output(&test_env, &target_path), @r"
+    @  6acbecf3f4d4
+    │ ○  e762e1985348 side
+    │ │ ○  7c2edabd7437 main second
+    │ ├─╯
+    │ ○  db357406dbfa first
+    ├─╯
+    ◆  000000000000
+    ");
+
+    // Bundle only the new commits
+    test_env.jj_cmd_ok(&source_path, &["new", "main", "-m=third"]);
+    test_env.jj_cmd_ok(&source_path, &["bookmark", "set", "main"]);
+    let (stdout, stderr) = test_env.jj_cmd_ok(
+        &source_path,
+        &[
+            "git",
+            "bundle",
+            "create",
+            "../incremental.bundle",
+            "-rmain",
+            "--exclude=main-",
+        ],
+    );
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @"Bundled 1 commits and 1 bookmarks to ../incremental.bundle");
+
+    let (stdout, stderr) = test_env.jj_cmd_ok(
+        &target_path,
+        &["git", "bundle", "unbundle", "../incremental.bundle"],
+    );
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @"Moved bookmark main");
+    insta::assert_snapshot!(get_log_output(&test_env, &target_path), @r"
+    @  6acbecf3f4d4
+    │ ○  5fa9d4dd8f95 main third
+    │ ○  7c2edabd7437 second
+    │ │ ○  e762e1985348 side
+    │ ├─╯
+    │ ○  db357406dbfa first
+    ├─╯
+    ◆  000000000000
+    ");
+}
+
+#[test]
+fn test_git_bundle_unbundle_bookmark_moved() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "source"]);
+    let source_path = test_env.env_root().join("source");
+
+    test_env.jj_cmd_ok(&source_path, &["commit", "-m=first"]);
+    test_env.jj_cmd_ok(&source_path, &["bookmark", "create", "-r@-", "main"]);
+    test_env.jj_cmd_ok(
+        &source_path,
+        &["git", "bundle", "create", "../first.bundle", "-rmain"],
+    );
+    test_env.jj_cmd_ok(&source_path, &["describe", "main", "-m=rewritten"]);
+    test_env.jj_cmd_ok(
+        &source_path,
+        &["git", "bundle", "create", "../rewritten.bundle", "-rmain"],
+    );
+
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "target"]);
+    let target_path = test_env.env_root().join("target");
+    test_env.jj_cmd_ok(
+        &target_path,
+        &["git", "bundle", "unbundle", "../first.bundle"],
+    );
+    // The bookmark isn't moved sideways
+    let (stdout, stderr) = test_env.jj_cmd_ok(
+        &target_path,
+        &["git", "bundle", "unbundle", "../rewritten.bundle"],
+    );
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @"Warning: Not moving bookmark main since the bundled commit isn't a descendant of it");
+    insta::assert_snapshot!(get_log_output(&test_env, &target_path), @r"
+    @  5b36783cd11c
+    │ ○  309bbc17ec77 rewritten
+    ├─╯
+    │ ○  fa15625b4a98 main first
+    ├─╯
+    ◆  000000000000
+    ");
+}
+
+#[test]
+fn test_git_bundle_errors() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "source"]);
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "target"]);
+    let source_path = test_env.env_root().join("source");
+    let target_path = test_env.env_root().join("target");
+
+    let stderr = test_env.jj_cmd_failure(
+        &source_path,
+        &["git", "bundle", "create", "../empty.bundle", "-rroot()"],
+    );
+    insta::assert_snapshot!(stderr, @"Error: No revisions to bundle");
+
+    // The prerequisites are missing in the target repo
+    test_env.jj_cmd_ok(&source_path, &["commit", "-m=first"]);
+    test_env.jj_cmd_ok(
+        &source_path,
+        &[
+            "git",
+            "bundle",
+            "create",
+            "../thin.bundle",
+            "-r@",
+            "--exclude=@-",
+        ],
+    );
+    let stderr = test_env.jj_cmd_failure(
+        &target_path,
+        &["git", "bundle", "unbundle", "../thin.bundle"],
+    );
+    insta::assert_snapshot!(stderr, @r"
+    Error: Failed to run git bundle: error: Repository lacks these prerequisite commits:
+    error: 8a518d859b51bb62c1f642b701ecd0d5619ed07a
+    ");
+}
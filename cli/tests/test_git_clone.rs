@@ -588,6 +588,61 @@ fn test_git_clone_with_depth() {
     "#);
 }
 
+#[test]
+fn test_git_clone_with_filter() {
+    let test_env = TestEnvironment::default();
+    let git_repo_path = test_env.env_root().join("source");
+    let git_repo = git2::Repository::init(&git_repo_path).unwrap();
+    git_repo
+        .config()
+        .unwrap()
+        .set_bool("uploadpack.allowFilter", true)
+        .unwrap();
+    set_up_non_empty_git_repo(&git_repo);
+
+    // The local transport ignores filters, so use a file:// URL
+    let source_url = format!("file://{}", git_repo_path.display());
+    let (_stdout, stderr) = test_env.jj_cmd_ok(
+        test_env.env_root(),
+        &["git", "clone", "--filter=blob:none", &source_url, "clone"],
+    );
+    insta::assert_snapshot!(stderr, @r#"
+    Fetching into new repo in "$TEST_ENV/clone"
+    bookmark: main@origin [new] untracked
+    Setting the revset alias "trunk()" to "main@origin"
+    Working copy now at: sqpuoqvx cad212e1 (empty) (no description set)
+    Parent commit      : mzyxwzks 9f01a0e0 main | message
+    Added 1 files, modified 0 files, removed 0 files
+    "#);
+
+    // The remote is the promisor remote, and the missing file was fetched on
+    // checkout
+    let clone_git_repo =
+        git2::Repository::open(test_env.env_root().join("clone/.jj/repo/store/git")).unwrap();
+    let clone_config = clone_git_repo.config().unwrap();
+    assert!(clone_config.get_bool("remote.origin.promisor").unwrap());
+    assert_eq!(
+        clone_config
+            .get_string("remote.origin.partialclonefilter")
+            .unwrap(),
+        "blob:none"
+    );
+    assert_eq!(
+        std::fs::read_to_string(test_env.env_root().join("clone/file")).unwrap(),
+        "content"
+    );
+
+    // Invalid filters are rejected by git
+    let stderr = test_env.jj_cmd_failure(
+        test_env.env_root(),
+        &["git", "clone", "--filter=bogus", &source_url, "bad"],
+    );
+    insta::assert_snapshot!(stderr, @r#"
+    Fetching into new repo in "$TEST_ENV/bad"
+    Error: Failed to run git fetch: fatal: invalid filter-spec 'bogus'
+    "#);
+}
+
 #[test]
 fn test_git_clone_invalid_immutable_heads() {
     let test_env = TestEnvironment::default();
@@ -264,6 +264,54 @@ fn test_git_colocated_export_bookmarks_on_snapshot() {
         .to_string(), @"4d2c49a8f8e2f1ba61f48ba79e5f4a5faa6512cf");
 }
 
+#[test]
+fn test_git_colocated_bookmark_patterns() {
+    let test_env = TestEnvironment::default();
+    let workspace_root = test_env.env_root().join("repo");
+    let git_repo = git2::Repository::init(&workspace_root).unwrap();
+    test_env.jj_cmd_ok(&workspace_root, &["git", "init", "--git-repo", "."]);
+    test_env.add_config(
+        r#"
+        git.import-bookmarks = ["main", "glob:team/*"]
+        git.export-bookmarks = ["main", "glob:team/*"]
+        "#,
+    );
+
+    // Bookmarks not matching the export patterns stay local to jj
+    test_env.jj_cmd_ok(&workspace_root, &["describe", "-m", "first"]);
+    test_env.jj_cmd_ok(
+        &workspace_root,
+        &["bookmark", "create", "main", "team/feature", "scratch"],
+    );
+    test_env.jj_cmd_ok(&workspace_root, &["new"]);
+    let git_branches = |git_repo: &git2::Repository| {
+        git_repo
+            .branches(Some(git2::BranchType::Local))
+            .unwrap()
+            .map(|branch| branch.unwrap().0.name().unwrap().unwrap().to_owned())
+            .collect::<Vec<_>>()
+            .join("\n")
+    };
+    insta::assert_snapshot!(git_branches(&git_repo), @r"
+    main
+    team/feature
+    ");
+
+    // Git branches not matching the import patterns aren't imported
+    let head = git_repo.head().unwrap().peel_to_commit().unwrap();
+    git_repo.branch("team/other", &head, false).unwrap();
+    git_repo.branch("unrelated", &head, false).unwrap();
+    insta::assert_snapshot!(get_bookmark_output(&test_env, &workspace_root), @r"
+    main: qpvuntsm fa15625b (empty) first
+      @git: qpvuntsm fa15625b (empty) first
+    scratch: qpvuntsm fa15625b (empty) first
+    team/feature: qpvuntsm fa15625b (empty) first
+      @git: qpvuntsm fa15625b (empty) first
+    team/other: qpvuntsm fa15625b (empty) first
+      @git: qpvuntsm fa15625b (empty) first
+    ");
+}
+
 #[test]
 fn test_git_colocated_rebase_on_import() {
     let test_env = TestEnvironment::default();
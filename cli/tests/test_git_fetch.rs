@@ -100,6 +100,52 @@ fn test_git_fetch_default_remote() {
     "###);
 }
 
+#[test]
+fn test_git_fetch_auto_track_bookmarks() {
+    let test_env = TestEnvironment::default();
+    // The pattern takes precedence over git.auto-local-bookmark
+    test_env.add_config(
+        r#"
+        git.auto-local-bookmark = true
+        git.auto-track-bookmarks = ["a1", "glob:a2*"]
+        "#,
+    );
+    let source_git_repo_path = test_env.env_root().join("source");
+    let _git_repo = git2::Repository::init(source_git_repo_path.clone()).unwrap();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "clone", "source", "target"]);
+    let target_jj_repo_path = test_env.env_root().join("target");
+    create_colocated_repo_and_bookmarks_from_trunk1(&test_env, &source_git_repo_path);
+
+    let (stdout, stderr) = test_env.jj_cmd_ok(&target_jj_repo_path, &["git", "fetch"]);
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r"
+    bookmark: a1@origin     [new] tracked
+    bookmark: a2@origin     [new] tracked
+    bookmark: b@origin      [new] untracked
+    bookmark: trunk1@origin [new] untracked
+    ");
+    insta::assert_snapshot!(get_bookmark_output(&test_env, &target_jj_repo_path), @r"
+    a1: nknoxmzm 359a9a02 descr_for_a1
+      @origin: nknoxmzm 359a9a02 descr_for_a1
+    a2: qkvnknrk decaa396 descr_for_a2
+      @origin: qkvnknrk decaa396 descr_for_a2
+    b@origin: vpupmnsl c7d4bdcb descr_for_b
+    trunk1@origin: zowqyktl ff36dc55 descr_for_trunk1
+    ");
+
+    // Untracked bookmarks can still be tracked explicitly
+    test_env.jj_cmd_ok(&target_jj_repo_path, &["bookmark", "track", "b@origin"]);
+    insta::assert_snapshot!(get_bookmark_output(&test_env, &target_jj_repo_path), @r"
+    a1: nknoxmzm 359a9a02 descr_for_a1
+      @origin: nknoxmzm 359a9a02 descr_for_a1
+    a2: qkvnknrk decaa396 descr_for_a2
+      @origin: qkvnknrk decaa396 descr_for_a2
+    b: vpupmnsl c7d4bdcb descr_for_b
+      @origin: vpupmnsl c7d4bdcb descr_for_b
+    trunk1@origin: zowqyktl ff36dc55 descr_for_trunk1
+    ");
+}
+
 #[test]
 fn test_git_fetch_single_remote() {
     let test_env = TestEnvironment::default();
@@ -226,6 +272,42 @@ fn test_git_fetch_multiple_remotes_from_config() {
     "###);
 }
 
+#[test]
+fn test_git_fetch_bookmarks_from_remote_config() {
+    let test_env = TestEnvironment::default();
+    test_env.add_config("git.auto-local-bookmark = true");
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+    add_git_remote(&test_env, &repo_path, "rem1");
+    add_git_remote(&test_env, &repo_path, "rem2");
+    test_env.add_config(
+        r#"
+        git.fetch = ["rem1", "rem2"]
+        remotes.rem1.fetch-bookmarks = ["other"]
+        "#,
+    );
+
+    // Only rem1 is restricted by its config
+    let (_stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["git", "fetch"]);
+    insta::assert_snapshot!(stderr, @r###"
+    Warning: No branch matching `other` found on any specified/configured remote
+    bookmark: rem2@rem2 [new] tracked
+    "###);
+    insta::assert_snapshot!(get_bookmark_output(&test_env, &repo_path), @r###"
+    rem2: yszkquru 2497a8a0 message
+      @rem2: yszkquru 2497a8a0 message
+    "###);
+
+    // --branch overrides the config
+    test_env.jj_cmd_ok(&repo_path, &["git", "fetch", "--branch", "rem1"]);
+    insta::assert_snapshot!(get_bookmark_output(&test_env, &repo_path), @r###"
+    rem1: qxosxrvv 6a211027 message
+      @rem1: qxosxrvv 6a211027 message
+    rem2: yszkquru 2497a8a0 message
+      @rem2: yszkquru 2497a8a0 message
+    "###);
+}
+
 #[test]
 fn test_git_fetch_nonexistent_remote() {
     let test_env = TestEnvironment::default();
@@ -336,6 +418,53 @@ fn test_git_fetch_prune_before_updating_tips() {
     "###);
 }
 
+#[test]
+fn test_git_fetch_prune_mode() {
+    let test_env = TestEnvironment::default();
+    test_env.add_config("git.auto-local-bookmark = true");
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+    add_git_remote(&test_env, &repo_path, "origin");
+    test_env.jj_cmd_ok(&repo_path, &["git", "fetch"]);
+    insta::assert_snapshot!(get_bookmark_output(&test_env, &repo_path), @r"
+    origin: oputwtnw ffecd2d6 message
+      @origin: oputwtnw ffecd2d6 message
+    ");
+
+    // Delete the bookmark in the remote
+    let git_repo = git2::Repository::open(test_env.env_root().join("origin")).unwrap();
+    git_repo
+        .find_reference("refs/heads/origin")
+        .unwrap()
+        .delete()
+        .unwrap();
+
+    // Keep the local bookmark
+    let (_stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["git", "fetch", "--prune=keep"]);
+    insta::assert_snapshot!(stderr, @"bookmark: origin@origin [deleted] untracked");
+    insta::assert_snapshot!(get_bookmark_output(&test_env, &repo_path), @"origin: oputwtnw ffecd2d6 message");
+    test_env.jj_cmd_ok(&repo_path, &["op", "undo"]);
+
+    // Keep the local bookmark, but mark it as deleted
+    test_env.add_config(r#"git.fetch-prune = "mark-deleted""#);
+    let (_stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["git", "fetch"]);
+    insta::assert_snapshot!(stderr, @"bookmark: origin@origin [deleted] untracked");
+    insta::assert_snapshot!(get_bookmark_output(&test_env, &repo_path), @r"
+    origin (conflicted):
+      - oputwtnw ffecd2d6 message
+      + oputwtnw ffecd2d6 message
+    ");
+    test_env.jj_cmd_ok(&repo_path, &["op", "undo"]);
+
+    // Delete the local bookmark, and abandon the unreachable commit
+    let (_stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["git", "fetch", "--prune=delete"]);
+    insta::assert_snapshot!(stderr, @r"
+    bookmark: origin@origin [deleted] untracked
+    Abandoned 1 commits that are no longer reachable.
+    ");
+    insta::assert_snapshot!(get_bookmark_output(&test_env, &repo_path), @"");
+}
+
 #[test]
 fn test_git_fetch_conflicting_bookmarks() {
     let test_env = TestEnvironment::default();
@@ -538,13 +667,15 @@ fn test_git_fetch_all() {
     "###);
     let (stdout, stderr) = test_env.jj_cmd_ok(&target_jj_repo_path, &["git", "fetch"]);
     insta::assert_snapshot!(stdout, @"");
-    insta::assert_snapshot!(stderr, @r###"
+    insta::assert_snapshot!(stderr, @r"
     bookmark: a1@origin     [updated] tracked
     bookmark: a2@origin     [updated] tracked
     bookmark: b@origin      [updated] tracked
     bookmark: trunk2@origin [new] tracked
     Abandoned 2 commits that are no longer reachable.
-    "###);
+    Warning: Remote bookmark b@origin was rewritten, and local bookmark b now has conflicts.
+    Hint: Use `jj bookmark rebase-onto-remote b --remote origin` to rebase the local commits onto the new remote target.
+    ");
     insta::assert_snapshot!(get_bookmark_output(&test_env, &target_jj_repo_path), @r###"
     a1: quxllqov 0424f6df descr_for_a1
       @origin: quxllqov 0424f6df descr_for_a1
@@ -720,11 +851,13 @@ fn test_git_fetch_some_of_many_bookmarks() {
         &["git", "fetch", "--branch", "b", "--branch", "a1"],
     );
     insta::assert_snapshot!(stdout, @"");
-    insta::assert_snapshot!(stderr, @r###"
+    insta::assert_snapshot!(stderr, @r"
     bookmark: a1@origin [updated] tracked
     bookmark: b@origin  [updated] tracked
     Abandoned 1 commits that are no longer reachable.
-    "###);
+    Warning: Remote bookmark b@origin was rewritten, and local bookmark b now has conflicts.
+    Hint: Use `jj bookmark rebase-onto-remote b --remote origin` to rebase the local commits onto the new remote target.
+    ");
     insta::assert_snapshot!(get_log_output(&test_env, &target_jj_repo_path), @r#"
     @  230dd059e1b0
     │ ○  01d115196c39 descr_for_b b?? b@origin
@@ -790,6 +923,95 @@ fn test_git_fetch_some_of_many_bookmarks() {
     "###);
 }
 
+#[test]
+fn test_git_fetch_excluded_bookmarks() {
+    let test_env = TestEnvironment::default();
+    test_env.add_config("git.auto-local-bookmark = true");
+    test_env.add_config(r#"revset-aliases."immutable_heads()" = "none()""#);
+    let source_git_repo_path = test_env.env_root().join("source");
+    let _git_repo = git2::Repository::init(source_git_repo_path.clone()).unwrap();
+
+    // Clone an empty repo. The target repo is a normal `jj` repo, *not* colocated
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "clone", "source", "target"]);
+    let target_jj_repo_path = test_env.env_root().join("target");
+    create_colocated_repo_and_bookmarks_from_trunk1(&test_env, &source_git_repo_path);
+
+    // Only the excluded patterns are specified
+    let (stdout, stderr) =
+        test_env.jj_cmd_ok(&target_jj_repo_path, &["git", "fetch", "--branch", "!b"]);
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r"
+    bookmark: a1@origin     [new] tracked
+    bookmark: a2@origin     [new] tracked
+    bookmark: trunk1@origin [new] tracked
+    ");
+    test_env.jj_cmd_ok(&target_jj_repo_path, &["op", "undo"]);
+
+    // Both included and excluded patterns are specified
+    let (stdout, stderr) = test_env.jj_cmd_ok(
+        &target_jj_repo_path,
+        &[
+            "git",
+            "fetch",
+            "--branch=glob:a*",
+            "--branch=b",
+            "--branch=!a2",
+        ],
+    );
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r"
+    bookmark: a1@origin [new] tracked
+    bookmark: b@origin  [new] tracked
+    ");
+    insta::assert_snapshot!(get_bookmark_output(&test_env, &target_jj_repo_path), @r"
+    a1: nknoxmzm 359a9a02 descr_for_a1
+      @origin: nknoxmzm 359a9a02 descr_for_a1
+    b: vpupmnsl c7d4bdcb descr_for_b
+      @origin: vpupmnsl c7d4bdcb descr_for_b
+    ");
+
+    // The excluded bookmark is left alone even if it was fetched before
+    test_env.jj_cmd_ok(&target_jj_repo_path, &["git", "fetch", "--branch=a2"]);
+    test_env.jj_cmd_ok(
+        &source_git_repo_path,
+        &["bookmark", "set", "--allow-backwards", "-r=b", "a2"],
+    );
+    let (stdout, stderr) =
+        test_env.jj_cmd_ok(&target_jj_repo_path, &["git", "fetch", "--branch=!a2"]);
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @"bookmark: trunk1@origin [new] tracked");
+    // The remote-tracking ref in the backing Git repo isn't updated either
+    let (stdout, stderr) = test_env.jj_cmd_ok(&target_jj_repo_path, &["git", "import"]);
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @"Nothing changed.");
+    insta::assert_snapshot!(get_bookmark_output(&test_env, &target_jj_repo_path), @r"
+    a1: nknoxmzm 359a9a02 descr_for_a1
+      @origin: nknoxmzm 359a9a02 descr_for_a1
+    a2: qkvnknrk decaa396 descr_for_a2
+      @origin: qkvnknrk decaa396 descr_for_a2
+    b: vpupmnsl c7d4bdcb descr_for_b
+      @origin: vpupmnsl c7d4bdcb descr_for_b
+    trunk1: zowqyktl ff36dc55 descr_for_trunk1
+      @origin: zowqyktl ff36dc55 descr_for_trunk1
+    ");
+
+    // Bookmarks deleted on the remote are still pruned
+    test_env.jj_cmd_ok(&source_git_repo_path, &["bookmark", "delete", "b"]);
+    let (stdout, stderr) =
+        test_env.jj_cmd_ok(&target_jj_repo_path, &["git", "fetch", "--branch=!a2"]);
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r"
+    bookmark: b@origin [deleted] untracked
+    Abandoned 1 commits that are no longer reachable.
+    ");
+
+    let stderr = test_env.jj_cmd_failure(
+        &target_jj_repo_path,
+        &["git", "fetch", "--branch", "!glob:^a*"],
+    );
+    insta::assert_snapshot!(stderr, @"Error: Invalid branch pattern provided. When fetching, branch names and globs may not contain the characters `:`, `^`, `?`, `[`, `]`");
+}
+
 #[test]
 fn test_git_fetch_bookmarks_some_missing() {
     let test_env = TestEnvironment::default();
@@ -1310,6 +1532,81 @@ fn test_git_fetch_removed_parent_bookmark() {
     "#);
 }
 
+#[test]
+fn test_git_fetch_rewritten_remote_bookmark() {
+    let test_env = TestEnvironment::default();
+    test_env.add_config("git.auto-local-bookmark = true");
+    let source_git_repo_path = test_env.env_root().join("source");
+    let _git_repo = git2::Repository::init(source_git_repo_path.clone()).unwrap();
+    test_env.jj_cmd_ok(&source_git_repo_path, &["git", "init", "--git-repo=."]);
+    create_commit(&test_env, &source_git_repo_path, "trunk", &[]);
+    create_commit(&test_env, &source_git_repo_path, "feature", &["trunk"]);
+
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "clone", "source", "target"]);
+    let target_jj_repo_path = test_env.env_root().join("target");
+    // Add a local commit on top of the remote bookmark
+    test_env.jj_cmd_ok(&target_jj_repo_path, &["new", "feature", "-m", "local"]);
+    std::fs::write(target_jj_repo_path.join("local"), "local\n").unwrap();
+    test_env.jj_cmd_ok(&target_jj_repo_path, &["bookmark", "set", "feature", "-r@"]);
+
+    // Rewrite the bookmark in the source repo
+    test_env.jj_cmd_ok(
+        &source_git_repo_path,
+        &["describe", "feature", "-m", "descr_for_feature rewritten"],
+    );
+
+    let (stdout, stderr) = test_env.jj_cmd_ok(&target_jj_repo_path, &["git", "fetch"]);
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r"
+    bookmark: feature@origin [updated] tracked
+    Warning: Remote bookmark feature@origin was rewritten, and local bookmark feature now has conflicts.
+    Hint: Use `jj bookmark rebase-onto-remote feature --remote origin` to rebase the local commits onto the new remote target.
+    ");
+    insta::assert_snapshot!(get_log_output(&test_env, &target_jj_repo_path), @r"
+    @  74963bc1a347 local feature??
+    ○  9e5cdfc24909 descr_for_feature
+    │ ○  b57de11ab9c8 descr_for_feature rewritten feature?? feature@origin
+    ├─╯
+    ◆  f08ad68caf36 descr_for_trunk trunk
+    ◆  000000000000
+    ");
+
+    let (stdout, stderr) = test_env.jj_cmd_ok(
+        &target_jj_repo_path,
+        &["bookmark", "rebase-onto-remote", "feature"],
+    );
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r"
+    Rebased 1 commits onto feature@origin
+    Abandoned 1 commits that are no longer reachable.
+    Moved bookmark feature to yqosqzyt 8b5775f3 feature* | local
+    Working copy now at: yqosqzyt 8b5775f3 feature* | local
+    Parent commit      : umyonxzm b57de11a feature@origin | descr_for_feature rewritten
+    ");
+    insta::assert_snapshot!(get_log_output(&test_env, &target_jj_repo_path), @r"
+    @  8b5775f36156 local feature*
+    ○  b57de11ab9c8 descr_for_feature rewritten feature@origin
+    ◆  f08ad68caf36 descr_for_trunk trunk
+    ◆  000000000000
+    ");
+    insta::assert_snapshot!(get_bookmark_output(&test_env, &target_jj_repo_path), @r"
+    feature: yqosqzyt 8b5775f3 local
+      @origin (behind by 1 commits): umyonxzm b57de11a descr_for_feature rewritten
+    trunk: utwvqsvn f08ad68c descr_for_trunk
+      @origin: utwvqsvn f08ad68c descr_for_trunk
+    ");
+
+    // The bookmark is no longer conflicted
+    let stderr = test_env.jj_cmd_failure(
+        &target_jj_repo_path,
+        &["bookmark", "rebase-onto-remote", "feature"],
+    );
+    insta::assert_snapshot!(stderr, @r"
+    Error: Bookmark feature doesn't have local commits on top of a rewritten feature@origin
+    Hint: Use `jj rebase` and `jj bookmark set` to update the bookmark manually.
+    ");
+}
+
 #[test]
 fn test_git_fetch_remote_only_bookmark() {
     let test_env = TestEnvironment::default();
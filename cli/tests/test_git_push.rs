@@ -15,6 +15,8 @@
 use std::path::Path;
 use std::path::PathBuf;
 
+use indoc::indoc;
+
 use crate::common::TestEnvironment;
 
 fn set_up() -> (TestEnvironment, PathBuf) {
@@ -143,6 +145,68 @@ fn test_git_push_current_bookmark() {
     "#);
 }
 
+#[test]
+fn test_git_push_create_pr_url() {
+    let (test_env, workspace_root) = set_up();
+    test_env.add_config(r#"remotes.origin.create-pr-url = "https://example.com/new/{bookmark}""#);
+    test_env.jj_cmd_ok(&workspace_root, &["new", "bookmark2"]);
+    test_env.jj_cmd_ok(&workspace_root, &["bookmark", "set", "bookmark2"]);
+    test_env.jj_cmd_ok(&workspace_root, &["bookmark", "create", "my-bookmark"]);
+    test_env.jj_cmd_ok(&workspace_root, &["describe", "-m", "foo"]);
+
+    // The URL is printed only for the bookmark that is new on the remote
+    let (stdout, stderr) =
+        test_env.jj_cmd_ok(&workspace_root, &["git", "push", "--allow-new", "--json"]);
+    insta::assert_snapshot!(stderr, @r#"
+    Changes to push to origin:
+      Move forward bookmark bookmark2 from 8476341eb395 to 133c95f1f797
+      Add bookmark my-bookmark to 133c95f1f797
+    Create a pull request for bookmark my-bookmark:
+      https://example.com/new/my-bookmark
+    "#);
+    let result: serde_json::Value = serde_json::from_str(&stdout).unwrap();
+    assert_eq!(result["remote"], "origin");
+    let bookmarks = result["bookmarks"].as_array().unwrap();
+    assert_eq!(bookmarks.len(), 2);
+    assert_eq!(bookmarks[0]["name"], "bookmark2");
+    assert!(bookmarks[0]["old_commit_id"]
+        .as_str()
+        .unwrap()
+        .starts_with("8476341eb395"));
+    assert_eq!(
+        bookmarks[0]["create_pr_url"],
+        "https://example.com/new/bookmark2"
+    );
+    assert_eq!(bookmarks[1]["name"], "my-bookmark");
+    assert_eq!(bookmarks[1]["old_commit_id"], serde_json::Value::Null);
+    assert!(bookmarks[1]["new_commit_id"]
+        .as_str()
+        .unwrap()
+        .starts_with("133c95f1f797"));
+    assert_eq!(
+        bookmarks[1]["create_pr_url"],
+        "https://example.com/new/my-bookmark"
+    );
+    assert_eq!(result["tags"].as_array().unwrap().len(), 0);
+
+    // An empty template disables the URL
+    test_env.jj_cmd_ok(&workspace_root, &["bookmark", "create", "other", "-r@-"]);
+    let (_stdout, stderr) = test_env.jj_cmd_ok(
+        &workspace_root,
+        &[
+            "git",
+            "push",
+            "--allow-new",
+            "-bother",
+            "--config=remotes.origin.create-pr-url=''",
+        ],
+    );
+    insta::assert_snapshot!(stderr, @r#"
+    Changes to push to origin:
+      Add bookmark other to 8476341eb395
+    "#);
+}
+
 #[test]
 fn test_git_push_parent_bookmark() {
     let (test_env, workspace_root) = set_up();
@@ -718,6 +782,117 @@ fn test_git_push_changes() {
     "#);
 }
 
+#[test]
+fn test_git_push_changes_with_name_template() {
+    let (test_env, workspace_root) = set_up();
+    test_env.jj_cmd_ok(&workspace_root, &["describe", "-m", "foo"]);
+    std::fs::write(workspace_root.join("file"), "contents").unwrap();
+    test_env.jj_cmd_ok(&workspace_root, &["new", "-m", "bar"]);
+    std::fs::write(workspace_root.join("file"), "modified").unwrap();
+
+    test_env.add_config(
+        r#"templates.git_push_bookmark = '"user/" ++ author.email().local() ++ "/" ++ change_id.short(6)'"#,
+    );
+    let (stdout, stderr) = test_env.jj_cmd_ok(&workspace_root, &["git", "push", "--change", "@"]);
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r"
+    Creating bookmark user/test.user/yostqs for revision yostqsxwqrlt
+    Changes to push to origin:
+      Add bookmark user/test.user/yostqs to cf1a53a8800a
+    ");
+
+    // The template takes precedence over the prefix
+    let (stdout, stderr) = test_env.jj_cmd_ok(
+        &workspace_root,
+        &[
+            "git",
+            "push",
+            "--config=git.push-bookmark-prefix=test-",
+            "--change=@-",
+        ],
+    );
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r"
+    Creating bookmark user/test.user/yqosqz for revision yqosqzytrlsw
+    Changes to push to origin:
+      Add bookmark user/test.user/yqosqz to a050abf4ff07
+    ");
+
+    // Names generated for different revisions must be unique
+    let stderr = test_env.jj_cmd_failure(
+        &workspace_root,
+        &[
+            "git",
+            "push",
+            r#"--config=templates.git_push_bookmark='"shared"'"#,
+            "--change=all:(@|@-)",
+        ],
+    );
+    insta::assert_snapshot!(stderr, @"Error: Bookmark name shared was generated for multiple revisions");
+
+    // The name must not be used on the remote by another change
+    test_env.jj_cmd_ok(
+        &workspace_root,
+        &[
+            "git",
+            "push",
+            r#"--config=templates.git_push_bookmark='"shared"'"#,
+            "--change=@-",
+        ],
+    );
+    let stderr = test_env.jj_cmd_failure(
+        &workspace_root,
+        &[
+            "git",
+            "push",
+            r#"--config=templates.git_push_bookmark='"shared"'"#,
+            "--change=@",
+        ],
+    );
+    insta::assert_snapshot!(stderr, @r"
+    Error: Bookmark name shared generated for revision yostqsxwqrlt is already used by revision yqosqzytrlsw
+    Hint: Configure `templates.git_push_bookmark` to generate unique names, or use `--bookmark` to push an existing bookmark.
+    ");
+
+    let stderr = test_env.jj_cmd_failure(
+        &workspace_root,
+        &[
+            "git",
+            "push",
+            "--config=templates.git_push_bookmark=''",
+            "--change=@",
+        ],
+    );
+    insta::assert_snapshot!(stderr, @"Error: Empty bookmark name was generated for revision yostqsxwqrlt");
+
+    // Surrounding whitespace is trimmed, but the name must be a valid ref name
+    let (stdout, stderr) = test_env.jj_cmd_ok(
+        &workspace_root,
+        &[
+            "git",
+            "push",
+            r#"--config=templates.git_push_bookmark='"\ntrimmed\n"'"#,
+            "--change=@",
+        ],
+    );
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r"
+    Creating bookmark trimmed for revision yostqsxwqrlt
+    Changes to push to origin:
+      Add bookmark trimmed to cf1a53a8800a
+    ");
+    let stderr = test_env.jj_cmd_failure(
+        &workspace_root,
+        &[
+            "git",
+            "push",
+            r#"--config=templates.git_push_bookmark='"has space"'"#,
+            "--change=@",
+        ],
+    );
+    insta::assert_snapshot!(stderr, @r#"Error: Invalid bookmark name "has space" was generated for revision yostqsxwqrlt"#);
+}
+
 #[test]
 fn test_git_push_revisions() {
     let (test_env, workspace_root) = set_up();
@@ -896,6 +1071,38 @@ fn test_git_push_conflict() {
     Error: Won't push commit e2221a796300 since it has conflicts
     Hint: Rejected commit: yostqsxw e2221a79 my-bookmark | (conflict) third
     "###);
+
+    // The conflict is preserved when another jj client fetches the commit
+    let (_stdout, stderr) = test_env.jj_cmd_ok(
+        &workspace_root,
+        &["git", "push", "--all", "--allow-conflicts"],
+    );
+    insta::assert_snapshot!(stderr, @r"
+    Changes to push to origin:
+      Add bookmark my-bookmark to e2221a796300
+    ");
+    let origin_git_repo_path = test_env
+        .env_root()
+        .join("origin")
+        .join(".jj")
+        .join("repo")
+        .join("store")
+        .join("git");
+    test_env.jj_cmd_ok(
+        test_env.env_root(),
+        &[
+            "git",
+            "clone",
+            origin_git_repo_path.to_str().unwrap(),
+            "other",
+        ],
+    );
+    let other_path = test_env.env_root().join("other");
+    let stdout = test_env.jj_cmd_success(
+        &other_path,
+        &["resolve", "--list", "-r", "my-bookmark@origin"],
+    );
+    insta::assert_snapshot!(stdout, @"file    2-sided conflict");
 }
 
 #[test]
@@ -924,6 +1131,31 @@ fn test_git_push_no_description() {
     );
 }
 
+#[test]
+fn test_git_push_warn_on_commit_class() {
+    let (test_env, workspace_root) = set_up();
+    test_env.add_config(
+        r#"
+        [classes]
+        wip = { revset = 'description(glob:"wip*")', warn-on-push = true }
+        quiet = 'description(glob:"wip*")'
+        "#,
+    );
+    test_env.jj_cmd_ok(&workspace_root, &["describe", "-m", "wip: foo"]);
+    test_env.jj_cmd_ok(&workspace_root, &["new", "-m", "bar"]);
+    test_env.jj_cmd_ok(&workspace_root, &["bookmark", "create", "my-bookmark"]);
+    let (stdout, stderr) = test_env.jj_cmd_ok(
+        &workspace_root,
+        &["git", "push", "--allow-new", "--bookmark", "my-bookmark"],
+    );
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r"
+    Warning: Pushing commit in class wip: yqosqzyt 1c859e7b (empty) wip: foo
+    Changes to push to origin:
+      Add bookmark my-bookmark to b8e63399af24
+    ");
+}
+
 #[test]
 fn test_git_push_no_description_in_immutable() {
     let (test_env, workspace_root) = set_up();
@@ -1385,6 +1617,282 @@ fn test_git_push_moved_sideways_untracked() {
     "###);
 }
 
+#[test]
+fn test_git_push_atomic() {
+    let (test_env, workspace_root) = set_up();
+    test_env.jj_cmd_ok(&workspace_root, &["bookmark", "delete", "bookmark1"]);
+    test_env.jj_cmd_ok(
+        &workspace_root,
+        &["bookmark", "set", "--allow-backwards", "bookmark2"],
+    );
+    test_env.jj_cmd_ok(&workspace_root, &["bookmark", "create", "my-bookmark"]);
+    test_env.jj_cmd_ok(&workspace_root, &["describe", "-m", "foo"]);
+    let (stdout, stderr) =
+        test_env.jj_cmd_ok(&workspace_root, &["git", "push", "--all", "--atomic"]);
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r"
+    Changes to push to origin:
+      Delete bookmark bookmark1 from d13ecdbda2a2
+      Move sideways bookmark bookmark2 from 8476341eb395 to c4a3c3105d92
+      Add bookmark my-bookmark to c4a3c3105d92
+    ");
+    insta::assert_snapshot!(get_bookmark_output(&test_env, &workspace_root), @r"
+    bookmark2: yqosqzyt c4a3c310 (empty) foo
+      @origin: yqosqzyt c4a3c310 (empty) foo
+    my-bookmark: yqosqzyt c4a3c310 (empty) foo
+      @origin: yqosqzyt c4a3c310 (empty) foo
+    ");
+}
+
+#[cfg(unix)]
+#[test]
+fn test_git_push_atomic_rejected() {
+    use std::os::unix::fs::PermissionsExt as _;
+
+    let (test_env, workspace_root) = set_up();
+    let origin_git_repo_path = test_env
+        .env_root()
+        .join("origin")
+        .join(".jj")
+        .join("repo")
+        .join("store")
+        .join("git");
+    // Reject updates of bookmark2 only
+    let hooks_dir = origin_git_repo_path.join("hooks");
+    std::fs::create_dir_all(&hooks_dir).unwrap();
+    let hook_path = hooks_dir.join("update");
+    std::fs::write(
+        &hook_path,
+        indoc! {r#"
+            #!/bin/sh
+            if [ "$1" = refs/heads/bookmark2 ]; then
+                echo "bookmark2 is protected" >&2
+                exit 1
+            fi
+        "#},
+    )
+    .unwrap();
+    std::fs::set_permissions(&hook_path, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+    test_env.jj_cmd_ok(&workspace_root, &["new", "bookmark1", "bookmark2"]);
+    test_env.jj_cmd_ok(&workspace_root, &["describe", "-m", "merge"]);
+    test_env.jj_cmd_ok(
+        &workspace_root,
+        &["bookmark", "set", "bookmark1", "bookmark2"],
+    );
+    let stderr = test_env.jj_cmd_failure(&workspace_root, &["git", "push", "--atomic"]);
+    // Remote messages are padded to clear the rest of the line
+    let stderr = stderr.replace("        \n", "\n");
+    insta::assert_snapshot!(stderr, @r"
+    Changes to push to origin:
+      Move forward bookmark bookmark1 from d13ecdbda2a2 to 33760ebf7538
+      Move forward bookmark bookmark2 from 8476341eb395 to 33760ebf7538
+    remote: bookmark2 is protected
+    remote: error: hook declined to update refs/heads/bookmark2
+    Error: Remote rejected the update of some bookmarks, so no bookmarks were updated. Rejected refs: refs/heads/bookmark2
+    ");
+    // Neither bookmark was updated on the remote
+    insta::assert_snapshot!(get_bookmark_output(&test_env, &workspace_root), @r"
+    bookmark1: vruxwmqv 33760ebf (empty) merge
+      @origin (behind by 2 commits): xtvrqkyv d13ecdbd (empty) description 1
+    bookmark2: vruxwmqv 33760ebf (empty) merge
+      @origin (behind by 2 commits): rlzusymt 8476341e (empty) description 2
+    ");
+}
+
+#[test]
+fn test_git_push_atomic_unsupported() {
+    let (test_env, workspace_root) = set_up();
+    let origin_git_repo_path = test_env
+        .env_root()
+        .join("origin")
+        .join(".jj")
+        .join("repo")
+        .join("store")
+        .join("git");
+    let origin_git_repo = git2::Repository::open(&origin_git_repo_path).unwrap();
+    origin_git_repo
+        .config()
+        .unwrap()
+        .set_bool("receive.advertiseAtomic", false)
+        .unwrap();
+
+    test_env.jj_cmd_ok(&workspace_root, &["new", "bookmark1"]);
+    test_env.jj_cmd_ok(&workspace_root, &["describe", "-m", "foo"]);
+    test_env.jj_cmd_ok(&workspace_root, &["bookmark", "set", "bookmark1"]);
+    let (stdout, stderr) = test_env.jj_cmd_ok(&workspace_root, &["git", "push", "--atomic"]);
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r"
+    Changes to push to origin:
+      Move forward bookmark bookmark1 from d13ecdbda2a2 to 8b4348056e20
+    Warning: Remote origin doesn't support atomic pushes, so the bookmarks were pushed non-atomically.
+    ");
+}
+
+#[cfg(unix)]
+#[test]
+fn test_git_push_options() {
+    use std::os::unix::fs::PermissionsExt as _;
+
+    let (test_env, workspace_root) = set_up();
+    let origin_git_repo_path = test_env
+        .env_root()
+        .join("origin")
+        .join(".jj")
+        .join("repo")
+        .join("store")
+        .join("git");
+    let origin_git_repo = git2::Repository::open(&origin_git_repo_path).unwrap();
+    origin_git_repo
+        .config()
+        .unwrap()
+        .set_bool("receive.advertisePushOptions", true)
+        .unwrap();
+    let hooks_dir = origin_git_repo_path.join("hooks");
+    std::fs::create_dir_all(&hooks_dir).unwrap();
+    let hook_path = hooks_dir.join("pre-receive");
+    std::fs::write(
+        &hook_path,
+        indoc! {r#"
+            #!/bin/sh
+            i=0
+            while [ "$i" -lt "${GIT_PUSH_OPTION_COUNT:-0}" ]; do
+                eval "echo \"push option: \$GIT_PUSH_OPTION_$i\"" >&2
+                i=$((i + 1))
+            done
+        "#},
+    )
+    .unwrap();
+    std::fs::set_permissions(&hook_path, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+    test_env.jj_cmd_ok(&workspace_root, &["new", "bookmark1", "-mfoo"]);
+    test_env.jj_cmd_ok(&workspace_root, &["bookmark", "set", "bookmark1"]);
+    let (stdout, stderr) = test_env.jj_cmd_ok(
+        &workspace_root,
+        &[
+            "git",
+            "push",
+            "--atomic",
+            "-o",
+            "merge_request.create",
+            "--push-option=topic=foo",
+        ],
+    );
+    // Remote messages are padded to clear the rest of the line
+    let stderr = stderr.replace("        \n", "\n");
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r"
+    Changes to push to origin:
+      Move forward bookmark bookmark1 from d13ecdbda2a2 to 0c142ff3a34f
+    remote: push option: merge_request.create
+    remote: push option: topic=foo
+    ");
+
+    // Non-atomic pushes go through libgit2, which doesn't support push options
+    // for local remotes
+    test_env.jj_cmd_ok(&workspace_root, &["new", "-mbar"]);
+    test_env.jj_cmd_ok(&workspace_root, &["bookmark", "set", "bookmark1"]);
+    let stderr = test_env.jj_cmd_failure(
+        &workspace_root,
+        &["git", "push", "-o", "merge_request.create"],
+    );
+    insta::assert_snapshot!(stderr, @r"
+    Changes to push to origin:
+      Move forward bookmark bookmark1 from 0c142ff3a34f to fb8899792717
+    Error: push-options not supported by remote; class=Invalid (3)
+    ");
+}
+
+#[test]
+fn test_git_push_signed_unsupported() {
+    let (test_env, workspace_root) = set_up();
+    test_env.jj_cmd_ok(&workspace_root, &["new", "bookmark1", "-mfoo"]);
+    test_env.jj_cmd_ok(&workspace_root, &["bookmark", "set", "bookmark1"]);
+    let stderr = test_env.jj_cmd_failure(&workspace_root, &["git", "push", "--signed"]);
+    insta::assert_snapshot!(stderr, @r"
+    Changes to push to origin:
+      Move forward bookmark bookmark1 from d13ecdbda2a2 to 0c142ff3a34f
+    Error: Failed to run git push: fatal: the receiving end does not support --signed push
+    fatal: the remote end hung up unexpectedly
+    ");
+
+    // The setting has the same effect
+    test_env.add_config("git.sign-on-push = true");
+    let stderr = test_env.jj_cmd_failure(&workspace_root, &["git", "push"]);
+    insta::assert_snapshot!(stderr, @r"
+    Changes to push to origin:
+      Move forward bookmark bookmark1 from d13ecdbda2a2 to 0c142ff3a34f
+    Error: Failed to run git push: fatal: the receiving end does not support --signed push
+    fatal: the remote end hung up unexpectedly
+    ");
+}
+
+#[test]
+fn test_git_push_tag() {
+    let (test_env, workspace_root) = set_up();
+    let origin_git_repo_path = test_env
+        .env_root()
+        .join("origin")
+        .join(".jj")
+        .join("repo")
+        .join("store")
+        .join("git");
+    let origin_git_repo = git2::Repository::open(&origin_git_repo_path).unwrap();
+
+    test_env.jj_cmd_ok(&workspace_root, &["new", "bookmark1", "-mfoo"]);
+    test_env.jj_cmd_ok(&workspace_root, &["tag", "create", "v1.0"]);
+    test_env.jj_cmd_ok(&workspace_root, &["tag", "create", "-rbookmark2", "v0.9"]);
+
+    let stderr = test_env.jj_cmd_failure(&workspace_root, &["git", "push", "--tag=v2.0"]);
+    insta::assert_snapshot!(stderr, @"Error: No such tag: v2.0");
+
+    let (stdout, stderr) = test_env.jj_cmd_ok(
+        &workspace_root,
+        &["git", "push", "--tag=glob:v*", "--dry-run"],
+    );
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r"
+    Changes to push to origin:
+      Add tag v0.9 to 8476341eb395
+      Add tag v1.0 to 0c142ff3a34f
+    Dry-run requested, not pushing.
+    ");
+
+    let (stdout, stderr) = test_env.jj_cmd_ok(&workspace_root, &["git", "push", "--tag=v1.0"]);
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r"
+    Changes to push to origin:
+      Add tag v1.0 to 0c142ff3a34f
+    ");
+    let pushed_commit_id = origin_git_repo
+        .find_reference("refs/tags/v1.0")
+        .unwrap()
+        .peel_to_commit()
+        .unwrap()
+        .id();
+    insta::assert_snapshot!(pushed_commit_id, @"0c142ff3a34fffc3760cb5ed44af21d6a1530bc2");
+    // Bookmarks are left alone
+    insta::assert_snapshot!(get_bookmark_output(&test_env, &workspace_root), @r"
+    bookmark1: xtvrqkyv d13ecdbd (empty) description 1
+      @origin: xtvrqkyv d13ecdbd (empty) description 1
+    bookmark2: rlzusymt 8476341e (empty) description 2
+      @origin: rlzusymt 8476341e (empty) description 2
+    ");
+
+    // Pushing a tag that already exists on the remote at the same commit is a
+    // no-op, but pushing it to another commit is rejected.
+    test_env.jj_cmd_ok(&workspace_root, &["git", "push", "--tag=v1.0"]);
+    test_env.jj_cmd_ok(&workspace_root, &["tag", "delete", "v1.0"]);
+    test_env.jj_cmd_ok(&workspace_root, &["tag", "create", "-rbookmark2", "v1.0"]);
+    let stderr = test_env.jj_cmd_failure(&workspace_root, &["git", "push", "--tag=v1.0"]);
+    insta::assert_snapshot!(stderr, @r"
+    Changes to push to origin:
+      Add tag v1.0 to 8476341eb395
+    Error: Refusing to push a bookmark that unexpectedly moved on the remote. Affected refs: refs/tags/v1.0
+    Hint: Try fetching from the remote, then make the bookmark point to where you want it to be, and push again.
+    ");
+}
+
 #[test]
 // TODO: This test fails with libgit2 v1.8.1 on Windows.
 #[cfg(not(target_os = "windows"))]
@@ -142,6 +142,35 @@ fn test_git_remote_set_url() {
     insta::assert_snapshot!(stdout, @r###"
     foo http://example.com/repo/bar
     "###);
+
+    // The push URL defaults to the fetch URL
+    let stdout = test_env.jj_cmd_success(&repo_path, &["git", "remote", "list", "--verbose"]);
+    insta::assert_snapshot!(stdout, @r###"
+    foo http://example.com/repo/bar (fetch)
+    foo http://example.com/repo/bar (push)
+    "###);
+    let (stdout, stderr) = test_env.jj_cmd_ok(
+        &repo_path,
+        &[
+            "git",
+            "remote",
+            "set-url",
+            "--push",
+            "foo",
+            "ssh://example.com/repo/bar",
+        ],
+    );
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @"");
+    let stdout = test_env.jj_cmd_success(&repo_path, &["git", "remote", "list", "--verbose"]);
+    insta::assert_snapshot!(stdout, @r###"
+    foo http://example.com/repo/bar (fetch)
+    foo ssh://example.com/repo/bar (push)
+    "###);
+    let stdout = test_env.jj_cmd_success(&repo_path, &["git", "remote", "list"]);
+    insta::assert_snapshot!(stdout, @r###"
+    foo http://example.com/repo/bar
+    "###);
 }
 
 #[test]
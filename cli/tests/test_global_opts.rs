@@ -116,6 +116,31 @@ fn test_no_subcommand() {
     Working copy now at: kxryzmor 89c70edf (empty) (no description set)
     Parent commit      : lylxulpl 51bd3589 foo
     "###);
+
+    // User-defined aliases can be used as the default command.
+    test_env.add_config(r#"aliases.l = ["log", "-r", "@"]"#);
+    test_env.add_config(r#"ui.default-command = "l""#);
+    let stdout = test_env.jj_cmd_success(&repo_path, &[]);
+    assert_eq!(
+        stdout,
+        test_env.jj_cmd_success(&repo_path, &["log", "-r", "@"])
+    );
+
+    // Invalid default commands are reported, but don't prevent `--help`.
+    test_env.add_config(r#"ui.default-command = []"#);
+    let stderr = test_env.jj_cmd_failure(&repo_path, &[]);
+    insta::assert_snapshot!(stderr, @r#"
+    Config error: Invalid `ui.default-command`: the command must not be empty
+    For help, see https://jj-vcs.github.io/jj/latest/config/.
+    "#);
+    test_env.add_config(r#"ui.default-command = "lgo""#);
+    let stderr = test_env.jj_cmd_failure(&repo_path, &[]);
+    insta::assert_snapshot!(stderr, @r#"
+    Config error: Invalid `ui.default-command`: unrecognized command 'lgo'
+    For help, see https://jj-vcs.github.io/jj/latest/config/.
+    "#);
+    let stdout = test_env.jj_cmd_success(&repo_path, &["--help"]);
+    insta::assert_snapshot!(stdout.lines().next().unwrap(), @"Jujutsu (An experimental VCS)");
 }
 
 #[test]
@@ -824,7 +849,7 @@ fn test_help() {
     let test_env = TestEnvironment::default();
 
     let stdout = test_env.jj_cmd_success(test_env.env_root(), &["diffedit", "-h"]);
-    insta::assert_snapshot!(stdout, @r###"
+    insta::assert_snapshot!(stdout, @r"
     Touch up the content changes in a revision with a diff editor
 
     Usage: jj diffedit [OPTIONS]
@@ -838,17 +863,19 @@ fn test_help() {
       -h, --help                 Print help (see more with '--help')
 
     Global Options:
-      -R, --repository <REPOSITORY>      Path to repository to operate on
-          --ignore-working-copy          Don't snapshot the working copy, and don't update it
-          --ignore-immutable             Allow rewriting immutable commits
-          --at-operation <AT_OPERATION>  Operation to load the repo at [aliases: at-op]
-          --debug                        Enable debug logging
-          --color <WHEN>                 When to colorize output (always, never, debug, auto)
-          --quiet                        Silence non-primary command output
-          --no-pager                     Disable the pager
-          --config <NAME=VALUE>          Additional configuration options (can be repeated)
-          --config-file <PATH>           Additional configuration files (can be repeated)
-    "###);
+      -R, --repository <REPOSITORY>        Path to repository to operate on
+          --ignore-working-copy            Don't snapshot the working copy, and don't update it
+          --ignore-immutable               Allow rewriting immutable commits
+          --at-operation <AT_OPERATION>    Operation to load the repo at [aliases: at-op]
+          --debug                          Enable debug logging
+          --color <WHEN>                   When to colorize output (always, never, debug, auto)
+          --quiet                          Silence non-primary command output
+          --no-pager                       Disable the pager
+          --conflict-marker-style <STYLE>  Style of conflict markers to materialize in the working copy
+                                           [possible values: diff, snapshot, git, zdiff3]
+          --config <NAME=VALUE>            Additional configuration options (can be repeated)
+          --config-file <PATH>             Additional configuration files (can be repeated)
+    ");
 }
 
 #[test]
@@ -869,3 +896,32 @@ fn test_debug_logging_enabled() {
     // Luckily, insta will print this in colour when reviewing.
     insta::assert_snapshot!(log_line, @"[32m INFO[0m [2mjj_cli::cli_util[0m[2m:[0m debug logging enabled");
 }
+
+#[test]
+fn test_metrics_file() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+    let metrics_path = test_env.env_root().join("metrics.jsonl");
+    test_env.add_config(format!(
+        "telemetry.metrics-file = {}",
+        toml_edit::Value::from(metrics_path.to_str().unwrap())
+    ));
+
+    test_env.jj_cmd_ok(&repo_path, &["log", "-r", "@"]);
+    test_env.jj_cmd_ok(&repo_path, &["bookmark", "list", "secret-name"]);
+    let lines = std::fs::read_to_string(&metrics_path).unwrap();
+    let entries: Vec<serde_json::Value> = lines
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    assert_eq!(entries.len(), 2);
+    assert_eq!(entries[0]["command"], "log");
+    assert_eq!(entries[0]["success"], true);
+    assert_eq!(entries[0]["spans"]["cmd_log"]["count"], 1);
+    assert_eq!(entries[0]["repo"]["num_commits"], 2);
+    assert_eq!(entries[1]["command"], "bookmark list");
+    // Arguments and paths aren't recorded
+    assert!(!lines.contains("secret-name"));
+    assert!(!lines.contains(test_env.env_root().to_str().unwrap()));
+}
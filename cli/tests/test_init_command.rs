@@ -562,3 +562,57 @@ fn test_init_local() {
     Error: --at-op is not respected
     "###);
 }
+
+#[test]
+fn test_init_local_shared_store() {
+    let test_env = TestEnvironment::default();
+    test_env.add_config(r#"ui.allow-init-native = true"#);
+    let (stdout, stderr) = test_env.jj_cmd_ok(
+        test_env.env_root(),
+        &["init", "--shared-store", "shared", "repo1"],
+    );
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r#"Initialized repo in "repo1""#);
+    test_env.jj_cmd_ok(
+        test_env.env_root(),
+        &["init", "--shared-store", "shared", "repo2"],
+    );
+
+    // Both repos write the objects to the shared store
+    let shared_store_path = test_env.env_root().join("shared");
+    for (name, contents) in [("repo1", "same"), ("repo2", "same")] {
+        let workspace_root = test_env.env_root().join(name);
+        std::fs::write(workspace_root.join("file"), contents).unwrap();
+        test_env.jj_cmd_ok(&workspace_root, &["commit", "-m", name]);
+        let store_path = workspace_root.join(".jj").join("repo").join("store");
+        assert!(store_path.join("shared_store").is_file());
+        assert_eq!(
+            std::fs::read_dir(store_path.join("files")).unwrap().count(),
+            0
+        );
+    }
+    assert_eq!(
+        std::fs::read_dir(shared_store_path.join("files"))
+            .unwrap()
+            .count(),
+        1
+    );
+    assert_eq!(
+        std::fs::read_dir(shared_store_path.join("users"))
+            .unwrap()
+            .count(),
+        2
+    );
+
+    let stderr = test_env.jj_cmd_cli_error(
+        test_env.env_root(),
+        &["init", "--git", "--shared-store", "shared", "repo3"],
+    );
+    insta::assert_snapshot!(stderr, @r"
+    error: the argument '--git' cannot be used with '--shared-store <DIR>'
+
+    Usage: jj init <DESTINATION>
+
+    For more information, try '--help'.
+    ");
+}
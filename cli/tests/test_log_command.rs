@@ -12,6 +12,9 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use jj_lib::op_store::RemoteRefState;
+use testutils::scenario::Scenario;
+
 use crate::common::get_stdout_string;
 use crate::common::TestEnvironment;
 
@@ -29,6 +32,47 @@ fn test_log_with_empty_revision() {
     "###);
 }
 
+#[test]
+fn test_log_scenario() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+    let scenario = Scenario::new("A-B-D, A-C-D")
+        .file("A", "file1", "a\n")
+        .file("C", "dir/file2", "c\n")
+        .remove_file("D", "dir/file2")
+        .bookmark("main", "D")
+        .remote_bookmark("main", "origin", "B", RemoteRefState::Tracking);
+    test_env.write_scenario(&repo_path, &scenario);
+
+    let template = r#"bookmarks ++ " " ++ description"#;
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &["log", "--no-graph", "-r=merges()", "-T", template],
+    );
+    insta::assert_snapshot!(stdout, @r###"
+    main* D
+    "###);
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &["log", "--no-graph", "-r=main@origin", "-T", template],
+    );
+    insta::assert_snapshot!(stdout, @r###"
+    main@origin B
+    "###);
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &["file", "show", "-r=description(C)", "dir/file2"],
+    );
+    insta::assert_snapshot!(stdout, @r###"
+    c
+    "###);
+    let stdout = test_env.jj_cmd_success(&repo_path, &["file", "list", "-r=main"]);
+    insta::assert_snapshot!(stdout, @r###"
+    file1
+    "###);
+}
+
 #[test]
 fn test_log_with_no_template() {
     let test_env = TestEnvironment::default();
@@ -36,7 +80,7 @@ fn test_log_with_no_template() {
     let repo_path = test_env.env_root().join("repo");
 
     let stderr = test_env.jj_cmd_cli_error(&repo_path, &["log", "-T"]);
-    insta::assert_snapshot!(stderr, @r#"
+    insta::assert_snapshot!(stderr, @r"
     error: a value is required for '--template <TEMPLATE>' but none was supplied
 
     For more information, try '--help'.
@@ -45,18 +89,20 @@ fn test_log_with_no_template() {
     - builtin_log_compact
     - builtin_log_compact_full_description
     - builtin_log_detailed
+    - builtin_log_json
     - builtin_log_node
     - builtin_log_node_ascii
     - builtin_log_oneline
     - builtin_op_log_comfortable
     - builtin_op_log_compact
+    - builtin_op_log_json
     - builtin_op_log_node
     - builtin_op_log_node_ascii
     - commit_summary_separator
     - description_placeholder
     - email_placeholder
     - name_placeholder
-    "#);
+    ");
 }
 
 #[test]
@@ -927,6 +973,7 @@ fn test_log_filtered_by_path() {
 #[test]
 fn test_log_limit() {
     let test_env = TestEnvironment::default();
+    test_env.add_config("ui.log-omitted-hint = true");
     test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
     let repo_path = test_env.env_root().join("repo");
 
@@ -941,7 +988,8 @@ fn test_log_limit() {
         &["new", "-m", "d", "description(c)", "description(b)"],
     );
 
-    let stdout = test_env.jj_cmd_success(&repo_path, &["log", "-T", "description", "--limit=3"]);
+    let (stdout, stderr) =
+        test_env.jj_cmd_ok(&repo_path, &["log", "-T", "description", "--limit=3"]);
     insta::assert_snapshot!(stdout, @r###"
     @    d
     ├─╮
@@ -949,16 +997,19 @@ fn test_log_limit() {
     ○ │  c
     ├─╯
     "###);
+    insta::assert_snapshot!(stderr, @"Hint: 2 more revisions were not shown because of --limit. Run without --limit to see them.");
 
     // Applied on sorted DAG
-    let stdout = test_env.jj_cmd_success(&repo_path, &["log", "-T", "description", "--limit=2"]);
+    let (stdout, stderr) =
+        test_env.jj_cmd_ok(&repo_path, &["log", "-T", "description", "--limit=2"]);
     insta::assert_snapshot!(stdout, @r###"
     @    d
     ├─╮
     │ ○  b
     "###);
+    insta::assert_snapshot!(stderr, @"Hint: 3 more revisions were not shown because of --limit. Run without --limit to see them.");
 
-    let stdout = test_env.jj_cmd_success(
+    let (stdout, stderr) = test_env.jj_cmd_ok(
         &repo_path,
         &["log", "-T", "description", "--limit=2", "--no-graph"],
     );
@@ -966,9 +1017,10 @@ fn test_log_limit() {
     d
     c
     "###);
+    insta::assert_snapshot!(stderr, @"Hint: 3 more revisions were not shown because of --limit. Run without --limit to see them.");
 
     // Applied on reversed DAG
-    let stdout = test_env.jj_cmd_success(
+    let (stdout, stderr) = test_env.jj_cmd_ok(
         &repo_path,
         &["log", "-T", "description", "--limit=3", "--reversed"],
     );
@@ -978,7 +1030,8 @@ fn test_log_limit() {
     ├─╮
     │ ○  c
     "###);
-    let stdout = test_env.jj_cmd_success(
+    insta::assert_snapshot!(stderr, @"Hint: 2 more revisions were not shown because of --limit. Run without --limit to see them.");
+    let (stdout, stderr) = test_env.jj_cmd_ok(
         &repo_path,
         &[
             "log",
@@ -993,9 +1046,10 @@ fn test_log_limit() {
     a
     b
     "###);
+    insta::assert_snapshot!(stderr, @"Hint: 2 more revisions were not shown because of --limit. Run without --limit to see them.");
 
     // Applied on filtered commits
-    let stdout = test_env.jj_cmd_success(
+    let (stdout, stderr) = test_env.jj_cmd_ok(
         &repo_path,
         &["log", "-T", "description", "--limit=1", "b", "c"],
     );
@@ -1004,6 +1058,7 @@ fn test_log_limit() {
     │
     ~
     "###);
+    insta::assert_snapshot!(stderr, @"Hint: 1 more revision was not shown because of --limit. Run without --limit to see them.");
 }
 
 #[test]
@@ -1122,6 +1177,57 @@ fn test_default_revset_per_repo() {
     );
 }
 
+#[test]
+fn test_log_preset() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+    test_env.jj_cmd_ok(&repo_path, &["describe", "-m", "first"]);
+    test_env.jj_cmd_ok(&repo_path, &["new", "-m", "second"]);
+
+    test_env.add_config(
+        r#"
+        [log-presets.first]
+        revisions = "description(first)"
+        template = "description.first_line()"
+
+        [log-presets.ids]
+        template = "change_id.short()"
+        "#,
+    );
+
+    // Revisions and template are taken from the preset
+    let stdout = test_env.jj_cmd_success(&repo_path, &["log", "--preset", "first"]);
+    insta::assert_snapshot!(stdout, @r"
+    ○  first
+    │
+    ~
+    ");
+    // Missing settings fall back to the defaults
+    let stdout = test_env.jj_cmd_success(&repo_path, &["log", "--preset", "ids"]);
+    insta::assert_snapshot!(stdout, @r"
+    @  kkmpptxzrspx
+    ○  qpvuntsmwlqt
+    ◆  zzzzzzzzzzzz
+    ");
+    // Explicit arguments take precedence over the preset
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &["log", "--preset", "first", "-r", "@", "-T", "description"],
+    );
+    insta::assert_snapshot!(stdout, @r"
+    @  second
+    │
+    ~
+    ");
+
+    let stderr = test_env.jj_cmd_failure(&repo_path, &["log", "--preset", "unknown"]);
+    insta::assert_snapshot!(stderr, @r"
+    Error: Unknown log preset: unknown
+    Hint: Available presets: first, ids
+    ");
+}
+
 #[test]
 fn test_multiple_revsets() {
     let test_env = TestEnvironment::default();
@@ -1577,6 +1683,68 @@ fn test_elided() {
     "###);
 }
 
+#[test]
+fn test_log_elided_hint() {
+    let test_env = TestEnvironment::default();
+    test_env.add_config("ui.log-omitted-hint = true");
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+    test_env.jj_cmd_ok(&repo_path, &["describe", "-m", "a"]);
+    std::fs::write(repo_path.join("file"), "a\n").unwrap();
+    test_env.jj_cmd_ok(&repo_path, &["new", "-m", "b"]);
+    test_env.jj_cmd_ok(&repo_path, &["new", "-m", "c"]);
+    std::fs::write(repo_path.join("file"), "c\n").unwrap();
+
+    let (stdout, stderr) = test_env.jj_cmd_ok(
+        &repo_path,
+        &[
+            "log",
+            "-T",
+            "description",
+            "-r",
+            "@",
+            "-r",
+            "description(a)",
+        ],
+    );
+    insta::assert_snapshot!(stdout, @r"
+    @  c
+    ~  (elided revisions)
+    ○  a
+    │
+    ~
+    ");
+    insta::assert_snapshot!(stderr, @"Hint: 1 revision was elided from the graph. Run `jj log -r 'connected((@) | (description(a)))'` to see them.");
+
+    // The revset can't be suggested if paths are specified
+    let (stdout, stderr) = test_env.jj_cmd_ok(
+        &repo_path,
+        &["log", "-T", "description", "-r", "::", "file"],
+    );
+    insta::assert_snapshot!(stdout, @r"
+    @  c
+    ~  (elided revisions)
+    ○  a
+    │
+    ~
+    ");
+    insta::assert_snapshot!(stderr, @"Hint: 1 revision was elided from the graph.");
+
+    // The hint is disabled by default
+    let (_stdout, stderr) = test_env.jj_cmd_ok(
+        &repo_path,
+        &[
+            "log",
+            "-T",
+            "description",
+            "-r",
+            "@ | description(a)",
+            "--config=ui.log-omitted-hint=false",
+        ],
+    );
+    insta::assert_snapshot!(stderr, @"");
+}
+
 #[test]
 fn test_log_with_custom_symbols() {
     // Test that elided commits are shown as synthetic nodes.
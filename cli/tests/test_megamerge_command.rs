@@ -0,0 +1,172 @@
+// Copyright 2024 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::path::Path;
+
+use crate::common::TestEnvironment;
+
+fn create_commit(test_env: &TestEnvironment, repo_path: &Path, name: &str, parents: &[&str]) {
+    if parents.is_empty() {
+        test_env.jj_cmd_ok(repo_path, &["new", "root()", "-m", name]);
+    } else {
+        let mut args = vec!["new", "-m", name];
+        args.extend(parents);
+        test_env.jj_cmd_ok(repo_path, &args);
+    }
+    test_env.jj_cmd_ok(repo_path, &["bookmark", "create", name]);
+}
+
+fn get_log_output(test_env: &TestEnvironment, repo_path: &Path) -> String {
+    test_env.jj_cmd_success(repo_path, &["log", "-T", "bookmarks"])
+}
+
+#[test]
+fn test_megamerge_update() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    create_commit(&test_env, &repo_path, "a", &[]);
+    create_commit(&test_env, &repo_path, "b", &[]);
+    test_env.jj_cmd_ok(
+        &repo_path,
+        &["new", "--merge-all", "a | b", "-m", "megamerge"],
+    );
+    test_env.jj_cmd_ok(&repo_path, &["bookmark", "create", "megamerge"]);
+    create_commit(&test_env, &repo_path, "top", &["megamerge"]);
+    // Nothing to update yet
+    let (stdout, stderr) =
+        test_env.jj_cmd_ok(&repo_path, &["megamerge", "update", "-r", "megamerge"]);
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @"Nothing changed.");
+
+    // Add commits on top of the merged branches
+    create_commit(&test_env, &repo_path, "a2", &["a"]);
+    create_commit(&test_env, &repo_path, "b2", &["b"]);
+    create_commit(&test_env, &repo_path, "b3", &["b"]);
+    test_env.jj_cmd_ok(&repo_path, &["edit", "top"]);
+    insta::assert_snapshot!(get_log_output(&test_env, &repo_path), @r"
+    @  top
+    ○    megamerge
+    ├─╮
+    │ │ ○  b3
+    │ ├─╯
+    │ │ ○  b2
+    │ ├─╯
+    │ ○  b
+    │ │ ○  a2
+    ├───╯
+    ○ │  a
+    ├─╯
+    ◆
+    ");
+    let (stdout, stderr) =
+        test_env.jj_cmd_ok(&repo_path, &["megamerge", "update", "-r", "megamerge"]);
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r"
+    Added parent: kpqxywon 981fa00a a2 | (empty) a2
+    Added parent: wqnwkozp aff895f8 b2 | (empty) b2
+    Added parent: kxryzmor ce50d83f b3 | (empty) b3
+    Removed parent: rlvkpnrz d8d5f980 a | (empty) a
+    Removed parent: zsuskuln 1868260a b | (empty) b
+    Rebased 1 descendant commits
+    Working copy now at: vruxwmqv 2f5014c8 top | (empty) top
+    Parent commit      : royxmykx 77c03b29 megamerge | (empty) megamerge
+    ");
+    insta::assert_snapshot!(get_log_output(&test_env, &repo_path), @r"
+    @  top
+    ○      megamerge
+    ├─┬─╮
+    │ │ ○  b3
+    │ ○ │  b2
+    │ ├─╯
+    │ ○  b
+    ○ │  a2
+    ○ │  a
+    ├─╯
+    ◆
+    ");
+}
+
+#[test]
+fn test_megamerge_update_merge_all() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    create_commit(&test_env, &repo_path, "a", &[]);
+    create_commit(&test_env, &repo_path, "b", &[]);
+    create_commit(&test_env, &repo_path, "c", &[]);
+    create_commit(&test_env, &repo_path, "megamerge", &["a", "b"]);
+    let (stdout, stderr) = test_env.jj_cmd_ok(
+        &repo_path,
+        &["megamerge", "update", "--merge-all", "(a | c)::"],
+    );
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r"
+    Added parent: royxmykx b915bd61 c | (empty) c
+    Removed parent: zsuskuln 1868260a b | (empty) b
+    Working copy now at: vruxwmqv 99d894ff megamerge | (empty) megamerge
+    Parent commit      : rlvkpnrz d8d5f980 a | (empty) a
+    Parent commit      : royxmykx b915bd61 c | (empty) c
+    ");
+    insta::assert_snapshot!(get_log_output(&test_env, &repo_path), @r"
+    @    megamerge
+    ├─╮
+    │ ○  c
+    ○ │  a
+    ├─╯
+    │ ○  b
+    ├─╯
+    ◆
+    ");
+
+    let stderr = test_env.jj_cmd_failure(
+        &repo_path,
+        &["megamerge", "update", "--merge-all", "megamerge"],
+    );
+    insta::assert_snapshot!(stderr, @"Error: The megamerge would have no parents");
+}
+
+#[test]
+fn test_megamerge_update_keeps_immutable_parents() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    create_commit(&test_env, &repo_path, "trunk", &[]);
+    create_commit(&test_env, &repo_path, "other", &["trunk"]);
+    create_commit(&test_env, &repo_path, "a", &[]);
+    create_commit(&test_env, &repo_path, "megamerge", &["trunk", "a"]);
+    create_commit(&test_env, &repo_path, "a2", &["a"]);
+    test_env.add_config(r#"revset-aliases."immutable_heads()" = "trunk""#);
+    let (stdout, stderr) =
+        test_env.jj_cmd_ok(&repo_path, &["megamerge", "update", "-r", "megamerge"]);
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r"
+    Added parent: znkkpsqq 649a055d a2 | (empty) a2
+    Removed parent: royxmykx 6ca0450a a | (empty) a
+    ");
+    insta::assert_snapshot!(get_log_output(&test_env, &repo_path), @r"
+    ○    megamerge
+    ├─╮
+    │ @  a2
+    │ ○  a
+    │ │ ○  other
+    ├───╯
+    ◆ │  trunk
+    ├─╯
+    ◆
+    ");
+}
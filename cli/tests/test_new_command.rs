@@ -138,6 +138,121 @@ fn test_new_merge() {
     "###);
 }
 
+#[test]
+fn test_new_merge_take_tree() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    test_env.jj_cmd_ok(&repo_path, &["describe", "-m", "a"]);
+    std::fs::write(repo_path.join("file1"), "a\n").unwrap();
+    std::fs::write(repo_path.join("file2"), "a\n").unwrap();
+    test_env.jj_cmd_ok(&repo_path, &["new", "root()", "-m", "b"]);
+    std::fs::write(repo_path.join("file1"), "b\n").unwrap();
+    std::fs::write(repo_path.join("file2"), "b\n").unwrap();
+
+    // Take only file1 from one side
+    let (stdout, stderr) = test_env.jj_cmd_ok(
+        &repo_path,
+        &[
+            "new",
+            "description(a)",
+            "description(b)",
+            "--take-tree=description(b)",
+            "--take-tree-path=file1",
+        ],
+    );
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r"
+    Working copy now at: zsuskuln 69d97ed7 (conflict) (no description set)
+    Parent commit      : qpvuntsm fbb96eeb a
+    Parent commit      : kkmpptxz cf15737e b
+    Added 0 files, modified 1 files, removed 0 files
+    There are unresolved conflicts at these paths:
+    file2    2-sided conflict
+      side #1: qpvuntsm fbb96eeb a
+      side #2: kkmpptxz cf15737e b
+    New conflicts appeared in these commits:
+      zsuskuln 69d97ed7 (conflict) (no description set)
+    To resolve the conflicts, start by updating to it:
+      jj new zsuskuln
+    Then use `jj resolve`, or edit the conflict markers in the file directly.
+    Once the conflicts are resolved, you may want to inspect the result with `jj diff`.
+    Then run `jj squash` to move the resolution into the conflicted commit.
+    ");
+    let stdout = test_env.jj_cmd_success(&repo_path, &["file", "show", "file1"]);
+    insta::assert_snapshot!(stdout, @"b");
+
+    // Take all paths from one side
+    test_env.jj_cmd_ok(&repo_path, &["undo"]);
+    let (_stdout, stderr) = test_env.jj_cmd_ok(
+        &repo_path,
+        &[
+            "new",
+            "description(a)",
+            "description(b)",
+            "--take-tree=description(a)",
+        ],
+    );
+    insta::assert_snapshot!(stderr, @r"
+    Working copy now at: yqosqzyt 0dbae62e (no description set)
+    Parent commit      : qpvuntsm fbb96eeb a
+    Parent commit      : kkmpptxz cf15737e b
+    Added 0 files, modified 2 files, removed 0 files
+    ");
+
+    // The revision must be one of the parents
+    let stderr = test_env.jj_cmd_failure(
+        &repo_path,
+        &["new", "description(a)", "--take-tree=description(b)"],
+    );
+    insta::assert_snapshot!(stderr, @"Error: Commit cf15737e538a passed to --take-tree is not a parent of the new commit");
+}
+
+#[test]
+fn test_new_merge_tool() {
+    let mut test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    test_env.jj_cmd_ok(&repo_path, &["describe", "-m", "a"]);
+    std::fs::write(repo_path.join("file1"), "a\n").unwrap();
+    std::fs::write(repo_path.join("file2"), "a\n").unwrap();
+    test_env.jj_cmd_ok(&repo_path, &["new", "root()", "-m", "b"]);
+    std::fs::write(repo_path.join("file1"), "b\n").unwrap();
+    std::fs::write(repo_path.join("file2"), "b\n").unwrap();
+
+    let editor_script = test_env.set_up_fake_editor();
+    std::fs::write(
+        &editor_script,
+        [
+            "write\nresolution1\n",
+            "next invocation\n",
+            "write\nresolution2\n",
+        ]
+        .join("\0"),
+    )
+    .unwrap();
+    let (stdout, stderr) = test_env.jj_cmd_ok(
+        &repo_path,
+        &["new", "description(a)", "description(b)", "--merge-tool"],
+    );
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r"
+    Resolved conflicts in: file1
+    Resolved conflicts in: file2
+    Working copy now at: zsuskuln 167b20d1 (no description set)
+    Parent commit      : qpvuntsm fbb96eeb a
+    Parent commit      : kkmpptxz cf15737e b
+    Added 0 files, modified 2 files, removed 0 files
+    ");
+    let stdout = test_env.jj_cmd_success(&repo_path, &["file", "show", "file1", "file2"]);
+    insta::assert_snapshot!(stdout, @r"
+    resolution1
+    resolution2
+    ");
+}
+
 #[test]
 fn test_new_insert_after() {
     let test_env = TestEnvironment::default();
@@ -674,6 +789,56 @@ fn test_new_error_revision_does_not_exist() {
     "###);
 }
 
+#[test]
+fn test_new_merge_all() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    test_env.jj_cmd_ok(&repo_path, &["new", "root()", "-m", "a1"]);
+    test_env.jj_cmd_ok(&repo_path, &["new", "-m", "a2"]);
+    test_env.jj_cmd_ok(&repo_path, &["bookmark", "create", "a"]);
+    test_env.jj_cmd_ok(&repo_path, &["new", "root()", "-m", "b"]);
+    test_env.jj_cmd_ok(&repo_path, &["bookmark", "create", "b"]);
+    test_env.jj_cmd_ok(&repo_path, &["new", "root()", "-m", "c"]);
+    test_env.jj_cmd_ok(&repo_path, &["bookmark", "create", "c"]);
+
+    // Only the heads of the revset become parents
+    let (stdout, stderr) = test_env.jj_cmd_ok(
+        &repo_path,
+        &["new", "--merge-all", "bookmarks() | a-", "-m", "megamerge"],
+    );
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r"
+    Working copy now at: yostqsxw 151a38d1 (empty) megamerge
+    Parent commit      : kkmpptxz 6c514871 a | (empty) a2
+    Parent commit      : mzvwutvl 6c80e787 b | (empty) b
+    Parent commit      : yqosqzyt d4d88571 c | (empty) c
+    ");
+    insta::assert_snapshot!(get_short_log_output(&test_env, &repo_path), @r"
+    @      megamerge
+    ├─┬─╮
+    │ │ ○  c
+    │ ○ │  b
+    │ ├─╯
+    ○ │  a2
+    ○ │  a1
+    ├─╯
+    ◆  root
+    ");
+
+    let stderr = test_env.jj_cmd_failure(&repo_path, &["new", "--merge-all", "none()"]);
+    insta::assert_snapshot!(stderr, @"Error: Revset `none()` didn't resolve to any revisions");
+    let stderr = test_env.jj_cmd_cli_error(&repo_path, &["new", "--merge-all", "a", "b"]);
+    insta::assert_snapshot!(stderr, @r"
+    error: the argument '--merge-all <REVSET>' cannot be used with '[REVSETS]...'
+
+    Usage: jj new --merge-all <REVSET> [REVSETS]...
+
+    For more information, try '--help'.
+    ");
+}
+
 fn setup_before_insertion(test_env: &TestEnvironment, repo_path: &Path) {
     test_env.jj_cmd_ok(repo_path, &["bookmark", "create", "A"]);
     test_env.jj_cmd_ok(repo_path, &["commit", "-m", "A"]);
@@ -141,7 +141,7 @@ fn test_op_log_with_no_template() {
     let repo_path = test_env.env_root().join("repo");
 
     let stderr = test_env.jj_cmd_cli_error(&repo_path, &["op", "log", "-T"]);
-    insta::assert_snapshot!(stderr, @r#"
+    insta::assert_snapshot!(stderr, @r"
     error: a value is required for '--template <TEMPLATE>' but none was supplied
 
     For more information, try '--help'.
@@ -150,18 +150,20 @@ fn test_op_log_with_no_template() {
     - builtin_log_compact
     - builtin_log_compact_full_description
     - builtin_log_detailed
+    - builtin_log_json
     - builtin_log_node
     - builtin_log_node_ascii
     - builtin_log_oneline
     - builtin_op_log_comfortable
     - builtin_op_log_compact
+    - builtin_op_log_json
     - builtin_op_log_node
     - builtin_op_log_node_ascii
     - commit_summary_separator
     - description_placeholder
     - email_placeholder
     - name_placeholder
-    "#);
+    ");
 }
 
 #[test]
@@ -202,6 +204,67 @@ fn test_op_log_no_graph() {
     "#);
 }
 
+#[test]
+fn test_op_log_no_snapshot_entries() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+    std::fs::write(repo_path.join("file1"), "").unwrap();
+    test_env.jj_cmd_ok(&repo_path, &["status"]);
+    std::fs::write(repo_path.join("file2"), "").unwrap();
+    test_env.jj_cmd_ok(&repo_path, &["status"]);
+    test_env.jj_cmd_ok(&repo_path, &["new"]);
+
+    let template = r#"description ++ "\n""#;
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &["op", "log", "--no-graph", "-n4", "-T", template],
+    );
+    insta::assert_snapshot!(stdout, @r###"
+    new empty commit
+    snapshot working copy
+    snapshot working copy
+    add workspace 'default'
+    "###);
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &[
+            "op",
+            "log",
+            "--no-graph",
+            "--no-snapshot-entries",
+            "-n2",
+            "-T",
+            template,
+        ],
+    );
+    insta::assert_snapshot!(stdout, @r###"
+    new empty commit
+    add workspace 'default'
+    "###);
+
+    // Squash the snapshot operations
+    let (stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["op", "abandon", "--squash-snapshots"]);
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r###"
+    Squashed 1 snapshot operations and reparented 2 descendant operations.
+    "###);
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &["op", "log", "--no-graph", "-n3", "-T", template],
+    );
+    insta::assert_snapshot!(stdout, @r###"
+    new empty commit
+    snapshot working copy
+    add workspace 'default'
+    "###);
+    let (_stdout, stderr) =
+        test_env.jj_cmd_ok(&repo_path, &["op", "abandon", "--squash-snapshots"]);
+    insta::assert_snapshot!(stderr, @r###"
+    Nothing changed.
+    "###);
+}
+
 #[test]
 fn test_op_log_no_graph_null_terminated() {
     let test_env = TestEnvironment::default();
@@ -299,6 +362,13 @@ fn test_op_log_builtin_templates() {
 
     [EOF]
     "#);
+
+    insta::assert_snapshot!(render(r#"builtin_op_log_json"#), @r#"
+    {"id":"d009cfc049934db1f8241a1eede02b3042a9a907fd8bc7c250b8325ae50dc52a879fed9a4e597c5025250e3b52a6357a28dfa74cb505b2d9f7012031c7629332","description":"describe commit 230dd059e1b059aefc0da06a2e5a7dbf22362f22","user":"test-username@host.example.com","start_time":"2001-02-03T04:05:08+07:00","end_time":"2001-02-03T04:05:08+07:00","tags":"args: jj describe -m 'description 0'","snapshot":false,"current_operation":true}
+    {"id":"eac759b9ab75793fd3da96e60939fb48f2cd2b2a9c1f13ffe723cf620f3005b8d3e7e923634a07ea39513e4f2f360c87b9ad5d331cf90d7a844864b83b72eba1","description":"add workspace 'default'","user":"test-username@host.example.com","start_time":"2001-02-03T04:05:07+07:00","end_time":"2001-02-03T04:05:07+07:00","tags":"","snapshot":false,"current_operation":false}
+    {"id":"00000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000","description":"","user":"@","start_time":"1970-01-01T00:00:00+00:00","end_time":"1970-01-01T00:00:00+00:00","tags":"","snapshot":false,"current_operation":false}
+    [EOF]
+    "#);
 }
 
 #[test]
@@ -720,8 +790,10 @@ fn test_op_recover_from_bad_gc() {
     // point, the index can be loaded, so this command succeeds.
     test_env.jj_cmd_ok(&repo_path, &["--at-op=@-", "describe", "-m4.1"]);
 
-    let stderr =
-        test_env.jj_cmd_internal_error(&repo_path, &["--at-op", head_op_id, "debug", "reindex"]);
+    let stderr = test_env.jj_cmd_internal_error(
+        &repo_path,
+        &["--at-op", head_op_id, "debug", "reindex", "--no-backup"],
+    );
     insta::assert_snapshot!(strip_last_line(&stderr), @r#"
     Internal error: Failed to index commits at operation e7377e6a642bae88039615ee159117d49688719e9d5ece9de8b0b42d7be7076904d2fa8381391f8289a0c3527405de81e8dd6504655311c69175c3681786dd3c
     Caused by:
@@ -2138,6 +2210,33 @@ fn test_op_show_patch() {
     ");
 }
 
+#[test]
+fn test_op_wait() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    // Times out if no operation is made
+    let stderr = test_env.jj_cmd_failure(&repo_path, &["op", "wait", "--timeout", "0"]);
+    insta::assert_snapshot!(stderr, @"Error: Timed out waiting for a new operation");
+
+    // --at-op doesn't make sense
+    let stderr = test_env.jj_cmd_cli_error(&repo_path, &["op", "wait", "--at-op", "@"]);
+    insta::assert_snapshot!(stderr, @"Error: --at-op is not respected");
+
+    // Returns once another process finishes an operation. Since the waiting
+    // process may start after the first operation, keep making operations
+    // until it returns.
+    let mut wait_cmd = test_env.jj_cmd(&repo_path, &["op", "wait", "--timeout", "60"]);
+    let waiter = std::thread::spawn(move || wait_cmd.assert().success());
+    while !waiter.is_finished() {
+        test_env.jj_cmd_ok(&repo_path, &["new", "-m", "concurrent"]);
+        std::thread::sleep(std::time::Duration::from_millis(100));
+    }
+    let stdout = get_stdout_string(&waiter.join().unwrap());
+    assert!(stdout.contains("new empty commit"), "{stdout}");
+}
+
 fn init_bare_git_repo(git_repo_path: &Path) -> git2::Repository {
     let git_repo = git2::Repository::init_bare(git_repo_path).unwrap();
     let git_blob_oid = git_repo.blob(b"some content").unwrap();
@@ -0,0 +1,88 @@
+// Copyright 2024 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::path::Path;
+
+use crate::common::TestEnvironment;
+
+fn create_commit(test_env: &TestEnvironment, repo_path: &Path, name: &str, content: &str) {
+    std::fs::write(repo_path.join(name), content).unwrap();
+    test_env.jj_cmd_ok(repo_path, &["commit", "-m", name]);
+}
+
+#[test]
+fn test_range_diff() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    create_commit(&test_env, &repo_path, "a", "a\n");
+    create_commit(&test_env, &repo_path, "b", "b\n");
+    create_commit(&test_env, &repo_path, "c", "c\n");
+    let old_ids = test_env.jj_cmd_success(
+        &repo_path,
+        &[
+            "log",
+            "--no-graph",
+            "-r",
+            "root()..@-",
+            "-T",
+            r#"commit_id ++ "|""#,
+        ],
+    );
+    let old_revset = format!("{old_ids}none()");
+
+    // Replace "a" with a copy that has a different change id
+    test_env.jj_cmd_ok(&repo_path, &["duplicate", "description(a)"]);
+    let a_copy = "description(a) ~ ::@";
+    test_env.jj_cmd_ok(
+        &repo_path,
+        &["rebase", "-s", "description(b)", "-d", a_copy],
+    );
+    test_env.jj_cmd_ok(&repo_path, &["abandon", "description(a) ~ ::@"]);
+    // Modify "b", drop "c", and add "d"
+    test_env.jj_cmd_ok(&repo_path, &["edit", "description(b)"]);
+    std::fs::write(repo_path.join("b"), "b\nb2\n").unwrap();
+    test_env.jj_cmd_ok(&repo_path, &["abandon", "description(c)"]);
+    test_env.jj_cmd_ok(&repo_path, &["new", "description(b)"]);
+    create_commit(&test_env, &repo_path, "d", "d\n");
+
+    let stdout = test_env.jj_cmd_success(&repo_path, &["range-diff", &old_revset, "root()..@-"]);
+    insta::assert_snapshot!(stdout, @r"
+    Unchanged: qpvuntsm hidden af42592f a
+            -> royxmykx d244568d a
+    Changed:   rlvkpnrz hidden 44cc221a b
+            -> rlvkpnrz a2aa6db0 b
+    Modified regular file b:
+       1    1: b
+            2: b2
+    Added:     kpqxywon bbc1a3c0 d
+    Removed:   kkmpptxz hidden 4f7d8e73 c
+    ");
+
+    // Summary format
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &["range-diff", &old_revset, "root()..@-", "--summary"],
+    );
+    insta::assert_snapshot!(stdout, @r"
+    Unchanged: qpvuntsm hidden af42592f a
+            -> royxmykx d244568d a
+    Changed:   rlvkpnrz hidden 44cc221a b
+            -> rlvkpnrz a2aa6db0 b
+    M b
+    Added:     kpqxywon bbc1a3c0 d
+    Removed:   kkmpptxz hidden 4f7d8e73 c
+    ");
+}
@@ -2806,6 +2806,217 @@ fn test_rebase_skip_if_on_destination() {
     "###);
 }
 
+#[test]
+fn test_rebase_keep_divergent() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    create_commit(&test_env, &repo_path, "a", &[]);
+    create_commit(&test_env, &repo_path, "b", &["a"]);
+    create_commit(&test_env, &repo_path, "c", &["b"]);
+    create_commit(&test_env, &repo_path, "d", &[]);
+    test_env.add_config(
+        r#"
+        revset-aliases."immutable_heads()" = "b"
+        revsets.log = "all()"
+        "#,
+    );
+    // Test the setup
+    insta::assert_snapshot!(get_long_log_output(&test_env, &repo_path), @r"
+    @  d  vruxwmqv  a3663003
+    │ ○  c  royxmykx  7e4fbf4f:  b
+    │ ◆  b  zsuskuln  1394f625:  a
+    │ ◆  a  rlvkpnrz  2443ea76
+    ├─╯
+    ◆    zzzzzzzz  00000000
+    ");
+
+    // Immutable commits can't be rebased without --keep-divergent
+    let stderr = test_env.jj_cmd_failure(&repo_path, &["rebase", "-r", "b", "-d", "d"]);
+    insta::assert_snapshot!(stderr, @r"
+    Error: Commit 1394f625cbbd is immutable
+    Hint: Could not modify commit: zsuskuln 1394f625 b | b
+    Hint: Pass `--ignore-immutable` or configure the set of immutable commits via `revset-aliases.immutable_heads()`.
+    ");
+
+    // Only supported with -r
+    let stderr = test_env.jj_cmd_cli_error(
+        &repo_path,
+        &["rebase", "-s", "b", "-d", "d", "--keep-divergent"],
+    );
+    insta::assert_snapshot!(stderr, @r"
+    error: the argument '--source <REVSETS>' cannot be used with '--keep-divergent'
+
+    Usage: jj rebase --source <REVSETS> <--destination <REVSETS>|--insert-after <REVSETS>|--insert-before <REVSETS>>
+
+    For more information, try '--help'.
+    ");
+
+    // Immutable commits are copied, mutable commits are rebased
+    let (stdout, stderr) = test_env.jj_cmd_ok(
+        &repo_path,
+        &["rebase", "-r", "a::c", "-d", "d", "--keep-divergent"],
+    );
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r"
+    Copied 2 immutable commits onto destination
+    Rebased 1 commits onto destination
+    ");
+    insta::assert_snapshot!(get_long_log_output(&test_env, &repo_path), @r"
+    ○  c  royxmykx  f24db309:  d
+    │ ○    zsuskuln  113952a9
+    │ ○    rlvkpnrz  b60bb696:  d
+    ├─╯
+    @  d  vruxwmqv  a3663003
+    │ ◆  b  zsuskuln  1394f625:  a
+    │ ◆  a  rlvkpnrz  2443ea76
+    ├─╯
+    ◆    zzzzzzzz  00000000
+    ");
+
+    // A single immutable commit
+    test_env.jj_cmd_ok(&repo_path, &["undo"]);
+    let (stdout, stderr) = test_env.jj_cmd_ok(
+        &repo_path,
+        &["rebase", "-r", "b", "-d", "d", "--keep-divergent"],
+    );
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @"Copied 1 immutable commit onto destination");
+
+    // The root commit still can't be rebased
+    let stderr = test_env.jj_cmd_failure(
+        &repo_path,
+        &["rebase", "-r", "root()", "-d", "d", "--keep-divergent"],
+    );
+    insta::assert_snapshot!(stderr, @"Error: The root commit 000000000000 is immutable");
+}
+
+#[test]
+fn test_rebase_reuse_existing() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    create_commit(&test_env, &repo_path, "a", &[]);
+    create_commit(&test_env, &repo_path, "b", &["a"]);
+    create_commit(&test_env, &repo_path, "c", &["a"]);
+    let old_b_commit_id =
+        test_env.jj_cmd_success(&repo_path, &["log", "--no-graph", "-r=b", "-T=commit_id"]);
+
+    // Rebase b onto c, and make the old b visible again
+    test_env.jj_cmd_ok(&repo_path, &["rebase", "-r", "b", "-d", "c"]);
+    test_env.jj_cmd_ok(&repo_path, &["edit", &old_b_commit_id]);
+    insta::assert_snapshot!(get_long_log_output(&test_env, &repo_path), @r"
+    @    zsuskuln  1394f625:  a
+    │ ○  b  zsuskuln  0d5059c6:  c
+    │ ○  c  royxmykx  c0cb3a0b:  a
+    ├─╯
+    ○  a  rlvkpnrz  2443ea76
+    ◆    zzzzzzzz  00000000
+    ");
+
+    // Rebasing the old b onto c again creates another divergent commit
+    let (stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["rebase", "-r", "@", "-d", "c"]);
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r"
+    Rebased 1 commits onto destination
+    Working copy now at: zsuskuln?? e1fd6a40 b
+    Parent commit      : royxmykx c0cb3a0b c | c
+    Added 1 files, modified 0 files, removed 0 files
+    ");
+    insta::assert_snapshot!(get_long_log_output(&test_env, &repo_path), @r"
+    @    zsuskuln  e1fd6a40:  c
+    │ ○  b  zsuskuln  0d5059c6:  c
+    ├─╯
+    ○  c  royxmykx  c0cb3a0b:  a
+    ○  a  rlvkpnrz  2443ea76
+    ◆    zzzzzzzz  00000000
+    ");
+    test_env.jj_cmd_ok(&repo_path, &["undo"]);
+
+    // With --reuse-existing, the existing rewrite is reused
+    let (stdout, stderr) = test_env.jj_cmd_ok(
+        &repo_path,
+        &["rebase", "-r", "@", "-d", "c", "--reuse-existing"],
+    );
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r"
+    Rebased 1 commits onto destination
+    Working copy now at: zsuskuln 0d5059c6 b | b
+    Parent commit      : royxmykx c0cb3a0b c | c
+    Added 1 files, modified 0 files, removed 0 files
+    ");
+    insta::assert_snapshot!(get_long_log_output(&test_env, &repo_path), @r"
+    @  b  zsuskuln  0d5059c6:  c
+    ○  c  royxmykx  c0cb3a0b:  a
+    ○  a  rlvkpnrz  2443ea76
+    ◆    zzzzzzzz  00000000
+    ");
+}
+
+#[test]
+fn test_rebase_paths() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    create_commit(&test_env, &repo_path, "a", &[]);
+    create_commit(&test_env, &repo_path, "b", &["a"]);
+    std::fs::write(repo_path.join("a"), "modified by b\n").unwrap();
+    create_commit(&test_env, &repo_path, "c", &["b"]);
+    create_commit(&test_env, &repo_path, "d", &["a"]);
+    // Test the setup
+    insta::assert_snapshot!(get_long_log_output(&test_env, &repo_path), @r"
+    @  d  vruxwmqv  4cc44fbf:  a
+    │ ○  c  royxmykx  84d3eef1:  b
+    │ ○  b  zsuskuln  3b31b9a1:  a
+    ├─╯
+    ○  a  rlvkpnrz  2443ea76
+    ◆    zzzzzzzz  00000000
+    ");
+
+    // Requires a single revision
+    let stderr = test_env.jj_cmd_failure(&repo_path, &["rebase", "-r=b|c", "-d=d", "--paths=a"]);
+    insta::assert_snapshot!(stderr, @"Error: --paths can only be used with a single revision");
+    let stderr = test_env.jj_cmd_cli_error(&repo_path, &["rebase", "-s=b", "-d=d", "--paths=a"]);
+    insta::assert_snapshot!(stderr, @r"
+    error: the argument '--source <REVSETS>' cannot be used with '--paths <FILESETS>'
+
+    Usage: jj rebase --source <REVSETS> <--destination <REVSETS>|--insert-after <REVSETS>|--insert-before <REVSETS>>
+
+    For more information, try '--help'.
+    ");
+
+    // No changes to the paths
+    let stderr = test_env.jj_cmd_failure(
+        &repo_path,
+        &["rebase", "-r=b", "-d=d", "--paths=nonexistent"],
+    );
+    insta::assert_snapshot!(stderr, @"Error: Revision 3b31b9a1481e has no changes to the given paths");
+
+    // Move the changes to "a" onto d, leaving the changes to "b" in place
+    let (stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["rebase", "-r=b", "-d=d", "--paths=a"]);
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r"
+    Rebased 1 descendant commits
+    Moved changes onto destination as: lylxulpl 8061f8f8 b
+    ");
+    insta::assert_snapshot!(get_long_log_output(&test_env, &repo_path), @r"
+    ○    lylxulpl  8061f8f8:  d
+    @  d  vruxwmqv  4cc44fbf:  a
+    │ ○  c  royxmykx  3ecb5e10:  b
+    │ ○  b  zsuskuln  b0d07159:  a
+    ├─╯
+    ○  a  rlvkpnrz  2443ea76
+    ◆    zzzzzzzz  00000000
+    ");
+    let stdout = test_env.jj_cmd_success(&repo_path, &["diff", "--summary", "-r=b"]);
+    insta::assert_snapshot!(stdout, @"A b");
+    let stdout = test_env.jj_cmd_success(&repo_path, &["diff", "--summary", "-r=d+"]);
+    insta::assert_snapshot!(stdout, @"M a");
+}
+
 fn get_log_output(test_env: &TestEnvironment, repo_path: &Path) -> String {
     let template = "bookmarks ++ surround(': ', '', parents.map(|c| c.bookmarks()))";
     test_env.jj_cmd_success(repo_path, &["log", "-T", template])
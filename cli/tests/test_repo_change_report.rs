@@ -30,13 +30,15 @@ fn test_report_conflicts() {
     let (stdout, stderr) =
         test_env.jj_cmd_ok(&repo_path, &["rebase", "-s=description(B)", "-d=root()"]);
     insta::assert_snapshot!(stdout, @"");
-    insta::assert_snapshot!(stderr, @r###"
+    insta::assert_snapshot!(stderr, @r"
     Rebased 3 commits onto destination
     Working copy now at: zsuskuln f8a2c4e0 (conflict) (empty) (no description set)
     Parent commit      : kkmpptxz 2271a49e (conflict) C
     Added 0 files, modified 1 files, removed 0 files
     There are unresolved conflicts at these paths:
     file    2-sided conflict including 1 deletion
+      side #1: zzzzzzzz 00000000 (empty) (no description set)
+      side #2: zsuskuln hidden b0288c0b (empty) (no description set)
     New conflicts appeared in these commits:
       kkmpptxz 2271a49e (conflict) C
       rlvkpnrz b7d83633 (conflict) B
@@ -45,7 +47,7 @@ fn test_report_conflicts() {
     Then use `jj resolve`, or edit the conflict markers in the file directly.
     Once the conflicts are resolved, you may want to inspect the result with `jj diff`.
     Then run `jj squash` to move the resolution into the conflicted commit.
-    "###);
+    ");
 
     let (stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["rebase", "-d=description(A)"]);
     insta::assert_snapshot!(stdout, @"");
@@ -63,7 +65,7 @@ fn test_report_conflicts() {
     let (stdout, stderr) =
         test_env.jj_cmd_ok(&repo_path, &["rebase", "-r=description(B)", "-d=root()"]);
     insta::assert_snapshot!(stdout, @"");
-    insta::assert_snapshot!(stderr, @r###"
+    insta::assert_snapshot!(stderr, @r"
     Rebased 1 commits onto destination
     Rebased 2 descendant commits
     Working copy now at: zsuskuln 588bd15c (conflict) (empty) (no description set)
@@ -71,6 +73,8 @@ fn test_report_conflicts() {
     Added 0 files, modified 1 files, removed 0 files
     There are unresolved conflicts at these paths:
     file    2-sided conflict
+      side #1: qpvuntsm 2d5ad737 A
+      side #2: zsuskuln hidden d70c003d (empty) (no description set)
     New conflicts appeared in these commits:
       kkmpptxz 331a2fce (conflict) C
       rlvkpnrz b42f84eb (conflict) B
@@ -80,18 +84,20 @@ fn test_report_conflicts() {
     Then use `jj resolve`, or edit the conflict markers in the file directly.
     Once the conflicts are resolved, you may want to inspect the result with `jj diff`.
     Then run `jj squash` to move the resolution into the conflicted commit.
-    "###);
+    ");
 
     // Resolve one of the conflicts by (mostly) following the instructions
     let (stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["new", "rlvkpnrzqnoo"]);
     insta::assert_snapshot!(stdout, @"");
-    insta::assert_snapshot!(stderr, @r###"
+    insta::assert_snapshot!(stderr, @r"
     Working copy now at: vruxwmqv 0485e30f (conflict) (empty) (no description set)
     Parent commit      : rlvkpnrz b42f84eb (conflict) B
     Added 0 files, modified 1 files, removed 0 files
     There are unresolved conflicts at these paths:
     file    2-sided conflict including 1 deletion
-    "###);
+      side #1: zzzzzzzz 00000000 (empty) (no description set)
+      side #2: rlvkpnrz hidden 5b49a582 B
+    ");
     std::fs::write(repo_path.join("file"), "resolved\n").unwrap();
     let (stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["squash"]);
     insta::assert_snapshot!(stdout, @"");
@@ -121,7 +127,7 @@ fn test_report_conflicts_with_divergent_commits() {
     let (stdout, stderr) =
         test_env.jj_cmd_ok(&repo_path, &["rebase", "-s=description(B)", "-d=root()"]);
     insta::assert_snapshot!(stdout, @"");
-    insta::assert_snapshot!(stderr, @r###"
+    insta::assert_snapshot!(stderr, @r"
     Concurrent modification detected, resolving automatically.
     Rebased 3 commits onto destination
     Working copy now at: zsuskuln?? 4ca807ad (conflict) C2
@@ -129,6 +135,8 @@ fn test_report_conflicts_with_divergent_commits() {
     Added 0 files, modified 1 files, removed 0 files
     There are unresolved conflicts at these paths:
     file    2-sided conflict including 1 deletion
+      side #1: zzzzzzzz 00000000 (empty) (no description set)
+      side #2: zsuskuln hidden 08337b9c C2
     New conflicts appeared in these commits:
       zsuskuln?? 1db43f23 (conflict) C3
       zsuskuln?? 4ca807ad (conflict) C2
@@ -138,7 +146,7 @@ fn test_report_conflicts_with_divergent_commits() {
     Then use `jj resolve`, or edit the conflict markers in the file directly.
     Once the conflicts are resolved, you may want to inspect the result with `jj diff`.
     Then run `jj squash` to move the resolution into the conflicted commit.
-    "###);
+    ");
 
     let (stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["rebase", "-d=description(A)"]);
     insta::assert_snapshot!(stdout, @"");
@@ -157,13 +165,15 @@ fn test_report_conflicts_with_divergent_commits() {
     let (stdout, stderr) =
         test_env.jj_cmd_ok(&repo_path, &["rebase", "-s=description(C2)", "-d=root()"]);
     insta::assert_snapshot!(stdout, @"");
-    insta::assert_snapshot!(stderr, @r###"
+    insta::assert_snapshot!(stderr, @r"
     Rebased 1 commits onto destination
     Working copy now at: zsuskuln?? 3c36afc9 (conflict) C2
     Parent commit      : zzzzzzzz 00000000 (empty) (no description set)
     Added 0 files, modified 1 files, removed 0 files
     There are unresolved conflicts at these paths:
     file    2-sided conflict including 1 deletion
+      side #1: zzzzzzzz 00000000 (empty) (no description set)
+      side #2: zsuskuln hidden f2d7a228 C2
     New conflicts appeared in these commits:
       zsuskuln?? 3c36afc9 (conflict) C2
     To resolve the conflicts, start by updating to it:
@@ -171,7 +181,7 @@ fn test_report_conflicts_with_divergent_commits() {
     Then use `jj resolve`, or edit the conflict markers in the file directly.
     Once the conflicts are resolved, you may want to inspect the result with `jj diff`.
     Then run `jj squash` to move the resolution into the conflicted commit.
-    "###);
+    ");
 
     let (stdout, stderr) =
         test_env.jj_cmd_ok(&repo_path, &["rebase", "-s=description(C3)", "-d=root()"]);
@@ -233,7 +233,7 @@ fn test_resolution() {
         ],
     );
     insta::assert_snapshot!(stdout, @"");
-    insta::assert_snapshot!(stderr, @r###"
+    insta::assert_snapshot!(stderr, @r"
     Resolving conflicts in: file
     Working copy now at: vruxwmqv 608a2310 conflict | (conflict) conflict
     Parent commit      : zsuskuln aa493daf a | a
@@ -241,6 +241,8 @@ fn test_resolution() {
     Added 0 files, modified 1 files, removed 0 files
     There are unresolved conflicts at these paths:
     file    2-sided conflict
+      side #1: (unknown)
+      side #2: (unknown)
     New conflicts appeared in these commits:
       vruxwmqv 608a2310 conflict | (conflict) conflict
     To resolve the conflicts, start by updating to it:
@@ -248,7 +250,7 @@ fn test_resolution() {
     Then use `jj resolve`, or edit the conflict markers in the file directly.
     Once the conflicts are resolved, you may want to inspect the result with `jj diff`.
     Then run `jj squash` to move the resolution into the conflicted commit.
-    "###);
+    ");
     insta::assert_snapshot!(
         std::fs::read_to_string(test_env.env_root().join("editor2")).unwrap(), @r###"
     <<<<<<< Conflict 1 of 1
@@ -378,7 +380,7 @@ fn test_resolution() {
         ],
     );
     insta::assert_snapshot!(stdout, @"");
-    insta::assert_snapshot!(stderr, @r###"
+    insta::assert_snapshot!(stderr, @r"
     Resolving conflicts in: file
     Working copy now at: vruxwmqv 8e03fefa conflict | (conflict) conflict
     Parent commit      : zsuskuln aa493daf a | a
@@ -386,6 +388,8 @@ fn test_resolution() {
     Added 0 files, modified 1 files, removed 0 files
     There are unresolved conflicts at these paths:
     file    2-sided conflict
+      side #1: (unknown)
+      side #2: (unknown)
     New conflicts appeared in these commits:
       vruxwmqv 8e03fefa conflict | (conflict) conflict
     To resolve the conflicts, start by updating to it:
@@ -393,7 +397,7 @@ fn test_resolution() {
     Then use `jj resolve`, or edit the conflict markers in the file directly.
     Once the conflicts are resolved, you may want to inspect the result with `jj diff`.
     Then run `jj squash` to move the resolution into the conflicted commit.
-    "###);
+    ");
     insta::assert_snapshot!(
         std::fs::read_to_string(test_env.env_root().join("editor4")).unwrap(), @r##"
     <<<<<<< Side #1 (Conflict 1 of 1)
@@ -459,7 +463,7 @@ fn test_resolution() {
         ],
     );
     insta::assert_snapshot!(stdout, @"");
-    insta::assert_snapshot!(stderr, @r###"
+    insta::assert_snapshot!(stderr, @r"
     Resolving conflicts in: file
     Working copy now at: vruxwmqv a786ac2f conflict | (conflict) conflict
     Parent commit      : zsuskuln aa493daf a | a
@@ -467,6 +471,8 @@ fn test_resolution() {
     Added 0 files, modified 1 files, removed 0 files
     There are unresolved conflicts at these paths:
     file    2-sided conflict
+      side #1: (unknown)
+      side #2: (unknown)
     New conflicts appeared in these commits:
       vruxwmqv a786ac2f conflict | (conflict) conflict
     To resolve the conflicts, start by updating to it:
@@ -474,7 +480,7 @@ fn test_resolution() {
     Then use `jj resolve`, or edit the conflict markers in the file directly.
     Once the conflicts are resolved, you may want to inspect the result with `jj diff`.
     Then run `jj squash` to move the resolution into the conflicted commit.
-    "###);
+    ");
     insta::assert_snapshot!(
         std::fs::read_to_string(test_env.env_root().join("editor5")).unwrap(), @"");
     insta::assert_snapshot!(test_env.jj_cmd_success(&repo_path, &["diff", "--git"]), 
@@ -567,6 +573,55 @@ fn check_resolve_produces_input_file(
     );
 }
 
+#[test]
+fn test_list_conflicts_json() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    create_commit(
+        &test_env,
+        &repo_path,
+        "base",
+        &[],
+        &[("file", "base\n"), ("binary", "base\0")],
+    );
+    create_commit(
+        &test_env,
+        &repo_path,
+        "a",
+        &["base"],
+        &[("file", "a\n"), ("binary", "a\0")],
+    );
+    // Like in diffs, only the start of a file is checked for null bytes
+    let late_null = format!("{}\0", "b\n".repeat(4000));
+    create_commit(
+        &test_env,
+        &repo_path,
+        "b",
+        &["base"],
+        &[("file", &late_null), ("binary", "b\0")],
+    );
+    create_commit(&test_env, &repo_path, "conflict", &["a", "b"], &[]);
+
+    let stdout = test_env.jj_cmd_success(&repo_path, &["resolve", "--list", "--json"]);
+    insta::assert_snapshot!(stdout, @r#"
+    {"path":"binary","sides":2,"commit_id":"fd8cd6ad2a20d088c9cc00fbc15a0082eb9aaf0e","parent_commit_ids":["803af62a23bac470a028a2fe98213c8046ca6676","fee183c470eb6e2a50997868cda005b1630454d9"],"removes":[{"type":"file","id":"d249428de78af3bdc1ab483fa486615df535e3c9","executable":false}],"adds":[{"type":"file","id":"90802fedc2462f10bf2114d086cacb9e3af99ffb","executable":false},{"type":"file","id":"28eacf2b08bcaecd186cfa2d9d93e8672a37d6a2","executable":false}],"side_commit_ids":["803af62a23bac470a028a2fe98213c8046ca6676","fee183c470eb6e2a50997868cda005b1630454d9"],"binary":true,"executable":false,"symlink":false}
+    {"path":"file","sides":2,"commit_id":"fd8cd6ad2a20d088c9cc00fbc15a0082eb9aaf0e","parent_commit_ids":["803af62a23bac470a028a2fe98213c8046ca6676","fee183c470eb6e2a50997868cda005b1630454d9"],"removes":[{"type":"file","id":"df967b96a579e45a18b8251732d16804b2e56a55","executable":false}],"adds":[{"type":"file","id":"78981922613b2afb6025042ff6bd878ac1994e85","executable":false},{"type":"file","id":"07c0d4cdefffa608c32da85e4ab716d9f3545fcb","executable":false}],"side_commit_ids":["803af62a23bac470a028a2fe98213c8046ca6676","fee183c470eb6e2a50997868cda005b1630454d9"],"binary":false,"executable":false,"symlink":false}
+    "#);
+
+    // --json requires --list
+    let stderr = test_env.jj_cmd_cli_error(&repo_path, &["resolve", "--json"]);
+    insta::assert_snapshot!(stderr, @r"
+    error: the following required arguments were not provided:
+      --list
+
+    Usage: jj resolve --list --json [FILESETS]...
+
+    For more information, try '--help'.
+    ");
+}
+
 #[test]
 fn test_normal_conflict_input_files() {
     let mut test_env = TestEnvironment::default();
@@ -650,7 +705,7 @@ fn test_baseless_conflict_input_files() {
 
 #[test]
 fn test_too_many_parents() {
-    let test_env = TestEnvironment::default();
+    let mut test_env = TestEnvironment::default();
     test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
     let repo_path = test_env.env_root().join("repo");
 
@@ -669,13 +724,147 @@ fn test_too_many_parents() {
     file    [38;5;1m3-sided[38;5;3m conflict[39m
     "###);
 
-    let error = test_env.jj_cmd_failure(&repo_path, &["resolve"]);
-    insta::assert_snapshot!(error, @r###"
-    Hint: Using default editor ':builtin'; run `jj config set --user ui.merge-editor :builtin` to disable this message.
+    // The sides are merged one at a time
+    let editor_script = test_env.set_up_fake_editor();
+    std::fs::write(
+        &editor_script,
+        [
+            "dump editor0",
+            "write\nab\n",
+            "next invocation\n",
+            "dump editor1",
+            "write\nabc\n",
+        ]
+        .join("\0"),
+    )
+    .unwrap();
+    let (stdout, stderr) = test_env.jj_cmd_ok(
+        &repo_path,
+        &[
+            "resolve",
+            "--config=merge-tools.fake-editor.merge-tool-edits-conflict-markers=true",
+        ],
+    );
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r"
     Resolving conflicts in: file
-    Error: Failed to resolve conflicts
-    Caused by: The conflict at "file" has 3 sides. At most 2 sides are supported.
-    "###);
+    Working copy now at: znkkpsqq c79466e5 conflict | conflict
+    Parent commit      : zsuskuln aa493daf a | a
+    Parent commit      : royxmykx db6a4daf b | b
+    Parent commit      : vruxwmqv a425542c c | c
+    Added 0 files, modified 1 files, removed 0 files
+    ");
+    insta::assert_snapshot!(
+        std::fs::read_to_string(test_env.env_root().join("editor0")).unwrap(), @r"
+    <<<<<<< Conflict 1 of 1
+    %%%%%%% Changes from base to side #1
+    -base
+    +a
+    +++++++ Contents of side #2
+    b
+    >>>>>>> Conflict 1 of 1 ends
+    ");
+    insta::assert_snapshot!(
+        std::fs::read_to_string(test_env.env_root().join("editor1")).unwrap(), @r"
+    <<<<<<< Conflict 1 of 1
+    +++++++ Contents of side #1
+    ab
+    %%%%%%% Changes from base to side #2
+    -base
+    +c
+    >>>>>>> Conflict 1 of 1 ends
+    ");
+    insta::assert_snapshot!(std::fs::read_to_string(repo_path.join("file")).unwrap(), @"abc");
+
+    // If a step is left unresolved, the remaining sides are kept
+    test_env.jj_cmd_ok(&repo_path, &["undo"]);
+    std::fs::write(
+        &editor_script,
+        ["write\n<<<<<<<\n%%%%%%%\n-base\n+a\n+++++++\nb2\n>>>>>>>\n"].join("\0"),
+    )
+    .unwrap();
+    let (stdout, stderr) = test_env.jj_cmd_ok(
+        &repo_path,
+        &[
+            "resolve",
+            "--config=merge-tools.fake-editor.merge-tool-edits-conflict-markers=true",
+        ],
+    );
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r"
+    Resolving conflicts in: file
+    Working copy now at: znkkpsqq 23a8923a conflict | (conflict) conflict
+    Parent commit      : zsuskuln aa493daf a | a
+    Parent commit      : royxmykx db6a4daf b | b
+    Parent commit      : vruxwmqv a425542c c | c
+    Added 0 files, modified 1 files, removed 0 files
+    There are unresolved conflicts at these paths:
+    file    3-sided conflict
+      side #1: zsuskuln aa493daf a | a
+      side #2: (unknown)
+      side #3: vruxwmqv a425542c c | c
+    New conflicts appeared in these commits:
+      znkkpsqq 23a8923a conflict | (conflict) conflict
+    To resolve the conflicts, start by updating to it:
+      jj new znkkpsqq
+    Then use `jj resolve`, or edit the conflict markers in the file directly.
+    Once the conflicts are resolved, you may want to inspect the result with `jj diff`.
+    Then run `jj squash` to move the resolution into the conflicted commit.
+    ");
+    insta::assert_snapshot!(std::fs::read_to_string(repo_path.join("file")).unwrap(), @r"
+    <<<<<<< Conflict 1 of 1
+    %%%%%%% Changes from base #1 to side #1
+    -base
+    +a
+    +++++++ Contents of side #2
+    b2
+    %%%%%%% Changes from base #2 to side #3
+    -base
+    +c
+    >>>>>>> Conflict 1 of 1 ends
+    ");
+
+    // The built-in resolutions pick among all sides
+    test_env.jj_cmd_ok(&repo_path, &["undo"]);
+    test_env.jj_cmd_ok(&repo_path, &["resolve", "--tool=:theirs"]);
+    insta::assert_snapshot!(std::fs::read_to_string(repo_path.join("file")).unwrap(), @"c");
+    test_env.jj_cmd_ok(&repo_path, &["undo"]);
+    test_env.jj_cmd_ok(&repo_path, &["resolve", "--tool=:union"]);
+    insta::assert_snapshot!(std::fs::read_to_string(repo_path.join("file")).unwrap(), @r"
+    a
+    b
+    c
+    ");
+}
+
+#[test]
+fn test_list_origins() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    create_commit(&test_env, &repo_path, "base", &[], &[("file", "base\n")]);
+    create_commit(&test_env, &repo_path, "a", &["base"], &[("file", "a\n")]);
+    create_commit(&test_env, &repo_path, "b", &["base"], &[("file", "b\n")]);
+    create_commit(&test_env, &repo_path, "c", &["base"], &[("file", "c\n")]);
+    create_commit(&test_env, &repo_path, "conflict", &["a", "b", "c"], &[]);
+    let stdout = test_env.jj_cmd_success(&repo_path, &["resolve", "--list", "--origins"]);
+    insta::assert_snapshot!(stdout, @r"
+    file    3-sided conflict
+      side #1: zsuskuln aa493daf a | a
+      side #2: royxmykx db6a4daf b | b
+      side #3: vruxwmqv a425542c c | c
+    ");
+
+    // Sides introduced by a rebase are found through the predecessors
+    test_env.jj_cmd_ok(&repo_path, &["rebase", "-r", "c", "-d", "a"]);
+    let stdout =
+        test_env.jj_cmd_success(&repo_path, &["resolve", "--list", "--origins", "-r", "c"]);
+    insta::assert_snapshot!(stdout, @r"
+    file    2-sided conflict
+      side #1: zsuskuln aa493daf a | a
+      side #2: vruxwmqv hidden a425542c c
+    ");
 }
 
 #[test]
@@ -775,7 +964,7 @@ fn test_simplify_conflict_sides() {
         ],
     );
     insta::assert_snapshot!(stdout, @"");
-    insta::assert_snapshot!(stderr, @r###"
+    insta::assert_snapshot!(stderr, @r"
     Resolving conflicts in: fileB
     Working copy now at: nkmrtpmo 69cc0c2d conflict | (conflict) conflict
     Parent commit      : kmkuslsw 4601566f conflictA | (conflict) (empty) conflictA
@@ -783,7 +972,11 @@ fn test_simplify_conflict_sides() {
     Added 0 files, modified 1 files, removed 0 files
     There are unresolved conflicts at these paths:
     fileA    2-sided conflict
+      side #1: zsuskuln fe2988e9 a1 | a1
+      side #2: royxmykx 9ec1d835 a2 | a2
     fileB    2-sided conflict
+      side #1: (unknown)
+      side #2: (unknown)
     New conflicts appeared in these commits:
       nkmrtpmo 69cc0c2d conflict | (conflict) conflict
     To resolve the conflicts, start by updating to it:
@@ -791,7 +984,7 @@ fn test_simplify_conflict_sides() {
     Then use `jj resolve`, or edit the conflict markers in the file directly.
     Once the conflicts are resolved, you may want to inspect the result with `jj diff`.
     Then run `jj squash` to move the resolution into the conflicted commit.
-    "###);
+    ");
     insta::assert_snapshot!(std::fs::read_to_string(repo_path.join("fileB")).unwrap(), @r###"
     <<<<<<< Conflict 1 of 1
     %%%%%%% Changes from base to side #1
@@ -1045,7 +1238,7 @@ fn test_multiple_conflicts() {
     std::fs::write(&editor_script, "expect\n\0write\nresolution another_file\n").unwrap();
     let (stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["resolve", "another_file"]);
     insta::assert_snapshot!(stdout, @"");
-    insta::assert_snapshot!(stderr, @r###"
+    insta::assert_snapshot!(stderr, @r"
     Resolving conflicts in: another_file
     Working copy now at: vruxwmqv 309e981c conflict | (conflict) conflict
     Parent commit      : zsuskuln de7553ef a | a
@@ -1053,6 +1246,8 @@ fn test_multiple_conflicts() {
     Added 0 files, modified 1 files, removed 0 files
     There are unresolved conflicts at these paths:
     this_file_has_a_very_long_name_to_test_padding 2-sided conflict
+      side #1: zsuskuln de7553ef a | a
+      side #2: royxmykx f68bc2f0 b | b
     New conflicts appeared in these commits:
       vruxwmqv 309e981c conflict | (conflict) conflict
     To resolve the conflicts, start by updating to it:
@@ -1060,7 +1255,7 @@ fn test_multiple_conflicts() {
     Then use `jj resolve`, or edit the conflict markers in the file directly.
     Once the conflicts are resolved, you may want to inspect the result with `jj diff`.
     Then run `jj squash` to move the resolution into the conflicted commit.
-    "###);
+    ");
     insta::assert_snapshot!(test_env.jj_cmd_success(&repo_path, &["diff", "--git"]), 
     @r###"
     diff --git a/another_file b/another_file
@@ -1166,3 +1361,270 @@ fn test_multiple_conflicts() {
     Error: No conflicts found at this revision
     "###);
 }
+
+#[test]
+fn test_resolve_prematerializes_next_conflict() {
+    let mut test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    create_commit(
+        &test_env,
+        &repo_path,
+        "base",
+        &[],
+        &[("file1", "base1\n"), ("file2", "base2\n")],
+    );
+    create_commit(
+        &test_env,
+        &repo_path,
+        "a",
+        &["base"],
+        &[("file1", "a1\n"), ("file2", "a2\n")],
+    );
+    create_commit(
+        &test_env,
+        &repo_path,
+        "b",
+        &["base"],
+        &[("file1", "b1\n"), ("file2", "b2\n")],
+    );
+    create_commit(&test_env, &repo_path, "conflict", &["a", "b"], &[]);
+
+    let cache_dir = repo_path.join(".jj").join("repo").join("conflict_cache");
+    let cache_entries = || -> Vec<_> {
+        std::fs::read_dir(&cache_dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .collect()
+    };
+
+    // Resolving the first conflict also caches the next one
+    let editor_script = test_env.set_up_fake_editor();
+    std::fs::write(&editor_script, "write\nresolution1\n").unwrap();
+    let edits_markers_arg =
+        "--config=merge-tools.fake-editor.merge-tool-edits-conflict-markers=true";
+    test_env.jj_cmd_ok(&repo_path, &["resolve", edits_markers_arg]);
+    assert_eq!(cache_entries().len(), 2);
+
+    // The cached content is passed to the tool
+    for path in cache_entries() {
+        std::fs::write(path, "cached\n").unwrap();
+    }
+    std::fs::write(
+        &editor_script,
+        ["expect\ncached\n", "write\nresolution2\n"].join("\0"),
+    )
+    .unwrap();
+    test_env.jj_cmd_ok(&repo_path, &["resolve", edits_markers_arg]);
+    insta::assert_snapshot!(test_env.jj_cmd_cli_error(&repo_path, &["resolve", "--list"]),
+    @r###"
+    Error: No conflicts found at this revision
+    "###);
+}
+
+#[test]
+fn test_resolve_automatic_tools() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    create_commit(
+        &test_env,
+        &repo_path,
+        "base",
+        &[],
+        &[("file1", "line 1\nbase\nline 3\n"), ("file2", "base\n")],
+    );
+    create_commit(
+        &test_env,
+        &repo_path,
+        "a",
+        &["base"],
+        &[("file1", "line 1\na\nline 3\n"), ("file2", "a\n")],
+    );
+    create_commit(
+        &test_env,
+        &repo_path,
+        "b",
+        &["base"],
+        &[("file1", "line 1\nb\nline 3\n"), ("file2", "b\n")],
+    );
+    create_commit(&test_env, &repo_path, "conflict", &["a", "b"], &[]);
+
+    // All conflicts are resolved at once
+    let (stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["resolve", "--tool", ":ours"]);
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r"
+    Resolving conflicts in: file1
+    Resolving conflicts in: file2
+    Working copy now at: vruxwmqv a97ae403 conflict | conflict
+    Parent commit      : zsuskuln 14d509d5 a | a
+    Parent commit      : royxmykx 5e948615 b | b
+    Added 0 files, modified 2 files, removed 0 files
+    ");
+    insta::assert_snapshot!(std::fs::read_to_string(repo_path.join("file1")).unwrap(), @r"
+    line 1
+    a
+    line 3
+    ");
+    insta::assert_snapshot!(std::fs::read_to_string(repo_path.join("file2")).unwrap(), @"a");
+
+    // Only matched conflicts are resolved
+    test_env.jj_cmd_ok(&repo_path, &["undo"]);
+    let (stdout, stderr) =
+        test_env.jj_cmd_ok(&repo_path, &["resolve", "--tool", ":theirs", "file2"]);
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r"
+    Resolving conflicts in: file2
+    Working copy now at: vruxwmqv 40ffaa30 conflict | (conflict) conflict
+    Parent commit      : zsuskuln 14d509d5 a | a
+    Parent commit      : royxmykx 5e948615 b | b
+    Added 0 files, modified 1 files, removed 0 files
+    There are unresolved conflicts at these paths:
+    file1    2-sided conflict
+      side #1: zsuskuln 14d509d5 a | a
+      side #2: royxmykx 5e948615 b | b
+    New conflicts appeared in these commits:
+      vruxwmqv 40ffaa30 conflict | (conflict) conflict
+    To resolve the conflicts, start by updating to it:
+      jj new vruxwmqv
+    Then use `jj resolve`, or edit the conflict markers in the file directly.
+    Once the conflicts are resolved, you may want to inspect the result with `jj diff`.
+    Then run `jj squash` to move the resolution into the conflicted commit.
+    ");
+    insta::assert_snapshot!(std::fs::read_to_string(repo_path.join("file2")).unwrap(), @"b");
+
+    let (stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["resolve", "--tool", ":union"]);
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r"
+    Resolving conflicts in: file1
+    Working copy now at: vruxwmqv 6d2389f4 conflict | conflict
+    Parent commit      : zsuskuln 14d509d5 a | a
+    Parent commit      : royxmykx 5e948615 b | b
+    Added 0 files, modified 1 files, removed 0 files
+    Existing conflicts were resolved or abandoned from these commits:
+      vruxwmqv hidden 40ffaa30 (conflict) conflict
+    ");
+    insta::assert_snapshot!(std::fs::read_to_string(repo_path.join("file1")).unwrap(), @r"
+    line 1
+    a
+    b
+    line 3
+    ");
+
+    // Automatic tools can't edit diffs
+    let stderr = test_env.jj_cmd_failure(&repo_path, &["diffedit", "--tool", ":ours"]);
+    insta::assert_snapshot!(stderr, @"Error: The tool `:ours` cannot be used as a diff editor");
+}
+
+#[test]
+fn test_resolve_automatic_tools_non_conflicting_hunks() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    create_commit(
+        &test_env,
+        &repo_path,
+        "base",
+        &[],
+        &[("file", "top\nline 2\nmiddle\nline 4\nbottom\n")],
+    );
+    create_commit(
+        &test_env,
+        &repo_path,
+        "a",
+        &["base"],
+        &[("file", "top a\nline 2\nmiddle a\nline 4\nbottom\n")],
+    );
+    create_commit(
+        &test_env,
+        &repo_path,
+        "b",
+        &["base"],
+        &[("file", "top\nline 2\nmiddle b\nline 4\nbottom b\n")],
+    );
+    create_commit(&test_env, &repo_path, "conflict", &["a", "b"], &[]);
+
+    // Changes which don't conflict are kept from both sides
+    test_env.jj_cmd_ok(&repo_path, &["resolve", "--tool", ":ours"]);
+    insta::assert_snapshot!(std::fs::read_to_string(repo_path.join("file")).unwrap(), @r"
+    top a
+    line 2
+    middle a
+    line 4
+    bottom b
+    ");
+
+    test_env.jj_cmd_ok(&repo_path, &["undo"]);
+    test_env.jj_cmd_ok(&repo_path, &["resolve", "--tool", ":theirs"]);
+    insta::assert_snapshot!(std::fs::read_to_string(repo_path.join("file")).unwrap(), @r"
+    top a
+    line 2
+    middle b
+    line 4
+    bottom b
+    ");
+}
+
+#[test]
+fn test_remember_resolution() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    create_commit(&test_env, &repo_path, "base", &[], &[("file", "base\n")]);
+    create_commit(&test_env, &repo_path, "a", &["base"], &[("file", "a\n")]);
+    create_commit(&test_env, &repo_path, "b", &["base"], &[("file", "b\n")]);
+    create_commit(&test_env, &repo_path, "conflict", &["a", "b"], &[]);
+    std::fs::write(repo_path.join("file"), "resolution\n").unwrap();
+
+    // Record the resolution made in the working copy
+    let (stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["resolve", "--remember"]);
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r###"
+    Recorded conflict resolutions in:
+      file
+    "###);
+    insta::assert_snapshot!(
+        test_env.jj_cmd_failure(&repo_path, &["resolve", "--remember", "-r", "a"]), @r###"
+    Error: No resolved conflicts found at this revision
+    "###);
+
+    // The same conflict is resolved automatically
+    let (_stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["new", "a", "b"]);
+    insta::assert_snapshot!(stderr, @r"
+    Reused recorded conflict resolutions in 1 commits
+    Working copy now at: kmkuslsw 80cb67ea (no description set)
+    Parent commit      : zsuskuln aa493daf a | a
+    Parent commit      : royxmykx db6a4daf b | b
+    ");
+    insta::assert_snapshot!(
+        test_env.jj_cmd_success(&repo_path, &["file", "show", "file"]), @r###"
+    resolution
+    "###);
+
+    // Recording another resolution replaces the old one
+    test_env.jj_cmd_ok(&repo_path, &["edit", "conflict"]);
+    std::fs::write(repo_path.join("file"), "new resolution\n").unwrap();
+    test_env.jj_cmd_ok(&repo_path, &["resolve", "--remember"]);
+    test_env.jj_cmd_ok(&repo_path, &["new", "a", "b"]);
+    insta::assert_snapshot!(
+        test_env.jj_cmd_success(&repo_path, &["file", "show", "file"]), @r###"
+    new resolution
+    "###);
+
+    // Forgotten resolutions are no longer applied
+    let (stdout, stderr) =
+        test_env.jj_cmd_ok(&repo_path, &["resolve", "--forget", "-r", "conflict"]);
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r###"
+    Forgot 1 recorded conflict resolutions
+    "###);
+    test_env.jj_cmd_ok(&repo_path, &["new", "a", "b"]);
+    insta::assert_snapshot!(test_env.jj_cmd_success(&repo_path, &["resolve", "--list"]),
+    @r###"
+    file    2-sided conflict
+    "###);
+}
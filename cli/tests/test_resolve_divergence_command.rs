@@ -0,0 +1,180 @@
+// Copyright 2024 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::path::Path;
+
+use crate::common::TestEnvironment;
+
+fn create_divergence(test_env: &TestEnvironment, repo_path: &Path) {
+    test_env.jj_cmd_ok(repo_path, &["new", "root()", "-m", "base"]);
+    test_env.jj_cmd_ok(repo_path, &["new"]);
+    std::fs::write(repo_path.join("file1"), "a\n").unwrap();
+    test_env.jj_cmd_ok(repo_path, &["describe", "-m", "one"]);
+    // The other commit has the same parent, but no changes
+    test_env.jj_cmd_ok(repo_path, &["describe", "-m", "two", "--at-op", "@--"]);
+    // Merge the concurrent operations
+    test_env.jj_cmd_ok(repo_path, &["status"]);
+}
+
+fn get_log_output(test_env: &TestEnvironment, repo_path: &Path) -> String {
+    let template = r#"separate(" ", change_id.short(), commit_id.short(), description.first_line(), bookmarks, if(divergent, "!divergence!"))"#;
+    test_env.jj_cmd_success(repo_path, &["log", "-T", template])
+}
+
+#[test]
+fn test_resolve_divergence_keep() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+    create_divergence(&test_env, &repo_path);
+    test_env.jj_cmd_ok(&repo_path, &["new", "description(two)", "-m", "child"]);
+    test_env.jj_cmd_ok(
+        &repo_path,
+        &["bookmark", "create", "-r", "description(two)", "two"],
+    );
+    insta::assert_snapshot!(get_log_output(&test_env, &repo_path), @r"
+    @  yqosqzytrlsw d397f42febfe child
+    ○  kkmpptxzrspx 49984315889d two two !divergence!
+    │ ○  kkmpptxzrspx 52bc4cd5ccc8 one !divergence!
+    ├─╯
+    ○  rlvkpnrzqnoo 494c7b830985 base
+    ◆  zzzzzzzzzzzz 000000000000
+    ");
+
+    // Without options, the divergent commits are listed
+    let (stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["resolve-divergence", "@-"]);
+    insta::assert_snapshot!(stdout, @r"
+    Change kkmpptxzrspx has 2 divergent commits:
+      kkmpptxz?? 49984315 two | (empty) two
+      kkmpptxz?? 52bc4cd5 one
+    ");
+    insta::assert_snapshot!(stderr, @"Hint: Use --keep to keep one of the commits, or --merge to combine them.");
+
+    // The child and the bookmark of the abandoned commit move to the kept one
+    let (stdout, stderr) = test_env.jj_cmd_ok(
+        &repo_path,
+        &["resolve-divergence", "@-", "--keep", "description(one)"],
+    );
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r"
+    Resolved divergent change kkmpptxzrspx: kkmpptxz a0a5e973 two | one
+    Rebased 1 descendant commits
+    Working copy now at: yqosqzyt 53bb7a28 (empty) child
+    Parent commit      : kkmpptxz a0a5e973 two | one
+    Added 1 files, modified 0 files, removed 0 files
+    ");
+    insta::assert_snapshot!(get_log_output(&test_env, &repo_path), @r"
+    @  yqosqzytrlsw 53bb7a28dd9f child
+    ○  kkmpptxzrspx a0a5e973ab64 one two
+    ○  rlvkpnrzqnoo 494c7b830985 base
+    ◆  zzzzzzzzzzzz 000000000000
+    ");
+
+    // Both commits are predecessors of the resolved commit
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &[
+            "evolog",
+            "-r",
+            "@-",
+            "-T",
+            r#"commit_id.short() ++ " " ++ description"#,
+        ],
+    );
+    insta::assert_snapshot!(stdout, @r"
+    ○    a0a5e973ab64 one
+    ├─╮
+    │ ○  49984315889d two
+    ○ │  52bc4cd5ccc8 one
+    ○ │  3ce83c0b3639
+    ├─╯
+    ○  6e2229ee5ed2
+    ");
+}
+
+#[test]
+fn test_resolve_divergence_merge() {
+    let mut test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+    create_divergence(&test_env, &repo_path);
+    insta::assert_snapshot!(get_log_output(&test_env, &repo_path), @r"
+    @  kkmpptxzrspx 52bc4cd5ccc8 one !divergence!
+    │ ○  kkmpptxzrspx 49984315889d two !divergence!
+    ├─╯
+    ○  rlvkpnrzqnoo 494c7b830985 base
+    ◆  zzzzzzzzzzzz 000000000000
+    ");
+
+    // The differing descriptions are combined in the editor
+    let edit_script = test_env.set_up_fake_editor();
+    std::fs::write(&edit_script, "dump editor0\0write\ncombined").unwrap();
+    let (stdout, stderr) = test_env.jj_cmd_ok(
+        &repo_path,
+        &["resolve-divergence", "description(two)", "--merge"],
+    );
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r"
+    Resolved divergent change kkmpptxzrspx: kkmpptxz 0401e1cd combined
+    Working copy now at: kkmpptxz 0401e1cd combined
+    Parent commit      : rlvkpnrz 494c7b83 (empty) base
+    ");
+    insta::assert_snapshot!(
+        std::fs::read_to_string(test_env.env_root().join("editor0")).unwrap(), @r#"
+    JJ: Enter a description for the combined commit.
+    JJ: Description from the destination commit:
+    two
+
+    JJ: Description from source commit:
+    one
+
+    JJ: Lines starting with "JJ:" (like this one) will be removed.
+    "#);
+    insta::assert_snapshot!(get_log_output(&test_env, &repo_path), @r"
+    @  kkmpptxzrspx 0401e1cd99fb combined
+    ○  rlvkpnrzqnoo 494c7b830985 base
+    ◆  zzzzzzzzzzzz 000000000000
+    ");
+    let stdout = test_env.jj_cmd_success(&repo_path, &["file", "show", "-r", "@", "file1"]);
+    insta::assert_snapshot!(stdout, @"a");
+}
+
+#[test]
+fn test_resolve_divergence_errors() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+    create_divergence(&test_env, &repo_path);
+
+    let stderr = test_env.jj_cmd_failure(&repo_path, &["resolve-divergence", "description(base)"]);
+    insta::assert_snapshot!(stderr, @"Error: Change rlvkpnrzqnoo is not divergent");
+    let stderr = test_env.jj_cmd_failure(&repo_path, &["resolve-divergence", "all()"]);
+    insta::assert_snapshot!(stderr, @"Error: Revset contains more than one change");
+    let stderr = test_env.jj_cmd_failure(
+        &repo_path,
+        &["resolve-divergence", "@", "--keep", "description(base)"],
+    );
+    insta::assert_snapshot!(stderr, @"Error: Commit 494c7b830985 is not a commit of change kkmpptxzrspx");
+    let stderr = test_env.jj_cmd_cli_error(
+        &repo_path,
+        &["resolve-divergence", "@", "--keep", "@", "--merge"],
+    );
+    insta::assert_snapshot!(stderr, @r"
+    error: the argument '--keep <REVSET>' cannot be used with '--merge'
+
+    Usage: jj resolve-divergence --keep <REVSET> <CHANGE>
+
+    For more information, try '--help'.
+    ");
+}
@@ -0,0 +1,117 @@
+// Copyright 2024 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#![cfg(unix)]
+
+use crate::common::TestEnvironment;
+
+#[test]
+fn test_run_on_revisions() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    std::fs::write(repo_path.join("file"), "a\n").unwrap();
+    test_env.jj_cmd_ok(&repo_path, &["commit", "-m", "first"]);
+    std::fs::write(repo_path.join("file"), "b\n").unwrap();
+    test_env.jj_cmd_ok(&repo_path, &["describe", "-m", "second"]);
+
+    let (stdout, stderr) = test_env.jj_cmd_ok(
+        &repo_path,
+        &["run", "cat file", "-r", "::@ ~ root()", "-j1"],
+    );
+    insta::assert_snapshot!(stdout, @r"
+    a
+    b
+    ");
+    insta::assert_snapshot!(stderr, @r"
+    Finished qpvuntsm 2d4fa21a first (ok)
+    Finished rlvkpnrz d8e152c4 second (ok)
+    Ran `cat file` on 2 revisions:
+      ok       qpvuntsm 2d4fa21a first
+      ok       rlvkpnrz d8e152c4 second
+    ");
+
+    // The command's environment identifies the revision
+    let (stdout, _stderr) = test_env.jj_cmd_ok(&repo_path, &["run", "echo $JJ_RUN_COMMIT_ID"]);
+    let commit_id = test_env.jj_cmd_success(
+        &repo_path,
+        &["log", "--no-graph", "-r@", "-Tcommit_id ++ '\n'"],
+    );
+    assert_eq!(stdout, commit_id);
+}
+
+#[test]
+fn test_run_failure() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    std::fs::write(repo_path.join("file"), "a\n").unwrap();
+    test_env.jj_cmd_ok(&repo_path, &["commit", "-m", "first"]);
+    test_env.jj_cmd_ok(&repo_path, &["describe", "-m", "second"]);
+
+    let stderr = test_env.jj_cmd_failure(
+        &repo_path,
+        &["run", "test -f file && exit 3", "-r", "::@ ~ root()", "-j1"],
+    );
+    insta::assert_snapshot!(stderr, @r"
+    Finished qpvuntsm 2d4fa21a first (exit 3)
+    Finished rlvkpnrz 9b5b195f (empty) second (exit 3)
+    Ran `test -f file && exit 3` on 2 revisions:
+      exit 3   qpvuntsm 2d4fa21a first
+      exit 3   rlvkpnrz 9b5b195f (empty) second
+    Error: The command failed on 2 of 2 revisions
+    ");
+}
+
+#[test]
+fn test_run_reuses_scratch_working_copy() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    std::fs::write(repo_path.join(".gitignore"), "ignored\n").unwrap();
+    std::fs::write(repo_path.join("file"), "a\n").unwrap();
+    test_env.jj_cmd_ok(&repo_path, &["commit", "-m", "first"]);
+
+    // Files written by the command don't end up in the user's working copy
+    test_env.jj_cmd_ok(
+        &repo_path,
+        &[
+            "run",
+            "echo x > file; echo x > new; echo x > ignored",
+            "-r@-",
+        ],
+    );
+    assert!(!repo_path.join("new").exists());
+    let stdout = test_env.jj_cmd_success(&repo_path, &["diff", "--summary", "-r@-"]);
+    insta::assert_snapshot!(stdout, @r"
+    A .gitignore
+    A file
+    ");
+
+    // Changes to tracked files and new files are reverted before the next run,
+    // but ignored files are kept
+    let (stdout, _stderr) = test_env.jj_cmd_ok(&repo_path, &["run", "cat file; ls", "-r@-"]);
+    insta::assert_snapshot!(stdout, @r"
+    a
+    file
+    ignored
+    ");
+
+    // --clean removes the ignored files too
+    let (stdout, _stderr) = test_env.jj_cmd_ok(&repo_path, &["run", "ls", "-r@-", "--clean"]);
+    insta::assert_snapshot!(stdout, @"file");
+}
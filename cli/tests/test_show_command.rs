@@ -243,6 +243,33 @@ fn test_show_with_template() {
     "###);
 }
 
+#[test]
+fn test_show_no_patch() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+    std::fs::write(repo_path.join("file1"), "foo\n").unwrap();
+    test_env.jj_cmd_ok(&repo_path, &["describe", "-m", "a new commit"]);
+
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &["show", "--no-patch", "-T", "builtin_log_json"],
+    );
+    insta::assert_snapshot!(stdout, @r#"{"commit_id":"701a20f914e1c77b85994bb4429f213883ec9c5e","change_id":"qpvuntsmwlqtpsluzzsnyyzlmlwvmlnu","parents":["0000000000000000000000000000000000000000"],"description":"a new commit\n","author":{"name":"Test User","email":"test.user@example.com","timestamp":"2001-02-03T04:05:08+07:00"},"committer":{"name":"Test User","email":"test.user@example.com","timestamp":"2001-02-03T04:05:08+07:00"},"local_bookmarks":[],"remote_bookmarks":[],"tags":[],"working_copies":"","current_working_copy":true,"immutable":false,"conflict":false,"empty":false,"divergent":false,"hidden":false,"diff_summary":"A file1\n"}"#);
+
+    let stderr = test_env.jj_cmd_cli_error(&repo_path, &["show", "--no-patch", "--git"]);
+    insta::assert_snapshot!(stderr, @r"
+    error: the argument '--no-patch' cannot be used with:
+      --git
+      --color-words
+      --tool <TOOL>
+
+    Usage: jj show --no-patch --git [REVSET]
+
+    For more information, try '--help'.
+    ");
+}
+
 #[test]
 fn test_show_with_no_template() {
     let test_env = TestEnvironment::default();
@@ -250,7 +277,7 @@ fn test_show_with_no_template() {
     let repo_path = test_env.env_root().join("repo");
 
     let stderr = test_env.jj_cmd_cli_error(&repo_path, &["show", "-T"]);
-    insta::assert_snapshot!(stderr, @r#"
+    insta::assert_snapshot!(stderr, @r"
     error: a value is required for '--template <TEMPLATE>' but none was supplied
 
     For more information, try '--help'.
@@ -259,18 +286,20 @@ fn test_show_with_no_template() {
     - builtin_log_compact
     - builtin_log_compact_full_description
     - builtin_log_detailed
+    - builtin_log_json
     - builtin_log_node
     - builtin_log_node_ascii
     - builtin_log_oneline
     - builtin_op_log_comfortable
     - builtin_op_log_compact
+    - builtin_op_log_json
     - builtin_op_log_node
     - builtin_op_log_node_ascii
     - commit_summary_separator
     - description_placeholder
     - email_placeholder
     - name_placeholder
-    "#);
+    ");
 }
 
 #[test]
@@ -0,0 +1,176 @@
+// Copyright 2024 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::path::Path;
+
+use crate::common::TestEnvironment;
+
+#[test]
+fn test_sign() {
+    let test_env = TestEnvironment::default();
+    test_env.add_config(
+        r#"
+        [signing]
+        backend = "test"
+        key = "default-key"
+        "#,
+    );
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+    test_env.jj_cmd_ok(&repo_path, &["commit", "-m", "one"]);
+    test_env.jj_cmd_ok(&repo_path, &["commit", "-m", "two"]);
+    test_env.jj_cmd_ok(&repo_path, &["describe", "-m", "three"]);
+    insta::assert_snapshot!(get_log_output(&test_env, &repo_path), @r"
+    three: unsigned
+    two: unsigned
+    one: unsigned
+    ");
+
+    let (stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["sign", "-r", "description(one)"]);
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r"
+    Signed 1 commits:
+      qpvuntsm d5c21485 (empty) one
+    Rebased 2 descendant commits
+    Working copy now at: kkmpptxz 20376741 (empty) three
+    Parent commit      : rlvkpnrz 44d9d585 (empty) two
+    ");
+    insta::assert_snapshot!(get_log_output(&test_env, &repo_path), @r"
+    three: unsigned
+    two: unsigned
+    one: default-key
+    ");
+
+    // Signing again with another key replaces the signature
+    let (_stdout, stderr) = test_env.jj_cmd_ok(
+        &repo_path,
+        &[
+            "sign",
+            "-r",
+            "description(one)|description(two)",
+            "--key",
+            "other-key",
+        ],
+    );
+    insta::assert_snapshot!(stderr, @r"
+    Signed 2 commits:
+      qpvuntsm 5df9d22b (empty) one
+      rlvkpnrz d2203144 (empty) two
+    Rebased 1 descendant commits
+    Working copy now at: kkmpptxz 6c448c0b (empty) three
+    Parent commit      : rlvkpnrz d2203144 (empty) two
+    ");
+    insta::assert_snapshot!(get_log_output(&test_env, &repo_path), @r"
+    three: unsigned
+    two: other-key
+    one: other-key
+    ");
+
+    // Immutable commits can't be signed
+    let stderr = test_env.jj_cmd_failure(&repo_path, &["sign", "-r", "root()"]);
+    insta::assert_snapshot!(stderr, @"Error: The root commit 000000000000 is immutable");
+}
+
+#[test]
+fn test_sign_no_backend() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    let stderr = test_env.jj_cmd_failure(&repo_path, &["sign"]);
+    insta::assert_snapshot!(stderr, @r"
+    Error: No signing backend is configured
+    Hint: Set `signing.backend` to sign commits. See https://jj-vcs.github.io/jj/latest/config/#commit-signing
+    ");
+}
+
+#[test]
+fn test_unsign() {
+    let test_env = TestEnvironment::default();
+    test_env.add_config(
+        r#"
+        [signing]
+        backend = "test"
+        key = "default-key"
+        "#,
+    );
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+    test_env.jj_cmd_ok(&repo_path, &["commit", "-m", "one"]);
+    test_env.jj_cmd_ok(&repo_path, &["commit", "-m", "two"]);
+    test_env.jj_cmd_ok(
+        &repo_path,
+        &["sign", "-r", "description(one)|description(two)"],
+    );
+    insta::assert_snapshot!(get_log_output(&test_env, &repo_path), @r"
+    : unsigned
+    two: default-key
+    one: default-key
+    ");
+
+    // Unsigned commits in the revset are left alone
+    let (stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["unsign", "-r", "::@ ~ root()"]);
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r"
+    Unsigned 2 commits:
+      qpvuntsm 55caf99f (empty) one
+      rlvkpnrz f3c2a6ac (empty) two
+    Rebased 1 descendant commits
+    Working copy now at: kkmpptxz cee8a456 (empty) (no description set)
+    Parent commit      : rlvkpnrz f3c2a6ac (empty) two
+    ");
+    insta::assert_snapshot!(get_log_output(&test_env, &repo_path), @r"
+    : unsigned
+    two: unsigned
+    one: unsigned
+    ");
+
+    let (_stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["unsign", "-r", "::@ ~ root()"]);
+    insta::assert_snapshot!(stderr, @"No signed revisions to unsign.");
+}
+
+/// Shows the key of the test signature of each commit, if any.
+fn get_log_output(test_env: &TestEnvironment, repo_path: &Path) -> String {
+    let stdout = test_env.jj_cmd_success(
+        repo_path,
+        &[
+            "log",
+            "--no-graph",
+            "-r",
+            "::@ ~ root()",
+            "-T",
+            r#"commit_id ++ " " ++ description.first_line() ++ "\n""#,
+        ],
+    );
+    let git_repo = git2::Repository::open(repo_path.join(".jj/repo/store/git")).unwrap();
+    stdout
+        .lines()
+        .map(|line| {
+            let (commit_id, description) = line.split_once(' ').unwrap();
+            let commit = git_repo
+                .find_commit(git2::Oid::from_str(commit_id).unwrap())
+                .unwrap();
+            let key = commit.header_field_bytes("gpgsig").ok().map(|sig| {
+                let sig = String::from_utf8(sig.to_vec()).unwrap();
+                sig.lines()
+                    .nth(1)
+                    .unwrap()
+                    .strip_prefix("KEY: ")
+                    .unwrap()
+                    .to_owned()
+            });
+            format!("{description}: {}\n", key.as_deref().unwrap_or("unsigned"))
+        })
+        .collect()
+}
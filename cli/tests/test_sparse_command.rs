@@ -14,6 +14,8 @@
 
 use std::io::Write;
 
+use indoc::indoc;
+
 use crate::common::TestEnvironment;
 
 #[test]
@@ -175,6 +177,124 @@ fn test_sparse_manage_patterns() {
     "###);
 }
 
+#[test]
+fn test_sparse_globs_and_exclusions() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    std::fs::create_dir_all(repo_path.join("lib").join("testdata")).unwrap();
+    std::fs::write(repo_path.join("README.md"), "contents").unwrap();
+    std::fs::write(repo_path.join("file"), "contents").unwrap();
+    std::fs::write(repo_path.join("lib").join("lib.rs"), "contents").unwrap();
+    std::fs::write(
+        repo_path.join("lib").join("testdata").join("data"),
+        "contents",
+    )
+    .unwrap();
+
+    // Include lib/ and Markdown files, but leave out the test data
+    let (stdout, stderr) = test_env.jj_cmd_ok(
+        &repo_path,
+        &[
+            "sparse",
+            "set",
+            "--clear",
+            "--add=lib",
+            "--add=glob:*.md",
+            "--add=!glob:lib/testdata/**",
+        ],
+    );
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r###"
+    Added 0 files, modified 0 files, removed 2 files
+    "###);
+    let stdout = test_env.jj_cmd_success(&repo_path, &["sparse", "list"]);
+    insta::assert_snapshot!(stdout, @r###"
+    lib
+    glob:*.md
+    !glob:lib/testdata/**
+    "###);
+    assert!(repo_path.join("README.md").exists());
+    assert!(!repo_path.join("file").exists());
+    assert!(repo_path.join("lib").join("lib.rs").exists());
+    assert!(!repo_path.join("lib").join("testdata").join("data").exists());
+
+    // Removing the exclusion brings the files back
+    let (_stdout, stderr) = test_env.jj_cmd_ok(
+        &repo_path,
+        &["sparse", "set", "--remove=!glob:lib/testdata/**"],
+    );
+    insta::assert_snapshot!(stderr, @r###"
+    Added 1 files, modified 0 files, removed 0 files
+    "###);
+    assert!(repo_path.join("lib").join("testdata").join("data").exists());
+
+    // Invalid glob
+    let stderr = test_env.jj_cmd_cli_error(&repo_path, &["sparse", "set", "--add=glob:["]);
+    insta::assert_snapshot!(stderr, @r###"
+    error: invalid value 'glob:[' for '--add <ADD>': Pattern syntax error near position 0: invalid range pattern
+
+    For more information, try '--help'.
+    "###);
+}
+
+#[test]
+fn test_sparse_apply_profile() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+    std::fs::write(
+        repo_path.join(".jj/repo/config.toml"),
+        indoc! {r#"
+            [sparse.profiles]
+            backend = ["server", "lib", "!glob:lib/testdata/**"]
+            bad = ["glob:["]
+        "#},
+    )
+    .unwrap();
+
+    std::fs::create_dir_all(repo_path.join("lib").join("testdata")).unwrap();
+    std::fs::create_dir(repo_path.join("server")).unwrap();
+    std::fs::write(repo_path.join("file"), "contents").unwrap();
+    std::fs::write(repo_path.join("lib").join("lib.rs"), "contents").unwrap();
+    std::fs::write(
+        repo_path.join("lib").join("testdata").join("data"),
+        "contents",
+    )
+    .unwrap();
+    std::fs::write(repo_path.join("server").join("main.rs"), "contents").unwrap();
+
+    let (stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["sparse", "apply-profile", "backend"]);
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r###"
+    Added 0 files, modified 0 files, removed 2 files
+    "###);
+    let stdout = test_env.jj_cmd_success(&repo_path, &["sparse", "list"]);
+    insta::assert_snapshot!(stdout, @r###"
+    lib
+    server
+    !glob:lib/testdata/**
+    "###);
+    assert!(!repo_path.join("file").exists());
+    assert!(repo_path.join("server").join("main.rs").exists());
+
+    // Unknown profile
+    let stderr = test_env.jj_cmd_failure(&repo_path, &["sparse", "apply-profile", "frontend"]);
+    insta::assert_snapshot!(stderr, @r###"
+    Error: Sparse profile 'frontend' is not defined
+    Hint: Defined profiles: backend, bad
+    "###);
+
+    // Invalid pattern in profile
+    let stderr = test_env.jj_cmd_failure(&repo_path, &["sparse", "apply-profile", "bad"]);
+    insta::assert_snapshot!(stderr, @r###"
+    Config error: Invalid pattern in sparse profile 'bad': glob:[
+    Caused by: Pattern syntax error near position 0: invalid range pattern
+    For help, see https://jj-vcs.github.io/jj/latest/config/.
+    "###);
+}
+
 #[test]
 fn test_sparse_editor_avoids_unc() {
     use std::path::PathBuf;
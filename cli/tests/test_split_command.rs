@@ -604,3 +604,204 @@ fn test_split_interactive() {
     Parent commit      : qpvuntsm 0e15949e (no description set)
     "###);
 }
+
+#[test]
+fn test_split_select() {
+    let mut test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    std::fs::write(repo_path.join("file1"), "a\nb\nc\nd\ne\n").unwrap();
+    test_env.jj_cmd_ok(&repo_path, &["new"]);
+    std::fs::write(repo_path.join("file1"), "a\nB\nc\nD\ne\n").unwrap();
+    std::fs::write(repo_path.join("file2"), "foo\n").unwrap();
+    std::fs::write(repo_path.join("file3"), "bar\n").unwrap();
+
+    test_env.set_up_fake_editor();
+    let (stdout, stderr) = test_env.jj_cmd_ok(
+        &repo_path,
+        &["split", "--select=hunks:file1:2", "--select=file:file3"],
+    );
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r"
+    First part: rlvkpnrz 720889ce (no description set)
+    Second part: kkmpptxz 3e5a1be1 (no description set)
+    Working copy now at: kkmpptxz 3e5a1be1 (no description set)
+    Parent commit      : rlvkpnrz 720889ce (no description set)
+    ");
+    let stdout = test_env.jj_cmd_success(&repo_path, &["diff", "--git", "-r@-"]);
+    insta::assert_snapshot!(stdout, @r"
+    diff --git a/file1 b/file1
+    index 9405325339..b7f446fbea 100644
+    --- a/file1
+    +++ b/file1
+    @@ -1,5 +1,5 @@
+     a
+     b
+     c
+    -d
+    +D
+     e
+    diff --git a/file3 b/file3
+    new file mode 100644
+    index 0000000000..5716ca5987
+    --- /dev/null
+    +++ b/file3
+    @@ -0,0 +1,1 @@
+    +bar
+    ");
+    let stdout = test_env.jj_cmd_success(&repo_path, &["diff", "--git", "-r@"]);
+    insta::assert_snapshot!(stdout, @r"
+    diff --git a/file1 b/file1
+    index b7f446fbea..2590c0b57a 100644
+    --- a/file1
+    +++ b/file1
+    @@ -1,5 +1,5 @@
+     a
+    -b
+    +B
+     c
+     D
+     e
+    diff --git a/file2 b/file2
+    new file mode 100644
+    index 0000000000..257cc5642c
+    --- /dev/null
+    +++ b/file2
+    @@ -0,0 +1,1 @@
+    +foo
+    ");
+
+    // Hunk doesn't exist
+    let stderr = test_env.jj_cmd_failure(&repo_path, &["split", "--select=hunks:file1:1-2"]);
+    insta::assert_snapshot!(stderr, @r#"
+    Error: Failed to select changes
+    Caused by: Hunk 2 doesn't exist in "file1", which has 1 hunks
+    "#);
+
+    // Unknown spec
+    let stderr = test_env.jj_cmd_failure(&repo_path, &["split", "--select=file1"]);
+    insta::assert_snapshot!(stderr, @r"
+    Error: Invalid selection `file1`
+    Hint: Use `file:PATH`, `hunks:PATH:1,2-3`, or `-` to read a patch from stdin.
+    ");
+}
+
+#[test]
+fn test_split_auto() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+    let workspace_path = &repo_path;
+
+    for dir in ["a", "b"] {
+        std::fs::create_dir(repo_path.join(dir)).unwrap();
+        for name in ["file1", "file2", "file3"] {
+            std::fs::write(repo_path.join(dir).join(name), "foo\n").unwrap();
+        }
+    }
+    std::fs::write(repo_path.join("top"), "foo\n").unwrap();
+    test_env.jj_cmd_ok(workspace_path, &["describe", "-m", "add files\n\nbody"]);
+    test_env.jj_cmd_ok(workspace_path, &["bookmark", "create", "main"]);
+    test_env.jj_cmd_ok(workspace_path, &["new", "-m", "child"]);
+
+    let template = r#"separate(" ", change_id.short(), description.first_line(), local_bookmarks) ++ "\n" ++ diff.summary()"#;
+
+    // A mode is required
+    let stderr = test_env.jj_cmd_cli_error(workspace_path, &["split", "--auto", "-r@-"]);
+    insta::assert_snapshot!(stderr, @r"
+    error: the following required arguments were not provided:
+      <--max-files <N>|--by-directory>
+
+    Usage: jj split --auto --revision <REVSET> <--max-files <N>|--by-directory> [FILESETS]...
+
+    For more information, try '--help'.
+    ");
+
+    let (stdout, stderr) = test_env.jj_cmd_ok(
+        workspace_path,
+        &["split", "--auto", "--by-directory", "--max-files=2", "-r@-"],
+    );
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r"
+    Rebased 1 descendant commits
+    Split into 5 commits:
+      qpvuntsm e220cf0e add files (1/5)
+      royxmykx dc5f8946 add files (2/5)
+      nppvrztz 1c186f65 add files (3/5)
+      xotyzkmz b5f5a7c7 add files (4/5)
+      lrptzzwt 86e5f033 main | add files (5/5)
+    Working copy now at: zsuskuln e91236ba (empty) child
+    Parent commit      : lrptzzwt 86e5f033 main | add files (5/5)
+    ");
+    let stdout = test_env.jj_cmd_success(workspace_path, &["log", "-T", template]);
+    insta::assert_snapshot!(stdout, @r"
+    @  zsuskulnrvyr child
+    ○  lrptzzwttnll add files (5/5) main
+    │  A b/file3
+    ○  xotyzkmzmuos add files (4/5)
+    │  A b/file1
+    │  A b/file2
+    ○  nppvrztzynpl add files (3/5)
+    │  A a/file3
+    ○  royxmykxtrkr add files (2/5)
+    │  A a/file1
+    │  A a/file2
+    ○  qpvuntsmwlqt add files (1/5)
+    │  A top
+    ◆  zzzzzzzzzzzz
+    ");
+    let stdout = test_env.jj_cmd_success(
+        workspace_path,
+        &["log", "--no-graph", "-r@--", "-T", "description"],
+    );
+    insta::assert_snapshot!(stdout, @r"
+    add files (4/5)
+
+    body
+    ");
+
+    // Parallel parts
+    test_env.jj_cmd_ok(workspace_path, &["op", "undo"]);
+    let (stdout, stderr) = test_env.jj_cmd_ok(
+        workspace_path,
+        &["split", "--auto", "--by-directory", "--parallel", "-r@-"],
+    );
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r"
+    Rebased 1 descendant commits
+    Split into 3 commits:
+      qpvuntsm 2a6cef81 add files (1/3)
+      znkkpsqq cc962e09 add files (2/3)
+      uuzqqzqu 1351c640 main | add files (3/3)
+    Working copy now at: zsuskuln 25999eb2 (empty) child
+    Parent commit      : qpvuntsm 2a6cef81 add files (1/3)
+    Parent commit      : znkkpsqq cc962e09 add files (2/3)
+    Parent commit      : uuzqqzqu 1351c640 main | add files (3/3)
+    ");
+    let stdout = test_env.jj_cmd_success(workspace_path, &["log", "-T", template]);
+    insta::assert_snapshot!(stdout, @r"
+    @      zsuskulnrvyr child
+    ├─┬─╮
+    │ │ ○  uuzqqzquvwzn add files (3/3) main
+    │ │ │  A b/file1
+    │ │ │  A b/file2
+    │ │ │  A b/file3
+    │ ○ │  znkkpsqqskkl add files (2/3)
+    │ ├─╯  A a/file1
+    │ │    A a/file2
+    │ │    A a/file3
+    ○ │  qpvuntsmwlqt add files (1/3)
+    ├─╯  A top
+    ◆  zzzzzzzzzzzz
+    ");
+
+    // Nothing to split
+    test_env.jj_cmd_ok(workspace_path, &["op", "undo"]);
+    let (stdout, stderr) = test_env.jj_cmd_ok(
+        workspace_path,
+        &["split", "--auto", "--max-files=10", "-r@-"],
+    );
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @"Warning: All changes fit in a single part, so the commit was not split");
+}
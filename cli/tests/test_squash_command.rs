@@ -15,6 +15,8 @@
 use std::path::Path;
 use std::path::PathBuf;
 
+use indoc::indoc;
+
 use crate::common::TestEnvironment;
 
 #[test]
@@ -1001,6 +1003,94 @@ fn test_squash_from_multiple_partial_no_op() {
     "###);
 }
 
+#[test]
+fn test_squash_select() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    std::fs::write(repo_path.join("file1"), "a\nb\nc\nd\ne\n").unwrap();
+    test_env.jj_cmd_ok(&repo_path, &["new"]);
+    std::fs::write(repo_path.join("file1"), "a\nB\nc\nD\ne\n").unwrap();
+    std::fs::write(repo_path.join("file2"), "foo\n").unwrap();
+
+    // Squash an edited patch, which only changes the first hunk
+    let patch = indoc! {"
+        diff --git a/file1 b/file1
+        --- a/file1
+        +++ b/file1
+        @@ -1,3 +1,3 @@
+         a
+        -b
+        +B2
+         c
+    "};
+    let (stdout, stderr) =
+        test_env.jj_cmd_stdin_ok(&repo_path, &["squash", "--select", "-"], patch);
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r"
+    Rebased 1 descendant commits
+    Working copy now at: rlvkpnrz 48224964 (no description set)
+    Parent commit      : qpvuntsm 6cbf4a53 (no description set)
+    ");
+    let stdout = test_env.jj_cmd_success(&repo_path, &["diff", "--git", "-r@-"]);
+    insta::assert_snapshot!(stdout, @r"
+    diff --git a/file1 b/file1
+    new file mode 100644
+    index 0000000000..a9d058af5d
+    --- /dev/null
+    +++ b/file1
+    @@ -0,0 +1,5 @@
+    +a
+    +B2
+    +c
+    +d
+    +e
+    ");
+    let stdout = test_env.jj_cmd_success(&repo_path, &["diff", "--git"]);
+    insta::assert_snapshot!(stdout, @r"
+    diff --git a/file1 b/file1
+    index a9d058af5d..2590c0b57a 100644
+    --- a/file1
+    +++ b/file1
+    @@ -1,5 +1,5 @@
+     a
+    -B2
+    +B
+     c
+    -d
+    +D
+     e
+    diff --git a/file2 b/file2
+    new file mode 100644
+    index 0000000000..257cc5642c
+    --- /dev/null
+    +++ b/file2
+    @@ -0,0 +1,1 @@
+    +foo
+    ");
+
+    // Patch which doesn't apply
+    let stderr = test_env
+        .jj_cmd_stdin(&repo_path, &["squash", "--select", "-"], patch)
+        .assert()
+        .failure()
+        .get_output()
+        .stderr
+        .clone();
+    insta::assert_snapshot!(String::from_utf8(stderr).unwrap(), @r#"
+    Error: Failed to select changes
+    Caused by: Patch doesn't apply to "file1" at line 4
+    "#);
+
+    // Nothing to select
+    let stderr = test_env.jj_cmd_failure(&repo_path, &["squash", "--select", "file:file3"]);
+    insta::assert_snapshot!(stderr, @r#"
+    Error: Failed to select changes
+    Caused by: No changes to select in "file3"
+    "#);
+}
+
 fn get_log_output(test_env: &TestEnvironment, repo_path: &Path) -> String {
     let template = r#"separate(
         " ",
@@ -197,10 +197,12 @@ fn test_status_display_relevant_working_commit_conflict_hints() {
 
     let stdout = test_env.jj_cmd_success(&repo_path, &["status"]);
 
-    insta::assert_snapshot!(stdout, @r###"
+    insta::assert_snapshot!(stdout, @r"
     The working copy is clean
     There are unresolved conflicts at these paths:
     conflicted.txt    2-sided conflict
+      side #1: zsuskuln 2c8b19fd Second part of conflicting change
+      side #2: kkmpptxz 1e8c2956 First part of conflicting change
     Working copy : yqosqzyt dcb25635 (conflict) (empty) boom-cont-2
     Parent commit: royxmykx 664a4c6c (conflict) (empty) boom-cont
     To resolve the conflicts, start by updating to the first one:
@@ -208,7 +210,7 @@ fn test_status_display_relevant_working_commit_conflict_hints() {
     Then use `jj resolve`, or edit the conflict markers in the file directly.
     Once the conflicts are resolved, you may want to inspect the result with `jj diff`.
     Then run `jj squash` to move the resolution into the conflicted commit.
-    "###);
+    ");
 
     // Resolve conflict
     test_env.jj_cmd_ok(&repo_path, &["new", "--message", "fixed 1"]);
@@ -352,11 +354,15 @@ fn test_status_simplify_conflict_sides() {
     );
 
     insta::assert_snapshot!(test_env.jj_cmd_success(&repo_path, &["status"]),
-    @r###"
+    @r"
     The working copy is clean
     There are unresolved conflicts at these paths:
     fileA    2-sided conflict
+      side #1: zsuskuln fe2988e9 a1 | a1
+      side #2: royxmykx 9ec1d835 a2 | a2
     fileB    2-sided conflict
+      side #1: vruxwmqv d6b4e4a6 b1 | b1
+      side #2: znkkpsqq 37b1e048 b2 | b2
     Working copy : nkmrtpmo 83c4b9e7 conflict | (conflict) (empty) conflict
     Parent commit: kmkuslsw 4601566f conflictA | (conflict) (empty) conflictA
     Parent commit: lylxulpl 6f8d8381 conflictB | (conflict) (empty) conflictB
@@ -366,5 +372,89 @@ fn test_status_simplify_conflict_sides() {
     Then use `jj resolve`, or edit the conflict markers in the file directly.
     Once the conflicts are resolved, you may want to inspect the result with `jj diff`.
     Then run `jj squash` to move the resolution into the conflicted commit.
-    "###);
+    ");
+}
+
+#[test]
+fn test_status_conflict_resolution_progress() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    create_commit(
+        &test_env,
+        &repo_path,
+        "base",
+        &[],
+        &[
+            ("file1", "base\n"),
+            ("file2", "base\n"),
+            ("file3", "base\n"),
+        ],
+    );
+    create_commit(
+        &test_env,
+        &repo_path,
+        "a",
+        &["base"],
+        &[("file1", "a\n"), ("file2", "a\n"), ("file3", "a\n")],
+    );
+    create_commit(
+        &test_env,
+        &repo_path,
+        "b",
+        &["base"],
+        &[("file1", "b\n"), ("file2", "b\n"), ("file3", "b\n")],
+    );
+    create_commit(&test_env, &repo_path, "conflict", &["a", "b"], &[]);
+    test_env.jj_cmd_ok(&repo_path, &["new"]);
+
+    // No progress hint before any conflict has been touched
+    let stdout = test_env.jj_cmd_success(&repo_path, &["status"]);
+    insta::assert_snapshot!(stdout, @r"
+    The working copy is clean
+    There are unresolved conflicts at these paths:
+    file1    2-sided conflict
+      side #1: zsuskuln c0a4f9c5 a | a
+      side #2: royxmykx 9801a2ad b | b
+    file2    2-sided conflict
+      side #1: zsuskuln c0a4f9c5 a | a
+      side #2: royxmykx 9801a2ad b | b
+    file3    2-sided conflict
+      side #1: zsuskuln c0a4f9c5 a | a
+      side #2: royxmykx 9801a2ad b | b
+    Working copy : znkkpsqq 872d249d (conflict) (empty) (no description set)
+    Parent commit: vruxwmqv dae7fea5 conflict | (conflict) (empty) conflict
+    To resolve the conflicts, start by updating to it:
+      jj new vruxwmqv
+    Then use `jj resolve`, or edit the conflict markers in the file directly.
+    Once the conflicts are resolved, you may want to inspect the result with `jj diff`.
+    Then run `jj squash` to move the resolution into the conflicted commit.
+    ");
+
+    // Resolve one conflict and edit the markers of another
+    std::fs::write(repo_path.join("file1"), "resolved\n").unwrap();
+    let file2 = std::fs::read_to_string(repo_path.join("file2")).unwrap();
+    std::fs::write(repo_path.join("file2"), file2.replace("+a", "+edited")).unwrap();
+    let stdout = test_env.jj_cmd_success(&repo_path, &["status"]);
+    insta::assert_snapshot!(stdout, @r"
+    Working copy changes:
+    M file1
+    M file2
+    There are unresolved conflicts at these paths:
+    file2    2-sided conflict
+      side #1: (unknown)
+      side #2: royxmykx 9801a2ad b | b
+    file3    2-sided conflict
+      side #1: zsuskuln c0a4f9c5 a | a
+      side #2: royxmykx 9801a2ad b | b
+    1 of 3 conflicts from the parent commit appear resolved, 1 partially edited. Run `jj status` again after finishing.
+    Working copy : znkkpsqq 4add935e (conflict) (no description set)
+    Parent commit: vruxwmqv dae7fea5 conflict | (conflict) (empty) conflict
+    To resolve the conflicts, start by updating to it:
+      jj new vruxwmqv
+    Then use `jj resolve`, or edit the conflict markers in the file directly.
+    Once the conflicts are resolved, you may want to inspect the result with `jj diff`.
+    Then run `jj squash` to move the resolution into the conflicted commit.
+    ");
 }
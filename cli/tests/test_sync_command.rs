@@ -0,0 +1,186 @@
+// Copyright 2024 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::path::Path;
+
+use crate::common::TestEnvironment;
+
+fn init_native_repo(test_env: &TestEnvironment, name: &str) {
+    test_env.jj_cmd_ok(
+        test_env.env_root(),
+        &["init", name, "--config=ui.allow-init-native=true"],
+    );
+}
+
+fn get_bookmark_output(test_env: &TestEnvironment, repo_path: &Path) -> String {
+    test_env.jj_cmd_success(repo_path, &["bookmark", "list", "--all-remotes"])
+}
+
+#[test]
+fn test_sync_pull_and_push() {
+    let test_env = TestEnvironment::default();
+    init_native_repo(&test_env, "origin");
+    init_native_repo(&test_env, "local");
+    let origin_path = test_env.env_root().join("origin");
+    let local_path = test_env.env_root().join("local");
+
+    std::fs::write(origin_path.join("file"), "a\n").unwrap();
+    test_env.jj_cmd_ok(&origin_path, &["commit", "-m", "first"]);
+    test_env.jj_cmd_ok(&origin_path, &["bookmark", "create", "main", "-r@-"]);
+
+    // The remote bookmark is pulled in untracked
+    let (stdout, stderr) = test_env.jj_cmd_ok(&local_path, &["sync", "../origin"]);
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @"Pulled bookmark main@origin");
+    insta::assert_snapshot!(get_bookmark_output(&test_env, &local_path), @"main@origin: qpvuntsm 57aa61c9 first");
+
+    // Once tracked, the local bookmark is pushed back
+    test_env.jj_cmd_ok(&local_path, &["bookmark", "track", "main@origin"]);
+    test_env.jj_cmd_ok(&local_path, &["new", "main", "-m", "second"]);
+    std::fs::write(local_path.join("file"), "b\n").unwrap();
+    test_env.jj_cmd_ok(&local_path, &["bookmark", "set", "main", "-r@"]);
+    let (stdout, stderr) = test_env.jj_cmd_ok(&local_path, &["sync", "../origin"]);
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r"
+    Pushed bookmark main to origin
+    Warning: The working-copy commit in workspace 'default' became immutable, so a new commit has been created on top of it.
+    Working copy now at: znkkpsqq 4aeb3d4f (empty) (no description set)
+    Parent commit      : vruxwmqv 847b6f2e main | second
+    ");
+    insta::assert_snapshot!(get_bookmark_output(&test_env, &origin_path), @"main: vruxwmqv 847b6f2e second");
+    let stdout = test_env.jj_cmd_success(&origin_path, &["file", "show", "-r", "main", "file"]);
+    insta::assert_snapshot!(stdout, @"b");
+
+    // Nothing to do
+    let (stdout, stderr) = test_env.jj_cmd_ok(&local_path, &["sync", "../origin"]);
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @"Nothing changed.");
+}
+
+#[test]
+fn test_sync_new_bookmark() {
+    let test_env = TestEnvironment::default();
+    init_native_repo(&test_env, "origin");
+    init_native_repo(&test_env, "local");
+    let origin_path = test_env.env_root().join("origin");
+    let local_path = test_env.env_root().join("local");
+
+    test_env.jj_cmd_ok(&local_path, &["describe", "-m", "feature"]);
+    test_env.jj_cmd_ok(&local_path, &["bookmark", "create", "feature"]);
+
+    // New bookmarks are only pushed when requested
+    let (_stdout, stderr) = test_env.jj_cmd_ok(&local_path, &["sync", "../origin"]);
+    insta::assert_snapshot!(stderr, @"Nothing changed.");
+    let (_stdout, stderr) =
+        test_env.jj_cmd_ok(&local_path, &["sync", "../origin", "--bookmark", "feature"]);
+    insta::assert_snapshot!(stderr, @"Pushed bookmark feature to origin");
+    insta::assert_snapshot!(get_bookmark_output(&test_env, &origin_path), @"feature: rlvkpnrz 3cd29fe5 (empty) feature");
+    insta::assert_snapshot!(get_bookmark_output(&test_env, &local_path), @r"
+    feature: rlvkpnrz 3cd29fe5 (empty) feature
+      @origin: rlvkpnrz 3cd29fe5 (empty) feature
+    ");
+
+    let stderr = test_env.jj_cmd_failure(&local_path, &["sync", "../origin", "-b", "unknown"]);
+    insta::assert_snapshot!(stderr, @"Error: No such bookmark: unknown");
+}
+
+#[test]
+fn test_sync_remote_bookmark_changed() {
+    let test_env = TestEnvironment::default();
+    init_native_repo(&test_env, "origin");
+    init_native_repo(&test_env, "local");
+    let origin_path = test_env.env_root().join("origin");
+    let local_path = test_env.env_root().join("local");
+
+    test_env.jj_cmd_ok(&origin_path, &["describe", "-m", "base"]);
+    test_env.jj_cmd_ok(&origin_path, &["bookmark", "create", "main"]);
+    test_env.jj_cmd_ok(&local_path, &["sync", "../origin"]);
+    test_env.jj_cmd_ok(&local_path, &["bookmark", "track", "main@origin"]);
+
+    // Both sides move the bookmark; pulling merges them into a conflict, which
+    // can't be pushed
+    test_env.jj_cmd_ok(&origin_path, &["new", "main", "-m", "origin change"]);
+    test_env.jj_cmd_ok(&origin_path, &["bookmark", "set", "main", "-r@"]);
+    test_env.jj_cmd_ok(&local_path, &["new", "main", "-m", "local change"]);
+    test_env.jj_cmd_ok(&local_path, &["bookmark", "set", "main", "-r@"]);
+    let stderr = test_env.jj_cmd_failure(&local_path, &["sync", "../origin"]);
+    insta::assert_snapshot!(stderr, @r"
+    Error: Bookmark main is conflicted
+    Hint: Use `jj sync --no-push` to pull only, then resolve the conflict.
+    ");
+
+    // Pulling alone records the conflict
+    let (_stdout, stderr) = test_env.jj_cmd_ok(&local_path, &["sync", "../origin", "--no-push"]);
+    insta::assert_snapshot!(stderr, @"Pulled bookmark main@origin");
+    insta::assert_snapshot!(get_bookmark_output(&test_env, &local_path), @r"
+    main (conflicted):
+      - qpvuntsm bd98ed35 (empty) base
+      + yostqsxw 951eb41f (empty) local change
+      + yqosqzyt 9029a6fd (empty) origin change
+      @origin (behind by 1 commits): yqosqzyt 9029a6fd (empty) origin change
+    ");
+}
+
+#[test]
+fn test_sync_shared_store() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(
+        test_env.env_root(),
+        &[
+            "init",
+            "origin",
+            "--shared-store",
+            "shared",
+            "--config=ui.allow-init-native=true",
+        ],
+    );
+    init_native_repo(&test_env, "local");
+    let origin_path = test_env.env_root().join("origin");
+    let local_path = test_env.env_root().join("local");
+
+    // The objects of the origin repo are in the shared store
+    std::fs::write(origin_path.join("file"), "a\n").unwrap();
+    test_env.jj_cmd_ok(&origin_path, &["commit", "-m", "first"]);
+    test_env.jj_cmd_ok(&origin_path, &["bookmark", "create", "main", "-r@-"]);
+    let (_stdout, stderr) = test_env.jj_cmd_ok(&local_path, &["sync", "../origin"]);
+    insta::assert_snapshot!(stderr, @"Pulled bookmark main@origin");
+    let stdout =
+        test_env.jj_cmd_success(&local_path, &["file", "show", "-r", "main@origin", "file"]);
+    insta::assert_snapshot!(stdout, @"a");
+
+    // Pushed objects are written to the shared store
+    test_env.jj_cmd_ok(&local_path, &["bookmark", "track", "main@origin"]);
+    test_env.jj_cmd_ok(&local_path, &["new", "main", "-m", "second"]);
+    std::fs::write(local_path.join("file"), "b\n").unwrap();
+    test_env.jj_cmd_ok(&local_path, &["bookmark", "set", "main", "-r@"]);
+    test_env.jj_cmd_ok(&local_path, &["sync", "../origin"]);
+    let stdout = test_env.jj_cmd_success(&origin_path, &["file", "show", "-r", "main", "file"]);
+    insta::assert_snapshot!(stdout, @"b");
+}
+
+#[test]
+fn test_sync_not_native() {
+    let test_env = TestEnvironment::default();
+    init_native_repo(&test_env, "local");
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "git-repo"]);
+    let local_path = test_env.env_root().join("local");
+
+    let stderr = test_env.jj_cmd_failure(&local_path, &["sync", "../git-repo"]);
+    insta::assert_snapshot!(stderr, @r"
+    Error: Only repos using the native backend can be synced
+    Hint: Use `jj git fetch` and `jj git push` for Git-backed repos.
+    ");
+    let stderr = test_env.jj_cmd_failure(&local_path, &["sync", "."]);
+    insta::assert_snapshot!(stderr, @"Error: Cannot sync a repo with itself");
+}
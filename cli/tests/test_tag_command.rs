@@ -117,3 +117,103 @@ fn test_tag_list() {
     added_targets: commit2
     "###);
 }
+
+#[test]
+fn test_tag_create_delete() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    test_env.jj_cmd_ok(&repo_path, &["describe", "-mcommit1"]);
+    test_env.jj_cmd_ok(&repo_path, &["new", "-mcommit2"]);
+
+    let (stdout, stderr) =
+        test_env.jj_cmd_ok(&repo_path, &["tag", "create", "-r@-", "v1.0", "v1.0-rc"]);
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @"Created 2 tags pointing to qpvuntsm caf975d0 (empty) commit1");
+
+    // Cannot recreate an existing tag
+    let stderr = test_env.jj_cmd_failure(&repo_path, &["tag", "create", "v1.0"]);
+    insta::assert_snapshot!(stderr, @r"
+    Error: Tag already exists: v1.0
+    Hint: Use `jj tag delete` to delete it first.
+    ");
+
+    // Tags don't move when the target commit is rewritten
+    test_env.jj_cmd_ok(
+        &repo_path,
+        &[
+            "describe",
+            "@-",
+            "-mcommit1 rewritten",
+            "--ignore-immutable",
+        ],
+    );
+    insta::assert_snapshot!(test_env.jj_cmd_success(&repo_path, &["tag", "list"]), @r"
+    v1.0: qpvuntsm hidden caf975d0 (empty) commit1
+    v1.0-rc: qpvuntsm hidden caf975d0 (empty) commit1
+    ");
+
+    // Tags created in jj survive importing from the backing Git repo
+    test_env.jj_cmd_ok(&repo_path, &["git", "import"]);
+    insta::assert_snapshot!(test_env.jj_cmd_success(&repo_path, &["tag", "list"]), @r"
+    v1.0: qpvuntsm hidden caf975d0 (empty) commit1
+    v1.0-rc: qpvuntsm hidden caf975d0 (empty) commit1
+    ");
+
+    let stderr = test_env.jj_cmd_failure(&repo_path, &["tag", "delete", "v2.0"]);
+    insta::assert_snapshot!(stderr, @"Error: No such tag: v2.0");
+    let (stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["tag", "delete", "glob:v1.0*"]);
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @"Deleted 2 tags.");
+    insta::assert_snapshot!(test_env.jj_cmd_success(&repo_path, &["tag", "list"]), @"");
+    insta::assert_snapshot!(
+        test_env.jj_cmd_success(&repo_path, &["op", "log", "-n2", "-Tdescription"]), @r"
+    @  delete tag v1.0, v1.0-rc
+    ○  describe commit caf975d0989a4e84d6d2fd1047ea03663244969c
+    ");
+}
+
+#[test]
+fn test_tag_create_delete_colocated() {
+    let test_env = TestEnvironment::default();
+    let repo_path = test_env.env_root().join("repo");
+    git2::Repository::init(&repo_path).unwrap();
+    test_env.jj_cmd_ok(&repo_path, &["git", "init", "--git-repo", "."]);
+    let git_repo = git2::Repository::open(&repo_path).unwrap();
+    let git_tag_names = || {
+        let names = git_repo.tag_names(None).unwrap();
+        names
+            .iter()
+            .flatten()
+            .map(|name| name.to_owned())
+            .collect::<Vec<_>>()
+    };
+
+    test_env.jj_cmd_ok(&repo_path, &["describe", "-mcommit1"]);
+    test_env.jj_cmd_ok(&repo_path, &["new"]);
+    test_env.jj_cmd_ok(&repo_path, &["tag", "create", "-r@-", "v1.0"]);
+    assert_eq!(git_tag_names(), ["v1.0"]);
+    let git_commit_id = git_repo
+        .find_reference("refs/tags/v1.0")
+        .unwrap()
+        .peel_to_commit()
+        .unwrap()
+        .id();
+    insta::assert_snapshot!(git_commit_id, @"caf975d0989a4e84d6d2fd1047ea03663244969c");
+
+    test_env.jj_cmd_ok(&repo_path, &["tag", "delete", "v1.0"]);
+    assert!(git_tag_names().is_empty());
+
+    // An annotated tag created by Git can be deleted too
+    let git_commit = git_repo.find_commit(git_commit_id).unwrap();
+    let signature = git2::Signature::now("Someone", "someone@example.com").unwrap();
+    git_repo
+        .tag("v2.0", git_commit.as_object(), &signature, "release", false)
+        .unwrap();
+    test_env.jj_cmd_ok(&repo_path, &["git", "import"]);
+    insta::assert_snapshot!(test_env.jj_cmd_success(&repo_path, &["tag", "list"]), @"v2.0: qpvuntsm caf975d0 (empty) commit1");
+    let (_stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["tag", "delete", "v2.0"]);
+    insta::assert_snapshot!(stderr, @"Deleted 1 tags.");
+    assert!(git_tag_names().is_empty());
+}
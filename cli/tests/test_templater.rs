@@ -113,7 +113,7 @@ fn test_templater_parse_error() {
       | ^-----^
       |
       = Keyword "builtin" doesn't exist
-    Hint: Did you mean "builtin_log_comfortable", "builtin_log_compact", "builtin_log_compact_full_description", "builtin_log_detailed", "builtin_log_node", "builtin_log_node_ascii", "builtin_log_oneline", "builtin_op_log_comfortable", "builtin_op_log_compact", "builtin_op_log_node", "builtin_op_log_node_ascii"?
+    Hint: Did you mean "builtin_log_comfortable", "builtin_log_compact", "builtin_log_compact_full_description", "builtin_log_detailed", "builtin_log_json", "builtin_log_node", "builtin_log_node_ascii", "builtin_log_oneline", "builtin_op_log_comfortable", "builtin_op_log_compact", "builtin_op_log_json", "builtin_op_log_node", "builtin_op_log_node_ascii"?
     "#);
 }
 
@@ -481,6 +481,56 @@ fn test_templater_bad_alias_decl() {
     "###);
 }
 
+#[test]
+fn test_templater_config_function() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+    test_env.add_config(indoc! {"
+        template-vars.machine = 'laptop'
+        template-vars.verbose = false
+        signing.key = 'secret'
+    "});
+    let render = |template| get_template_output(&test_env, &repo_path, "@", template);
+
+    insta::assert_snapshot!(
+        render(r#"config("template-vars.machine").as_string() ++ " " ++ config("user.email")"#),
+        @r#"laptop "test.user@example.com""#);
+    insta::assert_snapshot!(
+        render(r#"if(config("template-vars.verbose").as_boolean(), "verbose", "quiet")"#),
+        @"quiet");
+
+    // Repo config overrides user config
+    test_env.jj_cmd_ok(
+        &repo_path,
+        &["config", "set", "--repo", "template-vars.verbose", "true"],
+    );
+    insta::assert_snapshot!(
+        render(r#"if(config("template-vars.verbose").as_boolean(), "verbose", "quiet")"#),
+        @"verbose");
+
+    // Also available in operation templates
+    insta::assert_snapshot!(
+        test_env.jj_cmd_success(
+            &repo_path,
+            &["op", "log", "--no-graph", "-n1", "-T", r#"config("template-vars.machine")"#],
+        ),
+        @"'laptop'");
+
+    // Secrets can't be read
+    insta::assert_snapshot!(
+        test_env.jj_cmd_failure(&repo_path, &["log", "-T", r#"config("signing.key")"#]),
+        @r#"
+    Error: Failed to parse template: Config `signing.key` is not readable from templates (readable tables: template-vars, ui, user)
+    Caused by:  --> 1:8
+      |
+    1 | config("signing.key")
+      |        ^-----------^
+      |
+      = Config `signing.key` is not readable from templates (readable tables: template-vars, ui, user)
+    "#);
+}
+
 fn get_template_output(
     test_env: &TestEnvironment,
     repo_path: &Path,
@@ -143,3 +143,93 @@ fn test_util_exec_fail() {
     );
     insta::assert_snapshot!(strip_last_line(&err), @"Error: Failed to execute external command 'missing-program'");
 }
+
+#[test]
+fn test_util_check_templates() {
+    let test_env = TestEnvironment::default();
+    let golden_dir = test_env.env_root().join("golden");
+
+    // No golden files yet
+    let stderr =
+        test_env.jj_cmd_failure(test_env.env_root(), &["util", "check-templates", "golden"]);
+    insta::assert_snapshot!(stderr, @r"
+    Warning: No golden file for `templates.annotate_commit_summary`: golden/annotate_commit_summary.txt
+    Warning: No golden file for `templates.backout_description`: golden/backout_description.txt
+    Warning: No golden file for `templates.commit_summary`: golden/commit_summary.txt
+    Warning: No golden file for `templates.draft_commit_description`: golden/draft_commit_description.txt
+    Warning: No golden file for `templates.log`: golden/log.txt
+    Warning: No golden file for `templates.show`: golden/show.txt
+    Error: 6 templates don't match the golden files
+    Hint: Run `jj util check-templates --update golden` to accept the new output.
+    ");
+
+    let (stdout, stderr) = test_env.jj_cmd_ok(
+        test_env.env_root(),
+        &["util", "check-templates", "--update", "golden"],
+    );
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @"Updated golden files in golden");
+    insta::assert_snapshot!(std::fs::read_to_string(golden_dir.join("log.txt")).unwrap(), @r"
+    === root commit ===
+    zzzzzzzz root() 00000000
+    === empty description ===
+    zyzyzyzy test.user@example.com 2001-02-03 08:05:06 560c1f05
+    (empty) (no description set)
+    === description ===
+    zxzxzxzx test.user@example.com 2001-02-03 08:05:06 main d8c77d5e
+    Add file
+    === long names ===
+    zwzwzwzw someone.with.a.remarkably.long.name@subdomain.example.com 2001-02-03 08:05:06 a-remarkably-long-bookmark-name/with/several/path/components 37a67b69
+    (empty) A very long subject line A very long subject line A very long subject line A very long subject line A very long subject line A very long subject line A very long subject line A very long subject line
+    === conflict ===
+    zvzvzvzv test.user@example.com 2001-02-03 08:05:06 1caa1747 conflict
+    Conflicting changes
+    === divergent ===
+    zuzuzuzu?? test.user@example.com 2001-02-03 08:05:06 6baa8f77
+    (empty) Divergent change 1
+    ");
+    insta::assert_snapshot!(
+        std::fs::read_to_string(golden_dir.join("commit_summary.txt")).unwrap(), @r"
+    === root commit ===
+    zzzzzzzz 00000000 (empty) (no description set)
+    === empty description ===
+    zyzyzyzy 560c1f05 (empty) (no description set)
+    === description ===
+    zxzxzxzx d8c77d5e main | Add file
+    === long names ===
+    zwzwzwzw 37a67b69 a-remarkably-long-bookmark-name/with/several/path/components | (empty) A very long subject line A very long subject line A very long subject line A very long subject line A very long subject line A very long subject line A very long subject line A very long subject line
+    === conflict ===
+    zvzvzvzv 1caa1747 (conflict) Conflicting changes
+    === divergent ===
+    zuzuzuzu?? 6baa8f77 (empty) Divergent change 1
+    ");
+
+    let (stdout, stderr) =
+        test_env.jj_cmd_ok(test_env.env_root(), &["util", "check-templates", "golden"]);
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @"All templates match the golden files");
+
+    // Only the sections with changed output are reported
+    test_env.add_config(
+        r#"templates.commit_summary = 'if(conflict, "conflict", format_commit_summary_with_refs(self, bookmarks))'"#,
+    );
+    let stderr =
+        test_env.jj_cmd_failure(test_env.env_root(), &["util", "check-templates", "golden"]);
+    insta::assert_snapshot!(stderr, @r"
+    Warning: Output of `templates.commit_summary` changed: conflict
+    Error: 1 templates don't match the golden files
+    Hint: Run `jj util check-templates --update golden` to accept the new output.
+    ");
+
+    // Templates whose output depends on the current time are skipped
+    test_env.add_config(r#"template-aliases.'format_timestamp(timestamp)' = 'timestamp.ago()'"#);
+    let stderr =
+        test_env.jj_cmd_failure(test_env.env_root(), &["util", "check-templates", "golden"]);
+    insta::assert_snapshot!(stderr, @r"
+    Warning: Output of `templates.commit_summary` changed: conflict
+    Warning: Skipped `templates.log` because its output depends on the current time
+    Warning: Skipped `templates.show` because its output depends on the current time
+    Error: 1 templates don't match the golden files
+    Hint: Run `jj util check-templates --update golden` to accept the new output.
+    ");
+}
@@ -39,6 +39,8 @@ fn test_snapshot_large_file() {
         This will increase the maximum file size allowed for new files, in this repository only.
       - Run `jj --config snapshot.max-new-file-size=13 st`
         This will increase the maximum file size allowed for new files, for this command only.
+      - Run `jj file track --force large`
+        This will track the files regardless of their size.
     ");
 
     // test with a larger file using 'KB' human-readable syntax
@@ -56,6 +58,8 @@ fn test_snapshot_large_file() {
         This will increase the maximum file size allowed for new files, in this repository only.
       - Run `jj --config snapshot.max-new-file-size=11264 st`
         This will increase the maximum file size allowed for new files, for this command only.
+      - Run `jj file track --force large`
+        This will track the files regardless of their size.
     ");
 
     // test invalid configuration
@@ -89,6 +93,63 @@ fn test_snapshot_large_file() {
     insta::assert_snapshot!(stderr, @"");
 }
 
+#[test]
+fn test_snapshot_large_file_overrides() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+    test_env.add_config(indoc! {r#"
+        snapshot.max-new-file-size = 10
+        [snapshot.max-new-file-size-overrides]
+        "glob:assets/**" = "1KiB"
+        "glob:**/*.txt" = 0
+    "#});
+
+    std::fs::create_dir(repo_path.join("assets")).unwrap();
+    std::fs::write(repo_path.join("assets").join("image"), "a lot of text").unwrap();
+    std::fs::write(repo_path.join("large"), "a lot of text").unwrap();
+    std::fs::write(repo_path.join("large.txt"), "a lot of text").unwrap();
+    let (stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["file", "list"]);
+    insta::assert_snapshot!(stdout.replace('\\', "/"), @r"
+    assets/image
+    large.txt
+    ");
+    insta::assert_snapshot!(stderr, @r"
+    Warning: Refused to snapshot some files:
+      large: 13.0B (13 bytes); the maximum size allowed is 10.0B (10 bytes)
+    Hint: This is to prevent large files from being added by accident. You can fix this by:
+      - Adding the file to `.gitignore`
+      - Run `jj config set --repo snapshot.max-new-file-size 13`
+        This will increase the maximum file size allowed for new files, in this repository only.
+      - Run `jj --config snapshot.max-new-file-size=13 st`
+        This will increase the maximum file size allowed for new files, for this command only.
+      - Run `jj file track --force large`
+        This will track the files regardless of their size.
+    ");
+
+    // The limit reported for a path comes from the override that applies.
+    // Overrides from later config files take precedence, and paths with
+    // special characters are quoted in the hint.
+    test_env.add_config(r#"snapshot.max-new-file-size-overrides."glob:small*" = 5"#);
+    std::fs::write(repo_path.join("small.bin"), "123456").unwrap();
+    std::fs::write(repo_path.join("small (1).txt"), "123456").unwrap();
+    let (_stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["file", "list"]);
+    insta::assert_snapshot!(stderr, @r#"
+    Warning: Refused to snapshot some files:
+      large: 13.0B (13 bytes); the maximum size allowed is 10.0B (10 bytes)
+      small (1).txt: 6.0B (6 bytes); the maximum size allowed is 5.0B (5 bytes)
+      small.bin: 6.0B (6 bytes); the maximum size allowed is 5.0B (5 bytes)
+    Hint: This is to prevent large files from being added by accident. You can fix this by:
+      - Adding the file to `.gitignore`
+      - Run `jj config set --repo snapshot.max-new-file-size 13`
+        This will increase the maximum file size allowed for new files, in this repository only.
+      - Run `jj --config snapshot.max-new-file-size=13 st`
+        This will increase the maximum file size allowed for new files, for this command only.
+      - Run `jj file track --force large 'file:"small (1).txt"' small.bin`
+        This will track the files regardless of their size.
+    "#);
+}
+
 #[test]
 fn test_snapshot_large_file_restore() {
     let test_env = TestEnvironment::default();
@@ -114,6 +175,8 @@ fn test_snapshot_large_file_restore() {
         This will increase the maximum file size allowed for new files, in this repository only.
       - Run `jj --config snapshot.max-new-file-size=13 st`
         This will increase the maximum file size allowed for new files, for this command only.
+      - Run `jj file track --force file`
+        This will track the files regardless of their size.
     Created kkmpptxz e3eb7e81 (no description set)
     Working copy now at: kkmpptxz e3eb7e81 (no description set)
     Parent commit      : zzzzzzzz 00000000 (empty) (no description set)
@@ -238,6 +301,156 @@ fn test_materialize_and_snapshot_different_conflict_markers() {
     "##);
 }
 
+#[test]
+fn test_conflict_marker_style_overrides() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    // Use zdiff3-style markers for files in "zdiff3" only
+    test_env.add_config(
+        r#"
+        [[ui.conflict-marker-style-overrides]]
+        files = "zdiff3"
+        style = "zdiff3"
+        "#,
+    );
+
+    for dir in ["default", "zdiff3"] {
+        std::fs::create_dir(repo_path.join(dir)).unwrap();
+        std::fs::write(repo_path.join(dir).join("file"), "line 1\nline 2\n").unwrap();
+    }
+    test_env.jj_cmd_ok(&repo_path, &["commit", "-m", "base"]);
+    for dir in ["default", "zdiff3"] {
+        std::fs::write(
+            repo_path.join(dir).join("file"),
+            "line 1\ncommon\nleft\nline 2\n",
+        )
+        .unwrap();
+    }
+    test_env.jj_cmd_ok(&repo_path, &["commit", "-m", "side-a"]);
+    test_env.jj_cmd_ok(&repo_path, &["new", "description(base)", "-m", "side-b"]);
+    for dir in ["default", "zdiff3"] {
+        std::fs::write(
+            repo_path.join(dir).join("file"),
+            "line 1\ncommon\nright\nline 2\n",
+        )
+        .unwrap();
+    }
+    test_env.jj_cmd_ok(
+        &repo_path,
+        &["new", "description(side-a)", "description(side-b)"],
+    );
+
+    let default_file = repo_path.join("default").join("file");
+    let zdiff3_file = repo_path.join("zdiff3").join("file");
+    insta::assert_snapshot!(std::fs::read_to_string(&default_file).unwrap(), @r"
+    line 1
+    <<<<<<< Conflict 1 of 1
+    %%%%%%% Changes from base to side #1
+    +common
+    +left
+    +++++++ Contents of side #2
+    common
+    right
+    >>>>>>> Conflict 1 of 1 ends
+    line 2
+    ");
+    insta::assert_snapshot!(std::fs::read_to_string(&zdiff3_file).unwrap(), @r"
+    line 1
+    common
+    <<<<<<< Side #1 (Conflict 1 of 1)
+    left
+    ||||||| Base
+    =======
+    right
+    >>>>>>> Side #2 (Conflict 1 of 1 ends)
+    line 2
+    ");
+
+    // The overridden markers are parsed when the working copy is snapshotted
+    std::fs::write(
+        &zdiff3_file,
+        indoc! {"
+            line 1
+            common
+            <<<<<<<
+            left - edited
+            |||||||
+            =======
+            right
+            >>>>>>>
+            line 2
+        "},
+    )
+    .unwrap();
+    let stdout = test_env.jj_cmd_success(&repo_path, &["diff", "--git", "zdiff3"]);
+    insta::assert_snapshot!(stdout, @r"
+    diff --git a/zdiff3/file b/zdiff3/file
+    --- a/zdiff3/file
+    +++ b/zdiff3/file
+    @@ -1,10 +1,9 @@
+     line 1
+    +common
+     <<<<<<< Conflict 1 of 1
+    -%%%%%%% Changes from base to side #1
+    -+common
+    -+left
+    -+++++++ Contents of side #2
+    -common
+    -right
+    ++++++++ Contents of side #1
+    +left - edited
+    +%%%%%%% Changes from base to side #2
+    ++right
+     >>>>>>> Conflict 1 of 1 ends
+     line 2
+    ");
+}
+
+#[test]
+fn test_conflict_marker_style_arg() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+    let file_path = repo_path.join("file");
+
+    std::fs::write(&file_path, "base\n").unwrap();
+    test_env.jj_cmd_ok(&repo_path, &["commit", "-m", "base"]);
+    std::fs::write(&file_path, "left\n").unwrap();
+    test_env.jj_cmd_ok(&repo_path, &["commit", "-m", "side-a"]);
+    test_env.jj_cmd_ok(&repo_path, &["new", "description(base)", "-m", "side-b"]);
+    std::fs::write(&file_path, "right\n").unwrap();
+
+    // The argument overrides the configured style
+    test_env.jj_cmd_ok(
+        &repo_path,
+        &[
+            "new",
+            "description(side-a)",
+            "description(side-b)",
+            "--conflict-marker-style=git",
+        ],
+    );
+    insta::assert_snapshot!(std::fs::read_to_string(&file_path).unwrap(), @r"
+    <<<<<<< Side #1 (Conflict 1 of 1)
+    left
+    ||||||| Base
+    base
+    =======
+    right
+    >>>>>>> Side #2 (Conflict 1 of 1 ends)
+    ");
+
+    let stderr = test_env.jj_cmd_cli_error(&repo_path, &["st", "--conflict-marker-style=bad"]);
+    insta::assert_snapshot!(stderr, @r"
+    error: invalid value 'bad' for '--conflict-marker-style <STYLE>'
+      [possible values: diff, snapshot, git, zdiff3]
+
+    For more information, try '--help'.
+    ");
+}
+
 #[test]
 fn test_snapshot_invalid_ignore_pattern() {
     let test_env = TestEnvironment::default();
@@ -373,15 +586,18 @@ fn test_conflict_marker_length_stored_in_working_copy() {
 
     // The file should still be conflicted, and the new content should be saved
     let stdout = test_env.jj_cmd_success(&repo_path, &["st"]);
-    insta::assert_snapshot!(stdout, @r#"
+    insta::assert_snapshot!(stdout, @r"
     Working copy changes:
     M file
     There are unresolved conflicts at these paths:
     file    2-sided conflict
+      side #1: rlvkpnrz ce613b49 side-a
+      side #2: (unknown)
+    0 of 1 conflicts from the parent commit appear resolved, 1 partially edited. Run `jj status` again after finishing.
     Working copy : mzvwutvl 3a981880 (conflict) (no description set)
     Parent commit: rlvkpnrz ce613b49 side-a
     Parent commit: zsuskuln 7b2b03ab side-b
-    "#);
+    ");
     insta::assert_snapshot!(test_env.jj_cmd_success(&repo_path, &["diff", "--git"]), @r##"
     diff --git a/file b/file
     --- a/file
@@ -442,3 +658,46 @@ fn test_conflict_marker_length_stored_in_working_copy() {
     Normal { <executable> }           130 <timestamp> None "file"
     "#);
 }
+
+#[test]
+fn test_snapshot_auto_disabled() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+    test_env.add_config("snapshot.auto = false");
+
+    std::fs::write(repo_path.join("file"), "contents\n").unwrap();
+
+    // Read-only commands don't snapshot the working copy
+    let (stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["diff", "--summary"]);
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r###"
+    Working copy not snapshotted (snapshot.auto = false); run `jj snapshot` to record recent changes
+    "###);
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &["op", "log", "--ignore-working-copy", "-Tdescription"],
+    );
+    assert!(!stdout.contains("snapshot working copy"));
+
+    // `jj snapshot` does
+    let (stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["snapshot"]);
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @"");
+    let (stdout, _stderr) = test_env.jj_cmd_ok(&repo_path, &["diff", "--summary"]);
+    insta::assert_snapshot!(stdout, @r###"
+    A file
+    "###);
+
+    // Other commands still snapshot the working copy
+    std::fs::write(repo_path.join("file2"), "contents\n").unwrap();
+    let stdout = test_env.jj_cmd_success(&repo_path, &["file", "list"]);
+    insta::assert_snapshot!(stdout, @r###"
+    file
+    file2
+    "###);
+
+    // --quiet suppresses the notice
+    let (_stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["log", "--quiet", "-r@"]);
+    insta::assert_snapshot!(stderr, @"");
+}
@@ -82,6 +82,7 @@ fn test_workspaces_sparse_patterns() {
     let ws4_path = test_env.env_root().join("ws4");
     let ws5_path = test_env.env_root().join("ws5");
     let ws6_path = test_env.env_root().join("ws6");
+    let ws7_path = test_env.env_root().join("ws7");
 
     test_env.jj_cmd_ok(&ws1_path, &["sparse", "set", "--clear", "--add=foo"]);
     test_env.jj_cmd_ok(&ws1_path, &["workspace", "add", "../ws2"]);
@@ -120,6 +121,36 @@ fn test_workspaces_sparse_patterns() {
     );
     let stdout = test_env.jj_cmd_success(&ws6_path, &["sparse", "list"]);
     insta::assert_snapshot!(stdout, @"");
+
+    // --sparse-profile uses the patterns of the profile from the repo config
+    test_env.jj_cmd_ok(
+        &ws1_path,
+        &[
+            "config",
+            "set",
+            "--repo",
+            "sparse.profiles.docs",
+            r#"["docs", "glob:*.md"]"#,
+        ],
+    );
+    test_env.jj_cmd_ok(
+        &ws3_path,
+        &["workspace", "add", "--sparse-profile=docs", "../ws7"],
+    );
+    let stdout = test_env.jj_cmd_success(&ws7_path, &["sparse", "list"]);
+    insta::assert_snapshot!(stdout, @r###"
+    docs
+    glob:*.md
+    "###);
+    let stderr = test_env.jj_cmd_failure(
+        &ws3_path,
+        &["workspace", "add", "--sparse-profile=unknown", "../ws8"],
+    );
+    insta::assert_snapshot!(stderr, @r###"
+    Error: Sparse profile 'unknown' is not defined
+    Hint: Defined profiles: docs
+    "###);
+    assert!(!test_env.env_root().join("ws8").exists());
 }
 
 /// Test adding a second workspace while the current workspace is editing a
@@ -300,6 +331,28 @@ fn test_workspaces_add_workspace_at_revision() {
     "###);
 }
 
+/// Test that nothing is created if the '-r' revision can't be resolved
+#[test]
+fn test_workspaces_add_workspace_at_unknown_revision() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "main"]);
+    let main_path = test_env.env_root().join("main");
+    let secondary_path = test_env.env_root().join("secondary");
+
+    let stderr = test_env.jj_cmd_failure(
+        &main_path,
+        &["workspace", "add", "../secondary", "-r", "nonexistent"],
+    );
+    insta::assert_snapshot!(stderr, @r###"
+    Error: Revision "nonexistent" doesn't exist
+    "###);
+    assert!(!secondary_path.exists());
+    let stdout = test_env.jj_cmd_success(&main_path, &["workspace", "list"]);
+    insta::assert_snapshot!(stdout, @r###"
+    default: qpvuntsm 230dd059 (empty) (no description set)
+    "###);
+}
+
 /// Test multiple `-r` flags to `workspace add` to create a workspace
 /// working-copy commit with multiple parents.
 #[test]
@@ -1107,6 +1160,105 @@ fn test_list_workspaces_template() {
     "###);
 }
 
+/// Test listing the recorded path, staleness, and sparse patterns
+#[test]
+fn test_list_workspaces_verbose() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "main"]);
+    let main_path = test_env.env_root().join("main");
+    let secondary_path = test_env.env_root().join("secondary");
+
+    std::fs::write(main_path.join("file1"), "contents\n").unwrap();
+    std::fs::write(main_path.join("file2"), "contents\n").unwrap();
+    test_env.jj_cmd_ok(&main_path, &["new"]);
+    test_env.jj_cmd_ok(&main_path, &["workspace", "add", "../secondary"]);
+    test_env.jj_cmd_ok(
+        &secondary_path,
+        &["sparse", "set", "--clear", "--add", "file1"],
+    );
+
+    let stdout = test_env.jj_cmd_success(&main_path, &["workspace", "list", "--verbose"]);
+    insta::assert_snapshot!(stdout.replace('\\', "/"), @r"
+    default: rlvkpnrz 8eb6584b (empty) (no description set)
+      Path: $TEST_ENV/main
+      Status: up to date
+      Last operation: 543e3bab1b79
+      Sparse patterns:
+        .
+    secondary: pmmvwywv b0d78f83 (empty) (no description set)
+      Path: $TEST_ENV/secondary
+      Status: up to date
+      Last operation: 543e3bab1b79
+      Sparse patterns:
+        file1
+    ");
+
+    // Rewrite the working-copy commit of the secondary workspace from the main
+    // workspace.
+    std::fs::write(main_path.join("file1"), "changed in main\n").unwrap();
+    test_env.jj_cmd_ok(&main_path, &["squash"]);
+    let stdout = test_env.jj_cmd_success(&main_path, &["workspace", "list", "--verbose"]);
+    insta::assert_snapshot!(stdout.replace('\\', "/"), @r"
+    default: royxmykx b623b2ab (empty) (no description set)
+      Path: $TEST_ENV/main
+      Status: up to date
+      Last operation: 1e2c04ebaf4a
+      Sparse patterns:
+        .
+    secondary: pmmvwywv 0d5bd2eb (empty) (no description set)
+      Path: $TEST_ENV/secondary
+      Status: stale
+      Last operation: 543e3bab1b79
+      Sparse patterns:
+        file1
+    ");
+
+    test_env.jj_cmd_ok(&secondary_path, &["workspace", "update-stale"]);
+    let template =
+        r#"name ++ ": " ++ path ++ " stale=" ++ stale ++ " sparse=" ++ sparse_patterns ++ "\n""#;
+    let stdout = test_env.jj_cmd_success(&main_path, &["workspace", "list", "-T", template]);
+    insta::assert_snapshot!(stdout.replace('\\', "/"), @r"
+    default: $TEST_ENV/main stale=false sparse=.
+    secondary: $TEST_ENV/secondary stale=false sparse=file1
+    ");
+}
+
+/// Test that forgetting a workspace removes its recorded details
+#[test]
+fn test_list_workspaces_verbose_forgotten() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "main"]);
+    let main_path = test_env.env_root().join("main");
+
+    test_env.jj_cmd_ok(&main_path, &["workspace", "add", "../secondary"]);
+    test_env.jj_cmd_ok(&main_path, &["workspace", "forget", "secondary"]);
+    let stdout = test_env.jj_cmd_success(&main_path, &["workspace", "list", "--verbose"]);
+    insta::assert_snapshot!(stdout.replace('\\', "/"), @r"
+    default: qpvuntsm 230dd059 (empty) (no description set)
+      Path: $TEST_ENV/main
+      Status: up to date
+      Last operation: cbcf3d5418e9
+      Sparse patterns:
+        .
+    ");
+
+    // The working-copy commit is restored by undo, but the details aren't
+    // known until the workspace is used again.
+    test_env.jj_cmd_ok(&main_path, &["op", "undo"]);
+    let stdout = test_env.jj_cmd_success(&main_path, &["workspace", "list", "--verbose"]);
+    insta::assert_snapshot!(stdout.replace('\\', "/"), @r"
+    default: qpvuntsm 230dd059 (empty) (no description set)
+      Path: $TEST_ENV/main
+      Status: up to date
+      Last operation: 7441c5ff731d
+      Sparse patterns:
+        .
+    secondary: uuqppmxq 57d63245 (empty) (no description set)
+      Path: (unknown)
+      Status: unknown
+    ");
+}
+
 /// Test getting the workspace root from primary and secondary workspaces
 #[test]
 fn test_workspaces_root() {
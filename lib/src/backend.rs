@@ -391,6 +391,54 @@ pub fn make_root_commit(root_change_id: ChangeId, empty_tree_id: TreeId) -> Comm
     }
 }
 
+/// ID of an object that can be fetched on demand by a
+/// [`PartialFetchBackend`].
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub enum FetchableObjectId {
+    File(FileId),
+    Symlink(SymlinkId),
+    Tree(TreeId),
+}
+
+/// Backend capability to fetch objects that are missing locally, e.g. because
+/// the repo is a partial clone.
+pub trait PartialFetchBackend: Send + Sync {
+    /// Fetches the given objects in as few requests as possible. Objects that
+    /// are already available locally are skipped.
+    fn fetch_objects(&self, ids: &[FetchableObjectId]) -> BackendResult<()>;
+}
+
+/// Type of an object stored in a backend.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum ObjectKind {
+    Commit,
+    Tree,
+    File,
+    Symlink,
+    Conflict,
+}
+
+/// Object listed by an [`EnumerableBackend`].
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct StoredObject {
+    pub kind: ObjectKind,
+    /// Id of the object in binary form.
+    pub id: Vec<u8>,
+    /// Size of the object in bytes. Depending on the backend, this is the
+    /// size of the content or of its stored (e.g. compressed) form.
+    pub size: u64,
+}
+
+/// Backend capability to list the objects in its storage, e.g. for reporting
+/// statistics.
+pub trait EnumerableBackend: Send + Sync {
+    /// Returns all objects in the storage, including unreachable ones. Each
+    /// object is listed once. If the backend can't tell some kinds apart (like
+    /// Git blobs, which may be files, symlinks, or conflicts), the object is
+    /// reported as the most common kind.
+    fn all_objects(&self) -> BackendResult<Vec<StoredObject>>;
+}
+
 /// Defines the interface for commit backends.
 #[async_trait]
 pub trait Backend: Send + Sync + Debug {
@@ -421,6 +469,21 @@ pub trait Backend: Send + Sync + Debug {
     /// sent.
     fn concurrency(&self) -> usize;
 
+    /// Returns the interface to fetch missing objects on demand if the
+    /// backend's local storage may be incomplete.
+    ///
+    /// If this returns `Some`, the [`Store`](crate::store::Store) fetches an
+    /// object when it isn't found locally, and retries the read.
+    fn partial_fetch(&self) -> Option<&dyn PartialFetchBackend> {
+        None
+    }
+
+    /// Returns the interface to list the stored objects if the backend
+    /// supports it.
+    fn enumerable(&self) -> Option<&dyn EnumerableBackend> {
+        None
+    }
+
     async fn read_file(&self, path: &RepoPath, id: &FileId) -> BackendResult<Box<dyn Read>>;
 
     async fn write_file(
@@ -148,6 +148,13 @@ impl CommitBuilder<'_> {
     pub fn abandon(self) {
         self.inner.abandon(self.mut_repo);
     }
+
+    /// Records the old commit as rewritten to the `existing` commit instead of
+    /// writing new commit. The `existing` commit should be equivalent to the
+    /// one that would be written.
+    pub fn reuse(self, existing: Commit) -> Commit {
+        self.inner.reuse(self.mut_repo, existing)
+    }
 }
 
 /// Like `CommitBuilder`, but doesn't mutably borrow `MutableRepo`.
@@ -356,6 +363,17 @@ impl DetachedCommitBuilder {
         write_to_store(&self.store, self.commit.clone(), &self.sign_settings)
     }
 
+    /// Records the old commit as rewritten to the `existing` commit in the
+    /// `mut_repo` instead of writing new commit.
+    pub fn reuse(self, mut_repo: &mut MutableRepo, existing: Commit) -> Commit {
+        if let Some(rewrite_source) = self.rewrite_source {
+            if rewrite_source.change_id() == existing.change_id() {
+                mut_repo.set_rewritten_commit(rewrite_source.id().clone(), existing.id().clone());
+            }
+        }
+        existing
+    }
+
     /// Records the old commit as abandoned in the `mut_repo`.
     ///
     /// This is noop if there's no old commit that would be rewritten to the new
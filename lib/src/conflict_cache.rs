@@ -0,0 +1,238 @@
+// Copyright 2026 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Disposable on-disk cache of materialized file conflicts.
+//!
+//! Materializing a conflict in a large file requires a full diff of all terms,
+//! which can be slow. Since the result only depends on the file ids and the
+//! materialization parameters, it can be cached by content and reused by
+//! later commands. Currently only the external merge tools of `jj resolve` use
+//! the cache, so that invoking it repeatedly for the same set of conflicts is
+//! fast.
+
+use std::fs;
+use std::io;
+use std::io::Write as _;
+use std::path::Path;
+use std::path::PathBuf;
+use std::time::SystemTime;
+
+use bstr::BString;
+use tempfile::NamedTempFile;
+
+use crate::backend::FileId;
+use crate::conflicts::choose_materialized_conflict_marker_len;
+use crate::conflicts::materialize_merge_result_to_bytes_with_marker_len;
+use crate::conflicts::ConflictMarkerStyle;
+use crate::content_hash::blake2b_hash;
+use crate::content_hash::ContentHash;
+use crate::file_util::create_or_reuse_dir;
+use crate::file_util::persist_content_addressed_temp_file;
+use crate::file_util::IoResultExt as _;
+use crate::file_util::PathError;
+use crate::merge::Merge;
+
+/// On-disk cache of materialized file conflicts, located under the repo
+/// directory.
+///
+/// Entries are keyed by the conflicted file ids, so they never become stale.
+/// Failures to read or write the cache are not errors; the conflict is just
+/// materialized again.
+#[derive(Debug)]
+pub struct MaterializedConflictCache {
+    dir: PathBuf,
+}
+
+#[derive(ContentHash)]
+struct EntryKey {
+    file_ids: Merge<Option<FileId>>,
+    conflict_marker_style: String,
+    conflict_marker_len: Option<u64>,
+}
+
+impl MaterializedConflictCache {
+    /// Name of the cache directory within the repo directory.
+    pub fn dir_name() -> &'static str {
+        "conflict_cache"
+    }
+
+    /// Loads the cache of the repo at `repo_path`. The directory is created
+    /// lazily when the first entry is written.
+    pub fn load(repo_path: &Path) -> Self {
+        MaterializedConflictCache {
+            dir: repo_path.join(Self::dir_name()),
+        }
+    }
+
+    /// Returns the key identifying the materialized form of the conflict.
+    /// `None` marker length means the length is chosen from the contents.
+    pub fn entry_key(
+        file_ids: &Merge<Option<FileId>>,
+        conflict_marker_style: ConflictMarkerStyle,
+        conflict_marker_len: Option<usize>,
+    ) -> String {
+        let style = match conflict_marker_style {
+            ConflictMarkerStyle::Diff => "diff",
+            ConflictMarkerStyle::Snapshot => "snapshot",
+            ConflictMarkerStyle::Git => "git",
+            ConflictMarkerStyle::Zdiff3 => "zdiff3",
+        };
+        let key = EntryKey {
+            file_ids: file_ids.clone().simplify(),
+            conflict_marker_style: style.to_owned(),
+            conflict_marker_len: conflict_marker_len.map(|len| len as u64),
+        };
+        hex::encode(blake2b_hash(&key))
+    }
+
+    /// Looks up the materialized conflict.
+    pub fn get(
+        &self,
+        file_ids: &Merge<Option<FileId>>,
+        conflict_marker_style: ConflictMarkerStyle,
+        conflict_marker_len: Option<usize>,
+    ) -> Option<BString> {
+        let key = Self::entry_key(file_ids, conflict_marker_style, conflict_marker_len);
+        match fs::read(self.dir.join(&key)) {
+            Ok(data) => Some(data.into()),
+            Err(err) => {
+                if err.kind() != io::ErrorKind::NotFound {
+                    tracing::warn!(?err, %key, "failed to read materialized conflict");
+                }
+                None
+            }
+        }
+    }
+
+    /// Stores the materialized conflict. Errors are logged and ignored.
+    pub fn insert(
+        &self,
+        file_ids: &Merge<Option<FileId>>,
+        conflict_marker_style: ConflictMarkerStyle,
+        conflict_marker_len: Option<usize>,
+        data: &[u8],
+    ) {
+        let key = Self::entry_key(file_ids, conflict_marker_style, conflict_marker_len);
+        if let Err(err) = self.write_entry(&key, data) {
+            tracing::warn!(?err, %key, "failed to cache materialized conflict");
+        }
+    }
+
+    fn write_entry(&self, key: &str, data: &[u8]) -> Result<(), PathError> {
+        create_or_reuse_dir(&self.dir).context(&self.dir)?;
+        let mut temp_file = NamedTempFile::new_in(&self.dir).context(&self.dir)?;
+        temp_file.write_all(data).context(temp_file.path())?;
+        let path = self.dir.join(key);
+        persist_content_addressed_temp_file(temp_file, &path).context(&path)?;
+        Ok(())
+    }
+
+    /// Materializes the conflict with the given `contents` of the `file_ids`,
+    /// or returns the cached result. `None` marker length means the length is
+    /// chosen from the contents.
+    pub fn materialize(
+        &self,
+        file_ids: &Merge<Option<FileId>>,
+        contents: &Merge<BString>,
+        conflict_marker_style: ConflictMarkerStyle,
+        conflict_marker_len: Option<usize>,
+    ) -> BString {
+        if let Some(data) = self.get(file_ids, conflict_marker_style, conflict_marker_len) {
+            return data;
+        }
+        let data = materialize_merge_result_to_bytes_with_marker_len(
+            contents,
+            conflict_marker_style,
+            conflict_marker_len
+                .unwrap_or_else(|| choose_materialized_conflict_marker_len(contents)),
+        );
+        self.insert(file_ids, conflict_marker_style, conflict_marker_len, &data);
+        data
+    }
+
+    /// Removes entries that haven't been written since `keep_newer`.
+    pub fn gc(&self, keep_newer: SystemTime) -> Result<(), PathError> {
+        let entries = match fs::read_dir(&self.dir) {
+            Ok(entries) => entries,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(()),
+            Err(err) => {
+                let path = self.dir.clone();
+                return Err(PathError { path, error: err });
+            }
+        };
+        for entry in entries {
+            let entry = entry.context(&self.dir)?;
+            let path = entry.path();
+            let modified = entry
+                .metadata()
+                .and_then(|metadata| metadata.modified())
+                .context(&path)?;
+            if modified < keep_newer {
+                match fs::remove_file(&path) {
+                    Ok(()) => {}
+                    // Removed concurrently
+                    Err(err) if err.kind() == io::ErrorKind::NotFound => {}
+                    Err(err) => return Err(PathError { path, error: err }),
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+
+    fn file_ids(ids: [&str; 3]) -> Merge<Option<FileId>> {
+        let ids = ids.map(|id| Some(FileId::new(id.as_bytes().to_vec())));
+        Merge::from_vec(ids.to_vec())
+    }
+
+    #[test]
+    fn test_materialize_cached() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let cache = MaterializedConflictCache::load(temp_dir.path());
+        let ids = file_ids(["left", "base", "right"]);
+        let contents = Merge::from_vec(vec![
+            BString::from("a\n"),
+            BString::from("b\n"),
+            BString::from("c\n"),
+        ]);
+        let style = ConflictMarkerStyle::Git;
+        assert_eq!(cache.get(&ids, style, None), None);
+
+        let data = cache.materialize(&ids, &contents, style, None);
+        assert!(data.starts_with(b"<<<<<<<"));
+        assert_eq!(cache.get(&ids, style, None), Some(data.clone()));
+        // Different parameters are cached separately
+        assert_eq!(cache.get(&ids, ConflictMarkerStyle::Diff, None), None);
+        assert_eq!(cache.get(&ids, style, Some(7)), None);
+
+        // The cached data is returned without looking at the contents
+        let other_contents = Merge::resolved(BString::from("x\n"));
+        assert_eq!(cache.materialize(&ids, &other_contents, style, None), data);
+
+        cache
+            .gc(SystemTime::now() - Duration::from_secs(60))
+            .unwrap();
+        assert_eq!(cache.get(&ids, style, None), Some(data));
+        cache
+            .gc(SystemTime::now() + Duration::from_secs(60))
+            .unwrap();
+        assert_eq!(cache.get(&ids, style, None), None);
+    }
+}
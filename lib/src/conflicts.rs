@@ -14,10 +14,14 @@
 
 #![allow(missing_docs)]
 
+use std::collections::HashSet;
+use std::collections::VecDeque;
+use std::fmt::Debug;
 use std::io;
 use std::io::Read;
 use std::io::Write;
 use std::iter::zip;
+use std::sync::Arc;
 
 use bstr::BString;
 use bstr::ByteSlice;
@@ -36,6 +40,7 @@ use crate::backend::FileId;
 use crate::backend::SymlinkId;
 use crate::backend::TreeId;
 use crate::backend::TreeValue;
+use crate::commit::Commit;
 use crate::copies::CopiesTreeDiffEntry;
 use crate::copies::CopiesTreeDiffEntryPath;
 use crate::diff::Diff;
@@ -43,6 +48,7 @@ use crate::diff::DiffHunk;
 use crate::diff::DiffHunkKind;
 use crate::files;
 use crate::files::MergeResult;
+use crate::matchers::Matcher;
 use crate::merge::Merge;
 use crate::merge::MergeBuilder;
 use crate::merge::MergedTreeValue;
@@ -228,6 +234,10 @@ pub enum ConflictMarkerStyle {
     Snapshot,
     /// Style which replicates Git's "diff3" style to support external tools.
     Git,
+    /// Like [`ConflictMarkerStyle::Git`], but lines added identically by both
+    /// sides at the start or end of a conflict are moved out of it, like Git's
+    /// "zdiff3" style.
+    Zdiff3,
 }
 
 /// Characters which can be repeated to form a conflict marker line when
@@ -433,6 +443,19 @@ fn materialize_conflict_hunks(
                         output,
                     )?;
                 }
+                (ConflictMarkerStyle::Zdiff3, [left, base, right]) => {
+                    let (prefix, left, right, suffix) = split_common_lines(left, right);
+                    output.write_all(prefix)?;
+                    materialize_git_style_conflict(
+                        left,
+                        base,
+                        right,
+                        &conflict_info,
+                        conflict_marker_len,
+                        output,
+                    )?;
+                    output.write_all(suffix)?;
+                }
                 _ => {
                     materialize_jj_style_conflict(
                         hunk,
@@ -448,6 +471,34 @@ fn materialize_conflict_hunks(
     Ok(())
 }
 
+/// Splits the lines shared by `left` and `right` at their start and end off
+/// the differing middle parts. Returns `(prefix, left, right, suffix)`.
+fn split_common_lines<'a>(
+    left: &'a [u8],
+    right: &'a [u8],
+) -> (&'a [u8], &'a [u8], &'a [u8], &'a [u8]) {
+    let prefix_len: usize = zip(left.lines_with_terminator(), right.lines_with_terminator())
+        .take_while(|(l, r)| l == r)
+        .map(|(l, _)| l.len())
+        .sum();
+    let (left_rest, right_rest) = (&left[prefix_len..], &right[prefix_len..]);
+    // Only whole lines are moved to the suffix, so the last lines must be
+    // terminated the same way.
+    let suffix_len: usize = zip(
+        left_rest.lines_with_terminator().rev(),
+        right_rest.lines_with_terminator().rev(),
+    )
+    .take_while(|(l, r)| l == r)
+    .map(|(l, _)| l.len())
+    .sum();
+    (
+        &left[..prefix_len],
+        &left_rest[..left_rest.len() - suffix_len],
+        &right_rest[..right_rest.len() - suffix_len],
+        &left_rest[left_rest.len() - suffix_len..],
+    )
+}
+
 fn materialize_git_style_conflict(
     left: &[u8],
     base: &[u8],
@@ -905,6 +956,19 @@ pub async fn update_from_content(
         }
     }
 
+    write_conflict_sides(file_ids, used_file_ids, contents, store, path, content).await
+}
+
+/// Writes the parsed `contents` of each side of the conflict `used_file_ids`,
+/// which is either `file_ids` or its simplified form, to the store.
+async fn write_conflict_sides(
+    file_ids: &Merge<Option<FileId>>,
+    used_file_ids: &Merge<Option<FileId>>,
+    contents: Merge<Vec<u8>>,
+    store: &Store,
+    path: &RepoPath,
+    content: &[u8],
+) -> BackendResult<Merge<Option<FileId>>> {
     // If the user edited the empty placeholder for an absent side, we consider the
     // conflict resolved.
     if zip(contents.iter(), used_file_ids.iter())
@@ -914,8 +978,7 @@ pub async fn update_from_content(
         return Ok(Merge::normal(file_id));
     }
 
-    // Now write the new files contents we found by parsing the file with conflict
-    // markers.
+    // Now write the new files contents we found by parsing the file.
     // TODO: Write these concurrently
     let new_file_ids: Vec<Option<FileId>> = zip(contents.iter(), used_file_ids.iter())
         .map(|(content, file_id)| -> BackendResult<Option<FileId>> {
@@ -944,3 +1007,190 @@ pub async fn update_from_content(
     };
     Ok(new_file_ids)
 }
+
+/// Materializes file conflicts at certain paths in a custom format instead of
+/// with conflict markers.
+///
+/// This allows tools which understand a file format, such as a lock file, to
+/// process the conflicts written to the working copy. The format must be
+/// parseable back by [`ConflictMaterializer::parse()`] when the working copy
+/// is snapshotted.
+pub trait ConflictMaterializer: Debug + Send + Sync {
+    /// Returns true if conflicts at `path` should be materialized by this.
+    fn matches(&self, path: &RepoPath) -> bool;
+
+    /// Renders the conflicted file. `contents` has one term per side and
+    /// base, as returned by [`extract_as_single_hunk()`].
+    fn materialize(&self, path: &RepoPath, contents: &Merge<BString>) -> Vec<u8>;
+
+    /// Parses `content` previously written by [`Self::materialize()`] and
+    /// possibly edited since, into a conflict with `num_sides` sides.
+    ///
+    /// Returns `None` if the content no longer represents a conflict. It will
+    /// then be taken as the resolved file content.
+    fn parse(&self, path: &RepoPath, content: &[u8], num_sides: usize) -> Option<Merge<BString>>;
+}
+
+/// Set of [`ConflictMaterializer`]s. Conflicts at paths not matched by any of
+/// them are materialized with conflict markers.
+#[derive(Clone, Debug, Default)]
+pub struct ConflictMaterializers {
+    materializers: Vec<Arc<dyn ConflictMaterializer>>,
+}
+
+impl ConflictMaterializers {
+    /// Creates an empty set.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a materializer. Materializers added earlier take precedence.
+    pub fn add(&mut self, materializer: Arc<dyn ConflictMaterializer>) {
+        self.materializers.push(materializer);
+    }
+
+    /// Returns the materializer to use for conflicts at `path`, if any.
+    pub fn find(&self, path: &RepoPath) -> Option<&dyn ConflictMaterializer> {
+        self.materializers
+            .iter()
+            .find(|materializer| materializer.matches(path))
+            .map(|materializer| materializer.as_ref())
+    }
+}
+
+/// Materializes conflicts at the paths matched by a [`Matcher`] with conflict
+/// markers of a fixed style, overriding the default style for those paths.
+#[derive(Debug)]
+pub struct ConflictMarkerStyleMaterializer {
+    matcher: Box<dyn Matcher>,
+    conflict_marker_style: ConflictMarkerStyle,
+}
+
+impl ConflictMarkerStyleMaterializer {
+    /// Creates a materializer using `conflict_marker_style` for the paths
+    /// matched by `matcher`.
+    pub fn new(matcher: Box<dyn Matcher>, conflict_marker_style: ConflictMarkerStyle) -> Self {
+        ConflictMarkerStyleMaterializer {
+            matcher,
+            conflict_marker_style,
+        }
+    }
+}
+
+impl ConflictMaterializer for ConflictMarkerStyleMaterializer {
+    fn matches(&self, path: &RepoPath) -> bool {
+        self.matcher.matches(path)
+    }
+
+    fn materialize(&self, _path: &RepoPath, contents: &Merge<BString>) -> Vec<u8> {
+        materialize_merge_result_to_bytes(contents, self.conflict_marker_style).into()
+    }
+
+    fn parse(&self, _path: &RepoPath, content: &[u8], num_sides: usize) -> Option<Merge<BString>> {
+        // The marker length isn't recorded for custom materializers, but it
+        // can be recovered from the conflict start markers. Since the length
+        // is chosen to be longer than any marker-like line in the contents,
+        // the longest one is a real marker.
+        let conflict_marker_len = content
+            .lines_with_terminator()
+            .filter_map(parse_conflict_marker_any_len)
+            .filter(|marker| {
+                marker.kind == ConflictMarkerLineChar::ConflictStart
+                    && marker.len >= MIN_CONFLICT_MARKER_LEN
+            })
+            .map(|marker| marker.len)
+            .max()?;
+        let hunks = parse_conflict(content, num_sides, conflict_marker_len)?;
+        let mut contents = Merge::from_vec(vec![BString::default(); num_sides * 2 - 1]);
+        for hunk in hunks {
+            if let Some(slice) = hunk.as_resolved() {
+                for content in contents.iter_mut() {
+                    content.extend_from_slice(slice);
+                }
+            } else {
+                for (content, slice) in zip(contents.iter_mut(), hunk.iter()) {
+                    content.extend_from_slice(slice);
+                }
+            }
+        }
+        Some(contents)
+    }
+}
+
+/// Like [`update_from_content()`], but parses `content` materialized by the
+/// custom `materializer` instead of conflict markers.
+pub async fn update_from_custom_content(
+    file_ids: &Merge<Option<FileId>>,
+    store: &Store,
+    path: &RepoPath,
+    content: &[u8],
+    materializer: &dyn ConflictMaterializer,
+) -> BackendResult<Merge<Option<FileId>>> {
+    let simplified_file_ids = file_ids.clone().simplify();
+    let simplified_file_ids = &simplified_file_ids;
+
+    let merge_hunk = extract_as_single_hunk(simplified_file_ids, store, path).await?;
+    if content == materializer.materialize(path, &merge_hunk) {
+        return Ok(file_ids.clone());
+    }
+
+    let num_sides = simplified_file_ids.num_sides();
+    let Some(contents) = materializer
+        .parse(path, content, num_sides)
+        .filter(|contents| contents.num_sides() == num_sides)
+    else {
+        let file_id = store.write_file(path, &mut &content[..]).await?;
+        return Ok(Merge::normal(file_id));
+    };
+    let contents = contents.map(|content| content.to_vec());
+    write_conflict_sides(
+        file_ids,
+        simplified_file_ids,
+        contents,
+        store,
+        path,
+        content,
+    )
+    .await
+}
+
+/// Maximum number of commits visited when looking for the origins of the sides
+/// of a conflict.
+const MAX_ORIGIN_SEARCH_COMMITS: usize = 1000;
+
+/// Finds the commits the sides of the conflict at `path` in `commit`
+/// originated from.
+///
+/// The parents and predecessors of `commit` are walked breadth-first. For each
+/// side of the simplified `conflict`, the nearest commit where `path` is
+/// resolved to the same value as the side is returned. If a side wasn't found
+/// within a bounded number of commits, `None` is returned for it.
+pub fn find_conflict_side_origins(
+    commit: &Commit,
+    path: &RepoPath,
+    conflict: &MergedTreeValue,
+) -> BackendResult<Vec<Option<Commit>>> {
+    let conflict = conflict.clone().simplify();
+    let mut origins = vec![None; conflict.num_sides()];
+    let mut queue = VecDeque::from([commit.clone()]);
+    let mut visited = HashSet::from([commit.id().clone()]);
+    while let Some(current) = queue.pop_front() {
+        if origins.iter().all(Option::is_some) || visited.len() > MAX_ORIGIN_SEARCH_COMMITS {
+            break;
+        }
+        if let Some(value) = current.tree()?.path_value(path)?.as_resolved() {
+            for (origin, side) in zip(&mut origins, conflict.adds()) {
+                if origin.is_none() && side == value {
+                    *origin = Some(current.clone());
+                }
+            }
+        }
+        for next in current.parents().chain(current.predecessors()) {
+            let next = next?;
+            if visited.insert(next.id().clone()) {
+                queue.push_back(next);
+            }
+        }
+    }
+    Ok(origins)
+}
@@ -0,0 +1,135 @@
+// Copyright 2024 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Text interchange format of the commit graph stored in the index.
+//!
+//! The first line is a format header. Each following line describes one commit
+//! as space-separated hex strings: the commit id, the change id, and the parent
+//! commit ids. Parents are listed before their children.
+
+#![allow(missing_docs)]
+
+use std::collections::HashSet;
+use std::io;
+use std::io::BufRead;
+use std::io::Write;
+
+use thiserror::Error;
+
+use super::composite::CompositeIndex;
+use super::entry::IndexPosition;
+use crate::backend::ChangeId;
+use crate::backend::CommitId;
+use crate::object_id::ObjectId as _;
+
+const HEADER: &str = "jj-commit-graph v1";
+
+/// Commit recorded in the interchange format.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CommitGraphEntry {
+    pub commit_id: CommitId,
+    pub change_id: ChangeId,
+    pub parent_ids: Vec<CommitId>,
+}
+
+/// Error while reading commits in the interchange format.
+#[derive(Debug, Error)]
+pub enum CommitGraphReadError {
+    #[error("Failed to read commit graph")]
+    Io(#[from] io::Error),
+    #[error("Not a commit graph file (expected header {HEADER:?})")]
+    InvalidHeader,
+    #[error("Invalid commit graph at line {line_number}: {message}")]
+    InvalidEntry { line_number: usize, message: String },
+}
+
+/// Writes all commits in the `index` in index order, which lists parents
+/// before their children. Returns the number of commits written.
+pub fn write_commit_graph(index: &CompositeIndex, output: &mut dyn Write) -> io::Result<u32> {
+    writeln!(output, "{HEADER}")?;
+    for pos in 0..index.num_commits() {
+        let entry = index.entry_by_pos(IndexPosition(pos));
+        write!(
+            output,
+            "{} {}",
+            entry.commit_id().hex(),
+            entry.change_id().hex()
+        )?;
+        for parent in entry.parents() {
+            write!(output, " {}", parent.commit_id().hex())?;
+        }
+        writeln!(output)?;
+    }
+    Ok(index.num_commits())
+}
+
+/// Reads commits written by [`write_commit_graph()`].
+///
+/// The ids must be of the given lengths, each commit must be listed only once,
+/// and the parents of each commit must have been listed before it.
+pub fn read_commit_graph(
+    input: &mut dyn BufRead,
+    commit_id_length: usize,
+    change_id_length: usize,
+) -> Result<Vec<CommitGraphEntry>, CommitGraphReadError> {
+    let mut lines = input.lines();
+    match lines.next().transpose()? {
+        Some(header) if header == HEADER => {}
+        _ => return Err(CommitGraphReadError::InvalidHeader),
+    }
+    let mut seen_ids = HashSet::new();
+    let mut entries = vec![];
+    for (i, line) in lines.enumerate() {
+        // The header is line 1
+        let line_number = i + 2;
+        let invalid = |message: String| CommitGraphReadError::InvalidEntry {
+            line_number,
+            message,
+        };
+        let line = line?;
+        let mut fields = line.split_ascii_whitespace();
+        let (Some(commit_hex), Some(change_hex)) = (fields.next(), fields.next()) else {
+            return Err(invalid("expected commit id and change id".to_owned()));
+        };
+        let parse_commit_id = |hex: &str| match CommitId::try_from_hex(hex) {
+            Ok(id) if id.as_bytes().len() == commit_id_length => Ok(id),
+            _ => Err(invalid(format!("invalid commit id {hex:?}"))),
+        };
+        let commit_id = parse_commit_id(commit_hex)?;
+        let change_id = match ChangeId::try_from_hex(change_hex) {
+            Ok(id) if id.as_bytes().len() == change_id_length => id,
+            _ => return Err(invalid(format!("invalid change id {change_hex:?}"))),
+        };
+        let parent_ids: Vec<_> = fields.map(parse_commit_id).collect::<Result<_, _>>()?;
+        if let Some(parent_id) = parent_ids.iter().find(|id| !seen_ids.contains(*id)) {
+            return Err(invalid(format!(
+                "parent {} is not listed before commit {}",
+                parent_id.hex(),
+                commit_id.hex()
+            )));
+        }
+        if !seen_ids.insert(commit_id.clone()) {
+            return Err(invalid(format!(
+                "commit {} is listed more than once",
+                commit_id.hex()
+            )));
+        }
+        entries.push(CommitGraphEntry {
+            commit_id,
+            change_id,
+            parent_ids,
+        });
+    }
+    Ok(entries)
+}
@@ -287,6 +287,39 @@ impl CompositeIndex {
             })
     }
 
+    pub(super) fn is_ancestor_pos_batch(
+        &self,
+        pairs: &[(IndexPosition, IndexPosition)],
+    ) -> Vec<bool> {
+        let mut results = vec![false; pairs.len()];
+        // Group queries by descendant so the ancestors of each descendant are
+        // visited at most once.
+        let queries_by_descendant = (0..pairs.len()).into_group_map_by(|&i| pairs[i].1);
+        for (descendant_pos, query_indices) in queries_by_descendant {
+            if let [i] = query_indices[..] {
+                // A generation-bounded walk is cheaper for a single query.
+                results[i] = self.is_ancestor_pos(pairs[i].0, descendant_pos);
+                continue;
+            }
+            let Some(min_pos) = query_indices
+                .iter()
+                .map(|&i| pairs[i].0)
+                .filter(|&pos| pos <= descendant_pos)
+                .min()
+            else {
+                continue;
+            };
+            let mut ancestors = AncestorsBitSet::with_capacity(descendant_pos.0 + 1);
+            ancestors.add_head(descendant_pos);
+            ancestors.visit_until(self, min_pos);
+            for i in query_indices {
+                let ancestor_pos = pairs[i].0;
+                results[i] = ancestor_pos <= descendant_pos && ancestors.contains(ancestor_pos);
+            }
+        }
+        results
+    }
+
     pub(super) fn is_ancestor_pos(
         &self,
         ancestor_pos: IndexPosition,
@@ -466,6 +499,18 @@ impl Index for &CompositeIndex {
         self.is_ancestor_pos(ancestor_pos, descendant_pos)
     }
 
+    fn is_ancestor_batch(&self, pairs: &[(CommitId, CommitId)]) -> Vec<bool> {
+        let pos_pairs = pairs
+            .iter()
+            .map(|(ancestor_id, descendant_id)| {
+                let ancestor_pos = self.commit_id_to_pos(ancestor_id).unwrap();
+                let descendant_pos = self.commit_id_to_pos(descendant_id).unwrap();
+                (ancestor_pos, descendant_pos)
+            })
+            .collect_vec();
+        self.is_ancestor_pos_batch(&pos_pairs)
+    }
+
     fn common_ancestors(&self, set1: &[CommitId], set2: &[CommitId]) -> Vec<CommitId> {
         let pos1 = set1
             .iter()
@@ -21,6 +21,7 @@
 
 #![allow(missing_docs)]
 
+mod commit_graph;
 mod composite;
 mod entry;
 mod mutable;
@@ -30,6 +31,10 @@ pub mod revset_engine;
 mod revset_graph_iterator;
 mod store;
 
+pub use self::commit_graph::read_commit_graph;
+pub use self::commit_graph::write_commit_graph;
+pub use self::commit_graph::CommitGraphEntry;
+pub use self::commit_graph::CommitGraphReadError;
 pub use self::composite::AsCompositeIndex;
 pub use self::composite::CompositeIndex;
 pub use self::composite::IndexLevelStats;
@@ -1009,6 +1014,48 @@ mod tests {
         assert!(!index.is_ancestor(&id_4, &id_2));
     }
 
+    #[test]
+    fn test_is_ancestor_batch() {
+        let mut new_change_id = change_id_generator();
+        let mut index = DefaultMutableIndex::full(3, 16);
+        // 5
+        // |\
+        // 4 | 3
+        // | |/
+        // 1 2
+        // |/
+        // 0
+        let id_0 = CommitId::from_hex("000000");
+        let id_1 = CommitId::from_hex("111111");
+        let id_2 = CommitId::from_hex("222222");
+        let id_3 = CommitId::from_hex("333333");
+        let id_4 = CommitId::from_hex("444444");
+        let id_5 = CommitId::from_hex("555555");
+        index.add_commit_data(id_0.clone(), new_change_id(), &[]);
+        index.add_commit_data(id_1.clone(), new_change_id(), std::slice::from_ref(&id_0));
+        index.add_commit_data(id_2.clone(), new_change_id(), std::slice::from_ref(&id_0));
+        index.add_commit_data(id_3.clone(), new_change_id(), std::slice::from_ref(&id_2));
+        index.add_commit_data(id_4.clone(), new_change_id(), std::slice::from_ref(&id_1));
+        index.add_commit_data(id_5.clone(), new_change_id(), &[id_4.clone(), id_2.clone()]);
+
+        let all_ids = [&id_0, &id_1, &id_2, &id_3, &id_4, &id_5];
+        let pairs = all_ids
+            .iter()
+            .cartesian_product(&all_ids)
+            .map(|(&a, &d)| (a.clone(), d.clone()))
+            .collect_vec();
+        let expected = pairs
+            .iter()
+            .map(|(a, d)| index.is_ancestor(a, d))
+            .collect_vec();
+        assert_eq!(index.is_ancestor_batch(&pairs), expected);
+        assert_eq!(index.is_ancestor_batch(&[]), Vec::<bool>::new());
+        assert_eq!(
+            index.is_ancestor_batch(&[(id_3.clone(), id_5.clone()), (id_5.clone(), id_5.clone())]),
+            [false, true]
+        );
+    }
+
     #[test]
     fn test_common_ancestors() {
         let mut new_change_id = change_id_generator();
@@ -347,9 +347,9 @@ impl MutableIndexSegment {
         squashed
     }
 
-    pub(super) fn save_in(self, dir: &Path) -> io::Result<Arc<ReadonlyIndexSegment>> {
-        if self.num_local_commits() == 0 && self.parent_file.is_some() {
-            return Ok(self.parent_file.unwrap());
+    pub(super) fn save_in(&self, dir: &Path) -> io::Result<Arc<ReadonlyIndexSegment>> {
+        if let (0, Some(parent_file)) = (self.num_local_commits(), &self.parent_file) {
+            return Ok(parent_file.clone());
         }
 
         let mut buf = Vec::new();
@@ -370,7 +370,7 @@ impl MutableIndexSegment {
         Ok(ReadonlyIndexSegment::load_with_parent_file(
             &mut &buf[local_entries_offset..],
             index_file_id_hex,
-            self.parent_file,
+            self.parent_file.clone(),
             self.commit_id_length,
             self.change_id_length,
         )
@@ -468,7 +468,6 @@ impl DefaultMutableIndex {
         DefaultMutableIndex(mutable_segment)
     }
 
-    #[cfg(test)]
     pub(crate) fn add_commit_data(
         &mut self,
         commit_id: CommitId,
@@ -481,6 +480,15 @@ impl DefaultMutableIndex {
     pub(super) fn squash_and_save_in(self, dir: &Path) -> io::Result<Arc<ReadonlyIndexSegment>> {
         self.0.maybe_squash_with_ancestors().save_in(dir)
     }
+
+    /// Saves the commits added so far in `dir`, and continues on top of the
+    /// saved segment. Unlike `squash_and_save_in()`, the segment isn't squashed
+    /// with its ancestors, which would load their commits back into memory.
+    pub(super) fn flush_in(&mut self, dir: &Path) -> io::Result<()> {
+        let segment = self.0.save_in(dir)?;
+        self.0 = MutableIndexSegment::incremental(segment);
+        Ok(())
+    }
 }
 
 impl AsCompositeIndex for DefaultMutableIndex {
@@ -507,6 +515,10 @@ impl Index for DefaultMutableIndex {
         self.as_composite().is_ancestor(ancestor_id, descendant_id)
     }
 
+    fn is_ancestor_batch(&self, pairs: &[(CommitId, CommitId)]) -> Vec<bool> {
+        self.as_composite().is_ancestor_batch(pairs)
+    }
+
     fn common_ancestors(&self, set1: &[CommitId], set2: &[CommitId]) -> Vec<CommitId> {
         self.as_composite().common_ancestors(set1, set2)
     }
@@ -535,6 +547,10 @@ impl MutableIndex for DefaultMutableIndex {
         self
     }
 
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+
     fn into_any(self: Box<Self>) -> Box<dyn Any> {
         Box::new(*self)
     }
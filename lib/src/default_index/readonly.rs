@@ -623,6 +623,10 @@ impl Index for DefaultReadonlyIndex {
         self.as_composite().is_ancestor(ancestor_id, descendant_id)
     }
 
+    fn is_ancestor_batch(&self, pairs: &[(CommitId, CommitId)]) -> Vec<bool> {
+        self.as_composite().is_ancestor_batch(pairs)
+    }
+
     fn common_ancestors(&self, set1: &[CommitId], set2: &[CommitId]) -> Vec<CommitId> {
         self.as_composite().common_ancestors(set1, set2)
     }
@@ -20,16 +20,18 @@ use std::cmp::Reverse;
 use std::collections::BTreeSet;
 use std::collections::BinaryHeap;
 use std::collections::HashSet;
+use std::collections::VecDeque;
 use std::fmt;
 use std::iter;
 use std::ops::Range;
-use std::rc::Rc;
 use std::str;
 use std::sync::Arc;
 
 use futures::StreamExt as _;
 use itertools::Itertools;
 use pollster::FutureExt as _;
+use rayon::iter::IntoParallelIterator as _;
+use rayon::iter::ParallelIterator as _;
 
 use super::rev_walk::EagerRevWalk;
 use super::rev_walk::PeekableRevWalk;
@@ -69,6 +71,12 @@ use crate::union_find;
 
 type BoxedPredicateFn<'a> =
     Box<dyn FnMut(&CompositeIndex, IndexPosition) -> Result<bool, RevsetEvaluationError> + 'a>;
+type SharedPredicateFn<'a> = Arc<
+    dyn Fn(&CompositeIndex, IndexPosition) -> Result<bool, RevsetEvaluationError>
+        + Send
+        + Sync
+        + 'a,
+>;
 pub(super) type BoxedRevWalk<'a> =
     Box<dyn RevWalk<CompositeIndex, Item = Result<IndexPosition, RevsetEvaluationError>> + 'a>;
 
@@ -79,6 +87,17 @@ trait ToPredicateFn: fmt::Debug {
     fn to_predicate_fn<'a>(&self) -> BoxedPredicateFn<'a>
     where
         Self: 'a;
+
+    /// Creates function that can be evaluated for multiple entries
+    /// concurrently, in any order.
+    ///
+    /// Returns `None` if the predicate isn't expensive enough to be worth it.
+    fn to_shared_predicate_fn<'a>(&self) -> Option<SharedPredicateFn<'a>>
+    where
+        Self: 'a,
+    {
+        None
+    }
 }
 
 impl<T: ToPredicateFn + ?Sized> ToPredicateFn for Box<T> {
@@ -88,6 +107,13 @@ impl<T: ToPredicateFn + ?Sized> ToPredicateFn for Box<T> {
     {
         <T as ToPredicateFn>::to_predicate_fn(self)
     }
+
+    fn to_shared_predicate_fn<'a>(&self) -> Option<SharedPredicateFn<'a>>
+    where
+        Self: 'a,
+    {
+        <T as ToPredicateFn>::to_shared_predicate_fn(self)
+    }
 }
 
 trait InternalRevset: fmt::Debug + ToPredicateFn {
@@ -395,6 +421,14 @@ where
     where
         Self: 'a,
     {
+        if let Some(p) = self.predicate.to_shared_predicate_fn() {
+            return Box::new(ParallelFilterRevWalk {
+                candidates: self.candidates.positions(),
+                predicate: p,
+                batch_size: 1,
+                queue: VecDeque::new(),
+            });
+        }
         let mut p = self.predicate.to_predicate_fn();
         Box::new(self.candidates.positions().filter_map(move |index, pos| {
             pos.and_then(|pos| Ok(p(index, pos)?.then_some(pos)))
@@ -425,6 +459,49 @@ where
     }
 }
 
+/// Maximum number of candidates to test concurrently.
+const PARALLEL_FILTER_MAX_BATCH_SIZE: usize = 256;
+
+/// Filters candidates by testing them in batches on the rayon thread pool.
+///
+/// The batch size starts at 1 and grows up to the maximum, so a caller that
+/// only needs the first few entries doesn't test many more candidates than
+/// the serial walk would. Entries are emitted in the order of the candidates.
+struct ParallelFilterRevWalk<'a> {
+    candidates: BoxedRevWalk<'a>,
+    predicate: SharedPredicateFn<'a>,
+    batch_size: usize,
+    queue: VecDeque<Result<IndexPosition, RevsetEvaluationError>>,
+}
+
+impl RevWalk<CompositeIndex> for ParallelFilterRevWalk<'_> {
+    type Item = Result<IndexPosition, RevsetEvaluationError>;
+
+    fn next(&mut self, index: &CompositeIndex) -> Option<Self::Item> {
+        while self.queue.is_empty() {
+            let batch = iter::from_fn(|| self.candidates.next(index))
+                .take(self.batch_size)
+                .collect_vec();
+            if batch.is_empty() {
+                return None;
+            }
+            self.batch_size = (self.batch_size * 2).min(PARALLEL_FILTER_MAX_BATCH_SIZE);
+            let predicate = &self.predicate;
+            let test = |pos: Result<IndexPosition, RevsetEvaluationError>| {
+                pos.and_then(|pos| Ok(predicate(index, pos)?.then_some(pos)))
+                    .transpose()
+            };
+            if batch.len() == 1 {
+                self.queue.extend(batch.into_iter().filter_map(test));
+            } else {
+                let results: Vec<_> = batch.into_par_iter().map(test).collect();
+                self.queue.extend(results.into_iter().flatten());
+            }
+        }
+        self.queue.pop_front()
+    }
+}
+
 #[derive(Debug)]
 struct NotInPredicate<S>(S);
 
@@ -954,6 +1031,71 @@ impl EvaluationContext<'_> {
                 });
                 Ok(Box::new(EagerRevset { positions }))
             }
+            ResolvedExpression::HeadsWithin { candidates, domain } => {
+                let candidate_set = self.evaluate(candidates)?;
+                let domain_set = self.evaluate(domain)?;
+                let domain_positions: Vec<_> =
+                    domain_set.positions().attach(index).try_collect()?;
+                let candidate_positions: HashSet<_> = intersection_by(
+                    candidate_set.positions(),
+                    EagerRevWalk::new(domain_positions.iter().copied().map(Ok)),
+                    |pos1, pos2| pos1.cmp(pos2).reverse(),
+                )
+                .attach(index)
+                .try_collect()?;
+                // Positions are visited from children to parents. A commit is
+                // covered if it has a candidate descendant within the domain.
+                // Parents outside the domain may be marked, but they are never
+                // visited, so the ancestry isn't followed through them.
+                let mut covered = HashSet::new();
+                let mut positions = vec![];
+                for pos in domain_positions {
+                    let is_candidate = candidate_positions.contains(&pos);
+                    let is_covered = covered.contains(&pos);
+                    if is_candidate && !is_covered {
+                        positions.push(pos);
+                    }
+                    if is_candidate || is_covered {
+                        covered.extend(index.entry_by_pos(pos).parent_positions());
+                    }
+                }
+                Ok(Box::new(EagerRevset { positions }))
+            }
+            ResolvedExpression::RootsWithin { candidates, domain } => {
+                let candidate_set = self.evaluate(candidates)?;
+                let domain_set = self.evaluate(domain)?;
+                let mut domain_positions: Vec<_> =
+                    domain_set.positions().attach(index).try_collect()?;
+                let candidate_positions: HashSet<_> = intersection_by(
+                    candidate_set.positions(),
+                    EagerRevWalk::new(domain_positions.iter().copied().map(Ok)),
+                    |pos1, pos2| pos1.cmp(pos2).reverse(),
+                )
+                .attach(index)
+                .try_collect()?;
+                // Positions are visited from parents to children. A commit is
+                // reached if it is a candidate or has a candidate ancestor
+                // within the domain.
+                domain_positions.reverse();
+                let mut reached = HashSet::new();
+                let mut positions = vec![];
+                for pos in domain_positions {
+                    let is_candidate = candidate_positions.contains(&pos);
+                    let is_covered = index
+                        .entry_by_pos(pos)
+                        .parent_positions()
+                        .iter()
+                        .any(|parent_pos| reached.contains(parent_pos));
+                    if is_candidate && !is_covered {
+                        positions.push(pos);
+                    }
+                    if is_candidate || is_covered {
+                        reached.insert(pos);
+                    }
+                }
+                positions.reverse();
+                Ok(Box::new(EagerRevset { positions }))
+            }
             ResolvedExpression::ForkPoint(expression) => {
                 let expression_set = self.evaluate(expression)?;
                 let mut expression_positions_iter = expression_set.positions().attach(index);
@@ -974,6 +1116,32 @@ impl EvaluationContext<'_> {
                 let candidate_set = self.evaluate(candidates)?;
                 Ok(Box::new(self.take_latest_revset(&*candidate_set, *count)?))
             }
+            ResolvedExpression::First { candidates, count } => {
+                // Positions are emitted in descending order, so the first
+                // commits in topological order come last.
+                let candidate_set = self.evaluate(candidates)?;
+                let mut positions = VecDeque::with_capacity(*count);
+                for pos in candidate_set.positions().attach(index) {
+                    if *count == 0 {
+                        break;
+                    }
+                    if positions.len() == *count {
+                        positions.pop_front();
+                    }
+                    positions.push_back(pos?);
+                }
+                let positions = positions.into();
+                Ok(Box::new(EagerRevset { positions }))
+            }
+            ResolvedExpression::Last { candidates, count } => {
+                let candidate_set = self.evaluate(candidates)?;
+                let positions = candidate_set
+                    .positions()
+                    .attach(index)
+                    .take(*count)
+                    .try_collect()?;
+                Ok(Box::new(EagerRevset { positions }))
+            }
             ResolvedExpression::Coalesce(expression1, expression2) => {
                 let set1 = self.evaluate(expression1)?;
                 if set1.positions().attach(index).next().is_some() {
@@ -1141,6 +1309,42 @@ fn box_pure_predicate_fn<'a>(
     Box::new(PurePredicateFn(f))
 }
 
+/// Predicate which loads commits from the store, and is therefore worth
+/// evaluating concurrently.
+struct CommitPredicateFn<F>(Arc<F>);
+
+impl<F> fmt::Debug for CommitPredicateFn<F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CommitPredicateFn").finish_non_exhaustive()
+    }
+}
+
+impl<F> ToPredicateFn for CommitPredicateFn<F>
+where
+    F: Fn(&CompositeIndex, IndexPosition) -> Result<bool, RevsetEvaluationError> + Send + Sync,
+{
+    fn to_predicate_fn<'a>(&self) -> BoxedPredicateFn<'a>
+    where
+        Self: 'a,
+    {
+        let f = self.0.clone();
+        Box::new(move |index, pos| f(index, pos))
+    }
+
+    fn to_shared_predicate_fn<'a>(&self) -> Option<SharedPredicateFn<'a>>
+    where
+        Self: 'a,
+    {
+        Some(self.0.clone())
+    }
+}
+
+fn box_commit_predicate_fn<'a>(
+    f: impl Fn(&CompositeIndex, IndexPosition) -> Result<bool, RevsetEvaluationError> + Send + Sync + 'a,
+) -> Box<dyn ToPredicateFn + 'a> {
+    Box::new(CommitPredicateFn(Arc::new(f)))
+}
+
 fn build_predicate_fn(
     store: Arc<Store>,
     predicate: &RevsetFilterPredicate,
@@ -1155,7 +1359,7 @@ fn build_predicate_fn(
         }
         RevsetFilterPredicate::Description(pattern) => {
             let pattern = pattern.clone();
-            box_pure_predicate_fn(move |index, pos| {
+            box_commit_predicate_fn(move |index, pos| {
                 let entry = index.entry_by_pos(pos);
                 let commit = store.get_commit(&entry.commit_id())?;
                 Ok(pattern.matches(commit.description()))
@@ -1165,7 +1369,7 @@ fn build_predicate_fn(
             let pattern = pattern.clone();
             // TODO: Make these functions that take a needle to search for accept some
             // syntax for specifying whether it's a regex.
-            box_pure_predicate_fn(move |index, pos| {
+            box_commit_predicate_fn(move |index, pos| {
                 let entry = index.entry_by_pos(pos);
                 let commit = store.get_commit(&entry.commit_id())?;
                 Ok(pattern.matches(&commit.author().name)
@@ -1174,7 +1378,7 @@ fn build_predicate_fn(
         }
         RevsetFilterPredicate::Committer(pattern) => {
             let pattern = pattern.clone();
-            box_pure_predicate_fn(move |index, pos| {
+            box_commit_predicate_fn(move |index, pos| {
                 let entry = index.entry_by_pos(pos);
                 let commit = store.get_commit(&entry.commit_id())?;
                 Ok(pattern.matches(&commit.committer().name)
@@ -1183,7 +1387,7 @@ fn build_predicate_fn(
         }
         RevsetFilterPredicate::AuthorDate(expression) => {
             let expression = *expression;
-            box_pure_predicate_fn(move |index, pos| {
+            box_commit_predicate_fn(move |index, pos| {
                 let entry = index.entry_by_pos(pos);
                 let commit = store.get_commit(&entry.commit_id())?;
                 let author_date = &commit.author().timestamp;
@@ -1192,7 +1396,7 @@ fn build_predicate_fn(
         }
         RevsetFilterPredicate::CommitterDate(expression) => {
             let expression = *expression;
-            box_pure_predicate_fn(move |index, pos| {
+            box_commit_predicate_fn(move |index, pos| {
                 let entry = index.entry_by_pos(pos);
                 let commit = store.get_commit(&entry.commit_id())?;
                 let committer_date = &commit.committer().timestamp;
@@ -1200,8 +1404,8 @@ fn build_predicate_fn(
             })
         }
         RevsetFilterPredicate::File(expr) => {
-            let matcher: Rc<dyn Matcher> = expr.to_matcher().into();
-            box_pure_predicate_fn(move |index, pos| {
+            let matcher: Box<dyn Matcher> = expr.to_matcher();
+            box_commit_predicate_fn(move |index, pos| {
                 let entry = index.entry_by_pos(pos);
                 let commit = store.get_commit(&entry.commit_id())?;
                 Ok(has_diff_from_parent(&store, index, &commit, &*matcher)?)
@@ -1209,8 +1413,8 @@ fn build_predicate_fn(
         }
         RevsetFilterPredicate::DiffContains { text, files } => {
             let text_pattern = text.clone();
-            let files_matcher: Rc<dyn Matcher> = files.to_matcher().into();
-            box_pure_predicate_fn(move |index, pos| {
+            let files_matcher: Box<dyn Matcher> = files.to_matcher();
+            box_commit_predicate_fn(move |index, pos| {
                 let entry = index.entry_by_pos(pos);
                 let commit = store.get_commit(&entry.commit_id())?;
                 Ok(matches_diff_from_parent(
@@ -1222,14 +1426,14 @@ fn build_predicate_fn(
                 )?)
             })
         }
-        RevsetFilterPredicate::HasConflict => box_pure_predicate_fn(move |index, pos| {
+        RevsetFilterPredicate::HasConflict => box_commit_predicate_fn(move |index, pos| {
             let entry = index.entry_by_pos(pos);
             let commit = store.get_commit(&entry.commit_id())?;
             Ok(commit.has_conflict()?)
         }),
         RevsetFilterPredicate::Extension(ext) => {
             let ext = ext.clone();
-            box_pure_predicate_fn(move |index, pos| {
+            box_commit_predicate_fn(move |index, pos| {
                 let entry = index.entry_by_pos(pos);
                 let commit = store.get_commit(&entry.commit_id())?;
                 Ok(ext.matches_commit(&commit))
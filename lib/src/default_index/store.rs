@@ -27,6 +27,7 @@ use itertools::Itertools;
 use tempfile::NamedTempFile;
 use thiserror::Error;
 
+use super::commit_graph::CommitGraphEntry;
 use super::mutable::DefaultMutableIndex;
 use super::readonly::DefaultReadonlyIndex;
 use super::readonly::ReadonlyIndexLoadError;
@@ -179,11 +180,53 @@ impl DefaultIndexStore {
         Ok(DefaultReadonlyIndex::from_segment(index_segment))
     }
 
-    #[tracing::instrument(skip(self, store))]
+    /// Rebuilds index for the given `operation`, seeded with the commits
+    /// from an exported commit graph.
+    ///
+    /// The seed commits are trusted to exist in the backend. Commits reachable
+    /// from the operation but missing in the seed are indexed as usual. This
+    /// should be called after `reinit()`, as the seed is otherwise ignored if
+    /// an ancestor operation has already been indexed.
+    pub fn import_commit_graph(
+        &self,
+        entries: &[CommitGraphEntry],
+        operation: &Operation,
+        store: &Arc<Store>,
+    ) -> Result<DefaultReadonlyIndex, DefaultIndexStoreError> {
+        let mut seed_index =
+            DefaultMutableIndex::full(store.commit_id_length(), store.change_id_length());
+        for entry in entries {
+            seed_index.add_commit_data(
+                entry.commit_id.clone(),
+                entry.change_id.clone(),
+                &entry.parent_ids,
+            );
+        }
+        let seed_segment = seed_index
+            .squash_and_save_in(&self.segments_dir())
+            .map_err(DefaultIndexStoreError::SaveIndex)?;
+        let index_segment =
+            self.build_index_segments_on_seed(operation, store, Some(seed_segment))?;
+        Ok(DefaultReadonlyIndex::from_segment(index_segment))
+    }
+
     fn build_index_segments_at_operation(
         &self,
         operation: &Operation,
         store: &Arc<Store>,
+    ) -> Result<Arc<ReadonlyIndexSegment>, DefaultIndexStoreError> {
+        self.build_index_segments_on_seed(operation, store, None)
+    }
+
+    /// Builds index segments for the given `operation` on top of the index of
+    /// an ancestor operation, or on top of the `seed` segment if no ancestor
+    /// operation has been indexed.
+    #[tracing::instrument(skip(self, store, seed))]
+    fn build_index_segments_on_seed(
+        &self,
+        operation: &Operation,
+        store: &Arc<Store>,
+        seed: Option<Arc<ReadonlyIndexSegment>>,
     ) -> Result<Arc<ReadonlyIndexSegment>, DefaultIndexStoreError> {
         let view = operation.view()?;
         let operations_dir = self.operations_dir();
@@ -219,10 +262,16 @@ impl DefaultIndexStore {
         let maybe_parent_file;
         let mut mutable_index;
         match parent_op_id {
-            None => {
-                maybe_parent_file = None;
-                mutable_index = DefaultMutableIndex::full(commit_id_length, change_id_length);
-            }
+            None => match seed {
+                None => {
+                    maybe_parent_file = None;
+                    mutable_index = DefaultMutableIndex::full(commit_id_length, change_id_length);
+                }
+                Some(seed) => {
+                    maybe_parent_file = Some(seed.clone());
+                    mutable_index = DefaultMutableIndex::incremental(seed);
+                }
+            },
             Some(parent_op_id) => {
                 let parent_file = self.load_index_segments_at_operation(
                     &parent_op_id,
@@ -377,4 +426,14 @@ impl IndexStore for DefaultIndexStore {
             .map_err(|err| IndexWriteError(err.into()))?;
         Ok(Box::new(DefaultReadonlyIndex::from_segment(index_segment)))
     }
+
+    fn flush_index(&self, index: &mut dyn MutableIndex) -> Result<(), IndexWriteError> {
+        let index = index
+            .as_any_mut()
+            .downcast_mut::<DefaultMutableIndex>()
+            .expect("index to flush must be a DefaultMutableIndex");
+        index
+            .flush_in(&self.segments_dir())
+            .map_err(|err| IndexWriteError(DefaultIndexStoreError::SaveIndex(err).into()))
+    }
 }
@@ -182,15 +182,26 @@ mod platform {
 #[cfg(windows)]
 mod platform {
     use std::io;
+    use std::os::windows::fs::symlink_dir;
     use std::os::windows::fs::symlink_file;
     use std::path::Path;
+    use std::path::PathBuf;
 
     use winreg::enums::HKEY_LOCAL_MACHINE;
     use winreg::RegKey;
 
     /// Symlinks may or may not be enabled on Windows. They require the
-    /// Developer Mode setting, which is stored in the registry key below.
+    /// Developer Mode setting, which is stored in the registry key below, or
+    /// the privilege to create symlinks, which is checked by creating one.
     pub fn check_symlink_support() -> io::Result<bool> {
+        if developer_mode_enabled().unwrap_or(false) {
+            return Ok(true);
+        }
+        let temp_dir = tempfile::tempdir()?;
+        Ok(symlink_file("target", temp_dir.path().join("link")).is_ok())
+    }
+
+    fn developer_mode_enabled() -> io::Result<bool> {
         let hklm = RegKey::predef(HKEY_LOCAL_MACHINE);
         let sideloading =
             hklm.open_subkey("SOFTWARE\\Microsoft\\Windows\\CurrentVersion\\AppModelUnlock")?;
@@ -199,13 +210,28 @@ mod platform {
     }
 
     pub fn try_symlink<P: AsRef<Path>, Q: AsRef<Path>>(original: P, link: Q) -> io::Result<()> {
-        // this will create a nonfunctional link for directories, but at the moment
-        // we don't have enough information in the tree to determine whether the
-        // symlink target is a file or a directory
+        // Targets use forward slashes, which Windows doesn't accept in
+        // relative symlink targets.
+        let original = original.as_ref();
+        let original = match original.to_str() {
+            Some(original) => PathBuf::from(original.replace('/', "\\")),
+            None => original.to_owned(),
+        };
+        let link = link.as_ref();
+        // A directory symlink is needed if the target is a directory. The
+        // target can only be checked if it already exists, so a link to a
+        // directory that is yet to be checked out becomes a file symlink.
         // note: if developer mode is not enabled the error code will be 1314,
         // ERROR_PRIVILEGE_NOT_HELD
-
-        symlink_file(original, link)
+        let resolved_target = match link.parent() {
+            Some(dir) => dir.join(&original),
+            None => original.clone(),
+        };
+        if resolved_target.is_dir() {
+            symlink_dir(original, link)
+        } else {
+            symlink_file(original, link)
+        }
     }
 }
 
@@ -41,6 +41,10 @@ pub enum FsmonitorSettings {
     /// The Watchman filesystem monitor (<https://facebook.github.io/watchman/>).
     Watchman(WatchmanConfig),
 
+    /// The built-in filesystem monitor. Changed paths are recorded by a
+    /// separate `jj debug fsmonitor daemon` process.
+    Native,
+
     /// Only used in tests.
     Test {
         /// The set of changed files to pretend that the filesystem monitor is
@@ -67,6 +71,13 @@ impl FsmonitorSettings {
                         .optional()?
                         .unwrap_or_default(),
                 })),
+                "native" if cfg!(target_os = "linux") => Ok(Self::Native),
+                // TODO: Implement the daemon with FSEvents on macOS
+                "native" => Err(ConfigGetError::Type {
+                    name: name.to_owned(),
+                    error: "The native fsmonitor is only supported on Linux".into(),
+                    source_path: None,
+                }),
                 "test" => Err(ConfigGetError::Type {
                     name: name.to_owned(),
                     error: "Cannot use test fsmonitor in real repository".into(),
@@ -341,3 +352,455 @@ pub mod watchman {
         }
     }
 }
+
+/// Built-in filesystem monitor.
+///
+/// A long-running daemon watches the working copy and records the changed
+/// paths in the working-copy state directory. Snapshots consume the recorded
+/// paths instead of crawling the entire working copy. If the daemon isn't
+/// running, the caller falls back to crawling.
+///
+/// The daemon is currently only implemented on Linux (using inotify).
+pub mod native {
+    use std::fs;
+    use std::fs::File;
+    use std::io;
+    use std::path::Path;
+    use std::path::PathBuf;
+    use std::sync::atomic::AtomicU64;
+    use std::sync::atomic::Ordering;
+    use std::thread;
+    use std::time::Duration;
+    use std::time::Instant;
+
+    use thiserror::Error;
+    use tracing::instrument;
+
+    use crate::lock::FileLock;
+    use crate::lock::FileLockError;
+
+    /// Lock file held by the daemon for as long as it's running.
+    const DAEMON_LOCK_FILE_NAME: &str = "fsmonitor_daemon.lock";
+    /// NUL-separated list of paths changed since the last query.
+    const DIRTY_FILE_NAME: &str = "fsmonitor_dirty";
+    const DIRTY_LOCK_FILE_NAME: &str = "fsmonitor_dirty.lock";
+    /// Prefix of the files used to make sure the daemon has caught up with the
+    /// filesystem events before querying.
+    const COOKIE_PREFIX: &str = "fsmonitor_cookie.";
+    /// Dirty entry meaning that any path may have changed. It can't be a valid
+    /// relative path.
+    const ALL_PATHS_ENTRY: &[u8] = b"/";
+    /// How long to wait for the daemon to catch up before giving up and
+    /// crawling the working copy.
+    const SYNC_TIMEOUT: Duration = Duration::from_secs(1);
+
+    #[allow(missing_docs)]
+    #[derive(Debug, Error)]
+    pub enum Error {
+        #[error("A filesystem monitor daemon is already running for this working copy")]
+        AlreadyRunning,
+        #[error("The built-in filesystem monitor daemon is not supported on this platform")]
+        Unsupported,
+        #[error("Failed to watch directory {path}")]
+        Watch {
+            path: PathBuf,
+            #[source]
+            err: io::Error,
+        },
+        #[error(transparent)]
+        Lock(#[from] FileLockError),
+        #[error(transparent)]
+        Io(#[from] io::Error),
+    }
+
+    /// Returns whether a daemon is watching the working copy whose state is
+    /// stored in `state_path`.
+    pub fn is_daemon_running(state_path: &Path) -> Result<bool, Error> {
+        if !state_path.join(DIRTY_FILE_NAME).exists() {
+            // The daemon is either not running or still starting up.
+            return Ok(false);
+        }
+        let file = match File::open(state_path.join(DAEMON_LOCK_FILE_NAME)) {
+            Ok(file) => file,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(false),
+            Err(err) => return Err(err.into()),
+        };
+        platform::is_locked(&file)
+    }
+
+    /// Paths changed since the changes were last acknowledged.
+    #[derive(Clone, Debug, Default, Eq, PartialEq)]
+    pub struct ChangedFiles {
+        /// Changed paths relative to the working copy root. A path may refer
+        /// to a directory, in which case anything under it may have changed.
+        /// If it is `None`, then the caller must crawl the entire working copy
+        /// themselves.
+        pub paths: Option<Vec<PathBuf>>,
+        /// Entries of the dirty file the paths were read from.
+        entries: Vec<u8>,
+    }
+
+    /// Queries for the paths changed since the changes were last
+    /// acknowledged by `acknowledge_changed_files()`.
+    #[instrument]
+    pub fn query_changed_files(state_path: &Path) -> Result<ChangedFiles, Error> {
+        if !is_daemon_running(state_path)? {
+            return Ok(ChangedFiles::default());
+        }
+        if !sync_with_daemon(state_path)? {
+            tracing::warn!("Timed out waiting for the filesystem monitor daemon");
+            return Ok(ChangedFiles::default());
+        }
+        let _lock = FileLock::lock(state_path.join(DIRTY_LOCK_FILE_NAME))?;
+        let entries = match fs::read(state_path.join(DIRTY_FILE_NAME)) {
+            Ok(entries) => entries,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(ChangedFiles::default()),
+            Err(err) => return Err(err.into()),
+        };
+        let mut paths = Vec::new();
+        for entry in entries
+            .split(|&b| b == b'\0')
+            .filter(|entry| !entry.is_empty())
+        {
+            if entry == ALL_PATHS_ENTRY {
+                return Ok(ChangedFiles {
+                    paths: None,
+                    entries,
+                });
+            }
+            paths.push(platform::path_from_bytes(entry));
+        }
+        paths.sort_unstable();
+        paths.dedup();
+        Ok(ChangedFiles {
+            paths: Some(paths),
+            entries,
+        })
+    }
+
+    /// Removes the changes returned by `query_changed_files()` from the
+    /// recorded paths. This should be called only after the state derived
+    /// from them has been persisted, so the changes aren't lost if the caller
+    /// fails before that. Paths recorded after the query are kept.
+    pub fn acknowledge_changed_files(
+        state_path: &Path,
+        changes: &ChangedFiles,
+    ) -> Result<(), Error> {
+        if changes.entries.is_empty() {
+            return Ok(());
+        }
+        let _lock = FileLock::lock(state_path.join(DIRTY_LOCK_FILE_NAME))?;
+        let dirty_path = state_path.join(DIRTY_FILE_NAME);
+        let entries = match fs::read(&dirty_path) {
+            Ok(entries) => entries,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(()),
+            Err(err) => return Err(err.into()),
+        };
+        // The daemon only appends to the file, so the queried entries are
+        // still at the start of it unless the daemon has been restarted.
+        if let Some(new_entries) = entries.strip_prefix(changes.entries.as_slice()) {
+            fs::write(&dirty_path, new_entries)?;
+        }
+        Ok(())
+    }
+
+    /// Creates a cookie file and waits until the daemon removes it. Since the
+    /// daemon processes events in order, all changes made before the cookie
+    /// was created have been recorded by then.
+    fn sync_with_daemon(state_path: &Path) -> Result<bool, Error> {
+        static NEXT_COOKIE: AtomicU64 = AtomicU64::new(0);
+        let cookie_path = state_path.join(format!(
+            "{COOKIE_PREFIX}{}.{}",
+            std::process::id(),
+            NEXT_COOKIE.fetch_add(1, Ordering::Relaxed)
+        ));
+        File::create(&cookie_path)?;
+        let deadline = Instant::now() + SYNC_TIMEOUT;
+        while cookie_path.symlink_metadata().is_ok() {
+            if Instant::now() >= deadline {
+                fs::remove_file(&cookie_path).ok();
+                return Ok(false);
+            }
+            thread::sleep(Duration::from_millis(1));
+        }
+        Ok(true)
+    }
+
+    /// Watches the working copy at `working_copy_path` and records changed
+    /// paths in `state_path` until an error occurs. Only one daemon can run
+    /// per working copy.
+    pub fn run_daemon(working_copy_path: &Path, state_path: &Path) -> Result<(), Error> {
+        platform::run_daemon(working_copy_path, state_path)
+    }
+
+    #[cfg(target_os = "linux")]
+    mod platform {
+        use std::collections::HashMap;
+        use std::ffi::OsStr;
+        use std::fs;
+        use std::fs::File;
+        use std::io;
+        use std::io::Write as _;
+        use std::mem::MaybeUninit;
+        use std::os::fd::OwnedFd;
+        use std::os::unix::ffi::OsStrExt as _;
+        use std::path::Path;
+        use std::path::PathBuf;
+
+        use rustix::fs::inotify;
+        use rustix::fs::inotify::ReadFlags;
+        use rustix::fs::inotify::WatchFlags;
+        use rustix::fs::FlockOperation;
+        use rustix::io::Errno;
+        use tracing::info;
+
+        use super::Error;
+        use super::ALL_PATHS_ENTRY;
+        use super::COOKIE_PREFIX;
+        use super::DAEMON_LOCK_FILE_NAME;
+        use super::DIRTY_FILE_NAME;
+        use super::DIRTY_LOCK_FILE_NAME;
+        use crate::lock::FileLock;
+
+        const EXCLUDED_ROOT_DIRS: [&str; 2] = [".git", ".jj"];
+
+        pub fn is_locked(file: &File) -> Result<bool, Error> {
+            match rustix::fs::flock(file, FlockOperation::NonBlockingLockShared) {
+                // Nobody holds the lock, so the lock file was left behind by a
+                // daemon that didn't exit cleanly.
+                Ok(()) => Ok(false),
+                Err(Errno::WOULDBLOCK) => Ok(true),
+                Err(errno) => Err(io::Error::from(errno).into()),
+            }
+        }
+
+        pub fn path_from_bytes(bytes: &[u8]) -> PathBuf {
+            OsStr::from_bytes(bytes).into()
+        }
+
+        /// Removes the dirty file when the daemon exits so that snapshots stop
+        /// relying on it.
+        struct DirtyFileGuard<'a> {
+            state_path: &'a Path,
+        }
+
+        impl Drop for DirtyFileGuard<'_> {
+            fn drop(&mut self) {
+                fs::remove_file(self.state_path.join(DIRTY_FILE_NAME)).ok();
+            }
+        }
+
+        struct Watcher<'a> {
+            inotify_fd: &'a OwnedFd,
+            working_copy_path: &'a Path,
+            /// Watched directories, relative to the working copy root.
+            watched_dirs: HashMap<i32, PathBuf>,
+        }
+
+        impl Watcher<'_> {
+            fn watch_tree(&mut self, dir: &Path) -> Result<(), Error> {
+                let disk_dir = self.working_copy_path.join(dir);
+                let flags = WatchFlags::ATTRIB
+                    | WatchFlags::CLOSE_WRITE
+                    | WatchFlags::CREATE
+                    | WatchFlags::DELETE
+                    | WatchFlags::MODIFY
+                    | WatchFlags::MOVED_FROM
+                    | WatchFlags::MOVED_TO
+                    | WatchFlags::DONT_FOLLOW
+                    | WatchFlags::EXCL_UNLINK
+                    | WatchFlags::ONLYDIR;
+                let wd = match inotify::add_watch(self.inotify_fd, &disk_dir, flags) {
+                    Ok(wd) => wd,
+                    // The directory may have been removed or replaced since
+                    // the event was generated.
+                    Err(Errno::NOENT | Errno::NOTDIR) => return Ok(()),
+                    Err(errno) => {
+                        return Err(Error::Watch {
+                            path: disk_dir,
+                            err: errno.into(),
+                        })
+                    }
+                };
+                self.watched_dirs.insert(wd, dir.to_owned());
+                let entries = match fs::read_dir(&disk_dir) {
+                    Ok(entries) => entries,
+                    Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(()),
+                    Err(err) => return Err(err.into()),
+                };
+                for entry in entries {
+                    let entry = entry?;
+                    if !entry.file_type()?.is_dir() {
+                        continue;
+                    }
+                    let name = entry.file_name();
+                    if !is_excluded(dir, &name) {
+                        self.watch_tree(&dir.join(name))?;
+                    }
+                }
+                Ok(())
+            }
+
+            fn unwatch_tree(&mut self, dir: &Path) {
+                let inotify_fd = self.inotify_fd;
+                self.watched_dirs.retain(|&wd, path| {
+                    if path.starts_with(dir) {
+                        inotify::remove_watch(inotify_fd, wd).ok();
+                        false
+                    } else {
+                        true
+                    }
+                });
+            }
+        }
+
+        fn is_excluded(dir: &Path, name: &OsStr) -> bool {
+            dir.as_os_str().is_empty() && EXCLUDED_ROOT_DIRS.iter().any(|&n| name == n)
+        }
+
+        fn append_dirty_paths(state_path: &Path, paths: &[PathBuf]) -> Result<(), Error> {
+            if paths.is_empty() {
+                return Ok(());
+            }
+            let mut data = Vec::new();
+            for path in paths {
+                data.extend_from_slice(path.as_os_str().as_bytes());
+                data.push(b'\0');
+            }
+            let _lock = FileLock::lock(state_path.join(DIRTY_LOCK_FILE_NAME))?;
+            fs::OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(state_path.join(DIRTY_FILE_NAME))?
+                .write_all(&data)?;
+            Ok(())
+        }
+
+        pub fn run_daemon(working_copy_path: &Path, state_path: &Path) -> Result<(), Error> {
+            let lock_file = File::create(state_path.join(DAEMON_LOCK_FILE_NAME))?;
+            match rustix::fs::flock(&lock_file, FlockOperation::NonBlockingLockExclusive) {
+                Ok(()) => {}
+                Err(Errno::WOULDBLOCK) => return Err(Error::AlreadyRunning),
+                Err(errno) => return Err(io::Error::from(errno).into()),
+            }
+            // Changes made before the watches are set up aren't known, so the
+            // first query after startup will crawl the working copy.
+            let _dirty_file_guard = DirtyFileGuard { state_path };
+            fs::remove_file(state_path.join(DIRTY_FILE_NAME)).ok();
+
+            info!("Setting up inotify watches...");
+            let inotify_fd =
+                inotify::init(inotify::CreateFlags::CLOEXEC).map_err(io::Error::from)?;
+            let mut watcher = Watcher {
+                inotify_fd: &inotify_fd,
+                working_copy_path,
+                watched_dirs: HashMap::new(),
+            };
+            watcher.watch_tree(Path::new(""))?;
+            let state_wd = inotify::add_watch(
+                &inotify_fd,
+                state_path,
+                WatchFlags::CREATE | WatchFlags::ONLYDIR,
+            )
+            .map_err(|errno| Error::Watch {
+                path: state_path.to_owned(),
+                err: errno.into(),
+            })?;
+            append_dirty_paths(state_path, &[path_from_bytes(ALL_PATHS_ENTRY)])?;
+            info!("Watching {} directories", watcher.watched_dirs.len());
+
+            let mut buf = vec![MaybeUninit::uninit(); 64 * 1024];
+            let mut reader = inotify::Reader::new(&inotify_fd, &mut buf);
+            loop {
+                let mut changed_paths = Vec::new();
+                let mut new_dirs = Vec::new();
+                let mut cookies = Vec::new();
+                // Read at least one event, then drain the already-read ones.
+                loop {
+                    let event = reader.next().map_err(io::Error::from)?;
+                    let flags = event.events();
+                    let name = event
+                        .file_name()
+                        .map(|name| OsStr::from_bytes(name.to_bytes()));
+                    if flags.contains(ReadFlags::QUEUE_OVERFLOW) {
+                        changed_paths.push(path_from_bytes(ALL_PATHS_ENTRY));
+                    } else if event.wd() == state_wd {
+                        if let Some(name) = name {
+                            if name.as_bytes().starts_with(COOKIE_PREFIX.as_bytes()) {
+                                cookies.push(state_path.join(name));
+                            }
+                        }
+                    } else if flags.contains(ReadFlags::IGNORED) {
+                        watcher.watched_dirs.remove(&event.wd());
+                    } else if let (Some(dir), Some(name)) =
+                        (watcher.watched_dirs.get(&event.wd()), name)
+                    {
+                        if !is_excluded(dir, name) {
+                            let path = dir.join(name);
+                            if flags.contains(ReadFlags::ISDIR) {
+                                if flags.intersects(ReadFlags::CREATE | ReadFlags::MOVED_TO) {
+                                    new_dirs.push(path.clone());
+                                } else if flags.contains(ReadFlags::MOVED_FROM) {
+                                    // The watches would otherwise keep
+                                    // reporting events with the old path.
+                                    watcher.unwatch_tree(&path);
+                                }
+                            }
+                            changed_paths.push(path);
+                        }
+                    }
+                    if reader.is_buffer_empty() {
+                        break;
+                    }
+                }
+                for dir in &new_dirs {
+                    watcher.watch_tree(dir)?;
+                }
+                append_dirty_paths(state_path, &changed_paths)?;
+                for cookie in &cookies {
+                    fs::remove_file(cookie).ok();
+                }
+            }
+        }
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    mod platform {
+        use std::fs::File;
+        use std::path::Path;
+        use std::path::PathBuf;
+
+        use super::Error;
+
+        #[cfg(unix)]
+        pub fn is_locked(file: &File) -> Result<bool, Error> {
+            use rustix::fs::FlockOperation;
+            match rustix::fs::flock(file, FlockOperation::NonBlockingLockShared) {
+                Ok(()) => Ok(false),
+                Err(rustix::io::Errno::WOULDBLOCK) => Ok(true),
+                Err(errno) => Err(std::io::Error::from(errno).into()),
+            }
+        }
+
+        #[cfg(not(unix))]
+        pub fn is_locked(_file: &File) -> Result<bool, Error> {
+            Ok(false)
+        }
+
+        #[cfg(unix)]
+        pub fn path_from_bytes(bytes: &[u8]) -> PathBuf {
+            use std::ffi::OsStr;
+            use std::os::unix::ffi::OsStrExt as _;
+            OsStr::from_bytes(bytes).into()
+        }
+
+        #[cfg(not(unix))]
+        pub fn path_from_bytes(bytes: &[u8]) -> PathBuf {
+            String::from_utf8_lossy(bytes).into_owned().into()
+        }
+
+        pub fn run_daemon(_working_copy_path: &Path, _state_path: &Path) -> Result<(), Error> {
+            Err(Error::Unsupported)
+        }
+    }
+}
@@ -20,9 +20,13 @@ use std::collections::HashMap;
 use std::collections::HashSet;
 use std::default::Default;
 use std::fmt;
+use std::io;
 use std::io::Read;
 use std::num::NonZeroU32;
+use std::path::Path;
 use std::path::PathBuf;
+use std::process::Command;
+use std::process::Stdio;
 use std::str;
 
 use git2::Oid;
@@ -35,6 +39,7 @@ use crate::backend::CommitId;
 use crate::commit::Commit;
 use crate::git_backend::GitBackend;
 use crate::index::Index;
+use crate::merge::Merge;
 use crate::object_id::ObjectId;
 use crate::op_store::RefTarget;
 use crate::op_store::RefTargetOptionExt;
@@ -45,6 +50,7 @@ use crate::refs::BookmarkPushUpdate;
 use crate::repo::MutableRepo;
 use crate::repo::Repo;
 use crate::revset::RevsetExpression;
+use crate::settings::FetchPruneMode;
 use crate::settings::GitSettings;
 use crate::store::Store;
 use crate::str_util::StringPattern;
@@ -119,6 +125,28 @@ pub fn is_reserved_git_remote_ref(parsed_ref: &RefName) -> bool {
     to_remote_branch(parsed_ref, REMOTE_NAME_FOR_LOCAL_GIT_REPO).is_some()
 }
 
+/// Returns true if the branch `parsed_ref` may be imported as a bookmark
+/// according to `git.import-bookmarks`. Tags are always imported.
+fn is_bookmark_ref_imported(parsed_ref: &RefName, git_settings: &GitSettings) -> bool {
+    match parsed_ref {
+        RefName::LocalBranch(branch) | RefName::RemoteBranch { branch, .. } => {
+            git_settings.imports_bookmark(branch)
+        }
+        RefName::Tag(_) => true,
+    }
+}
+
+/// Returns true if the bookmark `parsed_ref` may be exported as a Git branch
+/// according to `git.export-bookmarks`.
+fn is_bookmark_ref_exported(parsed_ref: &RefName, git_settings: &GitSettings) -> bool {
+    match parsed_ref {
+        RefName::LocalBranch(branch) | RefName::RemoteBranch { branch, .. } => {
+            git_settings.exports_bookmark(branch)
+        }
+        RefName::Tag(_) => true,
+    }
+}
+
 fn get_git_backend(store: &Store) -> Option<&GitBackend> {
     store.backend_impl().downcast_ref()
 }
@@ -219,6 +247,10 @@ pub struct GitImportStats {
     /// Remote `(ref_name, (old_remote_ref, new_target))`s to be merged in to
     /// the local refs.
     pub changed_remote_refs: BTreeMap<RefName, (RemoteRef, RefTarget)>,
+    /// Tracked remote bookmark `(name, remote)`s whose old target is no longer
+    /// an ancestor of the new target, which usually means that the bookmark
+    /// was force-pushed.
+    pub rewritten_remote_bookmarks: Vec<(String, String)>,
 }
 
 #[derive(Debug)]
@@ -254,6 +286,9 @@ pub fn import_some_refs(
     let git_backend = get_git_backend(store).ok_or(GitImportError::UnexpectedBackend)?;
     let git_repo = git_backend.git_repo();
 
+    let git_ref_filter = |ref_name: &RefName| {
+        is_bookmark_ref_imported(ref_name, git_settings) && git_ref_filter(ref_name)
+    };
     let RefsToImport {
         changed_git_refs,
         changed_remote_refs,
@@ -296,6 +331,9 @@ pub fn import_some_refs(
         .add_heads(&head_commits)
         .map_err(GitImportError::InternalBackend)?;
 
+    let rewritten_remote_bookmarks =
+        find_rewritten_remote_bookmarks(mut_repo.index(), &changed_remote_refs);
+
     // Apply the change that happened in git since last time we imported refs.
     for (full_name, new_target) in changed_git_refs {
         mut_repo.set_git_ref_target(&full_name, new_target);
@@ -324,7 +362,16 @@ pub fn import_some_refs(
             }
             RefName::RemoteBranch { branch, remote } => {
                 if new_remote_ref.is_tracking() {
-                    mut_repo.merge_local_bookmark(branch, base_target, &new_remote_ref.target);
+                    if new_remote_ref.target.is_absent() {
+                        prune_local_bookmark(
+                            mut_repo,
+                            branch,
+                            base_target,
+                            git_settings.fetch_prune,
+                        );
+                    } else {
+                        mut_repo.merge_local_bookmark(branch, base_target, &new_remote_ref.target);
+                    }
                 }
                 // Remote-tracking branch is the last known state of the branch in the remote.
                 // It shouldn't diverge even if we had inconsistent view.
@@ -347,12 +394,70 @@ pub fn import_some_refs(
     let stats = GitImportStats {
         abandoned_commits,
         changed_remote_refs,
+        rewritten_remote_bookmarks,
     };
     Ok(stats)
 }
 
+/// Propagates deletion of the tracked remote bookmark to the local bookmark
+/// `name` as specified by the `mode`.
+fn prune_local_bookmark(
+    mut_repo: &mut MutableRepo,
+    name: &str,
+    base_target: &RefTarget,
+    mode: FetchPruneMode,
+) {
+    match mode {
+        FetchPruneMode::Delete => {
+            mut_repo.merge_local_bookmark(name, base_target, RefTarget::absent_ref());
+        }
+        FetchPruneMode::Keep => {}
+        FetchPruneMode::MarkDeleted => {
+            let local_target = mut_repo.get_local_bookmark(name);
+            if local_target.is_absent() {
+                return;
+            }
+            // Record the deletion as an unresolved conflict so the bookmark
+            // (and the commits it pins) stays around until the user decides.
+            let merge = Merge::from_vec(vec![
+                local_target.as_merge().clone(),
+                base_target.as_merge().clone(),
+                Merge::absent(),
+            ])
+            .flatten();
+            mut_repo.set_local_bookmark_target(name, RefTarget::from_merge(merge));
+        }
+    }
+}
+
+/// Finds tracked remote bookmarks that were moved to a commit which isn't a
+/// descendant of the old target.
+fn find_rewritten_remote_bookmarks(
+    index: &dyn Index,
+    changed_remote_refs: &BTreeMap<RefName, (RemoteRef, RefTarget)>,
+) -> Vec<(String, String)> {
+    changed_remote_refs
+        .iter()
+        .filter_map(|(ref_name, (old_remote_ref, new_target))| {
+            let RefName::RemoteBranch { branch, remote } = ref_name else {
+                return None;
+            };
+            let old_id = old_remote_ref.target.as_normal()?;
+            let new_id = new_target.as_normal()?;
+            (old_remote_ref.is_tracking() && !index.is_ancestor(old_id, new_id))
+                .then(|| (branch.clone(), remote.clone()))
+        })
+        .collect()
+}
+
+/// Maximum number of old remote heads to look for unreachable commits at once.
+const ABANDON_UNREACHABLE_HEADS_BATCH_SIZE: usize = 100;
+
 /// Finds commits that used to be reachable in git that no longer are reachable.
 /// Those commits will be recorded as abandoned in the `MutableRepo`.
+///
+/// The old heads are processed in batches to bound the size of each revset
+/// evaluation when many remote bookmarks disappear at once.
 fn abandon_unreachable_commits(
     mut_repo: &mut MutableRepo,
     changed_remote_refs: &BTreeMap<RefName, (RemoteRef, RefTarget)>,
@@ -373,18 +478,24 @@ fn abandon_unreachable_commits(
             .intersection(&RevsetExpression::visible_heads().ancestors()),
         RevsetExpression::root(),
     ]);
-    let abandoned_expression = pinned_expression
-        .range(&RevsetExpression::commits(hidable_git_heads))
-        // Don't include already-abandoned commits in GitImportStats
-        .intersection(&RevsetExpression::visible_heads().ancestors());
-    let abandoned_commits = abandoned_expression
-        .evaluate(mut_repo)
-        .unwrap()
-        .iter()
-        .map(Result::unwrap) // TODO: Return error to caller
-        .collect_vec();
-    for abandoned_commit in &abandoned_commits {
-        mut_repo.record_abandoned_commit(abandoned_commit.clone());
+    let mut abandoned_commits = vec![];
+    let mut seen = HashSet::new();
+    for heads in hidable_git_heads.chunks(ABANDON_UNREACHABLE_HEADS_BATCH_SIZE) {
+        let abandoned_expression = pinned_expression
+            .range(&RevsetExpression::commits(heads.to_vec()))
+            // Don't include already-abandoned commits in GitImportStats
+            .intersection(&RevsetExpression::visible_heads().ancestors());
+        let batch = abandoned_expression
+            .evaluate(mut_repo)
+            .unwrap()
+            .iter()
+            .map(Result::unwrap) // TODO: Return error to caller
+            .filter(|id| seen.insert(id.clone()))
+            .collect_vec();
+        for abandoned_commit in &batch {
+            mut_repo.record_abandoned_commit(abandoned_commit.clone());
+        }
+        abandoned_commits.extend(batch);
     }
     abandoned_commits
 }
@@ -421,11 +532,12 @@ fn diff_refs_to_import(
                 let RemoteRef { target, state } = remote_ref;
                 (ref_name, (target, *state))
             }),
-        // TODO: compare to tags stored in the "git" remote view. Since tags should never
-        // be moved locally in jj, we can consider local tags as merge base.
-        view.tags().iter().map(|(name, target)| {
-            let ref_name = RefName::Tag(name.to_owned());
-            (ref_name, (target, RemoteRefState::Tracking))
+        // Tags last seen in the Git repo are the merge base, so tags created or
+        // deleted in jj but not yet exported are left alone.
+        view.git_refs().iter().filter_map(|(full_name, target)| {
+            let ref_name = parse_git_ref(full_name).expect("stored git ref should be parsable");
+            matches!(ref_name, RefName::Tag(_))
+                .then_some((ref_name, (target, RemoteRefState::Tracking)))
         }),
     )
     .filter(|(ref_name, _)| git_ref_filter(ref_name))
@@ -501,8 +613,8 @@ fn default_remote_ref_state_for(ref_name: &RefName, git_settings: &GitSettings)
     match ref_name {
         // LocalBranch means Git-tracking branch
         RefName::LocalBranch(_) | RefName::Tag(_) => RemoteRefState::Tracking,
-        RefName::RemoteBranch { .. } => {
-            if git_settings.auto_local_bookmark {
+        RefName::RemoteBranch { branch, .. } => {
+            if git_settings.auto_tracks_bookmark(branch) {
                 RemoteRefState::Tracking
             } else {
                 RemoteRefState::New
@@ -652,18 +764,27 @@ struct RefsToExport {
 /// repo compared to our last remembered view of the Git repo). These will be
 /// marked conflicted by the next `jj git import`.
 ///
-/// We do not export tags and other refs at the moment, since these aren't
-/// supposed to be modified by JJ. For them, the Git state is considered
+/// Tags are exported as lightweight Git tags in the same way. Other refs
+/// aren't supposed to be modified by JJ. For them, the Git state is considered
 /// authoritative.
-pub fn export_refs(mut_repo: &mut MutableRepo) -> Result<Vec<FailedRefExport>, GitExportError> {
-    export_some_refs(mut_repo, |_| true)
+///
+/// Bookmarks excluded by `git.export-bookmarks` are kept local to JJ.
+pub fn export_refs(
+    mut_repo: &mut MutableRepo,
+    git_settings: &GitSettings,
+) -> Result<Vec<FailedRefExport>, GitExportError> {
+    export_some_refs(mut_repo, git_settings, |_| true)
 }
 
 pub fn export_some_refs(
     mut_repo: &mut MutableRepo,
+    git_settings: &GitSettings,
     git_ref_filter: impl Fn(&RefName) -> bool,
 ) -> Result<Vec<FailedRefExport>, GitExportError> {
     let git_repo = get_git_repo(mut_repo.store()).ok_or(GitExportError::UnexpectedBackend)?;
+    let git_ref_filter = |ref_name: &RefName| {
+        is_bookmark_ref_exported(ref_name, git_settings) && git_ref_filter(ref_name)
+    };
 
     let RefsToExport {
         branches_to_update,
@@ -672,7 +793,7 @@ pub fn export_some_refs(
     } = diff_refs_to_export(
         mut_repo.view(),
         mut_repo.store().root_commit_id(),
-        &git_ref_filter,
+        git_ref_filter,
     );
 
     // TODO: Also check other worktrees' HEAD.
@@ -781,7 +902,7 @@ fn diff_refs_to_export(
 ) -> RefsToExport {
     // Local targets will be copied to the "git" remote if successfully exported. So
     // the local branches are considered to be the new "git" remote branches.
-    let mut all_branch_targets: HashMap<RefName, (&RefTarget, &RefTarget)> = itertools::chain(
+    let mut all_branch_targets: HashMap<RefName, (&RefTarget, &RefTarget)> = itertools::chain!(
         view.local_bookmarks()
             .map(|(branch, target)| (RefName::LocalBranch(branch.to_owned()), target)),
         view.all_remote_bookmarks()
@@ -793,6 +914,9 @@ fn diff_refs_to_export(
                 };
                 (ref_name, &remote_ref.target)
             }),
+        view.tags()
+            .iter()
+            .map(|(name, target)| (RefName::Tag(name.to_owned()), target)),
     )
     .map(|(ref_name, new_target)| (ref_name, (RefTarget::absent_ref(), new_target)))
     .filter(|(ref_name, _)| git_ref_filter(ref_name))
@@ -808,9 +932,10 @@ fn diff_refs_to_export(
             // There are two situations where remote-tracking branches get out of sync:
             // 1. `jj branch forget`
             // 2. `jj op undo`/`restore` in colocated repo
+            // Tags are compared to the last imported state in the same way.
             matches!(
                 ref_name,
-                RefName::LocalBranch(..) | RefName::RemoteBranch { .. }
+                RefName::LocalBranch(..) | RefName::RemoteBranch { .. } | RefName::Tag(..)
             )
         })
         .filter(|(ref_name, _)| git_ref_filter(ref_name));
@@ -870,7 +995,14 @@ fn delete_git_ref(
     old_oid: &gix::oid,
 ) -> Result<(), FailedRefExportReason> {
     if let Ok(git_ref) = git_repo.find_reference(git_ref_name) {
-        if git_ref.inner.target.try_id() == Some(old_oid) {
+        // An annotated tag is recorded as the commit it points to.
+        let is_unchanged = git_ref.inner.target.try_id() == Some(old_oid)
+            || (git_ref_name.starts_with("refs/tags/")
+                && git_ref
+                    .clone()
+                    .into_fully_peeled_id()
+                    .is_ok_and(|id| id.as_ref() == old_oid));
+        if is_unchanged {
             // The branch has not been updated by git, so go ahead and delete it
             git_ref
                 .delete()
@@ -999,7 +1131,15 @@ pub fn reset_head(
         let new_git_commit_id = Oid::from_bytes(first_parent_id.as_bytes()).unwrap();
         let new_git_commit = git_repo.find_commit(new_git_commit_id)?;
         if git_head != &first_parent {
-            git_repo.set_head_detached(new_git_commit_id)?;
+            // Record the move in the HEAD reflog the way `git checkout` does,
+            // so that `git reflog` and `git checkout -` work in colocated
+            // repos.
+            let old_head_name = git_head
+                .as_normal()
+                .map_or_else(|| "(unborn)".to_owned(), |id| id.hex());
+            let reflog_message =
+                format!("checkout: moving from {old_head_name} to {new_git_commit_id}");
+            git_repo.reference("HEAD", new_git_commit_id, true, &reflog_message)?;
         }
 
         let is_same_tree = if git_head == &first_parent {
@@ -1028,7 +1168,11 @@ pub fn reset_head(
             false
         };
         if !skip_reset {
-            git_repo.reset(new_git_commit.as_object(), git2::ResetType::Mixed, None)?;
+            // HEAD has been moved above, so only the index needs to be reset
+            // to the new tree (like `git reset --mixed` would do.)
+            let mut index = git_repo.index()?;
+            index.read_tree(&new_git_commit.tree()?)?;
+            index.write()?;
         }
     } else {
         // Can't detach HEAD without a commit. Use placeholder ref to nullify the HEAD.
@@ -1145,6 +1289,18 @@ pub fn rename_remote(
     if new_remote_name == REMOTE_NAME_FOR_LOCAL_GIT_REPO {
         return Err(GitRemoteManagementError::RemoteReservedForLocalGitRepo);
     }
+    // Check the view before touching the Git config so that a failure doesn't
+    // leave the remote renamed in Git but not in jj.
+    if mut_repo
+        .view()
+        .remote_bookmarks(new_remote_name)
+        .next()
+        .is_some()
+    {
+        return Err(GitRemoteManagementError::RemoteAlreadyExists(
+            new_remote_name.to_owned(),
+        ));
+    }
     git_repo
         .remote_rename(old_remote_name, new_remote_name)
         .map_err(|err| {
@@ -1188,6 +1344,39 @@ pub fn set_remote_url(
     Ok(())
 }
 
+/// Sets the URL the remote is pushed to. If `new_push_url` is `None`, the
+/// fetch URL is used for pushing too.
+pub fn set_remote_push_url(
+    git_repo: &git2::Repository,
+    remote_name: &str,
+    new_push_url: Option<&str>,
+) -> Result<(), GitRemoteManagementError> {
+    if remote_name == REMOTE_NAME_FOR_LOCAL_GIT_REPO {
+        return Err(GitRemoteManagementError::RemoteReservedForLocalGitRepo);
+    }
+    git_repo.find_remote(remote_name).map_err(|err| {
+        if is_remote_not_found_err(&err) {
+            GitRemoteManagementError::NoSuchRemote(remote_name.to_owned())
+        } else {
+            GitRemoteManagementError::InternalGitError(err)
+        }
+    })?;
+    match new_push_url {
+        Some(url) => git_repo.remote_set_pushurl(remote_name, Some(url)),
+        None => {
+            // Repository::remote_set_pushurl(name, None) fails if there's no
+            // pushurl entry to remove.
+            let key = format!("remote.{remote_name}.pushurl");
+            match git_repo.config().and_then(|mut config| config.remove(&key)) {
+                Err(err) if err.code() == git2::ErrorCode::NotFound => Ok(()),
+                res => res,
+            }
+        }
+    }
+    .map_err(GitRemoteManagementError::InternalGitError)?;
+    Ok(())
+}
+
 fn rename_remote_refs(mut_repo: &mut MutableRepo, old_remote_name: &str, new_remote_name: &str) {
     mut_repo.rename_remote(old_remote_name, new_remote_name);
     let prefix = format!("refs/remotes/{old_remote_name}/");
@@ -1213,6 +1402,63 @@ fn rename_remote_refs(mut_repo: &mut MutableRepo, old_remote_name: &str, new_rem
 
 const INVALID_REFSPEC_CHARS: [char; 5] = [':', '^', '?', '[', ']'];
 
+fn to_refspec_glob(pattern: &StringPattern) -> Result<Cow<'_, str>, GitFetchError> {
+    pattern
+        .to_glob()
+        .filter(
+            /* This triggered by non-glob `*`s in addition to INVALID_REFSPEC_CHARS
+             * because `to_glob()` escapes such `*`s as `[*]`. */
+            |glob| !glob.contains(INVALID_REFSPEC_CHARS),
+        )
+        .ok_or(GitFetchError::InvalidBranchPattern)
+}
+
+/// Patterns of remote branch names to fetch.
+#[derive(Clone, Debug, Default)]
+pub struct BranchPatterns {
+    /// Branches to fetch.
+    pub included: Vec<StringPattern>,
+    /// Branches not to fetch even if they match any of the `included`
+    /// patterns.
+    pub excluded: Vec<StringPattern>,
+}
+
+impl BranchPatterns {
+    /// Pattern that matches all branches.
+    pub fn everything() -> Self {
+        BranchPatterns {
+            included: vec![StringPattern::everything()],
+            excluded: vec![],
+        }
+    }
+
+    /// Returns true if the branch `name` should be fetched.
+    pub fn matches(&self, name: &str) -> bool {
+        self.included.iter().any(|pattern| pattern.matches(name))
+            && !self.excluded.iter().any(|pattern| pattern.matches(name))
+    }
+
+    /// Translates the included patterns to Git refspecs.
+    ///
+    /// libgit2 doesn't support negative refspecs, so the excluded patterns are
+    /// only validated here. If there are any, the branches on the remote have
+    /// to be listed and filtered before fetching.
+    fn to_refspecs(&self, remote_name: &str) -> Result<Vec<String>, GitFetchError> {
+        for pattern in &self.excluded {
+            to_refspec_glob(pattern)?;
+        }
+        self.included
+            .iter()
+            .map(|pattern| {
+                let glob = to_refspec_glob(pattern)?;
+                Ok(format!(
+                    "+refs/heads/{glob}:refs/remotes/{remote_name}/{glob}"
+                ))
+            })
+            .collect()
+    }
+}
+
 #[derive(Error, Debug)]
 pub enum GitFetchError {
     #[error("No git remote named '{0}'")]
@@ -1224,6 +1470,8 @@ pub enum GitFetchError {
     InvalidBranchPattern,
     #[error("Failed to import Git refs")]
     GitImportError(#[from] GitImportError),
+    #[error("Failed to run git fetch: {0}")]
+    FetchCommand(String),
     // TODO: I'm sure there are other errors possible, such as transport-level errors.
     #[error("Unexpected git error when fetching")]
     InternalGitError(#[from] git2::Error),
@@ -1247,30 +1495,33 @@ fn fetch_options(
 }
 
 struct FetchedBranches {
-    branches: Vec<StringPattern>,
+    branches: BranchPatterns,
     remote: String,
 }
 
-struct GitFetch<'a> {
+struct GitFetch<'a, 'cb> {
     mut_repo: &'a mut MutableRepo,
     git_repo: &'a git2::Repository,
     git_settings: &'a GitSettings,
-    fetch_options: git2::FetchOptions<'a>,
+    callbacks: RemoteCallbacks<'cb>,
+    depth: Option<NonZeroU32>,
     fetched: Vec<FetchedBranches>,
 }
 
-impl<'a> GitFetch<'a> {
+impl<'a, 'cb> GitFetch<'a, 'cb> {
     fn new(
         mut_repo: &'a mut MutableRepo,
         git_repo: &'a git2::Repository,
         git_settings: &'a GitSettings,
-        fetch_options: git2::FetchOptions<'a>,
+        callbacks: RemoteCallbacks<'cb>,
+        depth: Option<NonZeroU32>,
     ) -> Self {
         GitFetch {
             mut_repo,
             git_repo,
             git_settings,
-            fetch_options,
+            callbacks,
+            depth,
             fetched: vec![],
         }
     }
@@ -1282,7 +1533,7 @@ impl<'a> GitFetch<'a> {
     /// subsequently imported into the `jj` repo by calling `import_refs()`.
     fn fetch(
         &mut self,
-        branch_names: &[StringPattern],
+        branches: &BranchPatterns,
         remote_name: &str,
     ) -> Result<Option<String>, GitFetchError> {
         let mut remote = self.git_repo.find_remote(remote_name).map_err(|err| {
@@ -1294,39 +1545,47 @@ impl<'a> GitFetch<'a> {
         })?;
         // At this point, we are only updating Git's remote tracking branches, not the
         // local branches.
-        let refspecs: Vec<_> = branch_names
-            .iter()
-            .map(|pattern| {
-                pattern
-                    .to_glob()
-                    .filter(
-                        /* This triggered by non-glob `*`s in addition to INVALID_REFSPEC_CHARS
-                         * because `to_glob()` escapes such `*`s as `[*]`. */
-                        |glob| !glob.contains(INVALID_REFSPEC_CHARS),
-                    )
-                    .map(|glob| format!("+refs/heads/{glob}:refs/remotes/{remote_name}/{glob}"))
-            })
-            .collect::<Option<_>>()
-            .ok_or(GitFetchError::InvalidBranchPattern)?;
-        if refspecs.is_empty() {
+        let mut refspecs = branches.to_refspecs(remote_name)?;
+        if branches.included.is_empty() {
             // Don't fall back to the base refspecs.
             return Ok(None);
         }
 
-        tracing::debug!("remote.download");
-        remote.download(&refspecs, Some(&mut self.fetch_options))?;
-        tracing::debug!("remote.prune");
-        remote.prune(None)?;
-        tracing::debug!("remote.update_tips");
-        remote.update_tips(
-            None,
-            git2::RemoteUpdateFlags::empty(),
-            git2::AutotagOption::Unspecified,
-            None,
-        )?;
+        if branches.excluded.is_empty() {
+            tracing::debug!("remote.download");
+            let mut fetch_options = fetch_options(self.callbacks.reborrow(), self.depth);
+            remote.download(&refspecs, Some(&mut fetch_options))?;
+            tracing::debug!("remote.prune");
+            remote.prune(None)?;
+        } else {
+            // Fetch the remaining branches by name, so the excluded ones
+            // aren't downloaded or updated.
+            let remote_branches = self.list_remote_branches(&mut remote, branches)?;
+            refspecs = remote_branches
+                .iter()
+                .map(|branch| format!("+refs/heads/{branch}:refs/remotes/{remote_name}/{branch}"))
+                .collect();
+            if !refspecs.is_empty() {
+                tracing::debug!("remote.download");
+                let mut fetch_options = fetch_options(self.callbacks.reborrow(), self.depth);
+                remote.download(&refspecs, Some(&mut fetch_options))?;
+            }
+            // libgit2 only prunes refs matching the refspecs, which don't
+            // include the deleted branches.
+            self.prune_remote_refs(branches, remote_name, &remote_branches)?;
+        }
+        if !refspecs.is_empty() {
+            tracing::debug!("remote.update_tips");
+            remote.update_tips(
+                None,
+                git2::RemoteUpdateFlags::empty(),
+                git2::AutotagOption::Unspecified,
+                None,
+            )?;
+        }
 
         self.fetched.push(FetchedBranches {
-            branches: branch_names.to_vec(),
+            branches: branches.clone(),
             remote: remote_name.to_string(),
         });
 
@@ -1348,6 +1607,56 @@ impl<'a> GitFetch<'a> {
         Ok(default_branch)
     }
 
+    /// Lists the branches on the remote which match the `branches` patterns.
+    fn list_remote_branches(
+        &mut self,
+        remote: &mut git2::Remote<'_>,
+        branches: &BranchPatterns,
+    ) -> Result<Vec<String>, git2::Error> {
+        let mut proxy_options = git2::ProxyOptions::new();
+        proxy_options.auto();
+        tracing::debug!("remote.connect");
+        let connection = remote.connect_auth(
+            git2::Direction::Fetch,
+            Some(self.callbacks.reborrow().into_git()),
+            Some(proxy_options),
+        )?;
+        let remote_branches = connection
+            .list()?
+            .iter()
+            .filter_map(|head| head.name().strip_prefix("refs/heads/"))
+            .filter(|branch| branches.matches(branch))
+            .map(|branch| branch.to_owned())
+            .collect();
+        Ok(remote_branches)
+    }
+
+    /// Deletes the remote-tracking refs of the branches matching the
+    /// `branches` patterns which no longer exist on the remote.
+    fn prune_remote_refs(
+        &self,
+        branches: &BranchPatterns,
+        remote_name: &str,
+        remote_branches: &[String],
+    ) -> Result<(), git2::Error> {
+        let prefix = format!("refs/remotes/{remote_name}/");
+        for git_ref in self.git_repo.references_glob(&format!("{prefix}*"))? {
+            let mut git_ref = git_ref?;
+            let is_deleted = git_ref
+                .name()
+                .and_then(|full_name| full_name.strip_prefix(&prefix))
+                .is_some_and(|branch| {
+                    branch != "HEAD"
+                        && branches.matches(branch)
+                        && !remote_branches.iter().any(|name| name == branch)
+                });
+            if is_deleted {
+                git_ref.delete()?;
+            }
+        }
+        Ok(())
+    }
+
     /// Import the previously fetched remote-tracking branches into the jj repo
     /// and update jj's local branches. We also import local tags since remote
     /// tags should have been merged by Git.
@@ -1370,10 +1679,7 @@ impl<'a> GitFetch<'a> {
                                 return false;
                             }
 
-                            fetched
-                                .branches
-                                .iter()
-                                .any(|pattern| pattern.matches(branch))
+                            fetched.branches.matches(branch)
                         })
                     }
                 },
@@ -1399,18 +1705,13 @@ pub fn fetch(
     mut_repo: &mut MutableRepo,
     git_repo: &git2::Repository,
     remote_name: &str,
-    branch_names: &[StringPattern],
+    branches: &BranchPatterns,
     callbacks: RemoteCallbacks<'_>,
     git_settings: &GitSettings,
     depth: Option<NonZeroU32>,
 ) -> Result<GitFetchStats, GitFetchError> {
-    let mut git_fetch = GitFetch::new(
-        mut_repo,
-        git_repo,
-        git_settings,
-        fetch_options(callbacks, depth),
-    );
-    let default_branch = git_fetch.fetch(branch_names, remote_name)?;
+    let mut git_fetch = GitFetch::new(mut_repo, git_repo, git_settings, callbacks, depth);
+    let default_branch = git_fetch.fetch(branches, remote_name)?;
     let import_stats = git_fetch.import_refs()?;
     let stats = GitFetchStats {
         default_branch,
@@ -1419,6 +1720,49 @@ pub fn fetch(
     Ok(stats)
 }
 
+/// Fetches the branches of the remote into a partial clone by running `git
+/// fetch --filter=<filter>`, since libgit2 doesn't support partial clones.
+///
+/// `git fetch` records the remote as the promisor remote of the repo, so the
+/// objects omitted by the `filter` (e.g. `blob:none`) can be fetched on demand.
+/// The Git backend has to be reloaded to notice the promisor remote. The fetched refs
+/// aren't imported; `fetch()` can be called afterwards to import them without
+/// downloading the commits again.
+pub fn fetch_partial(
+    git_repo: &git2::Repository,
+    remote_name: &str,
+    filter: &str,
+    depth: Option<NonZeroU32>,
+) -> Result<(), GitFetchError> {
+    git_repo.find_remote(remote_name).map_err(|err| {
+        if is_remote_not_found_err(&err) {
+            GitFetchError::NoSuchRemote(remote_name.to_string())
+        } else {
+            GitFetchError::InternalGitError(err)
+        }
+    })?;
+    let mut git = Command::new("git");
+    git.arg("--git-dir=."); // turn off discovery
+    git.args(["fetch", "--quiet", "--recurse-submodules=no"]);
+    git.arg(format!("--filter={filter}"));
+    if let Some(depth) = depth {
+        git.arg(format!("--depth={depth}"));
+    }
+    git.arg(remote_name);
+    // Don't specify it by GIT_DIR/--git-dir. On Windows, the "\\?\" path might
+    // not be supported by git.
+    git.current_dir(git_repo.path());
+    git.stdin(Stdio::null());
+    let output = git
+        .output()
+        .map_err(|err| GitFetchError::FetchCommand(err.to_string()))?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(GitFetchError::FetchCommand(stderr.trim_end().to_owned()));
+    }
+    Ok(())
+}
+
 #[derive(Error, Debug, PartialEq)]
 pub enum GitPushError {
     #[error("No git remote named '{0}'")]
@@ -1432,6 +1776,10 @@ pub enum GitPushError {
     RefInUnexpectedLocation(Vec<String>),
     #[error("Remote rejected the update of some refs (do you have permission to push to {0:?}?)")]
     RefUpdateRejected(Vec<String>),
+    #[error("Remote rejected the atomic update of some refs, so no refs were updated: {0:?}")]
+    AtomicUpdateRejected(Vec<String>),
+    #[error("Failed to run git push: {0}")]
+    PushCommand(String),
     // TODO: I'm sure there are other errors possible, such as transport-level errors,
     // and errors caused by the remote rejecting the push.
     #[error("Unexpected git error when pushing")]
@@ -1441,6 +1789,8 @@ pub enum GitPushError {
 #[derive(Clone, Debug)]
 pub struct GitBranchPushTargets {
     pub branch_updates: Vec<(String, BookmarkPushUpdate)>,
+    /// Tags to create on the remote. Tags are expected not to exist there yet.
+    pub tag_updates: Vec<(String, CommitId)>,
 }
 
 pub struct GitRefUpdate {
@@ -1453,14 +1803,40 @@ pub struct GitRefUpdate {
     pub new_target: Option<CommitId>,
 }
 
+#[derive(Clone, Debug, Default)]
+pub struct GitPushOptions {
+    /// Update either all refs or none of them.
+    ///
+    /// This requires the remote to support atomic pushes. If it doesn't, the
+    /// refs are pushed non-atomically instead.
+    pub atomic: bool,
+    /// Options to transmit to the server (`git push --push-option`).
+    ///
+    /// These can be interpreted by the server hooks, e.g. to create a merge
+    /// request or to set the topic of a change.
+    pub push_options: Vec<String>,
+    /// Sign the push with the key configured in Git (`git push --signed`).
+    ///
+    /// Since libgit2 doesn't support signed pushes, this requires the `git`
+    /// executable.
+    pub signed: bool,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct GitPushStats {
+    /// Whether the refs were updated atomically.
+    pub atomic: bool,
+}
+
 /// Pushes the specified branches and updates the repo view accordingly.
 pub fn push_branches(
     mut_repo: &mut MutableRepo,
     git_repo: &git2::Repository,
     remote_name: &str,
     targets: &GitBranchPushTargets,
+    options: &GitPushOptions,
     callbacks: RemoteCallbacks<'_>,
-) -> Result<(), GitPushError> {
+) -> Result<GitPushStats, GitPushError> {
     let ref_updates = targets
         .branch_updates
         .iter()
@@ -1469,8 +1845,25 @@ pub fn push_branches(
             expected_current_target: update.old_target.clone(),
             new_target: update.new_target.clone(),
         })
+        .chain(
+            targets
+                .tag_updates
+                .iter()
+                .map(|(tag_name, new_target)| GitRefUpdate {
+                    qualified_name: format!("refs/tags/{tag_name}"),
+                    expected_current_target: None,
+                    new_target: Some(new_target.clone()),
+                }),
+        )
         .collect_vec();
-    push_updates(mut_repo, git_repo, remote_name, &ref_updates, callbacks)?;
+    let stats = push_updates(
+        mut_repo,
+        git_repo,
+        remote_name,
+        &ref_updates,
+        options,
+        callbacks,
+    )?;
 
     // TODO: add support for partially pushed refs? we could update the view
     // excluding rejected refs, but the transaction would be aborted anyway
@@ -1485,7 +1878,7 @@ pub fn push_branches(
         mut_repo.set_remote_bookmark(branch_name, remote_name, new_remote_ref);
     }
 
-    Ok(())
+    Ok(stats)
 }
 
 /// Pushes the specified Git refs without updating the repo view.
@@ -1494,8 +1887,40 @@ pub fn push_updates(
     git_repo: &git2::Repository,
     remote_name: &str,
     updates: &[GitRefUpdate],
-    callbacks: RemoteCallbacks<'_>,
-) -> Result<(), GitPushError> {
+    options: &GitPushOptions,
+    mut callbacks: RemoteCallbacks<'_>,
+) -> Result<GitPushStats, GitPushError> {
+    if options.atomic {
+        find_push_remote(git_repo, remote_name)?;
+        match push_updates_with_git(
+            git_repo,
+            remote_name,
+            updates,
+            options,
+            true,
+            &mut callbacks,
+        )? {
+            GitCommandPushOutcome::Pushed => return Ok(GitPushStats { atomic: true }),
+            GitCommandPushOutcome::AtomicUnsupported => {
+                tracing::info!(
+                    "Remote {remote_name} doesn't support atomic pushes, falling back to a \
+                     non-atomic push"
+                );
+            }
+        }
+    }
+    if options.signed {
+        find_push_remote(git_repo, remote_name)?;
+        push_updates_with_git(
+            git_repo,
+            remote_name,
+            updates,
+            options,
+            false,
+            &mut callbacks,
+        )?;
+        return Ok(GitPushStats { atomic: false });
+    }
     let mut qualified_remote_refs_expected_locations = HashMap::new();
     let mut refspecs = vec![];
     for update in updates {
@@ -1523,28 +1948,152 @@ pub fn push_updates(
         remote_name,
         &qualified_remote_refs_expected_locations,
         &refspecs,
+        &options.push_options,
         callbacks,
-    )
+    )?;
+    Ok(GitPushStats { atomic: false })
 }
 
-fn push_refs(
-    repo: &dyn Repo,
+enum GitCommandPushOutcome {
+    Pushed,
+    AtomicUnsupported,
+}
+
+/// Pushes the refs by running `git push`, since libgit2 doesn't support atomic
+/// or signed pushes.
+///
+/// Unlike `push_refs()`, this requires the refs to be exactly at their
+/// expected locations on the remote. The credential callbacks aren't used;
+/// `git` authenticates with the remote using the system Git configuration.
+fn push_updates_with_git(
     git_repo: &git2::Repository,
     remote_name: &str,
-    qualified_remote_refs_expected_locations: &HashMap<&str, Option<&CommitId>>,
-    refspecs: &[String],
-    callbacks: RemoteCallbacks<'_>,
-) -> Result<(), GitPushError> {
+    updates: &[GitRefUpdate],
+    options: &GitPushOptions,
+    atomic: bool,
+    callbacks: &mut RemoteCallbacks<'_>,
+) -> Result<GitCommandPushOutcome, GitPushError> {
+    let mut git = Command::new("git");
+    git.arg("--git-dir=."); // turn off discovery
+    git.args(["push", "--porcelain", "--no-verify"]);
+    if atomic {
+        git.arg("--atomic");
+    }
+    if options.signed {
+        git.arg("--signed");
+    }
+    for push_option in &options.push_options {
+        git.arg(format!("--push-option={push_option}"));
+    }
+    git.arg(remote_name);
+    for update in updates {
+        let expected = update
+            .expected_current_target
+            .as_ref()
+            .map(|id| id.hex())
+            .unwrap_or_default();
+        git.arg(format!(
+            "--force-with-lease={}:{expected}",
+            update.qualified_name
+        ));
+    }
+    for update in updates {
+        let new_target = update
+            .new_target
+            .as_ref()
+            .map(|id| id.hex())
+            .unwrap_or_default();
+        git.arg(format!("{new_target}:{}", update.qualified_name));
+    }
+    // Don't specify it by GIT_DIR/--git-dir. On Windows, the "\\?\" path might
+    // not be supported by git.
+    git.current_dir(git_repo.path());
+    git.stdin(Stdio::null());
+    let output = git
+        .output()
+        .map_err(|err| GitPushError::PushCommand(err.to_string()))?;
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    if let Some(sideband_progress_cb) = callbacks.sideband_progress.as_mut() {
+        for message in stderr
+            .lines()
+            .filter_map(|line| line.strip_prefix("remote: "))
+        {
+            sideband_progress_cb(format!("{}\n", message.trim_end()).as_bytes());
+        }
+    }
+    if output.status.success() {
+        return Ok(GitCommandPushOutcome::Pushed);
+    }
+    if atomic && stderr.contains("does not support --atomic push") {
+        return Ok(GitCommandPushOutcome::AtomicUnsupported);
+    }
+
+    // Each line of the porcelain output is "<flag>\t<from>:<to>\t<summary>".
+    // If one ref is rejected, the other refs are rejected with "atomic push
+    // failed" (or "failure" if rejected by the remote).
+    let mut unexpected_location_refs = vec![];
+    let mut rejected_refs = vec![];
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    for line in stdout.lines() {
+        let Some(("!", refspec, summary)) = line.splitn(3, '\t').collect_tuple() else {
+            continue;
+        };
+        let Some((_, ref_name)) = refspec.split_once(':') else {
+            continue;
+        };
+        if summary.ends_with("(stale info)") {
+            unexpected_location_refs.push(ref_name.to_owned());
+        } else if !summary.contains("(atomic push fail") {
+            rejected_refs.push(ref_name.to_owned());
+        }
+    }
+    if !unexpected_location_refs.is_empty() {
+        unexpected_location_refs.sort();
+        Err(GitPushError::RefInUnexpectedLocation(
+            unexpected_location_refs,
+        ))
+    } else if !rejected_refs.is_empty() {
+        rejected_refs.sort();
+        if atomic {
+            Err(GitPushError::AtomicUpdateRejected(rejected_refs))
+        } else {
+            Err(GitPushError::RefUpdateRejected(rejected_refs))
+        }
+    } else {
+        let message = stderr
+            .lines()
+            .filter(|line| !line.starts_with("remote: "))
+            .join("\n");
+        Err(GitPushError::PushCommand(message))
+    }
+}
+
+fn find_push_remote<'repo>(
+    git_repo: &'repo git2::Repository,
+    remote_name: &str,
+) -> Result<git2::Remote<'repo>, GitPushError> {
     if remote_name == REMOTE_NAME_FOR_LOCAL_GIT_REPO {
         return Err(GitPushError::RemoteReservedForLocalGitRepo);
     }
-    let mut remote = git_repo.find_remote(remote_name).map_err(|err| {
+    git_repo.find_remote(remote_name).map_err(|err| {
         if is_remote_not_found_err(&err) {
             GitPushError::NoSuchRemote(remote_name.to_string())
         } else {
             GitPushError::InternalGitError(err)
         }
-    })?;
+    })
+}
+
+fn push_refs(
+    repo: &dyn Repo,
+    git_repo: &git2::Repository,
+    remote_name: &str,
+    qualified_remote_refs_expected_locations: &HashMap<&str, Option<&CommitId>>,
+    refspecs: &[String],
+    remote_push_options: &[String],
+    callbacks: RemoteCallbacks<'_>,
+) -> Result<(), GitPushError> {
+    let mut remote = find_push_remote(git_repo, remote_name)?;
     let mut remaining_remote_refs: HashSet<_> = qualified_remote_refs_expected_locations
         .keys()
         .copied()
@@ -1555,6 +2104,8 @@ fn push_refs(
         let mut proxy_options = git2::ProxyOptions::new();
         proxy_options.auto();
         push_options.proxy_options(proxy_options);
+        push_options
+            .remote_push_options(&remote_push_options.iter().map(String::as_str).collect_vec());
         let mut callbacks = callbacks.into_git();
         callbacks.push_negotiation(|updates| {
             for update in updates {
@@ -1712,6 +2263,123 @@ fn allow_push(
     }
 }
 
+#[derive(Error, Debug)]
+pub enum GitBundleError {
+    #[error("Failed to run git bundle: {0}")]
+    BundleCommand(String),
+    #[error("Unexpected ref in bundle: {0}")]
+    InvalidRef(String),
+    #[error("The repo is not backed by a git repo")]
+    UnexpectedBackend,
+    #[error(transparent)]
+    Backend(#[from] BackendError),
+    #[error(transparent)]
+    InternalGitError(#[from] git2::Error),
+    #[error(transparent)]
+    Io(#[from] io::Error),
+}
+
+/// Writes a Git bundle containing the commits reachable from the `refs` but
+/// not from the `excluded` commits.
+///
+/// The `refs` are `(qualified_name, target)` pairs to record in the bundle.
+/// The `excluded` commits become the prerequisites of the bundle, which the
+/// receiving repo must have.
+pub fn create_bundle(
+    repo: &dyn Repo,
+    path: &Path,
+    refs: &[(String, CommitId)],
+    excluded: &[CommitId],
+) -> Result<(), GitBundleError> {
+    let git_backend = get_git_backend(repo.store()).ok_or(GitBundleError::UnexpectedBackend)?;
+    // git bundle writes the refs as they are named in the repo. Create them in
+    // a temporary repo which borrows the objects, so the refs of the repo
+    // aren't touched.
+    let temp_dir = tempfile::Builder::new().prefix("jj-bundle-").tempdir()?;
+    git2::Repository::init_bare(temp_dir.path())?;
+    let objects_dir = git_backend.git_repo_path().join("objects");
+    std::fs::write(
+        temp_dir
+            .path()
+            .join("objects")
+            .join("info")
+            .join("alternates"),
+        format!("{}\n", objects_dir.display()),
+    )?;
+    let temp_repo = git2::Repository::open_bare(temp_dir.path())?;
+    for (qualified_name, target) in refs {
+        let oid = Oid::from_bytes(target.as_bytes())?;
+        temp_repo.reference(qualified_name, oid, true, "jj bundle")?;
+    }
+
+    let mut git = Command::new("git");
+    git.arg("--git-dir=."); // turn off discovery
+    git.args(["bundle", "create", "--quiet"]);
+    git.arg(std::env::current_dir()?.join(path));
+    git.args(refs.iter().map(|(qualified_name, _)| qualified_name));
+    git.args(excluded.iter().map(|id| format!("^{}", id.hex())));
+    git.current_dir(temp_dir.path());
+    git.stdin(Stdio::null());
+    let output = git.output()?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(GitBundleError::BundleCommand(stderr.trim_end().to_owned()));
+    }
+    Ok(())
+}
+
+/// Reads the commits from the Git bundle at `path` into the repo, and makes
+/// them visible.
+///
+/// Returns the `(qualified_name, target)` pairs recorded in the bundle. Tags
+/// are peeled to the commits they point to.
+pub fn unbundle(
+    mut_repo: &mut MutableRepo,
+    path: &Path,
+) -> Result<Vec<(String, CommitId)>, GitBundleError> {
+    let store = mut_repo.store().clone();
+    let git_backend = get_git_backend(&store).ok_or(GitBundleError::UnexpectedBackend)?;
+    let git_repo = git2::Repository::open_bare(git_backend.git_repo_path())?;
+
+    let mut git = Command::new("git");
+    git.arg("--git-dir=."); // turn off discovery
+    git.args(["bundle", "unbundle"]);
+    git.arg(std::env::current_dir()?.join(path));
+    git.current_dir(git_backend.git_repo_path());
+    git.stdin(Stdio::null());
+    let output = git.output()?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(GitBundleError::BundleCommand(stderr.trim_end().to_owned()));
+    }
+
+    // Each line of the output is "<object id> <ref name>".
+    let mut refs = vec![];
+    for line in String::from_utf8_lossy(&output.stdout).lines() {
+        let (hex, qualified_name) = line
+            .split_once(' ')
+            .ok_or_else(|| GitBundleError::InvalidRef(line.to_owned()))?;
+        let oid = Oid::from_str(hex).map_err(|_| GitBundleError::InvalidRef(line.to_owned()))?;
+        let commit = git_repo
+            .find_object(oid, None)
+            .and_then(|object| object.peel_to_commit())
+            .map_err(|_| GitBundleError::InvalidRef(line.to_owned()))?;
+        refs.push((
+            qualified_name.to_owned(),
+            CommitId::from_bytes(commit.id().as_bytes()),
+        ));
+    }
+
+    let head_ids = refs.iter().map(|(_, id)| id).unique().collect_vec();
+    git_backend.import_head_commits(head_ids.iter().copied())?;
+    let head_commits: Vec<_> = head_ids
+        .into_iter()
+        .map(|id| store.get_commit(id))
+        .try_collect()?;
+    mut_repo.add_heads(&head_commits)?;
+    Ok(refs)
+}
+
 #[non_exhaustive]
 #[derive(Default)]
 #[allow(clippy::type_complexity)]
@@ -1724,6 +2392,17 @@ pub struct RemoteCallbacks<'a> {
 }
 
 impl<'a> RemoteCallbacks<'a> {
+    /// Borrows the callbacks so they can be used for more than one connection.
+    fn reborrow(&mut self) -> RemoteCallbacks<'_> {
+        RemoteCallbacks {
+            progress: self.progress.as_deref_mut().map(|cb| cb as _),
+            sideband_progress: self.sideband_progress.as_deref_mut().map(|cb| cb as _),
+            get_ssh_keys: self.get_ssh_keys.as_deref_mut().map(|cb| cb as _),
+            get_password: self.get_password.as_deref_mut().map(|cb| cb as _),
+            get_username_password: self.get_username_password.as_deref_mut().map(|cb| cb as _),
+        }
+    }
+
     fn into_git(mut self) -> git2::RemoteCallbacks<'a> {
         let mut callbacks = git2::RemoteCallbacks::new();
         if let Some(progress_cb) = self.progress {
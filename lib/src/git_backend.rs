@@ -23,10 +23,12 @@ use std::fs;
 use std::io;
 use std::io::Cursor;
 use std::io::Read;
+use std::io::Write as _;
 use std::path::Path;
 use std::path::PathBuf;
 use std::process::Command;
 use std::process::ExitStatus;
+use std::process::Stdio;
 use std::str;
 use std::sync::Arc;
 use std::sync::Mutex;
@@ -58,12 +60,17 @@ use crate::backend::Conflict;
 use crate::backend::ConflictId;
 use crate::backend::ConflictTerm;
 use crate::backend::CopyRecord;
+use crate::backend::EnumerableBackend;
+use crate::backend::FetchableObjectId;
 use crate::backend::FileId;
 use crate::backend::MergedTreeId;
 use crate::backend::MillisSinceEpoch;
+use crate::backend::ObjectKind;
+use crate::backend::PartialFetchBackend;
 use crate::backend::SecureSig;
 use crate::backend::Signature;
 use crate::backend::SigningFn;
+use crate::backend::StoredObject;
 use crate::backend::SymlinkId;
 use crate::backend::Timestamp;
 use crate::backend::Tree;
@@ -131,6 +138,10 @@ pub enum GitBackendError {
     ReadMetadata(#[source] TableStoreError),
     #[error("Failed to write non-git metadata")]
     WriteMetadata(#[source] TableStoreError),
+    #[error("Failed to run git fetch command")]
+    FetchCommand(#[source] std::io::Error),
+    #[error("Failed to fetch missing objects from remote {remote}: {message}")]
+    FetchMissingObjects { remote: String, message: String },
 }
 
 impl From<GitBackendError> for BackendError {
@@ -159,6 +170,8 @@ pub struct GitBackend {
     empty_tree_id: TreeId,
     extra_metadata_store: TableStore,
     cached_extra_metadata: Mutex<Option<Arc<ReadonlyTable>>>,
+    /// Remote to fetch missing objects from if the repo is a partial clone.
+    promisor_remote: Option<String>,
 }
 
 impl GitBackend {
@@ -167,7 +180,9 @@ impl GitBackend {
     }
 
     fn new(base_repo: gix::ThreadSafeRepository, extra_metadata_store: TableStore) -> Self {
-        let repo = Mutex::new(base_repo.to_thread_local());
+        let repo = base_repo.to_thread_local();
+        let promisor_remote = find_promisor_remote(&repo);
+        let repo = Mutex::new(repo);
         let root_commit_id = CommitId::from_bytes(&[0; HASH_LENGTH]);
         let root_change_id = ChangeId::from_bytes(&[0; CHANGE_ID_LENGTH]);
         let empty_tree_id = TreeId::from_hex("4b825dc642cb6eb9a060e54bf8d69288fbee4904");
@@ -179,6 +194,7 @@ impl GitBackend {
             empty_tree_id,
             extra_metadata_store,
             cached_extra_metadata: Mutex::new(None),
+            promisor_remote,
         }
     }
 
@@ -817,6 +833,64 @@ fn run_git_gc(git_dir: &Path) -> Result<(), GitGcError> {
     Ok(())
 }
 
+/// Returns the remote to fetch missing objects from if `repo` is a partial
+/// clone.
+fn find_promisor_remote(repo: &gix::Repository) -> Option<String> {
+    let config = repo.config_snapshot();
+    if let Some(remote) = config.string("extensions.partialClone") {
+        return Some(remote.to_string());
+    }
+    repo.remote_names()
+        .into_iter()
+        .find(|name| config.boolean(format!("remote.{name}.promisor").as_str()) == Some(true))
+        .map(|name| name.to_string())
+}
+
+/// Fetches the given objects from the promisor remote of a partial clone. This
+/// is what Git itself does when an object is missing.
+fn run_git_fetch_objects(
+    git_dir: &Path,
+    remote: &str,
+    oids: &[gix::ObjectId],
+) -> Result<(), GitBackendError> {
+    let mut git = Command::new("git");
+    git.arg("--git-dir=."); // turn off discovery
+    git.args(["-c", "fetch.negotiationAlgorithm=noop", "fetch"]);
+    git.args([
+        "--quiet",
+        "--no-tags",
+        "--no-write-fetch-head",
+        "--recurse-submodules=no",
+        "--filter=blob:none",
+        "--stdin",
+        remote,
+    ]);
+    // Don't specify it by GIT_DIR/--git-dir. On Windows, the "\\?\" path might
+    // not be supported by git.
+    git.current_dir(git_dir);
+    git.stdin(Stdio::piped());
+    git.stdout(Stdio::null());
+    git.stderr(Stdio::piped());
+    let mut child = git.spawn().map_err(GitBackendError::FetchCommand)?;
+    let mut stdin = child.stdin.take().unwrap();
+    for oid in oids {
+        writeln!(stdin, "{oid}").map_err(GitBackendError::FetchCommand)?;
+    }
+    drop(stdin);
+    let output = child
+        .wait_with_output()
+        .map_err(GitBackendError::FetchCommand)?;
+    if !output.status.success() {
+        return Err(GitBackendError::FetchMissingObjects {
+            remote: remote.to_owned(),
+            message: String::from_utf8_lossy(&output.stderr)
+                .trim_end()
+                .to_owned(),
+        });
+    }
+    Ok(())
+}
+
 fn validate_git_object_id(id: &impl ObjectId) -> BackendResult<gix::ObjectId> {
     if id.as_bytes().len() != HASH_LENGTH {
         return Err(BackendError::InvalidHashLength {
@@ -911,6 +985,71 @@ impl Debug for GitBackend {
     }
 }
 
+impl EnumerableBackend for GitBackend {
+    fn all_objects(&self) -> BackendResult<Vec<StoredObject>> {
+        let locked_repo = self.lock_git_repo();
+        let mut objects = vec![];
+        let mut seen = HashSet::new();
+        let ids = locked_repo
+            .objects
+            .iter()
+            .map_err(|err| BackendError::Other(err.into()))?;
+        for id in ids {
+            let id = id.map_err(|err| BackendError::Other(err.into()))?;
+            // The same object may be stored in more than one pack
+            if !seen.insert(id) {
+                continue;
+            }
+            let header = locked_repo
+                .find_header(id)
+                .map_err(|err| BackendError::Other(err.into()))?;
+            let kind = match header.kind() {
+                // Symlinks and conflicts are also stored as blobs
+                gix::object::Kind::Blob => ObjectKind::File,
+                gix::object::Kind::Tree => ObjectKind::Tree,
+                gix::object::Kind::Commit => ObjectKind::Commit,
+                gix::object::Kind::Tag => continue,
+            };
+            objects.push(StoredObject {
+                kind,
+                id: id.as_bytes().to_vec(),
+                size: header.size(),
+            });
+        }
+        Ok(objects)
+    }
+}
+
+impl PartialFetchBackend for GitBackend {
+    fn fetch_objects(&self, ids: &[FetchableObjectId]) -> BackendResult<()> {
+        let Some(remote) = &self.promisor_remote else {
+            return Ok(());
+        };
+        let mut oids = Vec::with_capacity(ids.len());
+        {
+            let locked_repo = self.lock_git_repo();
+            for id in ids {
+                let oid = match id {
+                    FetchableObjectId::File(id) => validate_git_object_id(id)?,
+                    FetchableObjectId::Symlink(id) => validate_git_object_id(id)?,
+                    FetchableObjectId::Tree(id) => validate_git_object_id(id)?,
+                };
+                if !locked_repo.has_object(oid) {
+                    oids.push(oid);
+                }
+            }
+        }
+        if oids.is_empty() {
+            return Ok(());
+        }
+        oids.sort_unstable();
+        oids.dedup();
+        tracing::info!(count = oids.len(), remote, "fetching missing objects");
+        run_git_fetch_objects(self.git_repo_path(), remote, &oids)?;
+        Ok(())
+    }
+}
+
 #[async_trait]
 impl Backend for GitBackend {
     fn as_any(&self) -> &dyn Any {
@@ -945,6 +1084,16 @@ impl Backend for GitBackend {
         1
     }
 
+    fn partial_fetch(&self) -> Option<&dyn PartialFetchBackend> {
+        self.promisor_remote
+            .is_some()
+            .then_some(self as &dyn PartialFetchBackend)
+    }
+
+    fn enumerable(&self) -> Option<&dyn EnumerableBackend> {
+        Some(self)
+    }
+
     async fn read_file(&self, _path: &RepoPath, id: &FileId) -> BackendResult<Box<dyn Read>> {
         self.read_file_sync(id)
     }
@@ -1834,6 +1983,30 @@ mod tests {
         assert_eq!(git_signature2.email, EMPTY_STRING_PLACEHOLDER);
     }
 
+    #[test]
+    fn all_objects() {
+        let settings = user_settings();
+        let temp_dir = testutils::new_temp_dir();
+        let store_path = temp_dir.path();
+        let git_repo_path = temp_dir.path().join("git");
+        let git_repo = git2::Repository::init(git_repo_path).unwrap();
+
+        let backend = GitBackend::init_external(&settings, store_path, git_repo.path()).unwrap();
+        let file_id = backend
+            .write_file(RepoPath::root(), &mut &b"content"[..])
+            .block_on()
+            .unwrap();
+        let objects = backend.all_objects().unwrap();
+        assert_eq!(
+            objects,
+            [StoredObject {
+                kind: ObjectKind::File,
+                id: file_id.to_bytes(),
+                size: 7,
+            }]
+        );
+    }
+
     /// Test that parents get written correctly
     #[test]
     fn git_commit_parents() {
@@ -0,0 +1,197 @@
+// Copyright 2026 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Parsing of `.gitattributes` files.
+
+use ignore::gitignore;
+
+use crate::repo_path::RepoPath;
+
+/// State of an attribute for a path.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum AttributeState {
+    /// The attribute is set (`attr`).
+    Set,
+    /// The attribute is unset (`-attr`).
+    Unset,
+    /// The attribute is set to a value (`attr=value`).
+    Value(String),
+    /// No pattern says anything about the attribute, or it was reset to the
+    /// unspecified state (`!attr`).
+    Unspecified,
+}
+
+/// Patterns and attributes parsed from a `.gitattributes` file.
+///
+/// Only the file at the root of the workspace is supported. Macro definitions
+/// are ignored except for the built-in `binary` macro.
+#[derive(Debug, Default)]
+pub struct GitAttributes {
+    rules: Vec<AttributeRule>,
+}
+
+#[derive(Debug)]
+struct AttributeRule {
+    matcher: gitignore::Gitignore,
+    attributes: Vec<(String, AttributeState)>,
+}
+
+impl GitAttributes {
+    /// Returns attributes which don't match any path.
+    pub fn empty() -> Self {
+        GitAttributes::default()
+    }
+
+    /// Parses the content of a `.gitattributes` file. Lines that can't be
+    /// parsed are skipped, like Git does.
+    pub fn parse(input: &[u8]) -> Self {
+        let rules = input
+            .split(|b| *b == b'\n')
+            .filter_map(|line| std::str::from_utf8(line).ok())
+            .filter_map(parse_line)
+            .collect();
+        GitAttributes { rules }
+    }
+
+    /// Returns true if no attributes are defined.
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    /// Looks up the state of the attribute `name` for the file at `path`. If
+    /// multiple lines match, the last one wins.
+    pub fn get(&self, path: &RepoPath, name: &str) -> AttributeState {
+        let path = path.as_internal_file_string();
+        self.rules
+            .iter()
+            .rev()
+            .filter(|rule| rule.matcher.matched(path, false).is_ignore())
+            .find_map(|rule| {
+                rule.attributes
+                    .iter()
+                    .rev()
+                    .find(|(attr, _)| attr == name)
+                    .map(|(_, state)| state.clone())
+            })
+            .unwrap_or(AttributeState::Unspecified)
+    }
+}
+
+fn parse_line(line: &str) -> Option<AttributeRule> {
+    let line = line.trim();
+    if line.is_empty() || line.starts_with('#') || line.starts_with("[attr]") {
+        return None;
+    }
+    let mut tokens = line.split_ascii_whitespace();
+    let pattern = tokens.next()?;
+    // Negative patterns are forbidden in .gitattributes.
+    if pattern.starts_with('!') {
+        return None;
+    }
+    let mut builder = gitignore::GitignoreBuilder::new("");
+    builder.add_line(None, pattern).ok()?;
+    let matcher = builder.build().ok()?;
+    let mut attributes = vec![];
+    for token in tokens {
+        if token == "binary" {
+            // Built-in macro: "binary" is "-diff -merge -text".
+            for name in ["diff", "merge", "text"] {
+                attributes.push((name.to_owned(), AttributeState::Unset));
+            }
+            attributes.push((token.to_owned(), AttributeState::Set));
+        } else if let Some(name) = token.strip_prefix('-') {
+            attributes.push((name.to_owned(), AttributeState::Unset));
+        } else if let Some(name) = token.strip_prefix('!') {
+            attributes.push((name.to_owned(), AttributeState::Unspecified));
+        } else if let Some((name, value)) = token.split_once('=') {
+            attributes.push((name.to_owned(), AttributeState::Value(value.to_owned())));
+        } else {
+            attributes.push((token.to_owned(), AttributeState::Set));
+        }
+    }
+    Some(AttributeRule {
+        matcher,
+        attributes,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn get(input: &[u8], path: &str, name: &str) -> AttributeState {
+        let attributes = GitAttributes::parse(input);
+        attributes.get(RepoPath::from_internal_string(path), name)
+    }
+
+    #[test]
+    fn test_gitattributes_empty() {
+        let attributes = GitAttributes::empty();
+        assert!(attributes.is_empty());
+        assert_eq!(
+            attributes.get(RepoPath::from_internal_string("foo"), "text"),
+            AttributeState::Unspecified
+        );
+    }
+
+    #[test]
+    fn test_gitattributes_states() {
+        let input = b"a text\nb -text\nc text=auto\nd !text\n";
+        assert_eq!(get(input, "a", "text"), AttributeState::Set);
+        assert_eq!(get(input, "b", "text"), AttributeState::Unset);
+        assert_eq!(
+            get(input, "c", "text"),
+            AttributeState::Value("auto".to_owned())
+        );
+        assert_eq!(get(input, "d", "text"), AttributeState::Unspecified);
+        assert_eq!(get(input, "e", "text"), AttributeState::Unspecified);
+        assert_eq!(get(input, "a", "eol"), AttributeState::Unspecified);
+    }
+
+    #[test]
+    fn test_gitattributes_last_match_wins() {
+        let input = b"* text=auto eol=lf\n*.bat eol=crlf\n*.png binary\n";
+        assert_eq!(
+            get(input, "dir/run.bat", "eol"),
+            AttributeState::Value("crlf".to_owned())
+        );
+        assert_eq!(
+            get(input, "dir/run.bat", "text"),
+            AttributeState::Value("auto".to_owned())
+        );
+        assert_eq!(
+            get(input, "README", "eol"),
+            AttributeState::Value("lf".to_owned())
+        );
+        assert_eq!(get(input, "img/a.png", "text"), AttributeState::Unset);
+        assert_eq!(get(input, "img/a.png", "binary"), AttributeState::Set);
+    }
+
+    #[test]
+    fn test_gitattributes_skipped_lines() {
+        let input = b"# comment\n\n[attr]foo text\n!neg text\n  bar   text  \n";
+        assert_eq!(get(input, "foo", "text"), AttributeState::Unspecified);
+        assert_eq!(get(input, "neg", "text"), AttributeState::Unspecified);
+        assert_eq!(get(input, "bar", "text"), AttributeState::Set);
+    }
+
+    #[test]
+    fn test_gitattributes_directory_pattern() {
+        // Unlike .gitignore, patterns matching a directory don't apply to the
+        // files in it.
+        let input = b"dir text\ndir2/** text\n";
+        assert_eq!(get(input, "dir/file", "text"), AttributeState::Unspecified);
+        assert_eq!(get(input, "dir2/file", "text"), AttributeState::Set);
+    }
+}
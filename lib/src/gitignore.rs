@@ -21,6 +21,8 @@ use std::path::Path;
 use std::path::PathBuf;
 use std::sync::Arc;
 
+use blake2::Blake2b512;
+use digest::Digest;
 use ignore::gitignore;
 use thiserror::Error;
 
@@ -47,6 +49,7 @@ pub enum GitIgnoreError {
 pub struct GitIgnoreFile {
     parent: Option<Arc<GitIgnoreFile>>,
     matcher: gitignore::Gitignore,
+    fingerprint: u64,
 }
 
 impl GitIgnoreFile {
@@ -54,9 +57,19 @@ impl GitIgnoreFile {
         Arc::new(GitIgnoreFile {
             parent: None,
             matcher: gitignore::Gitignore::empty(),
+            fingerprint: 0,
         })
     }
 
+    /// Returns a hash of the patterns (and their prefixes) chained so far.
+    ///
+    /// Two `GitIgnoreFile`s built from the same sequence of inputs have the
+    /// same fingerprint, so it can be persisted to detect changes to the
+    /// ignore rules between runs.
+    pub fn fingerprint(&self) -> u64 {
+        self.fingerprint
+    }
+
     /// Concatenates new `.gitignore` content at the `prefix` directory.
     ///
     /// The `prefix` should be a slash-separated path relative to the workspace
@@ -95,7 +108,18 @@ impl GitIgnoreFile {
         } else {
             Some(self.clone())
         };
-        Ok(Arc::new(GitIgnoreFile { parent, matcher }))
+        let mut hasher = Blake2b512::new();
+        hasher.update(self.fingerprint.to_le_bytes());
+        hasher.update((prefix.len() as u64).to_le_bytes());
+        hasher.update(prefix.as_bytes());
+        hasher.update(input);
+        let digest = hasher.finalize();
+        let fingerprint = u64::from_le_bytes(digest[..8].try_into().unwrap());
+        Ok(Arc::new(GitIgnoreFile {
+            parent,
+            matcher,
+            fingerprint,
+        }))
     }
 
     /// Concatenates new `.gitignore` file at the `prefix` directory.
@@ -453,4 +477,20 @@ mod tests {
             .unwrap();
         assert!(ignore.matches("foo/bar.ext"));
     }
+
+    #[test]
+    fn test_gitignore_fingerprint() {
+        let chain = |prefix: &str, input: &[u8]| {
+            GitIgnoreFile::empty()
+                .chain("", Path::new(""), b"target\n")
+                .unwrap()
+                .chain(prefix, Path::new(""), input)
+                .unwrap()
+                .fingerprint()
+        };
+        assert_ne!(GitIgnoreFile::empty().fingerprint(), chain("", b""));
+        assert_eq!(chain("dir/", b"foo\n"), chain("dir/", b"foo\n"));
+        assert_ne!(chain("dir/", b"foo\n"), chain("dir/", b"bar\n"));
+        assert_ne!(chain("dir/", b"foo\n"), chain("dir2/", b"foo\n"));
+    }
 }
@@ -206,6 +206,91 @@ impl SigningBackend for GpgBackend {
     }
 }
 
+/// Signing backend for X.509 certificates using GnuPG's `gpgsm`.
+#[derive(Debug)]
+pub struct GpgsmBackend {
+    program: OsString,
+    allow_expired_keys: bool,
+    extra_args: Vec<OsString>,
+}
+
+impl GpgsmBackend {
+    pub fn new(program: OsString, allow_expired_keys: bool) -> Self {
+        Self {
+            program,
+            allow_expired_keys,
+            extra_args: vec![],
+        }
+    }
+
+    /// Primarily intended for testing
+    pub fn with_extra_args(mut self, args: &[OsString]) -> Self {
+        self.extra_args.extend_from_slice(args);
+        self
+    }
+
+    pub fn from_settings(settings: &UserSettings) -> Result<Self, ConfigGetError> {
+        let program = settings
+            .get_string("signing.backends.gpgsm.program")
+            .optional()?
+            .unwrap_or_else(|| "gpgsm".into());
+        let allow_expired_keys = settings
+            .get_bool("signing.backends.gpgsm.allow-expired-keys")
+            .optional()?
+            .unwrap_or(false);
+        Ok(Self::new(program.into(), allow_expired_keys))
+    }
+
+    fn create_command(&self) -> Command {
+        let mut command = Command::new(&self.program);
+        command
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .args(&self.extra_args);
+        command
+    }
+}
+
+impl SigningBackend for GpgsmBackend {
+    fn name(&self) -> &str {
+        "gpgsm"
+    }
+
+    fn can_read(&self, signature: &[u8]) -> bool {
+        signature.starts_with(b"-----BEGIN SIGNED MESSAGE-----")
+    }
+
+    fn sign(&self, data: &[u8], key: Option<&str>) -> Result<Vec<u8>, SignError> {
+        Ok(match key {
+            Some(key) => run_sign_command(self.create_command().args(["-abu", key]), data)?,
+            None => run_sign_command(self.create_command().arg("-ab"), data)?,
+        })
+    }
+
+    fn verify(&self, data: &[u8], signature: &[u8]) -> Result<Verification, SignError> {
+        let mut signature_file = tempfile::Builder::new()
+            .prefix(".jj-gpgsm-sig-tmp-")
+            .tempfile()
+            .map_err(GpgError::Io)?;
+        signature_file.write_all(signature).map_err(GpgError::Io)?;
+        signature_file.flush().map_err(GpgError::Io)?;
+
+        let sig_path = signature_file.into_temp_path();
+
+        // gpgsm reports the certificate fingerprint and subject in the same
+        // status lines as gpg does for OpenPGP keys.
+        let output = run_verify_command(
+            self.create_command()
+                .args(["--status-fd=1", "--verify"])
+                .arg(&sig_path)
+                .arg("-"),
+            data,
+        )?;
+
+        parse_gpg_verify_output(&output, self.allow_expired_keys)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -72,6 +72,16 @@ pub trait IndexStore: Send + Sync + Debug {
         index: Box<dyn MutableIndex>,
         op: &Operation,
     ) -> Result<Box<dyn ReadonlyIndex>, IndexWriteError>;
+
+    /// Writes the commits added to `index` so far to the index store before
+    /// the transaction is committed, so the in-memory part of the index
+    /// doesn't grow without bound. The `index` still contains the same commits
+    /// afterwards.
+    ///
+    /// The default implementation does nothing.
+    fn flush_index(&self, _index: &mut dyn MutableIndex) -> Result<(), IndexWriteError> {
+        Ok(())
+    }
 }
 
 /// Defines the interface for types that provide an index of the commits in a
@@ -97,6 +107,19 @@ pub trait Index: Send + Sync {
     /// `descendant_id` commit, or if `ancestor_id` equals `descendant_id`.
     fn is_ancestor(&self, ancestor_id: &CommitId, descendant_id: &CommitId) -> bool;
 
+    /// Returns whether the first commit of each `(ancestor_id, descendant_id)`
+    /// pair is an ancestor of the second commit.
+    ///
+    /// The default implementation calls [`Index::is_ancestor()`] for each
+    /// pair. Implementations may override it to visit ancestors shared by
+    /// many queries only once.
+    fn is_ancestor_batch(&self, pairs: &[(CommitId, CommitId)]) -> Vec<bool> {
+        pairs
+            .iter()
+            .map(|(ancestor_id, descendant_id)| self.is_ancestor(ancestor_id, descendant_id))
+            .collect()
+    }
+
     /// Returns the best common ancestor or ancestors of the commits in `set1`
     /// and `set2`. A "best common ancestor" has no descendants that are also
     /// common ancestors.
@@ -144,6 +167,8 @@ pub trait ReadonlyIndex: Send + Sync {
 pub trait MutableIndex {
     fn as_any(&self) -> &dyn Any;
 
+    fn as_any_mut(&mut self) -> &mut dyn Any;
+
     fn into_any(self: Box<Self>) -> Box<dyn Any>;
 
     fn as_index(&self) -> &dyn Index;
@@ -35,6 +35,7 @@ pub mod commit;
 pub mod commit_builder;
 pub mod config;
 mod config_resolver;
+pub mod conflict_cache;
 pub mod conflicts;
 pub mod copies;
 pub mod dag_walk;
@@ -53,6 +54,7 @@ pub mod fsmonitor;
 pub mod git;
 #[cfg(feature = "git")]
 pub mod git_backend;
+pub mod gitattributes;
 pub mod gitignore;
 pub mod gpg_signing;
 pub mod graph;
@@ -65,6 +67,7 @@ pub mod lock;
 pub mod matchers;
 pub mod merge;
 pub mod merged_tree;
+pub mod native_sync;
 pub mod object_id;
 pub mod op_heads_store;
 pub mod op_store;
@@ -74,7 +77,9 @@ pub mod operation;
 pub mod protos;
 pub mod refs;
 pub mod repo;
+pub mod repo_backup;
 pub mod repo_path;
+pub mod resolution_cache;
 pub mod revset;
 mod revset_parser;
 pub mod rewrite;
@@ -84,16 +89,21 @@ pub mod settings;
 pub mod signing;
 pub mod simple_op_heads_store;
 pub mod simple_op_store;
+pub mod sparse;
 pub mod ssh_signing;
 pub mod stacked_table;
 pub mod store;
 pub mod str_util;
 pub mod submodule_store;
+#[cfg(feature = "testing")]
+pub mod test_signing_backend;
 pub mod time_util;
 pub mod transaction;
 pub mod tree;
 pub mod tree_builder;
+pub mod tree_export;
 pub mod union_find;
 pub mod view;
 pub mod working_copy;
 pub mod workspace;
+pub mod workspace_store;
@@ -15,9 +15,11 @@
 #![allow(missing_docs)]
 
 use std::any::Any;
+use std::collections::HashSet;
 use std::fmt::Debug;
 use std::fs;
 use std::fs::File;
+use std::io;
 use std::io::Read;
 use std::io::Write;
 use std::path::Path;
@@ -36,6 +38,8 @@ use tempfile::NamedTempFile;
 use crate::backend::make_root_commit;
 use crate::backend::Backend;
 use crate::backend::BackendError;
+use crate::backend::BackendInitError;
+use crate::backend::BackendLoadError;
 use crate::backend::BackendResult;
 use crate::backend::ChangeId;
 use crate::backend::Commit;
@@ -44,12 +48,15 @@ use crate::backend::Conflict;
 use crate::backend::ConflictId;
 use crate::backend::ConflictTerm;
 use crate::backend::CopyRecord;
+use crate::backend::EnumerableBackend;
 use crate::backend::FileId;
 use crate::backend::MergedTreeId;
 use crate::backend::MillisSinceEpoch;
+use crate::backend::ObjectKind;
 use crate::backend::SecureSig;
 use crate::backend::Signature;
 use crate::backend::SigningFn;
+use crate::backend::StoredObject;
 use crate::backend::SymlinkId;
 use crate::backend::Timestamp;
 use crate::backend::Tree;
@@ -57,6 +64,8 @@ use crate::backend::TreeId;
 use crate::backend::TreeValue;
 use crate::content_hash::blake2b_hash;
 use crate::file_util::persist_content_addressed_temp_file;
+use crate::file_util::IoResultExt as _;
+use crate::file_util::PathError;
 use crate::index::Index;
 use crate::merge::MergeBuilder;
 use crate::object_id::ObjectId;
@@ -67,6 +76,25 @@ use crate::repo_path::RepoPathComponentBuf;
 const COMMIT_ID_LENGTH: usize = 64;
 const CHANGE_ID_LENGTH: usize = 16;
 
+const OBJECT_DIRS: [&str; 5] = ["commits", "trees", "files", "symlinks", "conflicts"];
+/// Kinds of the objects in `OBJECT_DIRS`, in the same order.
+const OBJECT_KINDS: [ObjectKind; 5] = [
+    ObjectKind::Commit,
+    ObjectKind::Tree,
+    ObjectKind::File,
+    ObjectKind::Symlink,
+    ObjectKind::Conflict,
+];
+/// Name of the file in the store directory which points to the shared object
+/// store.
+const SHARED_STORE_FILE: &str = "shared_store";
+/// Directory in the shared object store where the referencing stores are
+/// registered.
+const SHARED_STORE_USERS_DIR: &str = "users";
+/// Directory in the shared object store where the commits which each
+/// referencing store may still reach are recorded, one ID per line.
+const SHARED_STORE_HEADS_DIR: &str = "heads";
+
 fn map_not_found_err(err: std::io::Error, id: &impl ObjectId) -> BackendError {
     if err.kind() == std::io::ErrorKind::NotFound {
         BackendError::ObjectNotFound {
@@ -87,9 +115,20 @@ fn to_other_err(err: impl Into<Box<dyn std::error::Error + Send + Sync>>) -> Bac
     BackendError::Other(err.into())
 }
 
+/// Backend which stores objects as individual files in the store directory.
+///
+/// The objects can also be stored in a shared object store directory, which
+/// is referenced by many repos (like Git's alternates.) New objects are then
+/// written to the shared store, and objects are looked up in the private store
+/// directory first.
+///
+/// Each repo records the heads it had at its last GC plus the commits it
+/// wrote since then in the shared store, so that a GC run by any of the repos
+/// keeps the objects reachable from the others.
 #[derive(Debug)]
 pub struct LocalBackend {
     path: PathBuf,
+    shared_path: Option<PathBuf>,
     root_commit_id: CommitId,
     root_change_id: ChangeId,
     empty_tree_id: TreeId,
@@ -101,55 +140,308 @@ impl LocalBackend {
     }
 
     pub fn init(store_path: &Path) -> Self {
-        fs::create_dir(store_path.join("commits")).unwrap();
-        fs::create_dir(store_path.join("trees")).unwrap();
-        fs::create_dir(store_path.join("files")).unwrap();
-        fs::create_dir(store_path.join("symlinks")).unwrap();
-        fs::create_dir(store_path.join("conflicts")).unwrap();
-        let backend = Self::load(store_path);
-        let empty_tree_id = backend
-            .write_tree(RepoPath::root(), &Tree::default())
-            .block_on()
-            .unwrap();
-        assert_eq!(empty_tree_id, backend.empty_tree_id);
+        create_object_dirs(store_path).unwrap();
+        let backend = Self::new(store_path.to_path_buf(), None);
+        backend.write_empty_tree();
         backend
     }
 
-    pub fn load(store_path: &Path) -> Self {
+    /// Initializes a backend which writes objects to the shared object store
+    /// at `shared_store_path`. The shared store is created if it doesn't
+    /// exist.
+    pub fn init_with_shared_store(
+        store_path: &Path,
+        shared_store_path: &Path,
+    ) -> Result<Self, BackendInitError> {
+        let init_err = |err: io::Error| BackendInitError(err.into());
+        create_object_dirs(store_path).map_err(init_err)?;
+        fs::create_dir_all(shared_store_path.join(SHARED_STORE_USERS_DIR)).map_err(init_err)?;
+        fs::create_dir_all(shared_store_path.join(SHARED_STORE_HEADS_DIR)).map_err(init_err)?;
+        for dir in OBJECT_DIRS {
+            fs::create_dir_all(shared_store_path.join(dir)).map_err(init_err)?;
+        }
+        let store_path = store_path.canonicalize().map_err(init_err)?;
+        let shared_store_path = shared_store_path.canonicalize().map_err(init_err)?;
+        let shared_store_str = shared_store_path.to_str().ok_or_else(|| {
+            BackendInitError(
+                format!("Shared store path is not valid UTF-8: {shared_store_path:?}").into(),
+            )
+        })?;
+        fs::write(store_path.join(SHARED_STORE_FILE), shared_store_str).map_err(init_err)?;
+        let backend = Self::new(store_path, Some(shared_store_path));
+        backend.register_shared_store_user().map_err(init_err)?;
+        backend.write_empty_tree();
+        Ok(backend)
+    }
+
+    pub fn load(store_path: &Path) -> Result<Self, BackendLoadError> {
+        let shared_path = match fs::read_to_string(store_path.join(SHARED_STORE_FILE)) {
+            Ok(content) => Some(store_path.join(content.trim_end())),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => None,
+            Err(err) => return Err(BackendLoadError(err.into())),
+        };
+        Ok(Self::new(store_path.to_path_buf(), shared_path))
+    }
+
+    fn new(path: PathBuf, shared_path: Option<PathBuf>) -> Self {
         let root_commit_id = CommitId::from_bytes(&[0; COMMIT_ID_LENGTH]);
         let root_change_id = ChangeId::from_bytes(&[0; CHANGE_ID_LENGTH]);
         let empty_tree_id = TreeId::from_hex(
             "482ae5a29fbe856c7272f2071b8b0f0359ee2d89ff392b8a900643fbd0836eccd067b8bf41909e206c90d45d6e7d8b6686b93ecaee5fe1a9060d87b672101310",
         );
         LocalBackend {
-            path: store_path.to_path_buf(),
+            path,
+            shared_path,
             root_commit_id,
             root_change_id,
             empty_tree_id,
         }
     }
 
-    fn file_path(&self, id: &FileId) -> PathBuf {
-        self.path.join("files").join(id.hex())
+    fn write_empty_tree(&self) {
+        let empty_tree_id = self
+            .write_tree(RepoPath::root(), &Tree::default())
+            .block_on()
+            .unwrap();
+        assert_eq!(empty_tree_id, self.empty_tree_id);
+    }
+
+    /// Path to the shared object store if configured.
+    pub fn shared_store_path(&self) -> Option<&Path> {
+        self.shared_path.as_deref()
+    }
+
+    /// Copies the objects missing from this store from the `source` store,
+    /// including the objects in its shared store. Returns the number of
+    /// copied objects.
+    ///
+    /// Objects are copied before the commits and trees referring to them, so
+    /// an interrupted copy never leaves a commit behind whose contents are
+    /// missing.
+    pub fn copy_missing_objects_from(&self, source: &LocalBackend) -> Result<usize, PathError> {
+        let mut count = 0;
+        for dir in OBJECT_DIRS.into_iter().rev() {
+            let dest_dir = self.objects_path().join(dir);
+            for source_store_path in itertools::chain([&source.path], &source.shared_path) {
+                let source_dir = source_store_path.join(dir);
+                for entry in source_dir.read_dir().context(&source_dir)? {
+                    let entry = entry.context(&source_dir)?;
+                    let file_name = entry.file_name();
+                    let Some(hex) = file_name.to_str() else {
+                        continue;
+                    };
+                    if hex.starts_with('.') || self.contains_object_file(dir, hex) {
+                        continue;
+                    }
+                    let source_path = entry.path();
+                    let dest_path = dest_dir.join(hex);
+                    let mut temp_file = NamedTempFile::new_in(&dest_dir).context(&dest_dir)?;
+                    let mut source_file = File::open(&source_path).context(&source_path)?;
+                    io::copy(&mut source_file, temp_file.as_file_mut()).context(&source_path)?;
+                    persist_content_addressed_temp_file(temp_file, &dest_path)
+                        .context(&dest_path)?;
+                    // Like written commits, copied commits must be kept by
+                    // GC runs of the other repos using the shared store.
+                    if dir == "commits" {
+                        if let (Some(shared_path), Ok(id)) =
+                            (&self.shared_path, CommitId::try_from_hex(hex))
+                        {
+                            self.record_shared_store_commit(shared_path, &id)
+                                .context(shared_path)?;
+                        }
+                    }
+                    count += 1;
+                }
+            }
+        }
+        Ok(count)
     }
 
-    fn symlink_path(&self, id: &SymlinkId) -> PathBuf {
-        self.path.join("symlinks").join(id.hex())
+    /// Returns true if the object file named `hex` exists in `dir` of the
+    /// private or the shared store.
+    fn contains_object_file(&self, dir: &str, hex: &str) -> bool {
+        itertools::chain([&self.path], &self.shared_path)
+            .any(|store_path| store_path.join(dir).join(hex).exists())
     }
 
-    fn tree_path(&self, id: &TreeId) -> PathBuf {
-        self.path.join("trees").join(id.hex())
+    /// Directory where new objects are written.
+    fn objects_path(&self) -> &Path {
+        self.shared_path.as_deref().unwrap_or(&self.path)
     }
 
-    fn commit_path(&self, id: &CommitId) -> PathBuf {
-        self.path.join("commits").join(id.hex())
+    /// Path to the object file to read. Objects in the private store take
+    /// precedence over the shared store.
+    fn read_path(&self, dir: &str, id: &impl ObjectId) -> PathBuf {
+        let private_path = self.path.join(dir).join(id.hex());
+        match &self.shared_path {
+            Some(shared_path) if !private_path.exists() => shared_path.join(dir).join(id.hex()),
+            _ => private_path,
+        }
+    }
+
+    fn write_path(&self, dir: &str, id: &impl ObjectId) -> PathBuf {
+        self.objects_path().join(dir).join(id.hex())
     }
 
-    fn conflict_path(&self, id: &ConflictId) -> PathBuf {
-        self.path.join("conflicts").join(id.hex())
+    fn shared_store_user_name(&self) -> String {
+        hex::encode(&blake2b_hash(&self.path.to_string_lossy().into_owned())[..16])
+    }
+
+    fn register_shared_store_user(&self) -> io::Result<()> {
+        if let Some(shared_path) = &self.shared_path {
+            let name = self.shared_store_user_name();
+            let user_path = shared_path.join(SHARED_STORE_USERS_DIR).join(&name);
+            fs::write(user_path, self.path.to_string_lossy().as_bytes())?;
+            File::create(shared_path.join(SHARED_STORE_HEADS_DIR).join(&name))?;
+        }
+        Ok(())
+    }
+
+    /// Records that the store may reach the commit `id` until the next GC.
+    fn record_shared_store_commit(&self, shared_path: &Path, id: &CommitId) -> io::Result<()> {
+        let heads_path = shared_path
+            .join(SHARED_STORE_HEADS_DIR)
+            .join(self.shared_store_user_name());
+        let mut file = fs::OpenOptions::new().append(true).open(heads_path)?;
+        file.write_all(format!("{}\n", id.hex()).as_bytes())
+    }
+
+    /// Replaces the commits recorded for the store with its current `heads`.
+    fn record_shared_store_heads(&self, shared_path: &Path, heads: &[CommitId]) -> io::Result<()> {
+        let heads_dir = shared_path.join(SHARED_STORE_HEADS_DIR);
+        let mut temp_file = NamedTempFile::new_in(&heads_dir)?;
+        for id in heads {
+            writeln!(temp_file, "{}", id.hex())?;
+        }
+        temp_file.persist(heads_dir.join(self.shared_store_user_name()))?;
+        Ok(())
+    }
+
+    /// Returns the commits recorded by all stores referencing the shared
+    /// object store, or `None` if any of them hasn't recorded its commits.
+    /// Registrations of stores which no longer exist are removed.
+    fn shared_store_heads(&self, shared_path: &Path) -> io::Result<Option<Vec<CommitId>>> {
+        let mut heads = vec![];
+        for entry in fs::read_dir(shared_path.join(SHARED_STORE_USERS_DIR))? {
+            let entry = entry?;
+            let heads_path = shared_path
+                .join(SHARED_STORE_HEADS_DIR)
+                .join(entry.file_name());
+            let user_store_path = PathBuf::from(fs::read_to_string(entry.path())?);
+            if !user_store_path.join(SHARED_STORE_FILE).exists() {
+                fs::remove_file(entry.path())?;
+                match fs::remove_file(heads_path) {
+                    Ok(()) => {}
+                    Err(err) if err.kind() == io::ErrorKind::NotFound => {}
+                    Err(err) => return Err(err),
+                }
+                continue;
+            }
+            let content = match fs::read_to_string(heads_path) {
+                Ok(content) => content,
+                Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(None),
+                Err(err) => return Err(err),
+            };
+            // A line may be incomplete if the commit is being recorded.
+            heads.extend(
+                content
+                    .lines()
+                    .filter_map(|hex| CommitId::try_from_hex(hex).ok()),
+            );
+        }
+        Ok(Some(heads))
+    }
+
+    /// Collects the objects reachable from the `heads` as `(dir, hex)` pairs.
+    fn collect_reachable_objects(
+        &self,
+        heads: impl IntoIterator<Item = CommitId>,
+    ) -> BackendResult<HashSet<(&'static str, String)>> {
+        let mut reachable = HashSet::new();
+        let mut commit_ids: Vec<CommitId> = heads.into_iter().collect();
+        let mut tree_ids = vec![self.empty_tree_id.clone()];
+        let mut values = vec![];
+        while let Some(id) = commit_ids.pop() {
+            if id == self.root_commit_id || !reachable.insert(("commits", id.hex())) {
+                continue;
+            }
+            let commit = match self.read_commit(&id).block_on() {
+                Ok(commit) => commit,
+                // Predecessors may have been removed by GC
+                Err(BackendError::ObjectNotFound { .. }) => continue,
+                Err(err) => return Err(err),
+            };
+            commit_ids.extend(commit.parents);
+            commit_ids.extend(commit.predecessors);
+            tree_ids.extend(commit.root_tree.to_merge());
+            while !tree_ids.is_empty() || !values.is_empty() {
+                if let Some(id) = tree_ids.pop() {
+                    if reachable.insert(("trees", id.hex())) {
+                        let tree = self.read_tree(RepoPath::root(), &id).block_on()?;
+                        values.extend(tree.entries().map(|entry| entry.value().clone()));
+                    }
+                    continue;
+                }
+                match values.pop().unwrap() {
+                    TreeValue::File { id, .. } => {
+                        reachable.insert(("files", id.hex()));
+                    }
+                    TreeValue::Symlink(id) => {
+                        reachable.insert(("symlinks", id.hex()));
+                    }
+                    TreeValue::Tree(id) => tree_ids.push(id),
+                    TreeValue::GitSubmodule(_) => {}
+                    TreeValue::Conflict(id) => {
+                        if reachable.insert(("conflicts", id.hex())) {
+                            let conflict = self.read_conflict(RepoPath::root(), &id)?;
+                            values.extend(
+                                itertools::chain(conflict.removes, conflict.adds)
+                                    .map(|term| term.value),
+                            );
+                        }
+                    }
+                }
+            }
+        }
+        Ok(reachable)
+    }
+}
+
+impl EnumerableBackend for LocalBackend {
+    fn all_objects(&self) -> BackendResult<Vec<StoredObject>> {
+        let mut objects = vec![];
+        let mut seen = HashSet::new();
+        for store_path in itertools::chain([&self.path], &self.shared_path) {
+            for (dir, kind) in OBJECT_DIRS.into_iter().zip(OBJECT_KINDS) {
+                for entry in fs::read_dir(store_path.join(dir)).map_err(to_other_err)? {
+                    let entry = entry.map_err(to_other_err)?;
+                    // Skip temporary files and other unrelated files
+                    let Some(id) = entry
+                        .file_name()
+                        .to_str()
+                        .and_then(|name| hex::decode(name).ok())
+                    else {
+                        continue;
+                    };
+                    if !seen.insert((kind, id.clone())) {
+                        continue;
+                    }
+                    let size = entry.metadata().map_err(to_other_err)?.len();
+                    objects.push(StoredObject { kind, id, size });
+                }
+            }
+        }
+        Ok(objects)
     }
 }
 
+fn create_object_dirs(store_path: &Path) -> io::Result<()> {
+    for dir in OBJECT_DIRS {
+        fs::create_dir(store_path.join(dir))?;
+    }
+    Ok(())
+}
+
 #[async_trait]
 impl Backend for LocalBackend {
     fn as_any(&self) -> &dyn Any {
@@ -184,8 +476,12 @@ impl Backend for LocalBackend {
         1
     }
 
+    fn enumerable(&self) -> Option<&dyn EnumerableBackend> {
+        Some(self)
+    }
+
     async fn read_file(&self, _path: &RepoPath, id: &FileId) -> BackendResult<Box<dyn Read>> {
-        let path = self.file_path(id);
+        let path = self.read_path("files", id);
         let file = File::open(path).map_err(|err| map_not_found_err(err, id))?;
         Ok(Box::new(zstd::Decoder::new(file).map_err(to_other_err)?))
     }
@@ -195,7 +491,7 @@ impl Backend for LocalBackend {
         _path: &RepoPath,
         contents: &mut (dyn Read + Send),
     ) -> BackendResult<FileId> {
-        let temp_file = NamedTempFile::new_in(&self.path).map_err(to_other_err)?;
+        let temp_file = NamedTempFile::new_in(self.objects_path()).map_err(to_other_err)?;
         let mut encoder = zstd::Encoder::new(temp_file.as_file(), 0).map_err(to_other_err)?;
         let mut hasher = Blake2b512::new();
         let mut buff: Vec<u8> = vec![0; 1 << 14];
@@ -211,19 +507,19 @@ impl Backend for LocalBackend {
         encoder.finish().map_err(to_other_err)?;
         let id = FileId::new(hasher.finalize().to_vec());
 
-        persist_content_addressed_temp_file(temp_file, self.file_path(&id))
+        persist_content_addressed_temp_file(temp_file, self.write_path("files", &id))
             .map_err(to_other_err)?;
         Ok(id)
     }
 
     async fn read_symlink(&self, _path: &RepoPath, id: &SymlinkId) -> BackendResult<String> {
-        let path = self.symlink_path(id);
+        let path = self.read_path("symlinks", id);
         let target = fs::read_to_string(path).map_err(|err| map_not_found_err(err, id))?;
         Ok(target)
     }
 
     async fn write_symlink(&self, _path: &RepoPath, target: &str) -> BackendResult<SymlinkId> {
-        let mut temp_file = NamedTempFile::new_in(&self.path).map_err(to_other_err)?;
+        let mut temp_file = NamedTempFile::new_in(self.objects_path()).map_err(to_other_err)?;
         temp_file
             .write_all(target.as_bytes())
             .map_err(to_other_err)?;
@@ -231,13 +527,13 @@ impl Backend for LocalBackend {
         hasher.update(target.as_bytes());
         let id = SymlinkId::new(hasher.finalize().to_vec());
 
-        persist_content_addressed_temp_file(temp_file, self.symlink_path(&id))
+        persist_content_addressed_temp_file(temp_file, self.write_path("symlinks", &id))
             .map_err(to_other_err)?;
         Ok(id)
     }
 
     async fn read_tree(&self, _path: &RepoPath, id: &TreeId) -> BackendResult<Tree> {
-        let path = self.tree_path(id);
+        let path = self.read_path("trees", id);
         let buf = fs::read(path).map_err(|err| map_not_found_err(err, id))?;
 
         let proto = crate::protos::local_store::Tree::decode(&*buf).map_err(to_other_err)?;
@@ -245,7 +541,7 @@ impl Backend for LocalBackend {
     }
 
     async fn write_tree(&self, _path: &RepoPath, tree: &Tree) -> BackendResult<TreeId> {
-        let temp_file = NamedTempFile::new_in(&self.path).map_err(to_other_err)?;
+        let temp_file = NamedTempFile::new_in(self.objects_path()).map_err(to_other_err)?;
 
         let proto = tree_to_proto(tree);
         temp_file
@@ -255,13 +551,13 @@ impl Backend for LocalBackend {
 
         let id = TreeId::new(blake2b_hash(tree).to_vec());
 
-        persist_content_addressed_temp_file(temp_file, self.tree_path(&id))
+        persist_content_addressed_temp_file(temp_file, self.write_path("trees", &id))
             .map_err(to_other_err)?;
         Ok(id)
     }
 
     fn read_conflict(&self, _path: &RepoPath, id: &ConflictId) -> BackendResult<Conflict> {
-        let path = self.conflict_path(id);
+        let path = self.read_path("conflicts", id);
         let buf = fs::read(path).map_err(|err| map_not_found_err(err, id))?;
 
         let proto = crate::protos::local_store::Conflict::decode(&*buf).map_err(to_other_err)?;
@@ -269,7 +565,7 @@ impl Backend for LocalBackend {
     }
 
     fn write_conflict(&self, _path: &RepoPath, conflict: &Conflict) -> BackendResult<ConflictId> {
-        let temp_file = NamedTempFile::new_in(&self.path).map_err(to_other_err)?;
+        let temp_file = NamedTempFile::new_in(self.objects_path()).map_err(to_other_err)?;
 
         let proto = conflict_to_proto(conflict);
         temp_file
@@ -279,7 +575,7 @@ impl Backend for LocalBackend {
 
         let id = ConflictId::new(blake2b_hash(conflict).to_vec());
 
-        persist_content_addressed_temp_file(temp_file, self.conflict_path(&id))
+        persist_content_addressed_temp_file(temp_file, self.write_path("conflicts", &id))
             .map_err(to_other_err)?;
         Ok(id)
     }
@@ -292,7 +588,7 @@ impl Backend for LocalBackend {
             ));
         }
 
-        let path = self.commit_path(id);
+        let path = self.read_path("commits", id);
         let buf = fs::read(path).map_err(|err| map_not_found_err(err, id))?;
 
         let proto = crate::protos::local_store::Commit::decode(&*buf).map_err(to_other_err)?;
@@ -311,7 +607,7 @@ impl Backend for LocalBackend {
                 "Cannot write a commit with no parents".into(),
             ));
         }
-        let temp_file = NamedTempFile::new_in(&self.path).map_err(to_other_err)?;
+        let temp_file = NamedTempFile::new_in(self.objects_path()).map_err(to_other_err)?;
 
         let mut proto = commit_to_proto(&commit);
         if let Some(sign) = sign_with {
@@ -328,8 +624,12 @@ impl Backend for LocalBackend {
 
         let id = CommitId::new(blake2b_hash(&commit).to_vec());
 
-        persist_content_addressed_temp_file(temp_file, self.commit_path(&id))
+        persist_content_addressed_temp_file(temp_file, self.write_path("commits", &id))
             .map_err(to_other_err)?;
+        if let Some(shared_path) = &self.shared_path {
+            self.record_shared_store_commit(shared_path, &id)
+                .map_err(to_other_err)?;
+        }
         Ok((id, commit))
     }
 
@@ -342,7 +642,35 @@ impl Backend for LocalBackend {
         Ok(Box::pin(stream::empty()))
     }
 
-    fn gc(&self, _index: &dyn Index, _keep_newer: SystemTime) -> BackendResult<()> {
+    fn gc(&self, index: &dyn Index, keep_newer: SystemTime) -> BackendResult<()> {
+        // Only objects in the shared store are collected. Objects reachable
+        // from the commits recorded by the other repos are kept as well.
+        let Some(shared_path) = &self.shared_path else {
+            return Ok(());
+        };
+        let heads: Vec<_> = index.all_heads_for_gc().map_err(to_other_err)?.collect();
+        self.record_shared_store_heads(shared_path, &heads)
+            .map_err(to_other_err)?;
+        let Some(heads) = self.shared_store_heads(shared_path).map_err(to_other_err)? else {
+            return Ok(());
+        };
+        let reachable = self.collect_reachable_objects(heads)?;
+        for dir in OBJECT_DIRS {
+            for entry in fs::read_dir(shared_path.join(dir)).map_err(to_other_err)? {
+                let entry = entry.map_err(to_other_err)?;
+                let name = entry.file_name().to_string_lossy().into_owned();
+                if reachable.contains(&(dir, name)) {
+                    continue;
+                }
+                let mtime = entry
+                    .metadata()
+                    .and_then(|metadata| metadata.modified())
+                    .map_err(to_other_err)?;
+                if mtime < keep_newer {
+                    fs::remove_file(entry.path()).map_err(to_other_err)?;
+                }
+            }
+        }
         Ok(())
     }
 }
@@ -536,6 +864,7 @@ fn conflict_term_to_proto(part: &ConflictTerm) -> crate::protos::local_store::co
 #[cfg(test)]
 mod tests {
     use assert_matches::assert_matches;
+    use itertools::Itertools as _;
     use pollster::FutureExt;
 
     use super::*;
@@ -594,6 +923,44 @@ mod tests {
         assert_eq!(root_merge_commit, commit);
     }
 
+    #[test]
+    fn all_objects() {
+        let temp_dir = testutils::new_temp_dir();
+        let backend = LocalBackend::init(temp_dir.path());
+        let file_id = backend
+            .write_file(RepoPath::root(), &mut &b"content"[..])
+            .block_on()
+            .unwrap();
+        let symlink_id = backend
+            .write_symlink(RepoPath::root(), "target")
+            .block_on()
+            .unwrap();
+        // Writing the same content again doesn't add an object
+        backend
+            .write_file(RepoPath::root(), &mut &b"content"[..])
+            .block_on()
+            .unwrap();
+
+        let objects = backend.all_objects().unwrap();
+        let kinds_and_ids = objects
+            .iter()
+            .map(|object| (object.kind, object.id.clone()))
+            .sorted()
+            .collect_vec();
+        assert_eq!(
+            kinds_and_ids,
+            [
+                (ObjectKind::Tree, backend.empty_tree_id().to_bytes()),
+                (ObjectKind::File, file_id.to_bytes()),
+                (ObjectKind::Symlink, symlink_id.to_bytes()),
+            ]
+        );
+        // The empty tree is stored as an empty file
+        assert!(objects
+            .iter()
+            .all(|object| (object.size > 0) == (object.kind != ObjectKind::Tree)));
+    }
+
     fn create_signature() -> Signature {
         Signature {
             name: "Someone".to_string(),
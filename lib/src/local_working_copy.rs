@@ -17,6 +17,7 @@
 
 use std::any::Any;
 use std::cmp::Ordering;
+use std::collections::BTreeMap;
 use std::collections::HashSet;
 use std::error::Error;
 use std::fs;
@@ -35,9 +36,12 @@ use std::os::unix::fs::PermissionsExt;
 use std::path::Path;
 use std::path::PathBuf;
 use std::slice;
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::Ordering as AtomicOrdering;
 use std::sync::mpsc::channel;
 use std::sync::mpsc::Sender;
 use std::sync::Arc;
+use std::sync::Mutex;
 use std::sync::OnceLock;
 use std::time::UNIX_EPOCH;
 
@@ -49,6 +53,7 @@ use once_cell::unsync::OnceCell;
 use pollster::FutureExt;
 use prost::Message;
 use rayon::iter::IntoParallelIterator;
+use rayon::iter::IntoParallelRefIterator;
 use rayon::prelude::IndexedParallelIterator;
 use rayon::prelude::ParallelIterator;
 use tempfile::NamedTempFile;
@@ -58,6 +63,7 @@ use tracing::trace_span;
 
 use crate::backend::BackendError;
 use crate::backend::BackendResult;
+use crate::backend::FetchableObjectId;
 use crate::backend::FileId;
 use crate::backend::MergedTreeId;
 use crate::backend::MillisSinceEpoch;
@@ -70,15 +76,20 @@ use crate::conflicts::choose_materialized_conflict_marker_len;
 use crate::conflicts::materialize_merge_result_to_bytes_with_marker_len;
 use crate::conflicts::materialize_tree_value;
 use crate::conflicts::ConflictMarkerStyle;
+use crate::conflicts::ConflictMaterializers;
 use crate::conflicts::MaterializedTreeValue;
 use crate::conflicts::MIN_CONFLICT_MARKER_LEN;
 use crate::file_util::check_symlink_support;
 use crate::file_util::try_symlink;
+use crate::fileset::FilePatternParseError;
+use crate::fsmonitor;
 #[cfg(feature = "watchman")]
 use crate::fsmonitor::watchman;
 use crate::fsmonitor::FsmonitorSettings;
 #[cfg(feature = "watchman")]
 use crate::fsmonitor::WatchmanConfig;
+use crate::gitattributes::AttributeState;
+use crate::gitattributes::GitAttributes;
 use crate::gitignore::GitIgnoreFile;
 use crate::lock::FileLock;
 use crate::matchers::DifferenceMatcher;
@@ -87,6 +98,8 @@ use crate::matchers::FilesMatcher;
 use crate::matchers::IntersectionMatcher;
 use crate::matchers::Matcher;
 use crate::matchers::PrefixMatcher;
+use crate::matchers::UnionMatcher;
+use crate::matchers::Visit;
 use crate::merge::Merge;
 use crate::merge::MergeBuilder;
 use crate::merge::MergedTreeValue;
@@ -99,17 +112,22 @@ use crate::op_store::WorkspaceId;
 use crate::repo_path::RepoPath;
 use crate::repo_path::RepoPathBuf;
 use crate::repo_path::RepoPathComponent;
+use crate::sparse::sparse_matcher;
+use crate::sparse::SparsePattern;
+use crate::sparse::SparsePatternKind;
 use crate::store::Store;
 use crate::tree::Tree;
 use crate::working_copy::CheckoutError;
 use crate::working_copy::CheckoutOptions;
 use crate::working_copy::CheckoutStats;
+use crate::working_copy::EolConversionMode;
 use crate::working_copy::LockedWorkingCopy;
 use crate::working_copy::ResetError;
 use crate::working_copy::SnapshotError;
 use crate::working_copy::SnapshotOptions;
 use crate::working_copy::SnapshotProgress;
 use crate::working_copy::SnapshotStats;
+use crate::working_copy::SymlinkMode;
 use crate::working_copy::UntrackedReason;
 use crate::working_copy::WorkingCopy;
 use crate::working_copy::WorkingCopyFactory;
@@ -406,14 +424,71 @@ impl<'a> IntoIterator for FileStates<'a> {
     }
 }
 
+/// State of a directory as of the last full scan, used to skip reading the
+/// directory again if its entries haven't changed.
+#[derive(Clone, Debug, Eq, PartialEq)]
+struct DirectoryState {
+    /// The directory's mtime, or 0 if the directory must be scanned again.
+    mtime: MillisSinceEpoch,
+    /// Fingerprint of the ignore rules that applied to the directory.
+    ignore_fingerprint: u64,
+    /// Sorted names of the subdirectories that were scanned for new files.
+    /// Other subdirectories were either ignored or outside the sparse
+    /// patterns, so only their tracked files need to be visited.
+    subdirs: Vec<String>,
+}
+
+impl DirectoryState {
+    fn is_clean(&self, mtime: MillisSinceEpoch, ignore_fingerprint: u64) -> bool {
+        self.mtime != MillisSinceEpoch(0)
+            && self.mtime == mtime
+            && self.ignore_fingerprint == ignore_fingerprint
+    }
+}
+
+fn directory_states_from_proto(
+    entries: Vec<crate::protos::working_copy::DirectoryStateEntry>,
+) -> BTreeMap<RepoPathBuf, DirectoryState> {
+    entries
+        .into_iter()
+        .map(|entry| {
+            let path = RepoPathBuf::from_internal_string(entry.path);
+            let state = entry.state.unwrap_or_default();
+            let state = DirectoryState {
+                mtime: MillisSinceEpoch(state.mtime_millis_since_epoch),
+                ignore_fingerprint: state.ignore_fingerprint,
+                subdirs: state.subdirs,
+            };
+            (path, state)
+        })
+        .collect()
+}
+
+fn directory_states_to_proto(
+    directory_states: &BTreeMap<RepoPathBuf, DirectoryState>,
+) -> Vec<crate::protos::working_copy::DirectoryStateEntry> {
+    directory_states
+        .iter()
+        .map(
+            |(path, state)| crate::protos::working_copy::DirectoryStateEntry {
+                path: path.as_internal_file_string().to_owned(),
+                state: Some(crate::protos::working_copy::DirectoryState {
+                    mtime_millis_since_epoch: state.mtime.0,
+                    ignore_fingerprint: state.ignore_fingerprint,
+                    subdirs: state.subdirs.clone(),
+                }),
+            },
+        )
+        .collect()
+}
+
 pub struct TreeState {
     store: Arc<Store>,
     working_copy_path: PathBuf,
     state_path: PathBuf,
     tree_id: MergedTreeId,
     file_states: FileStatesMap,
-    // Currently only path prefixes
-    sparse_patterns: Vec<RepoPathBuf>,
+    sparse_patterns: Vec<SparsePattern>,
     own_mtime: MillisSinceEpoch,
     symlink_support: bool,
 
@@ -421,6 +496,18 @@ pub struct TreeState {
     /// the repo is configured to use the Watchman filesystem monitor and
     /// Watchman has been queried at least once.
     watchman_clock: Option<crate::protos::working_copy::WatchmanClock>,
+
+    /// Changes reported by the built-in filesystem monitor, which are
+    /// acknowledged once the tree state has been saved.
+    native_fsmonitor_changes: Option<fsmonitor::native::ChangedFiles>,
+
+    /// Directories that were fully scanned by the last snapshot. Only
+    /// populated if the untracked cache is enabled.
+    directory_states: BTreeMap<RepoPathBuf, DirectoryState>,
+
+    /// Paths which are skipped when snapshotting unless they're explicitly
+    /// refreshed.
+    assume_unchanged_paths: Vec<RepoPathBuf>,
 }
 
 fn file_state_from_proto(proto: &crate::protos::working_copy::FileState) -> FileState {
@@ -500,20 +587,93 @@ fn is_file_state_entries_proto_unique_and_sorted(
         .all(|(path1, path2)| path1 < path2)
 }
 
+/// Current version of the `SparsePatterns` message.
+const SPARSE_PATTERNS_VERSION: u32 = 1;
+/// Prefix written for readers of version 0 if the patterns can't be expressed
+/// as prefixes. It isn't valid UTF-8, so decoding the tree state fails.
+const SPARSE_PATTERNS_INCOMPATIBLE_PREFIX: &[u8] = b"\xff";
+
 fn sparse_patterns_from_proto(
     proto: Option<&crate::protos::working_copy::SparsePatterns>,
-) -> Vec<RepoPathBuf> {
-    let mut sparse_patterns = vec![];
-    if let Some(proto_sparse_patterns) = proto {
-        for prefix in &proto_sparse_patterns.prefixes {
-            sparse_patterns.push(RepoPathBuf::from_internal_string(prefix));
-        }
-    } else {
+) -> Result<Vec<SparsePattern>, FilePatternParseError> {
+    let Some(proto) = proto else {
         // For compatibility with old working copies.
         // TODO: Delete this is late 2022 or so.
-        sparse_patterns.push(RepoPathBuf::root());
+        return Ok(vec![SparsePattern::root()]);
+    };
+    if proto.version == 0 {
+        // Only included path prefixes are supported by version 0.
+        let sparse_patterns = proto
+            .prefixes
+            .iter()
+            .map(|prefix| {
+                let prefix = String::from_utf8_lossy(prefix);
+                SparsePattern::prefix(RepoPathBuf::from_internal_string(prefix))
+            })
+            .collect();
+        return Ok(sparse_patterns);
+    }
+    proto
+        .patterns
+        .iter()
+        .map(|proto_pattern| {
+            let pattern = match proto_pattern.kind() {
+                crate::protos::working_copy::SparsePatternKind::Prefix => {
+                    SparsePattern::prefix(RepoPathBuf::from_internal_string(&proto_pattern.pattern))
+                }
+                crate::protos::working_copy::SparsePatternKind::Glob => {
+                    SparsePattern::glob(&proto_pattern.pattern)?
+                }
+            };
+            Ok(if proto_pattern.exclude {
+                pattern.excluded()
+            } else {
+                pattern
+            })
+        })
+        .collect()
+}
+
+fn sparse_patterns_to_proto(
+    sparse_patterns: &[SparsePattern],
+) -> crate::protos::working_copy::SparsePatterns {
+    let mut proto = crate::protos::working_copy::SparsePatterns {
+        version: SPARSE_PATTERNS_VERSION,
+        ..Default::default()
+    };
+    for pattern in sparse_patterns {
+        let (kind, pattern_string) = match pattern.kind() {
+            SparsePatternKind::Prefix(path) => (
+                crate::protos::working_copy::SparsePatternKind::Prefix,
+                path.as_internal_file_string().to_owned(),
+            ),
+            SparsePatternKind::Glob(glob) => (
+                crate::protos::working_copy::SparsePatternKind::Glob,
+                glob.clone(),
+            ),
+        };
+        proto
+            .patterns
+            .push(crate::protos::working_copy::SparsePattern {
+                kind: kind as i32,
+                pattern: pattern_string,
+                exclude: pattern.is_exclude(),
+            });
     }
-    sparse_patterns
+    // Keep the working copy readable by older versions if possible. If not,
+    // make them fail rather than treating the patterns as an empty list.
+    proto.prefixes = match sparse_patterns
+        .iter()
+        .map(|pattern| pattern.as_included_prefix())
+        .collect::<Option<Vec<_>>>()
+    {
+        Some(prefixes) => prefixes
+            .iter()
+            .map(|path| path.as_internal_file_string().as_bytes().to_vec())
+            .collect(),
+        None => vec![SPARSE_PATTERNS_INCOMPATIBLE_PREFIX.to_vec()],
+    };
+    proto
 }
 
 /// Creates intermediate directories from the `working_copy_path` to the
@@ -644,6 +804,9 @@ fn can_create_new_file(disk_path: &Path) -> Result<bool, CheckoutError> {
 
 const RESERVED_DIR_NAMES: &[&str] = &[".git", ".jj"];
 
+/// Number of files to write concurrently before reporting checkout progress.
+const CHECKOUT_CHUNK_SIZE: usize = 1000;
+
 /// Suppose the `disk_path` exists, checks if the last component points to
 /// ".git" or ".jj" in the same parent directory.
 fn reject_reserved_existing_path(disk_path: &Path) -> Result<(), CheckoutError> {
@@ -719,6 +882,7 @@ fn file_state(metadata: &Metadata) -> Option<FileState> {
 struct FsmonitorMatcher {
     matcher: Option<Box<dyn Matcher>>,
     watchman_clock: Option<crate::protos::working_copy::WatchmanClock>,
+    native_changes: Option<fsmonitor::native::ChangedFiles>,
 }
 
 #[derive(Debug, Error)]
@@ -734,6 +898,11 @@ pub enum TreeStateError {
     WriteTreeState { path: PathBuf, source: io::Error },
     #[error("Persisting tree state to file {path}")]
     PersistTreeState { path: PathBuf, source: io::Error },
+    #[error("Invalid sparse pattern in tree state {path}")]
+    InvalidSparsePattern {
+        path: PathBuf,
+        source: FilePatternParseError,
+    },
     #[error("Filesystem monitor error")]
     Fsmonitor(#[source] Box<dyn Error + Send + Sync>),
 }
@@ -751,12 +920,31 @@ impl TreeState {
         self.file_states.all()
     }
 
-    pub fn sparse_patterns(&self) -> &Vec<RepoPathBuf> {
+    pub fn sparse_patterns(&self) -> &Vec<SparsePattern> {
         &self.sparse_patterns
     }
 
     fn sparse_matcher(&self) -> Box<dyn Matcher> {
-        Box::new(PrefixMatcher::new(&self.sparse_patterns))
+        sparse_matcher(&self.sparse_patterns)
+    }
+
+    /// Files and directories that aren't checked for changes when
+    /// snapshotting.
+    pub fn assume_unchanged_paths(&self) -> &[RepoPathBuf] {
+        &self.assume_unchanged_paths
+    }
+
+    /// Updates the paths that aren't checked for changes when snapshotting.
+    /// Files under paths which are no longer assumed unchanged will be
+    /// checked by the next snapshot.
+    pub fn set_assume_unchanged_paths(&mut self, mut paths: Vec<RepoPathBuf>) {
+        paths.sort_unstable();
+        paths.dedup();
+        if paths != self.assume_unchanged_paths {
+            self.assume_unchanged_paths = paths;
+            // Skipped directories weren't recorded by the untracked cache.
+            self.directory_states.clear();
+        }
     }
 
     pub fn init(
@@ -777,10 +965,13 @@ impl TreeState {
             state_path,
             tree_id,
             file_states: FileStatesMap::new(),
-            sparse_patterns: vec![RepoPathBuf::root()],
+            sparse_patterns: vec![SparsePattern::root()],
             own_mtime: MillisSinceEpoch(0),
             symlink_support: check_symlink_support().unwrap_or(false),
             watchman_clock: None,
+            native_fsmonitor_changes: None,
+            directory_states: BTreeMap::new(),
+            assume_unchanged_paths: vec![],
         }
     }
 
@@ -808,6 +999,59 @@ impl TreeState {
         Ok(wc)
     }
 
+    /// Returns true if symlinks should be materialized as symlinks rather than
+    /// regular files containing the target.
+    fn use_native_symlinks(&self, mode: SymlinkMode) -> bool {
+        match mode {
+            SymlinkMode::Auto => self.symlink_support,
+            SymlinkMode::File => false,
+        }
+    }
+
+    /// Returns the content filters for snapshotting, configured by the
+    /// `.gitattributes` file in the working copy.
+    fn content_filters_from_disk(&self, eol_conversion: EolConversionMode) -> ContentFilters {
+        if eol_conversion == EolConversionMode::None {
+            return ContentFilters::default();
+        }
+        // The attributes are best-effort. An unreadable file is ignored.
+        let attributes = fs::read(self.working_copy_path.join(GITATTRIBUTES_FILE)).map_or_else(
+            |_| GitAttributes::empty(),
+            |input| GitAttributes::parse(&input),
+        );
+        ContentFilters::new(eol_conversion, attributes)
+    }
+
+    /// Returns the content filters for checking out the `tree`, configured by
+    /// the `.gitattributes` file in it.
+    async fn content_filters_from_tree(
+        &self,
+        tree: &MergedTree,
+        eol_conversion: EolConversionMode,
+    ) -> BackendResult<ContentFilters> {
+        if eol_conversion == EolConversionMode::None {
+            return Ok(ContentFilters::default());
+        }
+        let path = RepoPath::from_internal_string(GITATTRIBUTES_FILE);
+        let attributes = match tree.path_value(path)?.into_resolved() {
+            Ok(Some(TreeValue::File { id, .. })) => {
+                let mut input = vec![];
+                self.store
+                    .read_file_async(path, &id)
+                    .await?
+                    .read_to_end(&mut input)
+                    .map_err(|err| BackendError::ReadFile {
+                        path: path.to_owned(),
+                        id: id.clone(),
+                        source: err.into(),
+                    })?;
+                GitAttributes::parse(&input)
+            }
+            _ => GitAttributes::empty(),
+        };
+        Ok(ContentFilters::new(eol_conversion, attributes))
+    }
+
     fn update_own_mtime(&mut self) {
         if let Ok(metadata) = self.state_path.join("tree_state").symlink_metadata() {
             self.own_mtime = mtime_from_metadata(&metadata);
@@ -842,8 +1086,20 @@ impl TreeState {
         }
         self.file_states =
             FileStatesMap::from_proto(proto.file_states, proto.is_file_states_sorted);
-        self.sparse_patterns = sparse_patterns_from_proto(proto.sparse_patterns.as_ref());
+        self.sparse_patterns =
+            sparse_patterns_from_proto(proto.sparse_patterns.as_ref()).map_err(|err| {
+                TreeStateError::InvalidSparsePattern {
+                    path: tree_state_path.to_owned(),
+                    source: err,
+                }
+            })?;
         self.watchman_clock = proto.watchman_clock;
+        self.directory_states = directory_states_from_proto(proto.directory_states);
+        self.assume_unchanged_paths = proto
+            .assume_unchanged_paths
+            .into_iter()
+            .map(RepoPathBuf::from_internal_string)
+            .collect();
         Ok(())
     }
 
@@ -863,14 +1119,14 @@ impl TreeState {
         proto.file_states = self.file_states.data.clone();
         // `FileStatesMap` is guaranteed to be sorted.
         proto.is_file_states_sorted = true;
-        let mut sparse_patterns = crate::protos::working_copy::SparsePatterns::default();
-        for path in &self.sparse_patterns {
-            sparse_patterns
-                .prefixes
-                .push(path.as_internal_file_string().to_owned());
-        }
-        proto.sparse_patterns = Some(sparse_patterns);
+        proto.sparse_patterns = Some(sparse_patterns_to_proto(&self.sparse_patterns));
         proto.watchman_clock = self.watchman_clock.clone();
+        proto.directory_states = directory_states_to_proto(&self.directory_states);
+        proto.assume_unchanged_paths = self
+            .assume_unchanged_paths
+            .iter()
+            .map(|path| path.as_internal_file_string().to_owned())
+            .collect();
 
         let mut temp_file = NamedTempFile::new_in(&self.state_path).unwrap();
         temp_file
@@ -894,6 +1150,14 @@ impl TreeState {
                     source: error,
                 }
             })?;
+        if let Some(changes) = self.native_fsmonitor_changes.take() {
+            // The changes will be scanned again if this fails.
+            if let Err(err) =
+                fsmonitor::native::acknowledge_changed_files(&self.state_path, &changes)
+            {
+                tracing::warn!(?err, "Failed to acknowledge filesystem monitor changes");
+            }
+        }
         Ok(())
     }
 
@@ -955,33 +1219,68 @@ impl TreeState {
             progress,
             start_tracking_matcher,
             max_new_file_size,
+            ref max_new_file_size_overrides,
             conflict_marker_style,
+            ref conflict_materializers,
+            symlinks,
+            eol_conversion,
+            untracked_cache,
+            refresh_matcher,
         } = options;
 
         let sparse_matcher = self.sparse_matcher();
+        let assume_unchanged_matcher = PrefixMatcher::new(&self.assume_unchanged_paths);
+        let skipped_matcher = DifferenceMatcher::new(&assume_unchanged_matcher, refresh_matcher);
 
         let fsmonitor_clock_needs_save = *fsmonitor_settings != FsmonitorSettings::None;
         let mut is_dirty = fsmonitor_clock_needs_save;
         let FsmonitorMatcher {
             matcher: fsmonitor_matcher,
             watchman_clock,
+            native_changes,
         } = self.make_fsmonitor_matcher(fsmonitor_settings)?;
         let fsmonitor_matcher = match fsmonitor_matcher.as_ref() {
             None => &EverythingMatcher,
             Some(fsmonitor_matcher) => fsmonitor_matcher.as_ref(),
         };
+        // Refreshed paths are read even if the fsmonitor doesn't report them
+        // as changed.
+        let changed_matcher = UnionMatcher::new(fsmonitor_matcher, refresh_matcher);
 
-        let matcher = IntersectionMatcher::new(sparse_matcher.as_ref(), fsmonitor_matcher);
+        let matcher = DifferenceMatcher::new(
+            IntersectionMatcher::new(sparse_matcher.as_ref(), &changed_matcher),
+            &skipped_matcher,
+        );
         if matcher.visit(RepoPath::root()).is_nothing() {
             // No need to load the current tree, set up channels, etc.
             self.watchman_clock = watchman_clock;
+            self.native_fsmonitor_changes = native_changes;
             return Ok((is_dirty, SnapshotStats::default()));
         }
 
+        // The cache can't tell whether untracked files in an unchanged
+        // directory would be tracked by a different matcher, so it's only used
+        // if all new files are to be tracked. The fsmonitor already tells us
+        // which directories changed. Paths assumed to be unchanged were
+        // skipped when the cache was recorded, so refreshing them requires a
+        // full scan.
+        let refreshes_skipped_paths = self
+            .assume_unchanged_paths
+            .iter()
+            .any(|path| refresh_matcher.matches(path) || !refresh_matcher.visit(path).is_nothing());
+        let use_untracked_cache = untracked_cache
+            && *fsmonitor_settings == FsmonitorSettings::None
+            && !refreshes_skipped_paths
+            && matches!(
+                start_tracking_matcher.visit(RepoPath::root()),
+                Visit::AllRecursively
+            );
+
         let (tree_entries_tx, tree_entries_rx) = channel();
         let (file_states_tx, file_states_rx) = channel();
         let (untracked_paths_tx, untracked_paths_rx) = channel();
         let (deleted_files_tx, deleted_files_rx) = channel();
+        let (directory_states_tx, directory_states_rx) = channel();
 
         trace_span!("traverse filesystem").in_scope(|| -> Result<(), SnapshotError> {
             let snapshotter = FileSnapshotter {
@@ -994,10 +1293,15 @@ impl TreeState {
                 file_states_tx,
                 untracked_paths_tx,
                 deleted_files_tx,
+                directory_states_tx: use_untracked_cache.then_some(directory_states_tx),
                 error: OnceLock::new(),
                 progress,
                 max_new_file_size,
+                max_new_file_size_overrides,
                 conflict_marker_style,
+                conflict_materializers,
+                native_symlinks: self.use_native_symlinks(symlinks),
+                content_filters: self.content_filters_from_disk(eol_conversion),
             };
             let directory_to_visit = DirectoryToVisit {
                 dir: RepoPathBuf::root(),
@@ -1045,6 +1349,11 @@ impl TreeState {
             is_dirty |= new_tree_id != self.tree_id;
             self.tree_id = new_tree_id;
         });
+        trace_span!("process directory states").in_scope(|| {
+            let directory_states: BTreeMap<_, _> = directory_states_rx.into_iter().collect();
+            is_dirty |= directory_states != self.directory_states;
+            self.directory_states = directory_states;
+        });
         if cfg!(debug_assertions) {
             let tree = self.current_tree().unwrap();
             let tree_paths: HashSet<_> = tree
@@ -1056,6 +1365,7 @@ impl TreeState {
             assert_eq!(state_paths, tree_paths);
         }
         self.watchman_clock = watchman_clock;
+        self.native_fsmonitor_changes = native_changes;
         Ok((is_dirty, stats))
     }
 
@@ -1066,6 +1376,7 @@ impl TreeState {
     ) -> Result<FsmonitorMatcher, SnapshotError> {
         let (watchman_clock, changed_files) = match fsmonitor_settings {
             FsmonitorSettings::None => (None, None),
+            FsmonitorSettings::Native => return Ok(self.make_native_fsmonitor_matcher()),
             FsmonitorSettings::Test { changed_files } => (None, Some(changed_files.clone())),
             #[cfg(feature = "watchman")]
             FsmonitorSettings::Watchman(config) => match self.query_watchman(config) {
@@ -1101,8 +1412,33 @@ impl TreeState {
         Ok(FsmonitorMatcher {
             matcher,
             watchman_clock,
+            native_changes: None,
         })
     }
+
+    fn make_native_fsmonitor_matcher(&self) -> FsmonitorMatcher {
+        let changes = match fsmonitor::native::query_changed_files(&self.state_path) {
+            Ok(changes) => changes,
+            Err(err) => {
+                tracing::warn!(?err, "Failed to query filesystem monitor");
+                Default::default()
+            }
+        };
+        // The reported paths may be directories, in which case anything under
+        // them may have changed.
+        let matcher = changes.paths.as_ref().map(|paths| {
+            let repo_paths = paths
+                .iter()
+                .filter_map(|path| RepoPathBuf::from_relative_path(path).ok())
+                .collect_vec();
+            Box::new(PrefixMatcher::new(repo_paths)) as Box<dyn Matcher>
+        });
+        FsmonitorMatcher {
+            matcher,
+            watchman_clock: None,
+            native_changes: Some(changes),
+        }
+    }
 }
 
 struct DirectoryToVisit<'a> {
@@ -1124,6 +1460,29 @@ struct PresentDirEntries {
     files: HashSet<String>,
 }
 
+/// What a full scan of a directory found that matters to the untracked
+/// cache.
+#[derive(Debug, Default)]
+struct DirectoryScan {
+    scanned_subdirs: Mutex<Vec<String>>,
+    found_untracked_files: AtomicBool,
+}
+
+/// Extracts `<name>` from `<dir>`, `<dir>/<name>`, or `<dir>/<name>/**`.
+///
+/// The `path` may be `<dir>` itself on file->dir transition, in which case
+/// the name is empty.
+fn entry_name_in_dir<'a>(dir: &RepoPath, path: &'a RepoPath) -> (PresentDirEntryKind, &'a str) {
+    debug_assert!(path.starts_with(dir));
+    let slash = !dir.is_root() as usize;
+    let len = dir.as_internal_file_string().len() + slash;
+    let tail = path.as_internal_file_string().get(len..).unwrap_or("");
+    match tail.split_once('/') {
+        Some((name, _)) => (PresentDirEntryKind::Dir, name),
+        None => (PresentDirEntryKind::File, tail),
+    }
+}
+
 /// Helper to scan local-disk directories and files in parallel.
 struct FileSnapshotter<'a> {
     tree_state: &'a TreeState,
@@ -1134,10 +1493,17 @@ struct FileSnapshotter<'a> {
     file_states_tx: Sender<(RepoPathBuf, FileState)>,
     untracked_paths_tx: Sender<(RepoPathBuf, UntrackedReason)>,
     deleted_files_tx: Sender<RepoPathBuf>,
+    /// Set if the untracked cache should be used and updated.
+    directory_states_tx: Option<Sender<(RepoPathBuf, DirectoryState)>>,
     error: OnceLock<SnapshotError>,
     progress: Option<&'a SnapshotProgress<'a>>,
     max_new_file_size: u64,
+    max_new_file_size_overrides: &'a [(Arc<dyn Matcher>, u64)],
     conflict_marker_style: ConflictMarkerStyle,
+    conflict_materializers: &'a ConflictMaterializers,
+    /// Whether symlinks are checked out as symlinks rather than regular files.
+    native_symlinks: bool,
+    content_filters: ContentFilters,
 }
 
 impl FileSnapshotter<'_> {
@@ -1164,6 +1530,14 @@ impl FileSnapshotter<'_> {
         }
     }
 
+    /// Returns the size limit for the new file at `path`.
+    fn max_new_file_size_for(&self, path: &RepoPath) -> u64 {
+        self.max_new_file_size_overrides
+            .iter()
+            .find(|(matcher, _)| matcher.matches(path))
+            .map_or(self.max_new_file_size, |&(_, size)| size)
+    }
+
     /// Visits the directory entries, spawns jobs to recurse into sub
     /// directories.
     fn visit_directory<'scope>(
@@ -1180,6 +1554,35 @@ impl FileSnapshotter<'_> {
 
         let git_ignore = git_ignore
             .chain_with_file(&dir.to_internal_dir_string(), disk_dir.join(".gitignore"))?;
+        // Stat the directory before reading it so that any concurrent
+        // modification makes the recorded mtime stale.
+        let dir_mtime = if self.directory_states_tx.is_some() {
+            let mtime = disk_dir
+                .metadata()
+                .map(|metadata| mtime_from_metadata(&metadata))
+                .ok();
+            if let (Some(mtime), Some(state)) = (mtime, self.tree_state.directory_states.get(&dir))
+            {
+                // Like for files, the mtime is ambiguous if it's the same as the
+                // state file's own mtime.
+                if state.is_clean(mtime, git_ignore.fingerprint())
+                    && state.mtime < self.tree_state.own_mtime
+                {
+                    return self.visit_unchanged_directory(
+                        dir,
+                        disk_dir,
+                        git_ignore,
+                        file_states,
+                        state,
+                        scope,
+                    );
+                }
+            }
+            mtime
+        } else {
+            None
+        };
+        let dir_scan = DirectoryScan::default();
         let dir_entries: Vec<_> = disk_dir
             .read_dir()
             .and_then(|entries| entries.try_collect())
@@ -1193,7 +1596,7 @@ impl FileSnapshotter<'_> {
             // sequential scan should be fast enough.
             .with_min_len(100)
             .filter_map(|entry| {
-                self.process_dir_entry(&dir, &git_ignore, file_states, &entry, scope)
+                self.process_dir_entry(&dir, &git_ignore, file_states, &entry, &dir_scan, scope)
                     .transpose()
             })
             .map(|item| match item {
@@ -1204,6 +1607,84 @@ impl FileSnapshotter<'_> {
             .collect::<Result<_, _>>()?;
         let present_entries = PresentDirEntries { dirs, files };
         self.emit_deleted_files(&dir, file_states, &present_entries);
+        if let Some(directory_states_tx) = &self.directory_states_tx {
+            let DirectoryScan {
+                scanned_subdirs,
+                found_untracked_files,
+            } = dir_scan;
+            // Untracked files would have to be reported again, so the
+            // directory can't be skipped next time.
+            let mtime = match dir_mtime {
+                Some(mtime) if !found_untracked_files.into_inner() => mtime,
+                _ => MillisSinceEpoch(0),
+            };
+            let mut subdirs = scanned_subdirs.into_inner().unwrap();
+            subdirs.sort_unstable();
+            let state = DirectoryState {
+                mtime,
+                ignore_fingerprint: git_ignore.fingerprint(),
+                subdirs,
+            };
+            directory_states_tx.send((dir, state)).ok();
+        }
+        Ok(())
+    }
+
+    /// Visits a directory whose entries haven't changed since the last scan.
+    /// Tracked files still need to be checked for modification, but there
+    /// can't be any new files other than in subdirectories.
+    fn visit_unchanged_directory<'scope>(
+        &'scope self,
+        dir: RepoPathBuf,
+        disk_dir: PathBuf,
+        git_ignore: Arc<GitIgnoreFile>,
+        file_states: FileStates<'scope>,
+        state: &DirectoryState,
+        scope: &rayon::Scope<'scope>,
+    ) -> Result<(), SnapshotError> {
+        let tracked_names = file_states
+            .iter()
+            .map(|(path, _state)| entry_name_in_dir(&dir, path).1)
+            .filter(|name| !name.is_empty())
+            .unique()
+            .collect_vec();
+        for name in tracked_names {
+            if state
+                .subdirs
+                .binary_search_by(|subdir| subdir.as_str().cmp(name))
+                .is_ok()
+            {
+                continue;
+            }
+            let name = RepoPathComponent::new(name);
+            if let Some(file_state) = file_states.get_at(&dir, name) {
+                if file_state.file_type == FileType::GitSubmodule {
+                    continue;
+                }
+            }
+            // A file, or a directory that was ignored or outside the sparse
+            // patterns, so visit only the paths we're already tracking.
+            self.visit_tracked_files(file_states.prefixed_at(&dir, name))?;
+        }
+        for name in &state.subdirs {
+            let name = RepoPathComponent::new(name);
+            let path = dir.join(name);
+            if self.matcher.visit(&path).is_nothing() {
+                continue;
+            }
+            let directory_to_visit = DirectoryToVisit {
+                dir: path,
+                disk_dir: disk_dir.join(name.as_internal_str()),
+                git_ignore: git_ignore.clone(),
+                file_states: file_states.prefixed_at(&dir, name),
+            };
+            self.spawn_ok(scope, |scope| {
+                self.visit_directory(directory_to_visit, scope)
+            });
+        }
+        if let Some(directory_states_tx) = &self.directory_states_tx {
+            directory_states_tx.send((dir, state.clone())).ok();
+        }
         Ok(())
     }
 
@@ -1213,6 +1694,7 @@ impl FileSnapshotter<'_> {
         git_ignore: &Arc<GitIgnoreFile>,
         file_states: FileStates<'scope>,
         entry: &DirEntry,
+        dir_scan: &DirectoryScan,
         scope: &rayon::Scope<'scope>,
     ) -> Result<Option<(PresentDirEntryKind, String)>, SnapshotError> {
         let file_type = entry.file_type().unwrap();
@@ -1245,6 +1727,11 @@ impl FileSnapshotter<'_> {
                 // tracking.
                 self.spawn_ok(scope, move |_| self.visit_tracked_files(file_states));
             } else if !self.matcher.visit(&path).is_nothing() {
+                dir_scan
+                    .scanned_subdirs
+                    .lock()
+                    .unwrap()
+                    .push(name_string.clone());
                 let directory_to_visit = DirectoryToVisit {
                     dir: path,
                     disk_dir: entry.path(),
@@ -1279,13 +1766,23 @@ impl FileSnapshotter<'_> {
                     message: format!("Failed to stat file {}", entry.path().display()),
                     err: err.into(),
                 })?;
-                if maybe_current_file_state.is_none() && metadata.len() > self.max_new_file_size {
+                // Only new files are subject to the size limit, so don't bother
+                // matching the overrides against tracked files.
+                let new_file_max_size = maybe_current_file_state
+                    .is_none()
+                    .then(|| self.max_new_file_size_for(&path));
+                if let Some(max_size) =
+                    new_file_max_size.filter(|&max_size| metadata.len() > max_size)
+                {
                     // Leave the large file untracked
                     let reason = UntrackedReason::FileTooLarge {
                         size: metadata.len(),
-                        max_size: self.max_new_file_size,
+                        max_size,
                     };
                     self.untracked_paths_tx.send((path, reason)).ok();
+                    dir_scan
+                        .found_untracked_files
+                        .store(true, AtomicOrdering::Relaxed);
                     Ok(None)
                 } else if let Some(new_file_state) = file_state(&metadata) {
                     self.process_present_file(
@@ -1372,18 +1869,9 @@ impl FileSnapshotter<'_> {
         file_states: FileStates<'_>,
         present_entries: &PresentDirEntries,
     ) {
-        let file_state_chunks = file_states.iter().chunk_by(|(path, _state)| {
-            // Extract <name> from <dir>, <dir>/<name>, or <dir>/<name>/**.
-            // (file_states may contain <dir> file on file->dir transition.)
-            debug_assert!(path.starts_with(dir));
-            let slash = !dir.is_root() as usize;
-            let len = dir.as_internal_file_string().len() + slash;
-            let tail = path.as_internal_file_string().get(len..).unwrap_or("");
-            match tail.split_once('/') {
-                Some((name, _)) => (PresentDirEntryKind::Dir, name),
-                None => (PresentDirEntryKind::File, tail),
-            }
-        });
+        let file_state_chunks = file_states
+            .iter()
+            .chunk_by(|(path, _state)| entry_name_in_dir(dir, path));
         file_state_chunks
             .into_iter()
             .filter(|&((kind, name), _)| match kind {
@@ -1421,14 +1909,17 @@ impl FileSnapshotter<'_> {
             Ok(None)
         } else {
             let current_tree_values = self.current_tree.path_value(repo_path)?;
-            let new_file_type = if !self.tree_state.symlink_support {
-                let mut new_file_type = new_file_state.file_type.clone();
-                if matches!(new_file_type, FileType::Normal { .. })
-                    && matches!(current_tree_values.as_normal(), Some(TreeValue::Symlink(_)))
-                {
-                    new_file_type = FileType::Symlink;
-                }
-                new_file_type
+            // A regular file in place of a symlink is read back as the symlink
+            // if symlinks are checked out as files, or if the symlink was
+            // checked out as a file before the mode changed.
+            let symlink_as_file = !self.native_symlinks
+                || maybe_current_file_state
+                    .is_some_and(|state| matches!(state.file_type, FileType::Normal { .. }));
+            let new_file_type = if symlink_as_file
+                && matches!(new_file_state.file_type, FileType::Normal { .. })
+                && matches!(current_tree_values.as_normal(), Some(TreeValue::Symlink(_)))
+            {
+                FileType::Symlink
             } else {
                 new_file_state.file_type.clone()
             };
@@ -1443,8 +1934,9 @@ impl FileSnapshotter<'_> {
                     )
                     .block_on()?,
                 FileType::Symlink => {
+                    let is_link = new_file_state.file_type == FileType::Symlink;
                     let id = self
-                        .write_symlink_to_store(repo_path, disk_path)
+                        .write_symlink_to_store(repo_path, disk_path, is_link)
                         .block_on()?;
                     Merge::normal(TreeValue::Symlink(id))
                 }
@@ -1493,17 +1985,30 @@ impl FileSnapshotter<'_> {
                 message: format!("Failed to open file {}", disk_path.display()),
                 err: err.into(),
             })?;
-            let new_file_ids = conflicts::update_from_content(
-                &old_file_ids,
-                self.store(),
-                repo_path,
-                &content,
-                self.conflict_marker_style,
-                materialized_conflict_data.map_or(MIN_CONFLICT_MARKER_LEN, |data| {
-                    data.conflict_marker_len as usize
-                }),
-            )
-            .block_on()?;
+            let content = self.content_filters.clean(repo_path, content);
+            let new_file_ids =
+                if let Some(materializer) = self.conflict_materializers.find(repo_path) {
+                    conflicts::update_from_custom_content(
+                        &old_file_ids,
+                        self.store(),
+                        repo_path,
+                        &content,
+                        materializer,
+                    )
+                    .block_on()?
+                } else {
+                    conflicts::update_from_content(
+                        &old_file_ids,
+                        self.store(),
+                        repo_path,
+                        &content,
+                        self.conflict_marker_style,
+                        materialized_conflict_data.map_or(MIN_CONFLICT_MARKER_LEN, |data| {
+                            data.conflict_marker_len as usize
+                        }),
+                    )
+                    .block_on()?
+                };
             match new_file_ids.into_resolved() {
                 Ok(file_id) => {
                     // On Windows, we preserve the executable bit from the merged trees.
@@ -1543,15 +2048,31 @@ impl FileSnapshotter<'_> {
             message: format!("Failed to open file {}", disk_path.display()),
             err: err.into(),
         })?;
-        Ok(self.store().write_file(path, &mut file).await?)
+        if self.content_filters.is_empty() {
+            return Ok(self.store().write_file(path, &mut file).await?);
+        }
+        let mut contents = vec![];
+        file.read_to_end(&mut contents)
+            .map_err(|err| SnapshotError::Other {
+                message: format!("Failed to read file {}", disk_path.display()),
+                err: err.into(),
+            })?;
+        let contents = self.content_filters.clean(path, contents);
+        Ok(self
+            .store()
+            .write_file(path, &mut contents.as_slice())
+            .await?)
     }
 
+    /// Writes the symlink at `disk_path` to the store. If `is_link` is false,
+    /// the path is a regular file containing the target.
     async fn write_symlink_to_store(
         &self,
         path: &RepoPath,
         disk_path: &Path,
+        is_link: bool,
     ) -> Result<SymlinkId, SnapshotError> {
-        if self.tree_state.symlink_support {
+        if is_link {
             let target = disk_path.read_link().map_err(|err| SnapshotError::Other {
                 message: format!("Failed to read symlink {}", disk_path.display()),
                 err: err.into(),
@@ -1562,7 +2083,14 @@ impl FileSnapshotter<'_> {
                     .ok_or_else(|| SnapshotError::InvalidUtf8SymlinkTarget {
                         path: disk_path.to_path_buf(),
                     })?;
-            Ok(self.store().write_symlink(path, str_target).await?)
+            // Symlinks are created with native path separators on Windows,
+            // but targets are stored with forward slashes.
+            let str_target = if cfg!(windows) {
+                str_target.replace('\\', "/")
+            } else {
+                str_target.to_owned()
+            };
+            Ok(self.store().write_symlink(path, &str_target).await?)
         } else {
             let target = fs::read(disk_path).map_err(|err| SnapshotError::Other {
                 message: format!("Failed to read file {}", disk_path.display()),
@@ -1680,12 +2208,7 @@ impl TreeState {
             other => CheckoutError::InternalBackendError(other),
         })?;
         let stats = self
-            .update(
-                &old_tree,
-                new_tree,
-                self.sparse_matcher().as_ref(),
-                options.conflict_marker_style,
-            )
+            .update(&old_tree, new_tree, self.sparse_matcher().as_ref(), options)
             .block_on()?;
         self.tree_id = new_tree.id();
         Ok(stats)
@@ -1693,7 +2216,7 @@ impl TreeState {
 
     pub fn set_sparse_patterns(
         &mut self,
-        sparse_patterns: Vec<RepoPathBuf>,
+        sparse_patterns: Vec<SparsePattern>,
         options: &CheckoutOptions,
     ) -> Result<CheckoutStats, CheckoutError> {
         let tree = self.current_tree().map_err(|err| match err {
@@ -1702,28 +2225,21 @@ impl TreeState {
             },
             other => CheckoutError::InternalBackendError(other),
         })?;
-        let old_matcher = PrefixMatcher::new(&self.sparse_patterns);
-        let new_matcher = PrefixMatcher::new(&sparse_patterns);
+        let old_matcher = sparse_matcher(&self.sparse_patterns);
+        let new_matcher = sparse_matcher(&sparse_patterns);
         let added_matcher = DifferenceMatcher::new(&new_matcher, &old_matcher);
         let removed_matcher = DifferenceMatcher::new(&old_matcher, &new_matcher);
         let empty_tree = MergedTree::resolved(Tree::empty(self.store.clone(), RepoPathBuf::root()));
         let added_stats = self
-            .update(
-                &empty_tree,
-                &tree,
-                &added_matcher,
-                options.conflict_marker_style,
-            )
+            .update(&empty_tree, &tree, &added_matcher, options)
             .block_on()?;
         let removed_stats = self
-            .update(
-                &tree,
-                &empty_tree,
-                &removed_matcher,
-                options.conflict_marker_style,
-            )
+            .update(&tree, &empty_tree, &removed_matcher, options)
             .block_on()?;
         self.sparse_patterns = sparse_patterns;
+        // Directories that were outside the old sparse patterns may contain
+        // untracked files that the cache doesn't know about.
+        self.directory_states.clear();
         assert_eq!(added_stats.updated_files, 0);
         assert_eq!(added_stats.removed_files, 0);
         assert_eq!(removed_stats.updated_files, 0);
@@ -1742,8 +2258,12 @@ impl TreeState {
         old_tree: &MergedTree,
         new_tree: &MergedTree,
         matcher: &dyn Matcher,
-        conflict_marker_style: ConflictMarkerStyle,
+        options: &CheckoutOptions<'_>,
     ) -> Result<CheckoutStats, CheckoutError> {
+        let native_symlinks = self.use_native_symlinks(options.symlinks);
+        let content_filters = self
+            .content_filters_from_tree(new_tree, options.eol_conversion)
+            .await?;
         // TODO: maybe it's better not include the skipped counts in the "intended"
         // counts
         let mut stats = CheckoutStats {
@@ -1754,20 +2274,13 @@ impl TreeState {
         };
         let mut changed_file_states = Vec::new();
         let mut deleted_files = HashSet::new();
-        let mut diff_stream = old_tree
-            .diff_stream(new_tree, matcher)
-            .map(|TreeDiffEntry { path, values }| async {
-                match values {
-                    Ok((before, after)) => {
-                        let result = materialize_tree_value(&self.store, &path, after).await;
-                        (path, result.map(|value| (before, value)))
-                    }
-                    Err(err) => (path, Err(err)),
-                }
-            })
-            .buffered(self.store.concurrency().max(1));
-        while let Some((path, data)) = diff_stream.next().await {
-            let (before, after) = data?;
+        // Removals are applied in diff order first so that emptied parent
+        // directories can be pruned without racing against files being written
+        // into them. The remaining files are then materialized concurrently.
+        let mut files_to_write = Vec::new();
+        let mut diff_stream = old_tree.diff_stream(new_tree, matcher);
+        while let Some(TreeDiffEntry { path, values }) = diff_stream.next().await {
+            let (before, after) = values?;
             if after.is_absent() {
                 stats.removed_files += 1;
             } else if before.is_absent() {
@@ -1784,7 +2297,7 @@ impl TreeState {
             // paths excluded by .gitignore can be marked as such so that
             // newly-"unignored" paths won't be snapshotted automatically.
             if matches!(before.as_normal(), Some(TreeValue::GitSubmodule(_)))
-                && matches!(after, MaterializedTreeValue::GitSubmodule(_))
+                && matches!(after.as_normal(), Some(TreeValue::GitSubmodule(_)))
             {
                 eprintln!("ignoring git submodule at {path:?}");
                 // Not updating the file state as if there were no diffs. Leave
@@ -1792,89 +2305,192 @@ impl TreeState {
                 continue;
             }
 
-            // Create parent directories no matter if after.is_present(). This
-            // ensures that the path never traverses symlinks.
-            let Some(disk_path) = create_parent_dirs(&self.working_copy_path, &path)? else {
-                changed_file_states.push((path, FileState::placeholder()));
-                stats.skipped_files += 1;
+            if after.is_present() {
+                files_to_write.push((path, before, after));
                 continue;
-            };
-            // If the path was present, check reserved path first and delete it.
-            let present_file_deleted = before.is_present() && remove_old_file(&disk_path)?;
-            // If not, create temporary file to test the path validity.
-            if !present_file_deleted && !can_create_new_file(&disk_path)? {
+            }
+            let Some(disk_path) = self.prepare_disk_path(&path, &before)? else {
                 changed_file_states.push((path, FileState::placeholder()));
                 stats.skipped_files += 1;
                 continue;
+            };
+            let mut parent_dir = disk_path.parent().unwrap();
+            loop {
+                if fs::remove_dir(parent_dir).is_err() {
+                    break;
+                }
+                parent_dir = parent_dir.parent().unwrap();
             }
+            deleted_files.insert(path);
+        }
 
-            // TODO: Check that the file has not changed before overwriting/removing it.
-            let file_state = match after {
-                MaterializedTreeValue::Absent | MaterializedTreeValue::AccessDenied(_) => {
-                    let mut parent_dir = disk_path.parent().unwrap();
-                    loop {
-                        if fs::remove_dir(parent_dir).is_err() {
-                            break;
+        // Files are written in chunks so that progress can be reported in path
+        // order.
+        for chunk in files_to_write.chunks(CHECKOUT_CHUNK_SIZE) {
+            // Fetch the contents of a partial clone in one batch rather than
+            // per file.
+            let object_ids = chunk
+                .iter()
+                .flat_map(|(_, _, after)| after.iter().flatten())
+                .filter_map(|value| match value {
+                    TreeValue::File { id, .. } => Some(FetchableObjectId::File(id.clone())),
+                    TreeValue::Symlink(id) => Some(FetchableObjectId::Symlink(id.clone())),
+                    _ => None,
+                })
+                .collect_vec();
+            self.store.prefetch_objects(&object_ids)?;
+            let results: Vec<_> = chunk
+                .par_iter()
+                .map(|(path, before, after)| {
+                    self.write_new_value(
+                        path,
+                        before,
+                        after.clone(),
+                        options,
+                        native_symlinks,
+                        &content_filters,
+                    )
+                })
+                .collect();
+            for ((path, _, _), result) in iter::zip(chunk, results) {
+                match result? {
+                    FileUpdate::Written(file_state) => {
+                        if let Some(progress) = options.progress {
+                            progress(path);
                         }
-                        parent_dir = parent_dir.parent().unwrap();
+                        changed_file_states.push((path.clone(), file_state));
                     }
-                    deleted_files.insert(path);
-                    continue;
-                }
-                MaterializedTreeValue::File {
-                    executable,
-                    mut reader,
-                    ..
-                } => self.write_file(&disk_path, &mut reader, executable)?,
-                MaterializedTreeValue::Symlink { id: _, target } => {
-                    if self.symlink_support {
-                        self.write_symlink(&disk_path, target)?
-                    } else {
-                        self.write_file(&disk_path, &mut target.as_bytes(), false)?
+                    FileUpdate::Skipped => {
+                        changed_file_states.push((path.clone(), FileState::placeholder()));
+                        stats.skipped_files += 1;
+                    }
+                    FileUpdate::Removed => {
+                        deleted_files.insert(path.clone());
                     }
                 }
-                MaterializedTreeValue::GitSubmodule(_) => {
-                    eprintln!("ignoring git submodule at {path:?}");
-                    FileState::for_gitsubmodule()
+            }
+        }
+        self.file_states
+            .merge_in(changed_file_states, &deleted_files);
+        Ok(stats)
+    }
+
+    /// Creates parent directories of the `path` and removes the old file if
+    /// it was present. Returns `None` if the path should be skipped because
+    /// there's an untracked file or directory in the way.
+    fn prepare_disk_path(
+        &self,
+        path: &RepoPath,
+        before: &MergedTreeValue,
+    ) -> Result<Option<PathBuf>, CheckoutError> {
+        // Create parent directories no matter if after.is_present(). This
+        // ensures that the path never traverses symlinks.
+        let Some(disk_path) = create_parent_dirs(&self.working_copy_path, path)? else {
+            return Ok(None);
+        };
+        // If the path was present, check reserved path first and delete it.
+        let present_file_deleted = before.is_present() && remove_old_file(&disk_path)?;
+        // If not, create temporary file to test the path validity.
+        if !present_file_deleted && !can_create_new_file(&disk_path)? {
+            return Ok(None);
+        }
+        Ok(Some(disk_path))
+    }
+
+    /// Materializes the new value at `path` in the working copy.
+    ///
+    /// This may be called concurrently for different paths.
+    fn write_new_value(
+        &self,
+        path: &RepoPath,
+        before: &MergedTreeValue,
+        after: MergedTreeValue,
+        options: &CheckoutOptions<'_>,
+        native_symlinks: bool,
+        content_filters: &ContentFilters,
+    ) -> Result<FileUpdate, CheckoutError> {
+        let after = materialize_tree_value(&self.store, path, after).block_on()?;
+        let Some(disk_path) = self.prepare_disk_path(path, before)? else {
+            return Ok(FileUpdate::Skipped);
+        };
+
+        // TODO: Check that the file has not changed before overwriting/removing it.
+        let file_state = match after {
+            MaterializedTreeValue::Absent | MaterializedTreeValue::AccessDenied(_) => {
+                // Empty parent directories are left behind since other files
+                // may be being written into them.
+                return Ok(FileUpdate::Removed);
+            }
+            MaterializedTreeValue::File {
+                executable,
+                mut reader,
+                ..
+            } => {
+                if content_filters.is_empty() {
+                    self.write_file(&disk_path, &mut reader, executable)?
+                } else {
+                    let mut contents = vec![];
+                    reader
+                        .read_to_end(&mut contents)
+                        .map_err(|err| CheckoutError::Other {
+                            message: format!(
+                                "Failed to read file {} from the store",
+                                path.as_internal_file_string()
+                            ),
+                            err: err.into(),
+                        })?;
+                    let contents = content_filters.smudge(path, contents);
+                    self.write_file(&disk_path, &mut contents.as_slice(), executable)?
                 }
-                MaterializedTreeValue::Tree(_) => {
-                    panic!("unexpected tree entry in diff at {path:?}");
+            }
+            MaterializedTreeValue::Symlink { id: _, target } => {
+                if native_symlinks {
+                    self.write_symlink(&disk_path, target)?
+                } else {
+                    self.write_file(&disk_path, &mut target.as_bytes(), false)?
                 }
-                MaterializedTreeValue::FileConflict {
-                    id: _,
-                    contents,
-                    executable,
-                } => {
+            }
+            MaterializedTreeValue::GitSubmodule(_) => {
+                eprintln!("ignoring git submodule at {path:?}");
+                FileState::for_gitsubmodule()
+            }
+            MaterializedTreeValue::Tree(_) => {
+                panic!("unexpected tree entry in diff at {path:?}");
+            }
+            MaterializedTreeValue::FileConflict {
+                id: _,
+                contents,
+                executable,
+            } => {
+                let (data, materialized_conflict_data) = if let Some(materializer) =
+                    options.conflict_materializers.find(path)
+                {
+                    (materializer.materialize(path, &contents), None)
+                } else {
                     let conflict_marker_len = choose_materialized_conflict_marker_len(&contents);
                     let data = materialize_merge_result_to_bytes_with_marker_len(
                         &contents,
-                        conflict_marker_style,
+                        options.conflict_marker_style,
                         conflict_marker_len,
                     )
                     .into();
                     let materialized_conflict_data = MaterializedConflictData {
                         conflict_marker_len: conflict_marker_len.try_into().unwrap_or(u32::MAX),
                     };
-                    self.write_conflict(
-                        &disk_path,
-                        data,
-                        executable,
-                        Some(materialized_conflict_data),
-                    )?
-                }
-                MaterializedTreeValue::OtherConflict { id } => {
-                    // Unless all terms are regular files, we can't do much
-                    // better than trying to describe the merge.
-                    let data = id.describe().into_bytes();
-                    let executable = false;
-                    self.write_conflict(&disk_path, data, executable, None)?
-                }
-            };
-            changed_file_states.push((path, file_state));
-        }
-        self.file_states
-            .merge_in(changed_file_states, &deleted_files);
-        Ok(stats)
+                    (data, Some(materialized_conflict_data))
+                };
+                let data = content_filters.smudge(path, data);
+                self.write_conflict(&disk_path, data, executable, materialized_conflict_data)?
+            }
+            MaterializedTreeValue::OtherConflict { id } => {
+                // Unless all terms are regular files, we can't do much
+                // better than trying to describe the merge.
+                let data = id.describe().into_bytes();
+                let executable = false;
+                self.write_conflict(&disk_path, data, executable, None)?
+            }
+        };
+        Ok(FileUpdate::Written(file_state))
     }
 
     pub async fn reset(&mut self, new_tree: &MergedTree) -> Result<(), ResetError> {
@@ -1931,6 +2547,9 @@ impl TreeState {
         self.file_states
             .merge_in(changed_file_states, &deleted_files);
         self.tree_id = new_tree.id();
+        // Files removed from the tree are now untracked but still on disk, so
+        // their directories need to be scanned again.
+        self.directory_states.clear();
         Ok(())
     }
 
@@ -1941,6 +2560,149 @@ impl TreeState {
     }
 }
 
+/// Result of updating a single path in the working copy.
+enum FileUpdate {
+    /// The new value was written to the working copy.
+    Written(FileState),
+    /// There was an untracked file or directory in the way.
+    Skipped,
+    /// The new value couldn't be read, so the old file was just removed.
+    Removed,
+}
+
+const GITATTRIBUTES_FILE: &str = ".gitattributes";
+
+/// Transforms the contents of regular files between their representation in
+/// the store and in the working copy, like Git's clean and smudge filters.
+trait ContentFilter: Send + Sync {
+    /// Converts the contents of a file in the working copy to be written to
+    /// the store.
+    fn clean(&self, path: &RepoPath, contents: Vec<u8>) -> Vec<u8>;
+
+    /// Converts the contents of a file in the store to be written to the
+    /// working copy.
+    fn smudge(&self, path: &RepoPath, contents: Vec<u8>) -> Vec<u8>;
+}
+
+/// Chain of content filters. Filters are applied in order when checking out,
+/// and in reverse order when snapshotting.
+#[derive(Default)]
+struct ContentFilters {
+    filters: Vec<Box<dyn ContentFilter>>,
+}
+
+impl ContentFilters {
+    fn new(eol_conversion: EolConversionMode, attributes: GitAttributes) -> Self {
+        let mut filters: Vec<Box<dyn ContentFilter>> = vec![];
+        if eol_conversion != EolConversionMode::None {
+            filters.push(Box::new(EolConversionFilter {
+                mode: eol_conversion,
+                attributes,
+            }));
+        }
+        ContentFilters { filters }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.filters.is_empty()
+    }
+
+    fn clean(&self, path: &RepoPath, contents: Vec<u8>) -> Vec<u8> {
+        self.filters
+            .iter()
+            .rev()
+            .fold(contents, |contents, filter| filter.clean(path, contents))
+    }
+
+    fn smudge(&self, path: &RepoPath, contents: Vec<u8>) -> Vec<u8> {
+        self.filters
+            .iter()
+            .fold(contents, |contents, filter| filter.smudge(path, contents))
+    }
+}
+
+/// Converts line endings of text files.
+struct EolConversionFilter {
+    mode: EolConversionMode,
+    attributes: GitAttributes,
+}
+
+impl EolConversionFilter {
+    /// Returns true if the file should be treated as text. Unless specified by
+    /// the `text` attribute, a file is binary if it contains a NUL byte in the
+    /// first 8000 bytes, like Git does.
+    fn is_text(&self, path: &RepoPath, contents: &[u8]) -> bool {
+        match self.attributes.get(path, "text") {
+            AttributeState::Set => true,
+            AttributeState::Unset => false,
+            AttributeState::Value(_) | AttributeState::Unspecified => {
+                !contents[..contents.len().min(8000)].contains(&0)
+            }
+        }
+    }
+
+    /// Returns true if the file should be checked out with CRLF line endings.
+    fn is_crlf_on_checkout(&self, path: &RepoPath) -> bool {
+        match self.attributes.get(path, "eol") {
+            AttributeState::Value(value) if value == "crlf" => true,
+            AttributeState::Value(value) if value == "lf" => false,
+            _ => self.mode == EolConversionMode::InputOutput,
+        }
+    }
+}
+
+impl ContentFilter for EolConversionFilter {
+    fn clean(&self, path: &RepoPath, contents: Vec<u8>) -> Vec<u8> {
+        if self.is_text(path, &contents) {
+            convert_crlf_to_lf(contents)
+        } else {
+            contents
+        }
+    }
+
+    fn smudge(&self, path: &RepoPath, contents: Vec<u8>) -> Vec<u8> {
+        if self.is_crlf_on_checkout(path) && self.is_text(path, &contents) {
+            convert_lf_to_crlf(contents)
+        } else {
+            contents
+        }
+    }
+}
+
+fn convert_crlf_to_lf(contents: Vec<u8>) -> Vec<u8> {
+    if !contents.windows(2).any(|pair| pair == b"\r\n") {
+        return contents;
+    }
+    let mut output = Vec::with_capacity(contents.len());
+    let mut bytes = contents.iter().copied().peekable();
+    while let Some(b) = bytes.next() {
+        if b == b'\r' && bytes.peek() == Some(&b'\n') {
+            continue;
+        }
+        output.push(b);
+    }
+    output
+}
+
+/// Converts bare LF line endings to CRLF. Existing CRLF line endings are left
+/// alone.
+fn convert_lf_to_crlf(contents: Vec<u8>) -> Vec<u8> {
+    let lf_count = contents.iter().filter(|&&b| b == b'\n').count();
+    if lf_count == 0 {
+        return contents;
+    }
+    let mut output = Vec::with_capacity(contents.len() + lf_count);
+    let mut prev = None;
+    for b in contents {
+        if b == b'\n' && prev != Some(b'\r') {
+            output.push(b'\r');
+        }
+        output.push(b);
+        prev = Some(b);
+    }
+    output
+}
+
 fn checkout_error_for_stat_error(err: io::Error, path: &Path) -> CheckoutError {
     CheckoutError::Other {
         message: format!("Failed to stat file {}", path.display()),
@@ -1984,7 +2746,7 @@ impl WorkingCopy for LocalWorkingCopy {
         Ok(self.tree_state()?.current_tree_id())
     }
 
-    fn sparse_patterns(&self) -> Result<&[RepoPathBuf], WorkingCopyStateError> {
+    fn sparse_patterns(&self) -> Result<&[SparsePattern], WorkingCopyStateError> {
         Ok(self.tree_state()?.sparse_patterns())
     }
 
@@ -2135,6 +2897,11 @@ impl LocalWorkingCopy {
         Ok(self.tree_state()?.file_states())
     }
 
+    /// See `TreeState::assume_unchanged_paths()`.
+    pub fn assume_unchanged_paths(&self) -> Result<&[RepoPathBuf], WorkingCopyStateError> {
+        Ok(self.tree_state()?.assume_unchanged_paths())
+    }
+
     #[instrument(skip_all)]
     fn save(&mut self) {
         self.write_proto(crate::protos::working_copy::Checkout {
@@ -2301,13 +3068,13 @@ impl LockedWorkingCopy for LockedLocalWorkingCopy {
         Ok(())
     }
 
-    fn sparse_patterns(&self) -> Result<&[RepoPathBuf], WorkingCopyStateError> {
+    fn sparse_patterns(&self) -> Result<&[SparsePattern], WorkingCopyStateError> {
         self.wc.sparse_patterns()
     }
 
     fn set_sparse_patterns(
         &mut self,
-        new_sparse_patterns: Vec<RepoPathBuf>,
+        new_sparse_patterns: Vec<SparsePattern>,
         options: &CheckoutOptions,
     ) -> Result<CheckoutStats, CheckoutError> {
         // TODO: Write a "pending_checkout" file with new sparse patterns so we can
@@ -2352,6 +3119,21 @@ impl LockedWorkingCopy for LockedLocalWorkingCopy {
 }
 
 impl LockedLocalWorkingCopy {
+    /// See `TreeState::assume_unchanged_paths()`.
+    pub fn assume_unchanged_paths(&self) -> Result<&[RepoPathBuf], WorkingCopyStateError> {
+        self.wc.assume_unchanged_paths()
+    }
+
+    /// See `TreeState::set_assume_unchanged_paths()`.
+    pub fn set_assume_unchanged_paths(
+        &mut self,
+        paths: Vec<RepoPathBuf>,
+    ) -> Result<(), WorkingCopyStateError> {
+        self.wc.tree_state_mut()?.set_assume_unchanged_paths(paths);
+        self.tree_state_dirty = true;
+        Ok(())
+    }
+
     pub fn reset_watchman(&mut self) -> Result<(), SnapshotError> {
         self.wc
             .tree_state_mut()
@@ -2566,4 +3348,53 @@ mod tests {
             None
         );
     }
+
+    #[test]
+    fn test_sparse_patterns_proto() {
+        let parse = |input| SparsePattern::parse(input).unwrap();
+
+        // Version 0 only has prefixes
+        let proto = crate::protos::working_copy::SparsePatterns {
+            prefixes: vec![b"a".to_vec(), b"b/c".to_vec()],
+            ..Default::default()
+        };
+        assert_eq!(
+            sparse_patterns_from_proto(Some(&proto)).unwrap(),
+            [parse("a"), parse("b/c")]
+        );
+        assert_eq!(
+            sparse_patterns_from_proto(None).unwrap(),
+            [SparsePattern::root()]
+        );
+
+        // Prefixes are still written for older versions if possible
+        let patterns = [parse("a"), parse("b/c")];
+        let proto = sparse_patterns_to_proto(&patterns);
+        assert_eq!(proto.version, SPARSE_PATTERNS_VERSION);
+        assert_eq!(proto.prefixes, [b"a".to_vec(), b"b/c".to_vec()]);
+        assert_eq!(sparse_patterns_from_proto(Some(&proto)).unwrap(), patterns);
+
+        let patterns = [parse("a"), parse("glob:*.md"), parse("!glob:a/test/**")];
+        let proto = sparse_patterns_to_proto(&patterns);
+        assert_eq!(
+            proto.prefixes,
+            [SPARSE_PATTERNS_INCOMPATIBLE_PREFIX.to_vec()]
+        );
+        assert_eq!(sparse_patterns_from_proto(Some(&proto)).unwrap(), patterns);
+
+        // Version 0 readers can't decode patterns other than prefixes
+        #[derive(Clone, PartialEq, prost::Message)]
+        struct SparsePatternsV0 {
+            #[prost(string, repeated, tag = "1")]
+            prefixes: Vec<String>,
+        }
+        assert!(SparsePatternsV0::decode(&*proto.encode_to_vec()).is_err());
+        let proto = sparse_patterns_to_proto(&[parse("a")]);
+        let proto_v0 = SparsePatternsV0::decode(&*proto.encode_to_vec()).unwrap();
+        assert_eq!(proto_v0.prefixes, ["a"]);
+
+        // An empty list of patterns is preserved
+        let proto = sparse_patterns_to_proto(&[]);
+        assert!(sparse_patterns_from_proto(Some(&proto)).unwrap().is_empty());
+    }
 }
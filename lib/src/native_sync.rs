@@ -0,0 +1,212 @@
+// Copyright 2024 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Synchronization between repos using the native (non-Git) backend.
+//!
+//! Commits, trees, files, operations, and views are all content-addressed in
+//! the native stores, so two repos can exchange them by copying the object
+//! files the other side is missing. Bookmarks are then exchanged much like
+//! with Git remotes: the bookmarks of the other repo are recorded as remote
+//! bookmarks, and tracked local bookmarks are pushed back.
+
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::Path;
+
+use tempfile::NamedTempFile;
+use thiserror::Error;
+
+use crate::backend::BackendError;
+use crate::backend::BackendLoadError;
+use crate::file_util::persist_content_addressed_temp_file;
+use crate::file_util::IoResultExt as _;
+use crate::file_util::PathError;
+use crate::local_backend::LocalBackend;
+use crate::op_store::RefTarget;
+use crate::op_store::RemoteRef;
+use crate::op_store::RemoteRefState;
+use crate::repo::MutableRepo;
+use crate::repo::ReadonlyRepo;
+use crate::repo::Repo as _;
+use crate::simple_op_store::SimpleOpStore;
+
+/// Directories of operations and views, relative to the repo directory. Views
+/// are copied first so that an interrupted copy never leaves an operation
+/// behind whose view is missing.
+const OP_OBJECT_DIRS: &[&str] = &["op_store/views", "op_store/operations"];
+
+/// Error that may occur while syncing with another repo.
+#[derive(Debug, Error)]
+pub enum NativeSyncError {
+    /// Failed to copy objects between the repos.
+    #[error(transparent)]
+    Path(#[from] PathError),
+    /// The local bookmark to push is conflicted.
+    #[error("Bookmark {0} is conflicted")]
+    ConflictedBookmark(String),
+    /// The bookmark was moved in the other repo since it was last pulled.
+    #[error("Bookmark {0} was changed in the remote repo since it was last pulled")]
+    RemoteBookmarkChanged(String),
+    /// A commit pointed to by a bookmark couldn't be read.
+    #[error(transparent)]
+    Backend(#[from] BackendError),
+    /// The commit store of a repo couldn't be loaded.
+    #[error(transparent)]
+    BackendLoad(#[from] BackendLoadError),
+}
+
+/// Returns true if `repo` uses the native commit and operation stores.
+pub fn is_native_repo(repo: &ReadonlyRepo) -> bool {
+    repo.store().backend_impl().is::<LocalBackend>()
+        && repo.op_store().as_any().is::<SimpleOpStore>()
+}
+
+/// Copies the objects missing from the repo at `dest_repo_path` from the repo
+/// at `source_repo_path`. Returns the number of copied objects.
+///
+/// Both paths point to repo directories (usually `.jj/repo`), which must use
+/// the native stores. The commits and their contents are located through the
+/// backends, so repos writing to a shared object store are supported.
+/// Operations are copied after the commits so that an interrupted copy never
+/// leaves an operation behind whose commits are missing.
+pub fn copy_objects(
+    source_repo_path: &Path,
+    dest_repo_path: &Path,
+) -> Result<usize, NativeSyncError> {
+    let source_backend = LocalBackend::load(&source_repo_path.join("store"))?;
+    let dest_backend = LocalBackend::load(&dest_repo_path.join("store"))?;
+    let mut count = dest_backend.copy_missing_objects_from(&source_backend)?;
+    for dir in OP_OBJECT_DIRS {
+        let source_dir = source_repo_path.join(dir);
+        let dest_dir = dest_repo_path.join(dir);
+        for entry in source_dir.read_dir().context(&source_dir)? {
+            let entry = entry.context(&source_dir)?;
+            let file_name = entry.file_name();
+            let dest_path = dest_dir.join(&file_name);
+            if file_name.to_string_lossy().starts_with('.') || dest_path.exists() {
+                continue;
+            }
+            let source_path = entry.path();
+            let mut temp_file = NamedTempFile::new_in(&dest_dir).context(&dest_dir)?;
+            let mut source_file = fs::File::open(&source_path).context(&source_path)?;
+            std::io::copy(&mut source_file, temp_file.as_file_mut()).context(&source_path)?;
+            persist_content_addressed_temp_file(temp_file, &dest_path).context(&dest_path)?;
+            count += 1;
+        }
+    }
+    Ok(count)
+}
+
+/// Records the local bookmarks of `remote_repo` as remote bookmarks of
+/// `remote_name` in `mut_repo`, and merges them into the tracking local
+/// bookmarks.
+///
+/// The objects of `remote_repo` must have been copied to `mut_repo` first.
+/// Returns the names of the updated remote bookmarks.
+pub fn pull_bookmarks(
+    mut_repo: &mut MutableRepo,
+    remote_repo: &ReadonlyRepo,
+    remote_name: &str,
+) -> Result<Vec<String>, NativeSyncError> {
+    let remote_view = remote_repo.view();
+    let names: BTreeSet<String> = remote_view
+        .local_bookmarks()
+        .map(|(name, _)| name.to_owned())
+        .chain(
+            mut_repo
+                .view()
+                .remote_bookmarks(remote_name)
+                .map(|(name, _)| name.to_owned()),
+        )
+        .collect();
+    let mut updated = vec![];
+    for name in names {
+        let old_remote_ref = mut_repo.get_remote_bookmark(&name, remote_name);
+        let new_target = remote_view.get_local_bookmark(&name);
+        if old_remote_ref.target == *new_target {
+            continue;
+        }
+        let head_commits: Vec<_> = new_target
+            .added_ids()
+            .map(|id| mut_repo.store().get_commit(id))
+            .collect::<Result<_, _>>()?;
+        mut_repo.add_heads(&head_commits)?;
+        let new_remote_ref = RemoteRef {
+            target: new_target.clone(),
+            state: if old_remote_ref.is_present() {
+                old_remote_ref.state
+            } else {
+                RemoteRefState::New
+            },
+        };
+        if new_remote_ref.is_tracking() {
+            mut_repo.merge_local_bookmark(
+                &name,
+                old_remote_ref.tracking_target(),
+                &new_remote_ref.target,
+            );
+        }
+        mut_repo.set_remote_bookmark(&name, remote_name, new_remote_ref);
+        updated.push(name);
+    }
+    Ok(updated)
+}
+
+/// Updates the local bookmarks of the remote repo being mutated by
+/// `remote_mut_repo` to the bookmarks in `mut_repo` which track `remote_name`,
+/// plus the `new_bookmarks` which don't exist in the remote repo yet.
+///
+/// Bookmarks which were changed in the remote repo since they were last
+/// pulled are rejected, and nothing is pushed in that case. The objects of
+/// `mut_repo` must have been copied to the remote repo first. Returns the
+/// names of the pushed bookmarks.
+pub fn push_bookmarks(
+    mut_repo: &mut MutableRepo,
+    remote_mut_repo: &mut MutableRepo,
+    remote_name: &str,
+    new_bookmarks: &[String],
+) -> Result<Vec<String>, NativeSyncError> {
+    let mut updates: Vec<(String, RefTarget)> = vec![];
+    for (name, targets) in mut_repo.view().local_remote_bookmarks(remote_name) {
+        let is_new = !targets.remote_ref.is_present()
+            && new_bookmarks.iter().any(|new_name| new_name == name);
+        if !targets.remote_ref.is_tracking() && !is_new {
+            continue;
+        }
+        if *targets.local_target == targets.remote_ref.target {
+            continue;
+        }
+        if targets.local_target.has_conflict() {
+            return Err(NativeSyncError::ConflictedBookmark(name.to_owned()));
+        }
+        if remote_mut_repo.get_local_bookmark(name) != targets.remote_ref.target {
+            return Err(NativeSyncError::RemoteBookmarkChanged(name.to_owned()));
+        }
+        updates.push((name.to_owned(), targets.local_target.clone()));
+    }
+    for (name, target) in &updates {
+        let head_commits: Vec<_> = target
+            .added_ids()
+            .map(|id| remote_mut_repo.store().get_commit(id))
+            .collect::<Result<_, _>>()?;
+        remote_mut_repo.add_heads(&head_commits)?;
+        remote_mut_repo.set_local_bookmark_target(name, target.clone());
+        let remote_ref = RemoteRef {
+            target: target.clone(),
+            state: RemoteRefState::Tracking,
+        };
+        mut_repo.set_remote_bookmark(name, remote_name, remote_ref);
+    }
+    Ok(updates.into_iter().map(|(name, _)| name).collect())
+}
@@ -18,6 +18,9 @@ use std::any::Any;
 use std::collections::HashSet;
 use std::fmt::Debug;
 use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+use std::time::Instant;
 
 use itertools::Itertools;
 use thiserror::Error;
@@ -153,3 +156,37 @@ where
     op_heads_store.update_op_heads(&old_op_heads, new_op.id())?;
     Ok(new_op)
 }
+
+/// Blocks until the set of op heads differs from `old_op_heads`, and returns
+/// the new op heads.
+///
+/// The store is polled every `poll_interval`. Returns `None` if the op heads
+/// didn't change within `timeout`.
+pub fn wait_for_op_heads_change(
+    op_heads_store: &dyn OpHeadsStore,
+    old_op_heads: &[OperationId],
+    poll_interval: Duration,
+    timeout: Option<Duration>,
+) -> Result<Option<Vec<OperationId>>, OpHeadsStoreError> {
+    let old_op_heads: HashSet<&OperationId> = old_op_heads.iter().collect();
+    let deadline = timeout.map(|timeout| Instant::now() + timeout);
+    loop {
+        let op_heads = op_heads_store.get_op_heads()?;
+        // The op heads can be transiently empty while another process is
+        // updating them. See resolve_op_heads().
+        if !op_heads.is_empty() && op_heads.iter().collect::<HashSet<_>>() != old_op_heads {
+            return Ok(Some(op_heads));
+        }
+        let sleep_duration = match deadline {
+            Some(deadline) => {
+                let now = Instant::now();
+                if now >= deadline {
+                    return Ok(None);
+                }
+                poll_interval.min(deadline - now)
+            }
+            None => poll_interval,
+        };
+        thread::sleep(sleep_duration);
+    }
+}
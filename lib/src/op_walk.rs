@@ -332,3 +332,92 @@ pub fn reparent_range(
         unreachable_count: unreachable_ids.len(),
     })
 }
+
+/// Squashes runs of consecutive snapshot operations into the last operation of
+/// each run.
+///
+/// A snapshot operation is squashed into its child if it has exactly one
+/// parent, it isn't one of the `head_ops`, and its only child is also a
+/// snapshot operation with a single parent. The squashed operation keeps the
+/// view and metadata of the child, but starts at the time the run started.
+/// Undoing it restores the state before the first snapshot in the run.
+///
+/// Returns the new head operation ids as well as some stats. The squashed
+/// operations become unreachable if the old operation heads are remapped to the
+/// new heads.
+pub fn squash_snapshot_operations(
+    op_store: &dyn OpStore,
+    head_ops: &[Operation],
+) -> OpStoreResult<ReparentStats> {
+    let ops: Vec<_> = walk_ancestors(head_ops).try_collect()?;
+    let head_ids: HashSet<_> = head_ops.iter().map(|op| op.id()).collect();
+    let mut children_map: HashMap<&OperationId, Vec<&Operation>> = HashMap::new();
+    for op in &ops {
+        for parent_id in op.parent_ids() {
+            children_map.entry(parent_id).or_default().push(op);
+        }
+    }
+    let is_squashable = |op: &Operation| {
+        op.metadata().is_snapshot
+            && op.parent_ids().len() == 1
+            && !head_ids.contains(op.id())
+            && match children_map
+                .get(op.id())
+                .map(|children| children.as_slice())
+            {
+                Some([child]) => child.metadata().is_snapshot && child.parent_ids().len() == 1,
+                _ => false,
+            }
+    };
+
+    // Maps squashed operations to their (rewritten) parent and to the start time
+    // of the run they belong to.
+    let mut replaced_ids: HashMap<OperationId, OperationId> = HashMap::new();
+    let mut rewritten_ids: HashMap<OperationId, OperationId> = HashMap::new();
+    let mut run_start_times = HashMap::new();
+    let mut unreachable_count = 0;
+    for old_op in ops.iter().rev() {
+        let map_id = |id: &OperationId| {
+            replaced_ids
+                .get(id)
+                .or_else(|| rewritten_ids.get(id))
+                .unwrap_or(id)
+                .clone()
+        };
+        if is_squashable(old_op) {
+            let parent_id = &old_op.parent_ids()[0];
+            let start_time = run_start_times
+                .get(parent_id)
+                .copied()
+                .unwrap_or(old_op.metadata().start_time);
+            let new_parent_id = map_id(parent_id);
+            replaced_ids.insert(old_op.id().clone(), new_parent_id);
+            run_start_times.insert(old_op.id().clone(), start_time);
+            unreachable_count += 1;
+            continue;
+        }
+        let new_parent_ids = old_op.parent_ids().iter().map(map_id).collect_vec();
+        if new_parent_ids == old_op.parent_ids() {
+            continue;
+        }
+        let mut data = old_op.store_operation().clone();
+        if let [parent_id] = old_op.parent_ids() {
+            if let Some(start_time) = run_start_times.get(parent_id) {
+                data.metadata.start_time = *start_time;
+            }
+        }
+        data.parents = new_parent_ids;
+        let new_id = op_store.write_operation(&data)?;
+        rewritten_ids.insert(old_op.id().clone(), new_id);
+    }
+
+    let new_head_ids = head_ops
+        .iter()
+        .map(|op| rewritten_ids.get(op.id()).unwrap_or(op.id()).clone())
+        .collect();
+    Ok(ReparentStats {
+        new_head_ids,
+        rewritten_count: rewritten_ids.len(),
+        unreachable_count,
+    })
+}
@@ -32,9 +32,51 @@ pub struct FileStateEntry {
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
+pub struct DirectoryState {
+    #[prost(int64, tag = "1")]
+    pub mtime_millis_since_epoch: i64,
+    /// Fingerprint of the ignore rules that applied when the directory was
+    /// scanned
+    #[prost(fixed64, tag = "2")]
+    pub ignore_fingerprint: u64,
+    /// Names of the subdirectories that were scanned for new files
+    #[prost(string, repeated, tag = "3")]
+    pub subdirs: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct DirectoryStateEntry {
+    #[prost(string, tag = "1")]
+    pub path: ::prost::alloc::string::String,
+    #[prost(message, optional, tag = "2")]
+    pub state: ::core::option::Option<DirectoryState>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SparsePattern {
+    #[prost(enumeration = "SparsePatternKind", tag = "1")]
+    pub kind: i32,
+    /// Workspace-relative path or glob pattern, using "/" as separator
+    #[prost(string, tag = "2")]
+    pub pattern: ::prost::alloc::string::String,
+    #[prost(bool, tag = "3")]
+    pub exclude: bool,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
 pub struct SparsePatterns {
-    #[prost(string, repeated, tag = "1")]
-    pub prefixes: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+    /// Path prefixes to include. Used by version 0, and also written by later
+    /// versions if all patterns are included prefixes. Otherwise, later versions
+    /// write a single invalid UTF-8 prefix so that version 0 readers fail to
+    /// decode the tree state instead of checking out nothing. Version 0 declared
+    /// this field as `string`, which is compatible on the wire.
+    #[prost(bytes = "vec", repeated, tag = "1")]
+    pub prefixes: ::prost::alloc::vec::Vec<::prost::alloc::vec::Vec<u8>>,
+    /// Version 1 stores all patterns in `patterns`.
+    #[prost(uint32, tag = "2")]
+    pub version: u32,
+    #[prost(message, repeated, tag = "3")]
+    pub patterns: ::prost::alloc::vec::Vec<SparsePattern>,
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -53,6 +95,14 @@ pub struct TreeState {
     pub sparse_patterns: ::core::option::Option<SparsePatterns>,
     #[prost(message, optional, tag = "4")]
     pub watchman_clock: ::core::option::Option<WatchmanClock>,
+    /// Directories that were fully scanned by the last snapshot, used to skip
+    /// reading unchanged directories
+    #[prost(message, repeated, tag = "7")]
+    pub directory_states: ::prost::alloc::vec::Vec<DirectoryStateEntry>,
+    /// Paths (files or directories) that are skipped when snapshotting unless
+    /// explicitly refreshed
+    #[prost(string, repeated, tag = "8")]
+    pub assume_unchanged_paths: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -119,3 +169,29 @@ impl FileType {
         }
     }
 }
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum SparsePatternKind {
+    Prefix = 0,
+    Glob = 1,
+}
+impl SparsePatternKind {
+    /// String value of the enum field names used in the ProtoBuf definition.
+    ///
+    /// The values are not transformed in any way and thus are considered stable
+    /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+    pub fn as_str_name(&self) -> &'static str {
+        match self {
+            SparsePatternKind::Prefix => "Prefix",
+            SparsePatternKind::Glob => "Glob",
+        }
+    }
+    /// Creates an enum from field names used in the ProtoBuf definition.
+    pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
+        match value {
+            "Prefix" => Some(Self::Prefix),
+            "Glob" => Some(Self::Glob),
+            _ => None,
+        }
+    }
+}
@@ -42,6 +42,7 @@ use crate::commit::Commit;
 use crate::commit::CommitByCommitterTimestamp;
 use crate::commit_builder::CommitBuilder;
 use crate::commit_builder::DetachedCommitBuilder;
+use crate::config::ConfigGetError;
 use crate::dag_walk;
 use crate::default_index::DefaultIndexStore;
 use crate::default_index::DefaultMutableIndex;
@@ -52,6 +53,7 @@ use crate::index::ChangeIdIndex;
 use crate::index::Index;
 use crate::index::IndexReadError;
 use crate::index::IndexStore;
+use crate::index::IndexWriteError;
 use crate::index::MutableIndex;
 use crate::index::ReadonlyIndex;
 use crate::local_backend::LocalBackend;
@@ -80,7 +82,6 @@ use crate::refs::merge_ref_targets;
 use crate::refs::merge_remote_refs;
 use crate::revset;
 use crate::revset::RevsetExpression;
-use crate::revset::RevsetIteratorExt;
 use crate::rewrite::merge_commit_trees;
 use crate::rewrite::rebase_commit_with_options;
 use crate::rewrite::CommitRewriter;
@@ -153,6 +154,8 @@ pub enum RepoInitError {
     OpHeadsStore(#[from] OpHeadsStoreError),
     #[error(transparent)]
     Path(#[from] PathError),
+    #[error(transparent)]
+    Config(#[from] ConfigGetError),
 }
 
 impl ReadonlyRepo {
@@ -193,7 +196,7 @@ impl ReadonlyRepo {
         let backend = backend_initializer(user_settings, &store_path)?;
         let backend_path = store_path.join("type");
         fs::write(&backend_path, backend.name()).context(&backend_path)?;
-        let store = Store::new(backend, signer);
+        let store = Store::new(backend, signer, user_settings.verify_writes()?);
         let repo_settings = user_settings.with_repo(&repo_path);
 
         let op_store_path = repo_path.join("op_store");
@@ -398,7 +401,7 @@ impl Default for StoreFactories {
         // Backends
         factories.add_backend(
             LocalBackend::name(),
-            Box::new(|_settings, store_path| Ok(Box::new(LocalBackend::load(store_path)))),
+            Box::new(|_settings, store_path| Ok(Box::new(LocalBackend::load(store_path)?))),
         );
         #[cfg(feature = "git")]
         factories.add_backend(
@@ -465,6 +468,8 @@ pub enum StoreLoadError {
     Backend(#[from] BackendLoadError),
     #[error(transparent)]
     Signing(#[from] SignInitError),
+    #[error(transparent)]
+    Config(#[from] ConfigGetError),
 }
 
 impl StoreFactories {
@@ -666,6 +671,7 @@ impl RepoLoader {
         let store = Store::new(
             store_factories.load_backend(user_settings, &repo_path.join("store"))?,
             Signer::from_settings(user_settings)?,
+            user_settings.verify_writes()?,
         );
         let repo_settings = user_settings.with_repo(repo_path);
         let root_op_data = RootOperationData {
@@ -841,8 +847,9 @@ enum Rewrite {
     /// not be rebased.
     Divergent(Vec<CommitId>),
     /// The old commit was abandoned. Children should be rebased onto the given
-    /// commits (typically the parents of the old commit).
-    Abandoned(Vec<CommitId>),
+    /// commits (typically the parents of the old commit). Bookmarks are
+    /// updated according to the given policy.
+    Abandoned(Vec<CommitId>, AbandonedBookmarks),
 }
 
 impl Rewrite {
@@ -850,14 +857,30 @@ impl Rewrite {
         match self {
             Rewrite::Rewritten(new_parent_id) => std::slice::from_ref(new_parent_id),
             Rewrite::Divergent(new_parent_ids) => new_parent_ids.as_slice(),
-            Rewrite::Abandoned(new_parent_ids) => new_parent_ids.as_slice(),
+            Rewrite::Abandoned(new_parent_ids, _) => new_parent_ids.as_slice(),
         }
     }
 }
 
+/// What to do with local bookmarks pointing to an abandoned commit.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum AbandonedBookmarks {
+    /// Move the bookmarks to the new parents of the abandoned commit.
+    #[default]
+    Move,
+    /// Delete the bookmarks.
+    Delete,
+}
+
+/// Number of commits added to the index of a `MutableRepo` after which they're
+/// flushed to the index store, so rewriting many commits doesn't keep all of
+/// them in the in-memory lookup tables of the index.
+const MAX_UNFLUSHED_INDEX_COMMITS: usize = 10_000;
+
 pub struct MutableRepo {
     base_repo: Arc<ReadonlyRepo>,
     index: Box<dyn MutableIndex>,
+    num_unflushed_index_commits: usize,
     view: DirtyCell<View>,
     // The commit identified by the key has been replaced by all the ones in the value.
     // * Bookmarks pointing to the old commit should be updated to the new commit, resulting in a
@@ -881,6 +904,7 @@ impl MutableRepo {
         MutableRepo {
             base_repo,
             index: mut_index,
+            num_unflushed_index_commits: 0,
             view: DirtyCell::with_clean(mut_view),
             parent_mapping: Default::default(),
         }
@@ -898,6 +922,19 @@ impl MutableRepo {
         self.index.as_ref()
     }
 
+    /// Writes the commits added to the index so far to the index store. The
+    /// default index then keeps them in the compact on-disk format instead of
+    /// the in-memory lookup tables, which grow fast.
+    ///
+    /// This is done automatically when many commits have been added.
+    pub fn flush_index(&mut self) -> Result<(), IndexWriteError> {
+        self.base_repo
+            .index_store()
+            .flush_index(self.index.as_mut())?;
+        self.num_unflushed_index_commits = 0;
+        Ok(())
+    }
+
     pub fn has_changes(&self) -> bool {
         !(self.parent_mapping.is_empty() && self.view() == &self.base_repo.view)
     }
@@ -970,10 +1007,25 @@ impl MutableRepo {
     /// `old_id` would be moved to the parent(s) of `old_id` as well.
     // TODO: Propagate errors from commit lookup or take a Commit as argument.
     pub fn record_abandoned_commit(&mut self, old_id: CommitId) {
+        self.record_abandoned_commit_with_bookmarks(old_id, AbandonedBookmarks::Move);
+    }
+
+    /// Record a commit as having been abandoned in this transaction.
+    ///
+    /// Same as `record_abandoned_commit()`, but local bookmarks at `old_id`
+    /// will be updated according to the given `bookmarks` policy.
+    pub fn record_abandoned_commit_with_bookmarks(
+        &mut self,
+        old_id: CommitId,
+        bookmarks: AbandonedBookmarks,
+    ) {
         assert_ne!(old_id, *self.store().root_commit_id());
         // Descendants should be rebased onto the commit's parents
         let old_commit = self.store().get_commit(&old_id).unwrap();
-        self.record_abandoned_commit_with_parents(old_id, old_commit.parent_ids().to_vec());
+        self.parent_mapping.insert(
+            old_id,
+            Rewrite::Abandoned(old_commit.parent_ids().to_vec(), bookmarks),
+        );
     }
 
     /// Record a commit as having been abandoned in this transaction.
@@ -989,7 +1041,10 @@ impl MutableRepo {
         assert_ne!(old_id, *self.store().root_commit_id());
         self.parent_mapping.insert(
             old_id,
-            Rewrite::Abandoned(new_parent_ids.into_iter().collect()),
+            Rewrite::Abandoned(
+                new_parent_ids.into_iter().collect(),
+                AbandonedBookmarks::Move,
+            ),
         );
     }
 
@@ -1108,6 +1163,12 @@ impl MutableRepo {
             .collect_vec();
         for (bookmark_name, (old_commit_id, new_commit_ids)) in changed_branches {
             let old_target = RefTarget::normal(old_commit_id.clone());
+            if let Some(Rewrite::Abandoned(_, AbandonedBookmarks::Delete)) =
+                self.parent_mapping.get(old_commit_id)
+            {
+                self.merge_local_bookmark(&bookmark_name, &old_target, RefTarget::absent_ref());
+                continue;
+            }
             let new_target = RefTarget::from_merge(
                 MergeBuilder::from_iter(
                     itertools::intersperse(new_commit_ids, old_commit_id)
@@ -1138,7 +1199,7 @@ impl MutableRepo {
         for (workspace_id, (old_commit_id, new_commit_ids)) in changed_wc_commits {
             let abandoned_old_commit = matches!(
                 self.parent_mapping.get(old_commit_id),
-                Some(Rewrite::Abandoned(_))
+                Some(Rewrite::Abandoned(..))
             );
             let new_wc_commit = if !abandoned_old_commit {
                 // We arbitrarily pick a new working-copy commit among the candidates.
@@ -1186,11 +1247,14 @@ impl MutableRepo {
         self.set_view(view);
     }
 
-    /// Find descendants of `root`, unless they've already been rewritten
-    /// (according to `parent_mapping`), and then return them in
-    /// an order they should be rebased in. The result is in reverse order
-    /// so the next value can be removed from the end.
-    fn find_descendants_to_rebase(&self, roots: Vec<CommitId>) -> BackendResult<Vec<Commit>> {
+    /// Find descendants of `roots`, unless they've already been rewritten
+    /// (according to `parent_mapping`), and then return their IDs in an order
+    /// they should be rebased in. The result is in reverse order so the next
+    /// value can be removed from the end.
+    ///
+    /// Only IDs are collected so that rebasing a huge number of commits doesn't
+    /// keep all of them in memory. The commits are loaded as they're visited.
+    fn find_descendants_to_rebase(&self, roots: Vec<CommitId>) -> BackendResult<Vec<CommitId>> {
         let store = self.store();
         let to_visit_expression =
             RevsetExpression::commits(roots)
@@ -1203,36 +1267,34 @@ impl MutableRepo {
             .map_err(|err| err.expect_backend_error())?;
         let to_visit: Vec<_> = to_visit_revset
             .iter()
-            .commits(store)
             .try_collect()
             // TODO: Return evaluation error to caller
             .map_err(|err| err.expect_backend_error())?;
         drop(to_visit_revset);
-        let to_visit_set: HashSet<CommitId> =
-            to_visit.iter().map(|commit| commit.id().clone()).collect();
+        let to_visit_set: HashSet<CommitId> = to_visit.iter().cloned().collect();
         let mut visited = HashSet::new();
         // Calculate an order where we rebase parents first, but if the parents were
         // rewritten, make sure we rebase the rewritten parent first.
         dag_walk::topo_order_reverse_ok(
             to_visit.into_iter().map(Ok),
-            |commit| commit.id().clone(),
-            |commit| -> Vec<BackendResult<Commit>> {
-                visited.insert(commit.id().clone());
+            |id| id.clone(),
+            |id| -> Vec<BackendResult<CommitId>> {
+                visited.insert(id.clone());
+                let commit = match store.get_commit(id) {
+                    Ok(commit) => commit,
+                    Err(err) => return vec![Err(err)],
+                };
                 let mut dependents = vec![];
-                for parent in commit.parents() {
-                    let Ok(parent) = parent else {
-                        dependents.push(parent);
-                        continue;
-                    };
-                    if let Some(rewrite) = self.parent_mapping.get(parent.id()) {
+                for parent_id in commit.parent_ids() {
+                    if let Some(rewrite) = self.parent_mapping.get(parent_id) {
                         for target in rewrite.new_parent_ids() {
                             if to_visit_set.contains(target) && !visited.contains(target) {
-                                dependents.push(store.get_commit(target));
+                                dependents.push(Ok(target.clone()));
                             }
                         }
                     }
-                    if to_visit_set.contains(parent.id()) {
-                        dependents.push(Ok(parent));
+                    if to_visit_set.contains(parent_id) {
+                        dependents.push(Ok(parent_id.clone()));
                     }
                 }
                 dependents
@@ -1258,7 +1320,8 @@ impl MutableRepo {
         mut callback: impl FnMut(CommitRewriter) -> BackendResult<()>,
     ) -> BackendResult<()> {
         let mut to_visit = self.find_descendants_to_rebase(roots)?;
-        while let Some(old_commit) = to_visit.pop() {
+        while let Some(old_commit_id) = to_visit.pop() {
+            let old_commit = self.store().get_commit(&old_commit_id)?;
             let new_parent_ids = self.new_parents(old_commit.parent_ids());
             let rewriter = CommitRewriter::new(self, old_commit, new_parent_ids);
             callback(rewriter)?;
@@ -1503,6 +1566,7 @@ impl MutableRepo {
                     .all(|parent_id| current_heads.contains(parent_id)) =>
             {
                 self.index.add_commit(head);
+                self.num_unflushed_index_commits += 1;
                 self.view.get_mut().add_head(head.id());
                 for parent_id in head.parent_ids() {
                     self.view.get_mut().remove_head(parent_id);
@@ -1529,12 +1593,17 @@ impl MutableRepo {
                 for CommitByCommitterTimestamp(missing_commit) in missing_commits.iter().rev() {
                     self.index.add_commit(missing_commit);
                 }
+                self.num_unflushed_index_commits += missing_commits.len();
                 for head in heads {
                     self.view.get_mut().add_head(head.id());
                 }
                 self.view.mark_dirty();
             }
         }
+        if self.num_unflushed_index_commits >= MAX_UNFLUSHED_INDEX_COMMITS {
+            self.flush_index()
+                .map_err(|err| BackendError::Other(err.into()))?;
+        }
         Ok(())
     }
 
@@ -0,0 +1,320 @@
+// Copyright 2024 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Backups of repo directories taken before they are rewritten in place.
+//!
+//! Commands that migrate or repair store files can snapshot the directories
+//! they are about to touch, so that an unexpected result can be rolled back.
+//! Each backup lives in its own timestamped directory under the repo
+//! directory, along with a metadata file recording the backup layout version.
+
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::path::PathBuf;
+
+use itertools::Itertools as _;
+use serde::Deserialize;
+use serde::Serialize;
+use thiserror::Error;
+
+use crate::file_util::create_or_reuse_dir;
+use crate::file_util::IoResultExt as _;
+use crate::file_util::PathError;
+
+/// Version of the backup directory layout written by this version of jj.
+pub const BACKUP_FORMAT_VERSION: u32 = 1;
+
+const METADATA_FILE_NAME: &str = "metadata.json";
+const CONTENTS_DIR_NAME: &str = "contents";
+
+/// Error creating, listing, or restoring backups.
+#[derive(Debug, Error)]
+pub enum RepoBackupError {
+    /// Failed to access the backup or repo directories.
+    #[error(transparent)]
+    Path(#[from] PathError),
+    /// The metadata of a backup couldn't be parsed.
+    #[error("Invalid backup metadata {path}")]
+    InvalidMetadata {
+        /// Path to the metadata file.
+        path: PathBuf,
+        /// Underlying parse error.
+        #[source]
+        source: serde_json::Error,
+    },
+    /// The backup was written in a layout this version doesn't understand.
+    #[error("Backup {name} has unsupported format version {version}")]
+    UnsupportedVersion {
+        /// Name of the backup.
+        name: String,
+        /// Format version recorded in the backup.
+        version: u32,
+    },
+    /// There's no backup of the given name.
+    #[error("No backup named {0}")]
+    NotFound(String),
+}
+
+/// Metadata stored along with a backup.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct BackupMetadata {
+    /// Layout version of the backup directory.
+    pub format_version: u32,
+    /// Short description of why the backup was taken, e.g. the command name.
+    pub reason: String,
+    /// Time the backup was taken, in RFC 3339 format.
+    pub timestamp: String,
+    /// Backed-up directories, relative to the repo directory.
+    pub dirs: Vec<String>,
+}
+
+/// A backup found in the backup area.
+#[derive(Clone, Debug)]
+pub struct RepoBackup {
+    /// Name of the backup directory.
+    pub name: String,
+    /// Metadata of the backup.
+    pub metadata: BackupMetadata,
+}
+
+/// Backup area of a repo, located under the repo directory.
+#[derive(Debug)]
+pub struct RepoBackups {
+    repo_path: PathBuf,
+    dir: PathBuf,
+}
+
+impl RepoBackups {
+    /// Name of the backup directory within the repo directory.
+    pub fn dir_name() -> &'static str {
+        "backups"
+    }
+
+    /// Loads the backup area of the repo at `repo_path`. The directory is
+    /// created lazily when the first backup is taken.
+    pub fn load(repo_path: &Path) -> Self {
+        RepoBackups {
+            repo_path: repo_path.to_owned(),
+            dir: repo_path.join(Self::dir_name()),
+        }
+    }
+
+    /// Copies the given directories (relative to the repo directory) to a new
+    /// backup. Directories that don't exist are skipped.
+    pub fn create(&self, reason: &str, dirs: &[&str]) -> Result<RepoBackup, RepoBackupError> {
+        create_or_reuse_dir(&self.dir).context(&self.dir)?;
+        let now = chrono::Utc::now();
+        // Populate a temporary directory first so a partially-written backup
+        // never shows up in the list.
+        let temp_dir = tempfile::Builder::new()
+            .prefix(".tmp-")
+            .tempdir_in(&self.dir)
+            .context(&self.dir)?;
+        let contents_dir = temp_dir.path().join(CONTENTS_DIR_NAME);
+        fs::create_dir(&contents_dir).context(&contents_dir)?;
+        let mut backed_up_dirs = vec![];
+        for &dir in dirs {
+            let source = self.repo_path.join(dir);
+            if !source.is_dir() {
+                continue;
+            }
+            copy_dir_all(&source, &contents_dir.join(dir))?;
+            backed_up_dirs.push(dir.to_owned());
+        }
+        let metadata = BackupMetadata {
+            format_version: BACKUP_FORMAT_VERSION,
+            reason: reason.to_owned(),
+            timestamp: now.to_rfc3339(),
+            dirs: backed_up_dirs,
+        };
+        let metadata_path = temp_dir.path().join(METADATA_FILE_NAME);
+        fs::write(
+            &metadata_path,
+            serde_json::to_vec_pretty(&metadata).unwrap(),
+        )
+        .context(&metadata_path)?;
+
+        let base_name = format!("{}-{reason}", now.format("%Y%m%dT%H%M%S%.3fZ"));
+        let mut name = base_name.clone();
+        for i in 1.. {
+            if !self.dir.join(&name).exists() {
+                break;
+            }
+            name = format!("{base_name}-{i}");
+        }
+        let backup_dir = self.dir.join(&name);
+        fs::rename(temp_dir.into_path(), &backup_dir).context(&backup_dir)?;
+        Ok(RepoBackup { name, metadata })
+    }
+
+    /// Lists backups, oldest first.
+    pub fn list(&self) -> Result<Vec<RepoBackup>, RepoBackupError> {
+        let entries = match fs::read_dir(&self.dir) {
+            Ok(entries) => entries,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(vec![]),
+            Err(err) => {
+                let path = self.dir.clone();
+                return Err(PathError { path, error: err }.into());
+            }
+        };
+        let mut backups = vec![];
+        for entry in entries {
+            let entry = entry.context(&self.dir)?;
+            let Ok(name) = entry.file_name().into_string() else {
+                continue;
+            };
+            if name.starts_with('.') {
+                continue;
+            }
+            backups.push(self.get(&name)?);
+        }
+        Ok(backups
+            .into_iter()
+            .sorted_by(|a, b| a.name.cmp(&b.name))
+            .collect())
+    }
+
+    /// Looks up the backup of the given name.
+    pub fn get(&self, name: &str) -> Result<RepoBackup, RepoBackupError> {
+        if name.is_empty() || name.starts_with('.') || name.contains(['/', '\\']) {
+            return Err(RepoBackupError::NotFound(name.to_owned()));
+        }
+        let metadata_path = self.dir.join(name).join(METADATA_FILE_NAME);
+        let data = match fs::read(&metadata_path) {
+            Ok(data) => data,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => {
+                return Err(RepoBackupError::NotFound(name.to_owned()));
+            }
+            Err(err) => {
+                let path = metadata_path;
+                return Err(PathError { path, error: err }.into());
+            }
+        };
+        let metadata =
+            serde_json::from_slice(&data).map_err(|source| RepoBackupError::InvalidMetadata {
+                path: metadata_path.clone(),
+                source,
+            })?;
+        Ok(RepoBackup {
+            name: name.to_owned(),
+            metadata,
+        })
+    }
+
+    /// Replaces the backed-up directories in the repo with their contents in
+    /// the backup of the given name.
+    pub fn restore(&self, name: &str) -> Result<RepoBackup, RepoBackupError> {
+        let backup = self.get(name)?;
+        if backup.metadata.format_version != BACKUP_FORMAT_VERSION {
+            return Err(RepoBackupError::UnsupportedVersion {
+                name: backup.name,
+                version: backup.metadata.format_version,
+            });
+        }
+        let contents_dir = self.dir.join(name).join(CONTENTS_DIR_NAME);
+        // Copy everything next to the targets before touching them, so that a
+        // failed copy leaves the repo as it was.
+        let staging_dir = tempfile::Builder::new()
+            .prefix(".tmp-restore-")
+            .tempdir_in(&self.dir)
+            .context(&self.dir)?;
+        for dir in &backup.metadata.dirs {
+            copy_dir_all(&contents_dir.join(dir), &staging_dir.path().join(dir))?;
+        }
+        for dir in &backup.metadata.dirs {
+            let target = self.repo_path.join(dir);
+            if target.exists() {
+                fs::remove_dir_all(&target).context(&target)?;
+            }
+            if let Some(parent) = target.parent() {
+                fs::create_dir_all(parent).context(parent)?;
+            }
+            fs::rename(staging_dir.path().join(dir), &target).context(&target)?;
+        }
+        Ok(backup)
+    }
+}
+
+fn copy_dir_all(source: &Path, target: &Path) -> Result<(), PathError> {
+    fs::create_dir_all(target).context(target)?;
+    for entry in source.read_dir().context(source)? {
+        let entry = entry.context(source)?;
+        let path = entry.path();
+        let target_path = target.join(entry.file_name());
+        let file_type = entry.file_type().context(&path)?;
+        if file_type.is_dir() {
+            copy_dir_all(&path, &target_path)?;
+        } else if file_type.is_file() {
+            fs::copy(&path, &target_path).context(&path)?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_create_and_restore() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let repo_path = temp_dir.path();
+        fs::create_dir_all(repo_path.join("index/segments")).unwrap();
+        fs::write(repo_path.join("index/type"), "default").unwrap();
+        fs::write(repo_path.join("index/segments/a"), "old").unwrap();
+
+        let backups = RepoBackups::load(repo_path);
+        assert!(backups.list().unwrap().is_empty());
+        let backup = backups.create("test", &["index", "missing"]).unwrap();
+        assert_eq!(backup.metadata.dirs, ["index"]);
+        assert_eq!(backup.metadata.format_version, BACKUP_FORMAT_VERSION);
+
+        fs::remove_file(repo_path.join("index/segments/a")).unwrap();
+        fs::write(repo_path.join("index/segments/b"), "new").unwrap();
+
+        let listed = backups.list().unwrap();
+        assert_eq!(listed.len(), 1);
+        assert_eq!(listed[0].name, backup.name);
+        backups.restore(&backup.name).unwrap();
+        assert_eq!(
+            fs::read_to_string(repo_path.join("index/segments/a")).unwrap(),
+            "old"
+        );
+        assert!(!repo_path.join("index/segments/b").exists());
+        assert_eq!(
+            fs::read_to_string(repo_path.join("index/type")).unwrap(),
+            "default"
+        );
+
+        // A second backup taken at the same time doesn't collide.
+        let backup2 = backups.create("test", &["index"]).unwrap();
+        assert_ne!(backup2.name, backup.name);
+        assert_eq!(backups.list().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_restore_invalid_name() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let backups = RepoBackups::load(temp_dir.path());
+        assert!(matches!(
+            backups.restore("nonexistent"),
+            Err(RepoBackupError::NotFound(_))
+        ));
+        assert!(matches!(
+            backups.restore("../index"),
+            Err(RepoBackupError::NotFound(_))
+        ));
+    }
+}
@@ -0,0 +1,337 @@
+// Copyright 2024 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Persistent cache of recorded conflict resolutions.
+//!
+//! Similar to Git's "rerere", a resolution is recorded per file conflict. The
+//! key is a content hash of the (simplified) conflicted value, so the same
+//! resolution can be reapplied whenever the same conflict reappears, e.g. when
+//! a stack of commits is rebased again.
+
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::io::Write as _;
+use std::path::Path;
+use std::path::PathBuf;
+
+use itertools::Itertools as _;
+use serde::Deserialize;
+use serde::Serialize;
+use tempfile::NamedTempFile;
+use thiserror::Error;
+
+use crate::backend::BackendError;
+use crate::backend::BackendResult;
+use crate::backend::CommitId;
+use crate::backend::FileId;
+use crate::backend::MergedTreeId;
+use crate::backend::SymlinkId;
+use crate::backend::TreeValue;
+use crate::content_hash::blake2b_hash;
+use crate::file_util::create_or_reuse_dir;
+use crate::file_util::IoResultExt as _;
+use crate::file_util::PathError;
+use crate::merge::Merge;
+use crate::merge::MergedTreeValue;
+use crate::merged_tree::MergedTree;
+use crate::merged_tree::MergedTreeBuilder;
+use crate::object_id::ObjectId as _;
+use crate::repo::MutableRepo;
+use crate::repo::Repo as _;
+use crate::revset::RevsetEvaluationError;
+use crate::revset::RevsetExpression;
+use crate::settings::UserSettings;
+
+/// Error reading or writing the resolution cache.
+#[derive(Debug, Error)]
+pub enum ResolutionCacheError {
+    /// Failed to access the cache directory or a record file.
+    #[error(transparent)]
+    Path(#[from] PathError),
+    /// A record file couldn't be parsed.
+    #[error("Invalid resolution record {path}")]
+    InvalidRecord {
+        /// Path to the record file.
+        path: PathBuf,
+        /// Underlying parse error.
+        #[source]
+        source: Box<dyn std::error::Error + Send + Sync>,
+    },
+    /// Error while contacting the Backend.
+    #[error(transparent)]
+    Backend(#[from] BackendError),
+    /// Error while finding the commits to resolve.
+    #[error(transparent)]
+    RevsetEvaluation(#[from] RevsetEvaluationError),
+}
+
+/// Serialized form of a resolved tree value.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+enum ResolutionRecord {
+    Absent,
+    File { id: String, executable: bool },
+    Symlink { id: String },
+    Submodule { id: String },
+}
+
+impl ResolutionRecord {
+    fn from_value(value: &Option<TreeValue>) -> Option<Self> {
+        match value {
+            None => Some(ResolutionRecord::Absent),
+            Some(TreeValue::File { id, executable }) => Some(ResolutionRecord::File {
+                id: id.hex(),
+                executable: *executable,
+            }),
+            Some(TreeValue::Symlink(id)) => Some(ResolutionRecord::Symlink { id: id.hex() }),
+            Some(TreeValue::GitSubmodule(id)) => Some(ResolutionRecord::Submodule { id: id.hex() }),
+            // Directories and legacy conflicts aren't file-level resolutions.
+            Some(TreeValue::Tree(_) | TreeValue::Conflict(_)) => None,
+        }
+    }
+
+    fn to_value(&self) -> Result<Option<TreeValue>, hex::FromHexError> {
+        let value = match self {
+            ResolutionRecord::Absent => None,
+            ResolutionRecord::File { id, executable } => Some(TreeValue::File {
+                id: FileId::try_from_hex(id)?,
+                executable: *executable,
+            }),
+            ResolutionRecord::Symlink { id } => {
+                Some(TreeValue::Symlink(SymlinkId::try_from_hex(id)?))
+            }
+            ResolutionRecord::Submodule { id } => {
+                Some(TreeValue::GitSubmodule(CommitId::try_from_hex(id)?))
+            }
+        };
+        Ok(value)
+    }
+}
+
+/// On-disk store of recorded conflict resolutions, located under the repo
+/// directory.
+#[derive(Debug)]
+pub struct ResolutionCache {
+    dir: PathBuf,
+}
+
+impl ResolutionCache {
+    /// Name of the cache directory within the repo directory.
+    pub fn dir_name() -> &'static str {
+        "resolution_cache"
+    }
+
+    /// Loads the cache of the repo at `repo_path`. The directory is created
+    /// lazily when the first resolution is recorded.
+    pub fn load(repo_path: &Path) -> Self {
+        ResolutionCache {
+            dir: repo_path.join(Self::dir_name()),
+        }
+    }
+
+    /// Returns the key identifying the given conflict.
+    pub fn conflict_key(conflict: &MergedTreeValue) -> String {
+        let simplified = conflict.clone().simplify();
+        hex::encode(blake2b_hash(&simplified))
+    }
+
+    fn record_path(&self, conflict: &MergedTreeValue) -> PathBuf {
+        self.dir.join(Self::conflict_key(conflict))
+    }
+
+    /// Records `resolution` for `conflict`, replacing any existing record.
+    ///
+    /// Returns `false` if the resolution can't be recorded, e.g. because it is
+    /// itself conflicted or a directory.
+    pub fn record(
+        &self,
+        conflict: &MergedTreeValue,
+        resolution: &MergedTreeValue,
+    ) -> Result<bool, ResolutionCacheError> {
+        let Some(record) = resolution
+            .as_resolved()
+            .and_then(ResolutionRecord::from_value)
+        else {
+            return Ok(false);
+        };
+        create_or_reuse_dir(&self.dir).context(&self.dir)?;
+        let data = serde_json::to_vec(&record).unwrap();
+        let mut temp_file = NamedTempFile::new_in(&self.dir).context(&self.dir)?;
+        temp_file.write_all(&data).context(temp_file.path())?;
+        let path = self.record_path(conflict);
+        // The record may already exist with a different resolution, so it must
+        // be replaced.
+        temp_file
+            .persist(&path)
+            .map_err(|err| err.error)
+            .context(&path)?;
+        Ok(true)
+    }
+
+    /// Removes the recorded resolution for `conflict`. Returns `true` if there
+    /// was one.
+    pub fn forget(&self, conflict: &MergedTreeValue) -> Result<bool, ResolutionCacheError> {
+        let path = self.record_path(conflict);
+        match fs::remove_file(&path) {
+            Ok(()) => Ok(true),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(false),
+            Err(err) => Err(PathError { path, error: err }.into()),
+        }
+    }
+
+    /// Looks up the recorded resolution for `conflict`.
+    pub fn get(
+        &self,
+        conflict: &MergedTreeValue,
+    ) -> Result<Option<MergedTreeValue>, ResolutionCacheError> {
+        let path = self.record_path(conflict);
+        match fs::read(&path) {
+            Ok(data) => Ok(Some(Merge::resolved(parse_record(&path, &data)?))),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(None),
+            Err(err) => Err(PathError { path, error: err }.into()),
+        }
+    }
+
+    /// Reads all recorded resolutions into memory.
+    pub fn read_all(&self) -> Result<RecordedResolutions, ResolutionCacheError> {
+        let entries = match fs::read_dir(&self.dir) {
+            Ok(entries) => entries,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => {
+                return Ok(RecordedResolutions::default());
+            }
+            Err(err) => {
+                let path = self.dir.clone();
+                return Err(PathError { path, error: err }.into());
+            }
+        };
+        let mut resolutions = HashMap::new();
+        for entry in entries {
+            let entry = entry.context(&self.dir)?;
+            let Ok(key) = entry.file_name().into_string() else {
+                continue;
+            };
+            // Skip temporary files left behind by interrupted writes.
+            if key.starts_with('.') {
+                continue;
+            }
+            let path = entry.path();
+            let data = fs::read(&path).context(&path)?;
+            resolutions.insert(key, parse_record(&path, &data)?);
+        }
+        Ok(RecordedResolutions { resolutions })
+    }
+}
+
+fn parse_record(path: &Path, data: &[u8]) -> Result<Option<TreeValue>, ResolutionCacheError> {
+    let to_error =
+        |source: Box<dyn std::error::Error + Send + Sync>| ResolutionCacheError::InvalidRecord {
+            path: path.to_owned(),
+            source,
+        };
+    let record: ResolutionRecord =
+        serde_json::from_slice(data).map_err(|err| to_error(err.into()))?;
+    record.to_value().map_err(|err| to_error(err.into()))
+}
+
+/// In-memory snapshot of the [`ResolutionCache`].
+#[derive(Clone, Debug, Default)]
+pub struct RecordedResolutions {
+    resolutions: HashMap<String, Option<TreeValue>>,
+}
+
+impl RecordedResolutions {
+    /// Returns true if no resolutions have been recorded.
+    pub fn is_empty(&self) -> bool {
+        self.resolutions.is_empty()
+    }
+
+    /// Looks up the recorded resolution for `conflict`.
+    pub fn get(&self, conflict: &MergedTreeValue) -> Option<&Option<TreeValue>> {
+        self.resolutions
+            .get(&ResolutionCache::conflict_key(conflict))
+    }
+
+    /// Replaces conflicts in `tree` with recorded resolutions. Returns `None`
+    /// if none of the conflicts have been resolved before.
+    pub fn resolve_tree(&self, tree: &MergedTree) -> BackendResult<Option<MergedTreeId>> {
+        if self.is_empty() {
+            return Ok(None);
+        }
+        let mut tree_builder = MergedTreeBuilder::new(tree.id());
+        let mut num_resolved = 0;
+        for (path, conflict) in tree.conflicts() {
+            if let Some(resolution) = self.get(&conflict?) {
+                tree_builder.set_or_remove(path, Merge::resolved(resolution.clone()));
+                num_resolved += 1;
+            }
+        }
+        if num_resolved == 0 {
+            return Ok(None);
+        }
+        Ok(Some(tree_builder.write_tree(tree.store())?))
+    }
+
+    /// Reapplies recorded resolutions to the conflicted commits created in the
+    /// current transaction, and rebases their descendants. Returns the number
+    /// of commits that were rewritten to use recorded resolutions.
+    ///
+    /// Pending rewrites must have been rebased beforehand.
+    pub fn reapply_to_new_commits(
+        &self,
+        mut_repo: &mut MutableRepo,
+        settings: &UserSettings,
+    ) -> Result<usize, ResolutionCacheError> {
+        if self.is_empty() {
+            return Ok(0);
+        }
+        let old_heads = mut_repo
+            .base_repo()
+            .view()
+            .heads()
+            .iter()
+            .cloned()
+            .collect_vec();
+        let new_heads = mut_repo.view().heads().iter().cloned().collect_vec();
+        let new_commit_ids: Vec<CommitId> = RevsetExpression::commits(old_heads)
+            .range(&RevsetExpression::commits(new_heads))
+            .evaluate(mut_repo)?
+            .iter()
+            .try_collect()?;
+        let mut num_resolved = 0;
+        mut_repo.transform_descendants(settings, new_commit_ids, |rewriter| {
+            let old_commit = rewriter.old_commit().clone();
+            if rewriter.parents_changed() {
+                let builder = rewriter.rebase(settings)?;
+                let tree = old_commit.store().get_root_tree(builder.tree_id())?;
+                if let Some(new_tree_id) = self.resolve_tree(&tree)? {
+                    builder.set_tree_id(new_tree_id).write()?;
+                    num_resolved += 1;
+                } else {
+                    builder.write()?;
+                }
+            } else if old_commit.has_conflict()? {
+                if let Some(new_tree_id) = self.resolve_tree(&old_commit.tree()?)? {
+                    rewriter
+                        .reparent(settings)
+                        .set_tree_id(new_tree_id)
+                        .write()?;
+                    num_resolved += 1;
+                }
+            }
+            Ok(())
+        })?;
+        Ok(num_resolved)
+    }
+}
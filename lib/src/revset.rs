@@ -17,14 +17,17 @@
 use std::any::Any;
 use std::collections::hash_map;
 use std::collections::HashMap;
+use std::collections::VecDeque;
 use std::convert::Infallible;
 use std::fmt;
 use std::ops::Range;
 use std::rc::Rc;
 use std::sync::Arc;
 
+use futures::future;
 use itertools::Itertools;
 use once_cell::sync::Lazy;
+use pollster::FutureExt as _;
 use thiserror::Error;
 
 use crate::backend::BackendError;
@@ -143,7 +146,9 @@ pub enum RevsetCommitRef {
 }
 
 /// A custom revset filter expression, defined by an extension.
-pub trait RevsetFilterExtension: std::fmt::Debug + Any {
+///
+/// The filter may be evaluated for multiple commits concurrently.
+pub trait RevsetFilterExtension: std::fmt::Debug + Any + Send + Sync {
     fn as_any(&self) -> &dyn Any;
 
     /// Returns true iff this filter matches the specified commit.
@@ -174,7 +179,7 @@ pub enum RevsetFilterPredicate {
     /// Commits with conflicts
     HasConflict,
     /// Custom predicates provided by extensions
-    Extension(Rc<dyn RevsetFilterExtension>),
+    Extension(Arc<dyn RevsetFilterExtension>),
 }
 
 mod private {
@@ -249,11 +254,33 @@ pub enum RevsetExpression<St: ExpressionState> {
     },
     Heads(Rc<Self>),
     Roots(Rc<Self>),
+    // Heads of "candidates" within "domain", where the ancestry is followed
+    // only through commits in "domain"
+    HeadsWithin {
+        candidates: Rc<Self>,
+        domain: Rc<Self>,
+    },
+    // Roots of "candidates" within "domain", where the ancestry is followed
+    // only through commits in "domain"
+    RootsWithin {
+        candidates: Rc<Self>,
+        domain: Rc<Self>,
+    },
     ForkPoint(Rc<Self>),
     Latest {
         candidates: Rc<Self>,
         count: usize,
     },
+    /// First `count` commits in topological order (closest to the root).
+    First {
+        candidates: Rc<Self>,
+        count: usize,
+    },
+    /// Last `count` commits in topological order (closest to the heads).
+    Last {
+        candidates: Rc<Self>,
+        count: usize,
+    },
     Filter(RevsetFilterPredicate),
     /// Marker for subtree that should be intersected as filter.
     AsFilter(Rc<Self>),
@@ -370,6 +397,24 @@ impl<St: ExpressionState> RevsetExpression<St> {
         })
     }
 
+    /// First `count` commits in `self` in topological order, i.e. the ones
+    /// closest to the root.
+    pub fn first(self: &Rc<Self>, count: usize) -> Rc<Self> {
+        Rc::new(Self::First {
+            candidates: self.clone(),
+            count,
+        })
+    }
+
+    /// Last `count` commits in `self` in topological order, i.e. the ones
+    /// closest to the heads.
+    pub fn last(self: &Rc<Self>, count: usize) -> Rc<Self> {
+        Rc::new(Self::Last {
+            candidates: self.clone(),
+            count,
+        })
+    }
+
     /// Commits in `self` that don't have descendants in `self`.
     pub fn heads(self: &Rc<Self>) -> Rc<Self> {
         Rc::new(Self::Heads(self.clone()))
@@ -462,6 +507,24 @@ impl<St: ExpressionState> RevsetExpression<St> {
         })
     }
 
+    /// Commits in `self` and `domain` that have no descendants in the same
+    /// set, where only paths through commits in `domain` are followed.
+    pub fn heads_within(self: &Rc<Self>, domain: &Rc<Self>) -> Rc<Self> {
+        Rc::new(Self::HeadsWithin {
+            candidates: self.clone(),
+            domain: domain.clone(),
+        })
+    }
+
+    /// Commits in `self` and `domain` that have no ancestors in the same set,
+    /// where only paths through commits in `domain` are followed.
+    pub fn roots_within(self: &Rc<Self>, domain: &Rc<Self>) -> Rc<Self> {
+        Rc::new(Self::RootsWithin {
+            candidates: self.clone(),
+            domain: domain.clone(),
+        })
+    }
+
     /// Commits reachable from `heads` but not from `self`.
     pub fn range(self: &Rc<Self>, heads: &Rc<Self>) -> Rc<Self> {
         Rc::new(Self::Range {
@@ -621,11 +684,29 @@ pub enum ResolvedExpression {
     },
     Heads(Box<Self>),
     Roots(Box<Self>),
+    /// Heads of `candidates` within `domain`.
+    HeadsWithin {
+        candidates: Box<Self>,
+        domain: Box<Self>,
+    },
+    /// Roots of `candidates` within `domain`.
+    RootsWithin {
+        candidates: Box<Self>,
+        domain: Box<Self>,
+    },
     ForkPoint(Box<Self>),
     Latest {
         candidates: Box<Self>,
         count: usize,
     },
+    First {
+        candidates: Box<Self>,
+        count: usize,
+    },
+    Last {
+        candidates: Box<Self>,
+        count: usize,
+    },
     Coalesce(Box<Self>, Box<Self>),
     Union(Box<Self>, Box<Self>),
     /// Intersects `candidates` with `predicate` by filtering.
@@ -713,6 +794,18 @@ static BUILTIN_FUNCTION_MAP: Lazy<HashMap<&'static str, RevsetFunction>> = Lazy:
         let candidates = lower_expression(diagnostics, arg, context)?;
         Ok(candidates.roots())
     });
+    map.insert("heads_of", |diagnostics, function, context| {
+        let [candidates_arg, domain_arg] = function.expect_exact_arguments()?;
+        let candidates = lower_expression(diagnostics, candidates_arg, context)?;
+        let domain = lower_expression(diagnostics, domain_arg, context)?;
+        Ok(candidates.heads_within(&domain))
+    });
+    map.insert("roots_of", |diagnostics, function, context| {
+        let [candidates_arg, domain_arg] = function.expect_exact_arguments()?;
+        let candidates = lower_expression(diagnostics, candidates_arg, context)?;
+        let domain = lower_expression(diagnostics, domain_arg, context)?;
+        Ok(candidates.roots_within(&domain))
+    });
     map.insert("visible_heads", |_diagnostics, function, _context| {
         function.expect_no_arguments()?;
         Ok(RevsetExpression::visible_heads())
@@ -812,6 +905,26 @@ static BUILTIN_FUNCTION_MAP: Lazy<HashMap<&'static str, RevsetFunction>> = Lazy:
         };
         Ok(candidates.latest(count))
     });
+    map.insert("first", |diagnostics, function, context| {
+        let ([candidates_arg], [count_opt_arg]) = function.expect_arguments()?;
+        let candidates = lower_expression(diagnostics, candidates_arg, context)?;
+        let count = if let Some(count_arg) = count_opt_arg {
+            expect_literal(diagnostics, "integer", count_arg)?
+        } else {
+            1
+        };
+        Ok(candidates.first(count))
+    });
+    map.insert("last", |diagnostics, function, context| {
+        let ([candidates_arg], [count_opt_arg]) = function.expect_arguments()?;
+        let candidates = lower_expression(diagnostics, candidates_arg, context)?;
+        let count = if let Some(count_arg) = count_opt_arg {
+            expect_literal(diagnostics, "integer", count_arg)?
+        } else {
+            1
+        };
+        Ok(candidates.last(count))
+    });
     map.insert("fork_point", |diagnostics, function, context| {
         let [expression_arg] = function.expect_exact_arguments()?;
         let expression = lower_expression(diagnostics, expression_arg, context)?;
@@ -1264,6 +1377,16 @@ fn try_transform_expression<St: ExpressionState, E>(
             RevsetExpression::Roots(candidates) => {
                 transform_rec(candidates, pre, post)?.map(RevsetExpression::Roots)
             }
+            RevsetExpression::HeadsWithin { candidates, domain } => {
+                transform_rec_pair((candidates, domain), pre, post)?.map(|(candidates, domain)| {
+                    RevsetExpression::HeadsWithin { candidates, domain }
+                })
+            }
+            RevsetExpression::RootsWithin { candidates, domain } => {
+                transform_rec_pair((candidates, domain), pre, post)?.map(|(candidates, domain)| {
+                    RevsetExpression::RootsWithin { candidates, domain }
+                })
+            }
             RevsetExpression::ForkPoint(expression) => {
                 transform_rec(expression, pre, post)?.map(RevsetExpression::ForkPoint)
             }
@@ -1272,6 +1395,16 @@ fn try_transform_expression<St: ExpressionState, E>(
                     candidates,
                     count: *count,
                 }),
+            RevsetExpression::First { candidates, count } => transform_rec(candidates, pre, post)?
+                .map(|candidates| RevsetExpression::First {
+                    candidates,
+                    count: *count,
+                }),
+            RevsetExpression::Last { candidates, count } => transform_rec(candidates, pre, post)?
+                .map(|candidates| RevsetExpression::Last {
+                    candidates,
+                    count: *count,
+                }),
             RevsetExpression::Filter(_) => None,
             RevsetExpression::AsFilter(candidates) => {
                 transform_rec(candidates, pre, post)?.map(RevsetExpression::AsFilter)
@@ -1457,6 +1590,16 @@ where
             let roots = folder.fold_expression(roots)?;
             RevsetExpression::Roots(roots).into()
         }
+        RevsetExpression::HeadsWithin { candidates, domain } => {
+            let candidates = folder.fold_expression(candidates)?;
+            let domain = folder.fold_expression(domain)?;
+            RevsetExpression::HeadsWithin { candidates, domain }.into()
+        }
+        RevsetExpression::RootsWithin { candidates, domain } => {
+            let candidates = folder.fold_expression(candidates)?;
+            let domain = folder.fold_expression(domain)?;
+            RevsetExpression::RootsWithin { candidates, domain }.into()
+        }
         RevsetExpression::ForkPoint(expression) => {
             let expression = folder.fold_expression(expression)?;
             RevsetExpression::ForkPoint(expression).into()
@@ -1466,6 +1609,16 @@ where
             let count = *count;
             RevsetExpression::Latest { candidates, count }.into()
         }
+        RevsetExpression::First { candidates, count } => {
+            let candidates = folder.fold_expression(candidates)?;
+            let count = *count;
+            RevsetExpression::First { candidates, count }.into()
+        }
+        RevsetExpression::Last { candidates, count } => {
+            let candidates = folder.fold_expression(candidates)?;
+            let count = *count;
+            RevsetExpression::Last { candidates, count }.into()
+        }
         RevsetExpression::Filter(predicate) => RevsetExpression::Filter(predicate.clone()).into(),
         RevsetExpression::AsFilter(candidates) => {
             let candidates = folder.fold_expression(candidates)?;
@@ -2353,6 +2506,18 @@ impl VisibilityResolutionContext<'_> {
             RevsetExpression::Roots(candidates) => {
                 ResolvedExpression::Roots(self.resolve(candidates).into())
             }
+            RevsetExpression::HeadsWithin { candidates, domain } => {
+                ResolvedExpression::HeadsWithin {
+                    candidates: self.resolve(candidates).into(),
+                    domain: self.resolve(domain).into(),
+                }
+            }
+            RevsetExpression::RootsWithin { candidates, domain } => {
+                ResolvedExpression::RootsWithin {
+                    candidates: self.resolve(candidates).into(),
+                    domain: self.resolve(domain).into(),
+                }
+            }
             RevsetExpression::ForkPoint(expression) => {
                 ResolvedExpression::ForkPoint(self.resolve(expression).into())
             }
@@ -2360,6 +2525,14 @@ impl VisibilityResolutionContext<'_> {
                 candidates: self.resolve(candidates).into(),
                 count: *count,
             },
+            RevsetExpression::First { candidates, count } => ResolvedExpression::First {
+                candidates: self.resolve(candidates).into(),
+                count: *count,
+            },
+            RevsetExpression::Last { candidates, count } => ResolvedExpression::Last {
+                candidates: self.resolve(candidates).into(),
+                count: *count,
+            },
             RevsetExpression::Filter(_) | RevsetExpression::AsFilter(_) => {
                 // Top-level filter without intersection: e.g. "~author(_)" is represented as
                 // `AsFilter(NotIn(Filter(Author(_))))`.
@@ -2460,8 +2633,12 @@ impl VisibilityResolutionContext<'_> {
             | RevsetExpression::Reachable { .. }
             | RevsetExpression::Heads(_)
             | RevsetExpression::Roots(_)
+            | RevsetExpression::HeadsWithin { .. }
+            | RevsetExpression::RootsWithin { .. }
             | RevsetExpression::ForkPoint(_)
-            | RevsetExpression::Latest { .. } => {
+            | RevsetExpression::Latest { .. }
+            | RevsetExpression::First { .. }
+            | RevsetExpression::Last { .. } => {
                 ResolvedPredicateExpression::Set(self.resolve(expression).into())
             }
             RevsetExpression::Filter(predicate) => {
@@ -2575,6 +2752,72 @@ impl<I: Iterator<Item = Result<CommitId, RevsetEvaluationError>>> Iterator
     }
 }
 
+/// Iterator adaptor that looks up the commits of the upcoming items in
+/// batches, so that backends with slow reads can fetch them concurrently.
+///
+/// Each item is yielded along with its commit, in the original order.
+pub struct CommitPrefetchIterator<I, T, F> {
+    store: Arc<Store>,
+    iter: I,
+    commit_id_fn: F,
+    batch_size: usize,
+    buffer: VecDeque<Result<(T, Commit), RevsetEvaluationError>>,
+}
+
+impl<I, T, F> CommitPrefetchIterator<I, T, F>
+where
+    I: Iterator<Item = Result<T, RevsetEvaluationError>>,
+    F: Fn(&T) -> CommitId,
+{
+    /// Creates an iterator that reads up to `batch_size` commits at a time.
+    /// `commit_id_fn` extracts the commit ID to look up from each item.
+    pub fn new(store: &Arc<Store>, iter: I, batch_size: usize, commit_id_fn: F) -> Self {
+        CommitPrefetchIterator {
+            store: store.clone(),
+            iter,
+            commit_id_fn,
+            batch_size: batch_size.max(1),
+            buffer: VecDeque::new(),
+        }
+    }
+
+    fn fill_buffer(&mut self) {
+        let items = self.iter.by_ref().take(self.batch_size).collect_vec();
+        let store = &self.store;
+        let commit_id_fn = &self.commit_id_fn;
+        let commits = future::join_all(items.iter().map(|item| async move {
+            match item {
+                Ok(item) => Some(store.get_commit_async(&commit_id_fn(item)).await),
+                Err(_) => None,
+            }
+        }))
+        .block_on();
+        let entries = items.into_iter().zip(commits).map(|(item, commit)| {
+            let item = item?;
+            let commit = commit
+                .expect("commit should have been read for non-error item")
+                .map_err(RevsetEvaluationError::StoreError)?;
+            Ok((item, commit))
+        });
+        self.buffer.extend(entries);
+    }
+}
+
+impl<I, T, F> Iterator for CommitPrefetchIterator<I, T, F>
+where
+    I: Iterator<Item = Result<T, RevsetEvaluationError>>,
+    F: Fn(&T) -> CommitId,
+{
+    type Item = Result<(T, Commit), RevsetEvaluationError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.buffer.is_empty() {
+            self.fill_buffer();
+        }
+        self.buffer.pop_front()
+    }
+}
+
 pub struct ReverseRevsetIterator {
     entries: Vec<CommitId>,
 }
@@ -3490,6 +3733,20 @@ mod tests {
             count: 2,
         }
         "###);
+        insta::assert_debug_snapshot!(
+            optimize(parse("first(bookmarks() & all())").unwrap()), @r#"
+        First {
+            candidates: CommitRef(Bookmarks(Substring(""))),
+            count: 1,
+        }
+        "#);
+        insta::assert_debug_snapshot!(
+            optimize(parse("last(bookmarks() & all(), 2)").unwrap()), @r#"
+        Last {
+            candidates: CommitRef(Bookmarks(Substring(""))),
+            count: 2,
+        }
+        "#);
 
         insta::assert_debug_snapshot!(
             optimize(parse("present(foo ~ bar)").unwrap()), @r###"
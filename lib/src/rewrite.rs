@@ -16,6 +16,7 @@
 
 use std::collections::HashMap;
 use std::collections::HashSet;
+use std::io::Read as _;
 use std::sync::Arc;
 
 use futures::StreamExt;
@@ -29,13 +30,17 @@ use crate::backend::BackendError;
 use crate::backend::BackendResult;
 use crate::backend::CommitId;
 use crate::backend::MergedTreeId;
+use crate::backend::TreeValue;
 use crate::commit::Commit;
 use crate::commit::CommitIteratorExt;
 use crate::commit_builder::CommitBuilder;
 use crate::dag_walk;
 use crate::index::Index;
+use crate::matchers::EverythingMatcher;
 use crate::matchers::Matcher;
 use crate::matchers::Visit;
+use crate::merge::Merge;
+use crate::merge::MergedTreeValue;
 use crate::merged_tree::MergedTree;
 use crate::merged_tree::MergedTreeBuilder;
 use crate::merged_tree::TreeDiffEntry;
@@ -118,6 +123,90 @@ pub fn restore_tree(
     }
 }
 
+/// Applies the changes from `source_base` to `source` onto `destination`.
+///
+/// The source trees may belong to a different store, e.g. of another repo. The
+/// contents of the changed paths are copied into the store of `destination`
+/// before the changes are merged in, so the result may contain conflicts.
+pub fn apply_tree_diff_across_stores(
+    source_base: &MergedTree,
+    source: &MergedTree,
+    destination: &MergedTree,
+) -> BackendResult<MergedTree> {
+    let store = destination.store();
+    let mut base_builder = MergedTreeBuilder::new(destination.id());
+    let mut side_builder = MergedTreeBuilder::new(destination.id());
+    async {
+        let mut diff_stream = source_base.diff_stream(source, &EverythingMatcher);
+        while let Some(TreeDiffEntry { path, values }) = diff_stream.next().await {
+            let (before, after) = values?;
+            let before = copy_merged_value(source.store(), store, &path, &before).await?;
+            let after = copy_merged_value(source.store(), store, &path, &after).await?;
+            base_builder.set_or_remove(path.clone(), before);
+            side_builder.set_or_remove(path, after);
+        }
+        Ok::<(), BackendError>(())
+    }
+    .block_on()?;
+    let base_tree = store.get_root_tree(&base_builder.write_tree(store)?)?;
+    let side_tree = store.get_root_tree(&side_builder.write_tree(store)?)?;
+    destination.merge(&base_tree, &side_tree)
+}
+
+/// Copies the file and symlink contents of `value` from `source_store` into
+/// `destination_store`.
+async fn copy_merged_value(
+    source_store: &Store,
+    destination_store: &Store,
+    path: &RepoPath,
+    value: &MergedTreeValue,
+) -> BackendResult<MergedTreeValue> {
+    let mut new_values = Vec::with_capacity(value.as_slice().len());
+    for term in value.iter() {
+        let new_term = match term {
+            None => None,
+            Some(TreeValue::File { id, executable }) => {
+                let mut contents = vec![];
+                source_store
+                    .read_file_async(path, id)
+                    .await?
+                    .read_to_end(&mut contents)
+                    .map_err(|err| BackendError::ReadFile {
+                        path: path.to_owned(),
+                        id: id.clone(),
+                        source: err.into(),
+                    })?;
+                let new_id = destination_store
+                    .write_file(path, &mut contents.as_slice())
+                    .await?;
+                Some(TreeValue::File {
+                    id: new_id,
+                    executable: *executable,
+                })
+            }
+            Some(TreeValue::Symlink(id)) => {
+                let target = source_store.read_symlink_async(path, id).await?;
+                let new_id = destination_store.write_symlink(path, &target).await?;
+                Some(TreeValue::Symlink(new_id))
+            }
+            // Submodules are recorded by commit id, which doesn't depend on
+            // the store.
+            Some(TreeValue::GitSubmodule(id)) => Some(TreeValue::GitSubmodule(id.clone())),
+            Some(TreeValue::Tree(_) | TreeValue::Conflict(_)) => {
+                return Err(BackendError::Other(
+                    format!(
+                        "Unexpected tree value at {} while copying changes",
+                        path.as_internal_file_string()
+                    )
+                    .into(),
+                ));
+            }
+        };
+        new_values.push(new_term);
+    }
+    Ok(Merge::from_vec(new_values))
+}
+
 pub fn rebase_commit(
     settings: &UserSettings,
     mut_repo: &mut MutableRepo,
@@ -319,12 +408,27 @@ pub fn rebase_commit_with_options(
         rewriter.simplify_ancestor_merge();
     }
 
+    let existing_rewrites = if options.reuse_existing_rewrites {
+        find_existing_rewrites(
+            rewriter.mut_repo,
+            &rewriter.old_commit,
+            &rewriter.new_parents,
+        )?
+    } else {
+        vec![]
+    };
     let single_parent = match &rewriter.new_parents[..] {
         [parent_id] => Some(parent_id.clone()),
         _ => None,
     };
     let new_parents_len = rewriter.new_parents.len();
     if let Some(builder) = rewriter.rebase_with_empty_behavior(settings, options.empty)? {
+        if let Some(existing) = existing_rewrites
+            .into_iter()
+            .find(|commit| is_same_rewrite(&builder, commit))
+        {
+            return Ok(RebasedCommit::Rewritten(builder.reuse(existing)));
+        }
         let new_commit = builder.write()?;
         Ok(RebasedCommit::Rewritten(new_commit))
     } else {
@@ -335,6 +439,36 @@ pub fn rebase_commit_with_options(
     }
 }
 
+/// Finds visible commits other than `old_commit` which have the same change ID
+/// and the given parents. These may be rewrites of `old_commit` created by a
+/// concurrent operation.
+fn find_existing_rewrites(
+    repo: &dyn Repo,
+    old_commit: &Commit,
+    new_parent_ids: &[CommitId],
+) -> BackendResult<Vec<Commit>> {
+    let Some(commit_ids) = repo.resolve_change_id(old_commit.change_id()) else {
+        return Ok(vec![]);
+    };
+    commit_ids
+        .iter()
+        .filter(|id| *id != old_commit.id())
+        .map(|id| repo.store().get_commit(id))
+        .filter_ok(|commit| commit.parent_ids() == new_parent_ids)
+        .try_collect()
+}
+
+/// Returns true if the `existing` commit introduces the same patch as the
+/// commit to be written by `builder`, and has the same metadata.
+fn is_same_rewrite(builder: &CommitBuilder, existing: &Commit) -> bool {
+    // The patch IDs are the same if the parents and the trees are.
+    builder.parents() == existing.parent_ids()
+        && builder.tree_id() == existing.tree_id()
+        && builder.change_id() == existing.change_id()
+        && builder.description() == existing.description()
+        && builder.author() == existing.author()
+}
+
 /// Moves changes from `sources` to the `destination` parent, returns new tree.
 pub fn rebase_to_dest_parent(
     repo: &dyn Repo,
@@ -383,6 +517,9 @@ pub struct RebaseOptions {
     /// If a merge commit would end up with one parent being an ancestor of the
     /// other, then filter out the ancestor.
     pub simplify_ancestor_merge: bool,
+    /// If an identical rewrite of a commit already exists on the new parents,
+    /// reuse it instead of creating a divergent commit.
+    pub reuse_existing_rewrites: bool,
 }
 
 #[derive(Default)]
@@ -742,6 +879,7 @@ pub fn move_commits(
     let rebase_descendant_options = &RebaseOptions {
         empty: EmptyBehaviour::Keep,
         simplify_ancestor_merge: options.simplify_ancestor_merge,
+        reuse_existing_rewrites: options.reuse_existing_rewrites,
     };
 
     // Rebase each commit onto its new parents in the reverse topological order
@@ -785,8 +923,8 @@ pub fn move_commits(
 
 #[derive(Default)]
 pub struct DuplicateCommitsStats {
-    /// Map of original commit ID to newly duplicated commit.
-    pub duplicated_commits: IndexMap<CommitId, Commit>,
+    /// Map of original commit ID to newly duplicated commit ID.
+    pub duplicated_commits: IndexMap<CommitId, CommitId>,
     /// The number of descendant commits which were rebased onto the duplicated
     /// commits.
     pub num_rebased: u32,
@@ -815,7 +953,7 @@ pub fn duplicate_commits(
         return Ok(DuplicateCommitsStats::default());
     }
 
-    let mut duplicated_old_to_new: IndexMap<CommitId, Commit> = IndexMap::new();
+    let mut duplicated_old_to_new: IndexMap<CommitId, CommitId> = IndexMap::new();
     let mut num_rebased = 0;
 
     let target_commit_ids: HashSet<_> = target_commits.iter().cloned().collect();
@@ -871,19 +1009,14 @@ pub fn duplicate_commits(
                 .unwrap()
                 .iter()
                 // Replace parent IDs with their new IDs if they were duplicated.
-                .map(|id| {
-                    duplicated_old_to_new
-                        .get(id)
-                        .map_or(id, |commit| commit.id())
-                        .clone()
-                })
+                .map(|id| duplicated_old_to_new.get(id).unwrap_or(id).clone())
                 .collect()
         };
         let new_commit = CommitRewriter::new(mut_repo, original_commit, new_parent_ids)
             .rebase(settings)?
             .generate_new_change_id()
             .write()?;
-        duplicated_old_to_new.insert(original_commit_id.clone(), new_commit);
+        duplicated_old_to_new.insert(original_commit_id.clone(), new_commit.id().clone());
     }
 
     // Replace the original commit IDs in `target_head_ids` with the duplicated
@@ -893,7 +1026,8 @@ pub fn duplicate_commits(
         .map(|commit_id| {
             duplicated_old_to_new
                 .get(&commit_id)
-                .map_or(commit_id, |commit| commit.id().clone())
+                .cloned()
+                .unwrap_or(commit_id)
         })
         .collect_vec();
 
@@ -943,7 +1077,7 @@ pub fn duplicate_commits_onto_parents(
         return Ok(DuplicateCommitsStats::default());
     }
 
-    let mut duplicated_old_to_new: IndexMap<CommitId, Commit> = IndexMap::new();
+    let mut duplicated_old_to_new: IndexMap<CommitId, CommitId> = IndexMap::new();
 
     // Topological order ensures that any parents of the original commit are
     // either not in `target_commits` or were already duplicated.
@@ -952,19 +1086,14 @@ pub fn duplicate_commits_onto_parents(
         let new_parent_ids = original_commit
             .parent_ids()
             .iter()
-            .map(|id| {
-                duplicated_old_to_new
-                    .get(id)
-                    .map_or(id, |commit| commit.id())
-                    .clone()
-            })
+            .map(|id| duplicated_old_to_new.get(id).unwrap_or(id).clone())
             .collect();
         let new_commit = mut_repo
             .rewrite_commit(settings, &original_commit)
             .generate_new_change_id()
             .set_parents(new_parent_ids)
             .write()?;
-        duplicated_old_to_new.insert(original_commit_id.clone(), new_commit);
+        duplicated_old_to_new.insert(original_commit_id.clone(), new_commit.id().clone());
     }
 
     Ok(DuplicateCommitsStats {
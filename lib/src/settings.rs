@@ -22,6 +22,7 @@ use std::sync::Mutex;
 use chrono::DateTime;
 use rand::prelude::*;
 use rand_chacha::ChaCha20Rng;
+use serde::de::IntoDeserializer as _;
 use serde::Deserialize;
 
 use crate::backend::ChangeId;
@@ -37,6 +38,7 @@ use crate::config::ToConfigNamePath;
 use crate::fmt_util::binary_prefix;
 use crate::fsmonitor::FsmonitorSettings;
 use crate::signing::SignBehavior;
+use crate::str_util::StringPattern;
 
 #[derive(Debug, Clone)]
 pub struct UserSettings {
@@ -54,7 +56,17 @@ pub struct RepoSettings {
 #[derive(Debug, Clone)]
 pub struct GitSettings {
     pub auto_local_bookmark: bool,
+    /// Remote bookmarks to track automatically when they are first imported.
+    /// `None` means `auto_local_bookmark` decides.
+    pub auto_track_bookmarks: Option<Vec<StringPattern>>,
     pub abandon_unreachable_commits: bool,
+    /// What to do with tracking local bookmarks when their remote bookmarks
+    /// are deleted.
+    pub fetch_prune: FetchPruneMode,
+    /// Git branches to import as bookmarks. `None` means all branches.
+    pub import_bookmarks: Option<Vec<StringPattern>>,
+    /// Bookmarks to export as Git branches. `None` means all bookmarks.
+    pub export_bookmarks: Option<Vec<StringPattern>>,
 }
 
 impl GitSettings {
@@ -68,18 +80,122 @@ impl GitSettings {
             .get_bool("git.abandon-unreachable-commits")
             .optional()?
             .unwrap_or(true);
+        let auto_track_bookmarks = get_string_patterns(settings, "git.auto-track-bookmarks")?;
         Ok(GitSettings {
             auto_local_bookmark,
+            auto_track_bookmarks,
             abandon_unreachable_commits,
+            fetch_prune: settings
+                .get("git.fetch-prune")
+                .optional()?
+                .unwrap_or_default(),
+            import_bookmarks: get_string_patterns(settings, "git.import-bookmarks")?,
+            export_bookmarks: get_string_patterns(settings, "git.export-bookmarks")?,
+        })
+    }
+
+    /// Returns true if the new remote bookmark `name` should be tracked when
+    /// it's imported.
+    pub fn auto_tracks_bookmark(&self, name: &str) -> bool {
+        self.auto_track_bookmarks
+            .as_ref()
+            .map_or(self.auto_local_bookmark, |patterns| {
+                patterns.iter().any(|pattern| pattern.matches(name))
+            })
+    }
+
+    /// Returns true if the Git branch `name` should be imported as a bookmark.
+    pub fn imports_bookmark(&self, name: &str) -> bool {
+        matches_any(self.import_bookmarks.as_deref(), name)
+    }
+
+    /// Returns true if the bookmark `name` should be exported as a Git branch.
+    pub fn exports_bookmark(&self, name: &str) -> bool {
+        matches_any(self.export_bookmarks.as_deref(), name)
+    }
+}
+
+/// Settings specific to a single Git remote, configured in the
+/// `remotes.<name>` table.
+#[derive(Debug, Clone, Default)]
+pub struct RemoteSettings {
+    /// Branches to fetch when none are specified on the command line. `None`
+    /// means all branches.
+    pub fetch_bookmarks: Option<Vec<StringPattern>>,
+    /// Prefix of the bookmarks created by `jj git push --change`, overriding
+    /// `git.push-bookmark-prefix`.
+    pub push_bookmark_prefix: Option<String>,
+    /// URL template printed after pushing a new bookmark to this remote, with
+    /// `{bookmark}` replaced by the bookmark name. An empty string disables
+    /// the URL, including the one derived for known forges.
+    pub create_pr_url: Option<String>,
+}
+
+impl RemoteSettings {
+    pub fn from_settings(
+        settings: &UserSettings,
+        remote_name: &str,
+    ) -> Result<Self, ConfigGetError> {
+        Ok(RemoteSettings {
+            fetch_bookmarks: get_string_patterns(
+                settings,
+                ["remotes", remote_name, "fetch-bookmarks"],
+            )?,
+            push_bookmark_prefix: settings
+                .get_string(["remotes", remote_name, "push-bookmark-prefix"])
+                .optional()?,
+            create_pr_url: settings
+                .get_string(["remotes", remote_name, "create-pr-url"])
+                .optional()?,
         })
     }
 }
 
+/// What to do with a tracking local bookmark when the remote bookmark is
+/// deleted.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, serde::Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum FetchPruneMode {
+    /// Delete the local bookmark unless it was moved locally.
+    #[default]
+    Delete,
+    /// Keep the local bookmark as is.
+    Keep,
+    /// Keep the local bookmark, but mark it as conflicted with the deletion.
+    MarkDeleted,
+}
+
+fn get_string_patterns(
+    settings: &UserSettings,
+    name: impl ToConfigNamePath,
+) -> Result<Option<Vec<StringPattern>>, ConfigGetError> {
+    settings
+        .get_value_with(name, |value| {
+            let patterns = Vec::<String>::deserialize(value.into_deserializer())?;
+            patterns
+                .iter()
+                .map(|pattern| StringPattern::parse(pattern))
+                .collect::<Result<_, _>>()
+                .map_err(Box::<dyn std::error::Error + Send + Sync>::from)
+        })
+        .optional()
+}
+
+fn matches_any(patterns: Option<&[StringPattern]>, name: &str) -> bool {
+    patterns.map_or(true, |patterns| {
+        patterns.iter().any(|pattern| pattern.matches(name))
+    })
+}
+
 impl Default for GitSettings {
     fn default() -> Self {
         GitSettings {
             auto_local_bookmark: false,
+            auto_track_bookmarks: None,
             abandon_unreachable_commits: true,
+            fetch_prune: FetchPruneMode::default(),
+            import_bookmarks: None,
+            export_bookmarks: None,
         }
     }
 }
@@ -179,6 +295,15 @@ impl UserSettings {
         self.get_string("user.email").unwrap_or_default()
     }
 
+    /// Whether every object written to the store should be read back and
+    /// checked against what was written.
+    pub fn verify_writes(&self) -> Result<bool, ConfigGetError> {
+        Ok(self
+            .get_bool("core.verify-writes")
+            .optional()?
+            .unwrap_or(false))
+    }
+
     pub fn fsmonitor_settings(&self) -> Result<FsmonitorSettings, ConfigGetError> {
         FsmonitorSettings::from_settings(self)
     }
@@ -225,6 +350,10 @@ impl UserSettings {
         GitSettings::from_settings(self)
     }
 
+    pub fn remote_settings(&self, remote_name: &str) -> Result<RemoteSettings, ConfigGetError> {
+        RemoteSettings::from_settings(self, remote_name)
+    }
+
     // separate from sign_settings as those two are needed in pretty different
     // places
     pub fn signing_backend(&self) -> Result<Option<String>, ConfigGetError> {
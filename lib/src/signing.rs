@@ -24,6 +24,7 @@ use thiserror::Error;
 use crate::backend::CommitId;
 use crate::config::ConfigGetError;
 use crate::gpg_signing::GpgBackend;
+use crate::gpg_signing::GpgsmBackend;
 use crate::settings::UserSettings;
 use crate::ssh_signing::SshBackend;
 
@@ -165,7 +166,9 @@ impl Signer {
         let mut backends: Vec<Box<dyn SigningBackend>> = vec![
             Box::new(GpgBackend::from_settings(settings).map_err(SignInitError::BackendConfig)?),
             Box::new(SshBackend::from_settings(settings).map_err(SignInitError::BackendConfig)?),
-            // Box::new(X509Backend::from_settings(settings).map_err(..)?),
+            Box::new(GpgsmBackend::from_settings(settings).map_err(SignInitError::BackendConfig)?),
+            #[cfg(feature = "testing")]
+            Box::new(crate::test_signing_backend::TestSigningBackend),
         ];
 
         let main_backend = settings
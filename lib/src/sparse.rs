@@ -0,0 +1,241 @@
+// Copyright 2026 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Patterns selecting which paths are present in a sparse working copy.
+
+use std::fmt;
+
+use crate::fileset::FilePattern;
+use crate::fileset::FilePatternParseError;
+use crate::fileset::FilesetExpression;
+use crate::matchers::Matcher;
+use crate::repo_path::RepoPath;
+use crate::repo_path::RepoPathBuf;
+
+/// Paths matched by a sparse pattern.
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum SparsePatternKind {
+    /// Matches the file or all files under the directory.
+    Prefix(RepoPathBuf),
+    /// Matches files with the workspace-relative glob pattern. The pattern
+    /// uses `/` as separator.
+    Glob(String),
+}
+
+/// Pattern that includes or excludes paths from a sparse working copy.
+///
+/// The text form is a workspace-relative path, optionally prefixed by `glob:`
+/// to match a glob pattern instead, and by `!` to exclude the matching paths.
+/// For example, `lib` and `!glob:lib/testdata/**` together include everything
+/// under `lib/` except for the test data.
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct SparsePattern {
+    // Sorts included patterns first.
+    exclude: bool,
+    kind: SparsePatternKind,
+}
+
+impl SparsePattern {
+    /// Pattern that includes the file or all files under the directory.
+    pub fn prefix(path: RepoPathBuf) -> Self {
+        SparsePattern {
+            exclude: false,
+            kind: SparsePatternKind::Prefix(path),
+        }
+    }
+
+    /// Pattern that includes all files in the workspace.
+    pub fn root() -> Self {
+        Self::prefix(RepoPathBuf::root())
+    }
+
+    /// Pattern that includes files matching the workspace-relative glob.
+    pub fn glob(input: &str) -> Result<Self, FilePatternParseError> {
+        // Validate the pattern and normalize the separators.
+        let glob = match FilePattern::root_file_glob(input)? {
+            FilePattern::FilePath(path) | FilePattern::PrefixPath(path) => {
+                path.as_internal_file_string().to_owned()
+            }
+            FilePattern::FileGlob { dir, pattern } => join_glob(&dir, pattern.as_str()),
+        };
+        Ok(SparsePattern {
+            exclude: false,
+            kind: SparsePatternKind::Glob(glob),
+        })
+    }
+
+    /// Parses the text form of a sparse pattern.
+    pub fn parse(input: &str) -> Result<Self, FilePatternParseError> {
+        let (exclude, input) = match input.strip_prefix('!') {
+            Some(rest) => (true, rest),
+            None => (false, input),
+        };
+        let pattern = if let Some(glob) = input.strip_prefix("glob:") {
+            Self::glob(glob)?
+        } else {
+            Self::prefix(RepoPathBuf::from_relative_path(input)?)
+        };
+        Ok(if exclude { pattern.excluded() } else { pattern })
+    }
+
+    /// Returns this pattern with the matching paths excluded instead of
+    /// included.
+    pub fn excluded(self) -> Self {
+        SparsePattern {
+            exclude: true,
+            ..self
+        }
+    }
+
+    /// Returns true if the matching paths are excluded.
+    pub fn is_exclude(&self) -> bool {
+        self.exclude
+    }
+
+    /// Returns the paths matched by this pattern.
+    pub fn kind(&self) -> &SparsePatternKind {
+        &self.kind
+    }
+
+    /// Returns the path if this pattern includes a path prefix.
+    pub fn as_included_prefix(&self) -> Option<&RepoPath> {
+        match &self.kind {
+            SparsePatternKind::Prefix(path) if !self.exclude => Some(path),
+            _ => None,
+        }
+    }
+
+    fn to_file_pattern(&self) -> FilePattern {
+        match &self.kind {
+            SparsePatternKind::Prefix(path) => FilePattern::PrefixPath(path.clone()),
+            SparsePatternKind::Glob(glob) => {
+                FilePattern::root_file_glob(glob).expect("glob should have been validated")
+            }
+        }
+    }
+}
+
+impl fmt::Display for SparsePattern {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.exclude {
+            write!(f, "!")?;
+        }
+        match &self.kind {
+            SparsePatternKind::Prefix(path) if path.is_root() => write!(f, "."),
+            SparsePatternKind::Prefix(path) => write!(f, "{}", path.as_internal_file_string()),
+            SparsePatternKind::Glob(glob) => write!(f, "glob:{glob}"),
+        }
+    }
+}
+
+fn join_glob(dir: &RepoPath, pattern: &str) -> String {
+    if dir.is_root() {
+        pattern.to_owned()
+    } else {
+        format!("{}/{pattern}", dir.as_internal_file_string())
+    }
+}
+
+/// Builds a matcher for the files present in a working copy with the given
+/// sparse patterns. A file is present if it matches any included pattern and
+/// no excluded pattern.
+pub fn sparse_matcher(patterns: &[SparsePattern]) -> Box<dyn Matcher> {
+    let (excluded, included): (Vec<_>, Vec<_>) = patterns
+        .iter()
+        .map(|pattern| (pattern.exclude, pattern.to_file_pattern()))
+        .partition(|(exclude, _)| *exclude);
+    let to_expression = |patterns: Vec<(bool, FilePattern)>| {
+        let expressions = patterns
+            .into_iter()
+            .map(|(_, pattern)| FilesetExpression::pattern(pattern))
+            .collect();
+        FilesetExpression::union_all(expressions)
+    };
+    let expression = if excluded.is_empty() {
+        to_expression(included)
+    } else {
+        to_expression(included).difference(to_expression(excluded))
+    };
+    expression.to_matcher()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn repo_path(value: &str) -> &RepoPath {
+        RepoPath::from_internal_string(value)
+    }
+
+    #[test]
+    fn test_parse_sparse_pattern() {
+        let parse = |input| SparsePattern::parse(input).unwrap();
+        assert_eq!(parse("."), SparsePattern::root());
+        assert_eq!(
+            parse("lib/"),
+            SparsePattern::prefix(repo_path("lib").to_owned())
+        );
+        assert_eq!(
+            parse("!lib/testdata"),
+            SparsePattern::prefix(repo_path("lib/testdata").to_owned()).excluded()
+        );
+        assert_eq!(
+            parse("glob:lib/*.rs").kind(),
+            &SparsePatternKind::Glob("lib/*.rs".to_owned())
+        );
+        assert!(parse("!glob:*.md").is_exclude());
+        // A glob without meta characters matches the exact path
+        assert_eq!(
+            parse("glob:lib/foo").kind(),
+            &SparsePatternKind::Glob("lib/foo".to_owned())
+        );
+
+        assert!(SparsePattern::parse("../foo").is_err());
+        assert!(SparsePattern::parse("glob:[").is_err());
+    }
+
+    #[test]
+    fn test_sparse_pattern_display_roundtrip() {
+        for input in [
+            ".",
+            "lib",
+            "!lib/testdata",
+            "glob:*.md",
+            "!glob:lib/**/*.bin",
+        ] {
+            let pattern = SparsePattern::parse(input).unwrap();
+            assert_eq!(pattern.to_string(), input);
+        }
+    }
+
+    #[test]
+    fn test_sparse_matcher() {
+        let patterns = ["lib", "glob:*.md", "!glob:lib/testdata/**", "!lib/big"]
+            .map(|input| SparsePattern::parse(input).unwrap());
+        let matcher = sparse_matcher(&patterns);
+        assert!(matcher.matches(repo_path("lib/src/lib.rs")));
+        assert!(matcher.matches(repo_path("README.md")));
+        assert!(!matcher.matches(repo_path("docs/README.md")));
+        assert!(!matcher.matches(repo_path("lib/testdata/file")));
+        assert!(!matcher.matches(repo_path("lib/testdata/dir/file")));
+        assert!(!matcher.matches(repo_path("lib/big")));
+        assert!(!matcher.matches(repo_path("lib/big/file")));
+        assert!(!matcher.matches(repo_path("src/main.rs")));
+
+        // Exclusions alone match nothing
+        let patterns = [SparsePattern::root().excluded()];
+        assert!(!sparse_matcher(&patterns).matches(repo_path("file")));
+        assert!(!sparse_matcher(&[]).matches(repo_path("file")));
+    }
+}
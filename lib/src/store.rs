@@ -17,6 +17,7 @@
 use std::any::Any;
 use std::fmt::Debug;
 use std::fmt::Formatter;
+use std::future::Future;
 use std::io::Read;
 use std::sync::Arc;
 use std::sync::Mutex;
@@ -28,13 +29,17 @@ use pollster::FutureExt;
 
 use crate::backend;
 use crate::backend::Backend;
+use crate::backend::BackendError;
 use crate::backend::BackendResult;
 use crate::backend::ChangeId;
 use crate::backend::CommitId;
 use crate::backend::ConflictId;
 use crate::backend::CopyRecord;
+use crate::backend::EnumerableBackend;
+use crate::backend::FetchableObjectId;
 use crate::backend::FileId;
 use crate::backend::MergedTreeId;
+use crate::backend::Signature;
 use crate::backend::SigningFn;
 use crate::backend::SymlinkId;
 use crate::backend::TreeId;
@@ -43,6 +48,7 @@ use crate::index::Index;
 use crate::merge::Merge;
 use crate::merge::MergedTreeValue;
 use crate::merged_tree::MergedTree;
+use crate::object_id::ObjectId;
 use crate::repo_path::RepoPath;
 use crate::repo_path::RepoPathBuf;
 use crate::signing::Signer;
@@ -59,6 +65,9 @@ const TREE_CACHE_CAPACITY: usize = 1000;
 pub struct Store {
     backend: Box<dyn Backend>,
     signer: Signer,
+    /// Whether to read back every written object and check that it matches
+    /// what was written.
+    verify_writes: bool,
     commit_cache: Mutex<CLruCache<CommitId, Arc<backend::Commit>>>,
     tree_cache: Mutex<CLruCache<(RepoPathBuf, TreeId), Arc<backend::Tree>>>,
 }
@@ -72,10 +81,11 @@ impl Debug for Store {
 }
 
 impl Store {
-    pub fn new(backend: Box<dyn Backend>, signer: Signer) -> Arc<Self> {
+    pub fn new(backend: Box<dyn Backend>, signer: Signer, verify_writes: bool) -> Arc<Self> {
         Arc::new(Store {
             backend,
             signer,
+            verify_writes,
             commit_cache: Mutex::new(CLruCache::new(COMMIT_CACHE_CAPACITY.try_into().unwrap())),
             tree_cache: Mutex::new(CLruCache::new(TREE_CACHE_CAPACITY.try_into().unwrap())),
         })
@@ -85,6 +95,17 @@ impl Store {
         self.backend.as_any()
     }
 
+    /// A unique name that identifies the backend.
+    pub fn backend_name(&self) -> &str {
+        self.backend.name()
+    }
+
+    /// Returns the interface to list the stored objects if the backend
+    /// supports it.
+    pub fn enumerable_backend(&self) -> Option<&dyn EnumerableBackend> {
+        self.backend.enumerable()
+    }
+
     pub fn signer(&self) -> &Signer {
         &self.signer
     }
@@ -161,6 +182,11 @@ impl Store {
         assert!(!commit.parents.is_empty());
 
         let (commit_id, commit) = self.backend.write_commit(commit, sign_with).await?;
+        if self.verify_writes
+            && !is_same_commit(&commit, &self.backend.read_commit(&commit_id).await?)
+        {
+            return Err(verification_error("commit", commit_id.hex()));
+        }
         let data = Arc::new(commit);
         {
             let mut locked_cache = self.commit_cache.lock().unwrap();
@@ -195,7 +221,11 @@ impl Store {
                 return Ok(data);
             }
         }
-        let data = self.backend.read_tree(dir, id).await?;
+        let data = self
+            .read_or_fetch(FetchableObjectId::Tree(id.clone()), || {
+                self.backend.read_tree(dir, id)
+            })
+            .await?;
         let data = Arc::new(data);
         let mut locked_cache = self.tree_cache.lock().unwrap();
         locked_cache.put(key, data.clone());
@@ -221,6 +251,9 @@ impl Store {
         tree: backend::Tree,
     ) -> BackendResult<Tree> {
         let tree_id = self.backend.write_tree(path, &tree).await?;
+        if self.verify_writes && self.backend.read_tree(path, &tree_id).await? != tree {
+            return Err(verification_error("tree", tree_id.hex()));
+        }
         let data = Arc::new(tree);
         {
             let mut locked_cache = self.tree_cache.lock().unwrap();
@@ -239,7 +272,10 @@ impl Store {
         path: &RepoPath,
         id: &FileId,
     ) -> BackendResult<Box<dyn Read>> {
-        self.backend.read_file(path, id).await
+        self.read_or_fetch(FetchableObjectId::File(id.clone()), || {
+            self.backend.read_file(path, id)
+        })
+        .await
     }
 
     pub async fn write_file(
@@ -247,7 +283,29 @@ impl Store {
         path: &RepoPath,
         contents: &mut (dyn Read + Send),
     ) -> BackendResult<FileId> {
-        self.backend.write_file(path, contents).await
+        if !self.verify_writes {
+            return self.backend.write_file(path, contents).await;
+        }
+        let read_error = |err: std::io::Error| BackendError::WriteObject {
+            object_type: "file",
+            source: err.into(),
+        };
+        let mut written = vec![];
+        contents.read_to_end(&mut written).map_err(read_error)?;
+        let id = self
+            .backend
+            .write_file(path, &mut written.as_slice())
+            .await?;
+        let mut stored = vec![];
+        self.backend
+            .read_file(path, &id)
+            .await?
+            .read_to_end(&mut stored)
+            .map_err(read_error)?;
+        if stored != written {
+            return Err(verification_error("file", id.hex()));
+        }
+        Ok(id)
     }
 
     pub fn read_symlink(&self, path: &RepoPath, id: &SymlinkId) -> BackendResult<String> {
@@ -259,11 +317,47 @@ impl Store {
         path: &RepoPath,
         id: &SymlinkId,
     ) -> BackendResult<String> {
-        self.backend.read_symlink(path, id).await
+        self.read_or_fetch(FetchableObjectId::Symlink(id.clone()), || {
+            self.backend.read_symlink(path, id)
+        })
+        .await
     }
 
     pub async fn write_symlink(&self, path: &RepoPath, contents: &str) -> BackendResult<SymlinkId> {
-        self.backend.write_symlink(path, contents).await
+        let id = self.backend.write_symlink(path, contents).await?;
+        if self.verify_writes && self.backend.read_symlink(path, &id).await? != contents {
+            return Err(verification_error("symlink", id.hex()));
+        }
+        Ok(id)
+    }
+
+    /// Fetches the given objects in one batch if the backend supports fetching
+    /// missing objects on demand. This avoids fetching them one by one when
+    /// many objects are about to be read.
+    pub fn prefetch_objects(&self, ids: &[FetchableObjectId]) -> BackendResult<()> {
+        match self.backend.partial_fetch() {
+            Some(fetcher) if !ids.is_empty() => fetcher.fetch_objects(ids),
+            _ => Ok(()),
+        }
+    }
+
+    /// Calls `read`, and calls it again after fetching the object if it's
+    /// missing locally.
+    async fn read_or_fetch<T, F>(
+        &self,
+        id: FetchableObjectId,
+        read: impl Fn() -> F,
+    ) -> BackendResult<T>
+    where
+        F: Future<Output = BackendResult<T>>,
+    {
+        match read().await {
+            Err(BackendError::ObjectNotFound { .. }) if self.backend.partial_fetch().is_some() => {
+                self.prefetch_objects(&[id])?;
+                read().await
+            }
+            result => result,
+        }
     }
 
     pub fn read_conflict(
@@ -280,8 +374,12 @@ impl Store {
         path: &RepoPath,
         contents: &MergedTreeValue,
     ) -> BackendResult<ConflictId> {
-        self.backend
-            .write_conflict(path, &contents.clone().into_backend_conflict())
+        let conflict = contents.clone().into_backend_conflict();
+        let id = self.backend.write_conflict(path, &conflict)?;
+        if self.verify_writes && self.backend.read_conflict(path, &id)? != conflict {
+            return Err(verification_error("conflict", id.hex()));
+        }
+        Ok(id)
     }
 
     pub fn tree_builder(self: &Arc<Self>, base_tree_id: TreeId) -> TreeBuilder {
@@ -292,3 +390,30 @@ impl Store {
         self.backend.gc(index, keep_newer)
     }
 }
+
+fn verification_error(object_type: &'static str, hash: String) -> BackendError {
+    BackendError::WriteObject {
+        object_type,
+        source: format!("Object {hash} read back from the store differs from what was written")
+            .into(),
+    }
+}
+
+/// Returns true if the commit read back from the backend matches the written
+/// one. Timestamps are compared in seconds since backends may not store
+/// sub-second precision.
+fn is_same_commit(written: &backend::Commit, stored: &backend::Commit) -> bool {
+    let is_same_signature = |a: &Signature, b: &Signature| {
+        a.name == b.name
+            && a.email == b.email
+            && a.timestamp.timestamp.0.div_euclid(1000) == b.timestamp.timestamp.0.div_euclid(1000)
+            && a.timestamp.tz_offset == b.timestamp.tz_offset
+    };
+    written.parents == stored.parents
+        && written.predecessors == stored.predecessors
+        && written.root_tree == stored.root_tree
+        && written.change_id == stored.change_id
+        && written.description == stored.description
+        && is_same_signature(&written.author, &stored.author)
+        && is_same_signature(&written.committer, &stored.committer)
+}
@@ -1,11 +1,29 @@
+// Copyright 2024 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Fake signing backend for tests.
+
 use hex::ToHex;
-use jj_lib::content_hash::blake2b_hash;
-use jj_lib::signing::SigStatus;
-use jj_lib::signing::SignError;
-use jj_lib::signing::SignResult;
-use jj_lib::signing::SigningBackend;
-use jj_lib::signing::Verification;
 
+use crate::content_hash::blake2b_hash;
+use crate::signing::SigStatus;
+use crate::signing::SignError;
+use crate::signing::SignResult;
+use crate::signing::SigningBackend;
+use crate::signing::Verification;
+
+/// A signing backend for tests. Signatures are hashes of the key and data.
 #[derive(Debug)]
 pub struct TestSigningBackend;
 
@@ -32,6 +50,8 @@ impl SigningBackend for TestSigningBackend {
     }
 
     fn verify(&self, data: &[u8], signature: &[u8]) -> SignResult<Verification> {
+        // The signature read back from a Git commit header ends with a newline.
+        let signature = signature.strip_suffix(b"\n").unwrap_or(signature);
         let Some(key) = signature
             .strip_prefix(PREFIX.as_bytes())
             .and_then(|s| s.splitn(2, |&b| b == b'\n').next())
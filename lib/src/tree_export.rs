@@ -0,0 +1,146 @@
+// Copyright 2026 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Writes files from a tree to arbitrary locations on disk.
+//!
+//! Unlike checking out a tree in a working copy, nothing about the written
+//! files is recorded, so they are never snapshotted or updated afterwards.
+
+use std::fs;
+use std::fs::File;
+use std::io;
+use std::path::Path;
+
+use thiserror::Error;
+
+use crate::backend::BackendError;
+use crate::conflicts::materialize_merge_result;
+use crate::conflicts::materialize_tree_value;
+use crate::conflicts::ConflictMarkerStyle;
+use crate::conflicts::MaterializedTreeValue;
+use crate::file_util::IoResultExt as _;
+use crate::file_util::PathError;
+use crate::merge::MergedTreeValue;
+use crate::repo_path::RepoPath;
+use crate::store::Store;
+
+/// Error while exporting a file from a tree.
+#[derive(Debug, Error)]
+pub enum TreeExportError {
+    /// Failed to read the value from the store.
+    #[error(transparent)]
+    Backend(#[from] BackendError),
+    /// Failed to write the file to disk.
+    #[error(transparent)]
+    Path(#[from] PathError),
+}
+
+/// What was written for an exported tree value.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ExportedValue {
+    /// A regular file.
+    File,
+    /// A file with conflict markers.
+    FileConflict,
+    /// A symlink, or a regular file containing the target on platforms
+    /// without symlink support.
+    Symlink,
+    /// Nothing, because the value can't be represented on disk (e.g. a Git
+    /// submodule or a conflict involving non-files), or because reading it was
+    /// denied.
+    Skipped,
+}
+
+/// Materializes the `value` of the file at `path` and writes it to
+/// `disk_path`. Missing parent directories are created, and an existing file
+/// at `disk_path` is replaced.
+pub async fn export_tree_value(
+    store: &Store,
+    path: &RepoPath,
+    value: MergedTreeValue,
+    disk_path: &Path,
+    conflict_marker_style: ConflictMarkerStyle,
+) -> Result<ExportedValue, TreeExportError> {
+    match materialize_tree_value(store, path, value).await? {
+        MaterializedTreeValue::File {
+            mut reader,
+            executable,
+            ..
+        } => {
+            prepare_disk_path(disk_path)?;
+            let mut file = File::create(disk_path).context(disk_path)?;
+            io::copy(&mut reader, &mut file).context(disk_path)?;
+            set_executable(&file, executable).context(disk_path)?;
+            Ok(ExportedValue::File)
+        }
+        MaterializedTreeValue::FileConflict {
+            contents,
+            executable,
+            ..
+        } => {
+            prepare_disk_path(disk_path)?;
+            let mut file = File::create(disk_path).context(disk_path)?;
+            materialize_merge_result(&contents, conflict_marker_style, &mut file)
+                .context(disk_path)?;
+            set_executable(&file, executable).context(disk_path)?;
+            Ok(ExportedValue::FileConflict)
+        }
+        MaterializedTreeValue::Symlink { target, .. } => {
+            prepare_disk_path(disk_path)?;
+            write_symlink(disk_path, &target).context(disk_path)?;
+            Ok(ExportedValue::Symlink)
+        }
+        MaterializedTreeValue::AccessDenied(_)
+        | MaterializedTreeValue::OtherConflict { .. }
+        | MaterializedTreeValue::GitSubmodule(_) => Ok(ExportedValue::Skipped),
+        MaterializedTreeValue::Absent | MaterializedTreeValue::Tree(_) => {
+            panic!("only file values can be exported")
+        }
+    }
+}
+
+/// Creates the parent directories of `disk_path` and removes an existing file
+/// at it.
+fn prepare_disk_path(disk_path: &Path) -> Result<(), PathError> {
+    if let Some(parent) = disk_path.parent() {
+        fs::create_dir_all(parent).context(parent)?;
+    }
+    // Don't follow symlinks so the link itself is replaced.
+    match disk_path.symlink_metadata() {
+        Ok(metadata) if !metadata.is_dir() => fs::remove_file(disk_path).context(disk_path),
+        _ => Ok(()),
+    }
+}
+
+#[cfg(unix)]
+fn set_executable(file: &File, executable: bool) -> io::Result<()> {
+    use std::os::unix::fs::PermissionsExt as _;
+    let mode = if executable { 0o755 } else { 0o644 };
+    file.set_permissions(fs::Permissions::from_mode(mode))
+}
+
+#[cfg(not(unix))]
+fn set_executable(_file: &File, _executable: bool) -> io::Result<()> {
+    Ok(())
+}
+
+#[cfg(unix)]
+fn write_symlink(disk_path: &Path, target: &str) -> io::Result<()> {
+    std::os::unix::fs::symlink(target, disk_path)
+}
+
+#[cfg(not(unix))]
+fn write_symlink(disk_path: &Path, target: &str) -> io::Result<()> {
+    fs::write(disk_path, target)
+}
@@ -29,12 +29,14 @@ use crate::backend::BackendError;
 use crate::backend::MergedTreeId;
 use crate::commit::Commit;
 use crate::conflicts::ConflictMarkerStyle;
+use crate::conflicts::ConflictMaterializers;
 use crate::dag_walk;
 use crate::fsmonitor::FsmonitorSettings;
 use crate::gitignore::GitIgnoreError;
 use crate::gitignore::GitIgnoreFile;
 use crate::matchers::EverythingMatcher;
 use crate::matchers::Matcher;
+use crate::matchers::NothingMatcher;
 use crate::op_heads_store::OpHeadsStoreError;
 use crate::op_store::OpStoreError;
 use crate::op_store::OperationId;
@@ -47,6 +49,7 @@ use crate::repo_path::InvalidRepoPathError;
 use crate::repo_path::RepoPath;
 use crate::repo_path::RepoPathBuf;
 use crate::settings::UserSettings;
+use crate::sparse::SparsePattern;
 use crate::store::Store;
 
 /// The trait all working-copy implementations must implement.
@@ -69,9 +72,9 @@ pub trait WorkingCopy: Send {
 
     /// Patterns that decide which paths from the current tree should be checked
     /// out in the working copy. An empty list means that no paths should be
-    /// checked out in the working copy. A single `RepoPath::root()` entry means
-    /// that all files should be checked out.
-    fn sparse_patterns(&self) -> Result<&[RepoPathBuf], WorkingCopyStateError>;
+    /// checked out in the working copy. A single `SparsePattern::root()` entry
+    /// means that all files should be checked out.
+    fn sparse_patterns(&self) -> Result<&[SparsePattern], WorkingCopyStateError>;
 
     /// Locks the working copy and returns an instance with methods for updating
     /// the working copy files and state.
@@ -137,7 +140,7 @@ pub trait LockedWorkingCopy {
     fn recover(&mut self, commit: &Commit) -> Result<(), ResetError>;
 
     /// See `WorkingCopy::sparse_patterns()`
-    fn sparse_patterns(&self) -> Result<&[RepoPathBuf], WorkingCopyStateError>;
+    fn sparse_patterns(&self) -> Result<&[SparsePattern], WorkingCopyStateError>;
 
     /// Updates the patterns that decide which paths from the current tree
     /// should be checked out in the working copy.
@@ -147,7 +150,7 @@ pub trait LockedWorkingCopy {
     // to use sparse).
     fn set_sparse_patterns(
         &mut self,
-        new_sparse_patterns: Vec<RepoPathBuf>,
+        new_sparse_patterns: Vec<SparsePattern>,
         options: &CheckoutOptions,
     ) -> Result<CheckoutStats, CheckoutError>;
 
@@ -220,8 +223,25 @@ pub struct SnapshotOptions<'a> {
     /// (depending on implementation)
     /// return `SnapshotError::NewFileTooLarge`.
     pub max_new_file_size: u64,
+    /// Per-path overrides of `max_new_file_size`. A new file gets the limit of
+    /// the first matcher that matches its path.
+    pub max_new_file_size_overrides: Vec<(Arc<dyn Matcher>, u64)>,
     /// Expected conflict marker style for checking for changed files.
     pub conflict_marker_style: ConflictMarkerStyle,
+    /// Custom formats of conflicts to parse instead of conflict markers.
+    pub conflict_materializers: ConflictMaterializers,
+    /// How symlinks are represented in the working copy. Must match the mode
+    /// used when checking out so regular files can be read back as symlinks.
+    pub symlinks: SymlinkMode,
+    /// Line ending conversion of text files. Must match the mode used when
+    /// checking out so converted files aren't reported as changed.
+    pub eol_conversion: EolConversionMode,
+    /// Whether to remember which directories were unchanged since the last
+    /// snapshot so they don't have to be read again. Only used if no
+    /// fsmonitor is configured and all new files are to be tracked.
+    pub untracked_cache: bool,
+    /// Paths to check for changes even if they're assumed to be unchanged.
+    pub refresh_matcher: &'a dyn Matcher,
 }
 
 impl SnapshotOptions<'_> {
@@ -233,7 +253,13 @@ impl SnapshotOptions<'_> {
             progress: None,
             start_tracking_matcher: &EverythingMatcher,
             max_new_file_size: u64::MAX,
+            max_new_file_size_overrides: vec![],
             conflict_marker_style: ConflictMarkerStyle::default(),
+            conflict_materializers: ConflictMaterializers::default(),
+            symlinks: SymlinkMode::default(),
+            eol_conversion: EolConversionMode::default(),
+            untracked_cache: false,
+            refresh_matcher: &NothingMatcher,
         }
     }
 }
@@ -248,6 +274,21 @@ pub struct SnapshotStats {
     pub untracked_paths: BTreeMap<RepoPathBuf, UntrackedReason>,
 }
 
+impl SnapshotStats {
+    /// Iterates over the new files that were left untracked because they
+    /// exceeded the size limit. Yields the path, the size of the file, and the
+    /// limit that applied to it.
+    pub fn files_too_large(&self) -> impl Iterator<Item = (&RepoPath, u64, u64)> {
+        self.untracked_paths
+            .iter()
+            .map(|(path, reason)| match reason {
+                UntrackedReason::FileTooLarge { size, max_size } => {
+                    (path.as_ref(), *size, *max_size)
+                }
+            })
+    }
+}
+
 /// Reason why the new path isn't tracked.
 #[derive(Clone, Debug)]
 pub enum UntrackedReason {
@@ -262,20 +303,66 @@ pub enum UntrackedReason {
 
 /// Options used when checking out a tree in the working copy.
 #[derive(Clone)]
-pub struct CheckoutOptions {
+pub struct CheckoutOptions<'a> {
     /// Conflict marker style to use when materializing files
     pub conflict_marker_style: ConflictMarkerStyle,
+    /// Custom formats to materialize conflicts at certain paths with
+    pub conflict_materializers: ConflictMaterializers,
+    /// A callback for the UI to display progress. It's called with the paths
+    /// written to the working copy, in path order.
+    pub progress: Option<&'a CheckoutProgress<'a>>,
+    /// How symlinks are represented in the working copy.
+    pub symlinks: SymlinkMode,
+    /// Line ending conversion of text files.
+    pub eol_conversion: EolConversionMode,
 }
 
-impl CheckoutOptions {
+impl CheckoutOptions<'_> {
     /// Create an instance for use in tests.
     pub fn empty_for_test() -> Self {
         CheckoutOptions {
             conflict_marker_style: ConflictMarkerStyle::default(),
+            conflict_materializers: ConflictMaterializers::default(),
+            progress: None,
+            symlinks: SymlinkMode::default(),
+            eol_conversion: EolConversionMode::default(),
         }
     }
 }
 
+/// How symlinks are represented in the working copy.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, serde::Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum SymlinkMode {
+    /// Create symlinks if the platform allows it, and regular files otherwise.
+    #[default]
+    Auto,
+    /// Always create regular files containing the symlink target. Such files
+    /// are read back as symlinks when snapshotting, so the symlinks in the
+    /// tree are preserved.
+    File,
+}
+
+/// Line ending conversion of text files in the working copy.
+///
+/// If enabled, `.gitattributes` at the root of the working copy can override
+/// the conversion per path with the `text`, `binary`, and `eol` attributes.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, serde::Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum EolConversionMode {
+    /// Files are written and read as is.
+    #[default]
+    None,
+    /// CRLF line endings are converted to LF when snapshotting.
+    Input,
+    /// CRLF line endings are converted to LF when snapshotting, and LF line
+    /// endings are converted to CRLF when checking out.
+    InputOutput,
+}
+
+/// A callback for getting checkout progress updates.
+pub type CheckoutProgress<'a> = dyn Fn(&RepoPath) + 'a + Sync;
+
 /// Stats about a checkout operation on a working copy. All "files" mentioned
 /// below may also be symlinks or materialized conflicts.
 #[derive(Debug, PartialEq, Eq, Clone)]
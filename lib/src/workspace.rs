@@ -28,6 +28,7 @@ use thiserror::Error;
 use crate::backend::BackendInitError;
 use crate::backend::MergedTreeId;
 use crate::commit::Commit;
+use crate::config::ConfigGetError;
 use crate::file_util::IoResultExt as _;
 use crate::file_util::PathError;
 use crate::local_backend::LocalBackend;
@@ -60,6 +61,8 @@ use crate::working_copy::LockedWorkingCopy;
 use crate::working_copy::WorkingCopy;
 use crate::working_copy::WorkingCopyFactory;
 use crate::working_copy::WorkingCopyStateError;
+use crate::workspace_store::WorkspaceRecord;
+use crate::workspace_store::WorkspaceStore;
 
 #[derive(Error, Debug)]
 pub enum WorkspaceInitError {
@@ -79,6 +82,8 @@ pub enum WorkspaceInitError {
     Backend(#[from] BackendInitError),
     #[error(transparent)]
     SignInit(#[from] SignInitError),
+    #[error(transparent)]
+    Config(#[from] ConfigGetError),
 }
 
 #[derive(Error, Debug)]
@@ -194,6 +199,23 @@ impl Workspace {
         Self::init_with_backend(user_settings, workspace_root, backend_initializer, signer)
     }
 
+    /// Initializes a workspace with a new local backend which writes objects
+    /// to the shared object store at `shared_store_path`.
+    pub fn init_local_with_shared_store(
+        user_settings: &UserSettings,
+        workspace_root: &Path,
+        shared_store_path: &Path,
+    ) -> Result<(Self, Arc<ReadonlyRepo>), WorkspaceInitError> {
+        let backend_initializer: &BackendInitializer = &|_settings, store_path| {
+            Ok(Box::new(LocalBackend::init_with_shared_store(
+                store_path,
+                shared_store_path,
+            )?))
+        };
+        let signer = Signer::from_settings(user_settings)?;
+        Self::init_with_backend(user_settings, workspace_root, backend_initializer, signer)
+    }
+
     /// Initializes a workspace with a new Git backend and bare Git repo in
     /// `.jj/repo/store/git`.
     #[cfg(feature = "git")]
@@ -311,6 +333,7 @@ impl Workspace {
                 RepoInitError::Backend(err) => WorkspaceInitError::Backend(err),
                 RepoInitError::OpHeadsStore(err) => WorkspaceInitError::OpHeadsStore(err),
                 RepoInitError::Path(err) => WorkspaceInitError::Path(err),
+                RepoInitError::Config(err) => WorkspaceInitError::Config(err),
             })?;
             let (working_copy, repo) = init_working_copy(
                 user_settings,
@@ -322,6 +345,7 @@ impl Workspace {
             )?;
             let repo_loader = repo.loader().clone();
             let workspace = Workspace::new(workspace_root, repo_dir, working_copy, repo_loader)?;
+            workspace.record_state();
             Ok((workspace, repo))
         })()
         .inspect_err(|_err| {
@@ -385,6 +409,7 @@ impl Workspace {
             working_copy,
             repo.loader().clone(),
         )?;
+        workspace.record_state();
         Ok((workspace, repo))
     }
 
@@ -419,6 +444,34 @@ impl Workspace {
         self.working_copy.as_ref()
     }
 
+    /// Writes down the path and working-copy state of this workspace so other
+    /// workspaces can report them. Failures are logged and otherwise ignored.
+    fn record_state(&self) {
+        let wc = self.working_copy();
+        let record = (|| {
+            Ok::<_, WorkingCopyStateError>(WorkspaceRecord {
+                path: Some(self.workspace_root.clone()),
+                operation_id: wc.operation_id().clone(),
+                tree_id: wc.tree_id()?.clone(),
+                sparse_patterns: wc.sparse_patterns()?.to_vec(),
+            })
+        })();
+        let store = WorkspaceStore::load(&self.repo_path);
+        let result = match record {
+            // Avoid rewriting the record on every command.
+            Ok(record) if matches!(store.get(wc.workspace_id()), Ok(Some(old)) if old == record) => {
+                Ok(())
+            }
+            Ok(record) => store
+                .set(wc.workspace_id(), &record)
+                .map_err(|err| err.to_string()),
+            Err(err) => Err(err.to_string()),
+        };
+        if let Err(err) = result {
+            tracing::warn!(err, "Failed to record workspace state");
+        }
+    }
+
     pub fn start_working_copy_mutation(
         &mut self,
     ) -> Result<LockedWorkspace, WorkingCopyStateError> {
@@ -475,6 +528,7 @@ impl LockedWorkspace<'_> {
     pub fn finish(self, operation_id: OperationId) -> Result<(), WorkingCopyStateError> {
         let new_wc = self.locked_wc.finish(operation_id)?;
         self.base.working_copy = new_wc;
+        self.base.record_state();
         Ok(())
     }
 }
@@ -0,0 +1,257 @@
+// Copyright 2024 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Best-effort records of the workspaces sharing a repo.
+//!
+//! Each workspace writes down its filesystem path and the state of its working
+//! copy whenever the working copy is updated. Other workspaces can then report
+//! where a workspace lives and whether it is stale without having to load it.
+//! The records are informational only; a missing or outdated record must not
+//! prevent any operation.
+
+use std::fs;
+use std::io;
+use std::io::Write as _;
+use std::path::Path;
+use std::path::PathBuf;
+
+use serde::Deserialize;
+use serde::Serialize;
+use tempfile::NamedTempFile;
+use thiserror::Error;
+
+use crate::backend::MergedTreeId;
+use crate::backend::TreeId;
+use crate::file_util::create_or_reuse_dir;
+use crate::file_util::IoResultExt as _;
+use crate::file_util::PathError;
+use crate::fileset::FilePatternParseError;
+use crate::merge::Merge;
+use crate::object_id::ObjectId as _;
+use crate::op_store::OperationId;
+use crate::op_store::WorkspaceId;
+use crate::repo_path::RepoPathBuf;
+use crate::sparse::SparsePattern;
+
+/// Error reading or writing a workspace record.
+#[derive(Debug, Error)]
+pub enum WorkspaceStoreError {
+    /// Failed to access the store directory or a record file.
+    #[error(transparent)]
+    Path(#[from] PathError),
+    /// A record file couldn't be parsed.
+    #[error("Invalid workspace record {path}")]
+    InvalidRecord {
+        /// Path to the record file.
+        path: PathBuf,
+        /// Underlying parse error.
+        #[source]
+        source: Box<dyn std::error::Error + Send + Sync>,
+    },
+}
+
+/// Last known state of a workspace.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct WorkspaceRecord {
+    /// Root directory of the workspace, if it could be represented.
+    pub path: Option<PathBuf>,
+    /// Operation the working copy was last updated to.
+    pub operation_id: OperationId,
+    /// Tree the working copy was last updated to.
+    pub tree_id: MergedTreeId,
+    /// Sparse patterns of the working copy.
+    pub sparse_patterns: Vec<SparsePattern>,
+}
+
+impl WorkspaceRecord {
+    /// Returns true if the working copy was last updated to a tree other than
+    /// `wc_tree_id`, the tree of the workspace's working-copy commit.
+    pub fn is_stale(&self, wc_tree_id: &MergedTreeId) -> bool {
+        self.tree_id.to_merge() != wc_tree_id.to_merge()
+    }
+}
+
+/// Version of the `WorkspaceRecordData` format. Version 0 stores the sparse
+/// patterns as internal path strings, and version 1 stores them in the format
+/// parsed by `SparsePattern::parse()`.
+const RECORD_VERSION: u32 = 1;
+
+/// Serialized form of `WorkspaceRecord`.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+struct WorkspaceRecordData {
+    #[serde(default)]
+    version: u32,
+    path: Option<String>,
+    operation_id: String,
+    tree_ids: Vec<String>,
+    sparse_patterns: Vec<String>,
+}
+
+impl WorkspaceRecordData {
+    fn from_record(record: &WorkspaceRecord) -> Self {
+        WorkspaceRecordData {
+            version: RECORD_VERSION,
+            path: record
+                .path
+                .as_ref()
+                .and_then(|path| path.to_str())
+                .map(|path| path.to_owned()),
+            operation_id: record.operation_id.hex(),
+            tree_ids: record
+                .tree_id
+                .to_merge()
+                .iter()
+                .map(|id| id.hex())
+                .collect(),
+            sparse_patterns: record
+                .sparse_patterns
+                .iter()
+                .map(|pattern| pattern.to_string())
+                .collect(),
+        }
+    }
+
+    fn to_record(&self) -> Result<WorkspaceRecord, Box<dyn std::error::Error + Send + Sync>> {
+        let tree_ids: Vec<_> = self
+            .tree_ids
+            .iter()
+            .map(|id| TreeId::try_from_hex(id))
+            .collect::<Result<_, _>>()?;
+        Ok(WorkspaceRecord {
+            path: self.path.as_ref().map(PathBuf::from),
+            operation_id: OperationId::try_from_hex(&self.operation_id)?,
+            tree_id: MergedTreeId::Merge(Merge::from_vec(tree_ids)),
+            sparse_patterns: self
+                .sparse_patterns
+                .iter()
+                .map(|pattern| match self.version {
+                    0 => Ok(SparsePattern::prefix(RepoPathBuf::from_relative_path(
+                        pattern,
+                    )?)),
+                    _ => SparsePattern::parse(pattern),
+                })
+                .collect::<Result<_, FilePatternParseError>>()?,
+        })
+    }
+}
+
+/// On-disk store of workspace records, located under the repo directory.
+#[derive(Debug)]
+pub struct WorkspaceStore {
+    dir: PathBuf,
+}
+
+impl WorkspaceStore {
+    /// Name of the store directory within the repo directory.
+    pub fn dir_name() -> &'static str {
+        "workspace_store"
+    }
+
+    /// Loads the store of the repo at `repo_path`. The directory is created
+    /// lazily when the first record is written.
+    pub fn load(repo_path: &Path) -> Self {
+        WorkspaceStore {
+            dir: repo_path.join(Self::dir_name()),
+        }
+    }
+
+    fn record_path(&self, workspace_id: &WorkspaceId) -> PathBuf {
+        // Workspace names may contain characters that aren't valid in file
+        // names.
+        self.dir.join(hex::encode(workspace_id.as_str()))
+    }
+
+    /// Writes the record of `workspace_id`, replacing any existing record.
+    pub fn set(
+        &self,
+        workspace_id: &WorkspaceId,
+        record: &WorkspaceRecord,
+    ) -> Result<(), WorkspaceStoreError> {
+        create_or_reuse_dir(&self.dir).context(&self.dir)?;
+        let data = serde_json::to_vec(&WorkspaceRecordData::from_record(record)).unwrap();
+        let mut temp_file = NamedTempFile::new_in(&self.dir).context(&self.dir)?;
+        temp_file.write_all(&data).context(temp_file.path())?;
+        let path = self.record_path(workspace_id);
+        temp_file
+            .persist(&path)
+            .map_err(|err| err.error)
+            .context(&path)?;
+        Ok(())
+    }
+
+    /// Removes the record of `workspace_id` if there is one.
+    pub fn remove(&self, workspace_id: &WorkspaceId) -> Result<(), WorkspaceStoreError> {
+        let path = self.record_path(workspace_id);
+        match fs::remove_file(&path) {
+            Ok(()) => Ok(()),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(()),
+            Err(err) => Err(PathError { path, error: err }.into()),
+        }
+    }
+
+    /// Looks up the record of `workspace_id`.
+    pub fn get(
+        &self,
+        workspace_id: &WorkspaceId,
+    ) -> Result<Option<WorkspaceRecord>, WorkspaceStoreError> {
+        let path = self.record_path(workspace_id);
+        let data = match fs::read(&path) {
+            Ok(data) => data,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(None),
+            Err(err) => return Err(PathError { path, error: err }.into()),
+        };
+        let invalid =
+            |source: Box<dyn std::error::Error + Send + Sync>| WorkspaceStoreError::InvalidRecord {
+                path: path.clone(),
+                source,
+            };
+        let record_data: WorkspaceRecordData =
+            serde_json::from_slice(&data).map_err(|err| invalid(err.into()))?;
+        let record = record_data.to_record().map_err(invalid)?;
+        Ok(Some(record))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_record_data_sparse_patterns() {
+        let parse = |input| SparsePattern::parse(input).unwrap();
+        let prefix = |input| SparsePattern::prefix(RepoPathBuf::from_internal_string(input));
+        let data = |json: &str| -> WorkspaceRecordData { serde_json::from_str(json).unwrap() };
+
+        // Version 0 only stores prefixes, which may look like other patterns
+        let record = data(
+            r#"{"path":null,"operation_id":"00","tree_ids":["00"],
+                "sparse_patterns":["","!a","glob:b"]}"#,
+        )
+        .to_record()
+        .unwrap();
+        assert_eq!(
+            record.sparse_patterns,
+            [SparsePattern::root(), prefix("!a"), prefix("glob:b")]
+        );
+
+        let patterns = vec![parse("a"), parse("!a/b"), parse("glob:*.md")];
+        let record = WorkspaceRecord {
+            sparse_patterns: patterns.clone(),
+            ..record
+        };
+        let record_data = WorkspaceRecordData::from_record(&record);
+        assert_eq!(record_data.version, RECORD_VERSION);
+        assert_eq!(record_data.to_record().unwrap().sparse_patterns, patterns);
+    }
+}
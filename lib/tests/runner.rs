@@ -19,6 +19,7 @@ mod test_id_prefix;
 mod test_index;
 mod test_init;
 mod test_load_repo;
+mod test_local_backend;
 mod test_local_working_copy;
 mod test_local_working_copy_concurrent;
 mod test_local_working_copy_sparse;
@@ -31,6 +32,7 @@ mod test_revset;
 mod test_rewrite;
 mod test_rewrite_duplicate;
 mod test_rewrite_transform;
+mod test_scenario;
 mod test_signing;
 mod test_ssh_signing;
 mod test_view;
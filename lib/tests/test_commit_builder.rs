@@ -128,6 +128,32 @@ fn test_initial(backend: TestRepoBackend) {
     );
 }
 
+#[test_case(TestRepoBackend::Local ; "local backend")]
+#[test_case(TestRepoBackend::Git ; "git backend")]
+fn test_verify_writes(backend: TestRepoBackend) {
+    let mut config = testutils::base_user_config();
+    let mut layer = ConfigLayer::empty(ConfigSource::User);
+    layer.set_value("core.verify-writes", true).unwrap();
+    config.add_layer(layer);
+    let settings = UserSettings::from_config(config).unwrap();
+    let test_repo = TestRepo::init_with_backend_and_settings(backend, &settings);
+    let repo = &test_repo.repo;
+    let store = repo.store();
+
+    // Objects read back from a healthy store match what was written
+    let file_path = RepoPath::from_internal_string("dir/file");
+    let tree = create_tree(repo, &[(file_path, "file contents")]);
+    let mut tx = repo.start_transaction(&settings);
+    let commit = tx
+        .repo_mut()
+        .new_commit(&settings, vec![store.root_commit_id().clone()], tree.id())
+        .set_description("description")
+        .write()
+        .unwrap();
+    tx.commit("test").unwrap();
+    assert_eq!(store.get_commit(commit.id()).unwrap(), commit);
+}
+
 #[test_case(TestRepoBackend::Local ; "local backend")]
 #[test_case(TestRepoBackend::Git ; "git backend")]
 fn test_rewrite(backend: TestRepoBackend) {
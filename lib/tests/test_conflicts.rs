@@ -21,7 +21,10 @@ use jj_lib::conflicts::materialize_merge_result_to_bytes;
 use jj_lib::conflicts::parse_conflict;
 use jj_lib::conflicts::update_from_content;
 use jj_lib::conflicts::ConflictMarkerStyle;
+use jj_lib::conflicts::ConflictMarkerStyleMaterializer;
+use jj_lib::conflicts::ConflictMaterializer as _;
 use jj_lib::conflicts::MIN_CONFLICT_MARKER_LEN;
+use jj_lib::matchers::EverythingMatcher;
 use jj_lib::merge::Merge;
 use jj_lib::repo::Repo;
 use jj_lib::repo_path::RepoPath;
@@ -583,6 +586,7 @@ fn test_materialize_parse_roundtrip_different_markers() {
         ConflictMarkerStyle::Diff,
         ConflictMarkerStyle::Snapshot,
         ConflictMarkerStyle::Git,
+        ConflictMarkerStyle::Zdiff3,
     ];
 
     // For every pair of conflict marker styles, materialize the conflict using the
@@ -610,6 +614,129 @@ fn test_materialize_parse_roundtrip_different_markers() {
     }
 }
 
+#[test]
+fn test_materialize_conflict_zdiff3() {
+    let test_repo = TestRepo::init();
+    let store = test_repo.repo.store();
+
+    let path = RepoPath::from_internal_string("file");
+    let base_id = testutils::write_file(
+        store,
+        path,
+        indoc! {"
+            line 1
+            line 2
+        "},
+    );
+    let left_id = testutils::write_file(
+        store,
+        path,
+        indoc! {"
+            line 1
+            common start
+            left
+            common end
+            line 2
+        "},
+    );
+    let right_id = testutils::write_file(
+        store,
+        path,
+        indoc! {"
+            line 1
+            common start
+            right
+            common end
+            line 2
+        "},
+    );
+
+    let conflict = Merge::from_removes_adds(
+        vec![Some(base_id.clone())],
+        vec![Some(left_id.clone()), Some(right_id.clone())],
+    );
+    let materialized =
+        materialize_conflict_string(store, path, &conflict, ConflictMarkerStyle::Zdiff3);
+    insta::assert_snapshot!(materialized, @r##"
+    line 1
+    common start
+    <<<<<<< Side #1 (Conflict 1 of 1)
+    left
+    ||||||| Base
+    =======
+    right
+    >>>>>>> Side #2 (Conflict 1 of 1 ends)
+    common end
+    line 2
+    "##);
+
+    let parsed = update_from_content(
+        &conflict,
+        store,
+        path,
+        materialized.as_bytes(),
+        ConflictMarkerStyle::Zdiff3,
+        MIN_CONFLICT_MARKER_LEN,
+    )
+    .block_on()
+    .unwrap();
+    assert_eq!(parsed, conflict);
+
+    // The per-file materializer parses the markers back without being told the
+    // marker length. The moved lines are parsed as if the base had them too,
+    // which doesn't change the materialized conflict.
+    let contents = extract_as_single_hunk(&conflict, store, path)
+        .block_on()
+        .unwrap();
+    let materializer = ConflictMarkerStyleMaterializer::new(
+        Box::new(EverythingMatcher),
+        ConflictMarkerStyle::Zdiff3,
+    );
+    let materialized = materializer.materialize(path, &contents);
+    let parsed = materializer.parse(path, &materialized, 2).unwrap();
+    assert_eq!(
+        parsed.removes().next().unwrap(),
+        "line 1\ncommon start\ncommon end\nline 2\n"
+    );
+    assert_eq!(parsed.adds().collect_vec(), contents.adds().collect_vec());
+    assert_eq!(materializer.materialize(path, &parsed), materialized);
+}
+
+#[test]
+fn test_conflict_marker_style_materializer_parse_marker_len() {
+    let test_repo = TestRepo::init();
+    let store = test_repo.repo.store();
+
+    // The unchanged marker-like line comes before the conflict, so the first
+    // conflict start marker isn't a real marker. Parsing with its length would
+    // end the conflict at the marker-like line added by the left side.
+    let path = RepoPath::from_internal_string("file");
+    let base_id = testutils::write_file(store, path, "<<<<<<<\nline 1\nline 2\n");
+    let left_id = testutils::write_file(store, path, "<<<<<<<\n>>>>>>>\nline 2\n");
+    let right_id = testutils::write_file(store, path, "<<<<<<<\nright 1\nline 2\n");
+    let conflict =
+        Merge::from_removes_adds(vec![Some(base_id)], vec![Some(left_id), Some(right_id)]);
+    let contents = extract_as_single_hunk(&conflict, store, path)
+        .block_on()
+        .unwrap();
+    let materializer =
+        ConflictMarkerStyleMaterializer::new(Box::new(EverythingMatcher), ConflictMarkerStyle::Git);
+    let materialized = materializer.materialize(path, &contents);
+    insta::assert_snapshot!(String::from_utf8(materialized.clone()).unwrap(), @r"
+    <<<<<<<
+    <<<<<<<<<<< Side #1 (Conflict 1 of 1)
+    >>>>>>>
+    ||||||||||| Base
+    line 1
+    ===========
+    right 1
+    >>>>>>>>>>> Side #2 (Conflict 1 of 1 ends)
+    line 2
+    ");
+    let parsed = materializer.parse(path, &materialized, 2).unwrap();
+    assert_eq!(parsed, contents);
+}
+
 #[test]
 fn test_materialize_conflict_no_newlines_at_eof() {
     let test_repo = TestRepo::init();
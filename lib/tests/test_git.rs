@@ -41,6 +41,8 @@ use jj_lib::git::GitBranchPushTargets;
 use jj_lib::git::GitFetchError;
 use jj_lib::git::GitImportError;
 use jj_lib::git::GitPushError;
+use jj_lib::git::GitPushOptions;
+use jj_lib::git::GitPushStats;
 use jj_lib::git::GitRefUpdate;
 use jj_lib::git::RefName;
 use jj_lib::git::SubmoduleConfig;
@@ -55,6 +57,7 @@ use jj_lib::repo::MutableRepo;
 use jj_lib::repo::ReadonlyRepo;
 use jj_lib::repo::Repo;
 use jj_lib::repo_path::RepoPath;
+use jj_lib::settings::FetchPruneMode;
 use jj_lib::settings::GitSettings;
 use jj_lib::settings::UserSettings;
 use jj_lib::signing::Signer;
@@ -607,6 +610,71 @@ fn test_import_refs_reimport_with_deleted_remote_ref() {
     assert_eq!(*view.heads(), expected_heads);
 }
 
+#[test]
+fn test_import_refs_reimport_with_deleted_remote_ref_fetch_prune() {
+    let settings = testutils::user_settings();
+    let mut git_settings = GitSettings {
+        auto_local_bookmark: true,
+        ..Default::default()
+    };
+    let test_workspace = TestRepo::init_with_backend(TestRepoBackend::Git);
+    let repo = &test_workspace.repo;
+    let git_repo = get_git_repo(repo);
+
+    let commit_base = empty_git_commit(&git_repo, "refs/heads/main", &[]);
+    let commit_feature =
+        empty_git_commit(&git_repo, "refs/remotes/origin/feature", &[&commit_base]);
+
+    let mut tx = repo.start_transaction(&settings);
+    git::import_refs(tx.repo_mut(), &git_settings).unwrap();
+    tx.repo_mut().rebase_descendants(&settings).unwrap();
+    let repo = tx.commit("test").unwrap();
+    assert_eq!(
+        repo.view().get_local_bookmark("feature"),
+        &RefTarget::normal(jj_id(&commit_feature))
+    );
+
+    delete_git_ref(&git_repo, "refs/remotes/origin/feature");
+
+    // The local bookmark is kept as is
+    git_settings.fetch_prune = FetchPruneMode::Keep;
+    let mut tx = repo.start_transaction(&settings);
+    let stats = git::import_refs(tx.repo_mut(), &git_settings).unwrap();
+    tx.repo_mut().rebase_descendants(&settings).unwrap();
+    let view = tx.repo().view();
+    assert!(stats.abandoned_commits.is_empty());
+    assert_eq!(
+        view.get_local_bookmark("feature"),
+        &RefTarget::normal(jj_id(&commit_feature))
+    );
+    assert!(view.get_remote_bookmark("feature", "origin").is_absent());
+    assert!(view.heads().contains(&jj_id(&commit_feature)));
+
+    // The local bookmark is kept, but conflicted with the deletion
+    git_settings.fetch_prune = FetchPruneMode::MarkDeleted;
+    let mut tx = repo.start_transaction(&settings);
+    let stats = git::import_refs(tx.repo_mut(), &git_settings).unwrap();
+    tx.repo_mut().rebase_descendants(&settings).unwrap();
+    let view = tx.repo().view();
+    assert!(stats.abandoned_commits.is_empty());
+    assert_eq!(
+        view.get_local_bookmark("feature"),
+        &RefTarget::from_legacy_form([jj_id(&commit_feature)], [jj_id(&commit_feature)],)
+    );
+    assert!(view.get_remote_bookmark("feature", "origin").is_absent());
+    assert!(view.heads().contains(&jj_id(&commit_feature)));
+
+    // The local bookmark is deleted, and the commit is abandoned
+    git_settings.fetch_prune = FetchPruneMode::Delete;
+    let mut tx = repo.start_transaction(&settings);
+    let stats = git::import_refs(tx.repo_mut(), &git_settings).unwrap();
+    tx.repo_mut().rebase_descendants(&settings).unwrap();
+    let view = tx.repo().view();
+    assert_eq!(stats.abandoned_commits, vec![jj_id(&commit_feature)]);
+    assert!(view.get_local_bookmark("feature").is_absent());
+    assert!(!view.heads().contains(&jj_id(&commit_feature)));
+}
+
 /// This test is nearly identical to the previous one, except the bookmarks are
 /// moved sideways instead of being deleted.
 #[test]
@@ -1118,6 +1186,66 @@ fn test_import_refs_reserved_remote_name() {
     assert_matches!(result, Err(GitImportError::RemoteReservedForLocalGitRepo));
 }
 
+#[test]
+fn test_import_refs_bookmark_patterns() {
+    let settings = testutils::user_settings();
+    let git_settings = GitSettings {
+        auto_local_bookmark: true,
+        import_bookmarks: Some(vec![StringPattern::glob("team/*").unwrap()]),
+        ..Default::default()
+    };
+    let test_repo = TestRepo::init_with_backend(TestRepoBackend::Git);
+    let repo = &test_repo.repo;
+    let git_repo = get_git_repo(repo);
+
+    let commit1 = empty_git_commit(&git_repo, "refs/heads/team/feature", &[]);
+    let commit2 = empty_git_commit(&git_repo, "refs/heads/private", &[]);
+    empty_git_commit(&git_repo, "refs/remotes/origin/team/remote", &[&commit1]);
+    empty_git_commit(&git_repo, "refs/remotes/origin/other", &[&commit2]);
+    empty_git_commit(&git_repo, "refs/tags/v1", &[&commit2]);
+
+    let mut tx = repo.start_transaction(&settings);
+    git::import_refs(tx.repo_mut(), &git_settings).unwrap();
+    let view = tx.repo().view();
+    assert_eq!(
+        view.bookmarks().map(|(name, _)| name).collect_vec(),
+        ["team/feature", "team/remote"]
+    );
+    assert!(view.get_git_ref("refs/heads/private").is_absent());
+    assert!(view
+        .get_remote_bookmark("other", "origin")
+        .target
+        .is_absent());
+    // Tags aren't subject to the bookmark patterns
+    assert!(view.get_tag("v1").is_present());
+}
+
+#[test]
+fn test_import_refs_auto_track_bookmarks() {
+    let settings = testutils::user_settings();
+    let git_settings = GitSettings {
+        auto_local_bookmark: false,
+        auto_track_bookmarks: Some(vec![StringPattern::glob("team/*").unwrap()]),
+        ..Default::default()
+    };
+    let test_repo = TestRepo::init_with_backend(TestRepoBackend::Git);
+    let repo = &test_repo.repo;
+    let git_repo = get_git_repo(repo);
+
+    let commit1 = empty_git_commit(&git_repo, "refs/remotes/origin/team/feature", &[]);
+    empty_git_commit(&git_repo, "refs/remotes/origin/other", &[&commit1]);
+
+    let mut tx = repo.start_transaction(&settings);
+    git::import_refs(tx.repo_mut(), &git_settings).unwrap();
+    let view = tx.repo().view();
+    assert!(view
+        .get_remote_bookmark("team/feature", "origin")
+        .is_tracking());
+    assert!(view.get_local_bookmark("team/feature").is_present());
+    assert!(!view.get_remote_bookmark("other", "origin").is_tracking());
+    assert!(view.get_local_bookmark("other").is_absent());
+}
+
 #[test]
 fn test_import_some_refs() {
     let settings = testutils::user_settings();
@@ -1490,7 +1618,9 @@ fn test_export_refs_no_detach() {
     mut_repo.rebase_descendants(&test_data.settings).unwrap();
 
     // Do an initial export to make sure `main` is considered
-    assert!(git::export_refs(mut_repo).unwrap().is_empty());
+    assert!(git::export_refs(mut_repo, &GitSettings::default())
+        .unwrap()
+        .is_empty());
     assert_eq!(
         mut_repo.get_git_ref("refs/heads/main"),
         RefTarget::normal(jj_id(&commit1))
@@ -1519,14 +1649,18 @@ fn test_export_refs_bookmark_changed() {
     git::import_head(mut_repo).unwrap();
     git::import_refs(mut_repo, &git_settings).unwrap();
     mut_repo.rebase_descendants(&test_data.settings).unwrap();
-    assert!(git::export_refs(mut_repo).unwrap().is_empty());
+    assert!(git::export_refs(mut_repo, &GitSettings::default())
+        .unwrap()
+        .is_empty());
 
     let new_commit = create_random_commit(mut_repo, &test_data.settings)
         .set_parents(vec![jj_id(&commit)])
         .write()
         .unwrap();
     mut_repo.set_local_bookmark_target("main", RefTarget::normal(new_commit.id().clone()));
-    assert!(git::export_refs(mut_repo).unwrap().is_empty());
+    assert!(git::export_refs(mut_repo, &GitSettings::default())
+        .unwrap()
+        .is_empty());
     assert_eq!(
         mut_repo.get_git_ref("refs/heads/main"),
         RefTarget::normal(new_commit.id().clone())
@@ -1544,6 +1678,45 @@ fn test_export_refs_bookmark_changed() {
     assert_eq!(git_repo.head().unwrap().name(), Some("refs/heads/feature"));
 }
 
+#[test]
+fn test_export_refs_tag_changed() {
+    // We can export tags created and deleted in jj
+    let test_data = GitRepoData::create();
+    let git_settings = GitSettings::default();
+    let git_repo = test_data.git_repo;
+    let commit = empty_git_commit(&git_repo, "refs/heads/main", &[]);
+    empty_git_commit(&git_repo, "refs/tags/old", &[&commit]);
+
+    let mut tx = test_data.repo.start_transaction(&test_data.settings);
+    let mut_repo = tx.repo_mut();
+    git::import_refs(mut_repo, &git_settings).unwrap();
+    mut_repo.set_tag_target("new", RefTarget::normal(jj_id(&commit)));
+    mut_repo.set_tag_target("old", RefTarget::absent());
+    assert!(git::export_refs(mut_repo, &git_settings)
+        .unwrap()
+        .is_empty());
+    assert_eq!(
+        mut_repo.get_git_ref("refs/tags/new"),
+        RefTarget::normal(jj_id(&commit))
+    );
+    assert_eq!(mut_repo.get_git_ref("refs/tags/old"), RefTarget::absent());
+    assert_eq!(
+        git_repo
+            .find_reference("refs/tags/new")
+            .unwrap()
+            .peel_to_commit()
+            .unwrap()
+            .id(),
+        commit.id()
+    );
+    assert!(git_repo.find_reference("refs/tags/old").is_err());
+
+    // Importing doesn't undo the changes
+    git::import_refs(mut_repo, &git_settings).unwrap();
+    assert_eq!(mut_repo.get_tag("new"), RefTarget::normal(jj_id(&commit)));
+    assert_eq!(mut_repo.get_tag("old"), RefTarget::absent());
+}
+
 #[test]
 fn test_export_refs_current_bookmark_changed() {
     // If we update a bookmark that is checked out in the git repo, HEAD gets
@@ -1558,14 +1731,18 @@ fn test_export_refs_current_bookmark_changed() {
     git::import_head(mut_repo).unwrap();
     git::import_refs(mut_repo, &git_settings).unwrap();
     mut_repo.rebase_descendants(&test_data.settings).unwrap();
-    assert!(git::export_refs(mut_repo).unwrap().is_empty());
+    assert!(git::export_refs(mut_repo, &GitSettings::default())
+        .unwrap()
+        .is_empty());
 
     let new_commit = create_random_commit(mut_repo, &test_data.settings)
         .set_parents(vec![jj_id(&commit1)])
         .write()
         .unwrap();
     mut_repo.set_local_bookmark_target("main", RefTarget::normal(new_commit.id().clone()));
-    assert!(git::export_refs(mut_repo).unwrap().is_empty());
+    assert!(git::export_refs(mut_repo, &GitSettings::default())
+        .unwrap()
+        .is_empty());
     assert_eq!(
         mut_repo.get_git_ref("refs/heads/main"),
         RefTarget::normal(new_commit.id().clone())
@@ -1595,7 +1772,9 @@ fn test_export_refs_unborn_git_bookmark(move_placeholder_ref: bool) {
     git::import_head(mut_repo).unwrap();
     git::import_refs(mut_repo, &git_settings).unwrap();
     mut_repo.rebase_descendants(&test_data.settings).unwrap();
-    assert!(git::export_refs(mut_repo).unwrap().is_empty());
+    assert!(git::export_refs(mut_repo, &GitSettings::default())
+        .unwrap()
+        .is_empty());
     assert!(git_repo.head().is_err(), "HEAD is unborn");
 
     let new_commit = write_random_commit(mut_repo, &test_data.settings);
@@ -1605,7 +1784,9 @@ fn test_export_refs_unborn_git_bookmark(move_placeholder_ref: bool) {
             .reference("refs/jj/root", git_id(&new_commit), false, "")
             .unwrap();
     }
-    assert!(git::export_refs(mut_repo).unwrap().is_empty());
+    assert!(git::export_refs(mut_repo, &GitSettings::default())
+        .unwrap()
+        .is_empty());
     assert_eq!(
         mut_repo.get_git_ref("refs/heads/main"),
         RefTarget::normal(new_commit.id().clone())
@@ -1653,7 +1834,9 @@ fn test_export_import_sequence() {
     mut_repo.set_local_bookmark_target("main", RefTarget::normal(commit_b.id().clone()));
 
     // Export the bookmark to git
-    assert!(git::export_refs(mut_repo).unwrap().is_empty());
+    assert!(git::export_refs(mut_repo, &GitSettings::default())
+        .unwrap()
+        .is_empty());
     assert_eq!(
         mut_repo.get_git_ref("refs/heads/main"),
         RefTarget::normal(commit_b.id().clone())
@@ -1707,7 +1890,9 @@ fn test_import_export_non_tracking_bookmark() {
     );
 
     // Export the bookmark to git
-    assert!(git::export_refs(mut_repo).unwrap().is_empty());
+    assert!(git::export_refs(mut_repo, &GitSettings::default())
+        .unwrap()
+        .is_empty());
     assert_eq!(mut_repo.get_git_ref("refs/heads/main"), RefTarget::absent());
 
     // Reimport with auto-local-bookmark on. Local bookmark shouldn't be created for
@@ -1777,7 +1962,9 @@ fn test_export_conflicts() {
     let commit_c = write_random_commit(mut_repo, &test_data.settings);
     mut_repo.set_local_bookmark_target("main", RefTarget::normal(commit_a.id().clone()));
     mut_repo.set_local_bookmark_target("feature", RefTarget::normal(commit_a.id().clone()));
-    assert!(git::export_refs(mut_repo).unwrap().is_empty());
+    assert!(git::export_refs(mut_repo, &GitSettings::default())
+        .unwrap()
+        .is_empty());
 
     // Create a conflict and export. It should not be exported, but other changes
     // should be.
@@ -1789,7 +1976,9 @@ fn test_export_conflicts() {
             [commit_b.id().clone(), commit_c.id().clone()],
         ),
     );
-    assert!(git::export_refs(mut_repo).unwrap().is_empty());
+    assert!(git::export_refs(mut_repo, &GitSettings::default())
+        .unwrap()
+        .is_empty());
     assert_eq!(
         git_repo
             .find_reference("refs/heads/feature")
@@ -1834,12 +2023,44 @@ fn test_export_bookmark_on_root_commit() {
         "on_root",
         RefTarget::normal(mut_repo.store().root_commit_id().clone()),
     );
-    let failed = git::export_refs(mut_repo).unwrap();
+    let failed = git::export_refs(mut_repo, &GitSettings::default()).unwrap();
     assert_eq!(failed.len(), 1);
     assert_eq!(failed[0].name, RefName::LocalBranch("on_root".to_string()));
     assert_matches!(failed[0].reason, FailedRefExportReason::OnRootCommit);
 }
 
+#[test]
+fn test_export_refs_bookmark_patterns() {
+    // Bookmarks not matching the export patterns stay local to jj
+    let test_data = GitRepoData::create();
+    let git_settings = GitSettings {
+        export_bookmarks: Some(vec![StringPattern::exact("main")]),
+        ..Default::default()
+    };
+    let git_repo = test_data.git_repo;
+    let commit = empty_git_commit(&git_repo, "refs/heads/main", &[]);
+    let mut tx = test_data.repo.start_transaction(&test_data.settings);
+    let mut_repo = tx.repo_mut();
+    git::import_refs(mut_repo, &git_settings).unwrap();
+    mut_repo.rebase_descendants(&test_data.settings).unwrap();
+
+    let new_commit = create_random_commit(mut_repo, &test_data.settings)
+        .set_parents(vec![jj_id(&commit)])
+        .write()
+        .unwrap();
+    mut_repo.set_local_bookmark_target("main", RefTarget::normal(new_commit.id().clone()));
+    mut_repo.set_local_bookmark_target("local-only", RefTarget::normal(jj_id(&commit)));
+    assert!(git::export_refs(mut_repo, &git_settings)
+        .unwrap()
+        .is_empty());
+    assert_eq!(
+        git_repo.find_reference("refs/heads/main").unwrap().target(),
+        Some(git_id(&new_commit))
+    );
+    assert!(git_repo.find_reference("refs/heads/local-only").is_err());
+    assert!(mut_repo.get_git_ref("refs/heads/local-only").is_absent());
+}
+
 #[test]
 fn test_export_partial_failure() {
     // Check that we skip bookmarks that fail to export
@@ -1857,7 +2078,7 @@ fn test_export_partial_failure() {
     // `main/sub` will conflict with `main` in Git, at least when using loose ref
     // storage
     mut_repo.set_local_bookmark_target("main/sub", target.clone());
-    let failed = git::export_refs(mut_repo).unwrap();
+    let failed = git::export_refs(mut_repo, &GitSettings::default()).unwrap();
     assert_eq!(failed.len(), 3);
     assert_eq!(failed[0].name, RefName::LocalBranch("".to_string()));
     assert_matches!(failed[0].reason, FailedRefExportReason::InvalidGitName);
@@ -1894,7 +2115,7 @@ fn test_export_partial_failure() {
     // Now remove the `main` bookmark and make sure that the `main/sub` gets
     // exported even though it didn't change
     mut_repo.set_local_bookmark_target("main", RefTarget::absent());
-    let failed = git::export_refs(mut_repo).unwrap();
+    let failed = git::export_refs(mut_repo, &GitSettings::default()).unwrap();
     assert_eq!(failed.len(), 2);
     assert_eq!(failed[0].name, RefName::LocalBranch("".to_string()));
     assert_matches!(failed[0].reason, FailedRefExportReason::InvalidGitName);
@@ -1961,7 +2182,9 @@ fn test_export_reexport_transitions() {
     ] {
         mut_repo.set_local_bookmark_target(bookmark, RefTarget::normal(commit_a.id().clone()));
     }
-    assert!(git::export_refs(mut_repo).unwrap().is_empty());
+    assert!(git::export_refs(mut_repo, &GitSettings::default())
+        .unwrap()
+        .is_empty());
 
     // Make changes on the jj side
     for bookmark in ["AXA", "AXB", "AXX"] {
@@ -2016,7 +2239,7 @@ fn test_export_reexport_transitions() {
     // export. They should have been unchanged in git and in
     // mut_repo.view().git_refs().
     assert_eq!(
-        git::export_refs(mut_repo)
+        git::export_refs(mut_repo, &GitSettings::default())
             .unwrap()
             .into_iter()
             .map(|failed| failed.name)
@@ -2090,7 +2313,9 @@ fn test_export_undo_reexport() {
     let commit_a = write_random_commit(mut_repo, &test_data.settings);
     let target_a = RefTarget::normal(commit_a.id().clone());
     mut_repo.set_local_bookmark_target("main", target_a.clone());
-    assert!(git::export_refs(mut_repo).unwrap().is_empty());
+    assert!(git::export_refs(mut_repo, &GitSettings::default())
+        .unwrap()
+        .is_empty());
     assert_eq!(
         git_repo.find_reference("refs/heads/main").unwrap().target(),
         Some(git_id(&commit_a))
@@ -2108,7 +2333,9 @@ fn test_export_undo_reexport() {
     mut_repo.set_remote_bookmark("main", "git", RemoteRef::absent());
 
     // Reexport should update the Git-tracking bookmark
-    assert!(git::export_refs(mut_repo).unwrap().is_empty());
+    assert!(git::export_refs(mut_repo, &GitSettings::default())
+        .unwrap()
+        .is_empty());
     assert_eq!(
         git_repo.find_reference("refs/heads/main").unwrap().target(),
         Some(git_id(&commit_a))
@@ -2181,6 +2408,69 @@ fn test_reset_head_to_root() {
     assert!(git_repo.find_reference("refs/jj/root").is_err());
 }
 
+#[test]
+fn test_reset_head_writes_reflog() {
+    // Create colocated workspace
+    let settings = testutils::user_settings();
+    let temp_dir = testutils::new_temp_dir();
+    let workspace_root = temp_dir.path().join("repo");
+    let git_repo = git2::Repository::init(&workspace_root).unwrap();
+    let (_workspace, repo) =
+        Workspace::init_external_git(&settings, &workspace_root, &workspace_root.join(".git"))
+            .unwrap();
+
+    let mut tx = repo.start_transaction(&settings);
+    let mut_repo = tx.repo_mut();
+
+    let root_commit_id = repo.store().root_commit_id();
+    let tree_id = repo.store().empty_merged_tree_id();
+    let file_path = RepoPath::from_internal_string("file.txt");
+    let tree2 = testutils::create_tree(&repo, &[(file_path, "contents\n")]);
+    let commit1 = mut_repo
+        .new_commit(&settings, vec![root_commit_id.clone()], tree_id.clone())
+        .write()
+        .unwrap();
+    let commit2 = mut_repo
+        .new_commit(&settings, vec![commit1.id().clone()], tree2.id())
+        .write()
+        .unwrap();
+    let commit3 = mut_repo
+        .new_commit(&settings, vec![commit2.id().clone()], tree2.id())
+        .write()
+        .unwrap();
+
+    git::reset_head(tx.repo_mut(), &git_repo, &commit2).unwrap();
+    git::reset_head(tx.repo_mut(), &git_repo, &commit3).unwrap();
+    // Resetting to the same parent doesn't add an entry
+    git::reset_head(tx.repo_mut(), &git_repo, &commit3).unwrap();
+
+    let reflog = git_repo.reflog("HEAD").unwrap();
+    let messages = reflog
+        .iter()
+        .map(|entry| entry.message().unwrap().to_owned())
+        .collect_vec();
+    assert_eq!(
+        messages,
+        vec![
+            format!(
+                "checkout: moving from {} to {}",
+                commit1.id().hex(),
+                commit2.id().hex()
+            ),
+            format!("checkout: moving from (unborn) to {}", commit1.id().hex()),
+        ]
+    );
+    assert!(git_repo.head_detached().unwrap());
+
+    // The index matches the new HEAD
+    let head_tree = git_repo.head().unwrap().peel_to_tree().unwrap();
+    let diff = git_repo
+        .diff_tree_to_index(Some(&head_tree), None, None)
+        .unwrap();
+    assert_eq!(diff.deltas().len(), 0);
+    assert_eq!(git_repo.index().unwrap().len(), 1);
+}
+
 #[test]
 fn test_reset_head_with_index() {
     // Create colocated workspace
@@ -2266,7 +2556,7 @@ fn test_fetch_empty_repo() {
         tx.repo_mut(),
         &test_data.git_repo,
         "origin",
-        &[StringPattern::everything()],
+        &git::BranchPatterns::everything(),
         git::RemoteCallbacks::default(),
         &git_settings,
         None,
@@ -2293,7 +2583,7 @@ fn test_fetch_initial_commit_head_is_not_set() {
         tx.repo_mut(),
         &test_data.git_repo,
         "origin",
-        &[StringPattern::everything()],
+        &git::BranchPatterns::everything(),
         git::RemoteCallbacks::default(),
         &git_settings,
         None,
@@ -2354,7 +2644,7 @@ fn test_fetch_initial_commit_head_is_set() {
         tx.repo_mut(),
         &test_data.git_repo,
         "origin",
-        &[StringPattern::everything()],
+        &git::BranchPatterns::everything(),
         git::RemoteCallbacks::default(),
         &git_settings,
         None,
@@ -2379,7 +2669,7 @@ fn test_fetch_success() {
         tx.repo_mut(),
         &test_data.git_repo,
         "origin",
-        &[StringPattern::everything()],
+        &git::BranchPatterns::everything(),
         git::RemoteCallbacks::default(),
         &git_settings,
         None,
@@ -2403,7 +2693,7 @@ fn test_fetch_success() {
         tx.repo_mut(),
         &test_data.git_repo,
         "origin",
-        &[StringPattern::everything()],
+        &git::BranchPatterns::everything(),
         git::RemoteCallbacks::default(),
         &git_settings,
         None,
@@ -2461,7 +2751,7 @@ fn test_fetch_prune_deleted_ref() {
         tx.repo_mut(),
         &test_data.git_repo,
         "origin",
-        &[StringPattern::everything()],
+        &git::BranchPatterns::everything(),
         git::RemoteCallbacks::default(),
         &git_settings,
         None,
@@ -2485,7 +2775,7 @@ fn test_fetch_prune_deleted_ref() {
         tx.repo_mut(),
         &test_data.git_repo,
         "origin",
-        &[StringPattern::everything()],
+        &git::BranchPatterns::everything(),
         git::RemoteCallbacks::default(),
         &git_settings,
         None,
@@ -2513,7 +2803,7 @@ fn test_fetch_no_default_branch() {
         tx.repo_mut(),
         &test_data.git_repo,
         "origin",
-        &[StringPattern::everything()],
+        &git::BranchPatterns::everything(),
         git::RemoteCallbacks::default(),
         &git_settings,
         None,
@@ -2537,7 +2827,7 @@ fn test_fetch_no_default_branch() {
         tx.repo_mut(),
         &test_data.git_repo,
         "origin",
-        &[StringPattern::everything()],
+        &git::BranchPatterns::everything(),
         git::RemoteCallbacks::default(),
         &git_settings,
         None,
@@ -2559,7 +2849,7 @@ fn test_fetch_empty_refspecs() {
         tx.repo_mut(),
         &test_data.git_repo,
         "origin",
-        &[],
+        &git::BranchPatterns::default(),
         git::RemoteCallbacks::default(),
         &git_settings,
         None,
@@ -2586,7 +2876,7 @@ fn test_fetch_no_such_remote() {
         tx.repo_mut(),
         &test_data.git_repo,
         "invalid-remote",
-        &[StringPattern::everything()],
+        &git::BranchPatterns::everything(),
         git::RemoteCallbacks::default(),
         &git_settings,
         None,
@@ -2703,15 +2993,17 @@ fn test_push_bookmarks_success() {
                 new_target: Some(setup.child_of_main_commit.id().clone()),
             },
         )],
+        tag_updates: vec![],
     };
     let result = git::push_branches(
         tx.repo_mut(),
         &clone_repo,
         "origin",
         &targets,
+        &GitPushOptions::default(),
         git::RemoteCallbacks::default(),
     );
-    assert_eq!(result, Ok(()));
+    assert_eq!(result, Ok(GitPushStats { atomic: false }));
 
     // Check that the ref got updated in the source repo
     let source_repo = git2::Repository::open(&setup.source_repo_dir).unwrap();
@@ -2772,15 +3064,17 @@ fn test_push_bookmarks_deletion() {
                 new_target: None,
             },
         )],
+        tag_updates: vec![],
     };
     let result = git::push_branches(
         tx.repo_mut(),
         &get_git_repo(&setup.jj_repo),
         "origin",
         &targets,
+        &GitPushOptions::default(),
         git::RemoteCallbacks::default(),
     );
-    assert_eq!(result, Ok(()));
+    assert_eq!(result, Ok(GitPushStats { atomic: false }));
 
     // Check that the ref got deleted in the source repo
     assert!(source_repo.find_reference("refs/heads/main").is_err());
@@ -2829,15 +3123,17 @@ fn test_push_bookmarks_mixed_deletion_and_addition() {
                 },
             ),
         ],
+        tag_updates: vec![],
     };
     let result = git::push_branches(
         tx.repo_mut(),
         &clone_repo,
         "origin",
         &targets,
+        &GitPushOptions::default(),
         git::RemoteCallbacks::default(),
     );
-    assert_eq!(result, Ok(()));
+    assert_eq!(result, Ok(GitPushStats { atomic: false }));
 
     // Check that the topic ref got updated in the source repo
     let source_repo = git2::Repository::open(&setup.source_repo_dir).unwrap();
@@ -2888,15 +3184,17 @@ fn test_push_bookmarks_not_fast_forward() {
                 new_target: Some(setup.sideways_commit.id().clone()),
             },
         )],
+        tag_updates: vec![],
     };
     let result = git::push_branches(
         tx.repo_mut(),
         &get_git_repo(&setup.jj_repo),
         "origin",
         &targets,
+        &GitPushOptions::default(),
         git::RemoteCallbacks::default(),
     );
-    assert_eq!(result, Ok(()));
+    assert_eq!(result, Ok(GitPushStats { atomic: false }));
 
     // Check that the ref got updated in the source repo
     let source_repo = git2::Repository::open(&setup.source_repo_dir).unwrap();
@@ -2939,6 +3237,7 @@ fn test_push_updates_unexpectedly_moved_sideways_on_remote() {
             &get_git_repo(&setup.jj_repo),
             "origin",
             &targets,
+            &GitPushOptions::default(),
             git::RemoteCallbacks::default(),
         )
     };
@@ -2972,7 +3271,7 @@ fn test_push_updates_unexpectedly_moved_sideways_on_remote() {
     // Moving the bookmark to the same place it already is is OK.
     assert_eq!(
         attempt_push_expecting_sideways(Some(setup.main_commit.id().clone())),
-        Ok(())
+        Ok(GitPushStats { atomic: false })
     );
 }
 
@@ -3006,6 +3305,7 @@ fn test_push_updates_unexpectedly_moved_forward_on_remote() {
             &get_git_repo(&setup.jj_repo),
             "origin",
             &targets,
+            &GitPushOptions::default(),
             git::RemoteCallbacks::default(),
         )
     };
@@ -3034,7 +3334,7 @@ fn test_push_updates_unexpectedly_moved_forward_on_remote() {
     // conflict resolution rules
     assert_eq!(
         attempt_push_expecting_parent(Some(setup.child_of_main_commit.id().clone())),
-        Ok(())
+        Ok(GitPushStats { atomic: false })
     );
 }
 
@@ -3064,6 +3364,7 @@ fn test_push_updates_unexpectedly_exists_on_remote() {
             &get_git_repo(&setup.jj_repo),
             "origin",
             &targets,
+            &GitPushOptions::default(),
             git::RemoteCallbacks::default(),
         )
     };
@@ -3076,7 +3377,7 @@ fn test_push_updates_unexpectedly_exists_on_remote() {
     // We *can* move the bookmark forward even if we didn't expect it to exist
     assert_eq!(
         attempt_push_expecting_absence(Some(setup.child_of_main_commit.id().clone())),
-        Ok(())
+        Ok(GitPushStats { atomic: false })
     );
 }
 
@@ -3095,9 +3396,10 @@ fn test_push_updates_success() {
             expected_current_target: Some(setup.main_commit.id().clone()),
             new_target: Some(setup.child_of_main_commit.id().clone()),
         }],
+        &GitPushOptions::default(),
         git::RemoteCallbacks::default(),
     );
-    assert_eq!(result, Ok(()));
+    assert_eq!(result, Ok(GitPushStats { atomic: false }));
 
     // Check that the ref got updated in the source repo
     let source_repo = git2::Repository::open(&setup.source_repo_dir).unwrap();
@@ -3132,6 +3434,7 @@ fn test_push_updates_no_such_remote() {
             expected_current_target: Some(setup.main_commit.id().clone()),
             new_target: Some(setup.child_of_main_commit.id().clone()),
         }],
+        &GitPushOptions::default(),
         git::RemoteCallbacks::default(),
     );
     assert!(matches!(result, Err(GitPushError::NoSuchRemote(_))));
@@ -3151,11 +3454,174 @@ fn test_push_updates_invalid_remote() {
             expected_current_target: Some(setup.main_commit.id().clone()),
             new_target: Some(setup.child_of_main_commit.id().clone()),
         }],
+        &GitPushOptions::default(),
         git::RemoteCallbacks::default(),
     );
     assert!(matches!(result, Err(GitPushError::NoSuchRemote(_))));
 }
 
+#[test]
+fn test_push_bookmarks_atomic() {
+    let settings = testutils::user_settings();
+    let temp_dir = testutils::new_temp_dir();
+    let setup = set_up_push_repos(&settings, &temp_dir);
+    let mut tx = setup.jj_repo.start_transaction(&settings);
+
+    let targets = GitBranchPushTargets {
+        branch_updates: vec![
+            (
+                "main".to_owned(),
+                BookmarkPushUpdate {
+                    old_target: Some(setup.main_commit.id().clone()),
+                    new_target: Some(setup.child_of_main_commit.id().clone()),
+                },
+            ),
+            (
+                "topic".to_owned(),
+                BookmarkPushUpdate {
+                    old_target: None,
+                    new_target: Some(setup.sideways_commit.id().clone()),
+                },
+            ),
+        ],
+        tag_updates: vec![],
+    };
+    let result = git::push_branches(
+        tx.repo_mut(),
+        &get_git_repo(&setup.jj_repo),
+        "origin",
+        &targets,
+        &GitPushOptions {
+            atomic: true,
+            ..Default::default()
+        },
+        git::RemoteCallbacks::default(),
+    );
+    assert_eq!(result, Ok(GitPushStats { atomic: true }));
+
+    // Check that both refs got updated in the source repo
+    let source_repo = git2::Repository::open(&setup.source_repo_dir).unwrap();
+    let get_source_target = |name| source_repo.find_reference(name).unwrap().target();
+    assert_eq!(
+        get_source_target("refs/heads/main"),
+        Some(git_id(&setup.child_of_main_commit))
+    );
+    assert_eq!(
+        get_source_target("refs/heads/topic"),
+        Some(git_id(&setup.sideways_commit))
+    );
+
+    // Check that the repo view got updated
+    let view = tx.repo_mut().view();
+    assert_eq!(
+        view.get_remote_bookmark("topic", "origin").target,
+        RefTarget::normal(setup.sideways_commit.id().clone()),
+    );
+}
+
+#[test]
+fn test_push_bookmarks_atomic_unexpected_location() {
+    let settings = testutils::user_settings();
+    let temp_dir = testutils::new_temp_dir();
+    let setup = set_up_push_repos(&settings, &temp_dir);
+    let mut tx = setup.jj_repo.start_transaction(&settings);
+
+    // `main` is not at the expected location, so `topic` shouldn't be created
+    // either.
+    let targets = GitBranchPushTargets {
+        branch_updates: vec![
+            (
+                "main".to_owned(),
+                BookmarkPushUpdate {
+                    old_target: Some(setup.sideways_commit.id().clone()),
+                    new_target: Some(setup.child_of_main_commit.id().clone()),
+                },
+            ),
+            (
+                "topic".to_owned(),
+                BookmarkPushUpdate {
+                    old_target: None,
+                    new_target: Some(setup.sideways_commit.id().clone()),
+                },
+            ),
+        ],
+        tag_updates: vec![],
+    };
+    let result = git::push_branches(
+        tx.repo_mut(),
+        &get_git_repo(&setup.jj_repo),
+        "origin",
+        &targets,
+        &GitPushOptions {
+            atomic: true,
+            ..Default::default()
+        },
+        git::RemoteCallbacks::default(),
+    );
+    assert_eq!(
+        result,
+        Err(GitPushError::RefInUnexpectedLocation(vec![
+            "refs/heads/main".to_owned()
+        ]))
+    );
+
+    let source_repo = git2::Repository::open(&setup.source_repo_dir).unwrap();
+    assert_eq!(
+        source_repo
+            .find_reference("refs/heads/main")
+            .unwrap()
+            .target(),
+        Some(git_id(&setup.main_commit))
+    );
+    assert!(source_repo.find_reference("refs/heads/topic").is_err());
+}
+
+#[test]
+fn test_push_bookmarks_atomic_unsupported() {
+    let settings = testutils::user_settings();
+    let temp_dir = testutils::new_temp_dir();
+    let setup = set_up_push_repos(&settings, &temp_dir);
+    let mut tx = setup.jj_repo.start_transaction(&settings);
+
+    let source_repo = git2::Repository::open(&setup.source_repo_dir).unwrap();
+    source_repo
+        .config()
+        .unwrap()
+        .set_bool("receive.advertiseAtomic", false)
+        .unwrap();
+
+    let targets = GitBranchPushTargets {
+        branch_updates: vec![(
+            "main".to_owned(),
+            BookmarkPushUpdate {
+                old_target: Some(setup.main_commit.id().clone()),
+                new_target: Some(setup.child_of_main_commit.id().clone()),
+            },
+        )],
+        tag_updates: vec![],
+    };
+    let result = git::push_branches(
+        tx.repo_mut(),
+        &get_git_repo(&setup.jj_repo),
+        "origin",
+        &targets,
+        &GitPushOptions {
+            atomic: true,
+            ..Default::default()
+        },
+        git::RemoteCallbacks::default(),
+    );
+    // Falls back to a non-atomic push
+    assert_eq!(result, Ok(GitPushStats { atomic: false }));
+    assert_eq!(
+        source_repo
+            .find_reference("refs/heads/main")
+            .unwrap()
+            .target(),
+        Some(git_id(&setup.child_of_main_commit))
+    );
+}
+
 #[test]
 fn test_bulk_update_extra_on_import_refs() {
     let settings = testutils::user_settings();
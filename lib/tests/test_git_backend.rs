@@ -14,6 +14,7 @@
 
 use std::collections::HashMap;
 use std::collections::HashSet;
+use std::io::Read as _;
 use std::path::Path;
 use std::process::Command;
 use std::sync::Arc;
@@ -23,6 +24,7 @@ use std::time::SystemTime;
 use futures::executor::block_on_stream;
 use jj_lib::backend::CommitId;
 use jj_lib::backend::CopyRecord;
+use jj_lib::backend::FileId;
 use jj_lib::commit::Commit;
 use jj_lib::git_backend::GitBackend;
 use jj_lib::repo::ReadonlyRepo;
@@ -30,6 +32,7 @@ use jj_lib::repo::Repo;
 use jj_lib::repo_path::RepoPath;
 use jj_lib::repo_path::RepoPathBuf;
 use jj_lib::settings::UserSettings;
+use jj_lib::signing::Signer;
 use jj_lib::store::Store;
 use jj_lib::transaction::Transaction;
 use maplit::hashset;
@@ -295,3 +298,72 @@ fn test_copy_detection() {
         HashMap::default(),
     );
 }
+
+#[test]
+fn test_partial_clone_fetches_missing_objects() {
+    // TODO: Better way to disable the test if git command couldn't be executed
+    if Command::new("git").arg("--version").status().is_err() {
+        eprintln!("Skipping because git command might fail to run");
+        return;
+    }
+
+    let settings = testutils::user_settings();
+    let temp_dir = testutils::new_temp_dir();
+    let source_path = temp_dir.path().join("source");
+    let clone_path = temp_dir.path().join("clone");
+    let store_path = temp_dir.path().join("store");
+
+    let source_repo = git2::Repository::init_bare(&source_path).unwrap();
+    let mut source_config = source_repo.config().unwrap();
+    source_config
+        .set_bool("uploadpack.allowFilter", true)
+        .unwrap();
+    source_config
+        .set_bool("uploadpack.allowAnySHA1InWant", true)
+        .unwrap();
+    let blob_oid = source_repo.blob(b"content\n").unwrap();
+    let mut tree_builder = source_repo.treebuilder(None).unwrap();
+    tree_builder.insert("file", blob_oid, 0o100644).unwrap();
+    let tree_oid = tree_builder.write().unwrap();
+    let tree = source_repo.find_tree(tree_oid).unwrap();
+    let signature = git2::Signature::now("Someone", "someone@example.com").unwrap();
+    source_repo
+        .commit(
+            Some("refs/heads/main"),
+            &signature,
+            &signature,
+            "initial",
+            &tree,
+            &[],
+        )
+        .unwrap();
+
+    let status = Command::new("git")
+        .args(["clone", "--quiet", "--bare", "--filter=blob:none"])
+        .arg(format!("file://{}", source_path.display()))
+        .arg(&clone_path)
+        .status()
+        .unwrap();
+    assert!(status.success());
+
+    std::fs::create_dir(&store_path).unwrap();
+    let backend = GitBackend::init_external(&settings, &store_path, &clone_path).unwrap();
+    let has_blob = || {
+        let clone_repo = git2::Repository::open(&clone_path).unwrap();
+        let exists = clone_repo.odb().unwrap().exists(blob_oid);
+        exists
+    };
+    assert!(!has_blob());
+    let store = Store::new(Box::new(backend), Signer::new(None, vec![]), false);
+
+    // The blob is fetched when it's read for the first time.
+    let file_id = FileId::from_bytes(blob_oid.as_bytes());
+    let mut content = String::new();
+    store
+        .read_file(RepoPath::from_internal_string("file"), &file_id)
+        .unwrap()
+        .read_to_string(&mut content)
+        .unwrap();
+    assert_eq!(content, "content\n");
+    assert!(has_blob());
+}
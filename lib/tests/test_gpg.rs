@@ -9,6 +9,7 @@ use std::process::Stdio;
 use assert_matches::assert_matches;
 use insta::assert_debug_snapshot;
 use jj_lib::gpg_signing::GpgBackend;
+use jj_lib::gpg_signing::GpgsmBackend;
 use jj_lib::signing::SigStatus;
 use jj_lib::signing::SignError;
 use jj_lib::signing::SigningBackend;
@@ -216,3 +217,199 @@ fn invalid_signature() {
         Err(SignError::InvalidSignatureFormat)
     );
 }
+
+// Self-signed certificate and RSA key of "Someone (jj test signing key)", as an
+// unencrypted PKCS#12 bundle.
+static GPGSM_PRIVATE_KEY: &str = r#"-----BEGIN PKCS12-----
+MIIJyQIBAzCCCY8GCSqGSIb3DQEHAaCCCYAEggl8MIIJeDCCBC8GCSqGSIb3DQEH
+BqCCBCAwggQcAgEAMIIEFQYJKoZIhvcNAQcBMBwGCiqGSIb3DQEMAQYwDgQINlyJ
+tDru30MCAggAgIID6NoAFRdjHMGiRZis8CInE6cGhp9uzHYXdlAHwxHPKWmfxU/I
+Ltws266/2I78QY8pqZ9gjcd3/fxiWnK5IMEUMEfYnPbjhtv6NMoO8m5rpLfeK4c6
+9sstNN7vrgaVfHJI6c8YsL5Z31wU1RiV/v2Rx3WEeNH++Kr3YHSCwdRSy9ivvAYj
+e9y0HPiEpHXzYHsMAQhkGS3eodMa4d0galwTdcEerAEmcaKfVitJTfAZlaZqPGS+
+5fLC337jEd3tMVK0kfTtdp2dG4asV9A8bJeTrg1J2DWXhVE0p8Q3abWq++mWlIJB
+h9Zsz7wK+MtiCXFOMp11uhXYgOCB53+NN36tI+4rm/5pRF5V5OIvjx8bKOJhl6xV
+ICEAjTIILpTokTJu60LpsL+UqIbEsNarrPG9EJLiCuwdvBHhpo0lnWlTHhjkuLkd
+mOrRsKINyJkv1zJ7FcplV09trkm2uyexDtqcOCls7WNAO61OGjTTVUpxQNMn2Ny2
+qi4h3lfIZ5pd1AuCd622nYPsAEX3GQto+TFbOBasN/ZTloWGcEEDYkbBOruTe3WC
+WEuncnqUpkpnUsOcDEgwDz6Gr0sGuZVEofYTgoLB2uDNV0AO+hQlCgnkTtrgZoxa
+6Rxl/vgrRubPUWEjn51peoOSnORjhlwCOfp64CuIxs/QtLtUaorriOogF6OzWX47
+HaA85YLq9ztDoOufn/FeaWno3tGReXcSU7xoS6HPG6+eIeKO11d8aqdjJpLVUpW0
+Tl6gSkKwZLfsowswBuzlvG7SS+byKL5WHP9h2JlEeIx08hRqLyAuz7scdh7+n+ei
+rCeHwHWD6ZDSBXD5rdjPuZe7xAguLeP1hQEdRxLrL/mHPZ81Qm+WhOBazT7FyClK
+uR20Eylk4QCide7NPhoR/55VR0cyMhJo84oBlqNdDxGRdbymKziBnh21lQby8ts2
+cOGSTUV3HwuzoEQhgemxWYczhvy6cJW8geO62A9eKoauiTsqCVTHZGydzXt7EAHG
+oKZYB3BAlTMdM/tXBNlhd+ydJJkHaItvjcK7Kfgrh+IkGfbY0GhiW1d7xI8BL0v0
+UwVLPzE3ogOV3udmx4hvSIa+cn/0qdt9664cqQc+/jaFhc/lhlFAKUR00mjG3STX
+paH5i73jHbyhmJWvcB95m8tYOKqjZWgWBS5i31sJ5mws5txHXHJ7xb5yCNm/SMTn
+E4hSYWiEjGk6C5SlVJ74tVThXJpBPXQXFW7JHfY0Z6L2ljP2E3l7lWJ2hwe27kTV
+bYRxrW5+nVv1PyAG+L13VQGJKIEKHLveNv7aOAViFWHLhH652FxZcMS0TqNxa6VS
+O+ZitmEwggVBBgkqhkiG9w0BBwGgggUyBIIFLjCCBSowggUmBgsqhkiG9w0BDAoB
+AqCCBO4wggTqMBwGCiqGSIb3DQEMAQMwDgQIVnGgRLrGed4CAggABIIEyBlUPmbp
+18IRdurGBfy9b32ICtqf3tVB3tIViSxFGvUj7t3ArvDOZF4zH75C5qgTb2YpSTLo
+SWZAQul0hkGGzEBsyODzwPxbSATW6QKzeCuUnz2NYDTjTSSuA/K6IxJEGt2zAGv0
+55+2MChbOS8BBwxzP64pnxB5q7BI6XIJuV+PYKmvmKgYzESokGnftDv4U8KQIVVu
+umfInGRQ/KRZZKaUEBh7NWvyP3DTCO20CNaLNXgs8O6Da4ccpdgs3cdkEjIcsiYa
+wY1agdsNg7TFNBEFL5dbEXHlI0eAn51VUaVBhD8LfjADTSpHM6u6hgNY8ra9nXtR
+aTeb2ykJJtPX3cYXyP5x5dbxE2jQju6thv4K0dKuTJWsSXLEMhid7FyZNFaXtNMW
+BTakP0GCJZJXbgU15Dpg6quYu8w5e3PQnJFWXOJtTvziuPMt3m0eImV0RBbRS7fC
+vuEq5vA2nln1vlQuIERnK3mUpAWL3IDzP2Zh8GwQLXq7zNQCvu0Ky/uNMtJc9oPi
+g3wPdtPRlvfZ4YWV52olQis8v1Cpnduq/AOhfdtpnQSXrYFkXBg5IAx6uZHVOPOu
+msAqiW5aUnMAgn5OhqBHo0boVS9mhhK/vtRsMqrzcC6opEKzoZqYy5VYx7/3uWws
+SmL5e0ZbOvMgteYtfOrktRj16gv3WdOnS5oC9KDffxx01CB0z3CIrWds5yirg3y2
+9anT88T3Ihsg/jJftum/P7SwpqsC38tGw60FyuuST5Iie8iJQjHpopf0Qv9fvM1P
+9oRhOETt/yCmDGJinKq/sV22NZpKpwcV/OPv3JCzGkFcRKQ/TO2G1juDBlbPP26I
+xhWXHXVBt3d4CV0NsbdqDjXxknJfXs1bDCHN9NdGTSBwcnRQVwlRGUQAXPN5e6Ai
+COL3eV1oYnc6ugXJCq3RoZVtKb8klYV5HCetjJ1C3Fm0LqMMJ/XT0xTREttLhaPl
+6AmkJFJ44qw/ZyX9ZmXdegIhu2q3tW1JkXEPUKIzQ9su5Pnr/oNEfMHDM4lHFrZt
+GB061NlBQFltLLw1EZqooJI5HBAIzBYOCgVT1Xn3YMT9XpmlyGIbd77AdBUIeZYk
+yfBhNYbKwAMr/8K+Y9LZ+HIt+cPtewItnOcjhluRslr7DSMVRt9jdEyVBbkLdfHU
+OPYPxhESH7ASbD4ZItB76rd0mkwnpPHI6bTMnEdP0oIUQgy8V0V6kogHUL3VJCX0
+028+NYFXEahfLYLl0OQ9mjRa3tmUaO6iMD6JGu6p50kBh14ldfdZVBoV+r0YFhMb
+bnbPG1xE4XhriNty+pabtKx8xH7GmAyQYJ9zpmQUU5dVFVT4GQbptkRpV52PPj5K
+PSkc6vbKuhF+LJK8NO//69ckV4Qc6RhVwGNj/LYuMmue5N4EebM3ASQG6NxaJWH9
+sJVM0LiTMF65Ygv34kAtdVN85NhYW3DlaEcq6vEc+bTmuaF2FhObTHtLN+BkOwDw
+ZFj+mzg2Q6i3N8x4qmXkm2AQt93aZDxerpnf/bDcNJapd8ilosdRpi5bICDZD3qJ
+OUW65N48YIyuSEK4BAZvzPZUZMjhBYC8W/qoQWSsa2obQLwFAjQ4nZ1g/mV8dNTD
+gZhVuB6jVMhmoEyinGkia99PpzElMCMGCSqGSIb3DQEJFTEWBBS/h+bwiC50eQF5
+T4UOdAnRUiu6GDAxMCEwCQYFKw4DAhoFAAQUwhWacIWzVPdUptY7GdLcUzxswUIE
+CJpisVKauD4fAgIIAA==
+-----END PKCS12-----
+"#;
+
+static GPGSM_FINGERPRINT: &str = "BF87E6F0882E747901794F850E7409D1522BBA18";
+
+struct GpgsmEnvironment {
+    homedir: tempfile::TempDir,
+}
+
+impl GpgsmEnvironment {
+    fn new() -> Result<Self, std::process::Output> {
+        let dir = tempfile::Builder::new()
+            .prefix("jj-gpgsm-signing-test-")
+            .tempdir()
+            .unwrap();
+
+        let path = dir.path();
+
+        #[cfg(unix)]
+        std::fs::set_permissions(path, Permissions::from_mode(0o700)).unwrap();
+
+        // The bundle has an empty passphrase, which is passed to the agent
+        // through the loopback pinentry.
+        std::fs::write(path.join("gpg-agent.conf"), "allow-loopback-pinentry\n").unwrap();
+        std::fs::write(path.join("key.p12"), GPGSM_PRIVATE_KEY).unwrap();
+        // Trust the self-signed certificate as a root certificate.
+        std::fs::write(
+            path.join("trustlist.txt"),
+            format!("{GPGSM_FINGERPRINT} S relax\n"),
+        )
+        .unwrap();
+
+        let mut gpgsm = std::process::Command::new("gpgsm")
+            .arg("--homedir")
+            .arg(path)
+            .args(["--batch", "--pinentry-mode=loopback", "--passphrase-fd=0"])
+            .arg("--import")
+            .arg(path.join("key.p12"))
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .unwrap();
+
+        gpgsm.stdin.as_mut().unwrap().write_all(b"\n").unwrap();
+
+        let res = gpgsm.wait_with_output().unwrap();
+
+        if !res.status.success() {
+            eprintln!("Failed to add private key to gpg-agent. Make sure it is running!");
+            eprintln!("{}", String::from_utf8_lossy(&res.stderr));
+            return Err(res);
+        }
+
+        Ok(GpgsmEnvironment { homedir: dir })
+    }
+}
+
+macro_rules! gpgsm_guard {
+    () => {
+        if Command::new("gpgsm").arg("--version").status().is_err() {
+            eprintln!("Skipping test because gpgsm is not installed on the system");
+            return;
+        }
+    };
+}
+
+fn gpgsm_backend(env: &GpgsmEnvironment) -> GpgsmBackend {
+    GpgsmBackend::new("gpgsm".into(), false).with_extra_args(&[
+        "--homedir".into(),
+        env.homedir.path().as_os_str().into(),
+        "--disable-crl-checks".into(),
+    ])
+}
+
+#[test]
+#[cfg_attr(windows, ignore = "stuck randomly on Windows CI #3140")] // FIXME
+fn gpgsm_signing_roundtrip() {
+    gpgsm_guard!();
+
+    let env = GpgsmEnvironment::new().unwrap();
+    let backend = gpgsm_backend(&env);
+    let data = b"hello world";
+    let signature = backend.sign(data, None).unwrap();
+    assert!(backend.can_read(&signature));
+
+    let check = backend.verify(data, &signature).unwrap();
+    assert_eq!(check.status, SigStatus::Good);
+    assert_eq!(check.key.unwrap(), GPGSM_FINGERPRINT);
+    assert_eq!(
+        check.display.unwrap(),
+        "/CN=Someone (jj test signing key)/EMail=someone@example.com"
+    );
+
+    let check = backend.verify(b"so so bad", &signature).unwrap();
+    assert_eq!(check.status, SigStatus::Bad);
+    assert_eq!(check.key.unwrap(), GPGSM_FINGERPRINT);
+}
+
+#[test]
+#[cfg_attr(windows, ignore = "stuck randomly on Windows CI #3140")] // FIXME
+fn gpgsm_signing_roundtrip_explicit_key() {
+    gpgsm_guard!();
+
+    let env = GpgsmEnvironment::new().unwrap();
+    let backend = gpgsm_backend(&env);
+    let data = b"hello world";
+    let signature = backend.sign(data, Some("someone@example.com")).unwrap();
+
+    assert_debug_snapshot!(backend.verify(data, &signature).unwrap(), @r#"
+    Verification {
+        status: Good,
+        key: Some(
+            "BF87E6F0882E747901794F850E7409D1522BBA18",
+        ),
+        display: Some(
+            "/CN=Someone (jj test signing key)/EMail=someone@example.com",
+        ),
+    }
+    "#);
+}
+
+#[test]
+#[cfg_attr(windows, ignore = "stuck randomly on Windows CI #3140")] // FIXME
+fn gpgsm_invalid_signature() {
+    gpgsm_guard!();
+
+    let env = GpgsmEnvironment::new().unwrap();
+    let backend = gpgsm_backend(&env);
+    let signature = br"-----BEGIN SIGNED MESSAGE-----
+    super duper invalid
+    -----END SIGNED MESSAGE-----";
+
+    assert!(!GpgBackend::new("gpg".into(), false).can_read(signature));
+    assert_matches!(
+        backend.verify(b"a", signature),
+        Err(SignError::InvalidSignatureFormat)
+    );
+}
@@ -612,6 +612,65 @@ fn test_index_commits_incremental_squashed() {
     assert_eq!(commits_by_level(&repo), vec![71, 20]);
 }
 
+#[test]
+fn test_index_commits_flushed() {
+    let settings = testutils::user_settings();
+    let test_repo = TestRepo::init();
+    let repo = &test_repo.repo;
+
+    let root_commit = repo.store().root_commit();
+    let mut tx = repo.start_transaction(&settings);
+    let commit_a = child_commit(tx.repo_mut(), &settings, &root_commit)
+        .write()
+        .unwrap();
+    let commit_b = child_commit(tx.repo_mut(), &settings, &commit_a)
+        .write()
+        .unwrap();
+    tx.repo_mut().flush_index().unwrap();
+
+    // The flushed commits are saved in a new segment, and the in-memory part of
+    // the index is empty
+    let stats = as_mutable_composite(tx.repo()).stats();
+    assert_eq!(stats.num_commits, 1 + 2);
+    let levels: Vec<_> = stats.levels.iter().map(|level| level.num_commits).collect();
+    assert_eq!(levels, vec![1, 2, 0]);
+    let segment_name = stats.levels[1].name.as_ref().unwrap();
+    let segments_dir = test_repo.repo_path().join("index").join("segments");
+    assert!(segments_dir.join(segment_name).is_file());
+
+    // New commits can be added on top of the flushed ones
+    let commit_c = child_commit(tx.repo_mut(), &settings, &commit_b)
+        .write()
+        .unwrap();
+    let index = as_mutable_composite(tx.repo());
+    assert_eq!(index.num_commits(), 1 + 3);
+    assert_eq!(generation_number(index, commit_c.id()), 3);
+
+    let repo = tx.commit("test").unwrap();
+    let index = as_readonly_composite(&repo);
+    assert_eq!(index.num_commits(), 1 + 3);
+    assert_eq!(generation_number(index, commit_a.id()), 1);
+    assert_eq!(generation_number(index, commit_b.id()), 2);
+    assert_eq!(generation_number(index, commit_c.id()), 3);
+    assert_eq!(commits_by_level(&repo), vec![4]);
+}
+
+#[test]
+fn test_index_commits_flushed_automatically() {
+    let settings = testutils::user_settings();
+    let test_repo = TestRepo::init();
+    let repo = &test_repo.repo;
+
+    // The index is flushed once 10000 commits have been added
+    let mut tx = repo.start_transaction(&settings);
+    for _ in 0..10_001 {
+        write_random_commit(tx.repo_mut(), &settings);
+    }
+    let stats = as_mutable_composite(tx.repo()).stats();
+    let levels: Vec<_> = stats.levels.iter().map(|level| level.num_commits).collect();
+    assert_eq!(levels, vec![1, 10_000, 1]);
+}
+
 #[test]
 fn test_reindex_no_segments_dir() {
     let settings = testutils::user_settings();
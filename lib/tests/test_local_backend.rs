@@ -0,0 +1,198 @@
+// Copyright 2024 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fs;
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Duration;
+use std::time::SystemTime;
+
+use jj_lib::backend::FileId;
+use jj_lib::local_backend::LocalBackend;
+use jj_lib::object_id::ObjectId as _;
+use jj_lib::repo::ReadonlyRepo;
+use jj_lib::repo::Repo;
+use jj_lib::repo_path::RepoPath;
+use jj_lib::settings::UserSettings;
+use jj_lib::signing::Signer;
+use testutils::create_tree;
+use testutils::TestEnvironment;
+
+fn init_repo_with_shared_store(
+    settings: &UserSettings,
+    repo_path: &Path,
+    shared_store_path: &Path,
+) -> Arc<ReadonlyRepo> {
+    fs::create_dir(repo_path).unwrap();
+    ReadonlyRepo::init(
+        settings,
+        repo_path,
+        &|_settings, store_path| {
+            Ok(Box::new(LocalBackend::init_with_shared_store(
+                store_path,
+                shared_store_path,
+            )?))
+        },
+        Signer::from_settings(settings).unwrap(),
+        ReadonlyRepo::default_op_store_initializer(),
+        ReadonlyRepo::default_op_heads_store_initializer(),
+        ReadonlyRepo::default_index_store_initializer(),
+        ReadonlyRepo::default_submodule_store_initializer(),
+    )
+    .unwrap()
+}
+
+fn list_dir(dir: &Path) -> Vec<String> {
+    let mut names: Vec<_> = fs::read_dir(dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+        .collect();
+    names.sort();
+    names
+}
+
+#[test]
+fn test_shared_store_dedup() {
+    let settings = testutils::user_settings();
+    let test_env = TestEnvironment::init();
+    let shared_store_path = test_env.root().join("shared");
+    let repo1_path = test_env.root().join("repo1");
+    let repo2_path = test_env.root().join("repo2");
+    let repo1 = init_repo_with_shared_store(&settings, &repo1_path, &shared_store_path);
+    let repo2 = init_repo_with_shared_store(&settings, &repo2_path, &shared_store_path);
+    assert_eq!(list_dir(&shared_store_path.join("users")).len(), 2);
+
+    let path = RepoPath::from_internal_string("file");
+    let file_id1 = testutils::write_file(repo1.store(), path, "contents");
+    let file_id2 = testutils::write_file(repo2.store(), path, "contents");
+    assert_eq!(file_id1, file_id2);
+
+    // The object is stored once in the shared store
+    assert_eq!(
+        list_dir(&shared_store_path.join("files")),
+        vec![file_id1.hex()]
+    );
+    assert!(list_dir(&repo1_path.join("store").join("files")).is_empty());
+    assert!(list_dir(&repo2_path.join("store").join("files")).is_empty());
+
+    // Objects written by one repo can be read by the other
+    let file_id = testutils::write_file(repo1.store(), path, "written by repo1");
+    assert_eq!(
+        testutils::read_file(repo2.store(), path, &file_id),
+        b"written by repo1"
+    );
+
+    // The shared store is still used after reloading the repo
+    let repo1 = test_env.load_repo_at_head(&settings, &repo1_path);
+    let backend = repo1
+        .store()
+        .backend_impl()
+        .downcast_ref::<LocalBackend>()
+        .unwrap();
+    assert_eq!(
+        backend.shared_store_path(),
+        Some(shared_store_path.canonicalize().unwrap().as_path())
+    );
+}
+
+#[test]
+fn test_shared_store_gc() {
+    let settings = testutils::user_settings();
+    let test_env = TestEnvironment::init();
+    let shared_store_path = test_env.root().join("shared");
+    let repo1_path = test_env.root().join("repo1");
+    let repo2_path = test_env.root().join("repo2");
+    let repo1 = init_repo_with_shared_store(&settings, &repo1_path, &shared_store_path);
+    let repo2 = init_repo_with_shared_store(&settings, &repo2_path, &shared_store_path);
+
+    let path = RepoPath::from_internal_string("file");
+    let write_commit = |repo: &Arc<ReadonlyRepo>, contents: &str| {
+        let mut tx = repo.start_transaction(&settings);
+        let tree_id = create_tree(repo, &[(path, contents)]).id();
+        let commit = tx
+            .repo_mut()
+            .new_commit(
+                &settings,
+                vec![repo.store().root_commit_id().clone()],
+                tree_id,
+            )
+            .write()
+            .unwrap();
+        (tx.commit("test").unwrap(), commit)
+    };
+    let (repo1, commit1) = write_commit(&repo1, "reachable from repo1");
+    let (repo2, commit2) = write_commit(&repo2, "reachable from repo2");
+    let reachable_file_id1 = testutils::write_file(repo1.store(), path, "reachable from repo1");
+    let reachable_file_id2 = testutils::write_file(repo2.store(), path, "reachable from repo2");
+    let unreachable_file_id = testutils::write_file(repo1.store(), path, "unreachable");
+    let file_exists = |id: &FileId| shared_store_path.join("files").join(id.hex()).exists();
+
+    // Don't rely on the exact system time because file modification time might
+    // have lower precision for example.
+    let now = || SystemTime::now() + Duration::from_secs(1);
+
+    // Objects newer than keep_newer are kept
+    repo1
+        .store()
+        .gc(repo1.index(), SystemTime::UNIX_EPOCH)
+        .unwrap();
+    assert!(file_exists(&unreachable_file_id));
+
+    // The objects reachable from the commits written by the other repo are
+    // kept
+    repo1.store().gc(repo1.index(), now()).unwrap();
+    assert!(!file_exists(&unreachable_file_id));
+    assert!(file_exists(&reachable_file_id1));
+    assert!(file_exists(&reachable_file_id2));
+    let repo2 = test_env.load_repo_at_head(&settings, &repo2_path);
+    let commit = repo2.store().get_commit(commit2.id()).unwrap();
+    assert_eq!(commit.tree().unwrap().id(), commit2.tree_id().clone());
+
+    // Once the other repo is deleted, the objects only it could reach are
+    // removed
+    fs::remove_dir_all(&repo2_path).unwrap();
+    repo1.store().gc(repo1.index(), now()).unwrap();
+    assert_eq!(list_dir(&shared_store_path.join("users")).len(), 1);
+    assert_eq!(list_dir(&shared_store_path.join("heads")).len(), 1);
+    assert_eq!(
+        list_dir(&shared_store_path.join("files")),
+        vec![reachable_file_id1.hex()]
+    );
+    let repo1 = test_env.load_repo_at_head(&settings, &repo1_path);
+    let commit = repo1.store().get_commit(commit1.id()).unwrap();
+    assert_eq!(commit.tree().unwrap().id(), commit1.tree_id().clone());
+}
+
+#[test]
+fn test_shared_store_gc_unrecorded_user() {
+    let settings = testutils::user_settings();
+    let test_env = TestEnvironment::init();
+    let shared_store_path = test_env.root().join("shared");
+    let repo1_path = test_env.root().join("repo1");
+    let repo2_path = test_env.root().join("repo2");
+    let repo1 = init_repo_with_shared_store(&settings, &repo1_path, &shared_store_path);
+    init_repo_with_shared_store(&settings, &repo2_path, &shared_store_path);
+
+    // If it isn't known which commits a repo may reach, nothing is removed
+    for name in list_dir(&shared_store_path.join("heads")) {
+        fs::remove_file(shared_store_path.join("heads").join(name)).unwrap();
+    }
+    let path = RepoPath::from_internal_string("file");
+    let file_id = testutils::write_file(repo1.store(), path, "unreachable");
+    repo1
+        .store()
+        .gc(repo1.index(), SystemTime::now() + Duration::from_secs(1))
+        .unwrap();
+    assert!(shared_store_path.join("files").join(file_id.hex()).exists());
+}
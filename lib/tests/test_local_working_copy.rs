@@ -19,17 +19,25 @@ use std::os::unix::net::UnixListener;
 use std::path::Path;
 use std::path::PathBuf;
 use std::sync::Arc;
+use std::sync::Mutex;
 
 use assert_matches::assert_matches;
+use bstr::BString;
+use bstr::ByteSlice as _;
 use indoc::indoc;
 use itertools::Itertools;
 use jj_lib::backend::MergedTreeId;
 use jj_lib::backend::TreeId;
 use jj_lib::backend::TreeValue;
+use jj_lib::conflicts::ConflictMaterializer;
+use jj_lib::conflicts::ConflictMaterializers;
 use jj_lib::file_util::check_symlink_support;
 use jj_lib::file_util::try_symlink;
 use jj_lib::fsmonitor::FsmonitorSettings;
 use jj_lib::local_working_copy::LocalWorkingCopy;
+use jj_lib::local_working_copy::LockedLocalWorkingCopy;
+use jj_lib::matchers::Matcher;
+use jj_lib::matchers::PrefixMatcher;
 use jj_lib::merge::Merge;
 use jj_lib::merge::MergedTreeValue;
 use jj_lib::merged_tree::MergedTree;
@@ -43,10 +51,13 @@ use jj_lib::repo_path::RepoPathBuf;
 use jj_lib::repo_path::RepoPathComponent;
 use jj_lib::secret_backend::SecretBackend;
 use jj_lib::settings::UserSettings;
+use jj_lib::sparse::SparsePattern;
 use jj_lib::working_copy::CheckoutError;
 use jj_lib::working_copy::CheckoutOptions;
 use jj_lib::working_copy::CheckoutStats;
+use jj_lib::working_copy::EolConversionMode;
 use jj_lib::working_copy::SnapshotOptions;
+use jj_lib::working_copy::SymlinkMode;
 use jj_lib::working_copy::UntrackedReason;
 use jj_lib::workspace::default_working_copy_factories;
 use jj_lib::workspace::LockedWorkspace;
@@ -110,7 +121,7 @@ fn test_root() {
     let mut test_workspace = TestWorkspace::init(&settings);
 
     let wc = test_workspace.workspace.working_copy();
-    assert_eq!(wc.sparse_patterns().unwrap(), vec![RepoPathBuf::root()]);
+    assert_eq!(wc.sparse_patterns().unwrap(), vec![SparsePattern::root()]);
     let new_tree = test_workspace.snapshot().unwrap();
     let repo = &test_workspace.repo;
     let wc_commit_id = repo
@@ -750,6 +761,46 @@ fn test_checkout_discard() {
     assert!(!reloaded_wc.file_states().unwrap().contains_path(file2_path));
 }
 
+#[test]
+fn test_checkout_progress() {
+    // Check out enough files to be written in multiple batches. Progress should
+    // still be reported in path order.
+    let settings = testutils::user_settings();
+    let mut test_workspace = TestWorkspace::init(&settings);
+    let repo = test_workspace.repo.clone();
+    let workspace_root = test_workspace.workspace.workspace_root().to_owned();
+
+    let paths = (0..2500)
+        .map(|i| RepoPathBuf::from_internal_string(format!("dir{}/file{i}", i % 7)))
+        .collect_vec();
+    let path_contents = paths
+        .iter()
+        .map(|path| (path.as_ref(), "contents"))
+        .collect_vec();
+    let tree = create_tree(&repo, &path_contents);
+    let commit = commit_with_tree(repo.store(), tree.id());
+
+    let reported_paths = Mutex::new(vec![]);
+    let progress = |path: &RepoPath| reported_paths.lock().unwrap().push(path.to_owned());
+    let options = CheckoutOptions {
+        progress: Some(&progress),
+        ..CheckoutOptions::empty_for_test()
+    };
+    let ws = &mut test_workspace.workspace;
+    let stats = ws
+        .check_out(repo.op_id().clone(), None, &commit, &options)
+        .unwrap();
+    assert_eq!(stats.added_files, 2500);
+    assert_eq!(stats.skipped_files, 0);
+    assert_eq!(
+        reported_paths.into_inner().unwrap(),
+        paths.iter().sorted().cloned().collect_vec()
+    );
+    for path in &paths {
+        assert!(path.to_fs_path_unchecked(&workspace_root).is_file());
+    }
+}
+
 #[test]
 fn test_snapshot_file_directory_transition() {
     let settings = testutils::user_settings();
@@ -922,6 +973,125 @@ fn test_materialize_snapshot_conflicted_files() {
     );
 }
 
+/// Writes each term of a conflict on its own line, e.g. "side: a".
+#[derive(Debug)]
+struct LineConflictMaterializer;
+
+impl ConflictMaterializer for LineConflictMaterializer {
+    fn matches(&self, path: &RepoPath) -> bool {
+        path.as_internal_file_string().ends_with(".lock")
+    }
+
+    fn materialize(&self, _path: &RepoPath, contents: &Merge<BString>) -> Vec<u8> {
+        let mut data = vec![];
+        for (i, content) in contents.iter().enumerate() {
+            let kind = if i % 2 == 0 { "side" } else { "base" };
+            data.extend_from_slice(format!("{kind}: ").as_bytes());
+            data.extend_from_slice(content);
+        }
+        data
+    }
+
+    fn parse(&self, _path: &RepoPath, content: &[u8], num_sides: usize) -> Option<Merge<BString>> {
+        let terms = content
+            .lines_with_terminator()
+            .enumerate()
+            .map(|(i, line)| {
+                let kind = if i % 2 == 0 { "side: " } else { "base: " };
+                line.strip_prefix(kind.as_bytes()).map(BString::from)
+            })
+            .collect::<Option<Vec<_>>>()?;
+        (terms.len() == num_sides * 2 - 1).then(|| Merge::from_vec(terms))
+    }
+}
+
+#[test]
+fn test_materialize_snapshot_custom_conflict_format() {
+    let settings = testutils::user_settings();
+    let mut test_workspace = TestWorkspace::init(&settings);
+    let repo = &test_workspace.repo.clone();
+    let workspace_root = test_workspace.workspace.workspace_root().to_owned();
+
+    let lock_path = RepoPath::from_internal_string("deps.lock");
+    let other_path = RepoPath::from_internal_string("other");
+    let side1_tree = create_tree(repo, &[(lock_path, "a\n"), (other_path, "1\n")]);
+    let base_tree = create_tree(repo, &[(lock_path, "b\n"), (other_path, "2\n")]);
+    let side2_tree = create_tree(repo, &[(lock_path, "c\n"), (other_path, "3\n")]);
+    let merged_tree = side1_tree.merge(&base_tree, &side2_tree).unwrap();
+    let commit = commit_with_tree(repo.store(), merged_tree.id());
+
+    let mut conflict_materializers = ConflictMaterializers::new();
+    conflict_materializers.add(Arc::new(LineConflictMaterializer));
+    test_workspace
+        .workspace
+        .check_out(
+            repo.op_id().clone(),
+            None,
+            &commit,
+            &CheckoutOptions {
+                conflict_materializers: conflict_materializers.clone(),
+                ..CheckoutOptions::empty_for_test()
+            },
+        )
+        .unwrap();
+
+    // Only the matching path uses the custom format
+    insta::assert_snapshot!(
+        std::fs::read_to_string(lock_path.to_fs_path_unchecked(&workspace_root)).unwrap(),
+        @r"
+    side: a
+    base: b
+    side: c
+    "
+    );
+    insta::assert_snapshot!(
+        std::fs::read_to_string(other_path.to_fs_path_unchecked(&workspace_root)).unwrap(),
+        @r"
+    <<<<<<< Conflict 1 of 1
+    %%%%%%% Changes from base to side #1
+    -2
+    +1
+    +++++++ Contents of side #2
+    3
+    >>>>>>> Conflict 1 of 1 ends
+    "
+    );
+
+    let options = SnapshotOptions {
+        conflict_materializers,
+        ..SnapshotOptions::empty_for_test()
+    };
+    let (tree, _stats) = test_workspace.snapshot_with_options(&options).unwrap();
+    assert_eq!(tree.id(), merged_tree.id());
+
+    // Editing a side in the custom format updates that side of the conflict
+    testutils::write_working_copy_file(
+        &workspace_root,
+        lock_path,
+        "side: a_edited\nbase: b\nside: c\n",
+    );
+    let (tree, _stats) = test_workspace.snapshot_with_options(&options).unwrap();
+    let expected_tree = create_tree(repo, &[(lock_path, "a_edited\n")])
+        .merge(&base_tree, &side2_tree)
+        .unwrap();
+    assert_eq!(
+        tree.path_value(lock_path).unwrap(),
+        expected_tree.path_value(lock_path).unwrap()
+    );
+
+    // Content which can't be parsed resolves the conflict
+    testutils::write_working_copy_file(&workspace_root, lock_path, "resolved\n");
+    let (tree, _stats) = test_workspace.snapshot_with_options(&options).unwrap();
+    let value = tree.path_value(lock_path).unwrap();
+    let Some(Some(TreeValue::File { id, .. })) = value.as_resolved() else {
+        panic!("unexpected value: {value:#?}");
+    };
+    assert_eq!(
+        testutils::read_file(repo.store(), lock_path, id),
+        b"resolved\n"
+    );
+}
+
 #[test]
 fn test_snapshot_racy_timestamps() {
     // Tests that file modifications are detected even if they happen the same
@@ -1262,6 +1432,134 @@ fn test_gitignores_ignored_directory_already_tracked() {
     assert_eq!(tree_entries(&new_tree), tree_entries(&expected_tree));
 }
 
+#[test]
+fn test_check_out_symlink_as_file() {
+    let settings = testutils::user_settings();
+    let mut test_workspace = TestWorkspace::init(&settings);
+    let repo = &test_workspace.repo;
+    let store = repo.store().clone();
+    let workspace_root = test_workspace.workspace.workspace_root().to_owned();
+
+    let symlink_path = RepoPath::from_internal_string("link");
+    let mut tree_builder = store.tree_builder(store.empty_tree_id().clone());
+    testutils::write_symlink(&mut tree_builder, symlink_path, "dir/target");
+    let tree_id = tree_builder.write_tree().unwrap();
+    let tree = MergedTree::resolved(store.get_tree(RepoPathBuf::root(), &tree_id).unwrap());
+    let commit = commit_with_tree(&store, tree.id());
+
+    // The symlink is written as a regular file containing the target
+    let ws = &mut test_workspace.workspace;
+    ws.check_out(
+        repo.op_id().clone(),
+        None,
+        &commit,
+        &CheckoutOptions {
+            symlinks: SymlinkMode::File,
+            ..CheckoutOptions::empty_for_test()
+        },
+    )
+    .unwrap();
+    let disk_path = symlink_path.to_fs_path_unchecked(&workspace_root);
+    assert!(disk_path.symlink_metadata().unwrap().file_type().is_file());
+    assert_eq!(std::fs::read_to_string(&disk_path).unwrap(), "dir/target");
+
+    // The file is read back as a symlink
+    let options = SnapshotOptions {
+        symlinks: SymlinkMode::File,
+        ..SnapshotOptions::empty_for_test()
+    };
+    let (new_tree, _stats) = test_workspace.snapshot_with_options(&options).unwrap();
+    assert_eq!(new_tree.id(), tree.id());
+
+    // Editing the file changes the target
+    std::fs::write(&disk_path, "other").unwrap();
+    let (new_tree, _stats) = test_workspace.snapshot_with_options(&options).unwrap();
+    let value = new_tree.path_value(symlink_path).unwrap();
+    let Some(TreeValue::Symlink(id)) = value.as_normal() else {
+        panic!("unexpected value: {value:?}");
+    };
+    assert_eq!(store.read_symlink(symlink_path, id).unwrap(), "other");
+}
+
+#[test]
+fn test_eol_conversion() {
+    let settings = testutils::user_settings();
+    let mut test_workspace = TestWorkspace::init(&settings);
+    let repo = &test_workspace.repo;
+    let workspace_root = test_workspace.workspace.workspace_root().to_owned();
+
+    let gitattributes_path = RepoPath::from_internal_string(".gitattributes");
+    let text_path = RepoPath::from_internal_string("file.txt");
+    let lf_path = RepoPath::from_internal_string("unix.txt");
+    let binary_path = RepoPath::from_internal_string("data.bin");
+    let gitattributes = "*.bin -text\nunix.txt eol=lf\n";
+    let tree = create_tree(
+        repo,
+        &[
+            (gitattributes_path, gitattributes),
+            (text_path, "a\nb\n"),
+            (lf_path, "a\nb\n"),
+            (binary_path, "a\nb\n"),
+        ],
+    );
+    let commit = commit_with_tree(repo.store(), tree.id());
+
+    // Text files are checked out with CRLF line endings unless the attributes
+    // say otherwise
+    let ws = &mut test_workspace.workspace;
+    ws.check_out(
+        repo.op_id().clone(),
+        None,
+        &commit,
+        &CheckoutOptions {
+            eol_conversion: EolConversionMode::InputOutput,
+            ..CheckoutOptions::empty_for_test()
+        },
+    )
+    .unwrap();
+    let read = |path: &RepoPath| std::fs::read(path.to_fs_path_unchecked(&workspace_root)).unwrap();
+    assert_eq!(read(text_path), b"a\r\nb\r\n");
+    assert_eq!(read(lf_path), b"a\nb\n");
+    assert_eq!(read(binary_path), b"a\nb\n");
+
+    // The line endings are normalized when snapshotting
+    let options = SnapshotOptions {
+        eol_conversion: EolConversionMode::InputOutput,
+        ..SnapshotOptions::empty_for_test()
+    };
+    let (new_tree, _stats) = test_workspace.snapshot_with_options(&options).unwrap();
+    assert_eq!(new_tree.id(), tree.id());
+
+    let write = |path: &RepoPath, contents: &str| {
+        std::fs::write(path.to_fs_path_unchecked(&workspace_root), contents).unwrap();
+    };
+    write(text_path, "c\r\nd\r\n");
+    write(lf_path, "c\r\nd\r\n");
+    write(binary_path, "c\r\nd\r\n");
+    let (new_tree, _stats) = test_workspace.snapshot_with_options(&options).unwrap();
+    let expected_tree = create_tree(
+        &test_workspace.repo,
+        &[
+            (gitattributes_path, gitattributes),
+            (text_path, "c\nd\n"),
+            (lf_path, "c\nd\n"),
+            (binary_path, "c\r\nd\r\n"),
+        ],
+    );
+    assert_eq!(new_tree.id(), expected_tree.id());
+
+    // Without conversion, files are snapshotted as is
+    write(text_path, "e\r\n");
+    let (new_tree, _stats) = test_workspace
+        .snapshot_with_options(&SnapshotOptions::empty_for_test())
+        .unwrap();
+    let value = new_tree.path_value(text_path).unwrap();
+    let expected_value = create_tree(&test_workspace.repo, &[(text_path, "e\r\n")])
+        .path_value(text_path)
+        .unwrap();
+    assert_eq!(value, expected_value);
+}
+
 #[test]
 fn test_dotgit_ignored() {
     // Tests that .git directories and files are always ignored (we could accept
@@ -2032,7 +2330,7 @@ fn test_fsmonitor() {
     let ws = &mut test_workspace.workspace;
     assert_eq!(
         ws.working_copy().sparse_patterns().unwrap(),
-        vec![RepoPathBuf::root()]
+        vec![SparsePattern::root()]
     );
 
     let foo_path = RepoPath::from_internal_string("foo");
@@ -2120,6 +2418,91 @@ fn test_fsmonitor() {
     }
 }
 
+#[cfg(target_os = "linux")]
+#[test]
+fn test_fsmonitor_native() {
+    use jj_lib::fsmonitor::native;
+
+    let settings = testutils::user_settings();
+    let mut test_workspace = TestWorkspace::init(&settings);
+    let workspace_root = test_workspace.workspace.workspace_root().to_owned();
+    let state_path = workspace_root.join(".jj").join("working_copy");
+
+    let foo_path = RepoPath::from_internal_string("foo");
+    let bar_path = RepoPath::from_internal_string("bar");
+    testutils::write_working_copy_file(&workspace_root, foo_path, "foo\n");
+
+    // The daemon thread is left running until the test process exits.
+    std::thread::spawn({
+        let workspace_root = workspace_root.clone();
+        let state_path = state_path.clone();
+        move || native::run_daemon(&workspace_root, &state_path)
+    });
+    for _ in 0..500 {
+        if native::is_daemon_running(&state_path).unwrap() {
+            break;
+        }
+        std::thread::sleep(std::time::Duration::from_millis(10));
+    }
+    assert!(native::is_daemon_running(&state_path).unwrap());
+    assert_matches!(
+        native::run_daemon(&workspace_root, &state_path),
+        Err(native::Error::AlreadyRunning)
+    );
+
+    // Changes made before the daemon started aren't known.
+    let changes = native::query_changed_files(&state_path).unwrap();
+    assert_eq!(changes.paths, None);
+    native::acknowledge_changed_files(&state_path, &changes).unwrap();
+    assert_eq!(
+        native::query_changed_files(&state_path).unwrap().paths,
+        Some(vec![])
+    );
+
+    // Changes are reported until they're acknowledged.
+    testutils::write_working_copy_file(&workspace_root, bar_path, "bar\n");
+    let changes = native::query_changed_files(&state_path).unwrap();
+    assert_eq!(changes.paths, Some(vec![PathBuf::from("bar")]));
+    testutils::write_working_copy_file(&workspace_root, foo_path, "foo\n");
+    assert_eq!(
+        native::query_changed_files(&state_path).unwrap().paths,
+        Some(vec![PathBuf::from("bar"), PathBuf::from("foo")])
+    );
+    native::acknowledge_changed_files(&state_path, &changes).unwrap();
+    assert_eq!(
+        native::query_changed_files(&state_path).unwrap().paths,
+        Some(vec![PathBuf::from("foo")])
+    );
+
+    // Paths which have been acknowledged aren't snapshotted.
+    let ws = &mut test_workspace.workspace;
+    let mut locked_ws = ws.start_working_copy_mutation().unwrap();
+    let (tree_id, _stats) = locked_ws
+        .locked_wc()
+        .snapshot(&SnapshotOptions {
+            fsmonitor_settings: FsmonitorSettings::Native,
+            ..SnapshotOptions::empty_for_test()
+        })
+        .unwrap();
+    let tree = test_workspace.repo.store().get_root_tree(&tree_id).unwrap();
+    assert!(tree.path_value(foo_path).unwrap().is_present());
+    assert!(tree.path_value(bar_path).unwrap().is_absent());
+
+    // The snapshotted changes are acknowledged only once the working copy
+    // state has been saved.
+    assert_eq!(
+        native::query_changed_files(&state_path).unwrap().paths,
+        Some(vec![PathBuf::from("foo")])
+    );
+    locked_ws
+        .finish(test_workspace.repo.op_id().clone())
+        .unwrap();
+    assert_eq!(
+        native::query_changed_files(&state_path).unwrap().paths,
+        Some(vec![])
+    );
+}
+
 #[test]
 fn test_snapshot_max_new_file_size() {
     let settings = testutils::user_settings();
@@ -2203,3 +2586,238 @@ fn test_snapshot_max_new_file_size() {
         UntrackedReason::FileTooLarge { .. }
     );
 }
+
+#[test]
+fn test_snapshot_max_new_file_size_overrides() {
+    let settings = testutils::user_settings();
+    let mut test_workspace = TestWorkspace::init(&settings);
+    let workspace_root = test_workspace.workspace.workspace_root().to_owned();
+    let assets_dir = RepoPath::from_internal_string("assets");
+    let assets_path = RepoPath::from_internal_string("assets/large");
+    let large_path = RepoPath::from_internal_string("large");
+    std::fs::create_dir(assets_dir.to_fs_path_unchecked(&workspace_root)).unwrap();
+    std::fs::write(
+        assets_path.to_fs_path_unchecked(&workspace_root),
+        vec![0; 100],
+    )
+    .unwrap();
+    std::fs::write(
+        large_path.to_fs_path_unchecked(&workspace_root),
+        vec![0; 100],
+    )
+    .unwrap();
+
+    // Files matching an override get its limit, other files the default one
+    let assets_matcher: Arc<dyn Matcher> = Arc::new(PrefixMatcher::new([assets_dir]));
+    let options = SnapshotOptions {
+        max_new_file_size: 10,
+        max_new_file_size_overrides: vec![(assets_matcher, 1000)],
+        ..SnapshotOptions::empty_for_test()
+    };
+    let (tree, stats) = test_workspace.snapshot_with_options(&options).unwrap();
+    assert!(tree.path_value(assets_path).unwrap().is_present());
+    assert!(tree.path_value(large_path).unwrap().is_absent());
+    assert_eq!(
+        stats.files_too_large().collect_vec(),
+        [(large_path, 100, 10)]
+    );
+}
+
+/// Sets the mtime of a directory to a fixed time in the past, so the working
+/// copy state is newer than it.
+fn set_old_dir_mtime(path: &Path) {
+    #[cfg(unix)]
+    let dir = std::fs::File::open(path).unwrap();
+    #[cfg(windows)]
+    let dir = {
+        use std::os::windows::fs::OpenOptionsExt as _;
+        const FILE_FLAG_BACKUP_SEMANTICS: u32 = 0x02000000;
+        std::fs::OpenOptions::new()
+            .write(true)
+            .custom_flags(FILE_FLAG_BACKUP_SEMANTICS)
+            .open(path)
+            .unwrap()
+    };
+    let mtime = std::time::UNIX_EPOCH + std::time::Duration::from_secs(1_000_000_000);
+    dir.set_modified(mtime).unwrap();
+}
+
+#[test]
+fn test_snapshot_untracked_cache() {
+    let settings = testutils::user_settings();
+    let mut test_workspace = TestWorkspace::init(&settings);
+    let workspace_root = test_workspace.workspace.workspace_root().to_owned();
+    let options = SnapshotOptions {
+        untracked_cache: true,
+        ..SnapshotOptions::empty_for_test()
+    };
+
+    let file_path = RepoPath::from_internal_string("dir/file");
+    let new_path = RepoPath::from_internal_string("dir/new");
+    let sub_new_path = RepoPath::from_internal_string("dir/sub/new");
+    std::fs::create_dir_all(workspace_root.join("dir").join("sub")).unwrap();
+    testutils::write_working_copy_file(&workspace_root, file_path, "contents");
+    let set_old_mtimes = || {
+        set_old_dir_mtime(&workspace_root);
+        set_old_dir_mtime(&workspace_root.join("dir"));
+        set_old_dir_mtime(&workspace_root.join("dir").join("sub"));
+    };
+    set_old_mtimes();
+    let (tree, _stats) = test_workspace.snapshot_with_options(&options).unwrap();
+    assert!(tree.path_value(file_path).unwrap().is_present());
+
+    // A new file in a directory whose mtime is unchanged isn't found, which
+    // shows that the directory wasn't read
+    testutils::write_working_copy_file(&workspace_root, new_path, "new");
+    set_old_mtimes();
+    let (tree, _stats) = test_workspace.snapshot_with_options(&options).unwrap();
+    assert!(tree.path_value(new_path).unwrap().is_absent());
+
+    // Tracked files in the unchanged directory are still checked
+    testutils::write_working_copy_file(&workspace_root, file_path, "modified contents");
+    let (tree, _stats) = test_workspace.snapshot_with_options(&options).unwrap();
+    assert_eq!(
+        tree.path_value(file_path).unwrap(),
+        create_tree(&test_workspace.repo, &[(file_path, "modified contents")])
+            .path_value(file_path)
+            .unwrap()
+    );
+
+    // Subdirectories of an unchanged directory are still visited
+    testutils::write_working_copy_file(&workspace_root, sub_new_path, "new");
+    let (tree, _stats) = test_workspace.snapshot_with_options(&options).unwrap();
+    assert!(tree.path_value(sub_new_path).unwrap().is_present());
+    assert!(tree.path_value(new_path).unwrap().is_absent());
+
+    // Snapshotting without the cache reads all directories
+    let tree = test_workspace.snapshot().unwrap();
+    assert!(tree.path_value(new_path).unwrap().is_present());
+}
+
+#[test]
+fn test_snapshot_untracked_cache_invalidation() {
+    let settings = testutils::user_settings();
+    let mut test_workspace = TestWorkspace::init(&settings);
+    let workspace_root = test_workspace.workspace.workspace_root().to_owned();
+    let options = SnapshotOptions {
+        untracked_cache: true,
+        max_new_file_size: 10,
+        ..SnapshotOptions::empty_for_test()
+    };
+
+    let gitignore_path = RepoPath::from_internal_string(".gitignore");
+    let ignored_path = RepoPath::from_internal_string("dir/file.log");
+    let large_path = RepoPath::from_internal_string("large/file");
+    std::fs::create_dir(workspace_root.join("dir")).unwrap();
+    std::fs::create_dir(workspace_root.join("large")).unwrap();
+    testutils::write_working_copy_file(&workspace_root, gitignore_path, "*.log\n");
+    testutils::write_working_copy_file(&workspace_root, ignored_path, "ignored");
+    testutils::write_working_copy_file(&workspace_root, large_path, "too large to track");
+    let set_old_mtimes = || {
+        set_old_dir_mtime(&workspace_root);
+        set_old_dir_mtime(&workspace_root.join("dir"));
+        set_old_dir_mtime(&workspace_root.join("large"));
+    };
+    set_old_mtimes();
+    let (tree, stats) = test_workspace.snapshot_with_options(&options).unwrap();
+    assert!(tree.path_value(ignored_path).unwrap().is_absent());
+    assert!(tree.path_value(large_path).unwrap().is_absent());
+    assert_eq!(
+        stats.untracked_paths.keys().collect_vec(),
+        [&large_path.to_owned()]
+    );
+
+    // Directories with files left untracked are read again so that the files
+    // are reported every time
+    let (_tree, stats) = test_workspace.snapshot_with_options(&options).unwrap();
+    assert_eq!(
+        stats.untracked_paths.keys().collect_vec(),
+        [&large_path.to_owned()]
+    );
+
+    // Changing the ignore rules invalidates the directories they apply to,
+    // even if their mtimes didn't change
+    testutils::write_working_copy_file(&workspace_root, gitignore_path, "");
+    set_old_mtimes();
+    let (tree, _stats) = test_workspace.snapshot_with_options(&options).unwrap();
+    assert!(tree.path_value(ignored_path).unwrap().is_present());
+}
+
+#[test]
+fn test_snapshot_assume_unchanged() {
+    let settings = testutils::user_settings();
+    let mut test_workspace = TestWorkspace::init(&settings);
+    let workspace_root = test_workspace.workspace.workspace_root().to_owned();
+
+    let gen_dir = RepoPath::from_internal_string("gen");
+    let gen_file_path = RepoPath::from_internal_string("gen/file");
+    let gen_new_path = RepoPath::from_internal_string("gen/new");
+    let other_path = RepoPath::from_internal_string("other");
+    std::fs::create_dir(workspace_root.join("gen")).unwrap();
+    testutils::write_working_copy_file(&workspace_root, gen_file_path, "contents");
+    let tree = test_workspace.snapshot().unwrap();
+    assert!(tree.path_value(gen_file_path).unwrap().is_present());
+
+    let mut locked_ws = test_workspace
+        .workspace
+        .start_working_copy_mutation()
+        .unwrap();
+    let locked_wc: &mut LockedLocalWorkingCopy =
+        locked_ws.locked_wc().as_any_mut().downcast_mut().unwrap();
+    locked_wc
+        .set_assume_unchanged_paths(vec![gen_dir.to_owned()])
+        .unwrap();
+    locked_ws
+        .finish(test_workspace.repo.op_id().clone())
+        .unwrap();
+
+    // Changes to the paths aren't snapshotted, but other changes are
+    std::fs::remove_file(gen_file_path.to_fs_path_unchecked(&workspace_root)).unwrap();
+    testutils::write_working_copy_file(&workspace_root, gen_new_path, "new");
+    testutils::write_working_copy_file(&workspace_root, other_path, "other");
+    let tree = test_workspace.snapshot().unwrap();
+    assert!(tree.path_value(gen_file_path).unwrap().is_present());
+    assert!(tree.path_value(gen_new_path).unwrap().is_absent());
+    assert!(tree.path_value(other_path).unwrap().is_present());
+
+    // Refreshed paths are snapshotted
+    let refresh_matcher = PrefixMatcher::new([gen_new_path]);
+    let options = SnapshotOptions {
+        refresh_matcher: &refresh_matcher,
+        ..SnapshotOptions::empty_for_test()
+    };
+    let (tree, _stats) = test_workspace.snapshot_with_options(&options).unwrap();
+    assert!(tree.path_value(gen_file_path).unwrap().is_present());
+    assert!(tree.path_value(gen_new_path).unwrap().is_present());
+
+    // The refreshed state is kept, and the paths are still assumed unchanged
+    testutils::write_working_copy_file(&workspace_root, gen_new_path, "modified");
+    let tree = test_workspace.snapshot().unwrap();
+    assert!(tree.path_value(gen_file_path).unwrap().is_present());
+    assert_eq!(
+        tree.path_value(gen_new_path).unwrap(),
+        create_tree(&test_workspace.repo, &[(gen_new_path, "new")])
+            .path_value(gen_new_path)
+            .unwrap()
+    );
+    let wc: &LocalWorkingCopy = test_workspace
+        .workspace
+        .working_copy()
+        .as_any()
+        .downcast_ref()
+        .unwrap();
+    assert_eq!(wc.assume_unchanged_paths().unwrap(), [gen_dir.to_owned()]);
+
+    // Refreshed paths are snapshotted even if the fsmonitor doesn't report
+    // them as changed
+    let refresh_matcher = PrefixMatcher::new([gen_file_path]);
+    let options = SnapshotOptions {
+        fsmonitor_settings: FsmonitorSettings::Test {
+            changed_files: vec![],
+        },
+        refresh_matcher: &refresh_matcher,
+        ..SnapshotOptions::empty_for_test()
+    };
+    let (tree, _stats) = test_workspace.snapshot_with_options(&options).unwrap();
+    assert!(tree.path_value(gen_file_path).unwrap().is_absent());
+}
@@ -18,7 +18,7 @@ use jj_lib::local_working_copy::LocalWorkingCopy;
 use jj_lib::matchers::EverythingMatcher;
 use jj_lib::repo::Repo;
 use jj_lib::repo_path::RepoPath;
-use jj_lib::repo_path::RepoPathBuf;
+use jj_lib::sparse::SparsePattern;
 use jj_lib::working_copy::CheckoutOptions;
 use jj_lib::working_copy::CheckoutStats;
 use jj_lib::working_copy::WorkingCopy;
@@ -27,8 +27,11 @@ use testutils::commit_with_tree;
 use testutils::create_tree;
 use testutils::TestWorkspace;
 
-fn to_owned_path_vec(paths: &[&RepoPath]) -> Vec<RepoPathBuf> {
-    paths.iter().map(|&path| path.to_owned()).collect()
+fn to_prefix_patterns(paths: &[&RepoPath]) -> Vec<SparsePattern> {
+    paths
+        .iter()
+        .map(|&path| SparsePattern::prefix(path.to_owned()))
+        .collect()
 }
 
 #[test]
@@ -74,7 +77,7 @@ fn test_sparse_checkout() {
 
     // Set sparse patterns to only dir1/
     let mut locked_ws = ws.start_working_copy_mutation().unwrap();
-    let sparse_patterns = to_owned_path_vec(&[dir1_path]);
+    let sparse_patterns = to_prefix_patterns(&[dir1_path]);
     let stats = locked_ws
         .locked_wc()
         .set_sparse_patterns(sparse_patterns.clone(), &CheckoutOptions::empty_for_test())
@@ -134,7 +137,7 @@ fn test_sparse_checkout() {
 
     // Set sparse patterns to file2, dir1/subdir1/ and dir2/
     let mut locked_wc = wc.start_mutation().unwrap();
-    let sparse_patterns = to_owned_path_vec(&[root_file1_path, dir1_subdir1_path, dir2_path]);
+    let sparse_patterns = to_prefix_patterns(&[root_file1_path, dir1_subdir1_path, dir2_path]);
     let stats = locked_wc
         .set_sparse_patterns(sparse_patterns.clone(), &CheckoutOptions::empty_for_test())
         .unwrap();
@@ -174,6 +177,104 @@ fn test_sparse_checkout() {
     );
 }
 
+#[test]
+fn test_sparse_checkout_globs_and_exclusions() {
+    let settings = testutils::user_settings();
+    let mut test_workspace = TestWorkspace::init(&settings);
+    let repo = &test_workspace.repo;
+    let working_copy_path = test_workspace.workspace.workspace_root().to_owned();
+
+    let root_file1_path = RepoPath::from_internal_string("file1.txt");
+    let root_file2_path = RepoPath::from_internal_string("file2");
+    let dir1_file1_path = RepoPath::from_internal_string("dir1/file1");
+    let dir1_subdir1_file1_path = RepoPath::from_internal_string("dir1/subdir1/file1");
+    let dir1_subdir1_new_path = RepoPath::from_internal_string("dir1/subdir1/new");
+    let dir2_file1_path = RepoPath::from_internal_string("dir2/file1.txt");
+
+    let tree = create_tree(
+        repo,
+        &[
+            (root_file1_path, "contents"),
+            (root_file2_path, "contents"),
+            (dir1_file1_path, "contents"),
+            (dir1_subdir1_file1_path, "contents"),
+            (dir2_file1_path, "contents"),
+        ],
+    );
+    let commit = commit_with_tree(repo.store(), tree.id());
+    test_workspace
+        .workspace
+        .check_out(
+            repo.op_id().clone(),
+            None,
+            &commit,
+            &CheckoutOptions::empty_for_test(),
+        )
+        .unwrap();
+
+    // Include dir1/ and top-level *.txt files, but not dir1/subdir1/
+    let mut locked_ws = test_workspace
+        .workspace
+        .start_working_copy_mutation()
+        .unwrap();
+    let sparse_patterns = ["dir1", "glob:*.txt", "!glob:dir1/subdir1/**"]
+        .map(|input| SparsePattern::parse(input).unwrap())
+        .to_vec();
+    let stats = locked_ws
+        .locked_wc()
+        .set_sparse_patterns(sparse_patterns.clone(), &CheckoutOptions::empty_for_test())
+        .unwrap();
+    assert_eq!(
+        stats,
+        CheckoutStats {
+            updated_files: 0,
+            added_files: 0,
+            removed_files: 3,
+            skipped_files: 0,
+        }
+    );
+    assert_eq!(
+        locked_ws.locked_wc().sparse_patterns().unwrap(),
+        sparse_patterns
+    );
+    locked_ws.finish(repo.op_id().clone()).unwrap();
+    assert!(root_file1_path
+        .to_fs_path_unchecked(&working_copy_path)
+        .exists());
+    assert!(!root_file2_path
+        .to_fs_path_unchecked(&working_copy_path)
+        .exists());
+    assert!(dir1_file1_path
+        .to_fs_path_unchecked(&working_copy_path)
+        .exists());
+    assert!(!dir1_subdir1_file1_path
+        .to_fs_path_unchecked(&working_copy_path)
+        .exists());
+    assert!(!dir2_file1_path
+        .to_fs_path_unchecked(&working_copy_path)
+        .exists());
+
+    // The patterns are persisted
+    let wc = test_workspace.workspace.working_copy();
+    assert_eq!(wc.sparse_patterns().unwrap(), sparse_patterns);
+
+    // New files in the excluded directory aren't snapshotted
+    std::fs::create_dir_all(
+        dir1_subdir1_file1_path
+            .parent()
+            .unwrap()
+            .to_fs_path_unchecked(&working_copy_path),
+    )
+    .unwrap();
+    std::fs::write(
+        dir1_subdir1_new_path.to_fs_path_unchecked(&working_copy_path),
+        "contents",
+    )
+    .unwrap();
+    let modified_tree = test_workspace.snapshot().unwrap();
+    assert_eq!(modified_tree.id(), tree.id());
+}
+
 /// Test that sparse patterns are respected on commit
 #[test]
 fn test_sparse_commit() {
@@ -214,7 +315,7 @@ fn test_sparse_commit() {
         .workspace
         .start_working_copy_mutation()
         .unwrap();
-    let sparse_patterns = to_owned_path_vec(&[dir1_path]);
+    let sparse_patterns = to_prefix_patterns(&[dir1_path]);
     locked_ws
         .locked_wc()
         .set_sparse_patterns(sparse_patterns, &CheckoutOptions::empty_for_test())
@@ -255,7 +356,7 @@ fn test_sparse_commit() {
         .workspace
         .start_working_copy_mutation()
         .unwrap();
-    let sparse_patterns = to_owned_path_vec(&[dir1_path, dir2_path]);
+    let sparse_patterns = to_prefix_patterns(&[dir1_path, dir2_path]);
     locked_ws
         .locked_wc()
         .set_sparse_patterns(sparse_patterns, &CheckoutOptions::empty_for_test())
@@ -291,7 +392,7 @@ fn test_sparse_commit_gitignore() {
         .workspace
         .start_working_copy_mutation()
         .unwrap();
-    let sparse_patterns = to_owned_path_vec(&[dir1_path]);
+    let sparse_patterns = to_prefix_patterns(&[dir1_path]);
     locked_ws
         .locked_wc()
         .set_sparse_patterns(sparse_patterns, &CheckoutOptions::empty_for_test())
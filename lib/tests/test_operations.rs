@@ -15,6 +15,8 @@
 use std::path::Path;
 use std::slice;
 use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
 use std::time::SystemTime;
 
 use assert_matches::assert_matches;
@@ -23,6 +25,7 @@ use jj_lib::backend::CommitId;
 use jj_lib::config::ConfigLayer;
 use jj_lib::config::ConfigSource;
 use jj_lib::object_id::ObjectId;
+use jj_lib::op_heads_store::wait_for_op_heads_change;
 use jj_lib::op_walk;
 use jj_lib::op_walk::OpsetEvaluationError;
 use jj_lib::op_walk::OpsetResolutionError;
@@ -155,6 +158,44 @@ fn test_concurrent_operations() {
     assert_eq!(list_dir(&op_heads_dir), vec![merged_op_id.hex()]);
 }
 
+#[test]
+fn test_wait_for_op_heads_change() {
+    let settings = testutils::user_settings();
+    let test_repo = TestRepo::init();
+    let repo = &test_repo.repo;
+    let op_heads_store = repo.op_heads_store().clone();
+    let op_id0 = repo.op_id().clone();
+
+    // Times out if nothing changes
+    let result = wait_for_op_heads_change(
+        op_heads_store.as_ref(),
+        slice::from_ref(&op_id0),
+        Duration::from_millis(1),
+        Some(Duration::from_millis(10)),
+    );
+    assert_matches!(result, Ok(None));
+
+    // Returns once another thread commits an operation
+    let waiter = thread::spawn({
+        let op_heads_store = op_heads_store.clone();
+        let op_id0 = op_id0.clone();
+        move || {
+            wait_for_op_heads_change(
+                op_heads_store.as_ref(),
+                slice::from_ref(&op_id0),
+                Duration::from_millis(1),
+                None,
+            )
+        }
+    });
+    let mut tx = repo.start_transaction(&settings);
+    write_random_commit(tx.repo_mut(), &settings);
+    let op_id1 = tx.commit("transaction 1").unwrap().op_id().clone();
+    // The old head may not have been removed yet when the new head is observed
+    let new_op_heads = waiter.join().unwrap().unwrap().unwrap();
+    assert!(new_op_heads.contains(&op_id1));
+}
+
 fn assert_heads(repo: &dyn Repo, expected: Vec<&CommitId>) {
     let expected = expected.iter().cloned().cloned().collect();
     assert_eq!(*repo.view().heads(), expected);
@@ -422,6 +463,83 @@ fn test_reparent_range_branchy() {
     assert_eq!(new_op_f.parent_ids(), slice::from_ref(repo_d.op_id()));
 }
 
+#[test]
+fn test_squash_snapshot_operations() {
+    let settings = testutils::user_settings();
+    let test_repo = TestRepo::init();
+    let repo_0 = test_repo.repo;
+    let loader = repo_0.loader();
+    let op_store = repo_0.op_store();
+
+    let read_op = |id| loader.load_operation(id).unwrap();
+
+    fn op_parents<const N: usize>(op: &Operation) -> [Operation; N] {
+        let parents: Vec<_> = op.parents().try_collect().unwrap();
+        parents.try_into().unwrap()
+    }
+
+    // Set up linear operation graph, where S* are snapshot operations:
+    // S5
+    // B
+    // S4
+    // S3
+    // S2
+    // A
+    // S1
+    // 0 (initial)
+    let random_tx = |repo: &Arc<ReadonlyRepo>, is_snapshot: bool| {
+        let mut tx = repo.start_transaction(&settings);
+        tx.set_is_snapshot(is_snapshot);
+        write_random_commit(tx.repo_mut(), &settings);
+        tx
+    };
+    let repo_s1 = random_tx(&repo_0, true).commit("op S1").unwrap();
+    let repo_a = random_tx(&repo_s1, false).commit("op A").unwrap();
+    let repo_s2 = random_tx(&repo_a, true).commit("op S2").unwrap();
+    let repo_s3 = random_tx(&repo_s2, true).commit("op S3").unwrap();
+    let repo_s4 = random_tx(&repo_s3, true).commit("op S4").unwrap();
+    let repo_b = random_tx(&repo_s4, false).commit("op B").unwrap();
+    let repo_s5 = random_tx(&repo_b, true).commit("op S5").unwrap();
+
+    // Squash S2|S3 into S4:
+    // S5'
+    // B'
+    // S4'
+    // A
+    // S1
+    // 0 (initial)
+    let stats = op_walk::squash_snapshot_operations(
+        op_store.as_ref(),
+        slice::from_ref(repo_s5.operation()),
+    )
+    .unwrap();
+    assert_eq!(stats.new_head_ids.len(), 1);
+    assert_eq!(stats.rewritten_count, 3);
+    assert_eq!(stats.unreachable_count, 2);
+    let new_op_s5 = read_op(&stats.new_head_ids[0]);
+    assert_eq!(new_op_s5.metadata(), repo_s5.operation().metadata());
+    let [new_op_b] = op_parents(&new_op_s5);
+    assert_eq!(new_op_b.view_id(), repo_b.operation().view_id());
+    let [new_op_s4] = op_parents(&new_op_b);
+    assert_eq!(new_op_s4.view_id(), repo_s4.operation().view_id());
+    assert_eq!(new_op_s4.parent_ids(), slice::from_ref(repo_a.op_id()));
+    assert_eq!(
+        new_op_s4.metadata().start_time,
+        repo_s2.operation().metadata().start_time
+    );
+    assert_eq!(
+        new_op_s4.metadata().end_time,
+        repo_s4.operation().metadata().end_time
+    );
+
+    // Nothing left to squash
+    let stats = op_walk::squash_snapshot_operations(op_store.as_ref(), slice::from_ref(&new_op_s5))
+        .unwrap();
+    assert_eq!(stats.new_head_ids, vec![new_op_s5.id().clone()]);
+    assert_eq!(stats.rewritten_count, 0);
+    assert_eq!(stats.unreachable_count, 0);
+}
+
 fn stable_op_id_settings() -> UserSettings {
     let mut config = testutils::base_user_config();
     config.add_layer(
@@ -38,11 +38,13 @@ use jj_lib::repo::Repo;
 use jj_lib::repo_path::RepoPath;
 use jj_lib::repo_path::RepoPathUiConverter;
 use jj_lib::revset::parse;
+use jj_lib::revset::CommitPrefetchIterator;
 use jj_lib::revset::DefaultSymbolResolver;
 use jj_lib::revset::FailingSymbolResolver;
 use jj_lib::revset::Revset;
 use jj_lib::revset::RevsetAliasesMap;
 use jj_lib::revset::RevsetDiagnostics;
+use jj_lib::revset::RevsetEvaluationError;
 use jj_lib::revset::RevsetExpression;
 use jj_lib::revset::RevsetExtensions;
 use jj_lib::revset::RevsetFilterPredicate;
@@ -1141,6 +1143,84 @@ fn test_evaluate_expression_roots() {
     );
 }
 
+#[test]
+fn test_evaluate_expression_heads_of_roots_of() {
+    let settings = testutils::user_settings();
+    let test_repo = TestRepo::init();
+    let repo = &test_repo.repo;
+
+    let mut tx = repo.start_transaction(&settings);
+    let mut_repo = tx.repo_mut();
+    let mut graph_builder = CommitGraphBuilder::new(&settings, mut_repo);
+    let commit1 = graph_builder.initial_commit();
+    let commit2 = graph_builder.commit_with_parents(&[&commit1]);
+    let commit3 = graph_builder.commit_with_parents(&[&commit2]);
+    let commit4 = graph_builder.commit_with_parents(&[&commit3]);
+
+    // Ancestry is followed through commits in the domain
+    assert_eq!(
+        resolve_commit_ids(
+            mut_repo,
+            &format!("heads_of({} | {}, all())", commit1.id(), commit3.id())
+        ),
+        vec![commit3.id().clone()]
+    );
+    assert_eq!(
+        resolve_commit_ids(
+            mut_repo,
+            &format!("roots_of({} | {}, all())", commit1.id(), commit3.id())
+        ),
+        vec![commit1.id().clone()]
+    );
+
+    // ...but not through commits outside of it
+    assert_eq!(
+        resolve_commit_ids(
+            mut_repo,
+            &format!(
+                "heads_of({} | {}, ~{})",
+                commit1.id(),
+                commit3.id(),
+                commit2.id()
+            )
+        ),
+        vec![commit3.id().clone(), commit1.id().clone()]
+    );
+    assert_eq!(
+        resolve_commit_ids(
+            mut_repo,
+            &format!(
+                "roots_of({} | {}, ~{})",
+                commit1.id(),
+                commit3.id(),
+                commit2.id()
+            )
+        ),
+        vec![commit3.id().clone(), commit1.id().clone()]
+    );
+
+    // Candidates outside of the domain are excluded
+    assert_eq!(
+        resolve_commit_ids(
+            mut_repo,
+            &format!(
+                "heads_of({}, {}::{})",
+                commit4.id(),
+                commit1.id(),
+                commit3.id()
+            )
+        ),
+        vec![]
+    );
+    assert_eq!(
+        resolve_commit_ids(
+            mut_repo,
+            &format!("roots_of(all(), {}::{})", commit2.id(), commit4.id())
+        ),
+        vec![commit2.id().clone()]
+    );
+}
+
 #[test]
 fn test_evaluate_expression_parents() {
     let settings = testutils::user_settings();
@@ -2473,6 +2553,58 @@ fn test_evaluate_expression_latest() {
     );
 }
 
+#[test]
+fn test_evaluate_expression_first_last() {
+    let settings = testutils::user_settings();
+    let test_repo = TestRepo::init();
+    let repo = &test_repo.repo;
+
+    let root_commit = repo.store().root_commit();
+    let mut tx = repo.start_transaction(&settings);
+    let mut_repo = tx.repo_mut();
+    let mut graph_builder = CommitGraphBuilder::new(&settings, mut_repo);
+    let commit1 = graph_builder.initial_commit();
+    let commit2 = graph_builder.commit_with_parents(&[&commit1]);
+    let commit3 = graph_builder.commit_with_parents(&[&commit2]);
+    let commit4 = graph_builder.commit_with_parents(&[&commit1]);
+
+    // Pick a single entry by default (count = 1)
+    assert_eq!(
+        resolve_commit_ids(mut_repo, "first(all())"),
+        vec![root_commit.id().clone()],
+    );
+    assert_eq!(
+        resolve_commit_ids(mut_repo, "last(all())"),
+        vec![commit4.id().clone()],
+    );
+
+    // Should not panic with count = 0 or empty set
+    assert_eq!(resolve_commit_ids(mut_repo, "first(all(), 0)"), vec![]);
+    assert_eq!(resolve_commit_ids(mut_repo, "last(all(), 0)"), vec![]);
+    assert_eq!(resolve_commit_ids(mut_repo, "first(none())"), vec![]);
+    assert_eq!(resolve_commit_ids(mut_repo, "last(none())"), vec![]);
+
+    // Ancestors come before descendants
+    assert_eq!(
+        resolve_commit_ids(mut_repo, &format!("first({}::, 2)", commit1.id())),
+        vec![commit2.id().clone(), commit1.id().clone()],
+    );
+    assert_eq!(
+        resolve_commit_ids(mut_repo, &format!("last(::{}, 2)", commit3.id())),
+        vec![commit3.id().clone(), commit2.id().clone()],
+    );
+
+    // Count larger than the set
+    assert_eq!(
+        resolve_commit_ids(mut_repo, &format!("first({}::, 10)", commit2.id())),
+        vec![commit3.id().clone(), commit2.id().clone()],
+    );
+    assert_eq!(
+        resolve_commit_ids(mut_repo, &format!("last({}::, 10)", commit2.id())),
+        vec![commit3.id().clone(), commit2.id().clone()],
+    );
+}
+
 #[test]
 fn test_evaluate_expression_fork_point() {
     let settings = testutils::user_settings();
@@ -2726,6 +2858,39 @@ fn test_evaluate_expression_description() {
     );
 }
 
+#[test]
+fn test_evaluate_expression_description_many_candidates() {
+    let settings = testutils::user_settings();
+    let test_repo = TestRepo::init();
+    let repo = &test_repo.repo;
+
+    let mut tx = repo.start_transaction(&settings);
+    let mut_repo = tx.repo_mut();
+
+    // More candidates than a single batch of the concurrent filter
+    let mut parent_id = repo.store().root_commit_id().clone();
+    let mut expected = vec![];
+    for i in 0..1000 {
+        let commit = create_random_commit(mut_repo, &settings)
+            .set_parents(vec![parent_id])
+            .set_description(if i % 3 == 0 { "fizz" } else { "buzz" })
+            .write()
+            .unwrap();
+        parent_id = commit.id().clone();
+        if i % 3 == 0 {
+            expected.push(commit.id().clone());
+        }
+    }
+    expected.reverse();
+
+    // Matches are emitted in index order
+    assert_eq!(resolve_commit_ids(mut_repo, "description(fizz)"), expected);
+    assert_eq!(
+        resolve_commit_ids(mut_repo, "description(fizz) & ::description(fizz)"),
+        expected
+    );
+}
+
 #[test]
 fn test_evaluate_expression_author() {
     let settings = testutils::user_settings();
@@ -4047,3 +4212,55 @@ fn test_revset_containing_fn() {
     assert!(!revset_has_commit(commit_c.id()).unwrap());
     assert!(revset_has_commit(commit_d.id()).unwrap());
 }
+
+#[test_case(1; "batch of one")]
+#[test_case(2; "batch of two")]
+#[test_case(10; "batch larger than input")]
+fn test_commit_prefetch_iterator(batch_size: usize) {
+    let settings = testutils::user_settings();
+    let test_repo = TestRepo::init();
+    let repo = &test_repo.repo;
+
+    let mut tx = repo.start_transaction(&settings);
+    let mut_repo = tx.repo_mut();
+    let commit_a = write_random_commit(mut_repo, &settings);
+    let commit_b = write_random_commit(mut_repo, &settings);
+    let commit_c = write_random_commit(mut_repo, &settings);
+    let repo = tx.commit("test").unwrap();
+
+    // Items are yielded in order along with their commits
+    let items = [&commit_a, &commit_b, &commit_c].map(|commit| Ok((commit.id().clone(), 1)));
+    let iter = CommitPrefetchIterator::new(
+        repo.store(),
+        items.into_iter(),
+        batch_size,
+        |(id, _): &(CommitId, i32)| id.clone(),
+    );
+    let commits: Vec<_> = iter
+        .map_ok(|((id, value), commit)| (id, value, commit))
+        .try_collect()
+        .unwrap();
+    assert_eq!(
+        commits,
+        [&commit_a, &commit_b, &commit_c].map(|commit| (commit.id().clone(), 1, commit.clone()))
+    );
+
+    // Errors are yielded at their original position
+    let items = vec![
+        Ok(commit_a.id().clone()),
+        Err(RevsetEvaluationError::Other("error".into())),
+        Ok(commit_c.id().clone()),
+    ];
+    let mut iter =
+        CommitPrefetchIterator::new(repo.store(), items.into_iter(), batch_size, CommitId::clone);
+    assert_matches!(iter.next(), Some(Ok((_, commit))) if commit == commit_a);
+    assert_matches!(iter.next(), Some(Err(RevsetEvaluationError::Other(_))));
+    assert_matches!(iter.next(), Some(Ok((_, commit))) if commit == commit_c);
+    assert_matches!(iter.next(), None);
+
+    // Missing commits are reported as store errors
+    let items = vec![Ok(CommitId::from_hex("abcdef"))];
+    let mut iter =
+        CommitPrefetchIterator::new(repo.store(), items.into_iter(), batch_size, CommitId::clone);
+    assert_matches!(iter.next(), Some(Err(RevsetEvaluationError::StoreError(_))));
+}
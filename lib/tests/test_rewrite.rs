@@ -21,6 +21,7 @@ use jj_lib::op_store::RefTarget;
 use jj_lib::op_store::RemoteRef;
 use jj_lib::op_store::RemoteRefState;
 use jj_lib::op_store::WorkspaceId;
+use jj_lib::repo::AbandonedBookmarks;
 use jj_lib::repo::Repo;
 use jj_lib::repo_path::RepoPath;
 use jj_lib::rewrite::rebase_commit_with_options;
@@ -28,6 +29,7 @@ use jj_lib::rewrite::restore_tree;
 use jj_lib::rewrite::CommitRewriter;
 use jj_lib::rewrite::EmptyBehaviour;
 use jj_lib::rewrite::RebaseOptions;
+use jj_lib::rewrite::RebasedCommit;
 use maplit::hashmap;
 use maplit::hashset;
 use test_case::test_case;
@@ -1153,6 +1155,50 @@ fn test_rebase_descendants_update_bookmark_after_abandon() {
     );
 }
 
+#[test]
+fn test_rebase_descendants_delete_bookmark_after_abandon() {
+    let settings = testutils::user_settings();
+    let test_repo = TestRepo::init();
+    let repo = &test_repo.repo;
+
+    // Bookmarks "main" and "other" point to commit B. B is then abandoned with
+    // bookmarks deleted. Both bookmarks should be deleted, and the child C
+    // should be rebased onto A.
+    //
+    // C
+    // |
+    // B main other   =>   C'
+    // |                   |
+    // A                   A
+    let mut tx = repo.start_transaction(&settings);
+    let mut graph_builder = CommitGraphBuilder::new(&settings, tx.repo_mut());
+    let commit_a = graph_builder.initial_commit();
+    let commit_b = graph_builder.commit_with_parents(&[&commit_a]);
+    let commit_c = graph_builder.commit_with_parents(&[&commit_b]);
+    tx.repo_mut()
+        .set_local_bookmark_target("main", RefTarget::normal(commit_b.id().clone()));
+    tx.repo_mut()
+        .set_local_bookmark_target("other", RefTarget::normal(commit_b.id().clone()));
+    let repo = tx.commit("test").unwrap();
+
+    let mut tx = repo.start_transaction(&settings);
+    tx.repo_mut()
+        .record_abandoned_commit_with_bookmarks(commit_b.id().clone(), AbandonedBookmarks::Delete);
+    let rebase_map = tx
+        .repo_mut()
+        .rebase_descendants_with_options_return_map(&settings, Default::default())
+        .unwrap();
+    let new_commit_c = assert_rebased_onto(tx.repo_mut(), &rebase_map, &commit_c, &[commit_a.id()]);
+    assert_eq!(rebase_map.len(), 1);
+    assert!(tx.repo_mut().get_local_bookmark("main").is_absent());
+    assert!(tx.repo_mut().get_local_bookmark("other").is_absent());
+
+    assert_eq!(
+        *tx.repo_mut().view().heads(),
+        hashset! {new_commit_c.id().clone()}
+    );
+}
+
 #[test]
 fn test_rebase_descendants_update_bookmarks_after_divergent_rewrite() {
     let settings = testutils::user_settings();
@@ -1644,6 +1690,7 @@ fn test_empty_commit_option(empty_behavior: EmptyBehaviour) {
             RebaseOptions {
                 empty: empty_behavior,
                 simplify_ancestor_merge: true,
+                reuse_existing_rewrites: false,
             },
         )
         .unwrap();
@@ -1714,6 +1761,74 @@ fn test_empty_commit_option(empty_behavior: EmptyBehaviour) {
     );
 }
 
+#[test_case(false; "create new commit")]
+#[test_case(true; "reuse existing commit")]
+fn test_rebase_reuse_existing_rewrites(reuse_existing_rewrites: bool) {
+    let settings = testutils::user_settings();
+    let test_repo = TestRepo::init();
+    let repo = &test_repo.repo;
+
+    // Rebase B onto C, where B' (a rewrite of B onto C created by a concurrent
+    // operation, for example) already exists.
+    //
+    // B'
+    // |
+    // C B
+    // |/
+    // A
+    let mut tx = repo.start_transaction(&settings);
+    let commit_a = write_random_commit(tx.repo_mut(), &settings);
+    let commit_b = create_random_commit(tx.repo_mut(), &settings)
+        .set_parents(vec![commit_a.id().clone()])
+        .write()
+        .unwrap();
+    let commit_c = create_random_commit(tx.repo_mut(), &settings)
+        .set_parents(vec![commit_a.id().clone()])
+        .set_tree_id(commit_a.tree_id().clone())
+        .write()
+        .unwrap();
+    let existing_commit_b = tx
+        .repo_mut()
+        .new_commit(
+            &settings,
+            vec![commit_c.id().clone()],
+            commit_b.tree_id().clone(),
+        )
+        .set_change_id(commit_b.change_id().clone())
+        .set_description(commit_b.description())
+        .set_author(commit_b.author().clone())
+        .write()
+        .unwrap();
+
+    let rebase_options = RebaseOptions {
+        empty: EmptyBehaviour::Keep,
+        simplify_ancestor_merge: false,
+        reuse_existing_rewrites,
+    };
+    let rewriter = CommitRewriter::new(tx.repo_mut(), commit_b, vec![commit_c.id().clone()]);
+    let RebasedCommit::Rewritten(new_commit_b) =
+        rebase_commit_with_options(&settings, rewriter, &rebase_options).unwrap()
+    else {
+        panic!("commit should be rewritten");
+    };
+    assert_eq!(new_commit_b.parent_ids(), &[commit_c.id().clone()]);
+    assert_eq!(new_commit_b.tree_id(), existing_commit_b.tree_id());
+    tx.repo_mut().rebase_descendants(&settings).unwrap();
+    if reuse_existing_rewrites {
+        assert_eq!(new_commit_b.id(), existing_commit_b.id());
+        assert_eq!(
+            *tx.repo_mut().view().heads(),
+            hashset! {existing_commit_b.id().clone()}
+        );
+    } else {
+        assert_ne!(new_commit_b.id(), existing_commit_b.id());
+        assert_eq!(
+            *tx.repo_mut().view().heads(),
+            hashset! {new_commit_b.id().clone(), existing_commit_b.id().clone()}
+        );
+    }
+}
+
 #[test]
 fn test_rebase_abandoning_empty() {
     let settings = testutils::user_settings();
@@ -1778,6 +1893,7 @@ fn test_rebase_abandoning_empty() {
     let rebase_options = RebaseOptions {
         empty: EmptyBehaviour::AbandonAllEmpty,
         simplify_ancestor_merge: true,
+        reuse_existing_rewrites: false,
     };
     let rewriter = CommitRewriter::new(tx.repo_mut(), commit_b, vec![commit_b2.id().clone()]);
     rebase_commit_with_options(&settings, rewriter, &rebase_options).unwrap();
@@ -14,13 +14,24 @@
 
 use itertools::Itertools as _;
 use jj_lib::backend::CommitId;
+use jj_lib::commit::Commit;
 use jj_lib::repo::Repo;
 use jj_lib::repo_path::RepoPath;
 use jj_lib::rewrite::duplicate_commits;
+use jj_lib::rewrite::DuplicateCommitsStats;
 use jj_lib::transaction::Transaction;
 use testutils::create_tree;
 use testutils::TestRepo;
 
+fn duplicated_commit(
+    tx: &Transaction,
+    stats: &DuplicateCommitsStats,
+    original_commit_id: &CommitId,
+) -> Commit {
+    let new_commit_id = &stats.duplicated_commits[original_commit_id];
+    tx.repo().store().get_commit(new_commit_id).unwrap()
+}
+
 #[test]
 fn test_duplicate_linear_contents() {
     let settings = testutils::user_settings();
@@ -93,7 +104,7 @@ fn test_duplicate_linear_contents() {
     let mut tx = repo.start_transaction(&settings);
     let stats = duplicate_onto(&mut tx, &[commit_e.id()], &[commit_a.id()]);
     assert_eq!(
-        stats.duplicated_commits[commit_e.id()].tree_id(),
+        duplicated_commit(&tx, &stats, commit_e.id()).tree_id(),
         &empty_tree_id
     );
 
@@ -101,7 +112,7 @@ fn test_duplicate_linear_contents() {
     let mut tx = repo.start_transaction(&settings);
     let stats = duplicate_onto(&mut tx, &[commit_e.id()], &[commit_b.id()]);
     assert_eq!(
-        stats.duplicated_commits[commit_e.id()].tree_id(),
+        duplicated_commit(&tx, &stats, commit_e.id()).tree_id(),
         &tree_1.id()
     );
 
@@ -109,7 +120,7 @@ fn test_duplicate_linear_contents() {
     let mut tx = repo.start_transaction(&settings);
     let stats = duplicate_onto(&mut tx, &[commit_c.id()], &[commit_a.id()]);
     assert_eq!(
-        stats.duplicated_commits[commit_c.id()].tree_id(),
+        duplicated_commit(&tx, &stats, commit_c.id()).tree_id(),
         &tree_2.id()
     );
 
@@ -117,7 +128,7 @@ fn test_duplicate_linear_contents() {
     let mut tx = repo.start_transaction(&settings);
     let stats = duplicate_onto(&mut tx, &[commit_d.id()], &[commit_b.id()]);
     assert_eq!(
-        stats.duplicated_commits[commit_d.id()].tree_id(),
+        duplicated_commit(&tx, &stats, commit_d.id()).tree_id(),
         &empty_tree_id
     );
 
@@ -125,7 +136,7 @@ fn test_duplicate_linear_contents() {
     let mut tx = repo.start_transaction(&settings);
     let stats = duplicate_onto(&mut tx, &[commit_b.id()], &[commit_d.id()]);
     assert_eq!(
-        stats.duplicated_commits[commit_b.id()].tree_id(),
+        duplicated_commit(&tx, &stats, commit_b.id()).tree_id(),
         &tree_1_2.id()
     );
 
@@ -133,11 +144,11 @@ fn test_duplicate_linear_contents() {
     let mut tx = repo.start_transaction(&settings);
     let stats = duplicate_onto(&mut tx, &[commit_e.id(), commit_d.id()], &[commit_b.id()]);
     assert_eq!(
-        stats.duplicated_commits[commit_d.id()].tree_id(),
+        duplicated_commit(&tx, &stats, commit_d.id()).tree_id(),
         &empty_tree_id
     );
     assert_eq!(
-        stats.duplicated_commits[commit_e.id()].tree_id(),
+        duplicated_commit(&tx, &stats, commit_e.id()).tree_id(),
         &empty_tree_id
     );
 
@@ -145,11 +156,11 @@ fn test_duplicate_linear_contents() {
     let mut tx = repo.start_transaction(&settings);
     let stats = duplicate_onto(&mut tx, &[commit_e.id(), commit_c.id()], &[commit_a.id()]);
     assert_eq!(
-        stats.duplicated_commits[commit_c.id()].tree_id(),
+        duplicated_commit(&tx, &stats, commit_c.id()).tree_id(),
         &tree_2.id()
     );
     assert_eq!(
-        stats.duplicated_commits[commit_e.id()].tree_id(),
+        duplicated_commit(&tx, &stats, commit_e.id()).tree_id(),
         &tree_2.id()
     );
 
@@ -157,7 +168,7 @@ fn test_duplicate_linear_contents() {
     let mut tx = repo.start_transaction(&settings);
     let stats = duplicate_onto(&mut tx, &[commit_d.id()], &[commit_c.id(), commit_b.id()]);
     assert_eq!(
-        stats.duplicated_commits[commit_d.id()].tree_id(),
+        duplicated_commit(&tx, &stats, commit_d.id()).tree_id(),
         &tree_2.id()
     );
 
@@ -170,7 +181,7 @@ fn test_duplicate_linear_contents() {
         &[commit_e.id()],
     );
     assert_eq!(
-        stats.duplicated_commits[commit_b.id()].tree_id(),
+        duplicated_commit(&tx, &stats, commit_b.id()).tree_id(),
         &tree_1_2.id()
     );
     let (head_id,) = tx.repo().view().heads().iter().collect_tuple().unwrap();
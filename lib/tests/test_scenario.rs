@@ -0,0 +1,92 @@
+// Copyright 2026 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use itertools::Itertools;
+use jj_lib::op_store::RefTarget;
+use jj_lib::op_store::RemoteRef;
+use jj_lib::op_store::RemoteRefState;
+use jj_lib::repo_path::RepoPath;
+use maplit::hashset;
+use testutils::create_tree;
+use testutils::scenario::Scenario;
+use testutils::TestRepo;
+
+#[test]
+fn test_scenario_graph() {
+    let settings = testutils::user_settings();
+    let test_repo = TestRepo::init();
+    let repo = &test_repo.repo;
+
+    // Parents may be described after their children
+    let mut tx = repo.start_transaction(&settings);
+    let commits = Scenario::new("B-D, A-B-C, C-D, E").write(tx.repo_mut(), &settings);
+    let repo = tx.commit("test").unwrap();
+
+    let parent_descriptions = |label: &str| {
+        commits[label]
+            .parents()
+            .map(|parent| parent.unwrap().description().to_owned())
+            .collect_vec()
+    };
+    assert_eq!(parent_descriptions("A"), [""]);
+    assert_eq!(parent_descriptions("B"), ["A\n"]);
+    assert_eq!(parent_descriptions("C"), ["B\n"]);
+    assert_eq!(parent_descriptions("D"), ["B\n", "C\n"]);
+    assert_eq!(parent_descriptions("E"), [""]);
+    assert_eq!(
+        *repo.view().heads(),
+        hashset! {commits["D"].id().clone(), commits["E"].id().clone()}
+    );
+}
+
+#[test]
+fn test_scenario_files_and_bookmarks() {
+    let settings = testutils::user_settings();
+    let test_repo = TestRepo::init();
+    let repo = &test_repo.repo;
+    let file1_path = RepoPath::from_internal_string("file1");
+    let file2_path = RepoPath::from_internal_string("dir/file2");
+
+    let mut tx = repo.start_transaction(&settings);
+    let commits = Scenario::new("A-B-D, A-C-D")
+        .file("A", "file1", "a\n")
+        .file("B", "file1", "b\n")
+        .file("C", "dir/file2", "c\n")
+        .remove_file("D", "dir/file2")
+        .bookmark("main", "D")
+        .remote_bookmark("main", "origin", "B", RemoteRefState::Tracking)
+        .write(tx.repo_mut(), &settings);
+    let repo = tx.commit("test").unwrap();
+
+    // Files are inherited from the parents
+    assert_eq!(
+        commits["C"].tree_id(),
+        &create_tree(&repo, &[(file1_path, "a\n"), (file2_path, "c\n")]).id()
+    );
+    assert_eq!(
+        commits["D"].tree_id(),
+        &create_tree(&repo, &[(file1_path, "b\n")]).id()
+    );
+    assert_eq!(
+        repo.view().get_local_bookmark("main"),
+        &RefTarget::normal(commits["D"].id().clone())
+    );
+    assert_eq!(
+        repo.view().get_remote_bookmark("main", "origin"),
+        &RemoteRef {
+            target: RefTarget::normal(commits["B"].id().clone()),
+            state: RemoteRefState::Tracking,
+        }
+    );
+}
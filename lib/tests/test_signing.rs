@@ -9,9 +9,9 @@ use jj_lib::signing::SigStatus;
 use jj_lib::signing::SignBehavior;
 use jj_lib::signing::Signer;
 use jj_lib::signing::Verification;
+use jj_lib::test_signing_backend::TestSigningBackend;
 use test_case::test_case;
 use testutils::create_random_commit;
-use testutils::test_signing_backend::TestSigningBackend;
 use testutils::write_random_commit;
 use testutils::TestRepoBackend;
 use testutils::TestWorkspace;
@@ -67,8 +67,8 @@ use tempfile::TempDir;
 
 use crate::test_backend::TestBackendFactory;
 
+pub mod scenario;
 pub mod test_backend;
-pub mod test_signing_backend;
 
 pub fn hermetic_libgit2() {
     // libgit2 respects init.defaultBranch (and possibly other config
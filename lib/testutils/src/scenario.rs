@@ -0,0 +1,240 @@
+// Copyright 2026 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Declarative descriptions of commit graphs for tests.
+//!
+//! ```ignore
+//! let commits = Scenario::new("A-B-C, B-D")
+//!     .file("A", "file", "a\n")
+//!     .file("D", "file", "d\n")
+//!     .bookmark("main", "C")
+//!     .remote_bookmark("main", "origin", "B", RemoteRefState::Tracking)
+//!     .write(tx.repo_mut(), &settings);
+//! let commit_d = &commits["D"];
+//! ```
+
+use std::collections::HashMap;
+use std::path::Path;
+
+use itertools::Itertools as _;
+use jj_lib::backend::TreeValue;
+use jj_lib::commit::Commit;
+use jj_lib::merge::Merge;
+use jj_lib::merged_tree::MergedTreeBuilder;
+use jj_lib::op_store::RefTarget;
+use jj_lib::op_store::RemoteRef;
+use jj_lib::op_store::RemoteRefState;
+use jj_lib::repo::MutableRepo;
+use jj_lib::repo::Repo as _;
+use jj_lib::repo::RepoLoader;
+use jj_lib::repo::StoreFactories;
+use jj_lib::repo_path::RepoPathBuf;
+use jj_lib::rewrite::merge_commit_trees;
+use jj_lib::settings::UserSettings;
+
+use crate::write_file;
+
+/// Commit graph described by a string like `"A-B-C, B-D"`, plus the files
+/// and bookmarks of the commits.
+///
+/// The graph is a comma-separated list of chains of commit labels, where each
+/// commit is a child of the commit before it in a chain. Commits which aren't
+/// preceded by any other commit are children of the root commit, and commits
+/// which are preceded by several commits are merges. For example, `"A-B-D,
+/// A-C-D"` describes a diamond.
+///
+/// Each commit is described by its label, and its tree is the merge of its
+/// parents' trees with the files set for the commit applied on top.
+#[derive(Clone, Debug)]
+pub struct Scenario {
+    /// Labels and their parents in the order the labels first appear.
+    graph: Vec<(String, Vec<String>)>,
+    files: Vec<(String, RepoPathBuf, Option<String>)>,
+    bookmarks: Vec<(String, String)>,
+    remote_bookmarks: Vec<(String, String, String, RemoteRefState)>,
+}
+
+impl Scenario {
+    /// Parses the commit graph. Panics if it's malformed.
+    pub fn new(graph: &str) -> Self {
+        let mut parsed: Vec<(String, Vec<String>)> = vec![];
+        for chain in graph.split(',') {
+            let mut parent: Option<&str> = None;
+            for label in chain.split('-').map(str::trim) {
+                assert!(
+                    !label.is_empty() && label.chars().all(|c| c.is_alphanumeric() || c == '_'),
+                    "invalid commit label {label:?} in graph {graph:?}"
+                );
+                let index = match parsed.iter().position(|(name, _)| name == label) {
+                    Some(index) => index,
+                    None => {
+                        parsed.push((label.to_owned(), vec![]));
+                        parsed.len() - 1
+                    }
+                };
+                let parents = &mut parsed[index].1;
+                if let Some(parent) = parent {
+                    if !parents.iter().any(|name| name == parent) {
+                        parents.push(parent.to_owned());
+                    }
+                }
+                parent = Some(label);
+            }
+        }
+        Scenario {
+            graph: parsed,
+            files: vec![],
+            bookmarks: vec![],
+            remote_bookmarks: vec![],
+        }
+    }
+
+    /// Sets the contents of the file at `path` in the commit.
+    pub fn file(mut self, commit: &str, path: &str, contents: &str) -> Self {
+        self.assert_label_exists(commit);
+        let path = RepoPathBuf::from_internal_string(path);
+        self.files
+            .push((commit.to_owned(), path, Some(contents.to_owned())));
+        self
+    }
+
+    /// Removes the file at `path` (inherited from the parents) in the commit.
+    pub fn remove_file(mut self, commit: &str, path: &str) -> Self {
+        self.assert_label_exists(commit);
+        let path = RepoPathBuf::from_internal_string(path);
+        self.files.push((commit.to_owned(), path, None));
+        self
+    }
+
+    /// Points the local bookmark `name` to the commit.
+    pub fn bookmark(mut self, name: &str, commit: &str) -> Self {
+        self.assert_label_exists(commit);
+        self.bookmarks.push((name.to_owned(), commit.to_owned()));
+        self
+    }
+
+    /// Points the bookmark `name` of the `remote` to the commit.
+    pub fn remote_bookmark(
+        mut self,
+        name: &str,
+        remote: &str,
+        commit: &str,
+        state: RemoteRefState,
+    ) -> Self {
+        self.assert_label_exists(commit);
+        self.remote_bookmarks
+            .push((name.to_owned(), remote.to_owned(), commit.to_owned(), state));
+        self
+    }
+
+    /// Writes the commits and bookmarks to the repo. Returns the commits by
+    /// their labels. The description of each commit is its label followed by a
+    /// newline.
+    pub fn write(
+        &self,
+        mut_repo: &mut MutableRepo,
+        settings: &UserSettings,
+    ) -> HashMap<String, Commit> {
+        let mut commits: HashMap<String, Commit> = HashMap::new();
+        // Parents may appear after their children in the description, so
+        // write the commits whose parents have all been written until none
+        // are left.
+        while commits.len() < self.graph.len() {
+            let num_written = commits.len();
+            for (label, parent_labels) in &self.graph {
+                if commits.contains_key(label)
+                    || !parent_labels.iter().all(|name| commits.contains_key(name))
+                {
+                    continue;
+                }
+                let parents = if parent_labels.is_empty() {
+                    vec![mut_repo.store().root_commit()]
+                } else {
+                    parent_labels
+                        .iter()
+                        .map(|name| commits[name].clone())
+                        .collect_vec()
+                };
+                let commit = self.write_commit(mut_repo, settings, label, &parents);
+                commits.insert(label.clone(), commit);
+            }
+            assert!(commits.len() > num_written, "cycle in commit graph");
+        }
+
+        for (name, label) in &self.bookmarks {
+            let target = RefTarget::normal(commits[label].id().clone());
+            mut_repo.set_local_bookmark_target(name, target);
+        }
+        for (name, remote, label, state) in &self.remote_bookmarks {
+            let remote_ref = RemoteRef {
+                target: RefTarget::normal(commits[label].id().clone()),
+                state: *state,
+            };
+            mut_repo.set_remote_bookmark(name, remote, remote_ref);
+        }
+        commits
+    }
+
+    /// Loads the repo at `repo_path` (the `.jj/repo` directory), and writes
+    /// the commits and bookmarks to it in a new operation. This is useful for
+    /// setting up repos which are then used through the CLI.
+    pub fn write_to_repo(
+        &self,
+        settings: &UserSettings,
+        repo_path: &Path,
+    ) -> HashMap<String, Commit> {
+        let repo =
+            RepoLoader::init_from_file_system(settings, repo_path, &StoreFactories::default())
+                .unwrap()
+                .load_at_head(settings)
+                .unwrap();
+        let mut tx = repo.start_transaction(settings);
+        let commits = self.write(tx.repo_mut(), settings);
+        tx.commit("create scenario").unwrap();
+        commits
+    }
+
+    fn write_commit(
+        &self,
+        mut_repo: &mut MutableRepo,
+        settings: &UserSettings,
+        label: &str,
+        parents: &[Commit],
+    ) -> Commit {
+        let store = mut_repo.store().clone();
+        let parent_tree = merge_commit_trees(mut_repo, parents).unwrap();
+        let mut tree_builder = MergedTreeBuilder::new(parent_tree.id());
+        for (_, path, contents) in self.files.iter().filter(|(name, ..)| name == label) {
+            let value = contents.as_ref().map(|contents| TreeValue::File {
+                id: write_file(&store, path, contents),
+                executable: false,
+            });
+            tree_builder.set_or_remove(path.clone(), Merge::resolved(value));
+        }
+        let tree_id = tree_builder.write_tree(&store).unwrap();
+        let parent_ids = parents.iter().map(|commit| commit.id().clone()).collect();
+        mut_repo
+            .new_commit(settings, parent_ids, tree_id)
+            .set_description(format!("{label}\n"))
+            .write()
+            .unwrap()
+    }
+
+    fn assert_label_exists(&self, label: &str) {
+        assert!(
+            self.graph.iter().any(|(name, _)| name == label),
+            "commit {label:?} isn't in the graph"
+        );
+    }
+}